@@ -1,5 +1,6 @@
 // src/api/handlers.rs
 use crate::api::types::{BridgeAssetsRequest, BridgeResponse, ErrorResponse};
+use crate::core::errors::WalletError;
 use crate::core::wallet_manager::WalletManager;
 use axum::{extract::State, http::StatusCode, Json};
 use serde_json::{json, Value};
@@ -60,6 +61,10 @@ pub async fn bridge_assets(
         .await
     {
         Ok(bridge_tx_id) => Ok(Json(BridgeResponse { bridge_tx_id })),
+        Err(err @ WalletError::PendingApproval(_)) => Err((
+            StatusCode::ACCEPTED,
+            Json(ErrorResponse { error: err.to_string(), code: "APPROVAL_PENDING".to_string() }),
+        )),
         Err(err) => {
             // 在返回 500 错误前，记录详细的底层错误信息和请求内容
             // 直接打印到 stderr，确保在测试输出里能看到底层错误（临时调试）
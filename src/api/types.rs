@@ -1,5 +1,24 @@
+use chrono::{DateTime, Utc};
+use ethers::types::transaction::eip712::TypedData;
 use serde::{Deserialize, Serialize};
 
+use crate::blockchain::bridge::BridgeQuote;
+use crate::blockchain::contract::{ContractCall, ContractCallResult, ContractReceipt};
+use crate::blockchain::offline::{OfflineSignedTransaction, OfflineSigningRequest};
+use crate::blockchain::simulation::SimulationResult;
+use crate::blockchain::staking::{StakeReceipt, StakingSummary};
+use crate::blockchain::swap::{SwapQuote, SwapReceipt};
+use crate::core::events::EventKind;
+use crate::core::wallet::backup::BackupManifest;
+use crate::crypto::message::MessageSignature;
+use crate::security::access_control::Role;
+use crate::security::approval::ApprovalRequest;
+use crate::security::auth::ApiKey;
+use crate::security::policy::SpendingPolicy;
+use crate::storage::{AuditLog, WebhookDelivery, WebhookRecord};
+use crate::walletconnect::{Session, SessionProposal, SessionRequest};
+use crate::webhooks;
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct CreateWalletRequest {
     pub name: String,
@@ -18,6 +37,14 @@ pub struct SendTransactionRequest {
     pub to_address: String,
     pub amount: String,
     pub network: String,
+    /// Broadcast even when the pre-flight simulation reverts.
+    #[serde(default)]
+    pub allow_revert: bool,
+}
+
+#[derive(Serialize)]
+pub struct SimulationResponse {
+    pub simulation: SimulationResult,
 }
 
 #[derive(Serialize)]
@@ -40,6 +67,117 @@ pub struct BridgeResponse {
     pub bridge_tx_id: String,
 }
 
+#[derive(Serialize)]
+pub struct BridgeRecoveryResponse {
+    /// Transfers redeemed, refunded or escalated in this pass.
+    pub recovered: usize,
+}
+
+/// Routes able to carry the requested transfer, best first.
+#[derive(Serialize)]
+pub struct BridgeQuoteResponse {
+    pub quotes: Vec<BridgeQuote>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SwapRequest {
+    pub network: String,
+    pub token_in: String,
+    pub token_out: String,
+    pub amount: String,
+    /// Defaults to `swap::DEFAULT_SLIPPAGE_BPS`.
+    pub slippage_bps: Option<u32>,
+    /// Defaults to `swap::DEFAULT_DEADLINE_SECONDS`.
+    pub deadline_seconds: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct SwapQuoteResponse {
+    pub quote: SwapQuote,
+}
+
+#[derive(Serialize)]
+pub struct SwapResponse {
+    pub swap: SwapReceipt,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ContractCallRequest {
+    pub network: String,
+    #[serde(flatten)]
+    pub call: ContractCall,
+    /// Send without the pre-flight simulation (`/contract/send` only).
+    #[serde(default)]
+    pub skip_simulation: bool,
+    /// Send even when the simulation reverts (`/contract/send` only).
+    #[serde(default)]
+    pub allow_revert: bool,
+}
+
+#[derive(Serialize)]
+pub struct ContractCallResponse {
+    pub result: ContractCallResult,
+}
+
+#[derive(Serialize)]
+pub struct ContractTransactionResponse {
+    pub transaction: ContractReceipt,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct StakeRequest {
+    pub network: String,
+    pub amount: String,
+    /// Vote account on Solana; defaults to the configured validator.
+    pub validator: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct UnstakeRequest {
+    pub network: String,
+    pub position_id: String,
+    /// Defaults to the whole position.
+    pub amount: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct WithdrawStakeRequest {
+    pub network: String,
+    pub position_id: String,
+}
+
+#[derive(Serialize)]
+pub struct StakeResponse {
+    pub stake: StakeReceipt,
+}
+
+/// The wallet's spending policy; `null` when it has none.
+#[derive(Serialize)]
+pub struct SpendingPolicyResponse {
+    pub policy: Option<SpendingPolicy>,
+}
+
+#[derive(Serialize)]
+pub struct ApprovalsResponse {
+    pub approvals: Vec<ApprovalRequest>,
+}
+
+#[derive(Serialize)]
+pub struct ApprovalResponse {
+    pub approval: ApprovalRequest,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ApprovalDecisionRequest {
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct StakingSummaryResponse {
+    pub summary: StakingSummary,
+}
+
 #[derive(Serialize)]
 pub struct BalanceResponse {
     pub balance: String,
@@ -55,6 +193,7 @@ pub struct TransactionHistoryResponse {
 #[derive(Serialize)]
 pub struct BackupResponse {
     pub seed_phrase: String,
+    pub manifest: BackupManifest,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -63,6 +202,9 @@ pub struct RestoreWalletRequest {
     pub seed_phrase: String,
     #[serde(default)]
     pub quantum_safe: bool,
+    /// Signed manifest from `/backup`; when present it must verify before restoring.
+    #[serde(default)]
+    pub manifest: Option<BackupManifest>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -73,6 +215,331 @@ pub struct MultiSigTransactionRequest {
     pub signatures: Vec<String>,
 }
 
+#[derive(Clone, Debug, Deserialize)]
+pub struct UnsignedTransactionRequest {
+    /// Wallet whose compliance rules and spending policy apply.
+    pub wallet: String,
+    /// Hex-encoded public key of the sending account (held by the offline signer).
+    pub from_public_key: String,
+    pub to_address: String,
+    pub amount: String,
+    pub network: String,
+    /// ERC-20 contract to transfer `amount` of instead of the native token.
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct UnsignedTransactionResponse {
+    pub request: OfflineSigningRequest,
+    /// The same request split into UR fragments for an animated QR code.
+    pub ur_fragments: Vec<String>,
+}
+
+/// Either the signed JSON document or its UR fragments.
+#[derive(Clone, Debug, Deserialize)]
+pub struct BroadcastSignedRequest {
+    #[serde(default)]
+    pub signed: Option<OfflineSignedTransaction>,
+    #[serde(default)]
+    pub ur_fragments: Option<Vec<String>>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SignMessageRequest {
+    pub network: String,
+    pub message: String,
+    /// When true, `message` is 0x-prefixed hex rather than UTF-8 text.
+    #[serde(default)]
+    pub hex: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SignTypedDataRequest {
+    pub network: String,
+    pub typed_data: TypedData,
+}
+
+#[derive(Serialize)]
+pub struct SignTypedDataResponse {
+    #[serde(flatten)]
+    pub signature: MessageSignature,
+    /// Human-readable rendering of the typed data for approval screens.
+    pub rendered: String,
+}
+
+/// Verifies either a plain message (`message`) or EIP-712 data (`typed_data`).
+#[derive(Clone, Debug, Deserialize)]
+pub struct VerifyMessageRequest {
+    pub network: String,
+    pub address: String,
+    pub signature: String,
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub hex: bool,
+    #[serde(default)]
+    pub typed_data: Option<TypedData>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct VerifyMessageResponse {
+    pub valid: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct WalletConnectPairRequest {
+    /// `wc:` pairing URI shown by the dApp.
+    pub uri: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct WalletConnectPairResponse {
+    pub topic: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct WalletConnectApproveProposalRequest {
+    /// Wallet whose accounts the session is granted.
+    pub wallet: String,
+}
+
+#[derive(Serialize)]
+pub struct WalletConnectProposalsResponse {
+    pub proposals: Vec<SessionProposal>,
+}
+
+#[derive(Serialize)]
+pub struct WalletConnectSessionResponse {
+    pub session: Session,
+}
+
+#[derive(Serialize)]
+pub struct WalletConnectSessionsResponse {
+    pub sessions: Vec<Session>,
+}
+
+#[derive(Serialize)]
+pub struct WalletConnectRequestsResponse {
+    pub requests: Vec<SessionRequest>,
+}
+
+/// Result sent back to the dApp: a signature or a transaction hash.
+#[derive(Serialize)]
+pub struct WalletConnectResultResponse {
+    pub result: serde_json::Value,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct LoginRequest {
+    pub username: String,
+    pub password: String,
+    /// TOTP or recovery code; required once two-factor is enabled.
+    #[serde(default)]
+    pub otp: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct OtpRequest {
+    pub code: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct RefreshTokenRequest {
+    pub refresh_token: String,
+}
+
+#[derive(Serialize)]
+pub struct AuditLogEntry {
+    pub id: i64,
+    pub wallet_id: Option<String>,
+    pub action: String,
+    pub details: Option<String>,
+    pub actor: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub request_id: Option<String>,
+    pub hash: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<AuditLog> for AuditLogEntry {
+    fn from(log: AuditLog) -> Self {
+        Self {
+            id: log.id,
+            wallet_id: log.wallet_id,
+            action: log.action,
+            details: log.details,
+            actor: log.actor,
+            ip_address: log.ip_address,
+            user_agent: log.user_agent,
+            request_id: log.request_id,
+            hash: log.hash,
+            created_at: log.created_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct AuditLogsResponse {
+    pub logs: Vec<AuditLogEntry>,
+    /// Rows matching the filters across all pages.
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    /// Event types to deliver; all of them when empty.
+    #[serde(default)]
+    pub events: Vec<String>,
+    /// Name of the only wallet whose events are delivered.
+    #[serde(default)]
+    pub wallet: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct WebhookEntry {
+    pub id: String,
+    pub url: String,
+    pub events: Vec<EventKind>,
+    pub wallet: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<WebhookRecord> for WebhookEntry {
+    fn from(webhook: WebhookRecord) -> Self {
+        Self {
+            events: webhooks::parse_event_kinds(&webhook.events).unwrap_or_default(),
+            id: webhook.id,
+            url: webhook.url,
+            wallet: webhook.wallet,
+            created_at: webhook.created_at,
+        }
+    }
+}
+
+/// A new webhook with the secret its deliveries are signed with, which is
+/// only returned here.
+#[derive(Serialize)]
+pub struct CreatedWebhookResponse {
+    #[serde(flatten)]
+    pub webhook: WebhookEntry,
+    pub secret: String,
+}
+
+#[derive(Serialize)]
+pub struct WebhooksResponse {
+    pub webhooks: Vec<WebhookEntry>,
+}
+
+#[derive(Serialize)]
+pub struct WebhookDeliveryEntry {
+    pub id: String,
+    pub event_type: String,
+    pub status: String,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub response_status: Option<i64>,
+    pub payload: serde_json::Value,
+    pub next_attempt_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<WebhookDelivery> for WebhookDeliveryEntry {
+    fn from(delivery: WebhookDelivery) -> Self {
+        Self {
+            payload: serde_json::from_str(&delivery.payload)
+                .unwrap_or(serde_json::Value::String(delivery.payload)),
+            id: delivery.id,
+            event_type: delivery.event_type,
+            status: delivery.status,
+            attempts: delivery.attempts,
+            last_error: delivery.last_error,
+            response_status: delivery.response_status,
+            next_attempt_at: delivery.next_attempt_at,
+            created_at: delivery.created_at,
+            updated_at: delivery.updated_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct WebhookDeliveriesResponse {
+    pub deliveries: Vec<WebhookDeliveryEntry>,
+}
+
+#[derive(Serialize)]
+pub struct WebhookDeliveryResponse {
+    pub delivery: WebhookDeliveryEntry,
+}
+
+/// What a `/api/stream` WebSocket client sends to change which wallets it
+/// receives events for, e.g. `{"subscribe": ["treasury"]}`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamCommand {
+    Subscribe(Vec<String>),
+    Unsubscribe(Vec<String>),
+}
+
+/// Stream messages other than wallet events, which are sent as they are
+/// published. Both carry a `type` field.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamMessage {
+    /// The wallets the client now receives events for.
+    Subscribed {
+        wallets: Vec<String>,
+    },
+    /// The client fell behind and this many events were skipped.
+    Lagged {
+        missed: u64,
+    },
+    Error {
+        error: String,
+        code: String,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CreateUserRequest {
+    pub username: String,
+    pub password: String,
+    /// Scopes the user may hold; read and write when empty.
+    #[serde(default)]
+    pub scopes: Vec<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    /// Scopes of the key; all of the caller's scopes when empty.
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    #[serde(default)]
+    pub expires_in_days: Option<i64>,
+}
+
+#[derive(Serialize)]
+pub struct ApiKeysResponse {
+    pub api_keys: Vec<ApiKey>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct AssignRoleRequest {
+    pub role: Role,
+}
+
+#[derive(Serialize)]
+pub struct RolesResponse {
+    pub username: String,
+    pub roles: Vec<Role>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ErrorResponse {
     pub error: String,
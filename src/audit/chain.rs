@@ -0,0 +1,268 @@
+// src/audit/chain.rs
+//! Hash chain over the persisted audit log.
+//!
+//! Every row stores the hash of the row before it and its own hash
+//! `h_i = SHA-256(h_{i-1} || row_i)`, starting from 32 zero bytes, so the hash
+//! of the latest row is the digest of the whole log up to it. Editing a row
+//! changes its hash, and deleting one breaks the link of the row after it;
+//! rewriting the chain from the edit onwards is caught by the signed
+//! checkpoints, which record the digest up to a row.
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::storage::{AuditLog, AuditSignature};
+
+/// Hash the first row is chained to.
+pub const GENESIS_HASH: [u8; 32] = [0u8; 32];
+
+/// Canonical byte encoding of a single audit row. Field order is fixed and
+/// every field is length-prefixed so values containing separators cannot
+/// collide with a different row. Actor and request id were added later and
+/// are only encoded when set, so rows written before them hash as they did.
+pub fn canonical_row(log: &AuditLog) -> Vec<u8> {
+    let mut fields: Vec<&str> = vec![
+        log.wallet_id.as_deref().unwrap_or(""),
+        &log.action,
+        log.details.as_deref().unwrap_or(""),
+        log.ip_address.as_deref().unwrap_or(""),
+        log.user_agent.as_deref().unwrap_or(""),
+    ];
+    if log.actor.is_some() || log.request_id.is_some() {
+        fields.push(log.actor.as_deref().unwrap_or(""));
+        fields.push(log.request_id.as_deref().unwrap_or(""));
+    }
+
+    let mut out = Vec::new();
+    out.extend_from_slice(&log.id.to_be_bytes());
+    for field in fields {
+        out.extend_from_slice(&(field.len() as u32).to_be_bytes());
+        out.extend_from_slice(field.as_bytes());
+    }
+    out.extend_from_slice(&log.created_at.timestamp_micros().to_be_bytes());
+    out
+}
+
+/// Hash of `log` chained to the hash of the row before it.
+pub fn row_hash(prev: &[u8; 32], log: &AuditLog) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(prev);
+    hasher.update(canonical_row(log));
+    hasher.finalize().into()
+}
+
+/// Rolling digest over `logs` in order, starting from `GENESIS_HASH`.
+pub fn chain_digest(logs: &[AuditLog]) -> [u8; 32] {
+    logs.iter().fold(GENESIS_HASH, |prev, log| row_hash(&prev, log))
+}
+
+/// Outcome of checking the stored audit log.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChainVerification {
+    pub rows_checked: usize,
+    pub checkpoints_checked: usize,
+    /// First row found deleted, edited or out of line; `None` when intact.
+    pub first_invalid_row: Option<i64>,
+    pub error: Option<String>,
+    /// Id of the key the checkpoints were checked against, to compare with
+    /// the pinned `artifact_key_id`.
+    pub key_id: Option<String>,
+}
+
+impl ChainVerification {
+    pub fn is_valid(&self) -> bool {
+        self.error.is_none()
+    }
+
+    fn broken(&mut self, row: Option<i64>, error: String) -> &mut Self {
+        if self.error.is_none() {
+            self.first_invalid_row = row;
+            self.error = Some(error);
+        }
+        self
+    }
+}
+
+/// Checks `logs`, the whole audit log in id order, against the hashes stored
+/// with each row and against the digests of `checkpoints`. Rows before the
+/// first hashed one predate chaining; from there on every row must carry
+/// both hashes. Signatures are checked by the caller, which holds the key.
+pub fn verify_chain(logs: &[AuditLog], checkpoints: &[AuditSignature]) -> ChainVerification {
+    let mut result = ChainVerification {
+        rows_checked: logs.len(),
+        checkpoints_checked: checkpoints.len(),
+        first_invalid_row: None,
+        error: None,
+        key_id: None,
+    };
+
+    let mut prev = GENESIS_HASH;
+    let mut started = false;
+    let mut digests = Vec::with_capacity(logs.len());
+    for (i, log) in logs.iter().enumerate() {
+        if log.prev_hash.is_some() && log.hash.is_some() {
+            started = true;
+        } else if started || log.prev_hash.is_some() || log.hash.is_some() {
+            result.broken(Some(log.id), format!("Row {} is missing its hash", log.id));
+            break;
+        }
+        let expected_prev = hex::encode(prev);
+        if log.prev_hash.as_deref().is_some_and(|stored| stored != expected_prev) {
+            result
+                .broken(Some(log.id), format!("Row {} does not follow the row before it", log.id));
+            break;
+        }
+        let hash = row_hash(&prev, log);
+        if log.hash.as_deref().is_some_and(|stored| stored != hex::encode(hash)) {
+            result.broken(Some(log.id), format!("Row {} was modified", log.id));
+            break;
+        }
+        if i > 0 && logs[i - 1].id + 1 != log.id {
+            result.broken(Some(log.id), format!("Rows before {} were deleted", log.id));
+            break;
+        }
+        digests.push((log.id, hash));
+        prev = hash;
+    }
+
+    for checkpoint in checkpoints {
+        match digests.binary_search_by_key(&checkpoint.last_log_id, |(id, _)| *id) {
+            Ok(i) if hex::encode(digests[i].1) == checkpoint.chain_digest => {}
+            Ok(_) => {
+                result.broken(
+                    Some(checkpoint.last_log_id),
+                    format!(
+                        "Rows up to {} do not match checkpoint {}",
+                        checkpoint.last_log_id, checkpoint.id
+                    ),
+                );
+            }
+            // rows after a break were not hashed; that break is reported already
+            Err(_) if result.error.is_some() => {}
+            Err(_) => {
+                result.broken(
+                    Some(checkpoint.last_log_id),
+                    format!(
+                        "Row {} signed by checkpoint {} is missing",
+                        checkpoint.last_log_id, checkpoint.id
+                    ),
+                );
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn log(id: i64, action: &str) -> AuditLog {
+        AuditLog {
+            id,
+            wallet_id: Some("w1".to_string()),
+            action: action.to_string(),
+            details: None,
+            ip_address: None,
+            user_agent: None,
+            actor: None,
+            request_id: None,
+            prev_hash: None,
+            hash: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Rows as `log_action` stores them, with their hashes.
+    fn chained(actions: &[&str]) -> Vec<AuditLog> {
+        let mut prev = GENESIS_HASH;
+        actions
+            .iter()
+            .enumerate()
+            .map(|(i, action)| {
+                let mut row = log(i as i64 + 1, action);
+                row.actor = Some("alice".to_string());
+                row.prev_hash = Some(hex::encode(prev));
+                prev = row_hash(&prev, &row);
+                row.hash = Some(hex::encode(prev));
+                row
+            })
+            .collect()
+    }
+
+    fn checkpoint(logs: &[AuditLog]) -> AuditSignature {
+        AuditSignature {
+            id: 1,
+            last_log_id: logs.last().unwrap().id,
+            chain_digest: hex::encode(chain_digest(logs)),
+            signature: String::new(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_chain_digest_detects_edits() {
+        let logs = vec![log(1, "wallet_created"), log(2, "wallet_accessed")];
+        let digest = chain_digest(&logs);
+
+        let mut edited = logs.clone();
+        edited[0].action = "wallet_deleted".to_string();
+        assert_ne!(chain_digest(&edited), digest);
+
+        let removed = vec![logs[1].clone()];
+        assert_ne!(chain_digest(&removed), digest);
+    }
+
+    #[test]
+    fn test_chain_digest_is_order_sensitive() {
+        let a = log(1, "a");
+        let b = log(2, "b");
+        assert_ne!(chain_digest(&[a.clone(), b.clone()]), chain_digest(&[b, a]));
+    }
+
+    #[test]
+    fn test_rows_without_actor_hash_as_before() {
+        let mut row = log(1, "wallet_created");
+        let legacy = canonical_row(&row);
+        row.actor = Some(String::new());
+        assert_ne!(canonical_row(&row), legacy);
+        row.actor = None;
+        row.request_id = Some("req".to_string());
+        assert_ne!(canonical_row(&row), legacy);
+    }
+
+    #[test]
+    fn test_verify_chain_finds_edited_and_deleted_rows() {
+        let logs = chained(&["wallet_created", "transaction_sent", "wallet_deleted"]);
+        let checkpoints = vec![checkpoint(&logs[..2])];
+        let result = verify_chain(&logs, &checkpoints);
+        assert!(result.is_valid(), "{:?}", result.error);
+        assert_eq!(result.rows_checked, 3);
+
+        let mut edited = logs.clone();
+        edited[1].details = Some("to someone else".to_string());
+        let result = verify_chain(&edited, &checkpoints);
+        assert_eq!(result.first_invalid_row, Some(2));
+
+        let deleted = vec![logs[0].clone(), logs[2].clone()];
+        assert_eq!(verify_chain(&deleted, &checkpoints).first_invalid_row, Some(3));
+
+        // stripping a row's hashes after the chain starts does not skip the check
+        let mut stripped = logs.clone();
+        stripped[1].prev_hash = None;
+        stripped[1].hash = None;
+        let result = verify_chain(&stripped, &[]);
+        assert_eq!(result.first_invalid_row, Some(2));
+        assert!(result.error.unwrap().contains("missing its hash"));
+        let mut stripped = logs.clone();
+        stripped[2].hash = None;
+        assert_eq!(verify_chain(&stripped, &[]).first_invalid_row, Some(3));
+
+        // deleting the newest signed row and rewriting the chain is caught by the checkpoint
+        let rewritten = chained(&["wallet_created"]);
+        let result = verify_chain(&rewritten, &checkpoints);
+        assert_eq!(result.first_invalid_row, Some(2));
+        let rewritten = chained(&["wallet_created", "transaction_sent_elsewhere"]);
+        assert!(!verify_chain(&rewritten, &checkpoints).is_valid());
+    }
+}
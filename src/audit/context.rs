@@ -0,0 +1,59 @@
+// src/audit/context.rs
+//! Who is behind the operation being audited.
+//!
+//! The API sets the context for the duration of each request; audit rows
+//! written while it runs record its actor, client address, user agent and
+//! request id. Work outside a request, such as the bridge relayer, has no
+//! context and is recorded without an actor.
+use std::future::Future;
+
+tokio::task_local! {
+    static CURRENT: AuditContext;
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuditContext {
+    pub actor: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub request_id: Option<String>,
+}
+
+impl AuditContext {
+    /// The context of the running task; empty outside `scope`.
+    pub fn current() -> Self {
+        CURRENT.try_with(Clone::clone).unwrap_or_default()
+    }
+
+    pub fn with_actor(mut self, actor: &str) -> Self {
+        self.actor = Some(actor.to_string());
+        self
+    }
+
+    /// Runs `f` with this context as the current one.
+    pub async fn scope<F: Future>(self, f: F) -> F::Output {
+        CURRENT.scope(self, f).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_context_is_scoped_to_the_future() {
+        assert_eq!(AuditContext::current(), AuditContext::default());
+        let context =
+            AuditContext { request_id: Some("req-1".to_string()), ..AuditContext::default() };
+        let inner = context
+            .clone()
+            .scope(async {
+                let outer = AuditContext::current();
+                outer.with_actor("alice").scope(async { AuditContext::current() }).await
+            })
+            .await;
+        assert_eq!(inner.actor.as_deref(), Some("alice"));
+        assert_eq!(inner.request_id.as_deref(), Some("req-1"));
+        assert_eq!(AuditContext::current(), AuditContext::default());
+    }
+}
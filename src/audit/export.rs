@@ -0,0 +1,647 @@
+// src/audit/export.rs
+//! Export of the audit log and security events to a SIEM.
+//!
+//! Records are rendered as JSON Lines, ArcSight CEF or RFC 5424 syslog and
+//! delivered to a file or to a collector over UDP or TCP. Each export has a
+//! name under which a cursor is stored: a run delivers the audit rows after
+//! the last one it delivered and the security events newer than the last
+//! one, then moves the cursor, so running it again sends nothing twice. A
+//! batch that fails to deliver leaves the cursor before it and is sent again
+//! by the next run; audit records carry their row id for deduplication.
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpStream, UdpSocket};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::monitoring::{SecurityEvent, SecuritySeverity};
+use crate::storage::{AuditExportCursor, AuditLog, AuditQuery, WalletStorageTrait};
+use crate::tools::generator::SiemExportConfig;
+
+const VENDOR: &str = "DeFi Hot Wallet";
+const PRODUCT: &str = "defi-hot-wallet";
+/// Syslog facility 13, "log audit".
+const SYSLOG_FACILITY: u8 = 13;
+/// Structured data id under the enterprise number reserved for examples
+/// (RFC 5612); collectors match on the name part.
+const SYSLOG_SD_ID: &str = "wallet@32473";
+/// Audit rows read per batch.
+const BATCH_SIZE: i64 = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    JsonLines,
+    Cef,
+    Syslog,
+}
+
+impl ExportFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportFormat::JsonLines => "json_lines",
+            ExportFormat::Cef => "cef",
+            ExportFormat::Syslog => "syslog",
+        }
+    }
+
+    /// Renders `record` as a single line. `hostname` is only used by syslog.
+    pub fn render(&self, record: &ExportRecord, hostname: &str) -> String {
+        match self {
+            ExportFormat::JsonLines => serde_json::to_string(record).unwrap_or_default(),
+            ExportFormat::Cef => to_cef(record),
+            ExportFormat::Syslog => to_syslog(record, hostname),
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "json_lines" | "jsonl" => Ok(ExportFormat::JsonLines),
+            "cef" => Ok(ExportFormat::Cef),
+            "syslog" => Ok(ExportFormat::Syslog),
+            other => Err(anyhow::anyhow!("Unknown export format: {}", other)),
+        }
+    }
+}
+
+/// An audit row or security event in the shape every format renders.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportRecord {
+    /// `audit` or `security`.
+    pub source: &'static str,
+    /// Audit row id; security events have none.
+    pub id: Option<i64>,
+    pub timestamp: DateTime<Utc>,
+    pub action: String,
+    /// CEF severity, 0 (lowest) to 10.
+    pub severity: u8,
+    pub actor: Option<String>,
+    pub wallet_id: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub request_id: Option<String>,
+    pub details: Option<String>,
+    pub hash: Option<String>,
+}
+
+impl From<&AuditLog> for ExportRecord {
+    fn from(log: &AuditLog) -> Self {
+        Self {
+            source: "audit",
+            id: Some(log.id),
+            timestamp: log.created_at,
+            action: log.action.clone(),
+            severity: 3,
+            actor: log.actor.clone(),
+            wallet_id: log.wallet_id.clone(),
+            ip_address: log.ip_address.clone(),
+            user_agent: log.user_agent.clone(),
+            request_id: log.request_id.clone(),
+            details: log.details.clone(),
+            hash: log.hash.clone(),
+        }
+    }
+}
+
+impl From<&SecurityEvent> for ExportRecord {
+    fn from(event: &SecurityEvent) -> Self {
+        let severity = match event.severity {
+            SecuritySeverity::Low => 3,
+            SecuritySeverity::Medium => 5,
+            SecuritySeverity::High => 8,
+            SecuritySeverity::Critical => 10,
+        };
+        Self {
+            source: "security",
+            id: None,
+            timestamp: event.timestamp,
+            action: event.event_type.as_str().to_string(),
+            severity,
+            actor: None,
+            wallet_id: event.wallet_id.clone(),
+            ip_address: event.source_ip.clone(),
+            user_agent: None,
+            request_id: None,
+            details: Some(event.description.clone()),
+            hash: None,
+        }
+    }
+}
+
+fn cef_header(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('|', "\\|")
+}
+
+fn cef_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('=', "\\=").replace('\r', "\\r").replace('\n', "\\n")
+}
+
+/// `CEF:0|vendor|product|version|signature|name|severity|extensions`
+fn to_cef(record: &ExportRecord) -> String {
+    let mut extensions = vec![format!("rt={}", record.timestamp.timestamp_millis())];
+    let mut push = |key: &str, value: Option<&str>| {
+        if let Some(value) = value.filter(|v| !v.is_empty()) {
+            extensions.push(format!("{}={}", key, cef_value(value)));
+        }
+    };
+    push("externalId", record.id.map(|id| id.to_string()).as_deref());
+    push("suser", record.actor.as_deref());
+    push("src", record.ip_address.as_deref());
+    push("requestClientApplication", record.user_agent.as_deref());
+    if record.wallet_id.is_some() {
+        push("cs1Label", Some("walletId"));
+        push("cs1", record.wallet_id.as_deref());
+    }
+    if record.request_id.is_some() {
+        push("cs2Label", Some("requestId"));
+        push("cs2", record.request_id.as_deref());
+    }
+    if record.hash.is_some() {
+        push("cs3Label", Some("hash"));
+        push("cs3", record.hash.as_deref());
+    }
+    push("msg", record.details.as_deref());
+
+    format!(
+        "CEF:0|{}|{}|{}|{}|{}|{}|{}",
+        cef_header(VENDOR),
+        cef_header(PRODUCT),
+        cef_header(env!("CARGO_PKG_VERSION")),
+        cef_header(&format!("{}:{}", record.source, record.action)),
+        cef_header(&record.action),
+        record.severity,
+        extensions.join(" ")
+    )
+}
+
+/// Syslog severity for a CEF severity.
+fn syslog_severity(severity: u8) -> u8 {
+    match severity {
+        10.. => 2,  // critical
+        8..=9 => 4, // warning
+        5..=7 => 5, // notice
+        _ => 6,     // informational
+    }
+}
+
+/// RFC 5424 header fields are printable ASCII without spaces; anything else
+/// is replaced and an empty value becomes the nil value.
+fn syslog_token(value: &str, max: usize) -> String {
+    let token: String =
+        value.chars().map(|c| if c.is_ascii_graphic() { c } else { '_' }).take(max).collect();
+    if token.is_empty() {
+        "-".to_string()
+    } else {
+        token
+    }
+}
+
+fn sd_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace(']', "\\]")
+}
+
+/// `<PRI>1 TIMESTAMP HOSTNAME APP-NAME PROCID MSGID [SD] MSG`
+fn to_syslog(record: &ExportRecord, hostname: &str) -> String {
+    let pri = SYSLOG_FACILITY * 8 + syslog_severity(record.severity);
+    let params: Vec<String> = [
+        ("source", Some(record.source)),
+        ("id", record.id.map(|id| id.to_string()).as_deref()),
+        ("actor", record.actor.as_deref()),
+        ("wallet", record.wallet_id.as_deref()),
+        ("ip", record.ip_address.as_deref()),
+        ("requestId", record.request_id.as_deref()),
+        ("hash", record.hash.as_deref()),
+    ]
+    .iter()
+    .filter_map(|(name, value)| value.map(|v| format!("{}=\"{}\"", name, sd_value(v))))
+    .collect();
+    let message = record.details.as_deref().unwrap_or("").replace(['\r', '\n'], " ");
+
+    let mut line = format!(
+        "<{}>1 {} {} {} {} {} [{} {}]",
+        pri,
+        record.timestamp.to_rfc3339_opts(SecondsFormat::Micros, true),
+        syslog_token(hostname, 255),
+        PRODUCT,
+        std::process::id(),
+        syslog_token(&record.action, 32),
+        SYSLOG_SD_ID,
+        params.join(" ")
+    );
+    if !message.is_empty() {
+        line.push(' ');
+        line.push_str(&message);
+    }
+    line
+}
+
+/// Where rendered records go.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportSink {
+    /// Appended to a file, one record per line.
+    File(PathBuf),
+    /// One datagram per record.
+    Udp(String),
+    /// One connection per batch; syslog uses octet counting (RFC 6587),
+    /// the other formats one record per line.
+    Tcp(String),
+}
+
+impl FromStr for ExportSink {
+    type Err = anyhow::Error;
+
+    /// `udp://host:port`, `tcp://host:port`, `file://path` or a plain path.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let address = |rest: &str| {
+            if rest
+                .rsplit_once(':')
+                .is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok())
+            {
+                Ok(rest.to_string())
+            } else {
+                Err(anyhow::anyhow!("Export endpoint {} needs a host and port", s))
+            }
+        };
+        if let Some(rest) = s.strip_prefix("udp://") {
+            Ok(ExportSink::Udp(address(rest)?))
+        } else if let Some(rest) = s.strip_prefix("tcp://") {
+            Ok(ExportSink::Tcp(address(rest)?))
+        } else if s.contains("://") && !s.starts_with("file://") {
+            Err(anyhow::anyhow!("Unsupported export endpoint: {}", s))
+        } else if s.trim_start_matches("file://").is_empty() {
+            Err(anyhow::anyhow!("Export file path is empty"))
+        } else {
+            Ok(ExportSink::File(PathBuf::from(s.trim_start_matches("file://"))))
+        }
+    }
+}
+
+impl ExportSink {
+    pub async fn deliver(&self, format: ExportFormat, lines: &[String]) -> Result<()> {
+        if lines.is_empty() {
+            return Ok(());
+        }
+        match self {
+            ExportSink::File(path) => {
+                let mut file =
+                    tokio::fs::OpenOptions::new().create(true).append(true).open(path).await?;
+                let mut content = lines.join("\n");
+                content.push('\n');
+                file.write_all(content.as_bytes()).await?;
+                file.flush().await?;
+            }
+            ExportSink::Udp(address) => {
+                let target = tokio::net::lookup_host(address)
+                    .await?
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("Cannot resolve {}", address))?;
+                let local = if target.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+                let socket = UdpSocket::bind(local).await?;
+                for line in lines {
+                    socket.send_to(line.as_bytes(), target).await?;
+                }
+            }
+            ExportSink::Tcp(address) => {
+                let mut stream = TcpStream::connect(address).await?;
+                let mut content = String::new();
+                for line in lines {
+                    if format == ExportFormat::Syslog {
+                        content.push_str(&format!("{} {}", line.len(), line));
+                    } else {
+                        content.push_str(line);
+                        content.push('\n');
+                    }
+                }
+                stream.write_all(content.as_bytes()).await?;
+                stream.shutdown().await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What one run of an exporter delivered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExportSummary {
+    pub audit_rows: usize,
+    pub security_events: usize,
+}
+
+/// Incremental export of the audit log and security events under a cursor.
+pub struct SiemExporter {
+    storage: Arc<dyn WalletStorageTrait + Send + Sync>,
+    name: String,
+    format: ExportFormat,
+    sink: ExportSink,
+    hostname: String,
+    interval: Duration,
+}
+
+impl SiemExporter {
+    pub fn new(
+        storage: Arc<dyn WalletStorageTrait + Send + Sync>,
+        name: &str,
+        format: ExportFormat,
+        sink: ExportSink,
+    ) -> Self {
+        Self {
+            storage,
+            name: name.to_string(),
+            format,
+            sink,
+            hostname: "-".to_string(),
+            interval: Duration::from_secs(60),
+        }
+    }
+
+    pub fn from_config(
+        storage: Arc<dyn WalletStorageTrait + Send + Sync>,
+        config: &SiemExportConfig,
+    ) -> Result<Self> {
+        let mut exporter =
+            Self::new(storage, &config.name, config.format, config.endpoint.parse()?)
+                .with_interval(Duration::from_secs(config.interval));
+        if let Some(hostname) = &config.hostname {
+            exporter = exporter.with_hostname(hostname);
+        }
+        Ok(exporter)
+    }
+
+    /// Host name written into syslog records.
+    pub fn with_hostname(mut self, hostname: &str) -> Self {
+        self.hostname = hostname.to_string();
+        self
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Delivers the audit rows and the `events` not delivered yet and moves
+    /// the cursor past them.
+    pub async fn export_pending(&self, events: &[SecurityEvent]) -> Result<ExportSummary> {
+        let mut cursor = self.storage.get_export_cursor(&self.name).await?.unwrap_or_else(|| {
+            AuditExportCursor {
+                name: self.name.clone(),
+                last_log_id: 0,
+                last_event_at: None,
+                updated_at: Utc::now(),
+            }
+        });
+        let mut summary = ExportSummary::default();
+
+        loop {
+            let query = AuditQuery {
+                after_id: Some(cursor.last_log_id),
+                oldest_first: true,
+                limit: BATCH_SIZE,
+                ..AuditQuery::default()
+            };
+            let logs = self.storage.query_audit_logs(&query).await?;
+            let Some(last) = logs.last() else {
+                break;
+            };
+            let lines: Vec<String> = logs
+                .iter()
+                .map(|log| self.format.render(&ExportRecord::from(log), &self.hostname))
+                .collect();
+            self.sink.deliver(self.format, &lines).await?;
+            cursor.last_log_id = last.id;
+            cursor.updated_at = Utc::now();
+            self.storage.set_export_cursor(&cursor).await?;
+            summary.audit_rows += logs.len();
+            if (logs.len() as i64) < BATCH_SIZE {
+                break;
+            }
+        }
+
+        let mut pending: Vec<&SecurityEvent> = events
+            .iter()
+            .filter(|e| cursor.last_event_at.is_none_or(|last| e.timestamp > last))
+            .collect();
+        pending.sort_by_key(|e| e.timestamp);
+        if let Some(last) = pending.last() {
+            let lines: Vec<String> = pending
+                .iter()
+                .map(|event| self.format.render(&ExportRecord::from(*event), &self.hostname))
+                .collect();
+            self.sink.deliver(self.format, &lines).await?;
+            cursor.last_event_at = Some(last.timestamp);
+            cursor.updated_at = Utc::now();
+            self.storage.set_export_cursor(&cursor).await?;
+            summary.security_events = pending.len();
+        }
+        Ok(summary)
+    }
+
+    /// Runs `export_pending` every interval until the task is aborted,
+    /// starting immediately, with the events held by the global security
+    /// monitor.
+    pub fn spawn(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.interval);
+            loop {
+                interval.tick().await;
+                let events = match crate::monitoring::get_security_monitor() {
+                    Some(monitor) => monitor.get_recent_security_events(usize::MAX).await,
+                    None => Vec::new(),
+                };
+                match self.export_pending(&events).await {
+                    Ok(summary) if summary != ExportSummary::default() => info!(
+                        "SIEM export {} delivered {} audit rows and {} security events",
+                        self.name, summary.audit_rows, summary.security_events
+                    ),
+                    Ok(_) => {}
+                    Err(e) => warn!("SIEM export {} failed: {}", self.name, e),
+                }
+            }
+        })
+    }
+}
+
+/// Delivers the audit rows created in `[since, until)` without touching any
+/// cursor. Returns how many were delivered.
+pub async fn export_range(
+    storage: &(dyn WalletStorageTrait + Send + Sync),
+    format: ExportFormat,
+    sink: &ExportSink,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    hostname: &str,
+) -> Result<usize> {
+    let mut query = AuditQuery {
+        since,
+        until,
+        after_id: Some(0),
+        oldest_first: true,
+        limit: BATCH_SIZE,
+        ..AuditQuery::default()
+    };
+    let mut exported = 0;
+    loop {
+        let logs = storage.query_audit_logs(&query).await?;
+        let Some(last) = logs.last() else {
+            break;
+        };
+        let lines: Vec<String> =
+            logs.iter().map(|log| format.render(&ExportRecord::from(log), hostname)).collect();
+        sink.deliver(format, &lines).await?;
+        exported += logs.len();
+        query.after_id = Some(last.id);
+        if (logs.len() as i64) < BATCH_SIZE {
+            break;
+        }
+    }
+    Ok(exported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitoring::SecurityEventType;
+    use crate::storage::WalletStorage;
+    use chrono::TimeZone;
+
+    fn record() -> ExportRecord {
+        ExportRecord {
+            source: "audit",
+            id: Some(7),
+            timestamp: Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap(),
+            action: "transaction_sent".to_string(),
+            severity: 3,
+            actor: Some("alice".to_string()),
+            wallet_id: Some("w1".to_string()),
+            ip_address: Some("10.0.0.1".to_string()),
+            user_agent: None,
+            request_id: Some("req-1".to_string()),
+            details: Some("0xabc 1.5 to a=b|c\nnext".to_string()),
+            hash: None,
+        }
+    }
+
+    #[test]
+    fn test_cef_escapes_values() {
+        let line = ExportFormat::Cef.render(&record(), "-");
+        assert!(line.starts_with("CEF:0|DeFi Hot Wallet|defi-hot-wallet|"), "{}", line);
+        assert!(line.contains("|audit:transaction_sent|transaction_sent|3|rt=1714564800000 "));
+        assert!(line.contains("externalId=7 suser=alice src=10.0.0.1 cs1Label=walletId cs1=w1"));
+        assert!(line.ends_with(r"msg=0xabc 1.5 to a\=b|c\nnext"), "{}", line);
+    }
+
+    #[test]
+    fn test_syslog_is_rfc5424() {
+        let line = ExportFormat::Syslog.render(&record(), "wallet host");
+        let prefix = format!(
+            "<110>1 2024-05-01T12:00:00.000000Z wallet_host defi-hot-wallet {} transaction_sent ",
+            std::process::id()
+        );
+        assert!(line.starts_with(&prefix), "{}", line);
+        assert!(line.contains(
+            r#"[wallet@32473 source="audit" id="7" actor="alice" wallet="w1" ip="10.0.0.1" requestId="req-1"]"#
+        ));
+        assert!(line.ends_with("] 0xabc 1.5 to a=b|c next"));
+
+        let critical = ExportRecord { severity: 10, ..record() };
+        assert!(ExportFormat::Syslog.render(&critical, "-").starts_with("<106>1 "));
+    }
+
+    #[test]
+    fn test_json_lines_and_sinks_parse() {
+        let line = ExportFormat::JsonLines.render(&record(), "-");
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["action"], "transaction_sent");
+        assert_eq!(value["id"], 7);
+
+        assert_eq!("jsonl".parse::<ExportFormat>().unwrap(), ExportFormat::JsonLines);
+        assert_eq!(
+            "udp://127.0.0.1:514".parse::<ExportSink>().unwrap(),
+            ExportSink::Udp("127.0.0.1:514".to_string())
+        );
+        assert_eq!(
+            "file:///var/log/audit.log".parse::<ExportSink>().unwrap(),
+            ExportSink::File(PathBuf::from("/var/log/audit.log"))
+        );
+        assert!("tcp://localhost".parse::<ExportSink>().is_err());
+        assert!("http://collector:80".parse::<ExportSink>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_exports_are_incremental() {
+        let storage = Arc::new(WalletStorage::new_with_url("sqlite::memory:").await.unwrap());
+        storage.log_action("w1", "wallet_created", "", None, None).await.unwrap();
+        storage.log_action("w1", "transaction_sent", "0xabc", None, None).await.unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let exporter = SiemExporter::new(
+            storage.clone(),
+            "siem",
+            ExportFormat::JsonLines,
+            ExportSink::File(path.clone()),
+        );
+        let event = SecurityEvent {
+            event_type: SecurityEventType::MultipleFailedLogins,
+            description: "5 failed logins".to_string(),
+            severity: SecuritySeverity::High,
+            timestamp: Utc::now(),
+            source_ip: Some("10.0.0.9".to_string()),
+            wallet_id: None,
+        };
+
+        let summary = exporter.export_pending(std::slice::from_ref(&event)).await.unwrap();
+        assert_eq!(summary, ExportSummary { audit_rows: 2, security_events: 1 });
+        // nothing new, nothing sent
+        let summary = exporter.export_pending(std::slice::from_ref(&event)).await.unwrap();
+        assert_eq!(summary, ExportSummary::default());
+
+        storage.log_action("w1", "wallet_backup", "", None, None).await.unwrap();
+        assert_eq!(exporter.export_pending(&[]).await.unwrap().audit_rows, 1);
+        let lines: Vec<serde_json::Value> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let actions: Vec<&str> = lines.iter().map(|l| l["action"].as_str().unwrap()).collect();
+        assert_eq!(
+            actions,
+            ["wallet_created", "transaction_sent", "multiple_failed_logins", "wallet_backup"]
+        );
+
+        // another export has its own cursor; a range export has none
+        let collector = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let sink = ExportSink::Udp(collector.local_addr().unwrap().to_string());
+        let exported =
+            export_range(storage.as_ref(), ExportFormat::Syslog, &sink, None, None, "host")
+                .await
+                .unwrap();
+        assert_eq!(exported, 3);
+        let mut buf = [0u8; 2048];
+        let len = collector.recv(&mut buf).await.unwrap();
+        let datagram = std::str::from_utf8(&buf[..len]).unwrap();
+        assert!(datagram.starts_with("<110>1 "), "{}", datagram);
+        assert!(datagram.contains(" wallet_created [wallet@32473 source=\"audit\" id=\"1\""));
+    }
+}
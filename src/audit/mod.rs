@@ -1,5 +1,8 @@
 pub mod alert;
+pub mod chain;
 pub mod confirmation;
+pub mod context;
+pub mod export;
 pub mod logging;
 pub mod operation_log;
 pub mod rollback;
@@ -1,10 +1,7 @@
 // src/bin/bridge_test.rs
 use chrono::Utc;
 use clap::{Parser, Subcommand};
-use defi_hot_wallet::blockchain::bridge::{
-    mock::{EthereumToBSCBridge, EthereumToSolanaBridge, SolanaToEthereumBridge},
-    BridgeTransactionStatus,
-};
+use defi_hot_wallet::blockchain::bridge::{BridgeTransactionStatus, ScriptedBridge};
 use defi_hot_wallet::blockchain::traits::Bridge;
 use defi_hot_wallet::core::{SecureWalletData, WalletInfo};
 use std::str::FromStr;
@@ -112,7 +109,7 @@ async fn execute_bridge_command(
         Commands::EthToSol { amount, token } => {
             println!("Testing ETH to Solana bridge with {} {}", amount, token);
 
-            let bridge = EthereumToSolanaBridge::new("0xMockBridgeContract");
+            let bridge = ScriptedBridge::new("eth-solana");
             let result = bridge
                 .transfer_across_chains("eth", "solana", &token, &amount, &wallet_data)
                 .await?;
@@ -124,7 +121,7 @@ async fn execute_bridge_command(
         Commands::SolToEth { amount, token } => {
             println!("Testing Solana to ETH bridge with {} {}", amount, token);
 
-            let bridge = SolanaToEthereumBridge::new("0xMockReverseBridgeContract");
+            let bridge = ScriptedBridge::new("solana-eth");
             let result = bridge
                 .transfer_across_chains("solana", "eth", &token, &amount, &wallet_data)
                 .await?;
@@ -136,7 +133,7 @@ async fn execute_bridge_command(
         Commands::EthToBsc { amount, token } => {
             println!("Testing ETH to BSC bridge with {} {}", amount, token);
 
-            let bridge = EthereumToBSCBridge::new("0xMockEthBscBridge");
+            let bridge = ScriptedBridge::new("eth-bsc");
             let result =
                 bridge.transfer_across_chains("eth", "bsc", &token, &amount, &wallet_data).await?;
 
@@ -202,10 +199,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_bridge_execution() {
-        std::env::set_var("BRIDGE_MOCK_FORCE_SUCCESS", "1");
         let result = run_bridge_test("eth", "solana", "10.0", "USDC").await;
         assert!(result.is_ok());
-        std::env::remove_var("BRIDGE_MOCK_FORCE_SUCCESS");
     }
 
     #[tokio::test]
@@ -219,10 +214,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_bridge_zero_value() {
-        std::env::set_var("BRIDGE_MOCK_FORCE_SUCCESS", "1");
         let result = run_bridge_test("eth", "solana", "0.0", "USDC").await;
         assert!(result.is_ok());
-        std::env::remove_var("BRIDGE_MOCK_FORCE_SUCCESS");
     }
 
     #[tokio::test]
@@ -1,22 +1,34 @@
 use anyhow::Context;
 use clap::Parser;
+use defi_hot_wallet::blockchain::offline::{OfflineSigningRequest, DEFAULT_UR_FRAGMENT_LEN};
+use defi_hot_wallet::blockchain::swap::{
+    format_token_amount, swap_routers_from_config, SwapParams,
+};
 use defi_hot_wallet::cli::{Cli, Commands};
-use defi_hot_wallet::core::config::WalletConfig;
+use defi_hot_wallet::core::config::{SwapConfig, WalletConfig};
 use defi_hot_wallet::core::WalletManager;
-use std::collections::HashMap;
+use defi_hot_wallet::crypto::keyfile::EncryptedKeyFile;
 use tokio::fs;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
-    // 从默认配置构建，然后覆盖对测试/CLI运行重要的字段
-    let mut wallet_config = WalletConfig::default();
-    // 对测试使用内存中的 sqlite 以避免接触磁盘
-    wallet_config.storage.database_url = "sqlite::memory:".to_string();
-    // 确保区块链网络映射存在（避免需要 BlockchainConfig::default）
-    wallet_config.blockchain.networks = HashMap::new();
-    let wallet_manager = WalletManager::new(&wallet_config).await?;
+    // Signing runs on the offline machine: no database, no network.
+    if let Commands::Sign { input, key_file, output, ur } = &cli.command {
+        return sign_offline(input, key_file, output.as_deref(), *ur).await;
+    }
+
+    // 与服务器使用同一份配置（DATABASE_URL 与网络）
+    let wallet_config = WalletConfig::from_env();
+    let mut wallet_manager = WalletManager::new(&wallet_config).await?;
+    if let Ok(path) = std::env::var("SWAP_CONFIG") {
+        let routers = swap_routers_from_config(
+            &SwapConfig::from_file(&path)?,
+            &WalletConfig::default().blockchain,
+        )?;
+        wallet_manager = wallet_manager.with_swap_routers(routers);
+    }
 
     match cli.command {
         Commands::Create { name, output } => {
@@ -42,6 +54,46 @@ async fn main() -> anyhow::Result<()> {
         Commands::Bridge { name, from_chain: _, to_chain: _, token: _, amount: _ } => {
             println!("桥接: {}", name);
         }
+        Commands::Swap {
+            name,
+            network,
+            token_in,
+            token_out,
+            amount,
+            slippage_bps,
+            deadline_seconds,
+            quote_only,
+        } => {
+            let params = SwapParams {
+                slippage_bps,
+                deadline_seconds,
+                ..SwapParams::new(&token_in, &token_out, &amount)
+            };
+            if quote_only {
+                let quote = wallet_manager.quote_swap(&name, &network, &params).await?;
+                println!("{}", serde_json::to_string_pretty(&quote)?);
+            } else {
+                let receipt = wallet_manager.swap_tokens(&name, &network, &params).await?;
+                println!(
+                    "Swap sent: {} ({} {} -> at least {} {})",
+                    receipt.tx_hash,
+                    receipt.quote.amount_in,
+                    token_in,
+                    receipt.quote.amount_out_min,
+                    token_out
+                );
+            }
+        }
+        Commands::Sign { .. } => unreachable!("signing is handled before any setup"),
+        Commands::ExportKey { name, network, output } => {
+            let passphrase = std::env::var("WALLET_KEY_PASSPHRASE")
+                .context("WALLET_KEY_PASSPHRASE is not set")?;
+            let key_file = wallet_manager.export_key_file(&name, &network, &passphrase).await?;
+            fs::write(&output, serde_json::to_string_pretty(&key_file)?)
+                .await
+                .context("write key file")?;
+            println!("Key of {} on {} written to {}", name, network, output.display());
+        }
         Commands::GenerateMnemonic => {
             // simple 12-word mock mnemonic for tests
             println!("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about");
@@ -54,6 +106,64 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Signs an exported request with a local key file. Nothing here touches the network.
+async fn sign_offline(
+    input: &std::path::Path,
+    key_file: &std::path::Path,
+    output: Option<&std::path::Path>,
+    ur: bool,
+) -> anyhow::Result<()> {
+    let content = fs::read_to_string(input).await.context("read unsigned transaction")?;
+    let request = if content.trim_start().starts_with("ur:") {
+        let fragments: Vec<String> =
+            content.lines().filter(|l| !l.trim().is_empty()).map(str::to_string).collect();
+        OfflineSigningRequest::from_ur(&fragments)?
+    } else {
+        OfflineSigningRequest::from_json(&content)?
+    };
+
+    println!(
+        "Signing {} transfer of {} to {}",
+        request.network, request.amount, request.to_address
+    );
+    // what is actually signed, as decoded from the transaction
+    let decoded = request.decoded()?;
+    println!("  to:       {}", decoded.to);
+    println!("  value:    {} ({} base units)", decoded.amount, decoded.value);
+    if let Some(chain_id) = decoded.chain_id {
+        println!("  chain id: {}", chain_id);
+    }
+    if let Some(call) = &decoded.token_call {
+        let decimals = request.token.as_ref().map_or(0, |token| token.decimals);
+        println!("  call:     {:?}", call);
+        println!(
+            "  tokens:   {} to {:?} ({} base units)",
+            format_token_amount(call.amount(), decimals),
+            call.counterparty(),
+            call.amount()
+        );
+    }
+
+    let key_file: EncryptedKeyFile =
+        serde_json::from_str(&fs::read_to_string(key_file).await.context("read key file")?)
+            .context("parse key file")?;
+    let passphrase =
+        std::env::var("WALLET_KEY_PASSPHRASE").context("WALLET_KEY_PASSPHRASE is not set")?;
+    let signer = key_file.signer(&passphrase)?;
+    let signed = request.sign(&signer).await?;
+
+    let rendered =
+        if ur { signed.to_ur(DEFAULT_UR_FRAGMENT_LEN)?.join("\n") } else { signed.to_json()? };
+    match output {
+        Some(path) => {
+            fs::write(path, rendered).await.context("write signed transaction")?;
+            println!("Signed transaction written to {}", path.display());
+        }
+        None => println!("{}", rendered),
+    }
+    Ok(())
+}
+
 /// 辅助函数：如果提供了 --output 路径，则将钱包信息写入文件。
 async fn write_wallet_output_if_requested(
     output_path: Option<&std::path::Path>,
@@ -0,0 +1,664 @@
+// src/blockchain/bridge/evm.rs
+//! Lock-and-mint bridge adapter for EVM networks.
+//!
+//! A transfer approves the source token bridge and calls its deposit function,
+//! which locks the tokens and publishes a message through the core bridge. The
+//! message (emitter, sequence) is read back from the receipt; once the source
+//! transaction has enough confirmations the transfer is `InTransit`, and it is
+//! `Completed` once the destination token bridge reports the message redeemed;
+//! its `TransferRedeemed` log then gives the destination transaction.
+//!
+//! Only the Wormhole token bridge ABI is implemented. Destinations must be EVM
+//! networks, since redemption is checked with an `eth_call`.
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use ethers::abi::{self, ParamType, Token};
+use ethers::providers::{Http, JsonRpcClient, Middleware, Provider};
+use ethers::types::{
+    Address, Bytes, Filter, Log, TransactionReceipt, TransactionRequest, H256, U256, U64,
+};
+use ethers::utils::{format_ether, format_units, id, keccak256, parse_units, ParseUnits};
+use tracing::info;
+
+use crate::blockchain::bridge::quote::BridgeTerms;
+use crate::blockchain::bridge::BridgeTransactionStatus;
+use crate::blockchain::ethereum::{address_from_public_key, send_with_signer};
+use crate::blockchain::traits::Bridge;
+use crate::core::config::{
+    split_route, BlockchainConfig, BridgeConfig, BridgeRouteConfig, BridgeTokenConfig,
+};
+use crate::core::wallet_info::SecureWalletData;
+use crate::crypto::signer::{Signer, SoftwareSigner};
+
+/// `LogMessagePublished(address indexed sender, uint64 sequence, uint32 nonce, bytes payload, uint8 consistencyLevel)`
+const LOG_MESSAGE_PUBLISHED: &str = "LogMessagePublished(address,uint64,uint32,bytes,uint8)";
+/// `TransferRedeemed(uint16 indexed emitterChainId, bytes32 indexed emitterAddress, uint64 indexed sequence)`
+const TRANSFER_REDEEMED: &str = "TransferRedeemed(uint16,bytes32,uint64)";
+/// How many recent destination blocks are searched for the redemption.
+const REDEMPTION_LOOKBACK_BLOCKS: u64 = 50_000;
+
+const APPROVE_GAS: u64 = 60_000;
+const TRANSFER_TOKENS_GAS: u64 = 300_000;
+/// Typical gas used by `completeTransfer` on the destination token bridge.
+const COMPLETE_TRANSFER_GAS: u64 = 300_000;
+/// Time for the guardians to observe and sign a finalized message.
+const GUARDIAN_SIGNING_SECONDS: u64 = 60;
+
+/// A cross-chain message published by the core bridge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BridgeMessage {
+    pub emitter_chain: u16,
+    /// Emitting contract (the token bridge), left-padded to 32 bytes.
+    pub emitter: H256,
+    pub sequence: u64,
+    pub nonce: u32,
+    pub consistency_level: u8,
+    pub payload: Bytes,
+}
+
+impl BridgeMessage {
+    /// Message id in the usual `<chain>/<emitter>/<sequence>` form.
+    pub fn id(&self) -> String {
+        format!("{}/{}/{}", self.emitter_chain, hex::encode(self.emitter), self.sequence)
+    }
+
+    /// Digest the destination bridge records on redemption:
+    /// `keccak256(keccak256(body))` over the signed message body.
+    pub fn digest(&self, timestamp: u32) -> H256 {
+        let mut body = Vec::with_capacity(51 + self.payload.len());
+        body.extend_from_slice(&timestamp.to_be_bytes());
+        body.extend_from_slice(&self.nonce.to_be_bytes());
+        body.extend_from_slice(&self.emitter_chain.to_be_bytes());
+        body.extend_from_slice(self.emitter.as_bytes());
+        body.extend_from_slice(&self.sequence.to_be_bytes());
+        body.push(self.consistency_level);
+        body.extend_from_slice(&self.payload);
+        H256(keccak256(keccak256(body)))
+    }
+}
+
+pub struct EvmLockMintBridge<P: JsonRpcClient = Http> {
+    route: BridgeRouteConfig,
+    source: Provider<P>,
+    destination: Provider<P>,
+    source_chain_id: u64,
+}
+
+impl EvmLockMintBridge<Http> {
+    pub fn new(
+        route: BridgeRouteConfig,
+        source_rpc_url: &str,
+        destination_rpc_url: &str,
+        source_chain_id: u64,
+    ) -> Result<Self> {
+        let source = Provider::<Http>::try_from(source_rpc_url)
+            .with_context(|| format!("Invalid source RPC URL '{}'", source_rpc_url))?;
+        let destination = Provider::<Http>::try_from(destination_rpc_url)
+            .with_context(|| format!("Invalid destination RPC URL '{}'", destination_rpc_url))?;
+        Ok(Self::with_providers(route, source, destination, source_chain_id))
+    }
+}
+
+impl<P: JsonRpcClient + 'static> EvmLockMintBridge<P> {
+    pub fn with_providers(
+        route: BridgeRouteConfig,
+        source: Provider<P>,
+        destination: Provider<P>,
+        source_chain_id: u64,
+    ) -> Self {
+        Self { route, source, destination, source_chain_id }
+    }
+
+    /// The message published by source transaction `tx_hash`, if it has been mined.
+    pub async fn bridge_message(&self, tx_hash: &str) -> Result<Option<BridgeMessage>> {
+        let Some(receipt) = self.receipt(tx_hash).await? else {
+            return Ok(None);
+        };
+        Ok(Some(self.message_from_receipt(&receipt)?))
+    }
+
+    async fn receipt(&self, tx_hash: &str) -> Result<Option<TransactionReceipt>> {
+        let hash = H256::from_str(tx_hash).context("Invalid source transaction hash")?;
+        Ok(self.source.get_transaction_receipt(hash).await?)
+    }
+
+    /// The transfer message in `receipt`. Only a message published by the
+    /// configured token bridge counts: any contract can publish through the
+    /// core bridge, and a message from another sender carries no transfer.
+    fn message_from_receipt(&self, receipt: &TransactionReceipt) -> Result<BridgeMessage> {
+        let core_bridge = parse_address(&self.route.core_bridge)?;
+        let token_bridge = H256::from(parse_address(&self.route.token_bridge)?);
+        let log = receipt
+            .logs
+            .iter()
+            .find(|log| {
+                log.address == core_bridge
+                    && log.topics.first() == Some(&H256(keccak256(LOG_MESSAGE_PUBLISHED)))
+                    && log.topics.get(1) == Some(&token_bridge)
+            })
+            .ok_or_else(|| anyhow::anyhow!("No bridge message in receipt"))?;
+        parse_message_log(log, self.route.source_bridge_chain)
+    }
+
+    /// Signs `tx` for the source chain and broadcasts it.
+    async fn send(&self, signer: &dyn Signer, tx: TransactionRequest) -> Result<H256> {
+        send_with_signer(&self.source, signer, tx, self.source_chain_id).await
+    }
+
+    async fn message_fee(&self) -> Result<U256> {
+        let call = TransactionRequest::new()
+            .to(parse_address(&self.route.core_bridge)?)
+            .data(selector("messageFee()"));
+        let out = self.source.call(&call.into(), None).await?;
+        Ok(U256::from_big_endian(out.get(..32).unwrap_or(&[0u8; 32])))
+    }
+
+    fn token(&self, token: &str) -> Result<&BridgeTokenConfig> {
+        self.route
+            .tokens
+            .get(token)
+            .ok_or_else(|| anyhow::anyhow!("Token {} is not supported on this bridge", token))
+    }
+
+    async fn is_redeemed(&self, digest: H256) -> Result<bool> {
+        let mut data = selector("isTransferCompleted(bytes32)").to_vec();
+        data.extend(abi::encode(&[Token::FixedBytes(digest.as_bytes().to_vec())]));
+        let call = TransactionRequest::new()
+            .to(parse_address(&self.route.destination_token_bridge)?)
+            .data(data);
+        let out = self.destination.call(&call.into(), None).await?;
+        Ok(out.get(31) == Some(&1))
+    }
+}
+
+#[async_trait]
+impl<P: JsonRpcClient + 'static> Bridge for EvmLockMintBridge<P> {
+    async fn transfer_across_chains(
+        &self,
+        from_chain: &str,
+        to_chain: &str,
+        token: &str,
+        amount: &str,
+        wallet_data: &SecureWalletData,
+    ) -> Result<String> {
+        let token_config = self.token(token)?;
+        let token_address = parse_address(&token_config.address)?;
+        let token_bridge = parse_address(&self.route.token_bridge)?;
+        let amount = match parse_units(amount, token_config.decimals as u32) {
+            Ok(ParseUnits::U256(amount)) => amount,
+            Ok(ParseUnits::I256(_)) => {
+                return Err(anyhow::anyhow!("Bridge amount must not be negative"))
+            }
+            Err(e) => return Err(anyhow::anyhow!("Invalid amount '{}': {}", amount, e)),
+        };
+        if amount.is_zero() {
+            return Err(anyhow::anyhow!("Bridge amount must be greater than zero"));
+        }
+
+        let signer = SoftwareSigner::for_network(&wallet_data.encrypted_master_key, from_chain)?;
+        let from = address_from_public_key(&signer.public_key().await?)?;
+        let recipient = SoftwareSigner::for_network(&wallet_data.encrypted_master_key, to_chain)?;
+        let recipient = address_from_public_key(&recipient.public_key().await?)?;
+
+        let gas_price = self.source.get_gas_price().await?;
+        let nonce = self.source.get_transaction_count(from, None).await?;
+        let message_fee = self.message_fee().await?;
+
+        let approve = TransactionRequest::new()
+            .from(from)
+            .to(token_address)
+            .data(encode_approve(token_bridge, amount))
+            .gas(APPROVE_GAS)
+            .gas_price(gas_price)
+            .nonce(nonce);
+        self.send(&signer, approve).await.context("Token approval failed")?;
+
+        let deposit = TransactionRequest::new()
+            .from(from)
+            .to(token_bridge)
+            .data(encode_transfer_tokens(
+                token_address,
+                amount,
+                self.route.destination_bridge_chain,
+                recipient,
+                rand::random(),
+            ))
+            .value(message_fee)
+            .gas(TRANSFER_TOKENS_GAS)
+            .gas_price(gas_price)
+            .nonce(nonce + 1);
+        let tx_hash = self.send(&signer, deposit).await.context("Bridge deposit failed")?;
+
+        info!(
+            "Bridge deposit {:?} sent: {} {} {} -> {}",
+            tx_hash, amount, token, from_chain, to_chain
+        );
+        Ok(format!("{:?}", tx_hash))
+    }
+
+    async fn check_transfer_status(&self, tx_id: &str) -> Result<BridgeTransactionStatus> {
+        let Some(receipt) = self.receipt(tx_id).await? else {
+            return Ok(BridgeTransactionStatus::Initiated);
+        };
+        if receipt.status != Some(U64::one()) {
+            return Ok(BridgeTransactionStatus::Failed("Source transaction reverted".to_string()));
+        }
+        let Some(block_number) = receipt.block_number else {
+            return Ok(BridgeTransactionStatus::Initiated);
+        };
+
+        let head = self.source.get_block_number().await?;
+        let confirmations = head.saturating_sub(block_number).as_u64() + 1;
+        if confirmations < self.route.confirmations {
+            return Ok(BridgeTransactionStatus::Initiated);
+        }
+
+        let message = match self.message_from_receipt(&receipt) {
+            Ok(message) => message,
+            Err(e) => return Ok(BridgeTransactionStatus::Failed(e.to_string())),
+        };
+        let block = self
+            .source
+            .get_block(block_number)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Source block {} not found", block_number))?;
+
+        if self.is_redeemed(message.digest(block.timestamp.as_u32())).await? {
+            Ok(BridgeTransactionStatus::Completed)
+        } else {
+            Ok(BridgeTransactionStatus::InTransit)
+        }
+    }
+
+    /// The destination token bridge emits `TransferRedeemed` for the message;
+    /// its transaction is the delivery. Only recent blocks are searched.
+    async fn destination_transaction(&self, tx_id: &str) -> Result<Option<String>> {
+        let Some(message) = self.bridge_message(tx_id).await? else {
+            return Ok(None);
+        };
+        let head = self.destination.get_block_number().await?;
+        let filter = Filter::new()
+            .address(parse_address(&self.route.destination_token_bridge)?)
+            .topic0(H256(keccak256(TRANSFER_REDEEMED)))
+            .topic1(H256::from_low_u64_be(message.emitter_chain.into()))
+            .topic2(message.emitter)
+            .topic3(H256::from_low_u64_be(message.sequence))
+            .from_block(head.saturating_sub(REDEMPTION_LOOKBACK_BLOCKS.into()));
+        let logs = self.destination.get_logs(&filter).await?;
+        Ok(logs.iter().find_map(|log| log.transaction_hash).map(|hash| format!("{:?}", hash)))
+    }
+
+    /// Gas for approve + deposit (plus the core bridge message fee) and for
+    /// the redemption, at current gas prices. Wrapped tokens are minted on the
+    /// destination, so there is no liquidity cap; the token bridge truncates
+    /// amounts to 8 decimals, which sets the minimum.
+    async fn quote_terms(
+        &self,
+        _from_chain: &str,
+        _to_chain: &str,
+        token: &str,
+    ) -> Result<BridgeTerms> {
+        let decimals = self.token(token)?.decimals.min(8);
+        let source_gas_price = self.source.get_gas_price().await?;
+        let message_fee = self.message_fee().await?;
+        let destination_gas_price = self.destination.get_gas_price().await?;
+
+        let source_gas =
+            source_gas_price * U256::from(APPROVE_GAS + TRANSFER_TOKENS_GAS) + message_fee;
+        let destination_gas = destination_gas_price * U256::from(COMPLETE_TRANSFER_GAS);
+        Ok(BridgeTerms {
+            protocol_fee_bps: 0,
+            source_gas: Some(format_ether(source_gas)),
+            destination_gas: Some(format_ether(destination_gas)),
+            min_amount: format_units(U256::one(), decimals as u32)?,
+            max_amount: None,
+            available_liquidity: None,
+            confirmations: self.route.confirmations,
+            relay_seconds: GUARDIAN_SIGNING_SECONDS,
+        })
+    }
+}
+
+/// Builds an adapter for every route in `config`, using the RPC endpoints and
+/// chain ids from `blockchain`.
+pub fn bridges_from_config(
+    config: &BridgeConfig,
+    blockchain: &BlockchainConfig,
+) -> Result<HashMap<String, Box<dyn Bridge>>> {
+    config.validate(blockchain)?;
+    let mut bridges: HashMap<String, Box<dyn Bridge>> = HashMap::new();
+    for (key, route) in &config.routes {
+        // validate() guarantees both networks exist and are EVM networks
+        let (from, to) = split_route(key, &blockchain.networks).expect("validated route key");
+        let source = &blockchain.networks[from];
+        let destination = &blockchain.networks[to];
+        let bridge = EvmLockMintBridge::new(
+            route.clone(),
+            &source.rpc_url,
+            &destination.rpc_url,
+            source.chain_id.expect("validated EVM network"),
+        )?;
+        bridges.insert(key.clone(), Box::new(bridge));
+    }
+    Ok(bridges)
+}
+
+fn parse_address(address: &str) -> Result<Address> {
+    Address::from_str(address).map_err(|e| anyhow::anyhow!("Invalid address '{}': {}", address, e))
+}
+
+fn selector(signature: &str) -> [u8; 4] {
+    id(signature)
+}
+
+/// ERC-20 `approve(spender, amount)` calldata.
+pub fn encode_approve(spender: Address, amount: U256) -> Bytes {
+    let mut data = selector("approve(address,uint256)").to_vec();
+    data.extend(abi::encode(&[Token::Address(spender), Token::Uint(amount)]));
+    data.into()
+}
+
+/// Wormhole `transferTokens(token, amount, recipientChain, recipient, arbiterFee, nonce)`
+/// calldata with no relayer fee.
+pub fn encode_transfer_tokens(
+    token: Address,
+    amount: U256,
+    recipient_chain: u16,
+    recipient: Address,
+    nonce: u32,
+) -> Bytes {
+    let mut data =
+        selector("transferTokens(address,uint256,uint16,bytes32,uint256,uint32)").to_vec();
+    data.extend(abi::encode(&[
+        Token::Address(token),
+        Token::Uint(amount),
+        Token::Uint(recipient_chain.into()),
+        Token::FixedBytes(H256::from(recipient).as_bytes().to_vec()),
+        Token::Uint(U256::zero()),
+        Token::Uint(nonce.into()),
+    ]));
+    data.into()
+}
+
+/// Decodes a `LogMessagePublished` log emitted by the core bridge.
+pub fn parse_message_log(log: &Log, emitter_chain: u16) -> Result<BridgeMessage> {
+    let sender = log
+        .topics
+        .get(1)
+        .copied()
+        .ok_or_else(|| anyhow::anyhow!("Bridge message log is missing its sender"))?;
+    let tokens = abi::decode(
+        &[ParamType::Uint(64), ParamType::Uint(32), ParamType::Bytes, ParamType::Uint(8)],
+        &log.data,
+    )
+    .context("Malformed bridge message log")?;
+
+    let uint = |i: usize| tokens[i].clone().into_uint().expect("decoded as uint");
+    Ok(BridgeMessage {
+        emitter_chain,
+        emitter: sender,
+        sequence: uint(0).as_u64(),
+        nonce: uint(1).as_u32(),
+        consistency_level: uint(3).as_u32() as u8,
+        payload: tokens[2].clone().into_bytes().expect("decoded as bytes").into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::BridgeProtocol;
+    use crate::core::wallet_info::test_wallet;
+    use ethers::providers::MockProvider;
+    use ethers::types::Block;
+
+    const CORE_BRIDGE: &str = "0x98f3c9e6E3fAce36bAAd05FE09d375Ef1464288B";
+    const TOKEN_BRIDGE: &str = "0x3ee18B2214AFF97000D974cf647E7C347E8fa585";
+    const USDC: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+
+    fn route() -> BridgeRouteConfig {
+        let mut tokens = HashMap::new();
+        tokens.insert(
+            "USDC".to_string(),
+            BridgeTokenConfig { address: USDC.to_string(), decimals: 6 },
+        );
+        BridgeRouteConfig {
+            protocol: BridgeProtocol::Wormhole,
+            token_bridge: TOKEN_BRIDGE.to_string(),
+            core_bridge: CORE_BRIDGE.to_string(),
+            destination_token_bridge: "0x5a58505a96D1dbf8dF91cB21B54419FC36e93fdE".to_string(),
+            source_bridge_chain: 2,
+            destination_bridge_chain: 5,
+            confirmations: 3,
+            tokens,
+        }
+    }
+
+    fn mock_bridge() -> (EvmLockMintBridge<MockProvider>, MockProvider, MockProvider) {
+        let (source, source_mock) = Provider::mocked();
+        let (destination, destination_mock) = Provider::mocked();
+        (
+            EvmLockMintBridge::with_providers(route(), source, destination, 1),
+            source_mock,
+            destination_mock,
+        )
+    }
+
+    /// `LogMessagePublished` as emitted for a token bridge transfer: sequence 4242,
+    /// nonce 7, consistency level 1 and a truncated transfer payload.
+    fn recorded_log() -> Log {
+        Log {
+            address: parse_address(CORE_BRIDGE).unwrap(),
+            topics: vec![
+                H256(keccak256(LOG_MESSAGE_PUBLISHED)),
+                H256::from(parse_address(TOKEN_BRIDGE).unwrap()),
+            ],
+            data: abi::encode(&[
+                Token::Uint(4242u64.into()),
+                Token::Uint(7u32.into()),
+                Token::Bytes(
+                    hex::decode("01000000000000000000000000000000000000000000000000000000000f4240")
+                        .unwrap(),
+                ),
+                Token::Uint(1u8.into()),
+            ])
+            .into(),
+            ..Default::default()
+        }
+    }
+
+    fn receipt(status: u64, block: u64) -> TransactionReceipt {
+        TransactionReceipt {
+            status: Some(status.into()),
+            block_number: Some(block.into()),
+            logs: vec![recorded_log()],
+            ..Default::default()
+        }
+    }
+
+    fn block(timestamp: u64) -> Block<H256> {
+        Block { timestamp: timestamp.into(), ..Default::default() }
+    }
+
+    fn abi_bool(value: bool) -> Bytes {
+        abi::encode(&[Token::Bool(value)]).into()
+    }
+
+    const TX: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+
+    #[test]
+    fn test_calldata_encoding() {
+        let bridge = parse_address(TOKEN_BRIDGE).unwrap();
+        let approve = encode_approve(bridge, 1_000_000u64.into());
+        assert_eq!(hex::encode(&approve[..4]), "095ea7b3");
+        assert_eq!(approve.len(), 4 + 2 * 32);
+
+        let recipient = Address::repeat_byte(0xab);
+        let deposit = encode_transfer_tokens(
+            parse_address(USDC).unwrap(),
+            1_000_000u64.into(),
+            5,
+            recipient,
+            9,
+        );
+        assert_eq!(deposit.len(), 4 + 6 * 32);
+        assert_eq!(&deposit[4 + 2 * 32 + 30..4 + 3 * 32], &[0, 5]);
+        // recipient is left-padded to bytes32
+        assert_eq!(&deposit[4 + 3 * 32..4 + 3 * 32 + 12], &[0u8; 12]);
+        assert_eq!(&deposit[4 + 3 * 32 + 12..4 + 4 * 32], recipient.as_bytes());
+    }
+
+    #[test]
+    fn test_parse_recorded_message_log() {
+        let message = parse_message_log(&recorded_log(), 2).unwrap();
+        assert_eq!(message.sequence, 4242);
+        assert_eq!(message.nonce, 7);
+        assert_eq!(message.consistency_level, 1);
+        assert_eq!(
+            message.id(),
+            "2/0000000000000000000000003ee18b2214aff97000d974cf647e7c347e8fa585/4242"
+        );
+        assert_ne!(message.digest(1), message.digest(2));
+    }
+
+    #[tokio::test]
+    async fn test_transfer_sends_approve_then_deposit() {
+        let (bridge, source, _) = mock_bridge();
+        let deposit_hash = H256::repeat_byte(0x22);
+        // MockProvider answers in LIFO order
+        source.push(deposit_hash).unwrap();
+        source.push(H256::repeat_byte(0x11)).unwrap();
+        source.push::<Bytes, _>(Bytes::from(abi::encode(&[Token::Uint(U256::zero())]))).unwrap();
+        source.push(U256::from(3)).unwrap();
+        source.push(U256::from(1_000_000_000u64)).unwrap();
+
+        let wallet = test_wallet();
+        let tx =
+            bridge.transfer_across_chains("eth", "polygon", "USDC", "1.5", &wallet).await.unwrap();
+        assert_eq!(tx, format!("{:?}", deposit_hash));
+
+        assert!(bridge
+            .transfer_across_chains("eth", "polygon", "DAI", "1", &wallet)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_status_follows_confirmations_and_redemption() {
+        // not mined yet
+        let (bridge, source, _) = mock_bridge();
+        source.push(Option::<TransactionReceipt>::None).unwrap();
+        assert_eq!(
+            bridge.check_transfer_status(TX).await.unwrap(),
+            BridgeTransactionStatus::Initiated
+        );
+
+        // mined at 100, head at 101: 2 of 3 confirmations
+        let (bridge, source, _) = mock_bridge();
+        source.push(U64::from(101)).unwrap();
+        source.push(receipt(1, 100)).unwrap();
+        assert_eq!(
+            bridge.check_transfer_status(TX).await.unwrap(),
+            BridgeTransactionStatus::Initiated
+        );
+
+        // confirmed, not yet redeemed on the destination
+        let (bridge, source, destination) = mock_bridge();
+        source.push(block(1_700_000_000)).unwrap();
+        source.push(U64::from(102)).unwrap();
+        source.push(receipt(1, 100)).unwrap();
+        destination.push::<Bytes, _>(abi_bool(false)).unwrap();
+        assert_eq!(
+            bridge.check_transfer_status(TX).await.unwrap(),
+            BridgeTransactionStatus::InTransit
+        );
+
+        // redeemed
+        let (bridge, source, destination) = mock_bridge();
+        source.push(block(1_700_000_000)).unwrap();
+        source.push(U64::from(110)).unwrap();
+        source.push(receipt(1, 100)).unwrap();
+        destination.push::<Bytes, _>(abi_bool(true)).unwrap();
+        assert_eq!(
+            bridge.check_transfer_status(TX).await.unwrap(),
+            BridgeTransactionStatus::Completed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_destination_transaction_from_redemption_log() {
+        let (bridge, source, destination) = mock_bridge();
+        let delivery = H256::repeat_byte(0x33);
+        source.push(receipt(1, 100)).unwrap();
+        let redeemed = Log { transaction_hash: Some(delivery), ..Default::default() };
+        destination.push::<Vec<Log>, _>(vec![redeemed]).unwrap();
+        destination.push(U64::from(200)).unwrap();
+        assert_eq!(
+            bridge.destination_transaction(TX).await.unwrap(),
+            Some(format!("{:?}", delivery))
+        );
+
+        // not redeemed within the lookback
+        let (bridge, source, destination) = mock_bridge();
+        source.push(receipt(1, 100)).unwrap();
+        destination.push::<Vec<Log>, _>(vec![]).unwrap();
+        destination.push(U64::from(200)).unwrap();
+        assert_eq!(bridge.destination_transaction(TX).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_quote_terms_use_live_gas_prices() {
+        let (bridge, source, destination) = mock_bridge();
+        let message_fee = U256::from(1_000_000_000_000u64);
+        source.push::<Bytes, _>(Bytes::from(abi::encode(&[Token::Uint(message_fee)]))).unwrap();
+        source.push(U256::from(10_000_000_000u64)).unwrap();
+        destination.push(U256::from(30_000_000_000u64)).unwrap();
+
+        let terms = bridge.quote_terms("eth", "polygon", "USDC").await.unwrap();
+        // 360k gas at 10 gwei plus the message fee
+        assert_eq!(terms.source_gas.as_deref(), Some("0.003601000000000000"));
+        assert_eq!(terms.destination_gas.as_deref(), Some("0.009000000000000000"));
+        assert_eq!(terms.min_amount, "0.000001");
+        assert_eq!(terms.protocol_fee_bps, 0);
+        assert_eq!(terms.confirmations, 3);
+
+        assert!(bridge.quote_terms("eth", "polygon", "DAI").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reverted_or_messageless_source_fails() {
+        let (bridge, source, _) = mock_bridge();
+        source.push(receipt(0, 100)).unwrap();
+        assert!(matches!(
+            bridge.check_transfer_status(TX).await.unwrap(),
+            BridgeTransactionStatus::Failed(_)
+        ));
+
+        let (bridge, source, _) = mock_bridge();
+        source.push(U64::from(110)).unwrap();
+        source.push(TransactionReceipt { logs: vec![], ..receipt(1, 100) }).unwrap();
+        assert_eq!(
+            bridge.check_transfer_status(TX).await.unwrap(),
+            BridgeTransactionStatus::Failed("No bridge message in receipt".to_string())
+        );
+
+        // a message published through the core bridge by another contract
+        let (bridge, source, _) = mock_bridge();
+        source.push(U64::from(110)).unwrap();
+        let mut foreign = recorded_log();
+        foreign.topics[1] = H256::from(Address::repeat_byte(0xee));
+        source.push(TransactionReceipt { logs: vec![foreign], ..receipt(1, 100) }).unwrap();
+        assert_eq!(
+            bridge.check_transfer_status(TX).await.unwrap(),
+            BridgeTransactionStatus::Failed("No bridge message in receipt".to_string())
+        );
+
+        let (bridge, source, _) = mock_bridge();
+        source.push(receipt(1, 100)).unwrap();
+        let message = bridge.bridge_message(TX).await.unwrap().unwrap();
+        assert_eq!(message.sequence, 4242);
+    }
+}
@@ -1,14 +1,23 @@
 // src/blockchain/bridge/mod.rs
 
 // Expose sub-modules
-pub mod mock;
+pub mod evm;
+pub mod quote;
+pub mod recovery;
 pub mod relay;
+pub mod relayer;
+pub mod scripted;
 pub mod transfer;
 
 use crate::blockchain::traits::Bridge;
 use crate::core::wallet_info::SecureWalletData;
 use serde::{Deserialize, Serialize};
 
+pub use evm::{bridges_from_config, EvmLockMintBridge};
+pub use quote::{BridgeQuote, BridgeTerms};
+pub use relayer::BridgeRelayer;
+pub use scripted::{ScriptedBridge, TransferScript};
+
 /// Represents the status of a cross-chain bridge transaction.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum BridgeTransactionStatus {
@@ -16,6 +25,11 @@ pub enum BridgeTransactionStatus {
     InTransit,
     Completed,
     Failed(String),
+    /// A refund was requested from the bridge and has not landed yet.
+    RefundPending,
+    Refunded,
+    /// Recovery could not redeem or refund the transfer; an operator must act.
+    ManualInterventionRequired(String),
 }
 
 /// Represents a cross-chain bridge transaction record.
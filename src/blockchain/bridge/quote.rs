@@ -0,0 +1,254 @@
+// src/blockchain/bridge/quote.rs
+//! Quotes for bridge transfers: fee breakdown, limits, liquidity and ETA.
+//!
+//! Each bridge reports `BridgeTerms` for a token through `Bridge::quote_terms`,
+//! reading live data (gas prices, message fees, pool balances) where it can.
+//! `build_quote` applies the terms to a concrete amount. Token amounts are
+//! fixed-point integers with `AMOUNT_DECIMALS` places, never floats.
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use ethers::types::U256;
+use ethers::utils::{format_units, parse_units, ParseUnits};
+use serde::{Deserialize, Serialize};
+
+/// Precision used for token amount arithmetic.
+pub const AMOUNT_DECIMALS: u32 = 18;
+
+/// Block time assumed for networks without a `NetworkConfig`.
+pub const DEFAULT_BLOCK_TIME_SECONDS: u64 = 12;
+
+/// What a bridge charges and enforces for one token on one route.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BridgeTerms {
+    /// Protocol fee in basis points of the amount, paid in the bridged token.
+    pub protocol_fee_bps: u32,
+    /// Gas to submit the transfer, in the source chain's native token, if known.
+    pub source_gas: Option<String>,
+    /// Gas to redeem on the destination, in its native token, if known.
+    pub destination_gas: Option<String>,
+    pub min_amount: String,
+    pub max_amount: Option<String>,
+    /// Tokens the bridge can release on the destination; `None` when it mints.
+    pub available_liquidity: Option<String>,
+    /// Source confirmations required before the transfer is relayed.
+    pub confirmations: u64,
+    /// Time the bridge needs to relay once the source transaction is final.
+    pub relay_seconds: u64,
+}
+
+impl Default for BridgeTerms {
+    /// Terms for bridges that do not report their own: a 1% fee, no limits.
+    fn default() -> Self {
+        Self {
+            protocol_fee_bps: 100,
+            source_gas: None,
+            destination_gas: None,
+            min_amount: "0".to_string(),
+            max_amount: None,
+            available_liquidity: None,
+            confirmations: 20,
+            relay_seconds: 60,
+        }
+    }
+}
+
+/// Block times of the two networks of a route, in seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockTimes {
+    pub source: u64,
+    pub destination: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeeBreakdown {
+    /// In the bridged token.
+    pub protocol_fee: String,
+    /// In the source chain's native token; `None` if the bridge cannot estimate it.
+    pub source_gas: Option<String>,
+    /// In the destination chain's native token; `None` if unknown.
+    pub destination_gas: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BridgeQuote {
+    /// Registered bridge key, `"<from>-<to>"`.
+    pub route: String,
+    pub from_chain: String,
+    pub to_chain: String,
+    pub token: String,
+    pub amount: String,
+    /// Amount delivered on the destination after the protocol fee.
+    pub amount_received: String,
+    pub fees: FeeBreakdown,
+    pub min_amount: String,
+    pub max_amount: Option<String>,
+    pub within_limits: bool,
+    pub liquidity_available: bool,
+    pub confirmations: u64,
+    pub eta_seconds: u64,
+    pub estimated_completion_time: DateTime<Utc>,
+}
+
+impl BridgeQuote {
+    /// Whether a transfer of the quoted amount would be accepted.
+    pub fn is_executable(&self) -> bool {
+        self.within_limits && self.liquidity_available
+    }
+}
+
+/// Applies `terms` to `amount`. The ETA is the source confirmations, the
+/// bridge's relay time and one destination block for the redemption.
+pub fn build_quote(
+    route: &str,
+    from_chain: &str,
+    to_chain: &str,
+    token: &str,
+    amount: &str,
+    terms: &BridgeTerms,
+    block_times: BlockTimes,
+) -> Result<BridgeQuote> {
+    let value = parse_amount(amount)?;
+    let min = parse_amount(&terms.min_amount)?;
+    let max = terms.max_amount.as_deref().map(parse_amount).transpose()?;
+    let liquidity = terms.available_liquidity.as_deref().map(parse_amount).transpose()?;
+
+    let protocol_fee = value * U256::from(terms.protocol_fee_bps) / U256::from(10_000u64);
+    let eta_seconds =
+        terms.confirmations * block_times.source + terms.relay_seconds + block_times.destination;
+
+    Ok(BridgeQuote {
+        route: route.to_string(),
+        from_chain: from_chain.to_string(),
+        to_chain: to_chain.to_string(),
+        token: token.to_string(),
+        amount: amount.to_string(),
+        amount_received: format_amount(value.saturating_sub(protocol_fee)),
+        fees: FeeBreakdown {
+            protocol_fee: format_amount(protocol_fee),
+            source_gas: terms.source_gas.clone(),
+            destination_gas: terms.destination_gas.clone(),
+        },
+        min_amount: terms.min_amount.clone(),
+        max_amount: terms.max_amount.clone(),
+        within_limits: value >= min && max.is_none_or(|max| value <= max),
+        liquidity_available: liquidity.is_none_or(|liquidity| value <= liquidity),
+        confirmations: terms.confirmations,
+        eta_seconds,
+        estimated_completion_time: Utc::now() + chrono::Duration::seconds(eta_seconds as i64),
+    })
+}
+
+/// Orders quotes best first: executable before not, then by protocol fee,
+/// then by estimated source gas and destination gas, then by ETA. Gas is in
+/// each chain's native token, so it only breaks ties between equal protocol
+/// fees rather than being priced against them; unknown gas ranks last.
+pub fn rank_quotes(quotes: &mut [BridgeQuote]) {
+    let gas = |gas: &Option<String>| {
+        gas.as_deref().and_then(|g| parse_amount(g).ok()).unwrap_or(U256::MAX)
+    };
+    quotes.sort_by_key(|q| {
+        let fee = parse_amount(&q.fees.protocol_fee).unwrap_or(U256::MAX);
+        (
+            !q.is_executable(),
+            fee,
+            gas(&q.fees.source_gas),
+            gas(&q.fees.destination_gas),
+            q.eta_seconds,
+        )
+    });
+}
+
+/// Parses a non-negative decimal token amount.
+pub fn parse_amount(amount: &str) -> Result<U256> {
+    match parse_units(amount.trim(), AMOUNT_DECIMALS) {
+        Ok(ParseUnits::U256(value)) => Ok(value),
+        Ok(ParseUnits::I256(_)) => Err(anyhow::anyhow!("Amount must not be negative: {}", amount)),
+        Err(e) => Err(anyhow::anyhow!("Invalid amount '{}': {}", amount, e)),
+    }
+}
+
+/// Formats an amount from `parse_amount` without trailing zeros.
+pub fn format_amount(value: U256) -> String {
+    let formatted = format_units(value, AMOUNT_DECIMALS).unwrap_or_else(|_| value.to_string());
+    match formatted.split_once('.') {
+        Some((whole, fraction)) if fraction.trim_end_matches('0').is_empty() => whole.to_string(),
+        Some((whole, fraction)) => format!("{}.{}", whole, fraction.trim_end_matches('0')),
+        None => formatted,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMES: BlockTimes = BlockTimes { source: 12, destination: 2 };
+
+    #[test]
+    fn test_quote_breaks_down_fees_and_eta() {
+        let terms = BridgeTerms {
+            protocol_fee_bps: 25,
+            source_gas: Some("0.004".to_string()),
+            min_amount: "1".to_string(),
+            max_amount: Some("1000".to_string()),
+            available_liquidity: Some("500".to_string()),
+            confirmations: 15,
+            relay_seconds: 30,
+            ..BridgeTerms::default()
+        };
+        let quote =
+            build_quote("eth-polygon", "eth", "polygon", "USDC", "200", &terms, TIMES).unwrap();
+        assert_eq!(quote.fees.protocol_fee, "0.5");
+        assert_eq!(quote.amount_received, "199.5");
+        assert_eq!(quote.fees.source_gas.as_deref(), Some("0.004"));
+        assert_eq!(quote.fees.destination_gas, None);
+        assert_eq!(quote.eta_seconds, 15 * 12 + 30 + 2);
+        assert!(quote.is_executable());
+
+        let too_much =
+            build_quote("eth-polygon", "eth", "polygon", "USDC", "600", &terms, TIMES).unwrap();
+        assert!(too_much.within_limits);
+        assert!(!too_much.liquidity_available);
+        let too_little =
+            build_quote("eth-polygon", "eth", "polygon", "USDC", "0.5", &terms, TIMES).unwrap();
+        assert!(!too_little.within_limits);
+
+        assert!(build_quote("eth-polygon", "eth", "polygon", "USDC", "-1", &terms, TIMES).is_err());
+        assert!(build_quote("eth-polygon", "eth", "polygon", "USDC", "abc", &terms, TIMES).is_err());
+    }
+
+    #[test]
+    fn test_rank_prefers_executable_then_cheaper_then_faster() {
+        let quote = |route: &str,
+                     bps: u32,
+                     confirmations: u64,
+                     liquidity: Option<&str>,
+                     source_gas: Option<&str>| {
+            let terms = BridgeTerms {
+                protocol_fee_bps: bps,
+                confirmations,
+                available_liquidity: liquidity.map(str::to_string),
+                source_gas: source_gas.map(str::to_string),
+                ..BridgeTerms::default()
+            };
+            build_quote(route, "eth", "solana", "USDC", "100", &terms, TIMES).unwrap()
+        };
+        let mut quotes = vec![
+            quote("cheap-dry", 0, 1, Some("10"), Some("0.001")),
+            quote("slow", 10, 64, None, Some("0.002")),
+            quote("fast", 10, 2, None, Some("0.002")),
+            quote("gas-hungry", 10, 1, None, Some("0.01")),
+            quote("gas-unknown", 10, 1, None, None),
+            quote("pricey", 50, 1, None, Some("0.001")),
+        ];
+        rank_quotes(&mut quotes);
+        let order: Vec<_> = quotes.iter().map(|q| q.route.as_str()).collect();
+        assert_eq!(order, ["fast", "slow", "gas-hungry", "gas-unknown", "pricey", "cheap-dry"]);
+    }
+
+    #[test]
+    fn test_amount_formatting() {
+        assert_eq!(format_amount(parse_amount("10.50").unwrap()), "10.5");
+        assert_eq!(format_amount(parse_amount("3").unwrap()), "3");
+        assert_eq!(format_amount(U256::zero()), "0");
+    }
+}
@@ -0,0 +1,423 @@
+// src/blockchain/bridge/recovery.rs
+//! Recovery of bridge transfers that failed or never completed.
+//!
+//! A transfer needs recovery when the bridge reported it `Failed`, or when it
+//! is still `Initiated`/`InTransit` past its `estimated_completion_time`.
+//! Recovery first tries to claim an overdue in-transit transfer on the
+//! destination, then asks the bridge for a refund (`RefundPending`, then
+//! `Refunded`), and escalates to `ManualInterventionRequired` when the bridge
+//! supports neither. Status changes are compare-and-set against the status
+//! the action was planned for, so recovery backs off from a transfer the
+//! relayer advanced in the meantime. Every step is stored as a
+//! `BridgeRecoveryStep` and written to the audit log under the transfer's
+//! wallet.
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use tracing::{info, warn};
+
+use crate::blockchain::bridge::{BridgeTransaction, BridgeTransactionStatus};
+use crate::blockchain::traits::Bridge;
+use crate::core::wallet_info::SecureWalletData;
+use crate::storage::{BridgeRecoveryStep, WalletStorageTrait};
+
+/// How long past its estimated completion time a transfer may stay pending
+/// before recovery treats it as stuck.
+pub const STUCK_TRANSFER_GRACE: Duration = Duration::from_secs(15 * 60);
+
+const REDEEM: &str = "redeem";
+const REFUND: &str = "refund";
+const ESCALATE: &str = "escalate";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecoveryAction {
+    /// Claim the transfer on the destination chain.
+    Redeem,
+    /// Ask the bridge to refund the transfer on the source chain.
+    Refund,
+    /// Poll the refund with this id.
+    CheckRefund(String),
+    /// Hand the transfer over to an operator.
+    Escalate(String),
+}
+
+impl RecoveryAction {
+    /// Whether the action signs on the wallet's behalf.
+    pub fn needs_wallet(&self) -> bool {
+        matches!(self, RecoveryAction::Redeem | RecoveryAction::Refund)
+    }
+}
+
+/// Next action for `tx` given the steps already taken. Pending statuses are
+/// assumed overdue (see `get_bridge_transactions_for_recovery`); a claim
+/// submitted after `stuck_before` is given until then to land.
+pub fn plan_recovery(
+    tx: &BridgeTransaction,
+    steps: &[BridgeRecoveryStep],
+    stuck_before: DateTime<Utc>,
+) -> Option<RecoveryAction> {
+    use BridgeTransactionStatus::*;
+    let attempted = |action: &str| steps.iter().any(|s| s.action == action);
+    let claim_in_flight = steps
+        .iter()
+        .any(|s| s.action == REDEEM && s.tx_hash.is_some() && s.created_at >= stuck_before);
+
+    match &tx.status {
+        InTransit if !attempted(REDEEM) => Some(RecoveryAction::Redeem),
+        InTransit if claim_in_flight => None,
+        Initiated | InTransit | Failed(_) if !attempted(REFUND) => Some(RecoveryAction::Refund),
+        Initiated | InTransit => Some(RecoveryAction::Escalate(
+            "Transfer is overdue and could not be redeemed or refunded".to_string(),
+        )),
+        Failed(reason) => Some(RecoveryAction::Escalate(reason.clone())),
+        RefundPending => {
+            let refund_id =
+                steps.iter().rev().find(|s| s.action == REFUND).and_then(|s| s.tx_hash.clone());
+            Some(match refund_id {
+                Some(id) => RecoveryAction::CheckRefund(id),
+                None => RecoveryAction::Escalate("Refund pending without a refund id".to_string()),
+            })
+        }
+        Completed | Refunded | ManualInterventionRequired(_) => None,
+    }
+}
+
+pub struct BridgeRecovery {
+    storage: Arc<dyn WalletStorageTrait + Send + Sync>,
+    bridges: Arc<HashMap<String, Box<dyn Bridge>>>,
+}
+
+impl BridgeRecovery {
+    pub fn new(
+        storage: Arc<dyn WalletStorageTrait + Send + Sync>,
+        bridges: Arc<HashMap<String, Box<dyn Bridge>>>,
+    ) -> Self {
+        Self { storage, bridges }
+    }
+
+    pub async fn plan(
+        &self,
+        tx: &BridgeTransaction,
+        stuck_before: DateTime<Utc>,
+    ) -> Result<Option<RecoveryAction>> {
+        let steps = self.storage.get_bridge_recovery_steps(&tx.id).await?;
+        Ok(plan_recovery(tx, &steps, stuck_before))
+    }
+
+    /// Carries out `action` for `tx` and records it; returns the new status
+    /// when it changed. `wallet_data` is required for `Redeem` and `Refund`.
+    pub async fn execute(
+        &self,
+        tx: &BridgeTransaction,
+        action: RecoveryAction,
+        wallet_data: Option<&SecureWalletData>,
+    ) -> Result<Option<BridgeTransactionStatus>> {
+        let bridge_key = format!("{}-{}", tx.from_chain, tx.to_chain);
+        let (Some(bridge), Some(source_tx_hash)) =
+            (self.bridges.get(&bridge_key), tx.source_tx_hash.as_deref())
+        else {
+            return self.escalate(tx, "Transfer is not tracked by any registered bridge").await;
+        };
+        let wallet =
+            || wallet_data.ok_or_else(|| anyhow::anyhow!("Recovery of {} needs the wallet", tx.id));
+
+        match action {
+            RecoveryAction::Redeem => {
+                match bridge.redeem_transfer(source_tx_hash, wallet()?).await {
+                    Ok(Some(claim_tx)) => {
+                        self.record(tx, REDEEM, Some(&claim_tx), None).await?;
+                        self.audit(tx, "bridge_redeem_submitted", json!({ "claim_tx": claim_tx }))
+                            .await?;
+                        Ok(None)
+                    }
+                    Ok(None) => {
+                        self.record(tx, REDEEM, None, Some("not supported by bridge")).await?;
+                        self.refund(tx, bridge.as_ref(), source_tx_hash, wallet()?).await
+                    }
+                    // Counted as attempted: the next pass moves on to a refund.
+                    Err(e) => {
+                        warn!("Redeeming bridge tx {} failed: {}", tx.id, e);
+                        self.record(tx, REDEEM, None, Some(&e.to_string())).await?;
+                        Ok(None)
+                    }
+                }
+            }
+            RecoveryAction::Refund => {
+                self.refund(tx, bridge.as_ref(), source_tx_hash, wallet()?).await
+            }
+            RecoveryAction::CheckRefund(refund_id) => {
+                match bridge.check_refund_status(&refund_id).await? {
+                    BridgeTransactionStatus::Completed => {
+                        let details = json!({ "refund_id": refund_id });
+                        self.set_status(
+                            tx,
+                            BridgeTransactionStatus::Refunded,
+                            "bridge_refunded",
+                            details,
+                        )
+                        .await
+                    }
+                    BridgeTransactionStatus::Failed(reason) => {
+                        self.escalate(tx, &format!("Refund failed: {}", reason)).await
+                    }
+                    _ => Ok(None),
+                }
+            }
+            RecoveryAction::Escalate(reason) => self.escalate(tx, &reason).await,
+        }
+    }
+
+    async fn refund(
+        &self,
+        tx: &BridgeTransaction,
+        bridge: &dyn Bridge,
+        source_tx_hash: &str,
+        wallet_data: &SecureWalletData,
+    ) -> Result<Option<BridgeTransactionStatus>> {
+        // Claim the row before asking for the refund, so a transfer the
+        // relayer completed meanwhile is never refunded as well.
+        let pending = BridgeTransactionStatus::RefundPending;
+        if !self.claim(tx, pending.clone()).await? {
+            return Ok(None);
+        }
+        let claimed = BridgeTransaction { status: pending.clone(), ..tx.clone() };
+
+        match bridge.refund_transfer(source_tx_hash, wallet_data).await {
+            Ok(Some(refund_id)) => {
+                self.record(tx, REFUND, Some(&refund_id), None).await?;
+                self.audit(tx, "bridge_refund_requested", json!({ "refund_id": refund_id }))
+                    .await?;
+                Ok(Some(pending))
+            }
+            Ok(None) => {
+                self.record(tx, REFUND, None, Some("not supported by bridge")).await?;
+                self.escalate(&claimed, "Bridge supports neither redemption nor refunds").await
+            }
+            Err(e) => {
+                self.record(tx, REFUND, None, Some(&e.to_string())).await?;
+                self.escalate(&claimed, &format!("Refund request failed: {}", e)).await
+            }
+        }
+    }
+
+    async fn escalate(
+        &self,
+        tx: &BridgeTransaction,
+        reason: &str,
+    ) -> Result<Option<BridgeTransactionStatus>> {
+        let status = BridgeTransactionStatus::ManualInterventionRequired(reason.to_string());
+        let changed = self
+            .set_status(tx, status, "bridge_manual_intervention", json!({ "reason": reason }))
+            .await?;
+        if changed.is_some() {
+            self.record(tx, ESCALATE, None, Some(reason)).await?;
+        }
+        Ok(changed)
+    }
+
+    async fn set_status(
+        &self,
+        tx: &BridgeTransaction,
+        status: BridgeTransactionStatus,
+        audit_action: &str,
+        details: serde_json::Value,
+    ) -> Result<Option<BridgeTransactionStatus>> {
+        if !self.claim(tx, status.clone()).await? {
+            return Ok(None);
+        }
+        self.audit(tx, audit_action, details).await?;
+        Ok(Some(status))
+    }
+
+    /// Moves `tx` from the status it was planned against to `status`; returns
+    /// `false` when the relayer or another recovery pass changed it first.
+    async fn claim(&self, tx: &BridgeTransaction, status: BridgeTransactionStatus) -> Result<bool> {
+        let updated = self
+            .storage
+            .update_bridge_transaction_status(&tx.id, &tx.status, status.clone(), None, None)
+            .await?;
+        if updated {
+            info!("Bridge tx {} recovery: {:?} -> {:?}", tx.id, tx.status, status);
+        } else {
+            info!("Bridge tx {} changed during recovery; skipping {:?}", tx.id, status);
+        }
+        Ok(updated)
+    }
+
+    async fn record(
+        &self,
+        tx: &BridgeTransaction,
+        action: &str,
+        tx_hash: Option<&str>,
+        details: Option<&str>,
+    ) -> Result<()> {
+        self.storage.store_bridge_recovery_step(&tx.id, action, tx_hash, details).await
+    }
+
+    async fn audit(
+        &self,
+        tx: &BridgeTransaction,
+        action: &str,
+        mut details: serde_json::Value,
+    ) -> Result<()> {
+        details["bridge_tx_id"] = json!(tx.id);
+        details["previous_status"] = json!(tx.status);
+        self.storage.log_action(&tx.from_wallet, action, &details.to_string(), None, None).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::bridge::scripted::{ScriptedBridge, TransferScript};
+    use crate::core::wallet_info::test_wallet;
+    use crate::storage::WalletStorage;
+
+    fn transfer(status: BridgeTransactionStatus) -> BridgeTransaction {
+        BridgeTransaction {
+            id: "t1".to_string(),
+            from_wallet: "wallet1".to_string(),
+            from_chain: "eth".to_string(),
+            to_chain: "solana".to_string(),
+            token: "USDC".to_string(),
+            amount: "10.0".to_string(),
+            status,
+            source_tx_hash: Some("0xabc".to_string()),
+            destination_tx_hash: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            fee_amount: None,
+            estimated_completion_time: Some(Utc::now()),
+        }
+    }
+
+    fn step(action: &str, tx_hash: Option<&str>, created_at: DateTime<Utc>) -> BridgeRecoveryStep {
+        BridgeRecoveryStep {
+            id: 0,
+            bridge_tx_id: "t1".to_string(),
+            action: action.to_string(),
+            tx_hash: tx_hash.map(str::to_string),
+            details: None,
+            created_at,
+        }
+    }
+
+    #[test]
+    fn test_plan_recovery() {
+        use BridgeTransactionStatus::*;
+        let now = Utc::now();
+        let earlier = now - chrono::Duration::hours(1);
+
+        assert_eq!(plan_recovery(&transfer(InTransit), &[], now), Some(RecoveryAction::Redeem));
+        assert_eq!(plan_recovery(&transfer(Initiated), &[], now), Some(RecoveryAction::Refund));
+        assert_eq!(
+            plan_recovery(&transfer(Failed("x".into())), &[], now),
+            Some(RecoveryAction::Refund)
+        );
+
+        // a recent claim gets time to land; a stale one falls through to a refund
+        let claim = [step(REDEEM, Some("0xclaim"), now)];
+        assert_eq!(plan_recovery(&transfer(InTransit), &claim, now), None);
+        let stale = [step(REDEEM, Some("0xclaim"), earlier)];
+        assert_eq!(plan_recovery(&transfer(InTransit), &stale, now), Some(RecoveryAction::Refund));
+
+        let refunded = [step(REFUND, Some("r1"), earlier)];
+        assert_eq!(
+            plan_recovery(&transfer(RefundPending), &refunded, now),
+            Some(RecoveryAction::CheckRefund("r1".to_string()))
+        );
+        assert!(matches!(
+            plan_recovery(&transfer(Failed("x".into())), &[step(REFUND, None, earlier)], now),
+            Some(RecoveryAction::Escalate(_))
+        ));
+        assert_eq!(plan_recovery(&transfer(Refunded), &[], now), None);
+        assert_eq!(
+            plan_recovery(&transfer(ManualInterventionRequired("y".into())), &[], now),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_transfer_is_refunded_and_audited() {
+        let storage = Arc::new(WalletStorage::new_with_url("sqlite::memory:").await.unwrap());
+        let bridge = ScriptedBridge::new("eth-solana");
+        bridge.push_script(
+            TransferScript::fails("slippage").refundable(BridgeTransactionStatus::Completed),
+        );
+        let mut bridges: HashMap<String, Box<dyn Bridge>> = HashMap::new();
+        bridges.insert("eth-solana".to_string(), Box::new(bridge.clone()));
+        let recovery = BridgeRecovery::new(storage.clone(), Arc::new(bridges));
+
+        let wallet = test_wallet();
+        let source_tx =
+            bridge.transfer_across_chains("eth", "solana", "USDC", "10.0", &wallet).await.unwrap();
+        let mut tx = transfer(BridgeTransactionStatus::Failed("slippage".into()));
+        tx.source_tx_hash = Some(source_tx);
+        storage.store_bridge_transaction(&tx).await.unwrap();
+
+        let action = recovery.plan(&tx, Utc::now()).await.unwrap().unwrap();
+        assert_eq!(action, RecoveryAction::Refund);
+        let status = recovery.execute(&tx, action, Some(&wallet)).await.unwrap();
+        assert_eq!(status, Some(BridgeTransactionStatus::RefundPending));
+        assert!(bridge.transfers()[0].refunded);
+
+        let tx = storage.get_bridge_transaction("t1").await.unwrap();
+        let action = recovery.plan(&tx, Utc::now()).await.unwrap().unwrap();
+        assert_eq!(action, RecoveryAction::CheckRefund("eth-solana_tx_1_refund".to_string()));
+        let status = recovery.execute(&tx, action, None).await.unwrap();
+        assert_eq!(status, Some(BridgeTransactionStatus::Refunded));
+
+        let actions: Vec<_> = storage
+            .get_audit_logs(Some("wallet1"))
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|l| l.action)
+            .collect();
+        assert!(actions.contains(&"bridge_refund_requested".to_string()));
+        assert!(actions.contains(&"bridge_refunded".to_string()));
+        let steps = storage.get_bridge_recovery_steps("t1").await.unwrap();
+        assert_eq!(steps.len(), 1);
+        assert_eq!(steps[0].action, REFUND);
+    }
+
+    #[tokio::test]
+    async fn test_recovery_skips_a_transfer_completed_meanwhile() {
+        let storage = Arc::new(WalletStorage::new_with_url("sqlite::memory:").await.unwrap());
+        let bridge = ScriptedBridge::with_default(
+            "eth-solana",
+            TransferScript::completes().refundable(BridgeTransactionStatus::Completed),
+        );
+        let mut bridges: HashMap<String, Box<dyn Bridge>> = HashMap::new();
+        bridges.insert("eth-solana".to_string(), Box::new(bridge.clone()));
+        let recovery = BridgeRecovery::new(storage.clone(), Arc::new(bridges));
+        let tx = transfer(BridgeTransactionStatus::Initiated);
+        storage.store_bridge_transaction(&tx).await.unwrap();
+
+        // the relayer completes the transfer after recovery planned a refund
+        assert!(storage
+            .update_bridge_transaction_status(
+                "t1",
+                &BridgeTransactionStatus::Initiated,
+                BridgeTransactionStatus::Completed,
+                None,
+                None,
+            )
+            .await
+            .unwrap());
+
+        let status =
+            recovery.execute(&tx, RecoveryAction::Refund, Some(&test_wallet())).await.unwrap();
+        assert_eq!(status, None);
+        assert_eq!(
+            storage.get_bridge_transaction("t1").await.unwrap().status,
+            BridgeTransactionStatus::Completed
+        );
+        assert!(storage.get_bridge_recovery_steps("t1").await.unwrap().is_empty());
+        assert!(storage.get_audit_logs(Some("wallet1")).await.unwrap().is_empty());
+    }
+}
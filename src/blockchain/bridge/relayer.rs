@@ -0,0 +1,359 @@
+// src/blockchain/bridge/relayer.rs
+//! Background relayer that drives persisted bridge transfers to a terminal state.
+//!
+//! Every transfer is stored as a `BridgeTransaction`. On each tick the relayer
+//! loads the non-terminal rows (so transfers resume after a restart), asks the
+//! bridge for the transfer's status and advances the row at most one step:
+//! `Initiated -> InTransit` once the source transaction is confirmed, then
+//! `InTransit -> Completed` once the destination chain has delivered. A failure
+//! reported by the bridge moves any non-terminal row to `Failed`. Each step is
+//! a compare-and-set on the status the relayer read, so a row that recovery
+//! moved in the meantime is left alone; a completed row also records the
+//! destination transaction when the bridge can find it. Every change is
+//! published as a `BridgeStatusChanged` event when an event bus is set.
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::blockchain::bridge::{relay, BridgeTransaction, BridgeTransactionStatus};
+use crate::blockchain::traits::Bridge;
+use crate::core::events::{self, EventBus, WalletEvent};
+use crate::storage::WalletStorageTrait;
+
+/// Default delay between two polls of the pending transfers.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+pub struct BridgeRelayer {
+    storage: Arc<dyn WalletStorageTrait + Send + Sync>,
+    bridges: Arc<HashMap<String, Box<dyn Bridge>>>,
+    poll_interval: Duration,
+    events: Option<Arc<EventBus>>,
+}
+
+impl BridgeRelayer {
+    pub fn new(
+        storage: Arc<dyn WalletStorageTrait + Send + Sync>,
+        bridges: Arc<HashMap<String, Box<dyn Bridge>>>,
+    ) -> Self {
+        Self { storage, bridges, poll_interval: DEFAULT_POLL_INTERVAL, events: None }
+    }
+
+    pub fn with_events(mut self, events: Arc<EventBus>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Polls every pending transfer once; returns how many rows changed status.
+    pub async fn poll_once(&self) -> Result<usize> {
+        let pending = self.storage.get_pending_bridge_transactions().await?;
+        let mut updated = 0;
+        for tx in &pending {
+            match self.advance(tx).await {
+                Ok(Some(_)) => updated += 1,
+                Ok(None) => {}
+                Err(e) => warn!("Failed to relay bridge tx {}: {}", tx.id, e),
+            }
+        }
+        Ok(updated)
+    }
+
+    /// Advances a single transfer by at most one step and persists the new status.
+    pub async fn advance(&self, tx: &BridgeTransaction) -> Result<Option<BridgeTransactionStatus>> {
+        let observed = self.observe(tx).await?;
+        let Some(next) = next_status(&tx.status, &observed) else {
+            return Ok(None);
+        };
+
+        let destination_tx_hash = match next {
+            BridgeTransactionStatus::Completed => self.destination_transaction(tx).await,
+            _ => None,
+        };
+        let updated = self
+            .storage
+            .update_bridge_transaction_status(
+                &tx.id,
+                &tx.status,
+                next.clone(),
+                None,
+                destination_tx_hash,
+            )
+            .await?;
+        if !updated {
+            info!("Bridge tx {} changed while relaying; leaving it as stored", tx.id);
+            return Ok(None);
+        }
+
+        info!("Bridge tx {}: {:?} -> {:?}", tx.id, tx.status, next);
+        if let Some(bus) = &self.events {
+            events::publish(
+                bus,
+                WalletEvent::BridgeStatusChanged {
+                    id: tx.id.clone(),
+                    wallet: tx.from_wallet.clone(),
+                    from_chain: tx.from_chain.clone(),
+                    to_chain: tx.to_chain.clone(),
+                    status: next.clone(),
+                },
+            );
+        }
+        Ok(Some(next))
+    }
+
+    /// Status reported by the bridge, or `Failed` when the transfer cannot be tracked at all.
+    async fn observe(&self, tx: &BridgeTransaction) -> Result<BridgeTransactionStatus> {
+        let bridge_key = format!("{}-{}", tx.from_chain, tx.to_chain);
+        let Some(bridge) = self.bridges.get(&bridge_key) else {
+            return Ok(BridgeTransactionStatus::Failed(format!(
+                "Unsupported bridge: {}",
+                bridge_key
+            )));
+        };
+        let Some(source_tx_hash) = &tx.source_tx_hash else {
+            return Ok(BridgeTransactionStatus::Failed("Missing source transaction hash".into()));
+        };
+        relay::relay_transaction(bridge.as_ref(), source_tx_hash).await
+    }
+
+    /// Destination transaction of a delivered transfer. A lookup failure is
+    /// logged rather than returned so the completion itself is still recorded.
+    async fn destination_transaction(&self, tx: &BridgeTransaction) -> Option<String> {
+        let bridge = self.bridges.get(&format!("{}-{}", tx.from_chain, tx.to_chain))?;
+        let source_tx_hash = tx.source_tx_hash.as_deref()?;
+        match bridge.destination_transaction(source_tx_hash).await {
+            Ok(hash) => hash,
+            Err(e) => {
+                warn!("Failed to find the destination transaction of bridge tx {}: {}", tx.id, e);
+                None
+            }
+        }
+    }
+
+    /// Runs `poll_once` every `poll_interval` until the task is aborted. The
+    /// first poll happens immediately so transfers left over from a previous
+    /// run are picked up on startup.
+    pub fn spawn(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.poll_interval);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.poll_once().await {
+                    warn!("Bridge relayer poll failed: {}", e);
+                }
+            }
+        })
+    }
+}
+
+/// The transition to apply given the stored status and what the bridge reports.
+pub fn next_status(
+    current: &BridgeTransactionStatus,
+    observed: &BridgeTransactionStatus,
+) -> Option<BridgeTransactionStatus> {
+    use BridgeTransactionStatus::*;
+    match (current, observed) {
+        (Completed | Failed(_) | RefundPending | Refunded | ManualInterventionRequired(_), _) => {
+            None
+        }
+        (_, Failed(reason)) => Some(Failed(reason.clone())),
+        (Initiated, InTransit | Completed) => Some(InTransit),
+        (InTransit, Completed) => Some(Completed),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::bridge::scripted::{ScriptedBridge, TransferScript};
+    use crate::core::wallet_info::test_wallet;
+    use crate::storage::WalletStorage;
+    use chrono::Utc;
+
+    async fn setup(
+        bridge: &ScriptedBridge,
+    ) -> (Arc<dyn WalletStorageTrait + Send + Sync>, BridgeRelayer) {
+        let storage: Arc<dyn WalletStorageTrait + Send + Sync> =
+            Arc::new(WalletStorage::new_with_url("sqlite::memory:").await.unwrap());
+        let relayer = BridgeRelayer::new(storage.clone(), bridges(bridge));
+        (storage, relayer)
+    }
+
+    fn bridges(bridge: &ScriptedBridge) -> Arc<HashMap<String, Box<dyn Bridge>>> {
+        let mut bridges: HashMap<String, Box<dyn Bridge>> = HashMap::new();
+        bridges.insert("eth-solana".to_string(), Box::new(bridge.clone()));
+        Arc::new(bridges)
+    }
+
+    /// Submits a transfer to `bridge` and records it the way `WalletManager` does.
+    async fn submit(
+        storage: &Arc<dyn WalletStorageTrait + Send + Sync>,
+        bridge: &ScriptedBridge,
+        id: &str,
+    ) {
+        let wallet = test_wallet();
+        let source_tx_hash =
+            bridge.transfer_across_chains("eth", "solana", "USDC", "10.0", &wallet).await.unwrap();
+        let mut tx = transfer(id, "solana", None);
+        tx.source_tx_hash = Some(source_tx_hash);
+        storage.store_bridge_transaction(&tx).await.unwrap();
+    }
+
+    fn transfer(id: &str, to_chain: &str, source_tx_hash: Option<&str>) -> BridgeTransaction {
+        BridgeTransaction {
+            id: id.to_string(),
+            from_wallet: "wallet1".to_string(),
+            from_chain: "eth".to_string(),
+            to_chain: to_chain.to_string(),
+            token: "USDC".to_string(),
+            amount: "10.0".to_string(),
+            status: BridgeTransactionStatus::Initiated,
+            source_tx_hash: source_tx_hash.map(str::to_string),
+            destination_tx_hash: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            fee_amount: Some("0.1".to_string()),
+            estimated_completion_time: Some(Utc::now()),
+        }
+    }
+
+    async fn status(
+        storage: &Arc<dyn WalletStorageTrait + Send + Sync>,
+        id: &str,
+    ) -> BridgeTransactionStatus {
+        storage.get_bridge_transaction(id).await.unwrap().status
+    }
+
+    #[test]
+    fn test_next_status_transitions() {
+        use BridgeTransactionStatus::*;
+        assert_eq!(next_status(&Initiated, &Initiated), None);
+        assert_eq!(next_status(&Initiated, &Completed), Some(InTransit));
+        assert_eq!(next_status(&InTransit, &Completed), Some(Completed));
+        assert_eq!(next_status(&InTransit, &Failed("x".into())), Some(Failed("x".into())));
+        assert_eq!(next_status(&Completed, &Failed("x".into())), None);
+    }
+
+    #[tokio::test]
+    async fn test_relayer_drives_transfer_to_completion() {
+        let bridge = ScriptedBridge::new("eth-solana");
+        bridge.push_script(
+            TransferScript::new()
+                .then(BridgeTransactionStatus::Initiated)
+                .then(BridgeTransactionStatus::InTransit)
+                .then(BridgeTransactionStatus::Completed),
+        );
+        let (storage, relayer) = setup(&bridge).await;
+        submit(&storage, &bridge, "t1").await;
+
+        // source not yet confirmed
+        assert_eq!(relayer.poll_once().await.unwrap(), 0);
+        assert_eq!(status(&storage, "t1").await, BridgeTransactionStatus::Initiated);
+
+        assert_eq!(relayer.poll_once().await.unwrap(), 1);
+        assert_eq!(status(&storage, "t1").await, BridgeTransactionStatus::InTransit);
+
+        assert_eq!(relayer.poll_once().await.unwrap(), 1);
+        let completed = storage.get_bridge_transaction("t1").await.unwrap();
+        assert_eq!(completed.status, BridgeTransactionStatus::Completed);
+        assert_eq!(completed.source_tx_hash.as_deref(), Some("eth-solana_tx_1"));
+        assert_eq!(completed.destination_tx_hash.as_deref(), Some("eth-solana_tx_1_delivery"));
+
+        // terminal rows are no longer polled
+        assert_eq!(relayer.poll_once().await.unwrap(), 0);
+        assert_eq!(bridge.transfers()[0].checks, 3);
+    }
+
+    #[tokio::test]
+    async fn test_relayer_does_not_overwrite_a_concurrent_change() {
+        let bridge = ScriptedBridge::with_default("eth-solana", TransferScript::fails("slippage"));
+        let (storage, relayer) = setup(&bridge).await;
+        submit(&storage, &bridge, "t1").await;
+        let stale = storage.get_bridge_transaction("t1").await.unwrap();
+
+        // recovery escalates the row after the relayer read it
+        let escalated = BridgeTransactionStatus::ManualInterventionRequired("operator".into());
+        assert!(storage
+            .update_bridge_transaction_status(
+                "t1",
+                &BridgeTransactionStatus::Initiated,
+                escalated.clone(),
+                None,
+                None,
+            )
+            .await
+            .unwrap());
+
+        assert_eq!(relayer.advance(&stale).await.unwrap(), None);
+        assert_eq!(status(&storage, "t1").await, escalated);
+    }
+
+    #[tokio::test]
+    async fn test_relayer_records_bridge_failure() {
+        let bridge = ScriptedBridge::with_default("eth-solana", TransferScript::fails("slippage"));
+        let (storage, relayer) = setup(&bridge).await;
+        submit(&storage, &bridge, "t1").await;
+
+        relayer.poll_once().await.unwrap();
+        relayer.poll_once().await.unwrap();
+        assert_eq!(
+            status(&storage, "t1").await,
+            BridgeTransactionStatus::Failed("slippage".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_relayer_fails_untrackable_transfers() {
+        let bridge = ScriptedBridge::new("eth-solana");
+        let (storage, relayer) = setup(&bridge).await;
+        storage.store_bridge_transaction(&transfer("no-hash", "solana", None)).await.unwrap();
+        storage
+            .store_bridge_transaction(&transfer("no-bridge", "bsc", Some("0xabc")))
+            .await
+            .unwrap();
+
+        assert_eq!(relayer.poll_once().await.unwrap(), 2);
+        for id in ["no-hash", "no-bridge"] {
+            assert!(
+                matches!(status(&storage, id).await, BridgeTransactionStatus::Failed(_)),
+                "{}",
+                id
+            );
+        }
+        assert!(storage.get_pending_bridge_transactions().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_relayer_resumes_pending_rows_after_restart() {
+        let bridge = ScriptedBridge::new("eth-solana");
+        let (storage, relayer) = setup(&bridge).await;
+        submit(&storage, &bridge, "t1").await;
+        relayer.poll_once().await.unwrap();
+        assert_eq!(status(&storage, "t1").await, BridgeTransactionStatus::InTransit);
+        drop(relayer);
+
+        // a fresh relayer over the same storage picks the in-transit row up
+        let restarted = Arc::new(
+            BridgeRelayer::new(storage.clone(), bridges(&bridge))
+                .with_poll_interval(Duration::from_millis(10)),
+        );
+        let handle = restarted.spawn();
+
+        for _ in 0..50 {
+            if status(&storage, "t1").await == BridgeTransactionStatus::Completed {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        handle.abort();
+        assert_eq!(status(&storage, "t1").await, BridgeTransactionStatus::Completed);
+    }
+}
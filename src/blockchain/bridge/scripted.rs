@@ -0,0 +1,346 @@
+// src/blockchain/bridge/scripted.rs
+//! A `Bridge` whose behaviour is fixed up front, for deterministic tests.
+//!
+//! Each transfer follows a `TransferScript`: an optional submit failure, a
+//! submit latency, the sequence of statuses returned by successive
+//! `check_transfer_status` calls (the last one repeats once the script runs
+//! out), and whether recovery may redeem or refund it. Scripts queued with
+//! `push_script` are consumed by transfers in order; when the queue is empty
+//! the default script is used. State lives on the
+//! instance rather than in globals or env vars, so tests can run in parallel.
+//! Clones share state, so a test can keep a handle after injecting the bridge.
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::blockchain::bridge::quote::BridgeTerms;
+use crate::blockchain::bridge::BridgeTransactionStatus;
+use crate::blockchain::traits::Bridge;
+use crate::core::wallet_info::SecureWalletData;
+
+/// One `check_transfer_status` answer and how long it takes to arrive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptedStep {
+    pub status: BridgeTransactionStatus,
+    pub latency: Duration,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TransferScript {
+    submit_error: Option<String>,
+    submit_latency: Duration,
+    steps: Vec<ScriptedStep>,
+    redeemable: bool,
+    refund: Option<BridgeTransactionStatus>,
+}
+
+impl TransferScript {
+    /// A script with no steps; status checks report `Completed` until steps are added.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Source confirmation followed by delivery: `InTransit`, then `Completed`.
+    pub fn completes() -> Self {
+        Self::new()
+            .then(BridgeTransactionStatus::InTransit)
+            .then(BridgeTransactionStatus::Completed)
+    }
+
+    /// Goes in transit and then fails with `reason`.
+    pub fn fails(reason: &str) -> Self {
+        Self::new()
+            .then(BridgeTransactionStatus::InTransit)
+            .then(BridgeTransactionStatus::Failed(reason.to_string()))
+    }
+
+    /// `transfer_across_chains` itself returns an error.
+    pub fn rejects(reason: &str) -> Self {
+        Self { submit_error: Some(reason.to_string()), ..Self::default() }
+    }
+
+    pub fn then(self, status: BridgeTransactionStatus) -> Self {
+        self.then_after(Duration::ZERO, status)
+    }
+
+    pub fn then_after(mut self, latency: Duration, status: BridgeTransactionStatus) -> Self {
+        self.steps.push(ScriptedStep { status, latency });
+        self
+    }
+
+    pub fn with_submit_latency(mut self, latency: Duration) -> Self {
+        self.submit_latency = latency;
+        self
+    }
+
+    /// `redeem_transfer` succeeds, after which status checks report `Completed`.
+    pub fn redeemable(mut self) -> Self {
+        self.redeemable = true;
+        self
+    }
+
+    /// `refund_transfer` succeeds and the refund then reports `status`.
+    pub fn refundable(mut self, status: BridgeTransactionStatus) -> Self {
+        self.refund = Some(status);
+        self
+    }
+
+    fn step(&self, index: usize) -> ScriptedStep {
+        self.steps.get(index).or_else(|| self.steps.last()).cloned().unwrap_or(ScriptedStep {
+            status: BridgeTransactionStatus::Completed,
+            latency: Duration::ZERO,
+        })
+    }
+}
+
+/// A transfer submitted to a `ScriptedBridge`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptedTransfer {
+    pub tx_id: String,
+    pub from_chain: String,
+    pub to_chain: String,
+    pub token: String,
+    pub amount: String,
+    /// Number of status checks answered so far.
+    pub checks: usize,
+    pub redeemed: bool,
+    pub refunded: bool,
+}
+
+#[derive(Default)]
+struct ScriptedState {
+    queued: VecDeque<TransferScript>,
+    transfers: Vec<ScriptedTransfer>,
+    scripts: HashMap<String, TransferScript>,
+}
+
+impl ScriptedState {
+    fn script(&self, tx_id: &str) -> Result<&TransferScript> {
+        self.scripts
+            .get(tx_id)
+            .ok_or_else(|| anyhow::anyhow!("Unknown bridge transaction: {}", tx_id))
+    }
+
+    fn transfer(&mut self, tx_id: &str) -> Result<&mut ScriptedTransfer> {
+        self.transfers
+            .iter_mut()
+            .find(|t| t.tx_id == tx_id)
+            .ok_or_else(|| anyhow::anyhow!("Unknown bridge transaction: {}", tx_id))
+    }
+}
+
+#[derive(Clone)]
+pub struct ScriptedBridge {
+    name: String,
+    default_script: TransferScript,
+    terms: BridgeTerms,
+    state: Arc<Mutex<ScriptedState>>,
+}
+
+impl ScriptedBridge {
+    /// `name` prefixes the generated transaction ids (`<name>_tx_<n>`).
+    pub fn new(name: &str) -> Self {
+        Self::with_default(name, TransferScript::completes())
+    }
+
+    pub fn with_default(name: &str, default_script: TransferScript) -> Self {
+        Self {
+            name: name.to_string(),
+            default_script,
+            terms: BridgeTerms::default(),
+            state: Arc::new(Mutex::new(ScriptedState::default())),
+        }
+    }
+
+    /// Terms reported by `quote_terms` for every token.
+    pub fn with_terms(mut self, terms: BridgeTerms) -> Self {
+        self.terms = terms;
+        self
+    }
+
+    /// Queues the script for the next transfer that has not been scripted yet.
+    pub fn push_script(&self, script: TransferScript) -> &Self {
+        self.state.lock().unwrap().queued.push_back(script);
+        self
+    }
+
+    /// Transfers submitted so far, in submission order.
+    pub fn transfers(&self) -> Vec<ScriptedTransfer> {
+        self.state.lock().unwrap().transfers.clone()
+    }
+}
+
+#[async_trait]
+impl Bridge for ScriptedBridge {
+    async fn transfer_across_chains(
+        &self,
+        from_chain: &str,
+        to_chain: &str,
+        token: &str,
+        amount: &str,
+        _wallet_data: &SecureWalletData,
+    ) -> Result<String> {
+        let script = {
+            let mut state = self.state.lock().unwrap();
+            state.queued.pop_front().unwrap_or_else(|| self.default_script.clone())
+        };
+
+        tokio::time::sleep(script.submit_latency).await;
+        if let Some(reason) = &script.submit_error {
+            return Err(anyhow::anyhow!("{}", reason));
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let tx_id = format!("{}_tx_{}", self.name, state.transfers.len() + 1);
+        state.transfers.push(ScriptedTransfer {
+            tx_id: tx_id.clone(),
+            from_chain: from_chain.to_string(),
+            to_chain: to_chain.to_string(),
+            token: token.to_string(),
+            amount: amount.to_string(),
+            checks: 0,
+            redeemed: false,
+            refunded: false,
+        });
+        state.scripts.insert(tx_id.clone(), script);
+        Ok(tx_id)
+    }
+
+    async fn check_transfer_status(&self, tx_id: &str) -> Result<BridgeTransactionStatus> {
+        let step = {
+            let mut state = self.state.lock().unwrap();
+            let transfer = state.transfer(tx_id)?;
+            let index = transfer.checks;
+            transfer.checks += 1;
+            if transfer.redeemed {
+                return Ok(BridgeTransactionStatus::Completed);
+            }
+            state.scripts[tx_id].step(index)
+        };
+
+        tokio::time::sleep(step.latency).await;
+        Ok(step.status)
+    }
+
+    async fn destination_transaction(&self, tx_id: &str) -> Result<Option<String>> {
+        let mut state = self.state.lock().unwrap();
+        let transfer = state.transfer(tx_id)?;
+        Ok(Some(format!("{}_delivery", transfer.tx_id)))
+    }
+
+    async fn redeem_transfer(
+        &self,
+        tx_id: &str,
+        _wallet_data: &SecureWalletData,
+    ) -> Result<Option<String>> {
+        let mut state = self.state.lock().unwrap();
+        if !state.script(tx_id)?.redeemable {
+            return Ok(None);
+        }
+        state.transfer(tx_id)?.redeemed = true;
+        Ok(Some(format!("{}_redeem", tx_id)))
+    }
+
+    async fn refund_transfer(
+        &self,
+        tx_id: &str,
+        _wallet_data: &SecureWalletData,
+    ) -> Result<Option<String>> {
+        let mut state = self.state.lock().unwrap();
+        if state.script(tx_id)?.refund.is_none() {
+            return Ok(None);
+        }
+        state.transfer(tx_id)?.refunded = true;
+        Ok(Some(format!("{}_refund", tx_id)))
+    }
+
+    async fn check_refund_status(&self, refund_id: &str) -> Result<BridgeTransactionStatus> {
+        let tx_id = refund_id
+            .strip_suffix("_refund")
+            .ok_or_else(|| anyhow::anyhow!("Unknown refund: {}", refund_id))?;
+        let state = self.state.lock().unwrap();
+        state
+            .script(tx_id)?
+            .refund
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("No refund for {}", tx_id))
+    }
+
+    async fn quote_terms(
+        &self,
+        _from_chain: &str,
+        _to_chain: &str,
+        _token: &str,
+    ) -> Result<BridgeTerms> {
+        Ok(self.terms.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::wallet_info::test_wallet;
+
+    #[tokio::test]
+    async fn test_scripts_are_consumed_in_order() {
+        let bridge = ScriptedBridge::new("eth-solana");
+        bridge
+            .push_script(TransferScript::fails("slippage"))
+            .push_script(TransferScript::rejects("paused"));
+
+        let first =
+            bridge.transfer_across_chains("eth", "solana", "USDC", "1", &test_wallet()).await;
+        assert_eq!(first.unwrap(), "eth-solana_tx_1");
+        let second =
+            bridge.transfer_across_chains("eth", "solana", "USDC", "2", &test_wallet()).await;
+        assert_eq!(second.unwrap_err().to_string(), "paused");
+        // queue exhausted: default script
+        let third =
+            bridge.transfer_across_chains("eth", "solana", "USDC", "3", &test_wallet()).await;
+        assert_eq!(third.unwrap(), "eth-solana_tx_2");
+
+        let statuses = [
+            bridge.check_transfer_status("eth-solana_tx_1").await.unwrap(),
+            bridge.check_transfer_status("eth-solana_tx_1").await.unwrap(),
+            bridge.check_transfer_status("eth-solana_tx_1").await.unwrap(),
+        ];
+        assert_eq!(
+            statuses,
+            [
+                BridgeTransactionStatus::InTransit,
+                BridgeTransactionStatus::Failed("slippage".to_string()),
+                BridgeTransactionStatus::Failed("slippage".to_string()),
+            ]
+        );
+        assert_eq!(
+            bridge.check_transfer_status("eth-solana_tx_2").await.unwrap(),
+            BridgeTransactionStatus::InTransit
+        );
+        assert!(bridge.check_transfer_status("unknown").await.is_err());
+
+        let transfers = bridge.transfers();
+        assert_eq!(transfers.len(), 2);
+        assert_eq!(transfers[0].checks, 3);
+        assert_eq!(transfers[1].amount, "3");
+    }
+
+    #[tokio::test]
+    async fn test_latencies_are_applied() {
+        let bridge = ScriptedBridge::with_default(
+            "slow",
+            TransferScript::new()
+                .with_submit_latency(Duration::from_millis(20))
+                .then_after(Duration::from_millis(30), BridgeTransactionStatus::Completed),
+        );
+
+        let start = tokio::time::Instant::now();
+        let tx_id =
+            bridge.transfer_across_chains("eth", "bsc", "USDT", "1", &test_wallet()).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        bridge.check_transfer_status(&tx_id).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+}
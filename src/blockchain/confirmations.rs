@@ -0,0 +1,341 @@
+// src/blockchain/confirmations.rs
+//! Background tracker that records when sent transactions settle on chain.
+//!
+//! Swaps and staking operations are stored as `pending` transactions. On each
+//! tick the tracker asks the network's client for the status of every pending
+//! row and marks it `confirmed` or `failed`, publishing a
+//! `TransactionConfirmed` event when an event bus is set. On networks that
+//! count confirmations a successful transaction only settles once it has the
+//! required number, with a `ConfirmationProgress` event for each new count
+//! before that.
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::Utc;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::blockchain::traits::{BlockchainClient, TransactionStatus};
+use crate::core::events::{self, EventBus, WalletEvent};
+use crate::storage::{TransactionRecord, WalletStorageTrait};
+
+/// Default delay between two polls of the pending transactions.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Default confirmations before a successful transaction is `confirmed`.
+pub const DEFAULT_REQUIRED_CONFIRMATIONS: u64 = 1;
+
+/// Outcome of checking one pending transaction.
+enum Check {
+    Settled(&'static str),
+    /// Mined with fewer confirmations than required.
+    Progress(u64),
+    Unchanged,
+}
+
+pub struct ConfirmationTracker {
+    storage: Arc<dyn WalletStorageTrait + Send + Sync>,
+    clients: Arc<HashMap<String, Box<dyn BlockchainClient>>>,
+    poll_interval: Duration,
+    required_confirmations: u64,
+    events: Option<Arc<EventBus>>,
+    /// Last confirmation count published per pending transaction id.
+    progress: Mutex<HashMap<String, u64>>,
+}
+
+impl ConfirmationTracker {
+    pub fn new(
+        storage: Arc<dyn WalletStorageTrait + Send + Sync>,
+        clients: Arc<HashMap<String, Box<dyn BlockchainClient>>>,
+    ) -> Self {
+        Self {
+            storage,
+            clients,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            required_confirmations: DEFAULT_REQUIRED_CONFIRMATIONS,
+            events: None,
+            progress: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_events(mut self, events: Arc<EventBus>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    pub fn with_required_confirmations(mut self, required: u64) -> Self {
+        self.required_confirmations = required.max(1);
+        self
+    }
+
+    /// Checks every pending transaction once; returns how many settled.
+    pub async fn poll_once(&self) -> Result<usize> {
+        let pending = self.storage.get_pending_transactions().await?;
+        if pending.is_empty() {
+            return Ok(0);
+        }
+        // Events name wallets the way the API does.
+        let names: HashMap<String, String> =
+            self.storage.list_wallets().await?.into_iter().map(|w| (w.id, w.name)).collect();
+        let mut settled = 0;
+        for tx in &pending {
+            let wallet = names.get(&tx.wallet_id).unwrap_or(&tx.wallet_id).clone();
+            let event = match self.check(tx).await {
+                Ok(Check::Settled(status)) => {
+                    settled += 1;
+                    self.progress.lock().unwrap().remove(&tx.id);
+                    WalletEvent::TransactionConfirmed {
+                        wallet,
+                        network: tx.network.clone(),
+                        tx_hash: tx.tx_hash.clone(),
+                        status: status.to_string(),
+                    }
+                }
+                Ok(Check::Progress(confirmations)) => {
+                    let last = self.progress.lock().unwrap().insert(tx.id.clone(), confirmations);
+                    if last == Some(confirmations) {
+                        continue;
+                    }
+                    WalletEvent::ConfirmationProgress {
+                        wallet,
+                        network: tx.network.clone(),
+                        tx_hash: tx.tx_hash.clone(),
+                        confirmations,
+                        required_confirmations: self.required_confirmations,
+                    }
+                }
+                Ok(Check::Unchanged) => continue,
+                Err(e) => {
+                    warn!("Failed to check transaction {}: {}", tx.tx_hash, e);
+                    continue;
+                }
+            };
+            if let Some(bus) = &self.events {
+                events::publish(bus, event);
+            }
+        }
+        Ok(settled)
+    }
+
+    /// Stores the settled status of one transaction. A successful one on a
+    /// network that counts confirmations stays pending until it has the
+    /// required number.
+    async fn check(&self, tx: &TransactionRecord) -> Result<Check> {
+        let Some(client) = self.clients.get(&tx.network) else {
+            return Ok(Check::Unchanged);
+        };
+        let (status, confirmed_at) = match client.get_transaction_status(&tx.tx_hash).await? {
+            TransactionStatus::Confirmed => {
+                match client.get_transaction_confirmations(&tx.tx_hash).await? {
+                    Some(n) if n < self.required_confirmations => return Ok(Check::Progress(n)),
+                    _ => ("confirmed", Some(Utc::now())),
+                }
+            }
+            TransactionStatus::Failed => ("failed", None),
+            TransactionStatus::Pending | TransactionStatus::Unknown => return Ok(Check::Unchanged),
+        };
+        info!("Transaction {} on {} is {}", tx.tx_hash, tx.network, status);
+        self.storage.update_transaction_status(&tx.id, status, confirmed_at).await?;
+        Ok(Check::Settled(status))
+    }
+
+    /// Runs `poll_once` every poll interval until the task is aborted,
+    /// starting immediately.
+    pub fn spawn(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.poll_interval);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.poll_once().await {
+                    warn!("Transaction confirmation poll failed: {}", e);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::traits::{SignedTransaction, UnsignedTransaction};
+    use crate::core::errors::WalletError;
+    use crate::core::events::EVENT_BUS_CAPACITY;
+    use crate::storage::WalletStorage;
+    use async_trait::async_trait;
+
+    struct StatusClient(TransactionStatus, Option<u64>);
+
+    #[async_trait]
+    impl BlockchainClient for StatusClient {
+        fn clone_box(&self) -> Box<dyn BlockchainClient> {
+            Box::new(StatusClient(self.0.clone(), self.1))
+        }
+
+        async fn get_balance(&self, _address: &str) -> Result<String, WalletError> {
+            Ok("0".to_string())
+        }
+
+        async fn build_transaction(
+            &self,
+            _from_public_key: &[u8],
+            _to_address: &str,
+            _amount: &str,
+        ) -> Result<UnsignedTransaction, WalletError> {
+            Err(WalletError::Other("not supported".to_string()))
+        }
+
+        async fn broadcast_transaction(
+            &self,
+            _tx: &SignedTransaction,
+        ) -> Result<String, WalletError> {
+            Err(WalletError::Other("not supported".to_string()))
+        }
+
+        async fn get_transaction_status(
+            &self,
+            _tx_hash: &str,
+        ) -> Result<TransactionStatus, WalletError> {
+            Ok(self.0.clone())
+        }
+
+        async fn get_transaction_confirmations(
+            &self,
+            _tx_hash: &str,
+        ) -> Result<Option<u64>, WalletError> {
+            Ok(self.1)
+        }
+
+        async fn estimate_fee(
+            &self,
+            _to_address: &str,
+            _amount: &str,
+        ) -> Result<String, WalletError> {
+            Ok("0".to_string())
+        }
+
+        async fn get_block_number(&self) -> Result<u64, WalletError> {
+            Ok(0)
+        }
+
+        fn validate_address(&self, _address: &str) -> anyhow::Result<bool> {
+            Ok(true)
+        }
+
+        fn get_network_name(&self) -> &str {
+            "test"
+        }
+
+        fn get_native_token(&self) -> &str {
+            "TEST"
+        }
+    }
+
+    fn pending(wallet_id: &str, id: &str, network: &str) -> TransactionRecord {
+        TransactionRecord {
+            id: id.to_string(),
+            wallet_id: wallet_id.to_string(),
+            tx_hash: format!("0x{}", id),
+            network: network.to_string(),
+            from_address: "0xfrom".to_string(),
+            to_address: "0xto".to_string(),
+            amount: "1".to_string(),
+            fee: "0".to_string(),
+            status: "pending".to_string(),
+            created_at: Utc::now(),
+            confirmed_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_settled_transactions_are_stored_and_published() {
+        let storage = Arc::new(WalletStorage::new_with_url("sqlite::memory:").await.unwrap());
+        storage.store_wallet("w1", b"data", false).await.unwrap();
+        let wallet_id = storage.list_wallets().await.unwrap().remove(0).id;
+        storage.store_transaction(&pending(&wallet_id, "a", "confirming")).await.unwrap();
+        storage.store_transaction(&pending(&wallet_id, "b", "stuck")).await.unwrap();
+        storage.store_transaction(&pending(&wallet_id, "c", "unknown-network")).await.unwrap();
+
+        let mut clients: HashMap<String, Box<dyn BlockchainClient>> = HashMap::new();
+        clients.insert(
+            "confirming".to_string(),
+            Box::new(StatusClient(TransactionStatus::Confirmed, None)),
+        );
+        clients
+            .insert("stuck".to_string(), Box::new(StatusClient(TransactionStatus::Pending, None)));
+        let bus = Arc::new(EventBus::new(EVENT_BUS_CAPACITY));
+        let mut received = bus.subscribe();
+        let tracker =
+            ConfirmationTracker::new(storage.clone(), Arc::new(clients)).with_events(bus.clone());
+
+        assert_eq!(tracker.poll_once().await.unwrap(), 1);
+        let still_pending = storage.get_pending_transactions().await.unwrap();
+        assert_eq!(still_pending.iter().map(|t| t.id.as_str()).collect::<Vec<_>>(), ["b", "c"]);
+        let confirmed = storage.get_wallet_transactions(&wallet_id).await.unwrap();
+        let a = confirmed.iter().find(|t| t.id == "a").unwrap();
+        assert_eq!(a.status, "confirmed");
+        assert!(a.confirmed_at.is_some());
+
+        assert_eq!(
+            received.try_recv().unwrap(),
+            WalletEvent::TransactionConfirmed {
+                wallet: "w1".to_string(),
+                network: "confirming".to_string(),
+                tx_hash: "0xa".to_string(),
+                status: "confirmed".to_string(),
+            }
+        );
+        assert!(received.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_confirmation_progress_is_published_until_settled() {
+        let storage = Arc::new(WalletStorage::new_with_url("sqlite::memory:").await.unwrap());
+        storage.store_wallet("w1", b"data", false).await.unwrap();
+        let wallet_id = storage.list_wallets().await.unwrap().remove(0).id;
+        storage.store_transaction(&pending(&wallet_id, "a", "eth")).await.unwrap();
+        let bus = Arc::new(EventBus::new(EVENT_BUS_CAPACITY));
+        let mut received = bus.subscribe();
+
+        let tracker_with = |confirmations| {
+            let mut clients: HashMap<String, Box<dyn BlockchainClient>> = HashMap::new();
+            clients.insert(
+                "eth".to_string(),
+                Box::new(StatusClient(TransactionStatus::Confirmed, Some(confirmations))),
+            );
+            ConfirmationTracker::new(storage.clone(), Arc::new(clients))
+                .with_required_confirmations(3)
+                .with_events(bus.clone())
+        };
+
+        let tracker = tracker_with(2);
+        assert_eq!(tracker.poll_once().await.unwrap(), 0);
+        assert_eq!(tracker.poll_once().await.unwrap(), 0);
+        assert_eq!(
+            received.try_recv().unwrap(),
+            WalletEvent::ConfirmationProgress {
+                wallet: "w1".to_string(),
+                network: "eth".to_string(),
+                tx_hash: "0xa".to_string(),
+                confirmations: 2,
+                required_confirmations: 3,
+            }
+        );
+        // an unchanged count is not published again
+        assert!(received.try_recv().is_err());
+        assert_eq!(storage.get_pending_transactions().await.unwrap().len(), 1);
+
+        assert_eq!(tracker_with(3).poll_once().await.unwrap(), 1);
+        assert!(matches!(
+            received.try_recv().unwrap(),
+            WalletEvent::TransactionConfirmed { status, .. } if status == "confirmed"
+        ));
+        assert!(storage.get_pending_transactions().await.unwrap().is_empty());
+    }
+}
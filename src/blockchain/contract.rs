@@ -0,0 +1,543 @@
+// src/blockchain/contract.rs
+//! Calls to arbitrary EVM contracts described by an ABI.
+//!
+//! The ABI may be JSON (a full ABI array or a single fragment) or
+//! human-readable signatures such as
+//! `function balanceOf(address) view returns (uint256)`. Arguments are JSON
+//! values matched against the function's parameter types: addresses, integers,
+//! byte strings and strings as JSON strings (integers may also be JSON
+//! numbers), booleans as JSON booleans, arrays and tuples as JSON arrays.
+//! Return values and custom errors are decoded the same way in reverse, with
+//! integers rendered as decimal strings so large values survive JSON.
+use anyhow::{anyhow, Context, Result};
+use ethers::abi::{self, Abi, Function, Param, ParamType, Token};
+use ethers::types::{Address, Bytes, I256, U256};
+use ethers::utils::id;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::blockchain::simulation::{decode_revert_reason, SimulationResult};
+
+/// A function call on a contract, as submitted through the API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractCall {
+    pub contract: String,
+    pub abi: Value,
+    /// Function name, or its full signature (`transfer(address,uint256)`) to
+    /// pick one of several overloads.
+    pub function: String,
+    #[serde(default)]
+    pub args: Vec<Value>,
+    /// Native token sent along with the call.
+    #[serde(default)]
+    pub value: Option<String>,
+}
+
+/// A `ContractCall` resolved against its ABI.
+#[derive(Debug, Clone)]
+pub struct EncodedCall {
+    pub abi: Abi,
+    pub function: Function,
+    pub data: Bytes,
+}
+
+/// Outcome of a read-only call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractCallResult {
+    pub success: bool,
+    /// Decoded return values, empty when the call reverted.
+    pub outputs: Vec<Value>,
+    pub revert_reason: Option<String>,
+    pub return_data: Bytes,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractReceipt {
+    pub tx_hash: String,
+    pub network: String,
+    pub contract: String,
+    pub function: String,
+    /// Pre-flight simulation, unless it was skipped or the network cannot
+    /// simulate.
+    pub simulation: Option<SimulationResult>,
+}
+
+impl ContractCall {
+    /// Resolves the function in the ABI and encodes the arguments as calldata.
+    pub fn encode(&self) -> Result<EncodedCall> {
+        let abi = parse_abi(&self.abi)?;
+        let function = find_function(&abi, &self.function, self.args.len())?.clone();
+        if function.inputs.len() != self.args.len() {
+            return Err(anyhow!(
+                "{} takes {} arguments, got {}",
+                signature(&function.name, &function.inputs),
+                function.inputs.len(),
+                self.args.len()
+            ));
+        }
+        let tokens = function
+            .inputs
+            .iter()
+            .zip(&self.args)
+            .map(|(param, arg)| {
+                tokenize(&param.kind, arg)
+                    .with_context(|| format!("Invalid argument {}", display_name(param)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let data = function.encode_input(&tokens)?.into();
+        Ok(EncodedCall { abi, function, data })
+    }
+}
+
+impl EncodedCall {
+    pub fn decode_output(&self, data: &[u8]) -> Result<Vec<Value>> {
+        let tokens = self
+            .function
+            .decode_output(data)
+            .with_context(|| format!("Cannot decode the return value of {}", self.function.name))?;
+        Ok(tokens.into_iter().map(token_to_json).collect())
+    }
+
+    /// Revert reason for `data`, decoding custom errors declared in the ABI.
+    pub fn decode_revert(&self, data: &[u8]) -> Option<String> {
+        if data.len() >= 4 {
+            for error in self.abi.errors.values().flatten() {
+                if id(signature(&error.name, &error.inputs)) != data[..4] {
+                    continue;
+                }
+                if let Ok(tokens) = error.decode(&data[4..]) {
+                    let args: Vec<String> = tokens
+                        .into_iter()
+                        .map(|t| match token_to_json(t) {
+                            Value::String(s) => s,
+                            other => other.to_string(),
+                        })
+                        .collect();
+                    return Some(format!("{}({})", error.name, args.join(", ")));
+                }
+            }
+        }
+        decode_revert_reason(data)
+    }
+
+    /// Turns the simulation of a read-only call into decoded return values or
+    /// a decoded revert reason.
+    pub fn call_result(&self, simulation: SimulationResult) -> Result<ContractCallResult> {
+        if !simulation.success {
+            let revert_reason =
+                self.decode_revert(&simulation.return_data).or(simulation.revert_reason);
+            return Ok(ContractCallResult {
+                success: false,
+                outputs: vec![],
+                revert_reason,
+                return_data: simulation.return_data,
+            });
+        }
+        Ok(ContractCallResult {
+            success: true,
+            outputs: self.decode_output(&simulation.return_data)?,
+            revert_reason: None,
+            return_data: simulation.return_data,
+        })
+    }
+}
+
+/// An ERC-20 call that moves tokens or lets someone else move them, decoded
+/// from raw calldata so checks see the real counterparty and amount rather
+/// than the token contract and the (zero) native value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenCall {
+    Transfer { to: Address, amount: U256 },
+    TransferFrom { from: Address, to: Address, amount: U256 },
+    Approve { spender: Address, amount: U256 },
+}
+
+impl TokenCall {
+    /// Decodes `transfer`, `transferFrom` and `approve` calldata; `None` for
+    /// any other call. Calldata with one of their selectors but arguments
+    /// that do not decode is an error rather than an unknown call.
+    pub fn decode(data: &[u8]) -> Result<Option<Self>> {
+        if data.len() < 4 {
+            return Ok(None);
+        }
+        let (selector, args) = data.split_at(4);
+        let (name, kinds) = if selector == id("transfer(address,uint256)") {
+            ("transfer", vec![ParamType::Address, ParamType::Uint(256)])
+        } else if selector == id("transferFrom(address,address,uint256)") {
+            ("transferFrom", vec![ParamType::Address, ParamType::Address, ParamType::Uint(256)])
+        } else if selector == id("approve(address,uint256)") {
+            ("approve", vec![ParamType::Address, ParamType::Uint(256)])
+        } else {
+            return Ok(None);
+        };
+        let tokens =
+            abi::decode(&kinds, args).with_context(|| format!("Malformed ERC-20 {} call", name))?;
+        let address = |i: usize| tokens[i].clone().into_address().expect("decoded as an address");
+        let amount = tokens[kinds.len() - 1].clone().into_uint().expect("decoded as a uint");
+        Ok(Some(match name {
+            "transfer" => TokenCall::Transfer { to: address(0), amount },
+            "transferFrom" => TokenCall::TransferFrom { from: address(0), to: address(1), amount },
+            _ => TokenCall::Approve { spender: address(0), amount },
+        }))
+    }
+
+    /// The call's calldata, the inverse of `decode`.
+    pub fn encode(&self) -> Bytes {
+        let (signature, tokens) = match self {
+            TokenCall::Transfer { to, amount } => {
+                ("transfer(address,uint256)", vec![Token::Address(*to), Token::Uint(*amount)])
+            }
+            TokenCall::TransferFrom { from, to, amount } => (
+                "transferFrom(address,address,uint256)",
+                vec![Token::Address(*from), Token::Address(*to), Token::Uint(*amount)],
+            ),
+            TokenCall::Approve { spender, amount } => {
+                ("approve(address,uint256)", vec![Token::Address(*spender), Token::Uint(*amount)])
+            }
+        };
+        [id(signature).as_slice(), &abi::encode(&tokens)].concat().into()
+    }
+
+    /// Who receives the tokens, or who may spend them for `approve`.
+    pub fn counterparty(&self) -> Address {
+        match self {
+            TokenCall::Transfer { to, .. } | TokenCall::TransferFrom { to, .. } => *to,
+            TokenCall::Approve { spender, .. } => *spender,
+        }
+    }
+
+    /// Amount moved or allowed, in the token's base units.
+    pub fn amount(&self) -> U256 {
+        match self {
+            TokenCall::Transfer { amount, .. }
+            | TokenCall::TransferFrom { amount, .. }
+            | TokenCall::Approve { amount, .. } => *amount,
+        }
+    }
+}
+
+/// Calldata of ERC-20 `decimals()`.
+pub fn decimals_call() -> Bytes {
+    Bytes::from(id("decimals()").to_vec())
+}
+
+/// Parses a JSON ABI, a single JSON fragment, or human-readable signatures.
+pub fn parse_abi(abi: &Value) -> Result<Abi> {
+    let parsed = match abi {
+        Value::String(signature) => abi::parse_abi(&[signature.as_str()])?,
+        Value::Array(items) if !items.is_empty() && items.iter().all(Value::is_string) => {
+            let signatures: Vec<&str> = items.iter().filter_map(Value::as_str).collect();
+            abi::parse_abi(&signatures)?
+        }
+        Value::Array(_) => serde_json::from_value(abi.clone())?,
+        Value::Object(_) => serde_json::from_value(Value::Array(vec![abi.clone()]))?,
+        _ => return Err(anyhow!("ABI must be JSON or human-readable signatures")),
+    };
+    Ok(parsed)
+}
+
+/// Finds `name` in the ABI. Overloads are told apart by their full signature
+/// or, failing that, by the number of arguments.
+fn find_function<'a>(abi: &'a Abi, name: &str, arg_count: usize) -> Result<&'a Function> {
+    if name.contains('(') {
+        let wanted: String = name.chars().filter(|c| !c.is_whitespace()).collect();
+        return abi
+            .functions()
+            .find(|f| signature(&f.name, &f.inputs) == wanted)
+            .ok_or_else(|| anyhow!("Function {} not found in ABI", name));
+    }
+    let overloads =
+        abi.functions_by_name(name).map_err(|_| anyhow!("Function {} not found in ABI", name))?;
+    if overloads.len() == 1 {
+        return Ok(&overloads[0]);
+    }
+    let mut matching = overloads.iter().filter(|f| f.inputs.len() == arg_count);
+    match (matching.next(), matching.next()) {
+        (Some(function), None) => Ok(function),
+        _ => Err(anyhow!("{} is overloaded; give its full signature instead", name)),
+    }
+}
+
+/// Canonical signature, e.g. `transfer(address,uint256)`.
+fn signature(name: &str, inputs: &[Param]) -> String {
+    let types: Vec<String> = inputs.iter().map(|p| p.kind.to_string()).collect();
+    format!("{}({})", name, types.join(","))
+}
+
+fn display_name(param: &Param) -> String {
+    if param.name.is_empty() {
+        param.kind.to_string()
+    } else {
+        format!("{} ({})", param.name, param.kind)
+    }
+}
+
+/// Converts a JSON argument to an ABI token of type `kind`.
+pub fn tokenize(kind: &ParamType, value: &Value) -> Result<Token> {
+    let token = match kind {
+        ParamType::Address => Token::Address(
+            scalar(value)?.parse::<Address>().map_err(|e| anyhow!("Invalid address: {}", e))?,
+        ),
+        ParamType::Uint(_) => {
+            let text = scalar(value)?;
+            let parsed = match text.strip_prefix("0x") {
+                Some(hex) => U256::from_str_radix(hex, 16).map_err(|e| e.to_string()),
+                None => U256::from_dec_str(&text).map_err(|e| e.to_string()),
+            };
+            Token::Uint(parsed.map_err(|e| anyhow!("Invalid unsigned integer {}: {}", text, e))?)
+        }
+        ParamType::Int(_) => {
+            let text = scalar(value)?;
+            let parsed = match text.strip_prefix("0x") {
+                Some(_) => I256::from_hex_str(&text),
+                None => I256::from_dec_str(&text),
+            };
+            Token::Int(parsed.map_err(|e| anyhow!("Invalid integer {}: {}", text, e))?.into_raw())
+        }
+        ParamType::Bool => match value {
+            Value::Bool(b) => Token::Bool(*b),
+            _ => {
+                Token::Bool(scalar(value)?.parse().map_err(|_| anyhow!("Expected true or false"))?)
+            }
+        },
+        ParamType::String => match value {
+            Value::String(s) => Token::String(s.clone()),
+            _ => return Err(anyhow!("Expected a string")),
+        },
+        ParamType::Bytes => Token::Bytes(hex_bytes(value)?),
+        ParamType::FixedBytes(size) => {
+            let bytes = hex_bytes(value)?;
+            if bytes.len() != *size {
+                return Err(anyhow!("Expected {} bytes, got {}", size, bytes.len()));
+            }
+            Token::FixedBytes(bytes)
+        }
+        ParamType::Array(inner) => {
+            Token::Array(items(value)?.iter().map(|v| tokenize(inner, v)).collect::<Result<_>>()?)
+        }
+        ParamType::FixedArray(inner, size) => {
+            let values = items(value)?;
+            if values.len() != *size {
+                return Err(anyhow!("Expected {} elements, got {}", size, values.len()));
+            }
+            Token::FixedArray(values.iter().map(|v| tokenize(inner, v)).collect::<Result<_>>()?)
+        }
+        ParamType::Tuple(kinds) => {
+            let values = items(value)?;
+            if values.len() != kinds.len() {
+                return Err(anyhow!("Expected {} tuple fields, got {}", kinds.len(), values.len()));
+            }
+            Token::Tuple(
+                kinds.iter().zip(values).map(|(k, v)| tokenize(k, v)).collect::<Result<_>>()?,
+            )
+        }
+    };
+    Ok(token)
+}
+
+/// Renders a decoded ABI token as JSON.
+pub fn token_to_json(token: Token) -> Value {
+    match token {
+        Token::Address(address) => Value::String(format!("{:?}", address)),
+        Token::FixedBytes(bytes) | Token::Bytes(bytes) => {
+            Value::String(format!("0x{}", hex::encode(bytes)))
+        }
+        Token::Uint(value) => Value::String(value.to_string()),
+        Token::Int(value) => Value::String(I256::from_raw(value).to_string()),
+        Token::Bool(value) => Value::Bool(value),
+        Token::String(value) => Value::String(value),
+        Token::FixedArray(tokens) | Token::Array(tokens) | Token::Tuple(tokens) => {
+            Value::Array(tokens.into_iter().map(token_to_json).collect())
+        }
+    }
+}
+
+fn scalar(value: &Value) -> Result<String> {
+    match value {
+        Value::String(s) => Ok(s.trim().to_string()),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Bool(b) => Ok(b.to_string()),
+        _ => Err(anyhow!("Expected a single value, got {}", value)),
+    }
+}
+
+fn items(value: &Value) -> Result<&Vec<Value>> {
+    value.as_array().ok_or_else(|| anyhow!("Expected a JSON array, got {}", value))
+}
+
+fn hex_bytes(value: &Value) -> Result<Vec<u8>> {
+    let text = scalar(value)?;
+    Ok(text.parse::<Bytes>().map_err(|e| anyhow!("Invalid hex bytes: {}", e))?.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const OWNER: &str = "0x742d35cc6634c0532925a3b844bc454e4438f44e";
+
+    fn erc20_transfer(abi: Value, args: Vec<Value>) -> ContractCall {
+        ContractCall {
+            contract: OWNER.to_string(),
+            abi,
+            function: "transfer".to_string(),
+            args,
+            value: None,
+        }
+    }
+
+    #[test]
+    fn test_encodes_json_and_human_readable_abi() {
+        let fragment = json!({
+            "type": "function",
+            "name": "transfer",
+            "stateMutability": "nonpayable",
+            "inputs": [
+                { "name": "to", "type": "address" },
+                { "name": "amount", "type": "uint256" }
+            ],
+            "outputs": [{ "name": "", "type": "bool" }]
+        });
+        let args = vec![json!(OWNER), json!("1000000")];
+        let from_json = erc20_transfer(fragment, args.clone()).encode().unwrap();
+        let from_signature = erc20_transfer(
+            json!(["function transfer(address to, uint256 amount) returns (bool)"]),
+            args,
+        )
+        .encode()
+        .unwrap();
+        assert_eq!(from_json.data, from_signature.data);
+        assert_eq!(hex::encode(&from_json.data[..4]), "a9059cbb");
+        assert_eq!(from_json.data.len(), 4 + 2 * 32);
+
+        let bad_address = erc20_transfer(
+            json!("function transfer(address, uint256)"),
+            vec![json!("0x1234"), json!(1)],
+        );
+        assert!(bad_address.encode().is_err());
+        let missing_arg =
+            erc20_transfer(json!("function transfer(address, uint256)"), vec![json!(OWNER)]);
+        assert!(missing_arg.encode().unwrap_err().to_string().contains("takes 2 arguments"));
+    }
+
+    #[test]
+    fn test_overloads_and_nested_types() {
+        let abi = json!([
+            "function deposit(uint256 amount)",
+            "function deposit(uint256 amount, address to)",
+            "function route(address[] hops, int256 delta, bytes4 tag)"
+        ]);
+        let call = |function: &str, args: Vec<Value>| ContractCall {
+            contract: OWNER.to_string(),
+            abi: abi.clone(),
+            function: function.to_string(),
+            args,
+            value: None,
+        };
+        assert!(call("deposit", vec![json!(1)]).encode().is_ok());
+        assert!(call("deposit(uint256,address)", vec![json!(1), json!(OWNER)]).encode().is_ok());
+
+        let route = call("route", vec![json!([OWNER, OWNER]), json!("-5"), json!("0xdeadbeef")]);
+        let encoded = route.encode().unwrap();
+        let tokens = abi::decode(
+            &[
+                ParamType::Array(Box::new(ParamType::Address)),
+                ParamType::Int(256),
+                ParamType::FixedBytes(4),
+            ],
+            &encoded.data[4..],
+        )
+        .unwrap();
+        assert_eq!(token_to_json(tokens[0].clone()), json!([OWNER, OWNER]));
+        assert_eq!(token_to_json(tokens[1].clone()), json!("-5"));
+        assert_eq!(token_to_json(tokens[2].clone()), json!("0xdeadbeef"));
+
+        let hop = ParamType::Tuple(vec![ParamType::Address, ParamType::Uint(24)]);
+        let token = tokenize(&hop, &json!([OWNER, 500])).unwrap();
+        assert_eq!(token_to_json(token), json!([OWNER, "500"]));
+        assert!(tokenize(&hop, &json!([OWNER])).is_err());
+        assert!(call("route", vec![json!([]), json!(0), json!("0xdead")]).encode().is_err());
+    }
+
+    #[test]
+    fn test_decodes_outputs_and_custom_errors() {
+        let abi = json!([
+            "function balanceOf(address owner) view returns (uint256, bool)",
+            "error InsufficientBalance(uint256 available, uint256 required)"
+        ]);
+        let call = ContractCall {
+            contract: OWNER.to_string(),
+            abi,
+            function: "balanceOf".to_string(),
+            args: vec![json!(OWNER)],
+            value: None,
+        };
+        let encoded = call.encode().unwrap();
+        let output = abi::encode(&[Token::Uint(U256::exp10(20)), Token::Bool(true)]);
+        let result = encoded
+            .call_result(SimulationResult {
+                success: true,
+                revert_reason: None,
+                gas_used: None,
+                return_data: output.into(),
+                token_events: vec![],
+                balance_changes: vec![],
+            })
+            .unwrap();
+        assert_eq!(result.outputs, vec![json!("100000000000000000000"), json!(true)]);
+
+        let mut revert = id("InsufficientBalance(uint256,uint256)").to_vec();
+        revert.extend(abi::encode(&[Token::Uint(1.into()), Token::Uint(2.into())]));
+        assert_eq!(encoded.decode_revert(&revert), Some("InsufficientBalance(1, 2)".to_string()));
+        let result = encoded
+            .call_result(SimulationResult {
+                success: false,
+                revert_reason: Some("execution reverted".to_string()),
+                gas_used: None,
+                return_data: revert.into(),
+                token_events: vec![],
+                balance_changes: vec![],
+            })
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.revert_reason.unwrap().starts_with("InsufficientBalance"));
+    }
+
+    #[test]
+    fn test_decodes_erc20_token_calls() {
+        let owner: Address = OWNER.parse().unwrap();
+        let spender = Address::repeat_byte(0x42);
+        let transfer = erc20_transfer(
+            json!(["function transfer(address to, uint256 amount) returns (bool)"]),
+            vec![json!(OWNER), json!("1500000")],
+        )
+        .encode()
+        .unwrap();
+        let decoded = TokenCall::decode(&transfer.data).unwrap().unwrap();
+        assert_eq!(decoded, TokenCall::Transfer { to: owner, amount: U256::from(1_500_000u64) });
+        assert_eq!(decoded.counterparty(), owner);
+        assert_eq!(decoded.encode(), transfer.data);
+
+        let mut transfer_from = id("transferFrom(address,address,uint256)").to_vec();
+        transfer_from.extend(abi::encode(&[
+            Token::Address(spender),
+            Token::Address(owner),
+            Token::Uint(7.into()),
+        ]));
+        let decoded = TokenCall::decode(&transfer_from).unwrap().unwrap();
+        assert_eq!(decoded.counterparty(), owner);
+        assert_eq!(decoded.amount(), U256::from(7));
+
+        let mut approve = id("approve(address,uint256)").to_vec();
+        approve.extend(abi::encode(&[Token::Address(spender), Token::Uint(U256::MAX)]));
+        let decoded = TokenCall::decode(&approve).unwrap().unwrap();
+        assert_eq!(decoded, TokenCall::Approve { spender, amount: U256::MAX });
+        assert_eq!(decoded.encode().as_ref(), approve.as_slice());
+
+        // other calls are not token movements; truncated token calls are refused
+        assert_eq!(TokenCall::decode(&decimals_call()).unwrap(), None);
+        assert_eq!(TokenCall::decode(&[]).unwrap(), None);
+        assert!(TokenCall::decode(&transfer.data[..36]).is_err());
+    }
+}
@@ -1,7 +1,15 @@
 pub mod bridge;
+pub mod confirmations;
+pub mod contract;
 pub mod ethereum;
+pub mod offline;
+pub mod simulation;
 pub mod solana;
+pub mod staking;
+pub mod swap;
 pub mod traits;
 
 pub use bridge::{BridgeTransaction, BridgeTransactionStatus};
-pub use traits::{BlockchainClient, Bridge};
+pub use traits::{
+    BlockchainClient, Bridge, SignedTransaction, StakingProvider, SwapRouter, UnsignedTransaction,
+};
@@ -0,0 +1,705 @@
+// src/blockchain/offline.rs
+//! Unsigned transaction export for air-gapped signing.
+//!
+//! The networked side builds an `OfflineSigningRequest`, ships it to the cold
+//! machine as JSON or as a sequence of UR-style text fragments (one per QR
+//! frame), and later imports the resulting `OfflineSignedTransaction` to
+//! broadcast it.
+//!
+//! EVM (RLP) and Solana messages are supported. Bitcoin PSBTs are not, since
+//! there is no Bitcoin client in this crate yet.
+//!
+//! The recipient, amount and chain shown next to a transaction are only
+//! metadata; the cold side decodes the transaction itself and refuses to sign
+//! when the two disagree. Calldata is refused unless it is an ERC-20
+//! `transfer` matching a token request.
+use std::str::FromStr;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, Bytes, NameOrAddress, U256};
+use ethers::utils::{format_units, parse_ether, rlp::Rlp};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::contract::TokenCall;
+use super::solana::{sol_to_lamports, SimulatedMessage};
+use super::swap::{format_token_amount, parse_token_amount};
+use super::traits::{SignedTransaction, UnsignedTransaction};
+use crate::core::config::WalletConfig;
+use crate::core::errors::WalletError;
+use crate::crypto::message::verify_solana_message;
+use crate::crypto::signer::Signer;
+
+/// Format version written into every exported document.
+pub const OFFLINE_FORMAT_VERSION: u8 = 1;
+
+/// UR type of an exported unsigned request.
+pub const UR_TYPE_UNSIGNED: &str = "dhw-unsigned-tx";
+/// UR type of a signed transaction coming back from the cold signer.
+pub const UR_TYPE_SIGNED: &str = "dhw-signed-tx";
+
+/// Default fragment body size in bytes; keeps each QR frame small enough to scan reliably.
+pub const DEFAULT_UR_FRAGMENT_LEN: usize = 200;
+
+/// An unsigned transaction plus the context the cold signer needs to show the user.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OfflineSigningRequest {
+    pub version: u8,
+    /// Wallet the transfer is checked and counted against.
+    pub wallet: String,
+    pub network: String,
+    pub from_public_key: Bytes,
+    pub to_address: String,
+    /// Amount of the native token, or of `token` when set.
+    pub amount: String,
+    /// ERC-20 token transferred instead of the native token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<OfflineToken>,
+    pub tx: UnsignedTransaction,
+    /// EIP-155 chain id of an EVM `tx`; kept here because legacy transactions
+    /// drop it when serialized.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<u64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The ERC-20 token an offline request transfers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OfflineToken {
+    pub contract: String,
+    pub decimals: u8,
+}
+
+impl OfflineSigningRequest {
+    pub fn new(
+        wallet: &str,
+        network: &str,
+        from_public_key: &[u8],
+        to_address: &str,
+        amount: &str,
+        tx: UnsignedTransaction,
+    ) -> Self {
+        let chain_id = match &tx {
+            UnsignedTransaction::Evm { tx } => tx.chain_id().map(|id| id.as_u64()),
+            UnsignedTransaction::Solana { .. } => None,
+        };
+        Self {
+            version: OFFLINE_FORMAT_VERSION,
+            wallet: wallet.to_string(),
+            network: network.to_string(),
+            from_public_key: from_public_key.to_vec().into(),
+            to_address: to_address.to_string(),
+            amount: amount.to_string(),
+            token: None,
+            tx,
+            chain_id,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Marks the request as a transfer of `amount` of `token`; `tx` must be
+    /// the token's `transfer` call.
+    pub fn with_token(mut self, token: OfflineToken) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    fn imported(mut self) -> Result<Self> {
+        check_version(self.version)?;
+        if let (UnsignedTransaction::Evm { tx }, Some(chain_id)) = (&mut self.tx, self.chain_id) {
+            match tx.chain_id() {
+                None => {
+                    tx.set_chain_id(chain_id);
+                }
+                Some(id) if id.as_u64() != chain_id => {
+                    return Err(anyhow::anyhow!(
+                        "Transaction is for chain {} but the request says chain {}",
+                        id,
+                        chain_id
+                    ));
+                }
+                Some(_) => {}
+            }
+        }
+        Ok(self)
+    }
+
+    /// What `tx` does, decoded from the transaction alone.
+    pub fn decoded(&self) -> Result<DecodedTransfer, WalletError> {
+        match &self.tx {
+            UnsignedTransaction::Evm { tx } => decode_evm(tx),
+            UnsignedTransaction::Solana { message } => decode_solana(message),
+        }
+    }
+
+    /// Decodes `tx` and checks that it pays `to_address` exactly `amount` (of
+    /// `token`, if set) on the chain of `network`, i.e. that it does what the
+    /// signer is shown.
+    pub fn verify(&self) -> Result<DecodedTransfer, WalletError> {
+        let decoded = self.decoded()?;
+        decoded.check(&self.network, &self.to_address, &self.amount, self.token.as_ref())?;
+        if let Some(chain_id) = self.chain_id {
+            if decoded.chain_id != Some(chain_id) {
+                return Err(mismatch("chain id", decoded.chain_id, chain_id));
+            }
+        }
+        Ok(decoded)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let request: Self = serde_json::from_str(json)?;
+        request.imported()
+    }
+
+    pub fn to_ur(&self, max_fragment_len: usize) -> Result<Vec<String>> {
+        ur_encode(UR_TYPE_UNSIGNED, &serde_json::to_vec(self)?, max_fragment_len)
+    }
+
+    pub fn from_ur(fragments: &[String]) -> Result<Self> {
+        let payload = ur_decode(UR_TYPE_UNSIGNED, fragments)?;
+        let request: Self = serde_json::from_slice(&payload)?;
+        request.imported()
+    }
+
+    /// Signs the request with `signer`, refusing keys other than the one it
+    /// was built for and transactions that do not match the request, see
+    /// `verify`.
+    pub async fn sign(&self, signer: &dyn Signer) -> Result<OfflineSignedTransaction, WalletError> {
+        let public_key = signer.public_key().await?;
+        if public_key != self.from_public_key.as_ref() {
+            return Err(WalletError::CryptoError(
+                "Signing key does not match the request's sender".to_string(),
+            ));
+        }
+        self.verify()?;
+
+        let tx = signer.sign_transaction(&self.tx).await?;
+        Ok(OfflineSignedTransaction {
+            version: OFFLINE_FORMAT_VERSION,
+            wallet: self.wallet.clone(),
+            network: self.network.clone(),
+            tx,
+        })
+    }
+}
+
+/// A signed transaction ready to be imported by the networked side.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OfflineSignedTransaction {
+    pub version: u8,
+    pub wallet: String,
+    pub network: String,
+    pub tx: SignedTransaction,
+}
+
+impl OfflineSignedTransaction {
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let signed: Self = serde_json::from_str(json)?;
+        check_version(signed.version)?;
+        Ok(signed)
+    }
+
+    pub fn to_ur(&self, max_fragment_len: usize) -> Result<Vec<String>> {
+        ur_encode(UR_TYPE_SIGNED, &serde_json::to_vec(self)?, max_fragment_len)
+    }
+
+    pub fn from_ur(fragments: &[String]) -> Result<Self> {
+        let payload = ur_decode(UR_TYPE_SIGNED, fragments)?;
+        let signed: Self = serde_json::from_slice(&payload)?;
+        check_version(signed.version)?;
+        Ok(signed)
+    }
+}
+
+/// A transfer as encoded in the transaction itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedTransfer {
+    /// Recipient of the native value; the token contract for `token_call`.
+    pub to: String,
+    /// Value in the chain's base unit, wei or lamports.
+    pub value: U256,
+    /// `value` in the native token, as request amounts are written.
+    pub amount: String,
+    /// EIP-155 chain id; `None` on Solana.
+    pub chain_id: Option<u64>,
+    /// The ERC-20 call in the calldata. Any other calldata fails to decode.
+    pub token_call: Option<TokenCall>,
+    /// Sender recovered from the signature; `None` before signing.
+    pub from: Option<String>,
+}
+
+impl DecodedTransfer {
+    /// Decodes a signed transaction, e.g. before broadcasting it, and
+    /// recovers who signed it.
+    pub fn from_signed(tx: &SignedTransaction) -> Result<Self, WalletError> {
+        match tx {
+            SignedTransaction::Evm { raw, .. } => {
+                let (tx, signature) =
+                    TypedTransaction::decode_signed(&Rlp::new(raw)).map_err(|e| {
+                        WalletError::SerializationError(format!(
+                            "Invalid signed transaction: {}",
+                            e
+                        ))
+                    })?;
+                let from = signature.recover(tx.sighash()).map_err(|e| {
+                    WalletError::ValidationError(format!("Cannot recover the sender: {}", e))
+                })?;
+                Ok(DecodedTransfer { from: Some(format!("{:?}", from)), ..decode_evm(&tx)? })
+            }
+            SignedTransaction::Solana { message, signature } => {
+                let fee_payer = solana_message(message)?.fee_payer;
+                if !verify_solana_message(message, signature, &fee_payer) {
+                    return Err(WalletError::ValidationError(
+                        "Transaction is not signed by its fee payer".to_string(),
+                    ));
+                }
+                Ok(DecodedTransfer { from: Some(fee_payer), ..decode_solana(message)? })
+            }
+        }
+    }
+
+    /// Refuses the transfer unless it pays `to_address` exactly `amount` on
+    /// the chain `network` has in the default configuration. With `token` the
+    /// transaction must instead be that token's `transfer` of `amount` to
+    /// `to_address`, sending no native value.
+    pub fn check(
+        &self,
+        network: &str,
+        to_address: &str,
+        amount: &str,
+        token: Option<&OfflineToken>,
+    ) -> Result<(), WalletError> {
+        let Some(chain_id) = self.chain_id else {
+            if token.is_some() {
+                return Err(WalletError::ValidationError(
+                    "Token transfers can only be signed offline on EVM networks".to_string(),
+                ));
+            }
+            if self.to != to_address {
+                return Err(mismatch("recipient", &self.to, to_address));
+            }
+            let lamports = U256::from(sol_to_lamports(amount)?);
+            if self.value != lamports {
+                return Err(mismatch("value", &self.amount, amount));
+            }
+            return Ok(());
+        };
+
+        let to = Address::from_str(to_address)
+            .map_err(|e| WalletError::AddressError(format!("Invalid recipient address: {}", e)))?;
+        match (&self.token_call, token) {
+            (None, None) => {
+                if Address::from_str(&self.to).ok() != Some(to) {
+                    return Err(mismatch("recipient", &self.to, to_address));
+                }
+                let wei = parse_ether(amount)
+                    .map_err(|e| WalletError::ValidationError(format!("Invalid amount: {}", e)))?;
+                if self.value != wei {
+                    return Err(mismatch("value", &self.amount, amount));
+                }
+            }
+            (Some(TokenCall::Transfer { to: recipient, amount: units }), Some(token)) => {
+                let contract = Address::from_str(&token.contract).map_err(|e| {
+                    WalletError::AddressError(format!("Invalid token address: {}", e))
+                })?;
+                if Address::from_str(&self.to).ok() != Some(contract) {
+                    return Err(mismatch("token contract", &self.to, &token.contract));
+                }
+                if !self.value.is_zero() {
+                    return Err(mismatch("value", &self.amount, "0"));
+                }
+                if *recipient != to {
+                    return Err(mismatch("token recipient", recipient, to_address));
+                }
+                let expected = parse_token_amount(amount, token.decimals)
+                    .map_err(|e| WalletError::ValidationError(e.to_string()))?;
+                if *units != expected {
+                    return Err(mismatch(
+                        "token amount",
+                        format_token_amount(*units, token.decimals),
+                        amount,
+                    ));
+                }
+            }
+            (Some(call), Some(_)) => {
+                return Err(WalletError::ValidationError(format!(
+                    "Only ERC-20 transfers can be signed offline, not {:?}",
+                    call
+                )))
+            }
+            (Some(call), None) => return Err(mismatch("call", call, "a native transfer")),
+            (None, Some(token)) => {
+                return Err(mismatch("call", "a native transfer", &token.contract))
+            }
+        }
+        let expected = WalletConfig::default()
+            .blockchain
+            .networks
+            .get(network)
+            .and_then(|config| config.chain_id);
+        if expected.is_some_and(|expected| expected != chain_id) {
+            return Err(mismatch("chain id", chain_id, network));
+        }
+        Ok(())
+    }
+}
+
+fn mismatch(
+    field: &str,
+    decoded: impl std::fmt::Debug,
+    shown: impl std::fmt::Debug,
+) -> WalletError {
+    WalletError::ValidationError(format!(
+        "Transaction {} {:?} does not match {:?}",
+        field, decoded, shown
+    ))
+}
+
+fn decode_evm(tx: &TypedTransaction) -> Result<DecodedTransfer, WalletError> {
+    let to = match tx.to() {
+        Some(NameOrAddress::Address(address)) => *address,
+        Some(NameOrAddress::Name(name)) => {
+            return Err(WalletError::ValidationError(format!(
+                "Recipient {} must be resolved before signing",
+                name
+            )))
+        }
+        None => {
+            return Err(WalletError::ValidationError("Transaction has no recipient".to_string()))
+        }
+    };
+    let Some(chain_id) = tx.chain_id() else {
+        return Err(WalletError::ValidationError(
+            "EVM transaction has no chain id and could be replayed on any chain".to_string(),
+        ));
+    };
+    let value = tx.value().copied().unwrap_or_default();
+    let data = tx.data().map(|data| data.as_ref()).unwrap_or_default();
+    let token_call = match TokenCall::decode(data) {
+        Ok(Some(call)) => Some(call),
+        Ok(None) if data.is_empty() => None,
+        Ok(None) => {
+            return Err(WalletError::ValidationError(format!(
+                "Transaction calls unknown function 0x{}",
+                hex::encode(&data[..data.len().min(4)])
+            )))
+        }
+        Err(e) => return Err(WalletError::ValidationError(e.to_string())),
+    };
+    Ok(DecodedTransfer {
+        to: format!("{:?}", to),
+        value,
+        amount: format_units(value, 18).map_err(|e| WalletError::Other(e.to_string()))?,
+        chain_id: Some(chain_id.as_u64()),
+        token_call,
+        from: None,
+    })
+}
+
+fn solana_message(message: &[u8]) -> Result<SimulatedMessage, WalletError> {
+    serde_json::from_slice(message)
+        .map_err(|e| WalletError::SerializationError(format!("Invalid Solana message: {}", e)))
+}
+
+fn decode_solana(message: &[u8]) -> Result<DecodedTransfer, WalletError> {
+    let message = solana_message(message)?;
+    let value = U256::from(message.lamports);
+    Ok(DecodedTransfer {
+        to: message.to,
+        value,
+        amount: format_units(value, 9).map_err(|e| WalletError::Other(e.to_string()))?,
+        chain_id: None,
+        token_call: None,
+        from: None,
+    })
+}
+
+fn check_version(version: u8) -> Result<()> {
+    if version != OFFLINE_FORMAT_VERSION {
+        return Err(anyhow::anyhow!("Unsupported offline format version: {}", version));
+    }
+    Ok(())
+}
+
+/// Splits `payload` into `ur:<type>/<seq>-<total>/<checksum>/<hex>` fragments.
+///
+/// This follows the UR framing (type, sequence, total) so fragments can be
+/// cycled through an animated QR code, but carries hex bodies rather than
+/// bytewords. `checksum` is the first 4 bytes of SHA-256 over the whole
+/// payload and ties fragments of one message together.
+pub fn ur_encode(ur_type: &str, payload: &[u8], max_fragment_len: usize) -> Result<Vec<String>> {
+    if max_fragment_len == 0 {
+        return Err(anyhow::anyhow!("Fragment length must be greater than zero"));
+    }
+    let checksum = hex::encode(&Sha256::digest(payload)[..4]);
+    let chunks: Vec<&[u8]> = payload.chunks(max_fragment_len).collect();
+    let total = chunks.len().max(1);
+
+    if chunks.is_empty() {
+        return Ok(vec![format!("ur:{}/1-1/{}/", ur_type, checksum)]);
+    }
+
+    Ok(chunks
+        .iter()
+        .enumerate()
+        .map(|(i, chunk)| {
+            format!("ur:{}/{}-{}/{}/{}", ur_type, i + 1, total, checksum, hex::encode(chunk))
+        })
+        .collect())
+}
+
+/// Reassembles fragments produced by `ur_encode`. Fragments may arrive in any
+/// order and duplicates are ignored, as happens when scanning a looping QR.
+pub fn ur_decode(ur_type: &str, fragments: &[String]) -> Result<Vec<u8>> {
+    let mut parts: Vec<Option<Vec<u8>>> = Vec::new();
+    let mut expected_checksum: Option<String> = None;
+
+    for fragment in fragments {
+        let rest = fragment
+            .trim()
+            .strip_prefix("ur:")
+            .ok_or_else(|| anyhow::anyhow!("Not a UR fragment: {}", fragment))?;
+        let fields: Vec<&str> = rest.split('/').collect();
+        if fields.len() != 4 {
+            return Err(anyhow::anyhow!("Malformed UR fragment: {}", fragment));
+        }
+        if !fields[0].eq_ignore_ascii_case(ur_type) {
+            return Err(anyhow::anyhow!("Unexpected UR type: {}", fields[0]));
+        }
+
+        let (seq, total) = fields[1]
+            .split_once('-')
+            .and_then(|(s, t)| Some((s.parse::<usize>().ok()?, t.parse::<usize>().ok()?)))
+            .ok_or_else(|| anyhow::anyhow!("Malformed UR sequence: {}", fields[1]))?;
+        if seq == 0 || seq > total {
+            return Err(anyhow::anyhow!("UR sequence out of range: {}", fields[1]));
+        }
+
+        let checksum = fields[2].to_ascii_lowercase();
+        match &expected_checksum {
+            Some(c) if *c != checksum => {
+                return Err(anyhow::anyhow!("UR fragments belong to different messages"))
+            }
+            None => expected_checksum = Some(checksum),
+            _ => {}
+        }
+
+        if parts.is_empty() {
+            parts = vec![None; total];
+        } else if parts.len() != total {
+            return Err(anyhow::anyhow!("Inconsistent UR fragment count"));
+        }
+        parts[seq - 1] = Some(hex::decode(fields[3])?);
+    }
+
+    let missing = parts.iter().filter(|p| p.is_none()).count();
+    if parts.is_empty() || missing > 0 {
+        return Err(anyhow::anyhow!("Incomplete UR message: {} fragment(s) missing", missing));
+    }
+
+    let payload: Vec<u8> = parts.into_iter().flatten().flatten().collect();
+    let checksum = hex::encode(&Sha256::digest(&payload)[..4]);
+    if Some(checksum) != expected_checksum {
+        return Err(anyhow::anyhow!("UR checksum mismatch"));
+    }
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::signer::SoftwareSigner;
+    use ethers::types::{Address, TransactionRequest};
+
+    async fn request_for(signer_key: &[u8]) -> (OfflineSigningRequest, SoftwareSigner) {
+        let signer = SoftwareSigner::secp256k1(signer_key).unwrap();
+        let public_key = signer.public_key().await.unwrap();
+        let tx = TransactionRequest::new()
+            .to(Address::repeat_byte(0x42))
+            .value(1_000u64)
+            .gas(21_000u64)
+            .gas_price(1_000_000_000u64)
+            .nonce(0u64)
+            .chain_id(11155111u64);
+        let request = OfflineSigningRequest::new(
+            "cold",
+            "sepolia",
+            &public_key,
+            "0x4242424242424242424242424242424242424242",
+            "0.000000000000001",
+            UnsignedTransaction::Evm { tx: tx.into() },
+        );
+        (request, signer)
+    }
+
+    #[test]
+    fn test_ur_roundtrip_out_of_order() {
+        let payload: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+        let mut fragments = ur_encode(UR_TYPE_UNSIGNED, &payload, 64).unwrap();
+        assert_eq!(fragments.len(), 16);
+        assert!(fragments[0].starts_with("ur:dhw-unsigned-tx/1-16/"));
+
+        fragments.reverse();
+        fragments.push(fragments[3].clone());
+        assert_eq!(ur_decode(UR_TYPE_UNSIGNED, &fragments).unwrap(), payload);
+    }
+
+    #[test]
+    fn test_ur_rejects_missing_and_foreign_fragments() {
+        let fragments = ur_encode(UR_TYPE_UNSIGNED, &[7u8; 300], 100).unwrap();
+        assert!(ur_decode(UR_TYPE_UNSIGNED, &fragments[..2]).is_err());
+        assert!(ur_decode(UR_TYPE_SIGNED, &fragments).is_err());
+
+        let other = ur_encode(UR_TYPE_UNSIGNED, &[8u8; 300], 100).unwrap();
+        let mixed = vec![fragments[0].clone(), other[1].clone(), fragments[2].clone()];
+        assert!(ur_decode(UR_TYPE_UNSIGNED, &mixed).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_offline_sign_roundtrip() {
+        let (request, signer) = request_for(&[0x11u8; 32]).await;
+
+        // hot side -> QR -> cold side
+        let fragments = request.to_ur(DEFAULT_UR_FRAGMENT_LEN).unwrap();
+        let received = OfflineSigningRequest::from_ur(&fragments).unwrap();
+        assert_eq!(received, request);
+
+        // cold side signs, hot side imports from JSON
+        let signed = received.sign(&signer).await.unwrap();
+        let imported = OfflineSignedTransaction::from_json(&signed.to_json().unwrap()).unwrap();
+        assert_eq!(imported, signed);
+        assert!(matches!(imported.tx, SignedTransaction::Evm { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_offline_sign_rejects_mismatched_metadata() {
+        let (request, signer) = request_for(&[0x11u8; 32]).await;
+        let decoded = request.verify().unwrap();
+        assert_eq!(decoded.to, "0x4242424242424242424242424242424242424242");
+        assert_eq!(decoded.value, U256::from(1_000u64));
+        assert_eq!(decoded.chain_id, Some(11155111));
+
+        let mut other_recipient = request.clone();
+        other_recipient.to_address = format!("{:?}", Address::repeat_byte(0x43));
+        let mut other_amount = request.clone();
+        other_amount.amount = "1".to_string();
+        let mut other_network = request.clone();
+        other_network.network = "eth".to_string();
+        for tampered in [other_recipient, other_amount, other_network] {
+            let err = tampered.sign(&signer).await.unwrap_err();
+            assert!(matches!(err, WalletError::ValidationError(_)), "{:?}", err);
+        }
+
+        // metadata claiming another chain than the transaction is refused on import
+        let mut json: serde_json::Value =
+            serde_json::from_str(&request.to_json().unwrap()).unwrap();
+        json["chain_id"] = 1.into();
+        json["tx"]["tx"]["chainId"] = "0xaa36a7".into();
+        let err = OfflineSigningRequest::from_json(&json.to_string()).unwrap_err();
+        assert!(err.to_string().contains("chain 11155111"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_offline_sign_checks_token_calls() {
+        const USDC: &str = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48";
+        let signer = SoftwareSigner::secp256k1(&[0x11u8; 32]).unwrap();
+        let public_key = signer.public_key().await.unwrap();
+        let recipient = Address::repeat_byte(0x42);
+        let request_with = |data: Bytes, token: Option<OfflineToken>| {
+            let tx = TransactionRequest::new()
+                .to(USDC.parse::<Address>().unwrap())
+                .data(data)
+                .gas(60_000u64)
+                .gas_price(1_000_000_000u64)
+                .nonce(0u64)
+                .chain_id(11155111u64);
+            let request = OfflineSigningRequest::new(
+                "cold",
+                "sepolia",
+                &public_key,
+                &format!("{:?}", recipient),
+                "1.5",
+                UnsignedTransaction::Evm { tx: tx.into() },
+            );
+            match token {
+                Some(token) => request.with_token(token),
+                None => request,
+            }
+        };
+        let usdc = OfflineToken { contract: USDC.to_string(), decimals: 6 };
+        let transfer = TokenCall::Transfer { to: recipient, amount: U256::from(1_500_000u64) };
+
+        let request = request_with(transfer.encode(), Some(usdc.clone()));
+        let received = OfflineSigningRequest::from_json(&request.to_json().unwrap()).unwrap();
+        assert_eq!(received.verify().unwrap().token_call, Some(transfer.clone()));
+        assert!(received.sign(&signer).await.is_ok());
+
+        let too_much = TokenCall::Transfer { to: recipient, amount: U256::from(2_000_000u64) };
+        let approve = TokenCall::Approve { spender: recipient, amount: U256::from(1_500_000u64) };
+        let refused = [
+            // calldata the request does not mention, or the other way round
+            request_with(transfer.encode(), None),
+            request_with(Bytes::new(), Some(usdc.clone())),
+            // token calls that do not match the request
+            request_with(too_much.encode(), Some(usdc.clone())),
+            request_with(approve.encode(), Some(usdc.clone())),
+            request_with(
+                transfer.encode(),
+                Some(OfflineToken { contract: format!("{:?}", recipient), decimals: 6 }),
+            ),
+            // calls that are not ERC-20 token movements
+            request_with(Bytes::from(vec![0xde, 0xad, 0xbe, 0xef]), Some(usdc.clone())),
+        ];
+        for request in refused {
+            let err = request.sign(&signer).await.unwrap_err();
+            assert!(matches!(err, WalletError::ValidationError(_)), "{:?}", err);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_signed_transactions_recover_their_sender() {
+        let (request, signer) = request_for(&[0x11u8; 32]).await;
+        let signed = request.sign(&signer).await.unwrap();
+        let public_key = signer.public_key().await.unwrap();
+        let sender = crate::blockchain::ethereum::address_from_public_key(&public_key).unwrap();
+        let decoded = DecodedTransfer::from_signed(&signed.tx).unwrap();
+        assert_eq!(decoded.from, Some(format!("{:?}", sender)));
+
+        // a Solana transaction must be signed by its fee payer
+        let ed25519 = SoftwareSigner::ed25519(&[0x33u8; 32]).unwrap();
+        let fee_payer = bs58::encode(ed25519.public_key().await.unwrap()).into_string();
+        let message = serde_json::to_vec(&serde_json::json!({
+            "fee_payer": fee_payer,
+            "to": "11111111111111111111111111111111",
+            "lamports": 250_000_000u64,
+            "recent_blockhash": "11111111111111111111111111111111",
+        }))
+        .unwrap();
+        let unsigned = UnsignedTransaction::Solana { message: message.into() };
+        let signed = ed25519.sign_transaction(&unsigned).await.unwrap();
+        assert_eq!(DecodedTransfer::from_signed(&signed).unwrap().from, Some(fee_payer));
+        let forged = SoftwareSigner::ed25519(&[0x44u8; 32])
+            .unwrap()
+            .sign_transaction(&unsigned)
+            .await
+            .unwrap();
+        assert!(DecodedTransfer::from_signed(&forged).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_offline_sign_rejects_wrong_key() {
+        let (request, _) = request_for(&[0x11u8; 32]).await;
+        let other = SoftwareSigner::secp256k1(&[0x22u8; 32]).unwrap();
+        assert!(request.sign(&other).await.is_err());
+    }
+}
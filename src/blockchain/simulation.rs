@@ -0,0 +1,399 @@
+// src/blockchain/simulation.rs
+//! Dry runs of transactions before they are signed.
+//!
+//! EVM transactions are traced with `debug_traceCall` and geth's `callTracer`
+//! (with logs), which shows every value transfer and ERC-20 event the
+//! transaction would produce. Nodes without the debug namespace fall back to a
+//! plain `eth_call`, which still reveals whether the transaction reverts and
+//! why, but only the top-level value transfer.
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use ethers::abi::{self, ParamType};
+use ethers::providers::{JsonRpcClient, Middleware, Provider, RpcError};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, Bytes, H256, I256, U256};
+use ethers::utils::keccak256;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::debug;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenEventKind {
+    Transfer,
+    Approval,
+}
+
+/// An ERC-20 `Transfer` or `Approval` emitted during a simulation. For
+/// approvals `from` is the owner and `to` the spender.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenEvent {
+    pub kind: TokenEventKind,
+    pub token: String,
+    pub from: String,
+    pub to: String,
+    pub amount: String,
+}
+
+/// Net change of one asset held by one address. `asset` is the native token
+/// symbol or a token contract address; `delta` is signed and in the asset's
+/// smallest unit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BalanceChange {
+    pub address: String,
+    pub asset: String,
+    pub delta: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationResult {
+    pub success: bool,
+    pub revert_reason: Option<String>,
+    pub gas_used: Option<u64>,
+    /// Return data of the top-level call, or the revert data when it failed.
+    pub return_data: Bytes,
+    pub token_events: Vec<TokenEvent>,
+    pub balance_changes: Vec<BalanceChange>,
+}
+
+impl SimulationResult {
+    /// Human-readable reason the simulation failed.
+    pub fn failure(&self) -> String {
+        self.revert_reason.clone().unwrap_or_else(|| "execution reverted".to_string())
+    }
+}
+
+/// One frame of a `callTracer` trace.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CallFrame {
+    from: Address,
+    #[serde(default)]
+    to: Option<Address>,
+    #[serde(default)]
+    value: Option<U256>,
+    #[serde(default)]
+    gas_used: Option<U256>,
+    #[serde(default)]
+    output: Option<Bytes>,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    revert_reason: Option<String>,
+    #[serde(default)]
+    calls: Vec<CallFrame>,
+    #[serde(default)]
+    logs: Vec<CallLog>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CallLog {
+    address: Address,
+    #[serde(default)]
+    topics: Vec<H256>,
+    #[serde(default)]
+    data: Bytes,
+}
+
+/// Native value moved between two addresses.
+type ValueTransfer = (Address, Address, U256);
+
+/// Simulates `tx` against the latest block. RPC failures are errors; a
+/// reverting transaction is a successful simulation with `success == false`.
+pub async fn simulate_evm<P: JsonRpcClient>(
+    provider: &Provider<P>,
+    tx: &TypedTransaction,
+    native_symbol: &str,
+) -> Result<SimulationResult> {
+    let tracer = json!({ "tracer": "callTracer", "tracerConfig": { "withLog": true } });
+    match provider.request::<_, CallFrame>("debug_traceCall", (tx, "latest", tracer)).await {
+        Ok(frame) => Ok(from_trace(&frame, native_symbol)),
+        Err(e) => {
+            debug!("debug_traceCall unavailable, falling back to eth_call: {}", e);
+            simulate_with_call(provider, tx, native_symbol).await
+        }
+    }
+}
+
+/// Simulates `tx` with a plain `eth_call`: the return or revert data and the
+/// top-level value transfer, without events.
+pub async fn simulate_with_call<P: JsonRpcClient>(
+    provider: &Provider<P>,
+    tx: &TypedTransaction,
+    native_symbol: &str,
+) -> Result<SimulationResult> {
+    match provider.call(tx, None).await {
+        Ok(return_data) => {
+            let transfers = match (tx.from(), tx.to_addr(), tx.value()) {
+                (Some(from), Some(to), Some(value)) => vec![(*from, *to, *value)],
+                _ => vec![],
+            };
+            Ok(SimulationResult {
+                success: true,
+                revert_reason: None,
+                gas_used: None,
+                return_data,
+                token_events: vec![],
+                balance_changes: balance_changes(native_symbol, &transfers, &[]),
+            })
+        }
+        Err(e) => {
+            let Some(response) = e.as_error_response() else {
+                return Err(e.into());
+            };
+            let return_data = response
+                .data
+                .as_ref()
+                .and_then(|data| data.as_str())
+                .and_then(|data| data.parse::<Bytes>().ok())
+                .unwrap_or_default();
+            if return_data.is_empty() && !response.message.contains("revert") {
+                return Err(e.into());
+            }
+            let revert_reason =
+                decode_revert_reason(&return_data).or_else(|| Some(response.message.clone()));
+            Ok(SimulationResult {
+                success: false,
+                revert_reason,
+                gas_used: None,
+                return_data,
+                token_events: vec![],
+                balance_changes: vec![],
+            })
+        }
+    }
+}
+
+fn from_trace(frame: &CallFrame, native_symbol: &str) -> SimulationResult {
+    let gas_used = frame.gas_used.map(|gas| gas.low_u64());
+    let return_data = frame.output.clone().unwrap_or_default();
+    if frame.error.is_some() {
+        let revert_reason = decode_revert_reason(&return_data)
+            .or_else(|| frame.revert_reason.clone())
+            .or_else(|| frame.error.clone());
+        return SimulationResult {
+            success: false,
+            revert_reason,
+            gas_used,
+            return_data,
+            token_events: vec![],
+            balance_changes: vec![],
+        };
+    }
+
+    let mut transfers = Vec::new();
+    let mut token_events = Vec::new();
+    collect_frame(frame, &mut transfers, &mut token_events);
+    let balance_changes = balance_changes(native_symbol, &transfers, &token_events);
+    SimulationResult {
+        success: true,
+        revert_reason: None,
+        gas_used,
+        return_data,
+        token_events,
+        balance_changes,
+    }
+}
+
+/// Walks the call tree, skipping reverted sub-calls whose effects are undone.
+fn collect_frame(
+    frame: &CallFrame,
+    transfers: &mut Vec<ValueTransfer>,
+    token_events: &mut Vec<TokenEvent>,
+) {
+    if frame.error.is_some() {
+        return;
+    }
+    if let (Some(to), Some(value)) = (frame.to, frame.value) {
+        if !value.is_zero() {
+            transfers.push((frame.from, to, value));
+        }
+    }
+    token_events.extend(
+        frame.logs.iter().filter_map(|log| decode_token_event(log.address, &log.topics, &log.data)),
+    );
+    for call in &frame.calls {
+        collect_frame(call, transfers, token_events);
+    }
+}
+
+/// Decodes an ERC-20 `Transfer` or `Approval` log. ERC-721 transfers, whose
+/// token id is indexed, are not matched.
+pub fn decode_token_event(token: Address, topics: &[H256], data: &[u8]) -> Option<TokenEvent> {
+    let kind = match topics.first()?.as_bytes() {
+        t if t == keccak256("Transfer(address,address,uint256)").as_slice() => {
+            TokenEventKind::Transfer
+        }
+        t if t == keccak256("Approval(address,address,uint256)").as_slice() => {
+            TokenEventKind::Approval
+        }
+        _ => return None,
+    };
+    if topics.len() != 3 || data.len() != 32 {
+        return None;
+    }
+    Some(TokenEvent {
+        kind,
+        token: format!("{:?}", token),
+        from: format!("{:?}", Address::from(topics[1])),
+        to: format!("{:?}", Address::from(topics[2])),
+        amount: U256::from_big_endian(data).to_string(),
+    })
+}
+
+/// Nets native transfers and token `Transfer` events into per-address deltas,
+/// leaving out assets whose balance does not change.
+fn balance_changes(
+    native_symbol: &str,
+    transfers: &[ValueTransfer],
+    token_events: &[TokenEvent],
+) -> Vec<BalanceChange> {
+    let mut deltas: BTreeMap<(String, String), I256> = BTreeMap::new();
+    let mut apply = |asset: &str, from: String, to: String, amount: I256| {
+        *deltas.entry((asset.to_string(), from)).or_insert_with(I256::zero) -= amount;
+        *deltas.entry((asset.to_string(), to)).or_insert_with(I256::zero) += amount;
+    };
+    for (from, to, value) in transfers {
+        apply(native_symbol, format!("{:?}", from), format!("{:?}", to), I256::from_raw(*value));
+    }
+    for event in token_events.iter().filter(|e| e.kind == TokenEventKind::Transfer) {
+        if let Ok(amount) = I256::from_dec_str(&event.amount) {
+            apply(&event.token, event.from.clone(), event.to.clone(), amount);
+        }
+    }
+    deltas
+        .into_iter()
+        .filter(|(_, delta)| !delta.is_zero())
+        .map(|((asset, address), delta)| BalanceChange { address, asset, delta: delta.to_string() })
+        .collect()
+}
+
+/// Decodes `Error(string)` and `Panic(uint256)` revert data; other custom
+/// errors are reported by selector.
+pub fn decode_revert_reason(data: &[u8]) -> Option<String> {
+    if data.len() < 4 {
+        return None;
+    }
+    let (selector, args) = data.split_at(4);
+    match selector {
+        [0x08, 0xc3, 0x79, 0xa0] => {
+            abi::decode(&[ParamType::String], args).ok()?.pop()?.into_string()
+        }
+        [0x4e, 0x48, 0x7b, 0x71] => {
+            let code = abi::decode(&[ParamType::Uint(256)], args).ok()?.pop()?.into_uint()?;
+            Some(format!("Panic(0x{:x})", code))
+        }
+        _ => Some(format!("Custom error 0x{}", hex::encode(selector))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::abi::Token;
+    use ethers::types::TransactionRequest;
+
+    fn topic(address: Address) -> H256 {
+        H256::from(address)
+    }
+
+    #[test]
+    fn test_decode_revert_reason() {
+        let mut error = ethers::utils::id("Error(string)").to_vec();
+        error.extend(abi::encode(&[Token::String("insufficient balance".to_string())]));
+        assert_eq!(decode_revert_reason(&error).as_deref(), Some("insufficient balance"));
+
+        let mut panic = ethers::utils::id("Panic(uint256)").to_vec();
+        panic.extend(abi::encode(&[Token::Uint(0x11.into())]));
+        assert_eq!(decode_revert_reason(&panic).as_deref(), Some("Panic(0x11)"));
+
+        assert_eq!(
+            decode_revert_reason(&[0xde, 0xad, 0xbe, 0xef]).unwrap(),
+            "Custom error 0xdeadbeef"
+        );
+        assert_eq!(decode_revert_reason(&[]), None);
+    }
+
+    #[tokio::test]
+    async fn test_trace_yields_events_and_balance_changes() {
+        let (provider, mock) = Provider::mocked();
+        let wallet = Address::repeat_byte(0x01);
+        let router = Address::repeat_byte(0x02);
+        let pool = Address::repeat_byte(0x03);
+        let usdc = Address::repeat_byte(0x0a);
+        let transfer = H256::from(keccak256("Transfer(address,address,uint256)"));
+        let amount = |value: u64| Bytes::from(abi::encode(&[Token::Uint(value.into())]));
+        mock.push(json!({
+            "from": wallet,
+            "to": router,
+            "value": "0xde0b6b3a7640000",
+            "gasUsed": "0x1d4c0",
+            "output": "0x",
+            "calls": [
+                {
+                    "from": router,
+                    "to": pool,
+                    "value": "0xde0b6b3a7640000",
+                    "logs": [{
+                        "address": usdc,
+                        "topics": [transfer, topic(pool), topic(wallet)],
+                        "data": amount(2_000_000_000),
+                    }],
+                },
+                {
+                    "from": router,
+                    "to": pool,
+                    "error": "execution reverted",
+                    "logs": [{
+                        "address": usdc,
+                        "topics": [transfer, topic(pool), topic(router)],
+                        "data": amount(1),
+                    }],
+                },
+            ],
+        }))
+        .unwrap();
+
+        let tx = TransactionRequest::new().from(wallet).to(router).value(U256::exp10(18));
+        let result = simulate_evm(&provider, &tx.into(), "ETH").await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.gas_used, Some(120_000));
+        assert_eq!(result.token_events.len(), 1);
+        assert_eq!(result.token_events[0].amount, "2000000000");
+
+        let delta = |address: Address, asset: &str| {
+            result
+                .balance_changes
+                .iter()
+                .find(|c| c.address == format!("{:?}", address) && c.asset == asset)
+                .map(|c| c.delta.clone())
+        };
+        assert_eq!(delta(wallet, "ETH").as_deref(), Some("-1000000000000000000"));
+        assert_eq!(delta(pool, "ETH").as_deref(), Some("1000000000000000000"));
+        // the router only passes the ETH through
+        assert_eq!(delta(router, "ETH"), None);
+        assert_eq!(delta(wallet, &format!("{:?}", usdc)).as_deref(), Some("2000000000"));
+    }
+
+    #[tokio::test]
+    async fn test_reverted_trace_reports_reason() {
+        let (provider, mock) = Provider::mocked();
+        let mut revert = ethers::utils::id("Error(string)").to_vec();
+        revert.extend(abi::encode(&[Token::String("STF".to_string())]));
+        mock.push(json!({
+            "from": Address::repeat_byte(0x01),
+            "to": Address::repeat_byte(0x02),
+            "gasUsed": "0x5208",
+            "output": Bytes::from(revert),
+            "error": "execution reverted",
+        }))
+        .unwrap();
+
+        let tx = TransactionRequest::new().to(Address::repeat_byte(0x02));
+        let result = simulate_evm(&provider, &tx.into(), "ETH").await.unwrap();
+        assert!(!result.success);
+        assert_eq!(result.failure(), "STF");
+        assert!(result.balance_changes.is_empty());
+    }
+}
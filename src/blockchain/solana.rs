@@ -4,7 +4,8 @@ use tracing::{debug, info};
 
 use crate::core::errors::WalletError;
 
-use super::traits::{BlockchainClient, TransactionStatus};
+use super::simulation::{BalanceChange, SimulationResult};
+use super::traits::{BlockchainClient, SignedTransaction, TransactionStatus, UnsignedTransaction};
 #[derive(Clone)]
 pub struct SolanaClient {
     _rpc_url: String,
@@ -40,6 +41,22 @@ impl SolanaClient {
     }
 }
 
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct SimulatedMessage {
+    pub(crate) fee_payer: String,
+    pub(crate) to: String,
+    pub(crate) lamports: u64,
+    recent_blockhash: String,
+}
+
+/// Lamports in `amount` SOL, rounded down as a transfer is built.
+pub(crate) fn sol_to_lamports(amount: &str) -> Result<u64, WalletError> {
+    let amount_f64: f64 = amount
+        .parse()
+        .map_err(|e| WalletError::ValidationError(format!("Invalid amount: {}", e)))?;
+    Ok((amount_f64 * 1_000_000_000.0) as u64)
+}
+
 #[async_trait]
 impl BlockchainClient for SolanaClient {
     fn clone_box(&self) -> Box<dyn BlockchainClient> {
@@ -60,17 +77,17 @@ impl BlockchainClient for SolanaClient {
         Ok(balance_sol.to_string())
     }
 
-    async fn send_transaction(
+    async fn build_transaction(
         &self,
-        private_key: &[u8],
+        from_public_key: &[u8],
         to: &str,
         amount: &str,
-    ) -> Result<String, WalletError> {
-        info!("💸 Sending {} SOL to {} (simulated)", amount, to);
+    ) -> Result<UnsignedTransaction, WalletError> {
+        info!("Building transfer of {} SOL to {} (simulated)", amount, to);
 
-        if private_key.len() != 32 {
+        if from_public_key.len() != 32 {
             return Err(WalletError::KeyDerivationError(
-                "Private key must be 32 bytes for Solana".to_string(),
+                "Fee payer public key must be 32 bytes for Solana".to_string(),
             ));
         }
 
@@ -78,10 +95,40 @@ impl BlockchainClient for SolanaClient {
             return Err(WalletError::AddressError(format!("Invalid recipient address: {}", to)));
         }
 
-        // Parse amount
-        let _amount_f64: f64 = amount
-            .parse()
-            .map_err(|e| WalletError::ValidationError(format!("Invalid amount: {}", e)))?;
+        // Simulated message - a real client would compile a system transfer
+        // instruction against a recent blockhash.
+        let message = SimulatedMessage {
+            fee_payer: bs58::encode(from_public_key).into_string(),
+            to: to.to_string(),
+            lamports: sol_to_lamports(amount)?,
+            recent_blockhash: format!("simulated_blockhash_{}", chrono::Utc::now().timestamp()),
+        };
+        let message = serde_json::to_vec(&message)
+            .map_err(|e| WalletError::SerializationError(e.to_string()))?;
+
+        Ok(UnsignedTransaction::Solana { message: message.into() })
+    }
+
+    async fn broadcast_transaction(&self, tx: &SignedTransaction) -> Result<String, WalletError> {
+        let (message, signature) = match tx {
+            SignedTransaction::Solana { message, signature } => (message, signature),
+            _ => return Err(WalletError::ValidationError("Not a Solana transaction".to_string())),
+        };
+
+        // The cluster would reject a transaction not signed by its fee payer; check it here too.
+        let parsed: SimulatedMessage = serde_json::from_slice(message)
+            .map_err(|e| WalletError::SerializationError(e.to_string()))?;
+        let fee_payer: [u8; 32] = bs58::decode(&parsed.fee_payer)
+            .into_vec()
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| WalletError::AddressError("Invalid fee payer".to_string()))?;
+        let key = ed25519_dalek::VerifyingKey::from_bytes(&fee_payer)
+            .map_err(|e| WalletError::CryptoError(e.to_string()))?;
+        let signature = ed25519_dalek::Signature::from_slice(signature)
+            .map_err(|e| WalletError::CryptoError(e.to_string()))?;
+        key.verify_strict(message, &signature)
+            .map_err(|_| WalletError::CryptoError("Invalid fee payer signature".to_string()))?;
 
         // Simulated transaction hash
         let tx_hash = format!("simulated_solana_tx_{}", chrono::Utc::now().timestamp());
@@ -90,6 +137,35 @@ impl BlockchainClient for SolanaClient {
         Ok(tx_hash)
     }
 
+    async fn simulate_transaction(
+        &self,
+        tx: &UnsignedTransaction,
+    ) -> Result<Option<SimulationResult>, WalletError> {
+        let UnsignedTransaction::Solana { message } = tx else {
+            return Err(WalletError::ValidationError("Not a Solana transaction".to_string()));
+        };
+
+        // Simulated preview - a real client would call simulateTransaction.
+        let parsed: SimulatedMessage = serde_json::from_slice(message)
+            .map_err(|e| WalletError::SerializationError(e.to_string()))?;
+        let change = |address: &str, delta: String| BalanceChange {
+            address: address.to_string(),
+            asset: "SOL".to_string(),
+            delta,
+        };
+        Ok(Some(SimulationResult {
+            success: true,
+            revert_reason: None,
+            gas_used: None,
+            return_data: Default::default(),
+            token_events: vec![],
+            balance_changes: vec![
+                change(&parsed.fee_payer, format!("-{}", parsed.lamports)),
+                change(&parsed.to, parsed.lamports.to_string()),
+            ],
+        }))
+    }
+
     async fn get_transaction_status(
         &self,
         tx_hash: &str,
@@ -160,4 +236,20 @@ mod tests {
         assert!(client.validate_address("Vote111111111111111111111111111111111111111").unwrap());
         assert!(!client.validate_address("invalid").unwrap());
     }
+
+    #[tokio::test]
+    async fn test_send_transaction_with_signer() {
+        use crate::crypto::signer::SoftwareSigner;
+
+        let client = SolanaClient::new("https://api.devnet.solana.com").await.unwrap();
+        let signer = SoftwareSigner::ed25519(&[7u8; 32]).unwrap();
+        let to = "11111111111111111111111111111111";
+
+        let tx_hash = client.send_transaction(&signer, to, "0.5").await.unwrap();
+        assert!(tx_hash.starts_with("simulated_solana_tx_"));
+
+        // a secp256k1 signer cannot sign a Solana message
+        let wrong = SoftwareSigner::secp256k1(&[7u8; 32]).unwrap();
+        assert!(client.send_transaction(&wrong, to, "0.5").await.is_err());
+    }
 }
@@ -0,0 +1,529 @@
+// src/blockchain/staking/lido.rs
+//! Lido liquid staking on Ethereum.
+//!
+//! Staking calls stETH `submit`, which mints stETH one-to-one for the ETH sent.
+//! Unstaking approves the WithdrawalQueue for the stETH being redeemed and
+//! files `requestWithdrawals`, split into requests of at most 1000 stETH as the
+//! queue requires. Each request becomes a `withdrawal:<id>` position that can
+//! be claimed for ETH once the protocol has finalized it.
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use ethers::abi::{self, ParamType, Token};
+use ethers::providers::{Http, JsonRpcClient, Middleware, Provider};
+use ethers::types::{Address, Bytes, TransactionRequest, U256};
+use ethers::utils::id;
+use tracing::info;
+
+use crate::blockchain::bridge::evm::encode_approve;
+use crate::blockchain::ethereum::{address_from_public_key, send_with_signer};
+use crate::blockchain::staking::{
+    StakeAction, StakePosition, StakeReceipt, StakeState, StakingProtocol,
+};
+use crate::blockchain::swap::{format_token_amount, parse_token_amount};
+use crate::blockchain::traits::StakingProvider;
+use crate::core::config::{
+    StakingProviderConfig, LIDO_MAINNET_STETH, LIDO_MAINNET_WITHDRAWAL_QUEUE,
+};
+use crate::core::wallet_info::SecureWalletData;
+use crate::crypto::signer::{Signer, SoftwareSigner};
+
+pub const ETH_DECIMALS: u8 = 18;
+/// Position id of the wallet's stETH balance.
+pub const STETH_POSITION: &str = "stETH";
+const WITHDRAWAL_PREFIX: &str = "withdrawal:";
+const SUBMIT_GAS: u64 = 150_000;
+const APPROVE_GAS: u64 = 60_000;
+/// Gas per request in a `requestWithdrawals` batch, on top of a fixed base.
+const REQUEST_GAS_BASE: u64 = 100_000;
+const REQUEST_GAS_EACH: u64 = 150_000;
+const CLAIM_GAS: u64 = 150_000;
+/// Bounds the WithdrawalQueue puts on a single request, in wei.
+const MIN_WITHDRAWAL_WEI: u64 = 100;
+const MAX_WITHDRAWAL_ETHER: u64 = 1_000;
+
+pub struct LidoStaking<P: JsonRpcClient = Http> {
+    network: String,
+    steth: Address,
+    queue: Address,
+    provider: Provider<P>,
+    chain_id: u64,
+}
+
+impl LidoStaking<Http> {
+    pub fn new(
+        network: &str,
+        config: &StakingProviderConfig,
+        rpc_url: &str,
+        chain_id: u64,
+    ) -> Result<Self> {
+        let provider = Provider::<Http>::try_from(rpc_url)
+            .with_context(|| format!("Invalid RPC URL '{}'", rpc_url))?;
+        Self::with_provider(network, config, provider, chain_id)
+    }
+}
+
+impl<P: JsonRpcClient + 'static> LidoStaking<P> {
+    pub fn with_provider(
+        network: &str,
+        config: &StakingProviderConfig,
+        provider: Provider<P>,
+        chain_id: u64,
+    ) -> Result<Self> {
+        let steth = config.steth.as_deref().unwrap_or(LIDO_MAINNET_STETH);
+        let queue = config.withdrawal_queue.as_deref().unwrap_or(LIDO_MAINNET_WITHDRAWAL_QUEUE);
+        Ok(Self {
+            network: network.to_string(),
+            steth: parse_address(steth)?,
+            queue: parse_address(queue)?,
+            provider,
+            chain_id,
+        })
+    }
+
+    async fn call(&self, to: Address, data: Bytes) -> Result<Bytes> {
+        let call = TransactionRequest::new().to(to).data(data);
+        Ok(self.provider.call(&call.into(), None).await?)
+    }
+
+    fn receipt(
+        &self,
+        tx_hash: String,
+        action: StakeAction,
+        position_id: &str,
+        amount: U256,
+        network_fee: U256,
+    ) -> StakeReceipt {
+        StakeReceipt {
+            tx_hash,
+            approval_tx_hash: None,
+            network: self.network.clone(),
+            protocol: StakingProtocol::Lido,
+            action,
+            position_id: position_id.to_string(),
+            validator: None,
+            amount: format_token_amount(amount, ETH_DECIMALS),
+            network_fee: format_token_amount(network_fee, ETH_DECIMALS),
+        }
+    }
+}
+
+#[async_trait]
+impl<P: JsonRpcClient + 'static> StakingProvider for LidoStaking<P> {
+    async fn stake(
+        &self,
+        amount: &str,
+        validator: Option<&str>,
+        wallet_data: &SecureWalletData,
+    ) -> Result<StakeReceipt> {
+        if validator.is_some() {
+            return Err(anyhow::anyhow!("Lido does not take a validator"));
+        }
+        let value = parse_token_amount(amount, ETH_DECIMALS)?;
+        if value.is_zero() {
+            return Err(anyhow::anyhow!("Stake amount must be greater than zero"));
+        }
+
+        let signer = SoftwareSigner::for_network(&wallet_data.encrypted_master_key, &self.network)?;
+        let from = address_from_public_key(&signer.public_key().await?)?;
+        let gas_price = self.provider.get_gas_price().await?;
+        let nonce = self.provider.get_transaction_count(from, None).await?;
+        let tx = TransactionRequest::new()
+            .from(from)
+            .to(self.steth)
+            .value(value)
+            .data(encode_submit(Address::zero()))
+            .gas(SUBMIT_GAS)
+            .gas_price(gas_price)
+            .nonce(nonce);
+        let tx_hash = send_with_signer(&self.provider, &signer, tx, self.chain_id)
+            .await
+            .context("Lido submit failed")?;
+        info!("Staked {} ETH with Lido on {} ({:?})", amount, self.network, tx_hash);
+        Ok(self.receipt(
+            format!("{:?}", tx_hash),
+            StakeAction::Stake,
+            STETH_POSITION,
+            value,
+            gas_price * U256::from(SUBMIT_GAS),
+        ))
+    }
+
+    async fn unstake(
+        &self,
+        position: &StakePosition,
+        amount: Option<&str>,
+        wallet_data: &SecureWalletData,
+    ) -> Result<StakeReceipt> {
+        if position.position_id != STETH_POSITION {
+            return Err(anyhow::anyhow!(
+                "Only the stETH balance can be unstaked, not {}",
+                position.position_id
+            ));
+        }
+        let balance = parse_token_amount(&position.balance, ETH_DECIMALS)?;
+        let value = match amount {
+            Some(amount) => parse_token_amount(amount, ETH_DECIMALS)?,
+            None => balance,
+        };
+        if value > balance {
+            return Err(anyhow::anyhow!(
+                "Cannot unstake {} stETH, balance is {}",
+                format_token_amount(value, ETH_DECIMALS),
+                position.balance
+            ));
+        }
+        let chunks = withdrawal_chunks(value)?;
+
+        let signer = SoftwareSigner::for_network(&wallet_data.encrypted_master_key, &self.network)?;
+        let from = address_from_public_key(&signer.public_key().await?)?;
+        let gas_price = self.provider.get_gas_price().await?;
+        let nonce = self.provider.get_transaction_count(from, None).await?;
+
+        let approve = TransactionRequest::new()
+            .from(from)
+            .to(self.steth)
+            .data(encode_approve(self.queue, value))
+            .gas(APPROVE_GAS)
+            .gas_price(gas_price)
+            .nonce(nonce);
+        let approval_hash = send_with_signer(&self.provider, &signer, approve, self.chain_id)
+            .await
+            .context("stETH approval failed")?;
+
+        let request_gas = REQUEST_GAS_BASE + REQUEST_GAS_EACH * chunks.len() as u64;
+        let request = TransactionRequest::new()
+            .from(from)
+            .to(self.queue)
+            .data(encode_request_withdrawals(&chunks, from))
+            .gas(request_gas)
+            .gas_price(gas_price)
+            .nonce(nonce + U256::one());
+        let tx_hash = send_with_signer(&self.provider, &signer, request, self.chain_id)
+            .await
+            .context("Lido withdrawal request failed")?;
+        info!(
+            "Requested withdrawal of {} stETH on {} in {} request(s) ({:?})",
+            format_token_amount(value, ETH_DECIMALS),
+            self.network,
+            chunks.len(),
+            tx_hash
+        );
+        Ok(StakeReceipt {
+            approval_tx_hash: Some(format!("{:?}", approval_hash)),
+            ..self.receipt(
+                format!("{:?}", tx_hash),
+                StakeAction::Unstake,
+                STETH_POSITION,
+                value,
+                gas_price * U256::from(APPROVE_GAS + request_gas),
+            )
+        })
+    }
+
+    async fn withdraw(
+        &self,
+        position: &StakePosition,
+        wallet_data: &SecureWalletData,
+    ) -> Result<StakeReceipt> {
+        let request_id = position
+            .position_id
+            .strip_prefix(WITHDRAWAL_PREFIX)
+            .and_then(|id| U256::from_dec_str(id).ok())
+            .ok_or_else(|| {
+                anyhow::anyhow!("{} is not a Lido withdrawal request", position.position_id)
+            })?;
+        if position.state != StakeState::Withdrawable {
+            return Err(anyhow::anyhow!("Withdrawal request {} is not finalized yet", request_id));
+        }
+
+        let signer = SoftwareSigner::for_network(&wallet_data.encrypted_master_key, &self.network)?;
+        let from = address_from_public_key(&signer.public_key().await?)?;
+        let gas_price = self.provider.get_gas_price().await?;
+        let nonce = self.provider.get_transaction_count(from, None).await?;
+        let tx = TransactionRequest::new()
+            .from(from)
+            .to(self.queue)
+            .data(encode_claim_withdrawal(request_id))
+            .gas(CLAIM_GAS)
+            .gas_price(gas_price)
+            .nonce(nonce);
+        let tx_hash = send_with_signer(&self.provider, &signer, tx, self.chain_id)
+            .await
+            .context("Lido withdrawal claim failed")?;
+        Ok(self.receipt(
+            format!("{:?}", tx_hash),
+            StakeAction::Withdraw,
+            &position.position_id,
+            parse_token_amount(&position.balance, ETH_DECIMALS)?,
+            gas_price * U256::from(CLAIM_GAS),
+        ))
+    }
+
+    async fn positions(&self, owner: &str) -> Result<Vec<StakePosition>> {
+        let owner = parse_address(owner)?;
+        let position = |position_id: String, balance: U256, state: StakeState| StakePosition {
+            network: self.network.clone(),
+            protocol: StakingProtocol::Lido,
+            position_id,
+            validator: None,
+            balance: format_token_amount(balance, ETH_DECIMALS),
+            state,
+            rewards: "0".to_string(),
+        };
+
+        let mut positions = Vec::new();
+        let balance = decode_uint(&self.call(self.steth, encode_balance_of(owner)).await?)?;
+        if !balance.is_zero() {
+            positions.push(position(STETH_POSITION.to_string(), balance, StakeState::Active));
+        }
+
+        let ids = decode_uint_array(
+            &self.call(self.queue, encode_get_withdrawal_requests(owner)).await?,
+        )?;
+        if ids.is_empty() {
+            return Ok(positions);
+        }
+        let statuses = decode_withdrawal_statuses(
+            &self.call(self.queue, encode_get_withdrawal_status(&ids)).await?,
+        )?;
+        for (id, status) in ids.into_iter().zip(statuses) {
+            if status.is_claimed {
+                continue;
+            }
+            let state = if status.is_finalized {
+                StakeState::Withdrawable
+            } else {
+                StakeState::Deactivating
+            };
+            positions.push(position(format!("{}{}", WITHDRAWAL_PREFIX, id), status.amount, state));
+        }
+        Ok(positions)
+    }
+}
+
+/// Splits `value` into withdrawal requests the queue accepts.
+fn withdrawal_chunks(value: U256) -> Result<Vec<U256>> {
+    let min = U256::from(MIN_WITHDRAWAL_WEI);
+    let max = U256::from(MAX_WITHDRAWAL_ETHER) * U256::exp10(ETH_DECIMALS as usize);
+    if value < min {
+        return Err(anyhow::anyhow!("Withdrawal must be at least {} wei", MIN_WITHDRAWAL_WEI));
+    }
+    let mut chunks = Vec::new();
+    let mut remaining = value;
+    while remaining > max {
+        chunks.push(max);
+        remaining -= max;
+    }
+    if remaining < min {
+        return Err(anyhow::anyhow!(
+            "Withdrawal would leave a request below the {} wei minimum",
+            MIN_WITHDRAWAL_WEI
+        ));
+    }
+    chunks.push(remaining);
+    Ok(chunks)
+}
+
+/// One entry of `getWithdrawalStatus`.
+#[derive(Debug)]
+struct WithdrawalStatus {
+    amount: U256,
+    is_finalized: bool,
+    is_claimed: bool,
+}
+
+fn parse_address(address: &str) -> Result<Address> {
+    Address::from_str(address).map_err(|e| anyhow::anyhow!("Invalid address '{}': {}", address, e))
+}
+
+fn decode_uint(out: &[u8]) -> Result<U256> {
+    abi::decode(&[ParamType::Uint(256)], out)
+        .context("Malformed uint256")?
+        .pop()
+        .and_then(Token::into_uint)
+        .ok_or_else(|| anyhow::anyhow!("Empty return data"))
+}
+
+fn decode_uint_array(out: &[u8]) -> Result<Vec<U256>> {
+    abi::decode(&[ParamType::Array(Box::new(ParamType::Uint(256)))], out)
+        .context("Malformed uint256 array")?
+        .pop()
+        .and_then(Token::into_array)
+        .ok_or_else(|| anyhow::anyhow!("Empty return data"))?
+        .into_iter()
+        .map(|t| t.into_uint().ok_or_else(|| anyhow::anyhow!("Non-integer request id")))
+        .collect()
+}
+
+/// Decodes `(amountOfStETH, amountOfShares, owner, timestamp, isFinalized,
+/// isClaimed)[]`.
+fn decode_withdrawal_statuses(out: &[u8]) -> Result<Vec<WithdrawalStatus>> {
+    let status = ParamType::Tuple(vec![
+        ParamType::Uint(256),
+        ParamType::Uint(256),
+        ParamType::Address,
+        ParamType::Uint(256),
+        ParamType::Bool,
+        ParamType::Bool,
+    ]);
+    abi::decode(&[ParamType::Array(Box::new(status))], out)
+        .context("Malformed withdrawal statuses")?
+        .pop()
+        .and_then(Token::into_array)
+        .ok_or_else(|| anyhow::anyhow!("Empty return data"))?
+        .into_iter()
+        .map(|entry| {
+            let fields = entry.into_tuple().unwrap_or_default();
+            match fields.as_slice() {
+                [Token::Uint(amount), _, _, _, Token::Bool(is_finalized), Token::Bool(is_claimed)] => {
+                    Ok(WithdrawalStatus {
+                        amount: *amount,
+                        is_finalized: *is_finalized,
+                        is_claimed: *is_claimed,
+                    })
+                }
+                _ => Err(anyhow::anyhow!("Malformed withdrawal status")),
+            }
+        })
+        .collect()
+}
+
+/// stETH `submit(referral)` calldata.
+pub fn encode_submit(referral: Address) -> Bytes {
+    let mut data = id("submit(address)").to_vec();
+    data.extend(abi::encode(&[Token::Address(referral)]));
+    data.into()
+}
+
+fn encode_balance_of(owner: Address) -> Bytes {
+    let mut data = id("balanceOf(address)").to_vec();
+    data.extend(abi::encode(&[Token::Address(owner)]));
+    data.into()
+}
+
+/// WithdrawalQueue `requestWithdrawals(amounts, owner)` calldata.
+pub fn encode_request_withdrawals(amounts: &[U256], owner: Address) -> Bytes {
+    let mut data = id("requestWithdrawals(uint256[],address)").to_vec();
+    data.extend(abi::encode(&[
+        Token::Array(amounts.iter().map(|a| Token::Uint(*a)).collect()),
+        Token::Address(owner),
+    ]));
+    data.into()
+}
+
+fn encode_get_withdrawal_requests(owner: Address) -> Bytes {
+    let mut data = id("getWithdrawalRequests(address)").to_vec();
+    data.extend(abi::encode(&[Token::Address(owner)]));
+    data.into()
+}
+
+fn encode_get_withdrawal_status(ids: &[U256]) -> Bytes {
+    let mut data = id("getWithdrawalStatus(uint256[])").to_vec();
+    data.extend(abi::encode(&[Token::Array(ids.iter().map(|i| Token::Uint(*i)).collect())]));
+    data.into()
+}
+
+/// WithdrawalQueue `claimWithdrawal(requestId)` calldata.
+pub fn encode_claim_withdrawal(request_id: U256) -> Bytes {
+    let mut data = id("claimWithdrawal(uint256)").to_vec();
+    data.extend(abi::encode(&[Token::Uint(request_id)]));
+    data.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::wallet_info::test_wallet;
+    use ethers::providers::MockProvider;
+    use ethers::types::H256;
+
+    const OWNER: &str = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
+
+    fn lido() -> (LidoStaking<MockProvider>, MockProvider) {
+        let (provider, mock) = Provider::mocked();
+        let config = StakingProviderConfig {
+            protocol: StakingProtocol::Lido,
+            steth: None,
+            withdrawal_queue: None,
+            default_validator: None,
+        };
+        (LidoStaking::with_provider("eth", &config, provider, 1).unwrap(), mock)
+    }
+
+    fn ether(amount: &str) -> U256 {
+        parse_token_amount(amount, ETH_DECIMALS).unwrap()
+    }
+
+    #[test]
+    fn test_withdrawal_chunks() {
+        assert_eq!(withdrawal_chunks(ether("1")).unwrap(), vec![ether("1")]);
+        assert_eq!(
+            withdrawal_chunks(ether("2500")).unwrap(),
+            vec![ether("1000"), ether("1000"), ether("500")]
+        );
+        assert!(withdrawal_chunks(U256::from(99)).is_err());
+        assert!(withdrawal_chunks(ether("1000") + U256::from(50)).is_err());
+        assert_eq!(hex::encode(&encode_submit(Address::zero())[..4]), "a1903eab");
+    }
+
+    #[tokio::test]
+    async fn test_stake_submits_eth() {
+        let (lido, mock) = lido();
+        let tx_hash = H256::repeat_byte(0x33);
+        // MockProvider answers in LIFO order: gas price, nonce, send
+        mock.push(tx_hash).unwrap();
+        mock.push(U256::from(3)).unwrap();
+        mock.push(U256::from(10_000_000_000u64)).unwrap();
+
+        let receipt = lido.stake("2", None, &test_wallet()).await.unwrap();
+        assert_eq!(receipt.tx_hash, format!("{:?}", tx_hash));
+        assert_eq!(receipt.position_id, STETH_POSITION);
+        assert_eq!(receipt.amount, "2");
+        assert_eq!(receipt.network_fee, "0.0015");
+
+        assert!(lido.stake("1", Some("validator"), &test_wallet()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_positions_include_unclaimed_requests() {
+        let (lido, mock) = lido();
+        let owner: Address = OWNER.parse().unwrap();
+        let status = |amount: &str, finalized: bool, claimed: bool| {
+            Token::Tuple(vec![
+                Token::Uint(ether(amount)),
+                Token::Uint(ether(amount)),
+                Token::Address(owner),
+                Token::Uint(1_700_000_000u64.into()),
+                Token::Bool(finalized),
+                Token::Bool(claimed),
+            ])
+        };
+        let statuses = abi::encode(&[Token::Array(vec![
+            status("1", true, false),
+            status("2", false, false),
+            status("3", true, true),
+        ])]);
+        let ids = abi::encode(&[Token::Array(
+            [7u64, 8, 9].iter().map(|i| Token::Uint((*i).into())).collect(),
+        )]);
+        // LIFO: balance, request ids, statuses
+        mock.push::<Bytes, _>(Bytes::from(statuses)).unwrap();
+        mock.push::<Bytes, _>(Bytes::from(ids)).unwrap();
+        mock.push::<Bytes, _>(Bytes::from(abi::encode(&[Token::Uint(ether("4.2"))]))).unwrap();
+
+        let positions = lido.positions(OWNER).await.unwrap();
+        assert_eq!(positions.len(), 3);
+        assert_eq!(positions[0].position_id, STETH_POSITION);
+        assert_eq!(positions[0].balance, "4.2");
+        assert_eq!(positions[1].position_id, "withdrawal:7");
+        assert_eq!(positions[1].state, StakeState::Withdrawable);
+        assert_eq!(positions[2].state, StakeState::Deactivating);
+
+        // only finalized requests can be claimed
+        assert!(lido.withdraw(&positions[2], &test_wallet()).await.is_err());
+        let err = lido.unstake(&positions[0], Some("5"), &test_wallet()).await.unwrap_err();
+        assert!(err.to_string().contains("balance is 4.2"));
+    }
+}
@@ -0,0 +1,182 @@
+// src/blockchain/staking/mod.rs
+//! Staking the native token of a network.
+//!
+//! A `StakingProvider` stakes, starts unstaking and withdraws positions for a
+//! wallet, and lists the positions it holds. Solana uses native stake accounts
+//! delegated to a vote account; Ethereum uses Lido, where staked ETH becomes
+//! stETH and unstaking goes through the withdrawal queue.
+use std::collections::HashMap;
+
+use anyhow::Result;
+use ethers::types::U256;
+use serde::{Deserialize, Serialize};
+
+use crate::blockchain::swap::{format_token_amount, parse_token_amount};
+use crate::blockchain::traits::StakingProvider;
+pub use crate::core::config::StakingProtocol;
+use crate::core::config::{BlockchainConfig, StakingConfig};
+
+pub mod lido;
+pub mod solana;
+
+pub use lido::LidoStaking;
+pub use solana::SolanaStaking;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StakeAction {
+    Stake,
+    /// Solana deactivation or a Lido withdrawal request.
+    Unstake,
+    Withdraw,
+}
+
+impl StakeAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StakeAction::Stake => "stake",
+            StakeAction::Unstake => "unstake",
+            StakeAction::Withdraw => "withdraw",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StakeState {
+    /// Delegated but not yet earning (Solana warm-up).
+    Activating,
+    Active,
+    /// Solana cool-down or a Lido withdrawal request awaiting finalization.
+    Deactivating,
+    /// Fully unstaked and ready to withdraw.
+    Withdrawable,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StakePosition {
+    pub network: String,
+    pub protocol: StakingProtocol,
+    /// Stake account address on Solana; `stETH` or `withdrawal:<request id>`
+    /// on Lido.
+    pub position_id: String,
+    pub validator: Option<String>,
+    /// Staked amount in the native token, excluding rent reserves.
+    pub balance: String,
+    pub state: StakeState,
+    /// Balance earned on top of what the wallet deposited, as far as the
+    /// wallet's own operation history can tell.
+    pub rewards: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StakeReceipt {
+    pub tx_hash: String,
+    /// Allowance transaction sent ahead of a Lido withdrawal request.
+    pub approval_tx_hash: Option<String>,
+    pub network: String,
+    pub protocol: StakingProtocol,
+    pub action: StakeAction,
+    pub position_id: String,
+    pub validator: Option<String>,
+    /// Amount staked, unstaked or withdrawn, in the native token.
+    pub amount: String,
+    pub network_fee: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StakingSummary {
+    pub wallet: String,
+    pub positions: Vec<StakePosition>,
+}
+
+/// Decimals of the token a protocol's amounts are expressed in.
+pub fn protocol_decimals(protocol: StakingProtocol) -> u8 {
+    match protocol {
+        StakingProtocol::SolanaNative => solana::SOL_DECIMALS,
+        StakingProtocol::Lido => lido::ETH_DECIMALS,
+    }
+}
+
+/// Rewards on a position: its balance plus whatever unstaking moved out of
+/// it, less what was deposited. Only Lido withdrawal requests move funds out;
+/// a deactivated Solana stake account keeps its balance until withdrawn.
+/// Positions the wallet never deposited into (such as Lido withdrawal
+/// requests) report zero.
+pub fn estimate_rewards(
+    protocol: StakingProtocol,
+    balance: &str,
+    deposited: &[String],
+    unstaked: &[String],
+) -> Result<String> {
+    let decimals = protocol_decimals(protocol);
+    if deposited.is_empty() {
+        return Ok("0".to_string());
+    }
+    let sum = |amounts: &[String]| -> Result<U256> {
+        amounts
+            .iter()
+            .try_fold(U256::zero(), |total, a| Ok(total + parse_token_amount(a, decimals)?))
+    };
+    let moved_out = match protocol {
+        StakingProtocol::Lido => sum(unstaked)?,
+        StakingProtocol::SolanaNative => U256::zero(),
+    };
+    let held = parse_token_amount(balance, decimals)? + moved_out;
+    Ok(format_token_amount(held.saturating_sub(sum(deposited)?), decimals))
+}
+
+/// Builds a provider for every network in `config`, using the RPC endpoints
+/// and chain ids from `blockchain`.
+pub fn staking_providers_from_config(
+    config: &StakingConfig,
+    blockchain: &BlockchainConfig,
+) -> Result<HashMap<String, Box<dyn StakingProvider>>> {
+    config.validate(blockchain)?;
+    let mut providers: HashMap<String, Box<dyn StakingProvider>> = HashMap::new();
+    for (network, provider) in &config.providers {
+        // validate() guarantees the network exists and matches the protocol
+        let network_config = &blockchain.networks[network];
+        let provider: Box<dyn StakingProvider> = match provider.protocol {
+            StakingProtocol::SolanaNative => Box::new(SolanaStaking::new(
+                network,
+                &network_config.rpc_url,
+                provider.default_validator.clone(),
+            )?),
+            StakingProtocol::Lido => Box::new(LidoStaking::new(
+                network,
+                provider,
+                &network_config.rpc_url,
+                network_config.chain_id.expect("validated EVM network"),
+            )?),
+        };
+        providers.insert(network.clone(), provider);
+    }
+    Ok(providers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_rewards() {
+        let amounts = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let lido = StakingProtocol::Lido;
+        assert_eq!(estimate_rewards(lido, "1.05", &amounts(&["1"]), &[]).unwrap(), "0.05");
+        // 0.5 already requested for withdrawal still counts as held
+        assert_eq!(
+            estimate_rewards(lido, "0.56", &amounts(&["1"]), &amounts(&["0.5"])).unwrap(),
+            "0.06"
+        );
+        // slashing or fees never show up as negative rewards
+        assert_eq!(estimate_rewards(lido, "0.9", &amounts(&["1"]), &[]).unwrap(), "0");
+        let solana = StakingProtocol::SolanaNative;
+        assert_eq!(estimate_rewards(solana, "5", &[], &[]).unwrap(), "0");
+        // deactivation leaves the balance in the stake account
+        assert_eq!(
+            estimate_rewards(solana, "2.1", &amounts(&["2"]), &amounts(&["2.1"])).unwrap(),
+            "0.1"
+        );
+    }
+}
@@ -0,0 +1,631 @@
+// src/blockchain/staking/solana.rs
+//! Native Solana staking through the stake program.
+//!
+//! Staking creates a fresh stake account derived from the wallet address with
+//! `create_with_seed`, initializes it with the wallet as both staker and
+//! withdrawer and delegates it to a vote account, all in one transaction.
+//! Unstaking deactivates the whole account; once the cool-down epoch has
+//! passed, withdrawing moves every lamport back to the wallet and closes it.
+//!
+//! Transactions are built as legacy messages signed only by the wallet, and
+//! sent with preflight enabled so the node simulates them before accepting.
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use base64::Engine;
+use chrono::Utc;
+use ethers::providers::{Http, JsonRpcClient};
+use ethers::types::U256;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tracing::info;
+
+use crate::blockchain::staking::{
+    StakeAction, StakePosition, StakeReceipt, StakeState, StakingProtocol,
+};
+use crate::blockchain::swap::{format_token_amount, parse_token_amount};
+use crate::blockchain::traits::{SignedTransaction, StakingProvider, UnsignedTransaction};
+use crate::core::wallet_info::SecureWalletData;
+use crate::crypto::signer::{Signer, SoftwareSigner};
+
+pub const SOL_DECIMALS: u8 = 9;
+/// Size of a stake account's state.
+const STAKE_ACCOUNT_SPACE: u64 = 200;
+const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+/// Byte offset of the withdraw authority in a stake account.
+const WITHDRAWER_OFFSET: usize = 44;
+
+const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
+const STAKE_PROGRAM: &str = "Stake11111111111111111111111111111111111111";
+const STAKE_CONFIG: &str = "StakeConfig11111111111111111111111111111111";
+const SYSVAR_RENT: &str = "SysvarRent111111111111111111111111111111111";
+const SYSVAR_CLOCK: &str = "SysvarC1ock11111111111111111111111111111111";
+const SYSVAR_STAKE_HISTORY: &str = "SysvarStakeHistory1111111111111111111111111";
+
+type Pubkey = [u8; 32];
+
+#[derive(Debug, Clone)]
+pub struct AccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl AccountMeta {
+    fn new(pubkey: Pubkey, is_signer: bool, is_writable: bool) -> Self {
+        Self { pubkey, is_signer, is_writable }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Instruction {
+    pub program_id: Pubkey,
+    pub accounts: Vec<AccountMeta>,
+    pub data: Vec<u8>,
+}
+
+pub struct SolanaStaking<C: JsonRpcClient = Http> {
+    network: String,
+    client: C,
+    default_validator: Option<String>,
+}
+
+impl SolanaStaking<Http> {
+    pub fn new(network: &str, rpc_url: &str, default_validator: Option<String>) -> Result<Self> {
+        let client =
+            Http::from_str(rpc_url).with_context(|| format!("Invalid RPC URL '{}'", rpc_url))?;
+        Ok(Self::with_client(network, client, default_validator))
+    }
+}
+
+impl<C: JsonRpcClient> SolanaStaking<C> {
+    pub fn with_client(network: &str, client: C, default_validator: Option<String>) -> Self {
+        Self { network: network.to_string(), client, default_validator }
+    }
+
+    async fn rpc(&self, method: &str, params: Value) -> Result<Value> {
+        self.client
+            .request::<_, Value>(method, params)
+            .await
+            .map_err(|e| anyhow::anyhow!("Solana RPC {} failed: {}", method, e))
+    }
+
+    async fn latest_blockhash(&self) -> Result<Pubkey> {
+        let result = self.rpc("getLatestBlockhash", json!([{ "commitment": "finalized" }])).await?;
+        let blockhash = result["value"]["blockhash"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("getLatestBlockhash returned no blockhash"))?;
+        parse_pubkey(blockhash)
+    }
+
+    /// Signs a message paid for by the wallet and submits it.
+    async fn send(&self, signer: &dyn Signer, instructions: &[Instruction]) -> Result<String> {
+        let payer = wallet_pubkey(signer).await?;
+        let blockhash = self.latest_blockhash().await?;
+        let message = compile_message(payer, instructions, blockhash);
+        let signed = signer
+            .sign_transaction(&UnsignedTransaction::Solana { message: message.into() })
+            .await?;
+        let SignedTransaction::Solana { message, signature } = signed else {
+            return Err(anyhow::anyhow!("Signer returned a non-Solana transaction"));
+        };
+        let mut wire = Vec::with_capacity(1 + signature.len() + message.len());
+        push_compact_u16(&mut wire, 1);
+        wire.extend_from_slice(&signature);
+        wire.extend_from_slice(&message);
+        let encoded = base64::engine::general_purpose::STANDARD.encode(wire);
+        let result =
+            self.rpc("sendTransaction", json!([encoded, { "encoding": "base64" }])).await?;
+        result
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("sendTransaction returned no signature"))
+    }
+
+    fn receipt(
+        &self,
+        tx_hash: String,
+        action: StakeAction,
+        position_id: String,
+        validator: Option<String>,
+        amount: String,
+    ) -> StakeReceipt {
+        StakeReceipt {
+            tx_hash,
+            approval_tx_hash: None,
+            network: self.network.clone(),
+            protocol: StakingProtocol::SolanaNative,
+            action,
+            position_id,
+            validator,
+            amount,
+            network_fee: format_token_amount(LAMPORTS_PER_SIGNATURE.into(), SOL_DECIMALS),
+        }
+    }
+}
+
+#[async_trait]
+impl<C: JsonRpcClient + 'static> StakingProvider for SolanaStaking<C> {
+    async fn stake(
+        &self,
+        amount: &str,
+        validator: Option<&str>,
+        wallet_data: &SecureWalletData,
+    ) -> Result<StakeReceipt> {
+        let validator =
+            validator.map(str::to_string).or_else(|| self.default_validator.clone()).ok_or_else(
+                || anyhow::anyhow!("No validator given and none configured for {}", self.network),
+            )?;
+        let vote_account = parse_pubkey(&validator)?;
+        let lamports = parse_lamports(amount)?;
+        if lamports == 0 {
+            return Err(anyhow::anyhow!("Stake amount must be greater than zero"));
+        }
+
+        let signer = SoftwareSigner::for_network(&wallet_data.encrypted_master_key, &self.network)?;
+        let owner = wallet_pubkey(&signer).await?;
+        let rent = self
+            .rpc("getMinimumBalanceForRentExemption", json!([STAKE_ACCOUNT_SPACE]))
+            .await?
+            .as_u64()
+            .ok_or_else(|| {
+                anyhow::anyhow!("getMinimumBalanceForRentExemption returned no amount")
+            })?;
+
+        let seed = format!("stake:{}", Utc::now().timestamp_millis());
+        let stake_account = create_with_seed(&owner, &seed, &parse_pubkey(STAKE_PROGRAM)?);
+        let instructions = [
+            create_account_with_seed_instruction(
+                owner,
+                stake_account,
+                &seed,
+                lamports + rent,
+                STAKE_ACCOUNT_SPACE,
+            )?,
+            initialize_instruction(stake_account, owner)?,
+            delegate_instruction(stake_account, vote_account, owner)?,
+        ];
+        let signature =
+            self.send(&signer, &instructions).await.context("Stake submission failed")?;
+        let position_id = bs58::encode(stake_account).into_string();
+        info!("Staked {} SOL on {} into {} ({})", amount, self.network, position_id, signature);
+        Ok(self.receipt(
+            signature,
+            StakeAction::Stake,
+            position_id,
+            Some(validator),
+            amount.to_string(),
+        ))
+    }
+
+    async fn unstake(
+        &self,
+        position: &StakePosition,
+        amount: Option<&str>,
+        wallet_data: &SecureWalletData,
+    ) -> Result<StakeReceipt> {
+        if !matches!(position.state, StakeState::Activating | StakeState::Active) {
+            return Err(anyhow::anyhow!("Stake account {} is not active", position.position_id));
+        }
+        if let Some(amount) = amount {
+            if parse_lamports(amount)? != parse_lamports(&position.balance)? {
+                return Err(anyhow::anyhow!(
+                    "Solana stake accounts are deactivated in full; {} holds {} SOL",
+                    position.position_id,
+                    position.balance
+                ));
+            }
+        }
+
+        let signer = SoftwareSigner::for_network(&wallet_data.encrypted_master_key, &self.network)?;
+        let owner = wallet_pubkey(&signer).await?;
+        let stake_account = parse_pubkey(&position.position_id)?;
+        let instructions = [deactivate_instruction(stake_account, owner)?];
+        let signature =
+            self.send(&signer, &instructions).await.context("Stake deactivation failed")?;
+        Ok(self.receipt(
+            signature,
+            StakeAction::Unstake,
+            position.position_id.clone(),
+            position.validator.clone(),
+            position.balance.clone(),
+        ))
+    }
+
+    async fn withdraw(
+        &self,
+        position: &StakePosition,
+        wallet_data: &SecureWalletData,
+    ) -> Result<StakeReceipt> {
+        if position.state != StakeState::Withdrawable {
+            return Err(anyhow::anyhow!(
+                "Stake account {} is not yet withdrawable",
+                position.position_id
+            ));
+        }
+
+        let signer = SoftwareSigner::for_network(&wallet_data.encrypted_master_key, &self.network)?;
+        let owner = wallet_pubkey(&signer).await?;
+        let stake_account = parse_pubkey(&position.position_id)?;
+        // withdraw the rent reserve too so the account is closed
+        let lamports = self.rpc("getBalance", json!([position.position_id])).await?["value"]
+            .as_u64()
+            .ok_or_else(|| anyhow::anyhow!("getBalance returned no amount"))?;
+        let instructions = [withdraw_instruction(stake_account, owner, lamports)?];
+        let signature =
+            self.send(&signer, &instructions).await.context("Stake withdrawal failed")?;
+        Ok(self.receipt(
+            signature,
+            StakeAction::Withdraw,
+            position.position_id.clone(),
+            position.validator.clone(),
+            format_token_amount(lamports.into(), SOL_DECIMALS),
+        ))
+    }
+
+    async fn positions(&self, owner: &str) -> Result<Vec<StakePosition>> {
+        parse_pubkey(owner)?;
+        let epoch = self.rpc("getEpochInfo", json!([])).await?["epoch"]
+            .as_u64()
+            .ok_or_else(|| anyhow::anyhow!("getEpochInfo returned no epoch"))?;
+        let accounts = self
+            .rpc(
+                "getProgramAccounts",
+                json!([STAKE_PROGRAM, {
+                    "encoding": "jsonParsed",
+                    "filters": [{ "memcmp": { "offset": WITHDRAWER_OFFSET, "bytes": owner } }],
+                }]),
+            )
+            .await?;
+        accounts
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("getProgramAccounts returned no accounts"))?
+            .iter()
+            .map(|account| self.parse_position(account, epoch))
+            .collect()
+    }
+}
+
+impl<C: JsonRpcClient> SolanaStaking<C> {
+    fn parse_position(&self, account: &Value, epoch: u64) -> Result<StakePosition> {
+        let position_id = account["pubkey"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Stake account without a pubkey"))?
+            .to_string();
+        let lamports = json_u64(&account["account"]["lamports"])
+            .ok_or_else(|| anyhow::anyhow!("Stake account {} has no lamports", position_id))?;
+        let info = &account["account"]["data"]["parsed"]["info"];
+        let reserve = json_u64(&info["meta"]["rentExemptReserve"]).unwrap_or(0);
+        let delegation = &info["stake"]["delegation"];
+
+        let (validator, state) = match json_u64(&delegation["activationEpoch"]) {
+            Some(activation) => {
+                let deactivation = json_u64(&delegation["deactivationEpoch"]).unwrap_or(u64::MAX);
+                let state = if deactivation == u64::MAX {
+                    if activation >= epoch {
+                        StakeState::Activating
+                    } else {
+                        StakeState::Active
+                    }
+                } else if deactivation >= epoch {
+                    StakeState::Deactivating
+                } else {
+                    StakeState::Withdrawable
+                };
+                (delegation["voter"].as_str().map(str::to_string), state)
+            }
+            // initialized but never delegated
+            None => (None, StakeState::Withdrawable),
+        };
+
+        Ok(StakePosition {
+            network: self.network.clone(),
+            protocol: StakingProtocol::SolanaNative,
+            position_id,
+            validator,
+            balance: format_token_amount(lamports.saturating_sub(reserve).into(), SOL_DECIMALS),
+            state,
+            rewards: "0".to_string(),
+        })
+    }
+}
+
+/// Serializes a legacy message paid for and signed by `payer` alone.
+pub fn compile_message(
+    payer: Pubkey,
+    instructions: &[Instruction],
+    recent_blockhash: Pubkey,
+) -> Vec<u8> {
+    let mut keys: Vec<AccountMeta> = vec![AccountMeta::new(payer, true, true)];
+    let mut merge = |meta: AccountMeta| match keys.iter_mut().find(|k| k.pubkey == meta.pubkey) {
+        Some(key) => {
+            key.is_signer |= meta.is_signer;
+            key.is_writable |= meta.is_writable;
+        }
+        None => keys.push(meta),
+    };
+    for instruction in instructions {
+        for meta in &instruction.accounts {
+            merge(meta.clone());
+        }
+        merge(AccountMeta::new(instruction.program_id, false, false));
+    }
+    // signers first, writable before read-only within each group; the sort is
+    // stable so the payer stays first
+    keys.sort_by_key(|k| (!k.is_signer, !k.is_writable));
+
+    let signers = keys.iter().filter(|k| k.is_signer).count();
+    let readonly_signed = keys.iter().filter(|k| k.is_signer && !k.is_writable).count();
+    let readonly_unsigned = keys.iter().filter(|k| !k.is_signer && !k.is_writable).count();
+    let index = |pubkey: &Pubkey| keys.iter().position(|k| &k.pubkey == pubkey).unwrap_or(0) as u8;
+
+    let mut message = vec![signers as u8, readonly_signed as u8, readonly_unsigned as u8];
+    push_compact_u16(&mut message, keys.len());
+    for key in &keys {
+        message.extend_from_slice(&key.pubkey);
+    }
+    message.extend_from_slice(&recent_blockhash);
+    push_compact_u16(&mut message, instructions.len());
+    for instruction in instructions {
+        message.push(index(&instruction.program_id));
+        push_compact_u16(&mut message, instruction.accounts.len());
+        message.extend(instruction.accounts.iter().map(|meta| index(&meta.pubkey)));
+        push_compact_u16(&mut message, instruction.data.len());
+        message.extend_from_slice(&instruction.data);
+    }
+    message
+}
+
+/// Solana's variable-length `compact-u16` length prefix.
+pub fn push_compact_u16(out: &mut Vec<u8>, mut value: usize) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+/// Address of the account `base` creates for `owner` with `seed`.
+pub fn create_with_seed(base: &Pubkey, seed: &str, owner: &Pubkey) -> Pubkey {
+    let mut hasher = Sha256::new();
+    hasher.update(base);
+    hasher.update(seed.as_bytes());
+    hasher.update(owner);
+    hasher.finalize().into()
+}
+
+/// System `CreateAccountWithSeed` funded by and based on `owner`.
+fn create_account_with_seed_instruction(
+    owner: Pubkey,
+    account: Pubkey,
+    seed: &str,
+    lamports: u64,
+    space: u64,
+) -> Result<Instruction> {
+    let mut data = 3u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&owner);
+    data.extend_from_slice(&(seed.len() as u64).to_le_bytes());
+    data.extend_from_slice(seed.as_bytes());
+    data.extend_from_slice(&lamports.to_le_bytes());
+    data.extend_from_slice(&space.to_le_bytes());
+    data.extend_from_slice(&parse_pubkey(STAKE_PROGRAM)?);
+    Ok(Instruction {
+        program_id: parse_pubkey(SYSTEM_PROGRAM)?,
+        accounts: vec![
+            AccountMeta::new(owner, true, true),
+            AccountMeta::new(account, false, true),
+            AccountMeta::new(owner, true, false),
+        ],
+        data,
+    })
+}
+
+/// Stake `Initialize` with `authority` as staker and withdrawer and no lockup.
+fn initialize_instruction(stake_account: Pubkey, authority: Pubkey) -> Result<Instruction> {
+    let mut data = 0u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&authority);
+    data.extend_from_slice(&authority);
+    // lockup: unix timestamp, epoch, custodian
+    data.extend_from_slice(&0i64.to_le_bytes());
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.extend_from_slice(&[0u8; 32]);
+    Ok(Instruction {
+        program_id: parse_pubkey(STAKE_PROGRAM)?,
+        accounts: vec![
+            AccountMeta::new(stake_account, false, true),
+            AccountMeta::new(parse_pubkey(SYSVAR_RENT)?, false, false),
+        ],
+        data,
+    })
+}
+
+fn delegate_instruction(
+    stake_account: Pubkey,
+    vote_account: Pubkey,
+    authority: Pubkey,
+) -> Result<Instruction> {
+    Ok(Instruction {
+        program_id: parse_pubkey(STAKE_PROGRAM)?,
+        accounts: vec![
+            AccountMeta::new(stake_account, false, true),
+            AccountMeta::new(vote_account, false, false),
+            AccountMeta::new(parse_pubkey(SYSVAR_CLOCK)?, false, false),
+            AccountMeta::new(parse_pubkey(SYSVAR_STAKE_HISTORY)?, false, false),
+            AccountMeta::new(parse_pubkey(STAKE_CONFIG)?, false, false),
+            AccountMeta::new(authority, true, false),
+        ],
+        data: 2u32.to_le_bytes().to_vec(),
+    })
+}
+
+fn deactivate_instruction(stake_account: Pubkey, authority: Pubkey) -> Result<Instruction> {
+    Ok(Instruction {
+        program_id: parse_pubkey(STAKE_PROGRAM)?,
+        accounts: vec![
+            AccountMeta::new(stake_account, false, true),
+            AccountMeta::new(parse_pubkey(SYSVAR_CLOCK)?, false, false),
+            AccountMeta::new(authority, true, false),
+        ],
+        data: 5u32.to_le_bytes().to_vec(),
+    })
+}
+
+/// Stake `Withdraw` of `lamports` back to `authority`.
+fn withdraw_instruction(
+    stake_account: Pubkey,
+    authority: Pubkey,
+    lamports: u64,
+) -> Result<Instruction> {
+    let mut data = 4u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&lamports.to_le_bytes());
+    Ok(Instruction {
+        program_id: parse_pubkey(STAKE_PROGRAM)?,
+        accounts: vec![
+            AccountMeta::new(stake_account, false, true),
+            AccountMeta::new(authority, false, true),
+            AccountMeta::new(parse_pubkey(SYSVAR_CLOCK)?, false, false),
+            AccountMeta::new(parse_pubkey(SYSVAR_STAKE_HISTORY)?, false, false),
+            AccountMeta::new(authority, true, false),
+        ],
+        data,
+    })
+}
+
+async fn wallet_pubkey(signer: &dyn Signer) -> Result<Pubkey> {
+    let public_key = signer.public_key().await?;
+    <Pubkey>::try_from(public_key.as_slice())
+        .map_err(|_| anyhow::anyhow!("Solana staking requires an Ed25519 key"))
+}
+
+fn parse_pubkey(address: &str) -> Result<Pubkey> {
+    let bytes = bs58::decode(address)
+        .into_vec()
+        .map_err(|e| anyhow::anyhow!("Invalid Solana address '{}': {}", address, e))?;
+    <Pubkey>::try_from(bytes.as_slice())
+        .map_err(|_| anyhow::anyhow!("Invalid Solana address '{}'", address))
+}
+
+fn parse_lamports(amount: &str) -> Result<u64> {
+    let lamports = parse_token_amount(amount, SOL_DECIMALS)?;
+    if lamports > U256::from(u64::MAX) {
+        return Err(anyhow::anyhow!("Amount {} SOL is too large", amount));
+    }
+    Ok(lamports.as_u64())
+}
+
+/// RPC nodes return some u64 fields as strings and others as numbers.
+fn json_u64(value: &Value) -> Option<u64> {
+    value.as_u64().or_else(|| value.as_str()?.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::wallet_info::test_wallet;
+    use ethers::providers::MockProvider;
+
+    const VOTE: &str = "Vote111111111111111111111111111111111111111";
+
+    fn staking() -> (SolanaStaking<MockProvider>, MockProvider) {
+        let mock = MockProvider::new();
+        (SolanaStaking::with_client("solana", mock.clone(), Some(VOTE.to_string())), mock)
+    }
+
+    fn blockhash() -> Value {
+        json!({ "context": { "slot": 1 }, "value": { "blockhash": VOTE, "lastValidBlockHeight": 10 } })
+    }
+
+    #[test]
+    fn test_message_encoding() {
+        let mut out = Vec::new();
+        push_compact_u16(&mut out, 0x7f);
+        push_compact_u16(&mut out, 300);
+        assert_eq!(out, vec![0x7f, 0xac, 0x02]);
+
+        let owner = [1u8; 32];
+        let stake_account = [2u8; 32];
+        let deactivate = deactivate_instruction(stake_account, owner).unwrap();
+        let message = compile_message(owner, &[deactivate], [9u8; 32]);
+        // one signer, no read-only signer, clock and stake program read-only
+        assert_eq!(&message[..3], &[1, 0, 2]);
+        assert_eq!(message[3], 4);
+        assert_eq!(&message[4..36], &owner);
+        assert_eq!(&message[36..68], &stake_account);
+        // blockhash, one instruction: program 3, accounts [1, 2, 0], data 5u32
+        let ix = &message[4 + 4 * 32 + 32..];
+        assert_eq!(ix, &[1, 3, 3, 1, 2, 0, 4, 5, 0, 0, 0]);
+    }
+
+    #[tokio::test]
+    async fn test_stake_creates_and_delegates_account() {
+        let (staking, mock) = staking();
+        // MockProvider answers in LIFO order: rent, blockhash, send
+        mock.push(json!("5sig")).unwrap();
+        mock.push(blockhash()).unwrap();
+        mock.push(json!(2_282_880u64)).unwrap();
+
+        let receipt = staking.stake("1.5", None, &test_wallet()).await.unwrap();
+        assert_eq!(receipt.tx_hash, "5sig");
+        assert_eq!(receipt.action, StakeAction::Stake);
+        assert_eq!(receipt.validator.as_deref(), Some(VOTE));
+        assert_eq!(receipt.amount, "1.5");
+        assert_eq!(receipt.network_fee, "0.000005");
+        assert!(parse_pubkey(&receipt.position_id).is_ok());
+
+        assert!(staking.stake("0", None, &test_wallet()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_positions_follow_epochs() {
+        let (staking, mock) = staking();
+        let account = |pubkey: &str, activation: &str, deactivation: &str| {
+            json!({
+                "pubkey": pubkey,
+                "account": {
+                    "lamports": 1_002_282_880u64,
+                    "data": { "program": "stake", "parsed": { "type": "delegated", "info": {
+                        "meta": { "rentExemptReserve": "2282880" },
+                        "stake": { "delegation": {
+                            "voter": VOTE,
+                            "activationEpoch": activation,
+                            "deactivationEpoch": deactivation,
+                        } },
+                    } } },
+                },
+            })
+        };
+        let max = u64::MAX.to_string();
+        let accounts = json!([
+            account(STAKE_CONFIG, "500", &max),
+            account(SYSVAR_RENT, "400", &max),
+            account(SYSVAR_CLOCK, "400", "500"),
+            account(SYSVAR_STAKE_HISTORY, "400", "450"),
+        ]);
+        mock.push(accounts).unwrap();
+        mock.push(json!({ "epoch": 500, "slotIndex": 0 })).unwrap();
+
+        let owner = bs58::encode([1u8; 32]).into_string();
+        let positions = staking.positions(&owner).await.unwrap();
+        let states: Vec<_> = positions.iter().map(|p| p.state).collect();
+        assert_eq!(
+            states,
+            vec![
+                StakeState::Activating,
+                StakeState::Active,
+                StakeState::Deactivating,
+                StakeState::Withdrawable
+            ]
+        );
+        assert_eq!(positions[0].balance, "1");
+        assert_eq!(positions[0].validator.as_deref(), Some(VOTE));
+
+        // deactivation is all-or-nothing
+        assert!(staking.unstake(&positions[1], Some("0.5"), &test_wallet()).await.is_err());
+        assert!(staking.withdraw(&positions[1], &test_wallet()).await.is_err());
+    }
+}
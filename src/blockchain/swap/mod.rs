@@ -0,0 +1,146 @@
+// src/blockchain/swap/mod.rs
+//! Token swaps through on-chain DEX routers.
+//!
+//! A `SwapRouter` prices a swap for a wallet (`quote_swap`) and executes it
+//! (`execute_swap`): approve the router for the input token if the allowance
+//! is short, simulate the swap with `eth_call`, then submit it with the
+//! minimum output derived from the slippage tolerance and a deadline.
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use ethers::types::U256;
+use ethers::utils::{format_units, parse_units, ParseUnits};
+use serde::{Deserialize, Serialize};
+
+use crate::blockchain::simulation::SimulationResult;
+
+pub use crate::core::config::SwapProtocol;
+
+pub mod uniswap;
+
+pub use uniswap::{swap_routers_from_config, UniswapRouter};
+
+/// Slippage tolerance used when a request does not give one (0.5%).
+pub const DEFAULT_SLIPPAGE_BPS: u32 = 50;
+/// Anything above this (50%) is almost certainly a mistake.
+pub const MAX_SLIPPAGE_BPS: u32 = 5_000;
+/// Time a submitted swap stays valid when a request does not give one.
+pub const DEFAULT_DEADLINE_SECONDS: u64 = 20 * 60;
+
+/// Sell exactly `amount_in` of `token_in` for `token_out`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SwapParams {
+    pub token_in: String,
+    pub token_out: String,
+    pub amount_in: String,
+    pub slippage_bps: u32,
+    /// Seconds from submission after which the router rejects the swap.
+    pub deadline_seconds: u64,
+}
+
+impl SwapParams {
+    pub fn new(token_in: &str, token_out: &str, amount_in: &str) -> Self {
+        Self {
+            token_in: token_in.to_string(),
+            token_out: token_out.to_string(),
+            amount_in: amount_in.to_string(),
+            slippage_bps: DEFAULT_SLIPPAGE_BPS,
+            deadline_seconds: DEFAULT_DEADLINE_SECONDS,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.token_in == self.token_out {
+            return Err(anyhow::anyhow!("Cannot swap {} for itself", self.token_in));
+        }
+        if self.slippage_bps > MAX_SLIPPAGE_BPS {
+            return Err(anyhow::anyhow!(
+                "Slippage tolerance {} bps exceeds the maximum of {} bps",
+                self.slippage_bps,
+                MAX_SLIPPAGE_BPS
+            ));
+        }
+        if self.deadline_seconds == 0 {
+            return Err(anyhow::anyhow!("Swap deadline must be in the future"));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SwapQuote {
+    pub network: String,
+    pub protocol: SwapProtocol,
+    /// Router contract the swap is sent to.
+    pub router: String,
+    pub token_in: String,
+    pub token_out: String,
+    pub amount_in: String,
+    /// Output at the current pool price.
+    pub amount_out: String,
+    /// Output below which the swap reverts, after slippage.
+    pub amount_out_min: String,
+    pub slippage_bps: u32,
+    /// V3 pool fee tier; `None` for V2 routers.
+    pub fee_tier: Option<u32>,
+    /// Whether the router must first be approved to spend `amount_in`.
+    pub approval_required: bool,
+    /// Gas for the approval (if needed) and the swap, in the native token.
+    pub network_fee: String,
+    pub deadline: DateTime<Utc>,
+}
+
+/// A submitted swap and the quote it was submitted at.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SwapReceipt {
+    pub tx_hash: String,
+    pub approval_tx_hash: Option<String>,
+    pub quote: SwapQuote,
+    /// Pre-flight simulation of the swap transaction; `None` when the swap
+    /// follows an approval sent in the same call, which it cannot be
+    /// simulated without.
+    pub simulation: Option<SimulationResult>,
+}
+
+/// `amount_out` less `slippage_bps`, rounded down.
+pub fn min_amount_out(amount_out: U256, slippage_bps: u32) -> U256 {
+    amount_out * U256::from(10_000 - slippage_bps.min(10_000)) / U256::from(10_000u64)
+}
+
+/// Parses a non-negative decimal amount of a token with `decimals` places.
+pub fn parse_token_amount(amount: &str, decimals: u8) -> Result<U256> {
+    match parse_units(amount.trim(), decimals as u32) {
+        Ok(ParseUnits::U256(value)) => Ok(value),
+        Ok(ParseUnits::I256(_)) => Err(anyhow::anyhow!("Amount must not be negative: {}", amount)),
+        Err(e) => Err(anyhow::anyhow!("Invalid amount '{}': {}", amount, e)),
+    }
+}
+
+/// Formats a raw token amount without trailing zeros.
+pub fn format_token_amount(value: U256, decimals: u8) -> String {
+    let formatted = format_units(value, decimals as u32).unwrap_or_else(|_| value.to_string());
+    match formatted.split_once('.') {
+        Some((whole, fraction)) if fraction.trim_end_matches('0').is_empty() => whole.to_string(),
+        Some((whole, fraction)) => format!("{}.{}", whole, fraction.trim_end_matches('0')),
+        None => formatted,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slippage_and_amounts() {
+        let out = parse_token_amount("1000", 6).unwrap();
+        assert_eq!(format_token_amount(min_amount_out(out, 50), 6), "995");
+        assert_eq!(min_amount_out(out, 0), out);
+        assert_eq!(format_token_amount(parse_token_amount("0.25", 18).unwrap(), 18), "0.25");
+        assert!(parse_token_amount("-1", 6).is_err());
+
+        let mut params = SwapParams::new("WETH", "USDC", "1");
+        assert!(params.validate().is_ok());
+        params.slippage_bps = MAX_SLIPPAGE_BPS + 1;
+        assert!(params.validate().is_err());
+        assert!(SwapParams::new("USDC", "USDC", "1").validate().is_err());
+    }
+}
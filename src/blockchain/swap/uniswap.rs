@@ -0,0 +1,634 @@
+// src/blockchain/swap/uniswap.rs
+//! Uniswap V2 and V3 router adapter for EVM networks.
+//!
+//! V2 routers quote with `getAmountsOut` and swap with
+//! `swapExactTokensForTokens` over a direct `[token_in, token_out]` path. V3
+//! quotes come from QuoterV2 `quoteExactInputSingle` and swaps go through
+//! SwapRouter `exactInputSingle` in the configured fee tier. Both take a
+//! deadline and a minimum output, so a swap that lands late or after the price
+//! moved past the slippage tolerance reverts instead of filling.
+//!
+//! The router is approved for exactly the amount being sold, never an
+//! unlimited allowance. The approval and the swap are sent back to back with
+//! consecutive nonces; the swap is simulated first only when the allowance is
+//! already in place, since it would revert against state without the approval.
+//! Either way its minimum output and deadline bound it on-chain.
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use ethers::abi::{self, ParamType, Token};
+use ethers::providers::{Http, JsonRpcClient, Middleware, Provider};
+use ethers::types::{Address, Bytes, TransactionRequest, H256, U256};
+use ethers::utils::id;
+use tracing::info;
+
+use crate::blockchain::bridge::evm::encode_approve;
+use crate::blockchain::ethereum::{address_from_public_key, send_with_signer};
+use crate::blockchain::simulation::{simulate_evm, SimulationResult};
+use crate::blockchain::swap::{
+    format_token_amount, min_amount_out, parse_token_amount, SwapParams, SwapQuote, SwapReceipt,
+};
+use crate::blockchain::traits::SwapRouter;
+use crate::core::config::{BlockchainConfig, SwapConfig, SwapProtocol, SwapRouterConfig};
+use crate::core::wallet_info::SecureWalletData;
+use crate::crypto::signer::{Signer, SoftwareSigner};
+
+const APPROVE_GAS: u64 = 60_000;
+/// Gas limit for a single-pool swap on either router version.
+const SWAP_GAS: u64 = 300_000;
+const NATIVE_DECIMALS: u8 = 18;
+
+/// A swap priced and ready to be signed.
+struct PreparedSwap {
+    quote: SwapQuote,
+    token_in: Address,
+    token_out: Address,
+    amount_in: U256,
+    amount_out_min: U256,
+    deadline: U256,
+    gas_price: U256,
+}
+
+pub struct UniswapRouter<P: JsonRpcClient = Http> {
+    network: String,
+    /// Symbol of the network's native token, for simulated balance changes.
+    native_token: String,
+    config: SwapRouterConfig,
+    provider: Provider<P>,
+    chain_id: u64,
+}
+
+impl UniswapRouter<Http> {
+    pub fn new(
+        network: &str,
+        native_token: &str,
+        config: SwapRouterConfig,
+        rpc_url: &str,
+        chain_id: u64,
+    ) -> Result<Self> {
+        let provider = Provider::<Http>::try_from(rpc_url)
+            .with_context(|| format!("Invalid RPC URL '{}'", rpc_url))?;
+        Ok(Self::with_provider(network, native_token, config, provider, chain_id))
+    }
+}
+
+impl<P: JsonRpcClient + 'static> UniswapRouter<P> {
+    pub fn with_provider(
+        network: &str,
+        native_token: &str,
+        config: SwapRouterConfig,
+        provider: Provider<P>,
+        chain_id: u64,
+    ) -> Self {
+        Self {
+            network: network.to_string(),
+            native_token: native_token.to_string(),
+            config,
+            provider,
+            chain_id,
+        }
+    }
+
+    fn token(&self, symbol: &str) -> Result<(Address, u8)> {
+        let token = self.config.tokens.get(symbol).ok_or_else(|| {
+            anyhow::anyhow!("Token {} is not swappable on {}", symbol, self.network)
+        })?;
+        Ok((parse_address(&token.address)?, token.decimals))
+    }
+
+    async fn call(&self, to: Address, data: Bytes) -> Result<Bytes> {
+        let call = TransactionRequest::new().to(to).data(data);
+        Ok(self.provider.call(&call.into(), None).await?)
+    }
+
+    /// Output for selling `amount_in` at the current pool price.
+    async fn amount_out(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+    ) -> Result<U256> {
+        match self.config.protocol {
+            SwapProtocol::UniswapV2 => {
+                let router = parse_address(&self.config.router)?;
+                let out = self
+                    .call(router, encode_get_amounts_out(amount_in, token_in, token_out))
+                    .await
+                    .context("Router quote failed")?;
+                decode_last_amount(&out)
+            }
+            SwapProtocol::UniswapV3 => {
+                let quoter = self.config.quoter.as_deref().ok_or_else(|| {
+                    anyhow::anyhow!("Uniswap V3 router on {} has no quoter", self.network)
+                })?;
+                let data = encode_quote_exact_input_single(
+                    token_in,
+                    token_out,
+                    amount_in,
+                    self.config.fee_tier,
+                );
+                let out =
+                    self.call(parse_address(quoter)?, data).await.context("Quoter call failed")?;
+                decode_first_word(&out)
+            }
+        }
+    }
+
+    async fn allowance(&self, token: Address, owner: Address) -> Result<U256> {
+        let router = parse_address(&self.config.router)?;
+        let mut data = id("allowance(address,address)").to_vec();
+        data.extend(abi::encode(&[Token::Address(owner), Token::Address(router)]));
+        decode_first_word(&self.call(token, data.into()).await?)
+    }
+
+    async fn prepare(&self, owner: Address, params: &SwapParams) -> Result<PreparedSwap> {
+        params.validate()?;
+        let (token_in, in_decimals) = self.token(&params.token_in)?;
+        let (token_out, out_decimals) = self.token(&params.token_out)?;
+        let amount_in = parse_token_amount(&params.amount_in, in_decimals)?;
+        if amount_in.is_zero() {
+            return Err(anyhow::anyhow!("Swap amount must be greater than zero"));
+        }
+
+        let amount_out = self.amount_out(token_in, token_out, amount_in).await?;
+        if amount_out.is_zero() {
+            return Err(anyhow::anyhow!(
+                "No liquidity for {} -> {} on {}",
+                params.token_in,
+                params.token_out,
+                self.network
+            ));
+        }
+        let amount_out_min = min_amount_out(amount_out, params.slippage_bps);
+        let approval_required = self.allowance(token_in, owner).await? < amount_in;
+        let gas_price = self.provider.get_gas_price().await?;
+        let gas = SWAP_GAS + if approval_required { APPROVE_GAS } else { 0 };
+        let deadline = Utc::now() + chrono::Duration::seconds(params.deadline_seconds as i64);
+
+        let quote = SwapQuote {
+            network: self.network.clone(),
+            protocol: self.config.protocol,
+            router: self.config.router.clone(),
+            token_in: params.token_in.clone(),
+            token_out: params.token_out.clone(),
+            amount_in: format_token_amount(amount_in, in_decimals),
+            amount_out: format_token_amount(amount_out, out_decimals),
+            amount_out_min: format_token_amount(amount_out_min, out_decimals),
+            slippage_bps: params.slippage_bps,
+            fee_tier: match self.config.protocol {
+                SwapProtocol::UniswapV2 => None,
+                SwapProtocol::UniswapV3 => Some(self.config.fee_tier),
+            },
+            approval_required,
+            network_fee: format_token_amount(gas_price * U256::from(gas), NATIVE_DECIMALS),
+            deadline,
+        };
+        Ok(PreparedSwap {
+            quote,
+            token_in,
+            token_out,
+            amount_in,
+            amount_out_min,
+            deadline: U256::from(deadline.timestamp() as u64),
+            gas_price,
+        })
+    }
+
+    fn swap_calldata(&self, swap: &PreparedSwap, recipient: Address) -> Bytes {
+        match self.config.protocol {
+            SwapProtocol::UniswapV2 => encode_swap_exact_tokens_for_tokens(
+                swap.amount_in,
+                swap.amount_out_min,
+                swap.token_in,
+                swap.token_out,
+                recipient,
+                swap.deadline,
+            ),
+            SwapProtocol::UniswapV3 => encode_exact_input_single(
+                swap.token_in,
+                swap.token_out,
+                self.config.fee_tier,
+                recipient,
+                swap.deadline,
+                swap.amount_in,
+                swap.amount_out_min,
+            ),
+        }
+    }
+
+    /// Simulates the swap and checks it returns at least the minimum output.
+    async fn simulate(
+        &self,
+        tx: &TransactionRequest,
+        swap: &PreparedSwap,
+        params: &SwapParams,
+    ) -> Result<SimulationResult> {
+        let simulation = simulate_evm(&self.provider, &tx.clone().into(), &self.native_token)
+            .await
+            .context("Swap simulation failed")?;
+        if !simulation.success {
+            return Err(anyhow::anyhow!("Swap simulation reverted: {}", simulation.failure()));
+        }
+        let simulated_out = match self.config.protocol {
+            SwapProtocol::UniswapV2 => decode_last_amount(&simulation.return_data)?,
+            SwapProtocol::UniswapV3 => decode_first_word(&simulation.return_data)?,
+        };
+        if simulated_out < swap.amount_out_min {
+            return Err(anyhow::anyhow!(
+                "Simulated swap returns {} {}, below the minimum of {}",
+                simulated_out,
+                params.token_out,
+                swap.amount_out_min
+            ));
+        }
+        Ok(simulation)
+    }
+
+    /// Signs `tx` for this network and broadcasts it.
+    async fn send(&self, signer: &dyn Signer, tx: TransactionRequest) -> Result<H256> {
+        send_with_signer(&self.provider, signer, tx, self.chain_id).await
+    }
+}
+
+#[async_trait]
+impl<P: JsonRpcClient + 'static> SwapRouter for UniswapRouter<P> {
+    async fn quote_swap(&self, owner: &str, params: &SwapParams) -> Result<SwapQuote> {
+        Ok(self.prepare(parse_address(owner)?, params).await?.quote)
+    }
+
+    async fn execute_swap(
+        &self,
+        params: &SwapParams,
+        wallet_data: &SecureWalletData,
+    ) -> Result<SwapReceipt> {
+        let signer = SoftwareSigner::for_network(&wallet_data.encrypted_master_key, &self.network)?;
+        let from = address_from_public_key(&signer.public_key().await?)?;
+        let router = parse_address(&self.config.router)?;
+
+        let swap = self.prepare(from, params).await?;
+        let mut nonce = self.provider.get_transaction_count(from, None).await?;
+
+        let approval_tx_hash = if swap.quote.approval_required {
+            let approve = TransactionRequest::new()
+                .from(from)
+                .to(swap.token_in)
+                .data(encode_approve(router, swap.amount_in))
+                .gas(APPROVE_GAS)
+                .gas_price(swap.gas_price)
+                .nonce(nonce);
+            let tx_hash = self.send(&signer, approve).await.context("Token approval failed")?;
+            nonce += U256::one();
+            Some(format!("{:?}", tx_hash))
+        } else {
+            None
+        };
+
+        let tx = TransactionRequest::new()
+            .from(from)
+            .to(router)
+            .data(self.swap_calldata(&swap, from))
+            .gas(SWAP_GAS)
+            .gas_price(swap.gas_price)
+            .nonce(nonce);
+        let simulation = match approval_tx_hash {
+            Some(_) => None,
+            None => Some(self.simulate(&tx, &swap, params).await?),
+        };
+
+        let tx_hash = self.send(&signer, tx).await.context("Swap submission failed")?;
+        info!(
+            "Swap {:?} sent on {}: {} {} -> {} (min {})",
+            tx_hash,
+            self.network,
+            swap.quote.amount_in,
+            params.token_in,
+            params.token_out,
+            swap.quote.amount_out_min
+        );
+        Ok(SwapReceipt {
+            tx_hash: format!("{:?}", tx_hash),
+            approval_tx_hash,
+            quote: swap.quote,
+            simulation,
+        })
+    }
+}
+
+/// Builds a router for every network in `config`, using the RPC endpoints and
+/// chain ids from `blockchain`.
+pub fn swap_routers_from_config(
+    config: &SwapConfig,
+    blockchain: &BlockchainConfig,
+) -> Result<HashMap<String, Box<dyn SwapRouter>>> {
+    config.validate(blockchain)?;
+    let mut routers: HashMap<String, Box<dyn SwapRouter>> = HashMap::new();
+    for (network, router) in &config.routers {
+        // validate() guarantees the network exists and is an EVM network
+        let network_config = &blockchain.networks[network];
+        let router = UniswapRouter::new(
+            network,
+            &network_config.native_token,
+            router.clone(),
+            &network_config.rpc_url,
+            network_config.chain_id.expect("validated EVM network"),
+        )?;
+        routers.insert(network.clone(), Box::new(router));
+    }
+    Ok(routers)
+}
+
+fn parse_address(address: &str) -> Result<Address> {
+    Address::from_str(address).map_err(|e| anyhow::anyhow!("Invalid address '{}': {}", address, e))
+}
+
+fn decode_first_word(out: &[u8]) -> Result<U256> {
+    out.get(..32)
+        .map(U256::from_big_endian)
+        .ok_or_else(|| anyhow::anyhow!("Short return data ({} bytes)", out.len()))
+}
+
+/// Last element of a returned `uint256[]`, the output amount of a V2 path.
+fn decode_last_amount(out: &[u8]) -> Result<U256> {
+    let tokens = abi::decode(&[ParamType::Array(Box::new(ParamType::Uint(256)))], out)
+        .context("Malformed amounts array")?;
+    tokens
+        .into_iter()
+        .next()
+        .and_then(Token::into_array)
+        .and_then(|mut amounts| amounts.pop())
+        .and_then(Token::into_uint)
+        .ok_or_else(|| anyhow::anyhow!("Empty amounts array"))
+}
+
+/// V2 `getAmountsOut(amountIn, [tokenIn, tokenOut])` calldata.
+pub fn encode_get_amounts_out(amount_in: U256, token_in: Address, token_out: Address) -> Bytes {
+    let mut data = id("getAmountsOut(uint256,address[])").to_vec();
+    data.extend(abi::encode(&[
+        Token::Uint(amount_in),
+        Token::Array(vec![Token::Address(token_in), Token::Address(token_out)]),
+    ]));
+    data.into()
+}
+
+/// V2 `swapExactTokensForTokens(amountIn, amountOutMin, path, to, deadline)` calldata.
+pub fn encode_swap_exact_tokens_for_tokens(
+    amount_in: U256,
+    amount_out_min: U256,
+    token_in: Address,
+    token_out: Address,
+    recipient: Address,
+    deadline: U256,
+) -> Bytes {
+    let mut data =
+        id("swapExactTokensForTokens(uint256,uint256,address[],address,uint256)").to_vec();
+    data.extend(abi::encode(&[
+        Token::Uint(amount_in),
+        Token::Uint(amount_out_min),
+        Token::Array(vec![Token::Address(token_in), Token::Address(token_out)]),
+        Token::Address(recipient),
+        Token::Uint(deadline),
+    ]));
+    data.into()
+}
+
+/// QuoterV2 `quoteExactInputSingle((tokenIn, tokenOut, amountIn, fee, sqrtPriceLimitX96))`
+/// calldata with no price limit.
+pub fn encode_quote_exact_input_single(
+    token_in: Address,
+    token_out: Address,
+    amount_in: U256,
+    fee_tier: u32,
+) -> Bytes {
+    let mut data = id("quoteExactInputSingle((address,address,uint256,uint24,uint160))").to_vec();
+    data.extend(abi::encode(&[Token::Tuple(vec![
+        Token::Address(token_in),
+        Token::Address(token_out),
+        Token::Uint(amount_in),
+        Token::Uint(fee_tier.into()),
+        Token::Uint(U256::zero()),
+    ])]));
+    data.into()
+}
+
+/// SwapRouter `exactInputSingle((tokenIn, tokenOut, fee, recipient, deadline,
+/// amountIn, amountOutMinimum, sqrtPriceLimitX96))` calldata with no price limit.
+pub fn encode_exact_input_single(
+    token_in: Address,
+    token_out: Address,
+    fee_tier: u32,
+    recipient: Address,
+    deadline: U256,
+    amount_in: U256,
+    amount_out_min: U256,
+) -> Bytes {
+    let mut data =
+        id("exactInputSingle((address,address,uint24,address,uint256,uint256,uint256,uint160))")
+            .to_vec();
+    data.extend(abi::encode(&[Token::Tuple(vec![
+        Token::Address(token_in),
+        Token::Address(token_out),
+        Token::Uint(fee_tier.into()),
+        Token::Address(recipient),
+        Token::Uint(deadline),
+        Token::Uint(amount_in),
+        Token::Uint(amount_out_min),
+        Token::Uint(U256::zero()),
+    ])]));
+    data.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::BridgeTokenConfig;
+    use crate::core::wallet_info::test_wallet;
+    use ethers::providers::MockProvider;
+
+    const WETH: &str = "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2";
+    const USDC: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+
+    fn config(protocol: SwapProtocol) -> SwapRouterConfig {
+        let mut tokens = HashMap::new();
+        tokens.insert(
+            "WETH".to_string(),
+            BridgeTokenConfig { address: WETH.to_string(), decimals: 18 },
+        );
+        tokens.insert(
+            "USDC".to_string(),
+            BridgeTokenConfig { address: USDC.to_string(), decimals: 6 },
+        );
+        SwapRouterConfig {
+            protocol,
+            router: "0xE592427A0AEce92De3Edee1F18E0157C05861564".to_string(),
+            quoter: Some("0x61fFE014bA17989E743c5F6cB21bF9697530B21e".to_string()),
+            fee_tier: 500,
+            tokens,
+        }
+    }
+
+    fn router(protocol: SwapProtocol) -> (UniswapRouter<MockProvider>, MockProvider) {
+        let (provider, mock) = Provider::mocked();
+        (UniswapRouter::with_provider("eth", "ETH", config(protocol), provider, 1), mock)
+    }
+
+    fn word(value: U256) -> Bytes {
+        abi::encode(&[Token::Uint(value)]).into()
+    }
+
+    /// `callTracer` result of a successful call returning `output`.
+    fn trace(output: Bytes) -> serde_json::Value {
+        serde_json::json!({ "from": OWNER, "to": OWNER, "gasUsed": "0x1d4c0", "output": output })
+    }
+
+    fn amounts(values: &[U256]) -> Bytes {
+        abi::encode(&[Token::Array(values.iter().map(|v| Token::Uint(*v)).collect())]).into()
+    }
+
+    const OWNER: &str = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
+
+    #[test]
+    fn test_calldata_encoding() {
+        let weth = parse_address(WETH).unwrap();
+        let usdc = parse_address(USDC).unwrap();
+        let recipient = Address::repeat_byte(0xab);
+
+        let v2 = encode_swap_exact_tokens_for_tokens(
+            U256::exp10(18),
+            2_000_000u64.into(),
+            weth,
+            usdc,
+            recipient,
+            1_700_000_000u64.into(),
+        );
+        assert_eq!(hex::encode(&v2[..4]), "38ed1739");
+        // head (5 words) + path length + two addresses
+        assert_eq!(v2.len(), 4 + 8 * 32);
+
+        let v3 = encode_exact_input_single(
+            weth,
+            usdc,
+            500,
+            recipient,
+            1_700_000_000u64.into(),
+            U256::exp10(18),
+            2_000_000u64.into(),
+        );
+        assert_eq!(hex::encode(&v3[..4]), "414bf389");
+        // static tuple is encoded inline
+        assert_eq!(v3.len(), 4 + 8 * 32);
+        assert_eq!(&v3[4 + 2 * 32 + 30..4 + 3 * 32], &[0x01, 0xf4]);
+
+        assert_eq!(decode_last_amount(&amounts(&[U256::one(), U256::from(9)])).unwrap(), 9.into());
+        assert!(decode_first_word(&[0u8; 4]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_v2_quote_applies_slippage_and_checks_allowance() {
+        let (router, mock) = router(SwapProtocol::UniswapV2);
+        // MockProvider answers in LIFO order: amounts, allowance, gas price
+        mock.push(U256::from(10_000_000_000u64)).unwrap();
+        mock.push::<Bytes, _>(word(U256::zero())).unwrap();
+        mock.push::<Bytes, _>(amounts(&[U256::exp10(18), U256::from(2_000_000_000u64)])).unwrap();
+
+        let params = SwapParams { slippage_bps: 100, ..SwapParams::new("WETH", "USDC", "1") };
+        let quote = router.quote_swap(OWNER, &params).await.unwrap();
+        assert_eq!(quote.amount_out, "2000");
+        assert_eq!(quote.amount_out_min, "1980");
+        assert_eq!(quote.fee_tier, None);
+        assert!(quote.approval_required);
+        // 300k swap + 60k approval at 10 gwei
+        assert_eq!(quote.network_fee, "0.0036");
+
+        assert!(router.quote_swap(OWNER, &SwapParams::new("WETH", "DAI", "1")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_v3_swap_approves_then_sends_without_waiting() {
+        let (router, mock) = router(SwapProtocol::UniswapV3);
+        let approval_hash = H256::repeat_byte(0x11);
+        let swap_hash = H256::repeat_byte(0x22);
+        let quoted = U256::from(2_000_000_000u64);
+        // LIFO: quote, allowance, gas price, nonce, approve, swap. The swap
+        // cannot be simulated before the approval is mined.
+        mock.push(swap_hash).unwrap();
+        mock.push(approval_hash).unwrap();
+        mock.push(U256::from(4)).unwrap();
+        mock.push(U256::from(10_000_000_000u64)).unwrap();
+        mock.push::<Bytes, _>(word(U256::zero())).unwrap();
+        mock.push::<Bytes, _>(word(quoted)).unwrap();
+
+        let receipt = router
+            .execute_swap(&SwapParams::new("WETH", "USDC", "1"), &test_wallet())
+            .await
+            .unwrap();
+        assert_eq!(receipt.tx_hash, format!("{:?}", swap_hash));
+        assert_eq!(receipt.approval_tx_hash, Some(format!("{:?}", approval_hash)));
+        assert_eq!(receipt.quote.amount_out_min, "1990");
+        assert_eq!(receipt.quote.fee_tier, Some(500));
+        assert_eq!(receipt.simulation, None);
+    }
+
+    #[tokio::test]
+    async fn test_swap_with_allowance_is_simulated_then_sent() {
+        let (router, mock) = router(SwapProtocol::UniswapV2);
+        let swap_hash = H256::repeat_byte(0x22);
+        let quoted = amounts(&[U256::exp10(18), U256::from(2_000_000_000u64)]);
+        // LIFO: quote, allowance, gas price, nonce, simulation, swap
+        mock.push(swap_hash).unwrap();
+        mock.push(trace(quoted.clone())).unwrap();
+        mock.push(U256::from(4)).unwrap();
+        mock.push(U256::from(10_000_000_000u64)).unwrap();
+        mock.push::<Bytes, _>(word(U256::MAX)).unwrap();
+        mock.push::<Bytes, _>(quoted).unwrap();
+
+        let receipt = router
+            .execute_swap(&SwapParams::new("WETH", "USDC", "1"), &test_wallet())
+            .await
+            .unwrap();
+        assert_eq!(receipt.tx_hash, format!("{:?}", swap_hash));
+        assert_eq!(receipt.approval_tx_hash, None);
+        assert!(receipt.simulation.unwrap().success);
+    }
+
+    #[tokio::test]
+    async fn test_simulation_below_minimum_is_not_sent() {
+        let (router, mock) = router(SwapProtocol::UniswapV3);
+        // allowance already covers the amount, so no approval is sent
+        mock.push(trace(word(U256::from(1_900_000_000u64)))).unwrap();
+        mock.push(U256::from(4)).unwrap();
+        mock.push(U256::from(10_000_000_000u64)).unwrap();
+        mock.push::<Bytes, _>(word(U256::MAX)).unwrap();
+        mock.push::<Bytes, _>(word(U256::from(2_000_000_000u64))).unwrap();
+
+        let err = router
+            .execute_swap(&SwapParams::new("WETH", "USDC", "1"), &test_wallet())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("below the minimum"));
+    }
+
+    #[tokio::test]
+    async fn test_reverting_simulation_is_not_sent() {
+        let (router, mock) = router(SwapProtocol::UniswapV3);
+        let reverted = serde_json::json!({
+            "from": OWNER,
+            "to": OWNER,
+            "error": "execution reverted",
+            "revertReason": "Too little received"
+        });
+        mock.push(reverted).unwrap();
+        mock.push(U256::from(4)).unwrap();
+        mock.push(U256::from(10_000_000_000u64)).unwrap();
+        mock.push::<Bytes, _>(word(U256::MAX)).unwrap();
+        mock.push::<Bytes, _>(word(U256::from(2_000_000_000u64))).unwrap();
+
+        let err = router
+            .execute_swap(&SwapParams::new("WETH", "USDC", "1"), &test_wallet())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Too little received"));
+    }
+}
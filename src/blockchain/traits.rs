@@ -1,9 +1,15 @@
 use async_trait::async_trait;
+use ethers::types::{transaction::eip2718::TypedTransaction, Bytes, H256};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    blockchain::bridge::BridgeTransactionStatus, core::errors::WalletError,
+    blockchain::bridge::{quote::BridgeTerms, BridgeTransactionStatus},
+    blockchain::simulation::SimulationResult,
+    blockchain::staking::{StakePosition, StakeReceipt},
+    blockchain::swap::{SwapParams, SwapQuote, SwapReceipt},
+    core::errors::WalletError,
     core::wallet_info::SecureWalletData,
+    crypto::signer::Signer,
 };
 
 /// Defines the interface for a cross-chain bridge.
@@ -18,6 +24,99 @@ pub trait Bridge: Send + Sync {
         amount: &str,
         wallet_data: &SecureWalletData,
     ) -> anyhow::Result<String>;
+
+    /// Fees, limits and timing for sending `token` over this bridge. Bridges
+    /// that can read live gas prices or liquidity should override this.
+    async fn quote_terms(
+        &self,
+        _from_chain: &str,
+        _to_chain: &str,
+        _token: &str,
+    ) -> anyhow::Result<BridgeTerms> {
+        Ok(BridgeTerms::default())
+    }
+
+    /// Hash of the destination-chain transaction that delivered `tx_id`, or
+    /// `None` when the bridge cannot tell.
+    async fn destination_transaction(&self, _tx_id: &str) -> anyhow::Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Claims a delivered but unredeemed transfer on the destination chain.
+    /// Returns the claim transaction hash, or `None` when the bridge cannot
+    /// redeem on the wallet's behalf.
+    async fn redeem_transfer(
+        &self,
+        _tx_id: &str,
+        _wallet_data: &SecureWalletData,
+    ) -> anyhow::Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Requests a refund of a transfer that will not be delivered. Returns the
+    /// refund id, or `None` when the protocol has no refunds.
+    async fn refund_transfer(
+        &self,
+        _tx_id: &str,
+        _wallet_data: &SecureWalletData,
+    ) -> anyhow::Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Progress of a refund from `refund_transfer`: `Completed` once the funds
+    /// are back on the source chain.
+    async fn check_refund_status(
+        &self,
+        refund_id: &str,
+    ) -> anyhow::Result<BridgeTransactionStatus> {
+        Err(anyhow::anyhow!("Bridge does not support refunds (refund {})", refund_id))
+    }
+}
+
+/// Defines the interface for swapping tokens through a DEX on one network.
+#[async_trait]
+pub trait SwapRouter: Send + Sync {
+    /// Prices `params` for the wallet at `owner`, whose allowance decides
+    /// whether an approval is needed.
+    async fn quote_swap(&self, owner: &str, params: &SwapParams) -> anyhow::Result<SwapQuote>;
+
+    /// Approves the router if needed, simulates the swap and submits it.
+    async fn execute_swap(
+        &self,
+        params: &SwapParams,
+        wallet_data: &SecureWalletData,
+    ) -> anyhow::Result<SwapReceipt>;
+}
+
+/// Defines the interface for staking the native token on one network.
+#[async_trait]
+pub trait StakingProvider: Send + Sync {
+    /// Stakes `amount` of the native token, delegating to `validator` where
+    /// the protocol has one.
+    async fn stake(
+        &self,
+        amount: &str,
+        validator: Option<&str>,
+        wallet_data: &SecureWalletData,
+    ) -> anyhow::Result<StakeReceipt>;
+
+    /// Starts unstaking `position`, all of it when `amount` is `None`.
+    async fn unstake(
+        &self,
+        position: &StakePosition,
+        amount: Option<&str>,
+        wallet_data: &SecureWalletData,
+    ) -> anyhow::Result<StakeReceipt>;
+
+    /// Withdraws a fully unstaked `position` back to the wallet.
+    async fn withdraw(
+        &self,
+        position: &StakePosition,
+        wallet_data: &SecureWalletData,
+    ) -> anyhow::Result<StakeReceipt>;
+
+    /// Lists the staking positions held by `owner`.
+    async fn positions(&self, owner: &str) -> anyhow::Result<Vec<StakePosition>>;
 }
 
 /// Represents the status of a standard blockchain transaction.
@@ -38,24 +137,102 @@ pub trait BlockchainClient: Send + Sync {
     /// Retrieves the balance of a given address.
     async fn get_balance(&self, address: &str) -> Result<String, WalletError>;
 
-    /// Sends a transaction from a private key to a recipient address.
+    /// Builds an unsigned native-token transfer from the account owning
+    /// `from_public_key` (fetching nonce, fees, blockhash as needed).
+    async fn build_transaction(
+        &self,
+        from_public_key: &[u8],
+        to_address: &str,
+        amount: &str,
+    ) -> Result<UnsignedTransaction, WalletError>;
+
+    /// Submits a signed transaction and returns its hash.
+    async fn broadcast_transaction(&self, tx: &SignedTransaction) -> Result<String, WalletError>;
+
+    /// Builds, signs with `signer` and broadcasts a transfer to a recipient address.
     async fn send_transaction(
         &self,
-        private_key: &[u8],
+        signer: &dyn Signer,
         to_address: &str,
         amount: &str,
-    ) -> Result<String, WalletError>;
+    ) -> Result<String, WalletError> {
+        let public_key = signer.public_key().await?;
+        let unsigned = self.build_transaction(&public_key, to_address, amount).await?;
+        let signed = signer.sign_transaction(&unsigned).await?;
+        self.broadcast_transaction(&signed).await
+    }
+
+    /// Builds an unsigned contract call from the account owning
+    /// `from_public_key`, sending `value` of the native token with `data`.
+    async fn build_contract_transaction(
+        &self,
+        _from_public_key: &[u8],
+        _contract: &str,
+        _data: Bytes,
+        _value: &str,
+    ) -> Result<UnsignedTransaction, WalletError> {
+        Err(WalletError::BlockchainError(format!(
+            "Contract calls are not supported on {}",
+            self.get_network_name()
+        )))
+    }
+
+    /// Executes a contract call read-only against the latest block. A revert
+    /// is reported in the result, not as an error.
+    async fn call_contract(
+        &self,
+        _from_public_key: &[u8],
+        _contract: &str,
+        _data: Bytes,
+        _value: &str,
+    ) -> Result<SimulationResult, WalletError> {
+        Err(WalletError::BlockchainError(format!(
+            "Contract calls are not supported on {}",
+            self.get_network_name()
+        )))
+    }
+
+    /// Dry-runs an unsigned transaction against the current chain state. A
+    /// revert is reported in the result, not as an error; `None` means the
+    /// network cannot simulate at all.
+    async fn simulate_transaction(
+        &self,
+        _tx: &UnsignedTransaction,
+    ) -> Result<Option<SimulationResult>, WalletError> {
+        Ok(None)
+    }
 
     /// Retrieves the status of a transaction given its hash.
     async fn get_transaction_status(&self, tx_hash: &str)
         -> Result<TransactionStatus, WalletError>;
 
+    /// Blocks that include or build on a mined transaction, counting its own.
+    /// `None` while it is unmined or when the network does not count
+    /// confirmations, in which case its status alone decides settlement.
+    async fn get_transaction_confirmations(
+        &self,
+        _tx_hash: &str,
+    ) -> Result<Option<u64>, WalletError> {
+        Ok(None)
+    }
+
     /// Estimates the fee for a transaction.
     async fn estimate_fee(&self, to_address: &str, amount: &str) -> Result<String, WalletError>;
 
     /// Gets the latest block number.
     async fn get_block_number(&self) -> Result<u64, WalletError>;
 
+    /// Native transfers to `address` in blocks `from_block..=to_block`.
+    /// Networks that cannot list them report none.
+    async fn incoming_transfers(
+        &self,
+        _address: &str,
+        _from_block: u64,
+        _to_block: u64,
+    ) -> Result<Vec<TransactionInfo>, WalletError> {
+        Ok(Vec::new())
+    }
+
     /// Validates if a given address string is valid for the blockchain.
     fn validate_address(&self, address: &str) -> anyhow::Result<bool>;
 
@@ -74,3 +251,24 @@ pub struct TransactionInfo {
     pub to: String,
     pub amount: String,
 }
+
+/// A transaction ready to be signed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "chain", rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)] // short-lived; not worth boxing the EVM transaction
+pub enum UnsignedTransaction {
+    /// EVM transaction; the signer signs its `sighash`.
+    Evm { tx: TypedTransaction },
+    /// Serialized Solana message; the signer signs the bytes directly.
+    Solana { message: Bytes },
+}
+
+/// A signed transaction ready to be broadcast.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "chain", rename_all = "snake_case")]
+pub enum SignedTransaction {
+    /// RLP-encoded signed EVM transaction and its hash.
+    Evm { raw: Bytes, hash: H256 },
+    /// Solana message with the fee payer's Ed25519 signature.
+    Solana { message: Bytes, signature: Bytes },
+}
@@ -1,6 +1,8 @@
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
+use crate::blockchain::swap::{DEFAULT_DEADLINE_SECONDS, DEFAULT_SLIPPAGE_BPS};
+
 /// DeFi Hot Wallet CLI (library-facing definitions)
 #[derive(Debug, Parser)]
 #[command(name = "wallet-cli", about = "DeFi Hot Wallet CLI", disable_help_subcommand = true)]
@@ -49,6 +51,53 @@ pub enum Commands {
         #[arg(long)]
         amount: String,
     },
+    /// Swap tokens through the DEX router configured for a network (SWAP_CONFIG)
+    Swap {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        network: String,
+        #[arg(long = "token-in")]
+        token_in: String,
+        #[arg(long = "token-out")]
+        token_out: String,
+        #[arg(long)]
+        amount: String,
+        /// Slippage tolerance in basis points
+        #[arg(long = "slippage-bps", default_value_t = DEFAULT_SLIPPAGE_BPS)]
+        slippage_bps: u32,
+        /// Seconds until the swap expires on-chain
+        #[arg(long = "deadline-seconds", default_value_t = DEFAULT_DEADLINE_SECONDS)]
+        deadline_seconds: u64,
+        /// Print the quote without sending anything
+        #[arg(long = "quote-only")]
+        quote_only: bool,
+    },
+    /// Sign an exported unsigned transaction on an offline machine
+    Sign {
+        /// Unsigned request: JSON, or UR fragments one per line
+        #[arg(long)]
+        input: PathBuf,
+        /// Passphrase-encrypted key file (read passphrase from WALLET_KEY_PASSPHRASE)
+        #[arg(long = "key-file")]
+        key_file: PathBuf,
+        /// Where to write the signed transaction (stdout if omitted)
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// Emit UR fragments instead of JSON
+        #[arg(long)]
+        ur: bool,
+    },
+    /// Export a wallet's key on one network as an encrypted key file for `sign`
+    ExportKey {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        network: String,
+        /// Where to write the key file (passphrase read from WALLET_KEY_PASSPHRASE)
+        #[arg(long)]
+        output: PathBuf,
+    },
     List,
     GenerateMnemonic,
     Help,
@@ -117,6 +117,23 @@ impl Default for WalletConfig {
 }
 
 impl WalletConfig {
+    /// Configuration the server and CLI binaries run with: the database at
+    /// `DATABASE_URL` (default `sqlite://./wallets.db`) and no networks, so no
+    /// RPC clients are opened at startup. Bridges, swap routers and staking
+    /// providers are built against the default networks instead.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            storage: StorageConfig {
+                database_url: std::env::var("DATABASE_URL")
+                    .unwrap_or_else(|_| "sqlite://./wallets.db".to_string()),
+                ..defaults.storage
+            },
+            blockchain: BlockchainConfig { networks: HashMap::new(), ..defaults.blockchain },
+            ..defaults
+        }
+    }
+
     /// Validates the configuration.
     pub fn validate(&self) -> Result<()> {
         if self.storage.database_url.is_empty() {
@@ -149,6 +166,332 @@ impl WalletConfig {
     }
 }
 
+/// Bridge protocol spoken by a route's contracts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BridgeProtocol {
+    /// Wormhole token bridge (`transferTokens` / `isTransferCompleted`).
+    Wormhole,
+}
+
+/// An ERC-20 token that may be sent over a bridge route.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeTokenConfig {
+    pub address: String,
+    pub decimals: u8,
+}
+
+/// Contracts of a lock-and-mint route between two EVM networks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeRouteConfig {
+    pub protocol: BridgeProtocol,
+    /// Token bridge on the source network; tokens are locked here.
+    pub token_bridge: String,
+    /// Core messaging contract on the source network that emits the transfer message.
+    pub core_bridge: String,
+    /// Token bridge on the destination network, queried for redemption.
+    pub destination_token_bridge: String,
+    /// Protocol-level chain ids (Wormhole chain ids, not EVM chain ids).
+    pub source_bridge_chain: u16,
+    pub destination_bridge_chain: u16,
+    /// Source confirmations required before the transfer counts as in transit.
+    #[serde(default = "default_bridge_confirmations")]
+    pub confirmations: u64,
+    /// Supported tokens by symbol.
+    #[serde(default)]
+    pub tokens: HashMap<String, BridgeTokenConfig>,
+}
+
+fn default_bridge_confirmations() -> u64 {
+    15
+}
+
+/// Splits a bridge key `"<from>-<to>"` into its networks. Network names may
+/// contain `-` themselves (`solana-devnet`), so a split into two configured
+/// networks is preferred over the first `-`.
+pub fn split_route<'a>(
+    key: &'a str,
+    networks: &HashMap<String, NetworkConfig>,
+) -> Option<(&'a str, &'a str)> {
+    key.match_indices('-')
+        .map(|(i, _)| (&key[..i], &key[i + 1..]))
+        .find(|(from, to)| networks.contains_key(*from) && networks.contains_key(*to))
+        .or_else(|| key.split_once('-'))
+}
+
+/// Bridge routes keyed `"<from>-<to>"`, using network names from `BlockchainConfig`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BridgeConfig {
+    #[serde(default)]
+    pub routes: HashMap<String, BridgeRouteConfig>,
+}
+
+impl BridgeConfig {
+    /// Checks that every route joins two configured EVM networks and that all
+    /// contract addresses parse.
+    pub fn validate(&self, blockchain: &BlockchainConfig) -> Result<()> {
+        for (key, route) in &self.routes {
+            let (from, to) = split_route(key, &blockchain.networks)
+                .ok_or_else(|| anyhow::anyhow!("Bridge route '{}' must be '<from>-<to>'", key))?;
+            for network in [from, to] {
+                let config = blockchain.networks.get(network).ok_or_else(|| {
+                    anyhow::anyhow!("Bridge route '{}' uses unknown network '{}'", key, network)
+                })?;
+                if config.chain_id.is_none() {
+                    return Err(anyhow::anyhow!(
+                        "Bridge route '{}': '{}' is not an EVM network",
+                        key,
+                        network
+                    ));
+                }
+            }
+            let addresses =
+                [&route.token_bridge, &route.core_bridge, &route.destination_token_bridge]
+                    .into_iter()
+                    .chain(route.tokens.values().map(|t| &t.address));
+            for address in addresses {
+                address.parse::<ethers::types::Address>().map_err(|e| {
+                    anyhow::anyhow!("Bridge route '{}': invalid address '{}': {}", key, address, e)
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads bridge routes from a TOML file.
+    pub fn from_file(path: &str) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(toml::from_str(&contents)?)
+    }
+}
+
+/// DEX router interface spoken by a network's swap contracts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SwapProtocol {
+    /// `getAmountsOut` / `swapExactTokensForTokens` on a V2-style router.
+    UniswapV2,
+    /// QuoterV2 `quoteExactInputSingle` and SwapRouter `exactInputSingle`.
+    UniswapV3,
+}
+
+impl SwapProtocol {
+    /// Name as written in config files.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SwapProtocol::UniswapV2 => "uniswap_v2",
+            SwapProtocol::UniswapV3 => "uniswap_v3",
+        }
+    }
+}
+
+/// Router contracts and tokens used for swaps on one EVM network.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwapRouterConfig {
+    pub protocol: SwapProtocol,
+    pub router: String,
+    /// Quoter contract; required for Uniswap V3, unused for V2.
+    #[serde(default)]
+    pub quoter: Option<String>,
+    /// V3 pool fee tier in hundredths of a basis point (3000 = 0.3%).
+    #[serde(default = "default_swap_fee_tier")]
+    pub fee_tier: u32,
+    /// Swappable ERC-20 tokens by symbol.
+    #[serde(default)]
+    pub tokens: HashMap<String, BridgeTokenConfig>,
+}
+
+fn default_swap_fee_tier() -> u32 {
+    3000
+}
+
+/// Swap routers keyed by network name from `BlockchainConfig`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SwapConfig {
+    #[serde(default)]
+    pub routers: HashMap<String, SwapRouterConfig>,
+}
+
+impl SwapConfig {
+    /// Checks that every router is on a configured EVM network, that V3
+    /// routers have a quoter and that all addresses parse.
+    pub fn validate(&self, blockchain: &BlockchainConfig) -> Result<()> {
+        for (network, router) in &self.routers {
+            let config = blockchain.networks.get(network).ok_or_else(|| {
+                anyhow::anyhow!("Swap router configured for unknown network '{}'", network)
+            })?;
+            if config.chain_id.is_none() {
+                return Err(anyhow::anyhow!("Swap router network '{}' is not EVM", network));
+            }
+            if router.protocol == SwapProtocol::UniswapV3 && router.quoter.is_none() {
+                return Err(anyhow::anyhow!("Uniswap V3 router on '{}' needs a quoter", network));
+            }
+            let addresses = std::iter::once(&router.router)
+                .chain(router.quoter.as_ref())
+                .chain(router.tokens.values().map(|t| &t.address));
+            for address in addresses {
+                address.parse::<ethers::types::Address>().map_err(|e| {
+                    anyhow::anyhow!(
+                        "Swap router '{}': invalid address '{}': {}",
+                        network,
+                        address,
+                        e
+                    )
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads swap routers from a TOML file.
+    pub fn from_file(path: &str) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(toml::from_str(&contents)?)
+    }
+}
+
+/// Staking mechanism offered on a network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StakingProtocol {
+    /// Native stake accounts delegated to a vote account.
+    SolanaNative,
+    /// Lido stETH deposits and withdrawal-queue requests.
+    Lido,
+}
+
+impl StakingProtocol {
+    /// Name as written in config files.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StakingProtocol::SolanaNative => "solana_native",
+            StakingProtocol::Lido => "lido",
+        }
+    }
+}
+
+/// Staking contracts or defaults for one network.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StakingProviderConfig {
+    pub protocol: StakingProtocol,
+    /// Lido stETH token; defaults to the mainnet deployment.
+    #[serde(default)]
+    pub steth: Option<String>,
+    /// Lido WithdrawalQueueERC721; defaults to the mainnet deployment.
+    #[serde(default)]
+    pub withdrawal_queue: Option<String>,
+    /// Vote account used when a Solana stake request does not name one.
+    #[serde(default)]
+    pub default_validator: Option<String>,
+}
+
+pub const LIDO_MAINNET_STETH: &str = "0xae7ab96520DE3A18E5e111B5EaAb095312D7fE84";
+pub const LIDO_MAINNET_WITHDRAWAL_QUEUE: &str = "0x889edC2eDab5f40e902b864aD4d7AdE8E412F9B1";
+
+/// Staking providers keyed by network name from `BlockchainConfig`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StakingConfig {
+    #[serde(default)]
+    pub providers: HashMap<String, StakingProviderConfig>,
+}
+
+impl StakingConfig {
+    /// Checks that every provider is on a configured network of the right
+    /// kind and that its addresses parse.
+    pub fn validate(&self, blockchain: &BlockchainConfig) -> Result<()> {
+        for (network, provider) in &self.providers {
+            let config = blockchain.networks.get(network).ok_or_else(|| {
+                anyhow::anyhow!("Staking configured for unknown network '{}'", network)
+            })?;
+            match provider.protocol {
+                StakingProtocol::SolanaNative => {
+                    if config.chain_id.is_some() {
+                        return Err(anyhow::anyhow!(
+                            "Solana staking network '{}' is not Solana",
+                            network
+                        ));
+                    }
+                    if let Some(validator) = &provider.default_validator {
+                        let key = bs58::decode(validator).into_vec().unwrap_or_default();
+                        if key.len() != 32 {
+                            return Err(anyhow::anyhow!(
+                                "Staking '{}': invalid vote account '{}'",
+                                network,
+                                validator
+                            ));
+                        }
+                    }
+                }
+                StakingProtocol::Lido => {
+                    if config.chain_id.is_none() {
+                        return Err(anyhow::anyhow!(
+                            "Lido staking network '{}' is not EVM",
+                            network
+                        ));
+                    }
+                    for address in provider.steth.iter().chain(provider.withdrawal_queue.iter()) {
+                        address.parse::<ethers::types::Address>().map_err(|e| {
+                            anyhow::anyhow!(
+                                "Staking '{}': invalid address '{}': {}",
+                                network,
+                                address,
+                                e
+                            )
+                        })?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads staking providers from a TOML file.
+    pub fn from_file(path: &str) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(toml::from_str(&contents)?)
+    }
+}
+
+pub const WALLETCONNECT_RELAY_URL: &str = "wss://relay.walletconnect.com";
+
+fn default_walletconnect_relay_url() -> String {
+    WALLETCONNECT_RELAY_URL.to_string()
+}
+
+/// The WalletConnect v2 relay dApp sessions go through.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletConnectConfig {
+    /// Project id issued by WalletConnect Cloud; the relay refuses clients
+    /// without one.
+    pub project_id: String,
+    #[serde(default = "default_walletconnect_relay_url")]
+    pub relay_url: String,
+}
+
+impl WalletConnectConfig {
+    /// Checks that a project id is set and that the relay is a WebSocket URL.
+    pub fn validate(&self) -> Result<()> {
+        if self.project_id.trim().is_empty() {
+            return Err(anyhow::anyhow!("WalletConnect needs a project id"));
+        }
+        if !self.relay_url.starts_with("wss://") && !self.relay_url.starts_with("ws://") {
+            return Err(anyhow::anyhow!(
+                "WalletConnect relay '{}' is not a WebSocket URL",
+                self.relay_url
+            ));
+        }
+        Ok(())
+    }
+
+    /// Loads the relay settings from a TOML file.
+    pub fn from_file(path: &str) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        let config: Self = toml::from_str(&contents)?;
+        config.validate()?;
+        Ok(config)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -180,4 +523,129 @@ mod tests {
         config.blockchain.networks.clear();
         assert!(config.validate().is_err());
     }
+
+    #[test]
+    fn test_bridge_config_from_toml() {
+        let config: BridgeConfig = toml::from_str(
+            r#"
+            [routes.eth-polygon]
+            protocol = "wormhole"
+            token_bridge = "0x3ee18B2214AFF97000D974cf647E7C347E8fa585"
+            core_bridge = "0x98f3c9e6E3fAce36bAAd05FE09d375Ef1464288B"
+            destination_token_bridge = "0x5a58505a96D1dbf8dF91cB21B54419FC36e93fdE"
+            source_bridge_chain = 2
+            destination_bridge_chain = 5
+
+            [routes.eth-polygon.tokens.USDC]
+            address = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"
+            decimals = 6
+            "#,
+        )
+        .unwrap();
+
+        let route = &config.routes["eth-polygon"];
+        assert_eq!(route.protocol, BridgeProtocol::Wormhole);
+        assert_eq!(route.confirmations, 15);
+        assert_eq!(route.tokens["USDC"].decimals, 6);
+
+        let wallet = WalletConfig::default();
+        assert!(config.validate(&wallet.blockchain).is_ok());
+
+        let mut to_solana = config.clone();
+        let route = to_solana.routes.remove("eth-polygon").unwrap();
+        to_solana.routes.insert("eth-solana".to_string(), route.clone());
+        assert!(to_solana.validate(&wallet.blockchain).is_err());
+
+        // network names may contain '-'
+        let mut blockchain = wallet.blockchain.clone();
+        let amoy =
+            NetworkConfig { chain_id: Some(80002), ..blockchain.networks["polygon"].clone() };
+        blockchain.networks.insert("polygon-amoy".to_string(), amoy);
+        assert_eq!(
+            split_route("eth-polygon-amoy", &blockchain.networks),
+            Some(("eth", "polygon-amoy"))
+        );
+        let mut to_amoy = BridgeConfig::default();
+        to_amoy.routes.insert("eth-polygon-amoy".to_string(), route);
+        assert!(to_amoy.validate(&blockchain).is_ok());
+    }
+
+    #[test]
+    fn test_swap_config_from_toml() {
+        let config: SwapConfig = toml::from_str(
+            r#"
+            [routers.eth]
+            protocol = "uniswap_v3"
+            router = "0xE592427A0AEce92De3Edee1F18E0157C05861564"
+            quoter = "0x61fFE014bA17989E743c5F6cB21bF9697530B21e"
+
+            [routers.eth.tokens.USDC]
+            address = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"
+            decimals = 6
+
+            [routers.polygon]
+            protocol = "uniswap_v2"
+            router = "0xa5E0829CaCEd8fFDD4De3c43696c57F7D7A678ff"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.routers["eth"].protocol, SwapProtocol::UniswapV3);
+        assert_eq!(config.routers["eth"].fee_tier, 3000);
+        assert_eq!(config.routers["polygon"].protocol, SwapProtocol::UniswapV2);
+
+        let wallet = WalletConfig::default();
+        assert!(config.validate(&wallet.blockchain).is_ok());
+
+        let mut no_quoter = config.clone();
+        no_quoter.routers.get_mut("eth").unwrap().quoter = None;
+        assert!(no_quoter.validate(&wallet.blockchain).is_err());
+
+        let mut on_solana = config.clone();
+        let router = on_solana.routers.remove("polygon").unwrap();
+        on_solana.routers.insert("solana".to_string(), router);
+        assert!(on_solana.validate(&wallet.blockchain).is_err());
+    }
+
+    #[test]
+    fn test_staking_config_from_toml() {
+        let config: StakingConfig = toml::from_str(
+            r#"
+            [providers.eth]
+            protocol = "lido"
+
+            [providers.solana]
+            protocol = "solana_native"
+            default_validator = "Vote111111111111111111111111111111111111111"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.providers["eth"].protocol, StakingProtocol::Lido);
+        assert!(config.providers["eth"].steth.is_none());
+
+        let wallet = WalletConfig::default();
+        assert!(config.validate(&wallet.blockchain).is_ok());
+
+        let mut swapped = config.clone();
+        let lido = swapped.providers.remove("eth").unwrap();
+        swapped.providers.insert("solana-devnet".to_string(), lido);
+        assert!(swapped.validate(&wallet.blockchain).is_err());
+
+        let mut bad_validator = config.clone();
+        bad_validator.providers.get_mut("solana").unwrap().default_validator =
+            Some("not-a-key".to_string());
+        assert!(bad_validator.validate(&wallet.blockchain).is_err());
+    }
+    #[test]
+    fn test_walletconnect_config_from_toml() {
+        let config: WalletConnectConfig = toml::from_str(r#"project_id = "abc123""#).unwrap();
+        assert_eq!(config.relay_url, WALLETCONNECT_RELAY_URL);
+        assert!(config.validate().is_ok());
+
+        let blank = WalletConnectConfig { project_id: " ".to_string(), ..config.clone() };
+        assert!(blank.validate().is_err());
+        let http = WalletConnectConfig { relay_url: "https://relay.example".to_string(), ..config };
+        assert!(http.validate().is_err());
+    }
 }
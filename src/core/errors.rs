@@ -25,6 +25,8 @@ pub enum WalletError {
     AddressError(String),
     /// Serialization/deserialization errors.
     SerializationError(String),
+    /// The operation was held for approval under this request id.
+    PendingApproval(String),
     /// Generic errors.
     Other(String),
 }
@@ -43,6 +45,7 @@ impl fmt::Display for WalletError {
             WalletError::KeyDerivationError(msg) => write!(f, "Key derivation error: {}", msg),
             WalletError::AddressError(msg) => write!(f, "Address error: {}", msg),
             WalletError::SerializationError(msg) => write!(f, "Serialization error: {}", msg),
+            WalletError::PendingApproval(id) => write!(f, "Pending approval: {}", id),
             WalletError::Other(msg) => write!(f, "Error: {}", msg),
         }
     }
@@ -0,0 +1,198 @@
+// src/core/events.rs
+//! Events published as wallets change.
+//!
+//! The manager, the bridge relayer and the security monitor publish to one
+//! `EventBus`; webhooks, the `/api/stream` endpoints and other subscribers
+//! receive every event and pick the kinds and wallets they care about. Publishing never waits for or
+//! fails because of a subscriber.
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+use crate::blockchain::bridge::BridgeTransactionStatus;
+use crate::monitoring;
+use crate::tools::async_support::AsyncEventBus;
+
+/// Events a subscriber can miss before it starts skipping.
+pub const EVENT_BUS_CAPACITY: usize = 1024;
+
+pub type EventBus = AsyncEventBus<WalletEvent>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WalletEvent {
+    /// The balance of a watched wallet changed; `previous` is the last
+    /// balance seen.
+    BalanceChanged { wallet: String, network: String, balance: String, previous: String },
+    /// A wallet broadcast a transaction.
+    NewTransaction {
+        wallet: String,
+        network: String,
+        tx_hash: String,
+        to_address: Option<String>,
+        amount: f64,
+        asset: String,
+    },
+    /// A sent transaction gained confirmations but has not settled yet.
+    ConfirmationProgress {
+        wallet: String,
+        network: String,
+        tx_hash: String,
+        confirmations: u64,
+        required_confirmations: u64,
+    },
+    /// A transaction sent from a wallet was confirmed or failed on chain.
+    TransactionConfirmed { wallet: String, network: String, tx_hash: String, status: String },
+    /// A transfer into a wallet was seen on chain.
+    IncomingDeposit {
+        wallet: String,
+        network: String,
+        from_address: String,
+        tx_hash: String,
+        sanctioned: bool,
+    },
+    BridgeStatusChanged {
+        id: String,
+        wallet: String,
+        from_chain: String,
+        to_chain: String,
+        status: BridgeTransactionStatus,
+    },
+    SecurityEvent {
+        event_type: String,
+        severity: String,
+        description: String,
+        wallet: Option<String>,
+        source_ip: Option<String>,
+    },
+    /// A held operation waits for approvers.
+    ApprovalNeeded {
+        id: String,
+        wallet: String,
+        reason: String,
+        approvals: u32,
+        required_approvals: u32,
+    },
+}
+
+impl WalletEvent {
+    pub fn kind(&self) -> EventKind {
+        match self {
+            WalletEvent::BalanceChanged { .. } => EventKind::BalanceChanged,
+            WalletEvent::NewTransaction { .. } => EventKind::NewTransaction,
+            WalletEvent::ConfirmationProgress { .. } => EventKind::ConfirmationProgress,
+            WalletEvent::TransactionConfirmed { .. } => EventKind::TransactionConfirmed,
+            WalletEvent::IncomingDeposit { .. } => EventKind::IncomingDeposit,
+            WalletEvent::BridgeStatusChanged { .. } => EventKind::BridgeStatusChanged,
+            WalletEvent::SecurityEvent { .. } => EventKind::SecurityEvent,
+            WalletEvent::ApprovalNeeded { .. } => EventKind::ApprovalNeeded,
+        }
+    }
+
+    /// Name of the wallet the event concerns, if any.
+    pub fn wallet(&self) -> Option<&str> {
+        match self {
+            WalletEvent::BalanceChanged { wallet, .. }
+            | WalletEvent::NewTransaction { wallet, .. }
+            | WalletEvent::ConfirmationProgress { wallet, .. }
+            | WalletEvent::TransactionConfirmed { wallet, .. }
+            | WalletEvent::IncomingDeposit { wallet, .. }
+            | WalletEvent::BridgeStatusChanged { wallet, .. }
+            | WalletEvent::ApprovalNeeded { wallet, .. } => Some(wallet),
+            WalletEvent::SecurityEvent { wallet, .. } => wallet.as_deref(),
+        }
+    }
+}
+
+impl From<&monitoring::SecurityEvent> for WalletEvent {
+    fn from(event: &monitoring::SecurityEvent) -> Self {
+        WalletEvent::SecurityEvent {
+            event_type: event.event_type.as_str().to_string(),
+            severity: event.severity.as_str().to_string(),
+            description: event.description.clone(),
+            wallet: event.wallet_id.clone(),
+            source_ip: event.source_ip.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    BalanceChanged,
+    NewTransaction,
+    ConfirmationProgress,
+    TransactionConfirmed,
+    IncomingDeposit,
+    BridgeStatusChanged,
+    SecurityEvent,
+    ApprovalNeeded,
+}
+
+impl EventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::BalanceChanged => "balance_changed",
+            EventKind::NewTransaction => "new_transaction",
+            EventKind::ConfirmationProgress => "confirmation_progress",
+            EventKind::TransactionConfirmed => "transaction_confirmed",
+            EventKind::IncomingDeposit => "incoming_deposit",
+            EventKind::BridgeStatusChanged => "bridge_status_changed",
+            EventKind::SecurityEvent => "security_event",
+            EventKind::ApprovalNeeded => "approval_needed",
+        }
+    }
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EventKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "balance_changed" => Ok(EventKind::BalanceChanged),
+            "new_transaction" => Ok(EventKind::NewTransaction),
+            "confirmation_progress" => Ok(EventKind::ConfirmationProgress),
+            "transaction_confirmed" => Ok(EventKind::TransactionConfirmed),
+            "incoming_deposit" => Ok(EventKind::IncomingDeposit),
+            "bridge_status_changed" => Ok(EventKind::BridgeStatusChanged),
+            "security_event" => Ok(EventKind::SecurityEvent),
+            "approval_needed" => Ok(EventKind::ApprovalNeeded),
+            other => Err(anyhow::anyhow!("Unknown event type: {}", other)),
+        }
+    }
+}
+
+/// Publishes `event`; having no subscriber is not an error.
+pub fn publish(bus: &EventBus, event: WalletEvent) {
+    let _ = bus.publish(event);
+}
+
+/// Republishes the security monitor's events on `bus` until the task is
+/// aborted or the monitor goes away.
+pub fn forward_security_events(
+    mut events: broadcast::Receiver<monitoring::SecurityEvent>,
+    bus: Arc<EventBus>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(event) => publish(&bus, WalletEvent::from(&event)),
+                Err(RecvError::Lagged(missed)) => {
+                    warn!("Missed {} security events while forwarding", missed)
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    })
+}
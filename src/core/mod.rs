@@ -1,6 +1,7 @@
 pub mod config;
 pub mod domain;
 pub mod errors;
+pub mod events;
 pub mod key_management;
 pub mod memory_protection;
 pub mod validation;
@@ -26,3 +26,66 @@ fn generate_mnemonic() -> Result<String, WalletError> {
         .map_err(|e| WalletError::MnemonicError(e.to_string()))?;
     Ok(mnemonic.to_string())
 }
+
+/// Integrity manifest for a wallet backup, signed with the artifact signing key.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BackupManifest {
+    pub wallet_name: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Hex SHA-256 of the backup payload.
+    pub payload_sha256: String,
+    pub signature: Option<crate::crypto::signing::PqSignature>,
+}
+
+impl BackupManifest {
+    /// Builds an unsigned manifest describing `payload`.
+    pub fn new(wallet_name: &str, payload: &[u8]) -> Self {
+        Self {
+            wallet_name: wallet_name.to_string(),
+            created_at: chrono::Utc::now(),
+            payload_sha256: payload_digest(payload),
+            signature: None,
+        }
+    }
+
+    /// Bytes covered by the signature (everything except the signature itself).
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        format!(
+            "defi-hot-wallet/backup-manifest/v1\n{}\n{}\n{}",
+            self.wallet_name,
+            self.created_at.to_rfc3339(),
+            self.payload_sha256
+        )
+        .into_bytes()
+    }
+
+    /// Checks that `payload` is the one this manifest was issued for.
+    pub fn matches_payload(&self, payload: &[u8]) -> bool {
+        self.payload_sha256 == payload_digest(payload)
+    }
+}
+
+fn payload_digest(payload: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    hex::encode(Sha256::digest(payload))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_binds_payload() {
+        let manifest = BackupManifest::new("w1", b"seed words");
+        assert!(manifest.matches_payload(b"seed words"));
+        assert!(!manifest.matches_payload(b"other words"));
+    }
+
+    #[test]
+    fn test_signing_bytes_cover_fields() {
+        let manifest = BackupManifest::new("w1", b"seed words");
+        let mut renamed = manifest.clone();
+        renamed.wallet_name = "w2".to_string();
+        assert_ne!(manifest.signing_bytes(), renamed.signing_bytes());
+    }
+}
@@ -13,9 +13,11 @@ use crate::storage::WalletStorageTrait;
 /// (ciphertext, salt, nonce)
 type WalletKeyMaterial = (Vec<u8>, Vec<u8>, Vec<u8>);
 
+/// `encryption_key` protects the wallet's master key unless `quantum_safe` is set.
 pub async fn recover_wallet(
     storage: &Arc<dyn WalletStorageTrait + Send + Sync>,
     quantum_crypto: &crate::crypto::quantum::QuantumSafeEncryption,
+    encryption_key: &[u8],
     wallet_name: &str,
     seed_phrase: &str,
     quantum_safe: bool,
@@ -59,6 +61,7 @@ pub async fn recover_wallet(
     store_wallet_securely(
         storage,
         quantum_crypto,
+        encryption_key,
         &mut encrypted_wallet_data,
         &master_key,
         quantum_safe,
@@ -81,6 +84,7 @@ async fn derive_master_key(mnemonic: &str) -> Result<Vec<u8>, WalletError> {
 async fn store_wallet_securely(
     storage: &Arc<dyn WalletStorageTrait + Send + Sync>,
     quantum_crypto: &crate::crypto::quantum::QuantumSafeEncryption,
+    encryption_key: &[u8],
     wallet_data: &mut SecureWalletData,
     master_key: &[u8; 32],
     quantum_safe: bool,
@@ -91,7 +95,7 @@ async fn store_wallet_securely(
             .map_err(|e| WalletError::CryptoError(e.to_string()))?;
         (encrypted, vec![], vec![])
     } else {
-        encrypt_traditional(master_key, master_key)
+        encrypt_traditional(master_key, encryption_key)
             .map_err(|e| WalletError::CryptoError(e.to_string()))?
     };
 
@@ -58,6 +58,25 @@ impl SecureWalletData {
     }
 }
 
+/// A wallet with a fixed, non-secret 32-byte master key, for unit tests of
+/// code that signs with (or merely receives) wallet data.
+#[cfg(test)]
+pub(crate) fn test_wallet() -> SecureWalletData {
+    SecureWalletData {
+        info: WalletInfo {
+            id: Uuid::new_v4(),
+            name: "test-wallet".to_string(),
+            created_at: Utc::now(),
+            quantum_safe: false,
+            multi_sig_threshold: 1,
+            networks: vec!["eth".to_string(), "solana".to_string()],
+        },
+        encrypted_master_key: vec![7u8; 32],
+        salt: vec![],
+        nonce: vec![],
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
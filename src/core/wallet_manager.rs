@@ -28,26 +28,68 @@ pub fn clear_injected_test_master_keys() {
     *TEST_MASTER_DEFAULT.lock().unwrap() = None;
 }
 // ------------------------------------------------------------------------------
-use tracing::{info, warn};
-
+use ethers::types::transaction::eip712::TypedData;
+use ethers::types::{Address, Bytes, U256};
+use ethers::utils::{format_ether, format_units};
+use serde_json::Value;
+use tracing::{debug, info, warn};
+
+use crate::audit::chain::{chain_digest, verify_chain, ChainVerification};
+use crate::audit::context::AuditContext;
+use crate::audit::export::{self, ExportFormat, ExportSink, SiemExporter};
 use crate::blockchain::{
     bridge::{
         // ...existing code...
-        mock::{EthereumToSolanaBridge, SolanaToEthereumBridge}, // 保持 mock 导入
+        quote::{self, BlockTimes, BridgeQuote},
+        recovery::{BridgeRecovery, RecoveryAction, STUCK_TRANSFER_GRACE},
+        BridgeRelayer,
         BridgeTransaction, // BridgeTransaction 仍在 bridge 模块中定义
         BridgeTransactionStatus,
     },
-    ethereum::EthereumClient,
+    confirmations::ConfirmationTracker,
+    contract::{
+        decimals_call, ContractCall, ContractCallResult, ContractReceipt, EncodedCall, TokenCall,
+    },
+    ethereum::{address_from_public_key, EthereumClient},
+    offline::{DecodedTransfer, OfflineSignedTransaction, OfflineSigningRequest, OfflineToken},
+    simulation::SimulationResult,
     solana::SolanaClient,
-    traits::{BlockchainClient, Bridge}, // 从 traits 导入
+    staking::{estimate_rewards, StakeAction, StakePosition, StakeReceipt, StakingSummary},
+    swap::{parse_token_amount, SwapParams, SwapQuote, SwapReceipt},
+    traits::{BlockchainClient, Bridge, StakingProvider, SwapRouter, UnsignedTransaction}, // 从 traits 导入
 };
-use crate::core::config::WalletConfig;
+use crate::core::config::{split_route, NetworkConfig, WalletConfig};
 use crate::core::errors::WalletError;
+use crate::core::events::{self, EventBus, EventKind, WalletEvent, EVENT_BUS_CAPACITY};
 use crate::core::validation::{validate_address, validate_amount};
+use crate::core::wallet::backup::BackupManifest;
 use crate::core::wallet::{backup, create, recover};
 use crate::core::wallet_info::{SecureWalletData, WalletInfo};
+use crate::crypto::keyfile::EncryptedKeyFile;
+use crate::crypto::message::{self, MessageSignature};
+use crate::crypto::signer::{Signer, SoftwareSigner};
+use crate::crypto::signing::{self, PqKeyPair, SignatureAlgorithm, ARTIFACT_SIGNING_KEY};
 use crate::crypto::{hsm::HSMManager, multisig::MultiSignature, quantum::QuantumSafeEncryption};
-use crate::storage::{WalletMetadata, WalletStorage, WalletStorageTrait};
+use crate::security::approval::{
+    ApprovalNotifier, ApprovalRequest, ApprovalSettings, ApprovalStatus, ApprovalVote,
+    HeldOperation, LogNotifier,
+};
+use crate::security::auth::{AuthService, LoginPolicy};
+use crate::security::compliance::{ComplianceChecker, ComplianceResult, TransactionType};
+use crate::security::policy::{PolicyRequest, SpendingPolicy};
+use crate::security::sanctions::{
+    feeds_from_config, SanctionsIndex, SanctionsList, SanctionsScreener,
+};
+use crate::storage::{
+    AuditLog, AuditQuery, AuditSignature, PolicySpendRecord, SanctionsListRecord, SigningKeyRecord,
+    StakingOperation, SwapRecord, TransactionRecord, WalletMetadata, WalletStorage,
+    WalletStorageTrait, WebhookDelivery, WebhookRecord,
+};
+use crate::tools::generator::{MonitoringConfig, SecurityConfig};
+use crate::walletconnect::{
+    self, PairingUri, Relay, Session, SessionProposal, SessionRequest, WalletConnect,
+};
+use crate::webhooks::{self, DeliveryStatus, WebhookDispatcher};
 
 #[allow(dead_code)]
 fn get_fallback_rpc_url(network: &str) -> Option<String> {
@@ -61,18 +103,166 @@ fn get_fallback_rpc_url(network: &str) -> Option<String> {
     }
 }
 
+/// Form a recipient is remembered in: EVM addresses are case-insensitive,
+/// base58 addresses are not.
+fn recipient_key(address: &str) -> String {
+    if address.starts_with("0x") {
+        address.to_lowercase()
+    } else {
+        address.to_string()
+    }
+}
+
+/// On-chain address of `public_key` on `network`: base58 on Solana, the
+/// Keccak-derived address on EVM networks.
+fn public_key_address(public_key: &[u8], network: &str) -> Result<String, WalletError> {
+    if network.starts_with("solana") {
+        Ok(bs58::encode(public_key).into_string())
+    } else {
+        Ok(format!("{:?}", address_from_public_key(public_key)?))
+    }
+}
+
+/// JSON-RPC error sent to a dApp when an approved request cannot be carried out.
+const WALLETCONNECT_REQUEST_FAILED: i64 = -32000;
+
+/// Most blocks searched for deposits in one balance poll; older blocks of a
+/// longer gap are skipped.
+const MAX_DEPOSIT_SCAN_BLOCKS: u64 = 128;
+
+fn walletconnect_error(e: anyhow::Error) -> WalletError {
+    WalletError::NetworkError(format!("WalletConnect: {}", e))
+}
+
+/// Checks that a dApp request names the session wallet's own account.
+fn ensure_account(requested: &Value, address: &str) -> Result<(), WalletError> {
+    match requested.as_str() {
+        Some(requested) if requested.eq_ignore_ascii_case(address) => Ok(()),
+        _ => Err(WalletError::ValidationError(format!("Request is not for account {}", address))),
+    }
+}
+
+/// What to do with an operation that compliance or the spending policy
+/// says needs sign-off.
+enum Approval<'a> {
+    /// Hold it as this operation until approvers decide.
+    Hold(HeldOperation),
+    /// It is being carried out under this approved request.
+    Granted(&'a str),
+    /// Refuse it: the caller cannot wait for approvers.
+    Refuse,
+}
+
+/// Wallets whose balances are polled for stream subscribers, by name.
+type WatchedBalances = Arc<Mutex<HashMap<String, WatchedWallet>>>;
+
+#[derive(Default)]
+struct WatchedWallet {
+    watchers: usize,
+    /// Public address polled per network, found once when the wallet is
+    /// first watched so polling never decrypts the wallet.
+    addresses: HashMap<String, String>,
+    /// Last balance seen per network.
+    balances: HashMap<String, String>,
+    /// Latest block seen per network; deposits are looked for after it.
+    blocks: HashMap<String, u64>,
+}
+
+/// Keeps a wallet's balance polled while held; see
+/// `WalletManager::watch_balances`.
+pub struct BalanceWatch {
+    wallet: String,
+    watched: WatchedBalances,
+}
+
+impl Drop for BalanceWatch {
+    fn drop(&mut self) {
+        let mut watched = self.watched.lock().expect("balance watch lock poisoned");
+        if let Some(entry) = watched.get_mut(&self.wallet) {
+            entry.watchers -= 1;
+            if entry.watchers == 0 {
+                watched.remove(&self.wallet);
+            }
+        }
+    }
+}
+
+/// A contract call that passed compliance and the spending policy, ready to sign.
+struct CheckedCall<'a> {
+    wallet: WalletMetadata,
+    client: &'a dyn BlockchainClient,
+    signer: SoftwareSigner,
+    unsigned: UnsignedTransaction,
+    policy_request: PolicyRequest,
+    /// The spend reserved by the policy check, released if the call is not sent.
+    reservation: i64,
+}
+
 pub struct WalletManager {
     storage: Arc<dyn WalletStorageTrait + Send + Sync>,
     quantum_crypto: QuantumSafeEncryption,
     _multisig: MultiSignature,
     _hsm: HSMManager,
     blockchain_clients: Arc<HashMap<String, Box<dyn BlockchainClient>>>,
-    #[allow(dead_code)]
     bridges: Arc<HashMap<String, Box<dyn Bridge>>>,
+    bridge_relayer: Arc<BridgeRelayer>,
+    bridge_recovery: BridgeRecovery,
+    networks: HashMap<String, NetworkConfig>,
+    swap_routers: HashMap<String, Box<dyn SwapRouter>>,
+    staking_providers: HashMap<String, Box<dyn StakingProvider>>,
+    compliance: Mutex<ComplianceChecker>,
+    sanctions: Arc<SanctionsScreener>,
+    sanctions_task: Option<tokio::task::JoinHandle<()>>,
+    siem_tasks: Vec<tokio::task::JoinHandle<()>>,
+    auth: AuthService,
+    approval_settings: ApprovalSettings,
+    /// Key id the artifact signing key must have, from the security config.
+    artifact_key_pin: Option<String>,
+    approval_notifier: Arc<dyn ApprovalNotifier>,
+    relayer_task: Option<tokio::task::JoinHandle<()>>,
+    events: Arc<EventBus>,
+    confirmations: Arc<ConfirmationTracker>,
+    webhooks: Arc<WebhookDispatcher>,
+    /// Confirmation polling, webhook delivery and security event forwarding.
+    event_tasks: Vec<tokio::task::JoinHandle<()>>,
+    watched_balances: WatchedBalances,
+    walletconnect: Option<Arc<WalletConnect>>,
+    walletconnect_task: Option<tokio::task::JoinHandle<()>>,
+}
+
+impl Drop for WalletManager {
+    fn drop(&mut self) {
+        if let Some(task) = self.relayer_task.take() {
+            task.abort();
+        }
+        if let Some(task) = self.walletconnect_task.take() {
+            task.abort();
+        }
+        if let Some(task) = self.sanctions_task.take() {
+            task.abort();
+        }
+        for task in self.siem_tasks.drain(..) {
+            task.abort();
+        }
+        for task in self.event_tasks.drain(..) {
+            task.abort();
+        }
+    }
 }
 
 impl WalletManager {
+    /// A manager without bridges; bridge transfers are refused until some
+    /// are configured, see `new_with_bridges`.
     pub async fn new(config: &WalletConfig) -> Result<Self, WalletError> {
+        Self::new_with_bridges(config, HashMap::new()).await
+    }
+
+    /// Like `new`, but with the given bridges keyed `"<from>-<to>"`, e.g. from
+    /// `bridges_from_config` or a `ScriptedBridge` in tests.
+    pub async fn new_with_bridges(
+        config: &WalletConfig,
+        bridges: HashMap<String, Box<dyn Bridge>>,
+    ) -> Result<Self, WalletError> {
         info!("Initializing WalletManager");
 
         let storage: Arc<dyn WalletStorageTrait + Send + Sync> = Arc::new(
@@ -85,16 +275,6 @@ impl WalletManager {
         let multisig = MultiSignature::new();
         let hsm = HSMManager::new().await.map_err(|e| WalletError::Other(e.to_string()))?;
 
-        // Initialize bridges
-        let mut bridges: HashMap<String, Box<dyn Bridge>> = HashMap::new();
-        bridges.insert(
-            "eth-solana".to_string(),
-            Box::new(EthereumToSolanaBridge::new("0x...EthSolBridge...")),
-        );
-        bridges.insert(
-            "solana-eth".to_string(),
-            Box::new(SolanaToEthereumBridge::new("0x...SolEthBridge...")),
-        );
         let bridges = Arc::new(bridges);
 
         let mut blockchain_clients: HashMap<String, Box<dyn BlockchainClient>> = HashMap::new();
@@ -169,19 +349,77 @@ impl WalletManager {
             }
         }
 
-        Ok(Self {
+        let events = Arc::new(EventBus::new(EVENT_BUS_CAPACITY));
+        // Resumes transfers left pending by a previous run, then keeps polling.
+        let bridge_relayer = Arc::new(
+            BridgeRelayer::new(Arc::clone(&storage), Arc::clone(&bridges))
+                .with_events(Arc::clone(&events)),
+        );
+        let relayer_task = Some(Arc::clone(&bridge_relayer).spawn());
+        let blockchain_clients = Arc::new(blockchain_clients);
+        let confirmations = Arc::new(
+            ConfirmationTracker::new(Arc::clone(&storage), Arc::clone(&blockchain_clients))
+                .with_events(Arc::clone(&events)),
+        );
+        let webhooks = Arc::new(WebhookDispatcher::new(Arc::clone(&storage)));
+        let mut event_tasks = vec![Arc::clone(&confirmations).spawn()];
+        event_tasks.extend(Arc::clone(&webhooks).spawn(events.subscribe()));
+        if let Some(monitor) = crate::monitoring::get_security_monitor() {
+            event_tasks
+                .push(events::forward_security_events(monitor.subscribe(), Arc::clone(&events)));
+        }
+        let bridge_recovery = BridgeRecovery::new(Arc::clone(&storage), Arc::clone(&bridges));
+        let auth = AuthService::from_env(Arc::clone(&storage));
+        let sanctions_index = SanctionsIndex::default();
+        let sanctions =
+            Arc::new(SanctionsScreener::new(Arc::clone(&storage), sanctions_index.clone()));
+
+        let manager = Self {
             storage,
             quantum_crypto,
             _multisig: multisig,
             _hsm: hsm,
-            blockchain_clients: Arc::new(blockchain_clients),
+            blockchain_clients,
+            bridge_recovery,
             bridges,
-        })
+            bridge_relayer,
+            networks: config.blockchain.networks.clone(),
+            swap_routers: HashMap::new(),
+            staking_providers: HashMap::new(),
+            compliance: Mutex::new(ComplianceChecker::new().with_sanctions_index(sanctions_index)),
+            sanctions,
+            sanctions_task: None,
+            siem_tasks: Vec::new(),
+            auth,
+            approval_settings: ApprovalSettings::default(),
+            artifact_key_pin: None,
+            approval_notifier: Arc::new(LogNotifier),
+            relayer_task,
+            events,
+            confirmations,
+            webhooks,
+            event_tasks,
+            watched_balances: WatchedBalances::default(),
+            walletconnect: None,
+            walletconnect_task: None,
+        };
+
+        match manager.verify_audit_log().await {
+            Ok(true) => {}
+            Ok(false) => warn!("Audit log does not match its latest signed checkpoint"),
+            Err(e) => warn!("Failed to verify audit log signature: {}", e),
+        }
+        match manager.sanctions.load().await {
+            Ok(count) => info!("Loaded {} sanctioned addresses", count),
+            Err(e) => warn!("Failed to load sanctions lists: {}", e),
+        }
+
+        Ok(manager)
     }
 
     #[cfg(test)]
     pub async fn new_with_storage(
-        _config: &WalletConfig,
+        config: &WalletConfig,
         storage: Arc<dyn WalletStorageTrait + Send + Sync>,
         _test_master_key: Option<Vec<u8>>,
     ) -> Result<Self, WalletError> {
@@ -190,32 +428,159 @@ impl WalletManager {
         let multisig = MultiSignature::new();
         let hsm = HSMManager::new().await.map_err(|e| WalletError::Other(e.to_string()))?;
 
-        let mut bridges: HashMap<String, Box<dyn Bridge>> = HashMap::new();
-        bridges.insert(
-            "eth-solana".to_string(),
-            Box::new(EthereumToSolanaBridge::new("0x...EthSolBridge...")),
+        let bridges = Arc::new(HashMap::new());
+        let events = Arc::new(EventBus::new(EVENT_BUS_CAPACITY));
+        let bridge_relayer = Arc::new(
+            BridgeRelayer::new(Arc::clone(&storage), Arc::clone(&bridges))
+                .with_events(Arc::clone(&events)),
         );
-        bridges.insert(
-            "solana-eth".to_string(),
-            Box::new(SolanaToEthereumBridge::new("0x...SolEthBridge...")),
+        let blockchain_clients = Arc::new(HashMap::new());
+        let confirmations = Arc::new(
+            ConfirmationTracker::new(Arc::clone(&storage), Arc::clone(&blockchain_clients))
+                .with_events(Arc::clone(&events)),
         );
-
+        let webhooks = Arc::new(WebhookDispatcher::new(Arc::clone(&storage)));
+        let bridge_recovery = BridgeRecovery::new(Arc::clone(&storage), Arc::clone(&bridges));
+        let auth = AuthService::from_env(Arc::clone(&storage));
+        let sanctions_index = SanctionsIndex::default();
+        let sanctions =
+            Arc::new(SanctionsScreener::new(Arc::clone(&storage), sanctions_index.clone()));
+
+        // No background tasks: tests drive the relayer with `poll_bridge_transfers`
+        // and confirmations with `poll_transaction_confirmations`.
         Ok(Self {
             storage,
             quantum_crypto,
             _multisig: multisig,
             _hsm: hsm,
-            blockchain_clients: Arc::new(HashMap::new()),
-            bridges: Arc::new(bridges),
+            blockchain_clients,
+            bridge_recovery,
+            bridges,
+            bridge_relayer,
+            networks: config.blockchain.networks.clone(),
+            swap_routers: HashMap::new(),
+            staking_providers: HashMap::new(),
+            compliance: Mutex::new(ComplianceChecker::new().with_sanctions_index(sanctions_index)),
+            sanctions,
+            sanctions_task: None,
+            siem_tasks: Vec::new(),
+            auth,
+            approval_settings: ApprovalSettings::default(),
+            artifact_key_pin: None,
+            approval_notifier: Arc::new(LogNotifier),
+            relayer_task: None,
+            events,
+            confirmations,
+            webhooks,
+            event_tasks: Vec::new(),
+            watched_balances: WatchedBalances::default(),
+            walletconnect: None,
+            walletconnect_task: None,
         })
     }
 
+    /// Users, API keys and tokens for authenticating API requests.
+    pub fn auth(&self) -> &AuthService {
+        &self.auth
+    }
+
+    /// Applies second factor and lockout settings to API logins, the
+    /// approval count and expiry to held operations, and the compliance
+    /// section's sanctioned addresses. Configured sanctions list files are
+    /// imported now and re-read every `sanctions_reload_interval` seconds.
+    /// `artifact_key_id` pins the key backups and audit checkpoints are
+    /// signed with, so a key swapped in the database is refused.
+    pub fn with_security_config(mut self, config: &SecurityConfig) -> Self {
+        self.auth.set_policy(LoginPolicy::from(config));
+        self.approval_settings = ApprovalSettings::from(config);
+        self.artifact_key_pin = config.artifact_key_id.clone();
+        if let Ok(compliance) = self.compliance.get_mut() {
+            for address in &config.compliance.sanctioned_addresses {
+                compliance.add_sanctioned_address(address.clone());
+            }
+        }
+        let feeds = feeds_from_config(&config.compliance);
+        if !feeds.is_empty() {
+            let screener =
+                SanctionsScreener::new(Arc::clone(&self.storage), self.sanctions.index().clone())
+                    .with_feeds(
+                        feeds,
+                        std::time::Duration::from_secs(config.compliance.sanctions_reload_interval),
+                    );
+            self.sanctions = Arc::new(screener);
+            if let Some(task) = self.sanctions_task.replace(Arc::clone(&self.sanctions).spawn()) {
+                task.abort();
+            }
+        }
+        self
+    }
+
+    /// Starts the SIEM exports configured in the monitoring section. An
+    /// export whose endpoint does not parse is skipped with a warning.
+    pub fn with_monitoring_config(mut self, config: &MonitoringConfig) -> Self {
+        for task in self.siem_tasks.drain(..) {
+            task.abort();
+        }
+        for export in &config.siem_exports {
+            match SiemExporter::from_config(Arc::clone(&self.storage), export) {
+                Ok(exporter) => self.siem_tasks.push(Arc::new(exporter).spawn()),
+                Err(e) => warn!("Skipping SIEM export {}: {}", export.name, e),
+            }
+        }
+        self
+    }
+
+    /// Sends approval notifications through `notifier` instead of the log.
+    pub fn with_approval_notifier(mut self, notifier: Arc<dyn ApprovalNotifier>) -> Self {
+        self.approval_notifier = notifier;
+        self
+    }
+
+    /// Enables swaps through the given routers, keyed by network name.
+    pub fn with_swap_routers(mut self, routers: HashMap<String, Box<dyn SwapRouter>>) -> Self {
+        self.swap_routers = routers;
+        self
+    }
+
+    /// Enables staking through the given providers, keyed by network name.
+    pub fn with_staking_providers(
+        mut self,
+        providers: HashMap<String, Box<dyn StakingProvider>>,
+    ) -> Self {
+        self.staking_providers = providers;
+        self
+    }
+
+    /// Enables WalletConnect dApp sessions over `relay` and starts handling
+    /// the messages dApps publish to it.
+    pub fn with_walletconnect(mut self, relay: Arc<dyn Relay>) -> Self {
+        let metadata = walletconnect::Metadata {
+            name: "DeFi Hot Wallet".to_string(),
+            description: "DeFi hot wallet".to_string(),
+            ..Default::default()
+        };
+        let walletconnect = Arc::new(WalletConnect::new(relay, metadata));
+        if let Some(task) = self.walletconnect_task.replace(Arc::clone(&walletconnect).spawn()) {
+            task.abort();
+        }
+        self.walletconnect = Some(walletconnect);
+        self
+    }
+
     pub async fn create_wallet(
         &self,
         name: &str,
         quantum_safe: bool,
     ) -> Result<WalletInfo, WalletError> {
-        create::create_wallet(&self.storage, &self.quantum_crypto, name, quantum_safe).await
+        let encryption_key = self.get_master_key_for_wallet(name)?;
+        create::create_wallet(
+            &self.storage,
+            &self.quantum_crypto,
+            &encryption_key,
+            name,
+            quantum_safe,
+        )
+        .await
     }
 
     pub async fn list_wallets(&self) -> Result<Vec<WalletMetadata>, WalletError> {
@@ -262,33 +627,54 @@ impl WalletManager {
         network: &str,
     ) -> Result<String, WalletError> {
         info!("Getting balance for wallet: {} on network: {}", wallet_name, network);
+        self.balance_on(wallet_name, network).await
+    }
 
-        let mut wallet_data = self.load_wallet_securely(wallet_name).await?;
-
+    async fn balance_on(&self, wallet_name: &str, network: &str) -> Result<String, WalletError> {
         let client = self.blockchain_clients.get(network).ok_or_else(|| {
             WalletError::BlockchainError(format!("Unsupported network: {}", network))
         })?;
 
-        let address = self
-            .derive_address(&wallet_data.encrypted_master_key, network)
-            .map_err(|e| WalletError::AddressError(e.to_string()))?;
+        let signer = self.wallet_signer(wallet_name, network).await?;
+        let address = self.signer_address(&signer, network).await?;
 
-        let balance = client
-            .get_balance(&address)
-            .await
-            .map_err(|e| WalletError::BlockchainError(e.to_string()))?;
+        client.get_balance(&address).await.map_err(|e| WalletError::BlockchainError(e.to_string()))
+    }
 
-        wallet_data.zeroize();
+    /// Sends a transfer after simulating it, refusing if the simulation reverts.
+    pub async fn send_transaction(
+        &self,
+        wallet_name: &str,
+        to_address: &str,
+        amount: &str,
+        network: &str,
+    ) -> Result<String, WalletError> {
+        self.send_transaction_with_preflight(wallet_name, to_address, amount, network, false).await
+    }
 
-        Ok(balance)
+    /// Sends a transfer after simulating it. A reverting simulation stops the
+    /// send unless `allow_revert` is set; networks whose client cannot
+    /// simulate are sent without a preview. A transfer that needs sign-off
+    /// is held and `PendingApproval` returned with the request id.
+    pub async fn send_transaction_with_preflight(
+        &self,
+        wallet_name: &str,
+        to_address: &str,
+        amount: &str,
+        network: &str,
+        allow_revert: bool,
+    ) -> Result<String, WalletError> {
+        self.execute_transfer(wallet_name, to_address, amount, network, allow_revert, None).await
     }
 
-    pub async fn send_transaction(
+    async fn execute_transfer(
         &self,
         wallet_name: &str,
         to_address: &str,
         amount: &str,
         network: &str,
+        allow_revert: bool,
+        approved: Option<&str>,
     ) -> Result<String, WalletError> {
         info!(
             "Sending transaction from wallet: {} to: {} amount: {} on: {}",
@@ -297,185 +683,2669 @@ impl WalletManager {
 
         validate_address(to_address, network)
             .map_err(|e| WalletError::ValidationError(e.to_string()))?;
-        validate_amount(amount).map_err(|e| WalletError::ValidationError(e.to_string()))?;
-
-        let mut wallet_data = self.load_wallet_securely(wallet_name).await?;
+        let value =
+            validate_amount(amount).map_err(|e| WalletError::ValidationError(e.to_string()))?;
+        let wallet = self.get_wallet_by_name(wallet_name).await?.ok_or_else(|| {
+            WalletError::StorageError(format!("Wallet not found: {}", wallet_name))
+        })?;
+        let compliance =
+            self.check_compliance(&wallet.id, &TransactionType::Transfer, value, to_address)?;
 
         let client = self.blockchain_clients.get(network).ok_or_else(|| {
             WalletError::BlockchainError(format!("Unsupported network: {}", network))
         })?;
+        let policy_request = PolicyRequest {
+            transaction_type: TransactionType::Transfer,
+            network: network.to_string(),
+            asset: client.get_native_token().to_string(),
+            amount: value,
+            recipient: Some(to_address.to_string()),
+        };
+        let (policy, reservation) = self.check_spending_policy(&wallet.id, &policy_request).await?;
+        let approval = match approved {
+            Some(id) => Approval::Granted(id),
+            None => Approval::Hold(HeldOperation::Transfer {
+                to_address: to_address.to_string(),
+                amount: amount.to_string(),
+                network: network.to_string(),
+                allow_revert,
+            }),
+        };
+        let sent = async {
+            self.require_approval(&wallet, [compliance, policy], approval).await?;
 
-        let private_key = self
-            .derive_private_key(&wallet_data.encrypted_master_key, network)
-            .map_err(|e| WalletError::KeyDerivationError(e.to_string()))?;
-        let tx_hash = client
-            .send_transaction(&private_key, to_address, amount)
-            .await
-            .map_err(|e| WalletError::BlockchainError(e.to_string()))?;
+            let mut wallet_data = self.load_wallet_securely(wallet_name).await?;
+            let signer = self.software_signer(&wallet_data.encrypted_master_key, network)?;
+            wallet_data.zeroize();
 
-        wallet_data.zeroize();
+            let public_key = signer.public_key().await?;
+            let unsigned = client.build_transaction(&public_key, to_address, amount).await?;
+            Self::preflight(client.as_ref(), &unsigned, allow_revert, None).await?;
+            Self::sign_and_broadcast(client.as_ref(), &signer, &unsigned).await
+        };
+        let tx_hash = self.release_on_failure(reservation, sent.await).await?;
+        self.record_policy_spend(&wallet, reservation, &tx_hash, &policy_request).await;
+        self.audit_sent(
+            &wallet.id,
+            "transaction_sent",
+            format!("{} {} to {} on {}", tx_hash, amount, to_address, network),
+        )
+        .await;
 
         info!("Transaction sent with hash: {}", tx_hash);
         Ok(tx_hash)
     }
 
-    pub async fn bridge_assets(
+    /// Previews a transfer: whether it would revert, the token events it
+    /// would emit and the resulting balance changes. Nothing is signed.
+    pub async fn simulate_transaction(
         &self,
         wallet_name: &str,
-        from_chain: &str,
-        to_chain: &str,
-        token: &str,
+        to_address: &str,
         amount: &str,
-    ) -> Result<String, WalletError> {
-        info!(
-            "Bridging assets from wallet: {} from: {} to: {} token: {} amount: {}",
-            wallet_name, from_chain, to_chain, token, amount
-        );
-
-        let mut wallet_data = self.load_wallet_securely(wallet_name).await?;
+        network: &str,
+    ) -> Result<SimulationResult, WalletError> {
+        validate_address(to_address, network)
+            .map_err(|e| WalletError::ValidationError(e.to_string()))?;
+        validate_amount(amount).map_err(|e| WalletError::ValidationError(e.to_string()))?;
 
-        let bridge_key = format!("{}-{}", from_chain, to_chain);
-        let bridge = self.bridges.get(&bridge_key).ok_or_else(|| {
-            WalletError::BlockchainError(format!("Unsupported bridge: {}", bridge_key))
+        let client = self.blockchain_clients.get(network).ok_or_else(|| {
+            WalletError::BlockchainError(format!("Unsupported network: {}", network))
         })?;
-
-        let tx_hash = bridge
-            .transfer_across_chains(from_chain, to_chain, token, amount, &wallet_data)
-            .await?;
-
-        wallet_data.zeroize();
-        Ok(tx_hash)
+        let signer = self.wallet_signer(wallet_name, network).await?;
+        let public_key = signer.public_key().await?;
+        let unsigned = client.build_transaction(&public_key, to_address, amount).await?;
+        client.simulate_transaction(&unsigned).await?.ok_or_else(|| {
+            WalletError::BlockchainError(format!("Simulation is not supported on {}", network))
+        })
     }
 
-    pub async fn get_block_number(&self, network: &str) -> Result<u64, WalletError> {
+    /// Calls a contract function read-only from the wallet's address and
+    /// decodes its return values or revert reason.
+    pub async fn call_contract(
+        &self,
+        wallet_name: &str,
+        network: &str,
+        call: &ContractCall,
+    ) -> Result<ContractCallResult, WalletError> {
+        validate_address(&call.contract, network)
+            .map_err(|e| WalletError::ValidationError(e.to_string()))?;
+        let encoded = call.encode().map_err(|e| WalletError::ValidationError(e.to_string()))?;
+
         let client = self.blockchain_clients.get(network).ok_or_else(|| {
             WalletError::BlockchainError(format!("Unsupported network: {}", network))
         })?;
-        client.get_block_number().await.map_err(|e| WalletError::BlockchainError(e.to_string()))
+        let signer = self.wallet_signer(wallet_name, network).await?;
+        let public_key = signer.public_key().await?;
+        let value = call.value.as_deref().unwrap_or("0");
+        let simulation =
+            client.call_contract(&public_key, &call.contract, encoded.data.clone(), value).await?;
+        encoded.call_result(simulation).map_err(|e| WalletError::BlockchainError(e.to_string()))
     }
 
-    pub async fn check_bridge_status(
+    /// Sends a contract call through the same checks as a transfer: address
+    /// and amount validation, compliance and approval, and a pre-flight
+    /// simulation (unless `simulate` is off) that refuses reverts unless
+    /// `allow_revert` is set.
+    pub async fn send_contract_transaction(
         &self,
-        bridge_tx_id: &str,
-    ) -> Result<BridgeTransactionStatus, WalletError> {
-        self.storage
-            .get_bridge_transaction(bridge_tx_id)
+        wallet_name: &str,
+        network: &str,
+        call: &ContractCall,
+        simulate: bool,
+        allow_revert: bool,
+    ) -> Result<ContractReceipt, WalletError> {
+        self.execute_contract_transaction(wallet_name, network, call, simulate, allow_revert, None)
             .await
-            .map(|tx| tx.status)
-            .map_err(|e| WalletError::StorageError(e.to_string()))
     }
 
-    pub async fn get_bridge_transaction_status(
+    async fn execute_contract_transaction(
         &self,
-        bridge_tx_id: &str,
-    ) -> Result<BridgeTransaction, WalletError> {
-        self.storage
-            .get_bridge_transaction(bridge_tx_id)
-            .await
-            .map_err(|e| WalletError::StorageError(e.to_string()))
-    }
+        wallet_name: &str,
+        network: &str,
+        call: &ContractCall,
+        simulate: bool,
+        allow_revert: bool,
+        approved: Option<&str>,
+    ) -> Result<ContractReceipt, WalletError> {
+        info!(
+            "Calling {} on contract {} from wallet: {} on: {}",
+            call.function, call.contract, wallet_name, network
+        );
 
-    pub async fn update_bridge_transaction_status(
-        &self,
-        bridge_tx_id: &str,
-        status: BridgeTransactionStatus,
-        source_tx_hash: Option<String>,
-    ) -> Result<(), WalletError> {
-        info!("Updating bridge tx {} status to {:?}", bridge_tx_id, status);
-        self.storage
-            .update_bridge_transaction_status(bridge_tx_id, status, source_tx_hash)
-            .await
-            .map_err(|e| WalletError::StorageError(e.to_string()))
+        validate_address(&call.contract, network)
+            .map_err(|e| WalletError::ValidationError(e.to_string()))?;
+        if let Some(value) = call.value.as_deref() {
+            validate_amount(value).map_err(|e| WalletError::ValidationError(e.to_string()))?;
+        }
+        let encoded = call.encode().map_err(|e| WalletError::ValidationError(e.to_string()))?;
+        let approval = match approved {
+            Some(id) => Approval::Granted(id),
+            None => Approval::Hold(HeldOperation::ContractCall {
+                network: network.to_string(),
+                call: call.clone(),
+                simulate,
+                allow_revert,
+            }),
+        };
+        let checked = self
+            .build_checked_call(
+                wallet_name,
+                network,
+                &call.contract,
+                encoded.data.clone(),
+                call.value.as_deref().unwrap_or("0"),
+                approval,
+            )
+            .await?;
+        let simulation = if simulate {
+            let simulation =
+                Self::preflight(checked.client, &checked.unsigned, allow_revert, Some(&encoded))
+                    .await;
+            self.release_on_failure(checked.reservation, simulation).await?
+        } else {
+            None
+        };
+        let tx_hash = self.send_checked_call(&checked).await?;
+
+        info!("Contract call {} sent with hash: {}", call.function, tx_hash);
+        Ok(ContractReceipt {
+            tx_hash,
+            network: network.to_string(),
+            contract: call.contract.clone(),
+            function: encoded.function.name.clone(),
+            simulation,
+        })
     }
 
-    pub fn calculate_bridge_fee(
+    /// Checks compliance and the spending policy for a call to `to`, see
+    /// `check_transfer`, and builds it from the wallet's account. `approval`
+    /// says what happens when the call needs sign-off.
+    async fn build_checked_call(
         &self,
-        from_chain: &str,
-        to_chain: &str,
-        _token: &str,
-        amount: &str,
-    ) -> Result<(String, chrono::DateTime<chrono::Utc>), WalletError> {
-        let amount_decimal =
-            amount.parse::<f64>().map_err(|e| WalletError::ValidationError(e.to_string()))?;
-        let fee = (amount_decimal * 0.01).to_string();
+        wallet_name: &str,
+        network: &str,
+        to: &str,
+        data: Bytes,
+        value: &str,
+        approval: Approval<'_>,
+    ) -> Result<CheckedCall<'_>, WalletError> {
+        let wallet = self.get_wallet_by_name(wallet_name).await?.ok_or_else(|| {
+            WalletError::StorageError(format!("Wallet not found: {}", wallet_name))
+        })?;
+        let native_amount = value
+            .parse::<f64>()
+            .ok()
+            .filter(|amount| amount.is_finite() && *amount >= 0.0)
+            .ok_or_else(|| WalletError::ValidationError(format!("Invalid value: {}", value)))?;
+        let token_call =
+            TokenCall::decode(&data).map_err(|e| WalletError::ValidationError(e.to_string()))?;
+
+        let signer = self.wallet_signer(wallet_name, network).await?;
+        let public_key = signer.public_key().await?;
+        let (policy_request, reservation) = self
+            .check_transfer(
+                &wallet,
+                network,
+                &public_key,
+                to,
+                native_amount,
+                token_call.as_ref(),
+                approval,
+            )
+            .await?;
+        let client = self.blockchain_clients.get(network).ok_or_else(|| {
+            WalletError::BlockchainError(format!("Unsupported network: {}", network))
+        })?;
+        let unsigned = client.build_contract_transaction(&public_key, to, data, value).await;
+        let unsigned = self.release_on_failure(reservation, unsigned).await?;
+        Ok(CheckedCall {
+            wallet,
+            client: client.as_ref(),
+            signer,
+            unsigned,
+            policy_request,
+            reservation,
+        })
+    }
 
-        let estimated_blocks = match (from_chain, to_chain) {
-            ("eth", _) => 20,
-            ("solana", _) => 32,
-            ("bsc", _) => 40,
-            _ => 30,
+    /// `decimals()` of the ERC-20 token at `token`, read on chain.
+    async fn token_decimals(
+        client: &dyn BlockchainClient,
+        public_key: &[u8],
+        token: &str,
+    ) -> Result<u32, WalletError> {
+        let result = client.call_contract(public_key, token, decimals_call(), "0").await?;
+        let decimals = if result.success && result.return_data.len() == 32 {
+            Some(U256::from_big_endian(&result.return_data)).filter(|d| *d <= U256::from(77))
+        } else {
+            None
         };
-
-        let now = chrono::Utc::now();
-        let estimated_time = now + chrono::Duration::seconds((estimated_blocks * 6) as i64);
-
-        Ok((fee, estimated_time))
+        decimals.map(|d| d.as_u32()).ok_or_else(|| {
+            WalletError::ValidationError(format!("Cannot read the decimals of token {}", token))
+        })
     }
 
-    #[allow(dead_code)]
-    fn start_bridge_monitor(&self, bridge_tx_id: String) {
-        let storage = Arc::clone(&self.storage);
-
-        tokio::spawn(async move {
-            info!("Starting bridge monitor for tx: {}", bridge_tx_id);
-            for _ in 0..20 {
-                tokio::time::sleep(std::time::Duration::from_secs(30)).await;
-                if let Ok(tx) = storage.get_bridge_transaction(&bridge_tx_id).await {
-                    if tx.status == BridgeTransactionStatus::Completed {
-                        break;
-                    }
-                }
-            }
-            info!("Bridge monitor completed for tx: {}", bridge_tx_id);
-        });
+    /// Signs and broadcasts a call built by `build_checked_call`, counting
+    /// it against the wallet's velocity limits.
+    async fn send_checked_call(&self, checked: &CheckedCall<'_>) -> Result<String, WalletError> {
+        let sent = Self::sign_and_broadcast(checked.client, &checked.signer, &checked.unsigned);
+        let tx_hash = self.release_on_failure(checked.reservation, sent.await).await?;
+        self.record_policy_spend(
+            &checked.wallet,
+            checked.reservation,
+            &tx_hash,
+            &checked.policy_request,
+        )
+        .await;
+        self.audit_sent(
+            &checked.wallet.id,
+            "contract_transaction_sent",
+            format!(
+                "{} to {} on {}",
+                tx_hash,
+                checked.policy_request.recipient.as_deref().unwrap_or("contract"),
+                checked.policy_request.network
+            ),
+        )
+        .await;
+        Ok(tx_hash)
     }
 
-    pub fn derive_address(&self, master_key: &[u8], network: &str) -> Result<String, WalletError> {
-        match network {
-            "eth" => {
-                let addr_bytes = if master_key.len() >= 20 {
-                    master_key[..20].to_vec()
-                } else {
-                    let mut v = vec![0u8; 20];
-                    v[..master_key.len()].copy_from_slice(master_key);
-                    v
-                };
-                Ok(format!("0x{}", hex::encode(&addr_bytes)))
+    /// Simulates `unsigned` before it is signed, refusing a revert or a
+    /// failed simulation unless `allow_revert` is set. Reverts of contract
+    /// calls are decoded with the call's ABI. Returns `None` when the network
+    /// cannot simulate.
+    async fn preflight(
+        client: &dyn BlockchainClient,
+        unsigned: &UnsignedTransaction,
+        allow_revert: bool,
+        call: Option<&EncodedCall>,
+    ) -> Result<Option<SimulationResult>, WalletError> {
+        let mut simulation = match client.simulate_transaction(unsigned).await {
+            Ok(Some(simulation)) => simulation,
+            Ok(None) => {
+                debug!("Sending without simulation on {}", client.get_network_name());
+                return Ok(None);
             }
-            "solana" => {
-                let key_bytes = if master_key.len() >= 32 {
-                    master_key[..32].to_vec()
-                } else {
-                    let mut v = vec![0u8; 32];
-                    v[..master_key.len()].copy_from_slice(master_key);
-                    v
-                };
-                Ok(bs58::encode(&key_bytes).into_string())
+            Err(e) if allow_revert => {
+                warn!("Sending without a simulation on {}: {}", client.get_network_name(), e);
+                return Ok(None);
             }
-            _ => Err(WalletError::ValidationError(format!("Unsupported network: {}", network))),
+            Err(e) => {
+                return Err(WalletError::ValidationError(format!(
+                    "Transaction could not be simulated: {}",
+                    e
+                )))
+            }
+        };
+        if !simulation.success {
+            if let Some(reason) = call.and_then(|c| c.decode_revert(&simulation.return_data)) {
+                simulation.revert_reason = Some(reason);
+            }
+            if !allow_revert {
+                return Err(WalletError::ValidationError(format!(
+                    "Transaction would revert: {}",
+                    simulation.failure()
+                )));
+            }
+            warn!("Sending transaction that simulates as reverted: {}", simulation.failure());
         }
+        Ok(Some(simulation))
     }
 
-    fn derive_private_key(&self, master_key: &[u8], network: &str) -> Result<Vec<u8>, WalletError> {
-        use sha2::{Digest, Sha256};
-        let mut hasher = Sha256::new();
-        hasher.update(master_key);
-        hasher.update(network.as_bytes());
-        Ok(hasher.finalize().to_vec())
+    async fn sign_and_broadcast(
+        client: &dyn BlockchainClient,
+        signer: &dyn Signer,
+        unsigned: &UnsignedTransaction,
+    ) -> Result<String, WalletError> {
+        let signed = signer.sign_transaction(unsigned).await?;
+        client
+            .broadcast_transaction(&signed)
+            .await
+            .map_err(|e| WalletError::BlockchainError(e.to_string()))
     }
 
-    async fn load_wallet_securely(
+    /// Sends a transfer from `wallet_name` signed by an external `Signer`
+    /// (HSM, MPC, remote service), so the key never has to be loaded by this
+    /// process. It is checked like any other transfer of the wallet.
+    pub async fn send_transaction_with_signer(
         &self,
         wallet_name: &str,
-    ) -> Result<SecureWalletData, WalletError> {
-        let (serialized_data, quantum_safe) = self
-            .storage
-            .load_wallet(wallet_name)
-            .await
-            .map_err(|e| WalletError::StorageError(e.to_string()))?;
-
-        let mut wallet_data: SecureWalletData = bincode::deserialize(&serialized_data)
-            .map_err(|e| WalletError::SerializationError(e.to_string()))?;
+        signer: &dyn Signer,
+        to_address: &str,
+        amount: &str,
+        network: &str,
+    ) -> Result<String, WalletError> {
+        info!("Sending transaction to: {} amount: {} on: {}", to_address, amount, network);
+
+        validate_address(to_address, network)
+            .map_err(|e| WalletError::ValidationError(e.to_string()))?;
+        let value =
+            validate_amount(amount).map_err(|e| WalletError::ValidationError(e.to_string()))?;
+        let wallet = self.get_wallet_by_name(wallet_name).await?.ok_or_else(|| {
+            WalletError::StorageError(format!("Wallet not found: {}", wallet_name))
+        })?;
+        let client = self.blockchain_clients.get(network).ok_or_else(|| {
+            WalletError::BlockchainError(format!("Unsupported network: {}", network))
+        })?;
+        let public_key = signer.public_key().await?;
+        let (policy_request, reservation) = self
+            .check_transfer(
+                &wallet,
+                network,
+                &public_key,
+                to_address,
+                value,
+                None,
+                Approval::Refuse,
+            )
+            .await?;
+
+        let sent = client
+            .send_transaction(signer, to_address, amount)
+            .await
+            .map_err(|e| WalletError::BlockchainError(e.to_string()));
+        let tx_hash = self.release_on_failure(reservation, sent).await?;
+        self.record_policy_spend(&wallet, reservation, &tx_hash, &policy_request).await;
+        self.audit_sent(
+            &wallet.id,
+            "transaction_sent",
+            format!("{} {} to {} on {}", tx_hash, amount, to_address, network),
+        )
+        .await;
+
+        info!("Transaction sent with hash: {}", tx_hash);
+        Ok(tx_hash)
+    }
+
+    /// Builds an unsigned transfer from `wallet_name` for signing on an
+    /// offline machine holding the wallet's key on `network`, exported with
+    /// `export_key_file`. `from_public_key` must be that key. With `token`
+    /// the transfer is an ERC-20 `transfer` of `amount` of the token at that
+    /// address. The transfer is checked now and again on broadcast.
+    pub async fn prepare_offline_transaction(
+        &self,
+        wallet_name: &str,
+        from_public_key: &[u8],
+        to_address: &str,
+        amount: &str,
+        network: &str,
+        token: Option<&str>,
+    ) -> Result<OfflineSigningRequest, WalletError> {
+        info!(
+            "Preparing offline transaction to: {} amount: {} on: {}",
+            to_address, amount, network
+        );
+
+        validate_address(to_address, network)
+            .map_err(|e| WalletError::ValidationError(e.to_string()))?;
+        let value =
+            validate_amount(amount).map_err(|e| WalletError::ValidationError(e.to_string()))?;
+        let wallet = self.get_wallet_by_name(wallet_name).await?.ok_or_else(|| {
+            WalletError::StorageError(format!("Wallet not found: {}", wallet_name))
+        })?;
+        let client = self.blockchain_clients.get(network).ok_or_else(|| {
+            WalletError::BlockchainError(format!("Unsupported network: {}", network))
+        })?;
+        let signer = self.wallet_signer(wallet_name, network).await?;
+        if public_key_address(from_public_key, network).ok()
+            != Some(self.signer_address(&signer, network).await?)
+        {
+            return Err(WalletError::ValidationError(format!(
+                "Public key is not the key of wallet {} on {}",
+                wallet_name, network
+            )));
+        }
+
+        let token = match token {
+            Some(_) if network.starts_with("solana") => {
+                return Err(WalletError::ValidationError(
+                    "Token transfers can only be signed offline on EVM networks".to_string(),
+                ))
+            }
+            Some(contract) => {
+                validate_address(contract, network)
+                    .map_err(|e| WalletError::ValidationError(e.to_string()))?;
+                let decimals =
+                    Self::token_decimals(client.as_ref(), from_public_key, contract).await? as u8;
+                let call = TokenCall::Transfer {
+                    to: to_address.parse::<Address>().map_err(|e| {
+                        WalletError::AddressError(format!("Invalid recipient address: {}", e))
+                    })?,
+                    amount: parse_token_amount(amount, decimals)
+                        .map_err(|e| WalletError::ValidationError(e.to_string()))?,
+                };
+                Some((OfflineToken { contract: contract.to_string(), decimals }, call))
+            }
+            None => None,
+        };
+        let (to, value, call) = match &token {
+            Some((token, call)) => (token.contract.as_str(), 0.0, Some(call)),
+            None => (to_address, value, None),
+        };
+        let (_, reservation) = self
+            .check_transfer(&wallet, network, from_public_key, to, value, call, Approval::Refuse)
+            .await?;
+        // nothing is sent yet; the spend is reserved again on broadcast
+        self.release_policy_spend(reservation).await;
+
+        let request = match token {
+            Some((token, call)) => {
+                let tx = client
+                    .build_contract_transaction(
+                        from_public_key,
+                        &token.contract,
+                        call.encode(),
+                        "0",
+                    )
+                    .await?;
+                OfflineSigningRequest::new(
+                    wallet_name,
+                    network,
+                    from_public_key,
+                    to_address,
+                    amount,
+                    tx,
+                )
+                .with_token(token)
+            }
+            None => {
+                let tx = client.build_transaction(from_public_key, to_address, amount).await?;
+                OfflineSigningRequest::new(
+                    wallet_name,
+                    network,
+                    from_public_key,
+                    to_address,
+                    amount,
+                    tx,
+                )
+            }
+        };
+        Ok(request)
+    }
+
+    /// Broadcasts a transaction signed offline. The sender must be the
+    /// wallet's account on the network. The recipient and amount, or the
+    /// ERC-20 call, are decoded from the signed transaction and checked
+    /// against the wallet's compliance rules and spending policy again before
+    /// it is sent.
+    pub async fn broadcast_offline_transaction(
+        &self,
+        signed: &OfflineSignedTransaction,
+    ) -> Result<String, WalletError> {
+        let client = self.blockchain_clients.get(&signed.network).ok_or_else(|| {
+            WalletError::BlockchainError(format!("Unsupported network: {}", signed.network))
+        })?;
+
+        let decoded = DecodedTransfer::from_signed(&signed.tx)?;
+        let chain_id = self.networks.get(&signed.network).and_then(|config| config.chain_id);
+        if chain_id.is_some() && decoded.chain_id != chain_id {
+            return Err(WalletError::ValidationError(format!(
+                "Transaction for chain {:?} cannot be broadcast on {}",
+                decoded.chain_id, signed.network
+            )));
+        }
+        let value: f64 = decoded.amount.parse().map_err(|_| {
+            WalletError::ValidationError(format!("Invalid amount: {}", decoded.amount))
+        })?;
+        let wallet = self.get_wallet_by_name(&signed.wallet).await?.ok_or_else(|| {
+            WalletError::StorageError(format!("Wallet not found: {}", signed.wallet))
+        })?;
+        let signer = self.wallet_signer(&signed.wallet, &signed.network).await?;
+        let address = self.signer_address(&signer, &signed.network).await?;
+        if decoded.from.as_deref() != Some(address.as_str()) {
+            return Err(WalletError::ValidationError(format!(
+                "Transaction is signed by {} rather than wallet {} ({})",
+                decoded.from.as_deref().unwrap_or("nobody"),
+                signed.wallet,
+                address
+            )));
+        }
+        let public_key = signer.public_key().await?;
+        let (policy_request, reservation) = self
+            .check_transfer(
+                &wallet,
+                &signed.network,
+                &public_key,
+                &decoded.to,
+                value,
+                decoded.token_call.as_ref(),
+                Approval::Refuse,
+            )
+            .await?;
+
+        let sent = client
+            .broadcast_transaction(&signed.tx)
+            .await
+            .map_err(|e| WalletError::BlockchainError(e.to_string()));
+        let tx_hash = self.release_on_failure(reservation, sent).await?;
+        self.record_policy_spend(&wallet, reservation, &tx_hash, &policy_request).await;
+        self.audit_sent(
+            &wallet.id,
+            "transaction_sent",
+            format!(
+                "{} {} {} to {} on {} (signed offline)",
+                tx_hash,
+                policy_request.amount,
+                policy_request.asset,
+                policy_request.recipient.as_deref().unwrap_or_default(),
+                signed.network
+            ),
+        )
+        .await;
+
+        info!("Offline-signed transaction broadcast with hash: {}", tx_hash);
+        Ok(tx_hash)
+    }
+
+    /// Compliance and spending policy checks for a transfer from `wallet` to
+    /// `to`. It is checked as a transfer of `value` of the native token to
+    /// `to`, or, for an ERC-20 `transfer`, `transferFrom` or `approve` call
+    /// on the contract `to`, of the decoded amount of the token (the contract
+    /// address is the policy asset) to the decoded recipient or spender.
+    /// `approval` says what happens when the transfer needs sign-off; a
+    /// transfer signed outside this process passes `Approval::Refuse`, as
+    /// nothing here can sign it later. Returns the request to record the
+    /// spend against once it is sent, and the spend's reservation.
+    #[allow(clippy::too_many_arguments)]
+    async fn check_transfer(
+        &self,
+        wallet: &WalletMetadata,
+        network: &str,
+        public_key: &[u8],
+        to: &str,
+        value: f64,
+        token_call: Option<&TokenCall>,
+        approval: Approval<'_>,
+    ) -> Result<(PolicyRequest, i64), WalletError> {
+        if token_call.is_some() && value > 0.0 {
+            return Err(WalletError::ValidationError(
+                "ERC-20 token calls cannot send the native token".to_string(),
+            ));
+        }
+        let client = self.blockchain_clients.get(network).ok_or_else(|| {
+            WalletError::BlockchainError(format!("Unsupported network: {}", network))
+        })?;
+        let (asset, amount, recipient) = match token_call {
+            Some(call) => {
+                let decimals = Self::token_decimals(client.as_ref(), public_key, to).await?;
+                let amount = format_units(call.amount(), decimals)
+                    .ok()
+                    .and_then(|amount| amount.parse::<f64>().ok())
+                    .ok_or_else(|| {
+                        WalletError::ValidationError(format!(
+                            "Invalid token amount: {}",
+                            call.amount()
+                        ))
+                    })?;
+                (to.to_lowercase(), amount, format!("{:?}", call.counterparty()))
+            }
+            None => (client.get_native_token().to_string(), value, to.to_string()),
+        };
+
+        let compliance =
+            self.check_compliance(&wallet.id, &TransactionType::Transfer, amount, &recipient)?;
+        // the token contract itself is screened too
+        let contract_compliance = match token_call {
+            Some(_) => self.check_compliance(&wallet.id, &TransactionType::Transfer, amount, to)?,
+            None => None,
+        };
+        let policy_request = PolicyRequest {
+            transaction_type: TransactionType::Transfer,
+            network: network.to_string(),
+            asset,
+            amount,
+            recipient: Some(recipient),
+        };
+        let (policy, reservation) = self.check_spending_policy(&wallet.id, &policy_request).await?;
+        let approved = self
+            .require_approval(wallet, [compliance, contract_compliance, policy], approval)
+            .await;
+        self.release_on_failure(reservation, approved).await?;
+        Ok((policy_request, reservation))
+    }
+
+    /// Signs an off-chain message with the wallet's key on `network`:
+    /// EIP-191 `personal_sign` on EVM networks, raw Ed25519 on Solana.
+    pub async fn sign_message(
+        &self,
+        wallet_name: &str,
+        network: &str,
+        message: &[u8],
+    ) -> Result<MessageSignature, WalletError> {
+        info!("Signing message with wallet: {} on: {}", wallet_name, network);
+
+        let signer = self.wallet_signer(wallet_name, network).await?;
+        let address = self.signer_address(&signer, network).await?;
+        let signature = if network.starts_with("solana") {
+            message::sign_solana_message(&signer, message).await?
+        } else {
+            message::sign_personal_message(&signer, message).await?.to_vec()
+        };
+
+        Ok(MessageSignature {
+            network: network.to_string(),
+            address,
+            signature: format!("0x{}", hex::encode(signature)),
+        })
+    }
+
+    /// Signs EIP-712 typed data with the wallet's key on an EVM network.
+    pub async fn sign_typed_data(
+        &self,
+        wallet_name: &str,
+        network: &str,
+        typed_data: &TypedData,
+    ) -> Result<MessageSignature, WalletError> {
+        info!("Signing typed data with wallet: {} on: {}", wallet_name, network);
+
+        if network.starts_with("solana") {
+            return Err(WalletError::ValidationError(
+                "EIP-712 typed data is only supported on EVM networks".to_string(),
+            ));
+        }
+
+        let signer = self.wallet_signer(wallet_name, network).await?;
+        let address = self.signer_address(&signer, network).await?;
+        let signature = message::sign_typed_data(&signer, typed_data).await?;
+
+        Ok(MessageSignature {
+            network: network.to_string(),
+            address,
+            signature: format!("0x{}", hex::encode(signature.to_vec())),
+        })
+    }
+
+    /// Checks a message signature from `sign_message` against `address`.
+    pub fn verify_message(
+        &self,
+        network: &str,
+        message: &[u8],
+        signature: &[u8],
+        address: &str,
+    ) -> bool {
+        if network.starts_with("solana") {
+            message::verify_solana_message(message, signature, address)
+        } else {
+            message::verify_personal_message(message, signature, address)
+        }
+    }
+
+    /// Checks a signature from `sign_typed_data` against `address`.
+    pub fn verify_typed_data(
+        &self,
+        typed_data: &TypedData,
+        signature: &[u8],
+        address: &str,
+    ) -> bool {
+        message::verify_typed_data(typed_data, signature, address)
+    }
+
+    async fn wallet_signer(
+        &self,
+        wallet_name: &str,
+        network: &str,
+    ) -> Result<SoftwareSigner, WalletError> {
+        let mut wallet_data = self.load_wallet_securely(wallet_name).await?;
+        let signer = self.software_signer(&wallet_data.encrypted_master_key, network);
+        wallet_data.zeroize();
+        signer
+    }
+
+    /// On-chain address controlled by `signer` on `network`.
+    async fn signer_address(
+        &self,
+        signer: &dyn Signer,
+        network: &str,
+    ) -> Result<String, WalletError> {
+        public_key_address(&signer.public_key().await?, network)
+    }
+
+    /// Starts a bridge transfer and returns its id. A transfer that needs
+    /// sign-off is held and `PendingApproval` returned with the request id.
+    pub async fn bridge_assets(
+        &self,
+        wallet_name: &str,
+        from_chain: &str,
+        to_chain: &str,
+        token: &str,
+        amount: &str,
+    ) -> Result<String, WalletError> {
+        self.execute_bridge(wallet_name, from_chain, to_chain, token, amount, None).await
+    }
+
+    async fn execute_bridge(
+        &self,
+        wallet_name: &str,
+        from_chain: &str,
+        to_chain: &str,
+        token: &str,
+        amount: &str,
+        approved: Option<&str>,
+    ) -> Result<String, WalletError> {
+        info!(
+            "Bridging assets from wallet: {} from: {} to: {} token: {} amount: {}",
+            wallet_name, from_chain, to_chain, token, amount
+        );
+
+        let bridge_key = format!("{}-{}", from_chain, to_chain);
+        let bridge = self.bridges.get(&bridge_key).ok_or_else(|| {
+            WalletError::BlockchainError(format!("Unsupported bridge: {}", bridge_key))
+        })?;
+
+        let quote = self
+            .quote_route(&bridge_key, bridge.as_ref(), from_chain, to_chain, token, amount)
+            .await?;
+        if !quote.within_limits {
+            return Err(WalletError::ValidationError(format!(
+                "Amount {} is outside the bridge limits for {} (min {}, max {})",
+                amount,
+                token,
+                quote.min_amount,
+                quote.max_amount.as_deref().unwrap_or("none")
+            )));
+        }
+        if !quote.liquidity_available {
+            return Err(WalletError::BridgeError(format!(
+                "Insufficient liquidity for {} {} on {}",
+                amount, token, to_chain
+            )));
+        }
+
+        let wallet = self.get_wallet_by_name(wallet_name).await?.ok_or_else(|| {
+            WalletError::StorageError(format!("Wallet not found: {}", wallet_name))
+        })?;
+        let policy_request = PolicyRequest {
+            transaction_type: TransactionType::Bridge,
+            network: from_chain.to_string(),
+            asset: token.to_string(),
+            amount: amount.parse::<f64>().unwrap_or(0.0),
+            recipient: None,
+        };
+        let (policy, reservation) = self.check_spending_policy(&wallet.id, &policy_request).await?;
+        let approval = match approved {
+            Some(id) => Approval::Granted(id),
+            None => Approval::Hold(HeldOperation::Bridge {
+                from_chain: from_chain.to_string(),
+                to_chain: to_chain.to_string(),
+                token: token.to_string(),
+                amount: amount.to_string(),
+            }),
+        };
+        let sent = async {
+            self.require_approval(&wallet, [policy], approval).await?;
+
+            let mut wallet_data = self.load_wallet_securely(wallet_name).await?;
+
+            let tx_hash = bridge
+                .transfer_across_chains(from_chain, to_chain, token, amount, &wallet_data)
+                .await;
+            wallet_data.zeroize();
+            Ok(tx_hash?)
+        };
+        let tx_hash = self.release_on_failure(reservation, sent.await).await?;
+        self.record_policy_spend(&wallet, reservation, &tx_hash, &policy_request).await;
+
+        let now = chrono::Utc::now();
+        let bridge_tx = BridgeTransaction {
+            id: uuid::Uuid::new_v4().to_string(),
+            from_wallet: wallet_name.to_string(),
+            from_chain: from_chain.to_string(),
+            to_chain: to_chain.to_string(),
+            token: token.to_string(),
+            amount: amount.to_string(),
+            status: BridgeTransactionStatus::Initiated,
+            source_tx_hash: Some(tx_hash.clone()),
+            destination_tx_hash: None,
+            created_at: now,
+            updated_at: now,
+            fee_amount: Some(quote.fees.protocol_fee),
+            estimated_completion_time: Some(quote.estimated_completion_time),
+        };
+        self.storage.store_bridge_transaction(&bridge_tx).await.map_err(|e| {
+            // the transfer is already on-chain; keep the hash in the log so it can be reconciled
+            warn!("Failed to record bridge transfer {}: {}", tx_hash, e);
+            WalletError::StorageError(e.to_string())
+        })?;
+
+        self.audit_sent(
+            &wallet.id,
+            "bridge_initiated",
+            format!(
+                "{} {} {} from {} to {} (source tx {})",
+                bridge_tx.id, amount, token, from_chain, to_chain, tx_hash
+            ),
+        )
+        .await;
+        info!("Bridge transfer {} recorded (source tx {})", bridge_tx.id, tx_hash);
+        Ok(bridge_tx.id)
+    }
+
+    /// Runs one relayer pass over pending bridge transfers; returns how many advanced.
+    pub async fn poll_bridge_transfers(&self) -> Result<usize, WalletError> {
+        self.bridge_relayer.poll_once().await.map_err(|e| WalletError::BridgeError(e.to_string()))
+    }
+
+    /// Receives every wallet event published from now on: new transactions
+    /// and their confirmations, balance changes of watched wallets, deposits,
+    /// bridge status changes, security events and approvals.
+    pub fn subscribe_events(&self) -> tokio::sync::broadcast::Receiver<WalletEvent> {
+        self.events.subscribe()
+    }
+
+    /// Polls `wallet`'s balances on every network until the returned guard
+    /// and every other guard for it are dropped, publishing
+    /// `BalanceChanged` events as they change. The first watch of a wallet
+    /// loads it once to derive the addresses to poll.
+    pub async fn watch_balances(&self, wallet: &str) -> Result<BalanceWatch, WalletError> {
+        let already_watched = {
+            let mut watched = self.watched_balances.lock().expect("balance watch lock poisoned");
+            match watched.get_mut(wallet) {
+                Some(entry) => {
+                    entry.watchers += 1;
+                    true
+                }
+                None => false,
+            }
+        };
+        if !already_watched {
+            let addresses = self.balance_addresses(wallet).await?;
+            let mut watched = self.watched_balances.lock().expect("balance watch lock poisoned");
+            let entry = watched.entry(wallet.to_string()).or_default();
+            entry.watchers += 1;
+            entry.addresses = addresses;
+        }
+        Ok(BalanceWatch { wallet: wallet.to_string(), watched: Arc::clone(&self.watched_balances) })
+    }
+
+    /// The address `get_balance` queries for `wallet` on each network: the
+    /// address of the key that signs for it there.
+    async fn balance_addresses(
+        &self,
+        wallet_name: &str,
+    ) -> Result<HashMap<String, String>, WalletError> {
+        let mut wallet_data = self.load_wallet_securely(wallet_name).await?;
+        let mut addresses = HashMap::new();
+        for network in self.blockchain_clients.keys() {
+            let address = match self.software_signer(&wallet_data.encrypted_master_key, network) {
+                Ok(signer) => self.signer_address(&signer, network).await,
+                Err(e) => Err(e),
+            };
+            match address {
+                Ok(address) => {
+                    addresses.insert(network.clone(), address);
+                }
+                Err(e) => debug!("Not polling {} on {}: {}", wallet_name, network, e),
+            }
+        }
+        wallet_data.zeroize();
+        Ok(addresses)
+    }
+
+    /// Checks the balances of the watched wallets once; returns how many
+    /// changed. The first balance seen for a wallet and network is only
+    /// recorded. When a balance goes up, the senders of the transfers
+    /// received since the last poll are screened as deposits.
+    pub async fn poll_balances(&self) -> usize {
+        let wallets: Vec<(String, HashMap<String, String>)> = self
+            .watched_balances
+            .lock()
+            .expect("balance watch lock poisoned")
+            .iter()
+            .map(|(wallet, entry)| (wallet.clone(), entry.addresses.clone()))
+            .collect();
+        let mut changed = 0;
+        for (wallet, addresses) in wallets {
+            for (network, address) in &addresses {
+                let Some(client) = self.blockchain_clients.get(network) else {
+                    continue;
+                };
+                let block = client.get_block_number().await.ok();
+                let balance = match client.get_balance(address).await {
+                    Ok(balance) => balance,
+                    Err(e) => {
+                        debug!("Failed to poll balance of {} on {}: {}", wallet, network, e);
+                        continue;
+                    }
+                };
+                let (previous, last_block) = {
+                    let mut watched =
+                        self.watched_balances.lock().expect("balance watch lock poisoned");
+                    // unwatched while polling
+                    let Some(entry) = watched.get_mut(&wallet) else {
+                        break;
+                    };
+                    let last_block = match block {
+                        Some(block) => entry.blocks.insert(network.clone(), block),
+                        None => entry.blocks.get(network).copied(),
+                    };
+                    (entry.balances.insert(network.clone(), balance.clone()), last_block)
+                };
+                if let Some(previous) = previous.filter(|previous| *previous != balance) {
+                    changed += 1;
+                    let received = matches!(
+                        (previous.parse::<f64>(), balance.parse::<f64>()),
+                        (Ok(previous), Ok(balance)) if balance > previous
+                    );
+                    events::publish(
+                        &self.events,
+                        WalletEvent::BalanceChanged {
+                            wallet: wallet.clone(),
+                            network: network.clone(),
+                            balance,
+                            previous,
+                        },
+                    );
+                    if let (true, Some(last_block), Some(block)) = (received, last_block, block) {
+                        self.screen_deposits(
+                            &wallet,
+                            network,
+                            client.as_ref(),
+                            address,
+                            last_block,
+                            block,
+                        )
+                        .await;
+                    }
+                }
+            }
+        }
+        changed
+    }
+
+    /// Screens the senders of transfers `address` received after
+    /// `last_block` up to `block`, at most `MAX_DEPOSIT_SCAN_BLOCKS` of them.
+    async fn screen_deposits(
+        &self,
+        wallet: &str,
+        network: &str,
+        client: &dyn BlockchainClient,
+        address: &str,
+        last_block: u64,
+        block: u64,
+    ) {
+        if block <= last_block {
+            return;
+        }
+        let from_block = (last_block + 1).max(block.saturating_sub(MAX_DEPOSIT_SCAN_BLOCKS - 1));
+        let transfers = match client.incoming_transfers(address, from_block, block).await {
+            Ok(transfers) => transfers,
+            Err(e) => {
+                debug!("Failed to list deposits of {} on {}: {}", wallet, network, e);
+                return;
+            }
+        };
+        for transfer in transfers {
+            if let Err(e) =
+                self.screen_incoming_transfer(wallet, network, &transfer.from, &transfer.hash).await
+            {
+                warn!("Failed to screen deposit {} to {}: {}", transfer.hash, wallet, e);
+            }
+        }
+    }
+
+    /// Runs `poll_balances` every `interval` while the manager is alive.
+    pub fn spawn_balance_watcher(
+        self: &Arc<Self>,
+        interval: std::time::Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        let manager = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let Some(manager) = manager.upgrade() else {
+                    break;
+                };
+                manager.poll_balances().await;
+            }
+        })
+    }
+
+    /// Checks every pending transaction once; returns how many settled.
+    pub async fn poll_transaction_confirmations(&self) -> Result<usize, WalletError> {
+        self.confirmations.poll_once().await.map_err(|e| WalletError::StorageError(e.to_string()))
+    }
+
+    /// Registers `url` for the given event types (all of them when empty),
+    /// optionally only for one wallet. The returned record holds the signing
+    /// secret, which is not shown again.
+    pub async fn create_webhook(
+        &self,
+        url: &str,
+        kinds: &[EventKind],
+        wallet_name: Option<&str>,
+    ) -> Result<WebhookRecord, WalletError> {
+        let parsed = reqwest::Url::parse(url)
+            .map_err(|e| WalletError::ValidationError(format!("Invalid webhook URL: {}", e)))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(WalletError::ValidationError(
+                "Webhook URL must use http or https".to_string(),
+            ));
+        }
+        if let Some(name) = wallet_name {
+            self.get_wallet_by_name(name).await?.ok_or_else(|| {
+                WalletError::ValidationError(format!("Wallet not found: {}", name))
+            })?;
+        }
+        let webhook = WebhookRecord {
+            id: uuid::Uuid::new_v4().to_string(),
+            url: url.to_string(),
+            secret: webhooks::generate_secret(),
+            events: webhooks::format_event_kinds(kinds),
+            wallet: wallet_name.map(str::to_string),
+            created_at: chrono::Utc::now(),
+        };
+        self.storage
+            .store_webhook(&webhook)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?;
+        self.storage
+            .log_event(None, "webhook_created", &format!("{} {}", webhook.id, webhook.url))
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?;
+        Ok(webhook)
+    }
+
+    pub async fn list_webhooks(&self) -> Result<Vec<WebhookRecord>, WalletError> {
+        self.storage.list_webhooks().await.map_err(|e| WalletError::StorageError(e.to_string()))
+    }
+
+    /// Removes a webhook and its deliveries; `false` when it did not exist.
+    pub async fn delete_webhook(&self, id: &str) -> Result<bool, WalletError> {
+        let deleted = self
+            .storage
+            .delete_webhook(id)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?;
+        if deleted {
+            self.storage
+                .log_event(None, "webhook_deleted", id)
+                .await
+                .map_err(|e| WalletError::StorageError(e.to_string()))?;
+        }
+        Ok(deleted)
+    }
+
+    /// The webhook's most recent deliveries, optionally only those in `status`.
+    pub async fn webhook_deliveries(
+        &self,
+        webhook_id: &str,
+        status: Option<DeliveryStatus>,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, WalletError> {
+        self.webhook_or_error(webhook_id).await?;
+        self.storage
+            .get_webhook_deliveries(webhook_id, status.as_ref().map(DeliveryStatus::as_str), limit)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))
+    }
+
+    /// Queues one of the webhook's deliveries to be sent again right away,
+    /// typically a dead one once the endpoint is fixed.
+    pub async fn retry_webhook_delivery(
+        &self,
+        webhook_id: &str,
+        delivery_id: &str,
+    ) -> Result<WebhookDelivery, WalletError> {
+        self.webhook_or_error(webhook_id).await?;
+        let not_found =
+            || WalletError::StorageError(format!("Webhook delivery not found: {}", delivery_id));
+        let delivery = self
+            .storage
+            .get_webhook_delivery(delivery_id)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?
+            .filter(|d| d.webhook_id == webhook_id)
+            .ok_or_else(not_found)?;
+        self.webhooks
+            .retry(&delivery.id)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?
+            .ok_or_else(not_found)
+    }
+
+    /// Attempts the webhook deliveries that are due; returns how many were
+    /// delivered.
+    pub async fn deliver_webhooks(&self) -> Result<usize, WalletError> {
+        self.webhooks.deliver_due().await.map_err(|e| WalletError::StorageError(e.to_string()))
+    }
+
+    async fn webhook_or_error(&self, id: &str) -> Result<WebhookRecord, WalletError> {
+        self.storage
+            .get_webhook(id)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?
+            .ok_or_else(|| WalletError::StorageError(format!("Webhook not found: {}", id)))
+    }
+
+    /// Runs one recovery pass over failed transfers and transfers still pending
+    /// after `stuck_before`: each is redeemed, refunded or escalated for manual
+    /// intervention. Returns how many transfers were acted on.
+    pub async fn recover_bridge_transfers(
+        &self,
+        stuck_before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<usize, WalletError> {
+        let candidates = self
+            .storage
+            .get_bridge_transactions_for_recovery(stuck_before)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?;
+
+        let mut acted = 0;
+        for tx in &candidates {
+            let action = self
+                .bridge_recovery
+                .plan(tx, stuck_before)
+                .await
+                .map_err(|e| WalletError::StorageError(e.to_string()))?;
+            let Some(mut action) = action else {
+                continue;
+            };
+
+            let mut wallet_data = None;
+            if action.needs_wallet() {
+                match self.load_wallet_securely(&tx.from_wallet).await {
+                    Ok(data) => wallet_data = Some(data),
+                    Err(e) => {
+                        action = RecoveryAction::Escalate(format!("Wallet unavailable: {}", e))
+                    }
+                }
+            }
+            let result = self.bridge_recovery.execute(tx, action, wallet_data.as_ref()).await;
+            if let Some(mut data) = wallet_data {
+                data.zeroize();
+            }
+            match result {
+                Ok(_) => acted += 1,
+                Err(e) => warn!("Recovery of bridge tx {} failed: {}", tx.id, e),
+            }
+        }
+        Ok(acted)
+    }
+
+    /// Recovery pass over failed transfers and transfers more than
+    /// `STUCK_TRANSFER_GRACE` past their estimated completion time.
+    pub async fn recover_overdue_bridge_transfers(&self) -> Result<usize, WalletError> {
+        let grace = chrono::Duration::from_std(STUCK_TRANSFER_GRACE).expect("grace fits in chrono");
+        self.recover_bridge_transfers(chrono::Utc::now() - grace).await
+    }
+
+    /// Runs `recover_overdue_bridge_transfers` every `interval` while the
+    /// manager is alive. Recovery and the relayer may act on the same
+    /// transfer; each status change is a compare-and-set, so the loser backs off.
+    pub fn spawn_bridge_recovery(
+        self: &Arc<Self>,
+        interval: std::time::Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        let manager = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let Some(manager) = manager.upgrade() else {
+                    break;
+                };
+                match manager.recover_overdue_bridge_transfers().await {
+                    Ok(0) => {}
+                    Ok(acted) => info!("Bridge recovery acted on {} transfers", acted),
+                    Err(e) => warn!("Bridge recovery pass failed: {}", e),
+                }
+            }
+        })
+    }
+
+    pub async fn get_block_number(&self, network: &str) -> Result<u64, WalletError> {
+        let client = self.blockchain_clients.get(network).ok_or_else(|| {
+            WalletError::BlockchainError(format!("Unsupported network: {}", network))
+        })?;
+        client.get_block_number().await.map_err(|e| WalletError::BlockchainError(e.to_string()))
+    }
+
+    pub async fn check_bridge_status(
+        &self,
+        bridge_tx_id: &str,
+    ) -> Result<BridgeTransactionStatus, WalletError> {
+        self.storage
+            .get_bridge_transaction(bridge_tx_id)
+            .await
+            .map(|tx| tx.status)
+            .map_err(|e| WalletError::StorageError(e.to_string()))
+    }
+
+    pub async fn get_bridge_transaction_status(
+        &self,
+        bridge_tx_id: &str,
+    ) -> Result<BridgeTransaction, WalletError> {
+        self.storage
+            .get_bridge_transaction(bridge_tx_id)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))
+    }
+
+    /// Moves the transfer from `expected` to `status`; returns `false` when it
+    /// was no longer in `expected`.
+    pub async fn update_bridge_transaction_status(
+        &self,
+        bridge_tx_id: &str,
+        expected: &BridgeTransactionStatus,
+        status: BridgeTransactionStatus,
+        source_tx_hash: Option<String>,
+        destination_tx_hash: Option<String>,
+    ) -> Result<bool, WalletError> {
+        info!("Updating bridge tx {} status from {:?} to {:?}", bridge_tx_id, expected, status);
+        self.storage
+            .update_bridge_transaction_status(
+                bridge_tx_id,
+                expected,
+                status,
+                source_tx_hash,
+                destination_tx_hash,
+            )
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))
+    }
+
+    /// Protocol fee and estimated completion time of the registered bridge
+    /// from `from_chain` to `to_chain`, from its live quote.
+    pub async fn calculate_bridge_fee(
+        &self,
+        from_chain: &str,
+        to_chain: &str,
+        token: &str,
+        amount: &str,
+    ) -> Result<(String, chrono::DateTime<chrono::Utc>), WalletError> {
+        let route = format!("{}-{}", from_chain, to_chain);
+        let bridge = self.bridges.get(&route).ok_or_else(|| {
+            WalletError::BlockchainError(format!("Unsupported bridge: {}", route))
+        })?;
+        let quote =
+            self.quote_route(&route, bridge.as_ref(), from_chain, to_chain, token, amount).await?;
+        Ok((quote.fees.protocol_fee, quote.estimated_completion_time))
+    }
+
+    /// Quotes `amount` of `token` on every registered bridge matching the
+    /// optional source and destination, best route first. Bridges that do not
+    /// support the token are left out.
+    pub async fn quote_bridge(
+        &self,
+        from_chain: Option<&str>,
+        to_chain: Option<&str>,
+        token: &str,
+        amount: &str,
+    ) -> Result<Vec<BridgeQuote>, WalletError> {
+        if quote::parse_amount(amount)
+            .map_err(|e| WalletError::ValidationError(e.to_string()))?
+            .is_zero()
+        {
+            return Err(WalletError::ValidationError("Amount must be greater than zero".into()));
+        }
+
+        let mut quotes = Vec::new();
+        for (key, bridge) in self.bridges.iter() {
+            let Some((from, to)) = split_route(key, &self.networks) else {
+                continue;
+            };
+            if from_chain.is_some_and(|c| c != from) || to_chain.is_some_and(|c| c != to) {
+                continue;
+            }
+            match self.quote_route(key, bridge.as_ref(), from, to, token, amount).await {
+                Ok(quote) => quotes.push(quote),
+                Err(e) => debug!("No quote from bridge {}: {}", key, e),
+            }
+        }
+        quote::rank_quotes(&mut quotes);
+        Ok(quotes)
+    }
+
+    async fn quote_route(
+        &self,
+        key: &str,
+        bridge: &dyn Bridge,
+        from_chain: &str,
+        to_chain: &str,
+        token: &str,
+        amount: &str,
+    ) -> Result<BridgeQuote, WalletError> {
+        let terms = bridge
+            .quote_terms(from_chain, to_chain, token)
+            .await
+            .map_err(|e| WalletError::BridgeError(e.to_string()))?;
+        quote::build_quote(
+            key,
+            from_chain,
+            to_chain,
+            token,
+            amount,
+            &terms,
+            self.block_times(from_chain, to_chain),
+        )
+        .map_err(|e| WalletError::ValidationError(e.to_string()))
+    }
+
+    fn block_times(&self, from_chain: &str, to_chain: &str) -> BlockTimes {
+        let block_time = |network: &str| {
+            self.networks
+                .get(network)
+                .map_or(quote::DEFAULT_BLOCK_TIME_SECONDS, |n| n.block_time_seconds)
+        };
+        BlockTimes { source: block_time(from_chain), destination: block_time(to_chain) }
+    }
+
+    fn swap_router(&self, network: &str) -> Result<&dyn SwapRouter, WalletError> {
+        self.swap_routers.get(network).map(|r| r.as_ref()).ok_or_else(|| {
+            WalletError::ValidationError(format!("No swap router configured for {}", network))
+        })
+    }
+
+    /// Prices a swap for the wallet's account on `network` without sending anything.
+    pub async fn quote_swap(
+        &self,
+        wallet_name: &str,
+        network: &str,
+        params: &SwapParams,
+    ) -> Result<SwapQuote, WalletError> {
+        info!(
+            "Quoting swap for wallet: {} on: {} {} {} -> {}",
+            wallet_name, network, params.amount_in, params.token_in, params.token_out
+        );
+
+        params.validate().map_err(|e| WalletError::ValidationError(e.to_string()))?;
+        let router = self.swap_router(network)?;
+
+        let signer = self.wallet_signer(wallet_name, network).await?;
+        let owner = self.signer_address(&signer, network).await?;
+
+        router
+            .quote_swap(&owner, params)
+            .await
+            .map_err(|e| WalletError::BlockchainError(e.to_string()))
+    }
+
+    /// Swaps tokens on `network` and records the swap in the wallet's history.
+    /// A swap that needs sign-off is held and `PendingApproval` returned.
+    pub async fn swap_tokens(
+        &self,
+        wallet_name: &str,
+        network: &str,
+        params: &SwapParams,
+    ) -> Result<SwapReceipt, WalletError> {
+        self.execute_swap(wallet_name, network, params, None).await
+    }
+
+    async fn execute_swap(
+        &self,
+        wallet_name: &str,
+        network: &str,
+        params: &SwapParams,
+        approved: Option<&str>,
+    ) -> Result<SwapReceipt, WalletError> {
+        info!(
+            "Swapping for wallet: {} on: {} {} {} -> {}",
+            wallet_name, network, params.amount_in, params.token_in, params.token_out
+        );
+
+        params.validate().map_err(|e| WalletError::ValidationError(e.to_string()))?;
+        let router = self.swap_router(network)?;
+        let wallet = self.get_wallet_by_name(wallet_name).await?.ok_or_else(|| {
+            WalletError::StorageError(format!("Wallet not found: {}", wallet_name))
+        })?;
+        let policy_request = PolicyRequest {
+            transaction_type: TransactionType::Swap,
+            network: network.to_string(),
+            asset: params.token_in.clone(),
+            amount: params.amount_in.parse::<f64>().unwrap_or(0.0),
+            recipient: None,
+        };
+        let (policy, reservation) = self.check_spending_policy(&wallet.id, &policy_request).await?;
+        let approval = match approved {
+            Some(id) => Approval::Granted(id),
+            None => Approval::Hold(HeldOperation::Swap {
+                network: network.to_string(),
+                params: params.clone(),
+            }),
+        };
+        let sent = async {
+            self.require_approval(&wallet, [policy], approval).await?;
+
+            let mut wallet_data = self.load_wallet_securely(wallet_name).await?;
+            let result = async {
+                let signer = self.software_signer(&wallet_data.encrypted_master_key, network)?;
+                let from = self.signer_address(&signer, network).await?;
+                let receipt = router
+                    .execute_swap(params, &wallet_data)
+                    .await
+                    .map_err(|e| WalletError::BlockchainError(e.to_string()))?;
+                Ok((from, receipt))
+            }
+            .await;
+            wallet_data.zeroize();
+            result
+        };
+        let (from, receipt) = self.release_on_failure(reservation, sent.await).await?;
+        self.record_policy_spend(&wallet, reservation, &receipt.tx_hash, &policy_request).await;
+
+        // The swap is on-chain already: failing to record it is logged, not
+        // returned, and the pending row lets confirmations track its receipt.
+        let quote = &receipt.quote;
+        let now = chrono::Utc::now();
+        if let Err(e) = self
+            .storage
+            .store_transaction(&TransactionRecord {
+                id: uuid::Uuid::new_v4().to_string(),
+                wallet_id: wallet.id.clone(),
+                tx_hash: receipt.tx_hash.clone(),
+                network: network.to_string(),
+                from_address: from,
+                to_address: quote.router.clone(),
+                amount: quote.amount_in.clone(),
+                fee: quote.network_fee.clone(),
+                status: "pending".to_string(),
+                created_at: now,
+                confirmed_at: None,
+            })
+            .await
+        {
+            warn!("Failed to record swap {}: {}", receipt.tx_hash, e);
+        }
+        if let Err(e) = self
+            .storage
+            .store_swap(&SwapRecord {
+                tx_hash: receipt.tx_hash.clone(),
+                wallet_id: wallet.id.clone(),
+                network: network.to_string(),
+                protocol: quote.protocol.as_str().to_string(),
+                token_in: quote.token_in.clone(),
+                token_out: quote.token_out.clone(),
+                amount_in: quote.amount_in.clone(),
+                expected_amount_out: quote.amount_out.clone(),
+                min_amount_out: quote.amount_out_min.clone(),
+                approval_tx_hash: receipt.approval_tx_hash.clone(),
+                created_at: now,
+            })
+            .await
+        {
+            warn!("Failed to record swap details {}: {}", receipt.tx_hash, e);
+        }
+        self.audit_sent(
+            &wallet.id,
+            "swap_submitted",
+            format!(
+                "{} {} -> {} (min {}) on {}: {}",
+                quote.amount_in,
+                quote.token_in,
+                quote.token_out,
+                quote.amount_out_min,
+                network,
+                receipt.tx_hash
+            ),
+        )
+        .await;
+
+        info!("Swap sent with hash: {}", receipt.tx_hash);
+        Ok(receipt)
+    }
+
+    /// Runs an operation past the compliance checker. A refusal is an error;
+    /// `Some(reason)` means the operation needs sign-off, see `require_approval`.
+    fn check_compliance(
+        &self,
+        wallet_id: &str,
+        transaction_type: &TransactionType,
+        amount: f64,
+        recipient: &str,
+    ) -> Result<Option<String>, WalletError> {
+        let result = self
+            .compliance
+            .lock()
+            .map_err(|_| WalletError::Other("Compliance checker lock poisoned".to_string()))?
+            // the wallet does not know its user's country
+            .check_transaction(wallet_id, transaction_type, amount, recipient, "")
+            .map_err(|e| WalletError::Other(e.to_string()))?;
+        match result {
+            ComplianceResult::Compliant => Ok(None),
+            ComplianceResult::NonCompliant(reason) => {
+                Err(WalletError::ValidationError(format!("Compliance check failed: {}", reason)))
+            }
+            ComplianceResult::RequiresApproval(reason) => {
+                Ok(Some(format!("Compliance: {}", reason)))
+            }
+        }
+    }
+
+    /// Imports a sanctions list in place of the stored version of its source;
+    /// returns how many distinct addresses are now screened.
+    pub async fn import_sanctions_list(&self, list: &SanctionsList) -> Result<usize, WalletError> {
+        self.sanctions.import(list).await.map_err(|e| WalletError::StorageError(e.to_string()))
+    }
+
+    /// Re-reads the configured sanctions list files now; returns how many
+    /// had changed and were imported.
+    pub async fn reload_sanctions_lists(&self) -> usize {
+        self.sanctions.reload_feeds().await
+    }
+
+    /// The stored sanctions lists with their versions.
+    pub async fn sanctions_lists(&self) -> Result<Vec<SanctionsListRecord>, WalletError> {
+        self.storage
+            .get_sanctions_lists()
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))
+    }
+
+    pub fn is_address_sanctioned(&self, address: &str) -> Result<bool, WalletError> {
+        Ok(self
+            .compliance
+            .lock()
+            .map_err(|_| WalletError::Other("Compliance checker lock poisoned".to_string()))?
+            .is_address_sanctioned(address))
+    }
+
+    /// Screens the sender of a transfer received by the wallet and publishes
+    /// it as a deposit; `poll_balances` calls it for the transfers behind a
+    /// watched balance going up. Funds cannot be refused once on chain, so a
+    /// sanctioned sender is recorded in the audit log and reported rather
+    /// than treated as an error.
+    pub async fn screen_incoming_transfer(
+        &self,
+        wallet_name: &str,
+        network: &str,
+        from_address: &str,
+        tx_hash: &str,
+    ) -> Result<bool, WalletError> {
+        let wallet = self.get_wallet_by_name(wallet_name).await?.ok_or_else(|| {
+            WalletError::StorageError(format!("Wallet not found: {}", wallet_name))
+        })?;
+        let sanctioned = self.is_address_sanctioned(from_address)?;
+        events::publish(
+            &self.events,
+            WalletEvent::IncomingDeposit {
+                wallet: wallet.name.clone(),
+                network: network.to_string(),
+                from_address: from_address.to_string(),
+                tx_hash: tx_hash.to_string(),
+                sanctioned,
+            },
+        );
+        if !sanctioned {
+            return Ok(false);
+        }
+        warn!(
+            "Wallet {} received {} on {} from sanctioned address {}",
+            wallet_name, tx_hash, network, from_address
+        );
+        let details = serde_json::json!({
+            "network": network,
+            "from_address": from_address,
+            "tx_hash": tx_hash,
+        });
+        self.storage
+            .log_action(&wallet.id, "sanctioned_sender", &details.to_string(), None, None)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?;
+        events::publish(
+            &self.events,
+            WalletEvent::SecurityEvent {
+                event_type: "sanctioned_sender".to_string(),
+                severity: "high".to_string(),
+                description: format!("{} received {} from {}", wallet.name, tx_hash, from_address),
+                wallet: Some(wallet.name.clone()),
+                source_ip: None,
+            },
+        );
+        Ok(true)
+    }
+
+    /// The wallet's spending policy, `None` when it has none.
+    pub async fn spending_policy(
+        &self,
+        wallet_name: &str,
+    ) -> Result<Option<SpendingPolicy>, WalletError> {
+        let wallet = self.get_wallet_by_name(wallet_name).await?.ok_or_else(|| {
+            WalletError::StorageError(format!("Wallet not found: {}", wallet_name))
+        })?;
+        self.load_spending_policy(&wallet.id).await
+    }
+
+    /// Replaces the wallet's spending policy after validating it.
+    pub async fn set_spending_policy(
+        &self,
+        wallet_name: &str,
+        policy: &SpendingPolicy,
+    ) -> Result<(), WalletError> {
+        policy.validate().map_err(|e| WalletError::ValidationError(e.to_string()))?;
+        let wallet = self.get_wallet_by_name(wallet_name).await?.ok_or_else(|| {
+            WalletError::StorageError(format!("Wallet not found: {}", wallet_name))
+        })?;
+        let json = serde_json::to_string(policy).map_err(|e| WalletError::Other(e.to_string()))?;
+        self.storage
+            .set_spending_policy(&wallet.id, &json)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?;
+        self.storage
+            .log_action(&wallet.id, "policy_updated", &json, None, None)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?;
+        info!("Spending policy updated for wallet: {}", wallet_name);
+        Ok(())
+    }
+
+    /// Removes the wallet's spending policy; `false` when it had none.
+    pub async fn remove_spending_policy(&self, wallet_name: &str) -> Result<bool, WalletError> {
+        let wallet = self.get_wallet_by_name(wallet_name).await?.ok_or_else(|| {
+            WalletError::StorageError(format!("Wallet not found: {}", wallet_name))
+        })?;
+        let removed = self
+            .storage
+            .delete_spending_policy(&wallet.id)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?;
+        if removed {
+            self.storage
+                .log_action(&wallet.id, "policy_removed", "", None, None)
+                .await
+                .map_err(|e| WalletError::StorageError(e.to_string()))?;
+            info!("Spending policy removed for wallet: {}", wallet_name);
+        }
+        Ok(removed)
+    }
+
+    async fn load_spending_policy(
+        &self,
+        wallet_id: &str,
+    ) -> Result<Option<SpendingPolicy>, WalletError> {
+        let json = self
+            .storage
+            .get_spending_policy(wallet_id)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?;
+        json.map(|json| serde_json::from_str(&json))
+            .transpose()
+            .map_err(|e| WalletError::StorageError(format!("Invalid spending policy: {}", e)))
+    }
+
+    /// Evaluates the wallet's spending policy, if it has one, and writes the
+    /// decision to the audit log. A denial is an error; `Some(reason)` means
+    /// the operation needs sign-off. Every recipient is noted so cooling
+    /// periods run from the first time the wallet was asked to pay it.
+    ///
+    /// An operation that is not denied has its amount reserved in the same
+    /// storage transaction the limits are evaluated in, so concurrent sends
+    /// cannot both spend the same headroom. The returned reservation must be
+    /// settled: by `record_policy_spend` once the operation is sent, or by
+    /// `release_on_failure` when it is not.
+    async fn check_spending_policy(
+        &self,
+        wallet_id: &str,
+        request: &PolicyRequest,
+    ) -> Result<(Option<String>, i64), WalletError> {
+        let now = chrono::Utc::now();
+        let first_seen = match request.recipient.as_deref() {
+            Some(recipient) => Some(
+                self.storage
+                    .note_recipient(wallet_id, &recipient_key(recipient), now)
+                    .await
+                    .map_err(|e| WalletError::StorageError(e.to_string()))?,
+            ),
+            None => None,
+        };
+        let policy = self.load_spending_policy(wallet_id).await?;
+        let since = now
+            - policy.as_ref().map(SpendingPolicy::lookback).unwrap_or_else(chrono::Duration::zero);
+        let spend = PolicySpendRecord {
+            id: 0,
+            wallet_id: wallet_id.to_string(),
+            tx_hash: String::new(),
+            transaction_type: format!("{:?}", request.transaction_type),
+            network: request.network.clone(),
+            asset: request.asset.clone(),
+            recipient: request.recipient.as_deref().map(recipient_key),
+            amount: request.amount,
+            created_at: now,
+        };
+        let mut decision = ComplianceResult::Compliant;
+        let reservation = self
+            .storage
+            .reserve_policy_spend(&spend, since, &mut |recent| {
+                if let Some(policy) = &policy {
+                    decision = policy.evaluate(request, recent, first_seen, now);
+                }
+                !matches!(decision, ComplianceResult::NonCompliant(_))
+            })
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?;
+        if policy.is_none() {
+            // nothing to evaluate, so the spend was reserved
+            return Ok((None, reservation.unwrap_or_default()));
+        }
+
+        let operation = format!(
+            "{:?} {} {} on {}{}",
+            request.transaction_type,
+            request.amount,
+            request.asset,
+            request.network,
+            request.recipient.as_deref().map(|r| format!(" to {}", r)).unwrap_or_default()
+        );
+        let (action, details) = match &decision {
+            ComplianceResult::Compliant => ("policy_allowed", operation),
+            ComplianceResult::NonCompliant(reason) => {
+                ("policy_denied", format!("{}: {}", operation, reason))
+            }
+            ComplianceResult::RequiresApproval(reason) => {
+                ("policy_approval_required", format!("{}: {}", operation, reason))
+            }
+        };
+        if let Err(e) = self.storage.log_action(wallet_id, action, &details, None, None).await {
+            if let Some(reservation) = reservation {
+                self.release_policy_spend(reservation).await;
+            }
+            return Err(WalletError::StorageError(e.to_string()));
+        }
+
+        let reason = match decision {
+            ComplianceResult::Compliant => None,
+            ComplianceResult::NonCompliant(reason) => {
+                return Err(WalletError::ValidationError(format!("Spending policy: {}", reason)))
+            }
+            ComplianceResult::RequiresApproval(reason) => {
+                Some(format!("Spending policy: {}", reason))
+            }
+        };
+        // only denied spends go unreserved
+        Ok((reason, reservation.unwrap_or_default()))
+    }
+
+    /// Records a signed and broadcast operation in the audit log. It is on
+    /// chain already, so failing to record it is logged, not returned.
+    async fn audit_sent(&self, wallet_id: &str, action: &str, details: String) {
+        if let Err(e) = self.storage.log_action(wallet_id, action, &details, None, None).await {
+            warn!("Failed to audit {} ({}): {}", action, details, e);
+        }
+    }
+
+    /// Counts a broadcast operation against the wallet's velocity limits,
+    /// settling the spend `check_spending_policy` reserved for it, and
+    /// announces it as a new transaction.
+    async fn record_policy_spend(
+        &self,
+        wallet: &WalletMetadata,
+        reservation: i64,
+        tx_hash: &str,
+        request: &PolicyRequest,
+    ) {
+        events::publish(
+            &self.events,
+            WalletEvent::NewTransaction {
+                wallet: wallet.name.clone(),
+                network: request.network.clone(),
+                tx_hash: tx_hash.to_string(),
+                to_address: request.recipient.clone(),
+                amount: request.amount,
+                asset: request.asset.clone(),
+            },
+        );
+        if let Err(e) = self.storage.confirm_policy_spend(reservation, tx_hash).await {
+            warn!("Failed to record spend {} against wallet policy: {}", tx_hash, e);
+        }
+    }
+
+    /// Gives back the spend reserved for an operation that was not sent.
+    async fn release_policy_spend(&self, reservation: i64) {
+        if let Err(e) = self.storage.release_policy_spend(reservation).await {
+            warn!("Failed to release reserved spend {}: {}", reservation, e);
+        }
+    }
+
+    /// Passes `result` through, releasing the spend `reservation` if it is an
+    /// error: the operation it was reserved for was not sent.
+    async fn release_on_failure<T>(
+        &self,
+        reservation: i64,
+        result: Result<T, WalletError>,
+    ) -> Result<T, WalletError> {
+        if result.is_err() {
+            self.release_policy_spend(reservation).await;
+        }
+        result
+    }
+
+    /// Decides what happens to an operation given the sign-off reasons from
+    /// its checks: nothing when there are none, otherwise it is held, let
+    /// through under an approved request, or refused, as `approval` says.
+    async fn require_approval(
+        &self,
+        wallet: &WalletMetadata,
+        reasons: impl IntoIterator<Item = Option<String>>,
+        approval: Approval<'_>,
+    ) -> Result<(), WalletError> {
+        let reasons: Vec<String> = reasons.into_iter().flatten().collect();
+        if reasons.is_empty() {
+            return Ok(());
+        }
+        let reason = reasons.join("; ");
+        match approval {
+            Approval::Granted(id) => {
+                info!("Going ahead under approval request {}: {}", id, reason);
+                Ok(())
+            }
+            Approval::Refuse => {
+                Err(WalletError::ValidationError(format!("Requires approval: {}", reason)))
+            }
+            Approval::Hold(operation) => {
+                let request = ApprovalRequest::new(
+                    &wallet.id,
+                    &wallet.name,
+                    operation,
+                    &reason,
+                    AuditContext::current().actor.as_deref(),
+                    &self.approval_settings,
+                );
+                self.storage
+                    .store_approval_request(&request)
+                    .await
+                    .map_err(|e| WalletError::StorageError(e.to_string()))?;
+                self.storage
+                    .log_action(
+                        &wallet.id,
+                        "approval_requested",
+                        &format!("{}: {}", request.id, reason),
+                        None,
+                        None,
+                    )
+                    .await
+                    .map_err(|e| WalletError::StorageError(e.to_string()))?;
+                self.notify_approvers(&request).await;
+                info!("Operation on wallet {} held for approval as {}", wallet.name, request.id);
+                Err(WalletError::PendingApproval(request.id))
+            }
+        }
+    }
+
+    /// Held operations, newest first, only those in `status` if given.
+    /// Pending requests past their expiry are expired first.
+    pub async fn approval_requests(
+        &self,
+        status: Option<ApprovalStatus>,
+    ) -> Result<Vec<ApprovalRequest>, WalletError> {
+        self.expire_approval_requests().await?;
+        self.storage
+            .list_approval_requests(status)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))
+    }
+
+    /// A held operation, `None` when there is no request `id`.
+    pub async fn approval_request(&self, id: &str) -> Result<Option<ApprovalRequest>, WalletError> {
+        let request = self
+            .storage
+            .get_approval_request(id)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?;
+        match request {
+            Some(request) if request.is_expired(chrono::Utc::now()) => {
+                self.finish_approval(&request, ApprovalStatus::Expired, None).await?;
+                self.approval_request_or_error(id).await.map(Some)
+            }
+            request => Ok(request),
+        }
+    }
+
+    /// Expires pending requests past their expiry; returns how many.
+    pub async fn expire_approval_requests(&self) -> Result<usize, WalletError> {
+        let now = chrono::Utc::now();
+        let pending = self
+            .storage
+            .list_approval_requests(Some(ApprovalStatus::Pending))
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?;
+        let mut expired = 0;
+        for request in pending.iter().filter(|request| request.is_expired(now)) {
+            if self.finish_approval(request, ApprovalStatus::Expired, None).await? {
+                expired += 1;
+            }
+        }
+        Ok(expired)
+    }
+
+    /// Records `approver`'s approval of a pending request. The principal
+    /// that submitted it cannot vote, and each approver counts once. The
+    /// vote that brings it to its required approvals carries the operation
+    /// out; the returned request then shows whether it was executed or failed.
+    pub async fn approve_request(
+        &self,
+        id: &str,
+        approver: &str,
+        comment: Option<&str>,
+    ) -> Result<ApprovalRequest, WalletError> {
+        let request = self.vote_on_request(id, approver, true, comment).await?;
+        if request.approvals() < request.required_approvals {
+            return Ok(request);
+        }
+        // only one vote wins the transition, so the operation runs once
+        if !self
+            .storage
+            .update_approval_status(id, ApprovalStatus::Pending, ApprovalStatus::Approved, None)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?
+        {
+            return self.approval_request_or_error(id).await;
+        }
+
+        info!("Approval request {} approved, executing", id);
+        let (status, result) = match self.execute_approved(&request).await {
+            Ok(result) => (ApprovalStatus::Executed, result),
+            Err(e) => {
+                warn!("Approved operation {} failed: {}", id, e);
+                (ApprovalStatus::Failed, e.to_string())
+            }
+        };
+        let approved = ApprovalRequest { status: ApprovalStatus::Approved, ..request };
+        self.finish_approval(&approved, status, Some(&result)).await?;
+        self.approval_request_or_error(id).await
+    }
+
+    /// Records `approver`'s rejection, which drops the request.
+    pub async fn reject_request(
+        &self,
+        id: &str,
+        approver: &str,
+        comment: Option<&str>,
+    ) -> Result<ApprovalRequest, WalletError> {
+        let request = self.vote_on_request(id, approver, false, comment).await?;
+        self.finish_approval(&request, ApprovalStatus::Rejected, None).await?;
+        self.approval_request_or_error(id).await
+    }
+
+    async fn vote_on_request(
+        &self,
+        id: &str,
+        approver: &str,
+        approved: bool,
+        comment: Option<&str>,
+    ) -> Result<ApprovalRequest, WalletError> {
+        let request = self.approval_request(id).await?.ok_or_else(|| {
+            WalletError::StorageError(format!("Approval request not found: {}", id))
+        })?;
+        if request.status != ApprovalStatus::Pending {
+            return Err(WalletError::ValidationError(format!(
+                "Approval request {} is {}",
+                id, request.status
+            )));
+        }
+        if request.is_requester(approver) {
+            return Err(WalletError::ValidationError(format!(
+                "{} submitted approval request {} and cannot vote on it",
+                approver, id
+            )));
+        }
+        let vote = ApprovalVote {
+            approver: approver.to_string(),
+            approved,
+            comment: comment.map(str::to_string),
+            created_at: chrono::Utc::now(),
+        };
+        if !self
+            .storage
+            .add_approval_vote(id, &vote)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?
+        {
+            return Err(WalletError::ValidationError(format!(
+                "{} has already voted on approval request {}",
+                approver, id
+            )));
+        }
+        self.storage
+            .log_action(
+                &request.wallet_id,
+                "approval_vote",
+                &format!(
+                    "{}: {} by {}{}",
+                    id,
+                    if approved { "approved" } else { "rejected" },
+                    approver,
+                    comment.map(|c| format!(" ({})", c)).unwrap_or_default()
+                ),
+                None,
+                None,
+            )
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?;
+
+        let request = self.approval_request_or_error(id).await?;
+        if approved {
+            self.notify_approvers(&request).await;
+        }
+        Ok(request)
+    }
+
+    /// Carries out an approved operation; returns its transaction hash, or
+    /// the transfer id for a bridge transfer.
+    async fn execute_approved(&self, request: &ApprovalRequest) -> Result<String, WalletError> {
+        let wallet = request.wallet_name.as_str();
+        let approved = Some(request.id.as_str());
+        match &request.operation {
+            HeldOperation::Transfer { to_address, amount, network, allow_revert } => {
+                self.execute_transfer(wallet, to_address, amount, network, *allow_revert, approved)
+                    .await
+            }
+            HeldOperation::ContractCall { network, call, simulate, allow_revert } => self
+                .execute_contract_transaction(
+                    wallet,
+                    network,
+                    call,
+                    *simulate,
+                    *allow_revert,
+                    approved,
+                )
+                .await
+                .map(|receipt| receipt.tx_hash),
+            HeldOperation::Bridge { from_chain, to_chain, token, amount } => {
+                self.execute_bridge(wallet, from_chain, to_chain, token, amount, approved).await
+            }
+            HeldOperation::Swap { network, params } => self
+                .execute_swap(wallet, network, params, approved)
+                .await
+                .map(|receipt| receipt.tx_hash),
+        }
+    }
+
+    /// Moves a request on from its current status, writes the outcome to
+    /// the audit log and notifies. `false` when another caller moved it first.
+    async fn finish_approval(
+        &self,
+        request: &ApprovalRequest,
+        status: ApprovalStatus,
+        result: Option<&str>,
+    ) -> Result<bool, WalletError> {
+        if !self
+            .storage
+            .update_approval_status(&request.id, request.status, status, result)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?
+        {
+            return Ok(false);
+        }
+        self.storage
+            .log_action(
+                &request.wallet_id,
+                &format!("approval_{}", status),
+                &format!(
+                    "{}{}",
+                    request.id,
+                    result.map(|r| format!(": {}", r)).unwrap_or_default()
+                ),
+                None,
+                None,
+            )
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?;
+        let request = self.approval_request_or_error(&request.id).await?;
+        self.notify_approvers(&request).await;
+        Ok(true)
+    }
+
+    async fn approval_request_or_error(&self, id: &str) -> Result<ApprovalRequest, WalletError> {
+        self.storage
+            .get_approval_request(id)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?
+            .ok_or_else(|| WalletError::StorageError(format!("Approval request not found: {}", id)))
+    }
+
+    /// Notifications are best effort: a failing notifier never blocks a decision.
+    async fn notify_approvers(&self, request: &ApprovalRequest) {
+        if let Err(e) = self.approval_notifier.notify(request).await {
+            warn!("Failed to send notification for approval request {}: {}", request.id, e);
+        }
+        if request.status == ApprovalStatus::Pending {
+            events::publish(
+                &self.events,
+                WalletEvent::ApprovalNeeded {
+                    id: request.id.clone(),
+                    wallet: request.wallet_name.clone(),
+                    reason: request.reason.clone(),
+                    approvals: request.approvals(),
+                    required_approvals: request.required_approvals,
+                },
+            );
+        }
+    }
+
+    fn staking_provider(&self, network: &str) -> Result<&dyn StakingProvider, WalletError> {
+        self.staking_providers.get(network).map(|p| p.as_ref()).ok_or_else(|| {
+            WalletError::ValidationError(format!("No staking configured for {}", network))
+        })
+    }
+
+    /// The wallet's staking position `position_id` on `network`.
+    async fn staking_position(
+        &self,
+        wallet_name: &str,
+        network: &str,
+        position_id: &str,
+    ) -> Result<StakePosition, WalletError> {
+        let provider = self.staking_provider(network)?;
+        let signer = self.wallet_signer(wallet_name, network).await?;
+        let owner = self.signer_address(&signer, network).await?;
+        provider
+            .positions(&owner)
+            .await
+            .map_err(|e| WalletError::BlockchainError(e.to_string()))?
+            .into_iter()
+            .find(|p| p.position_id == position_id)
+            .ok_or_else(|| {
+                WalletError::ValidationError(format!(
+                    "No staking position {} on {}",
+                    position_id, network
+                ))
+            })
+    }
+
+    /// Stakes `amount` of the native token on `network`, delegating to
+    /// `validator` where the protocol has one.
+    pub async fn stake(
+        &self,
+        wallet_name: &str,
+        network: &str,
+        amount: &str,
+        validator: Option<&str>,
+    ) -> Result<StakeReceipt, WalletError> {
+        info!("Staking for wallet: {} on: {} amount: {}", wallet_name, network, amount);
+
+        let provider = self.staking_provider(network)?;
+        let wallet = self.get_wallet_by_name(wallet_name).await?.ok_or_else(|| {
+            WalletError::StorageError(format!("Wallet not found: {}", wallet_name))
+        })?;
+        let value =
+            validate_amount(amount).map_err(|e| WalletError::ValidationError(e.to_string()))?;
+        let compliance = self.check_compliance(
+            &wallet.id,
+            &TransactionType::Stake,
+            value,
+            validator.unwrap_or(""),
+        )?;
+        self.require_approval(&wallet, [compliance], Approval::Refuse).await?;
+
+        let mut wallet_data = self.load_wallet_securely(wallet_name).await?;
+        let result = provider.stake(amount, validator, &wallet_data).await;
+        wallet_data.zeroize();
+        let receipt = result.map_err(|e| WalletError::BlockchainError(e.to_string()))?;
+        self.record_staking_operation(&wallet.id, wallet_name, &receipt).await?;
+        Ok(receipt)
+    }
+
+    /// Starts unstaking a position, all of it when `amount` is `None`.
+    pub async fn unstake(
+        &self,
+        wallet_name: &str,
+        network: &str,
+        position_id: &str,
+        amount: Option<&str>,
+    ) -> Result<StakeReceipt, WalletError> {
+        info!("Unstaking for wallet: {} on: {} position: {}", wallet_name, network, position_id);
+
+        let provider = self.staking_provider(network)?;
+        let wallet = self.get_wallet_by_name(wallet_name).await?.ok_or_else(|| {
+            WalletError::StorageError(format!("Wallet not found: {}", wallet_name))
+        })?;
+        let position = self.staking_position(wallet_name, network, position_id).await?;
+        let value = validate_amount(amount.unwrap_or(&position.balance))
+            .map_err(|e| WalletError::ValidationError(e.to_string()))?;
+        let compliance =
+            self.check_compliance(&wallet.id, &TransactionType::Unstake, value, position_id)?;
+        self.require_approval(&wallet, [compliance], Approval::Refuse).await?;
+
+        let mut wallet_data = self.load_wallet_securely(wallet_name).await?;
+        let result = provider.unstake(&position, amount, &wallet_data).await;
+        wallet_data.zeroize();
+        let receipt = result.map_err(|e| WalletError::BlockchainError(e.to_string()))?;
+        self.record_staking_operation(&wallet.id, wallet_name, &receipt).await?;
+        Ok(receipt)
+    }
+
+    /// Withdraws a fully unstaked position back to the wallet.
+    pub async fn withdraw_stake(
+        &self,
+        wallet_name: &str,
+        network: &str,
+        position_id: &str,
+    ) -> Result<StakeReceipt, WalletError> {
+        info!(
+            "Withdrawing stake for wallet: {} on: {} position: {}",
+            wallet_name, network, position_id
+        );
+
+        let provider = self.staking_provider(network)?;
+        let wallet = self.get_wallet_by_name(wallet_name).await?.ok_or_else(|| {
+            WalletError::StorageError(format!("Wallet not found: {}", wallet_name))
+        })?;
+        let position = self.staking_position(wallet_name, network, position_id).await?;
+        let value = position.balance.parse::<f64>().unwrap_or(0.0);
+        let compliance =
+            self.check_compliance(&wallet.id, &TransactionType::Unstake, value, position_id)?;
+        self.require_approval(&wallet, [compliance], Approval::Refuse).await?;
+
+        let mut wallet_data = self.load_wallet_securely(wallet_name).await?;
+        let result = provider.withdraw(&position, &wallet_data).await;
+        wallet_data.zeroize();
+        let receipt = result.map_err(|e| WalletError::BlockchainError(e.to_string()))?;
+        self.record_staking_operation(&wallet.id, wallet_name, &receipt).await?;
+        Ok(receipt)
+    }
+
+    /// Records a staking operation in the wallet's transaction history, the
+    /// staking ledger used for rewards, and the audit log.
+    async fn record_staking_operation(
+        &self,
+        wallet_id: &str,
+        wallet_name: &str,
+        receipt: &StakeReceipt,
+    ) -> Result<(), WalletError> {
+        let signer = self.wallet_signer(wallet_name, &receipt.network).await?;
+        let from = self.signer_address(&signer, &receipt.network).await?;
+        let now = chrono::Utc::now();
+        self.storage
+            .store_transaction(&TransactionRecord {
+                id: uuid::Uuid::new_v4().to_string(),
+                wallet_id: wallet_id.to_string(),
+                tx_hash: receipt.tx_hash.clone(),
+                network: receipt.network.clone(),
+                from_address: from,
+                to_address: receipt.position_id.clone(),
+                amount: receipt.amount.clone(),
+                fee: receipt.network_fee.clone(),
+                status: "pending".to_string(),
+                created_at: now,
+                confirmed_at: None,
+            })
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?;
+        self.storage
+            .store_staking_operation(&StakingOperation {
+                tx_hash: receipt.tx_hash.clone(),
+                wallet_id: wallet_id.to_string(),
+                network: receipt.network.clone(),
+                protocol: receipt.protocol.as_str().to_string(),
+                action: receipt.action.as_str().to_string(),
+                position_id: receipt.position_id.clone(),
+                amount: receipt.amount.clone(),
+                created_at: now,
+            })
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?;
+        self.storage
+            .log_action(
+                wallet_id,
+                &format!("{}_submitted", receipt.action.as_str()),
+                &format!(
+                    "{} {} on {} position {}: {}",
+                    receipt.action.as_str(),
+                    receipt.amount,
+                    receipt.network,
+                    receipt.position_id,
+                    receipt.tx_hash
+                ),
+                None,
+                None,
+            )
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?;
+
+        info!("Staking {} sent with hash: {}", receipt.action.as_str(), receipt.tx_hash);
+        Ok(())
+    }
+
+    /// Staking positions held by the wallet on every staking network, with
+    /// rewards estimated from the wallet's own staking history.
+    pub async fn staking_summary(&self, wallet_name: &str) -> Result<StakingSummary, WalletError> {
+        let wallet = self.get_wallet_by_name(wallet_name).await?.ok_or_else(|| {
+            WalletError::StorageError(format!("Wallet not found: {}", wallet_name))
+        })?;
+        let operations = self
+            .storage
+            .get_staking_operations(&wallet.id)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?;
+
+        let mut networks: Vec<&String> = self.staking_providers.keys().collect();
+        networks.sort();
+        let mut positions = Vec::new();
+        for network in networks {
+            let signer = self.wallet_signer(wallet_name, network).await?;
+            let owner = self.signer_address(&signer, network).await?;
+            let found = self.staking_providers[network]
+                .positions(&owner)
+                .await
+                .map_err(|e| WalletError::BlockchainError(e.to_string()))?;
+            for mut position in found {
+                let amounts = |action: StakeAction| -> Vec<String> {
+                    operations
+                        .iter()
+                        .filter(|op| {
+                            op.network == position.network
+                                && op.position_id == position.position_id
+                                && op.action == action.as_str()
+                        })
+                        .map(|op| op.amount.clone())
+                        .collect()
+                };
+                let (deposited, unstaked) =
+                    (amounts(StakeAction::Stake), amounts(StakeAction::Unstake));
+                position.rewards =
+                    estimate_rewards(position.protocol, &position.balance, &deposited, &unstaked)
+                        .map_err(|e| WalletError::Other(e.to_string()))?;
+                positions.push(position);
+            }
+        }
+        Ok(StakingSummary { wallet: wallet_name.to_string(), positions })
+    }
+
+    fn walletconnect(&self) -> Result<&WalletConnect, WalletError> {
+        self.walletconnect
+            .as_deref()
+            .ok_or_else(|| WalletError::ValidationError("WalletConnect is not enabled".to_string()))
+    }
+
+    /// Pairs with a dApp from the `wc:` URI it shows and returns the pairing
+    /// topic. The dApp's session proposal then appears in
+    /// `walletconnect_proposals`.
+    pub async fn pair_walletconnect(&self, uri: &str) -> Result<String, WalletError> {
+        let walletconnect = self.walletconnect()?;
+        let uri: PairingUri = uri.parse().map_err(|e: anyhow::Error| {
+            WalletError::ValidationError(format!("Invalid pairing URI: {}", e))
+        })?;
+        if uri.is_expired() {
+            return Err(WalletError::ValidationError("Pairing URI has expired".to_string()));
+        }
+        walletconnect.pair(&uri).await.map_err(walletconnect_error)?;
+        Ok(uri.topic)
+    }
+
+    pub fn walletconnect_proposals(&self) -> Result<Vec<SessionProposal>, WalletError> {
+        self.walletconnect()?.proposals().map_err(walletconnect_error)
+    }
+
+    /// Approves a session proposal for `wallet_name`, granting the dApp the
+    /// wallet's accounts on the requested EVM chains it is enabled for.
+    pub async fn approve_walletconnect_proposal(
+        &self,
+        id: u64,
+        wallet_name: &str,
+    ) -> Result<Session, WalletError> {
+        let walletconnect = self.walletconnect()?;
+        let proposal =
+            walletconnect.proposal(id).map_err(walletconnect_error)?.ok_or_else(|| {
+                WalletError::ValidationError(format!("Unknown WalletConnect proposal {}", id))
+            })?;
+        let accounts = self.walletconnect_accounts(wallet_name).await?;
+        let namespaces = walletconnect::session_namespaces(&proposal, &accounts)
+            .map_err(|e| WalletError::ValidationError(e.to_string()))?;
+        let session = walletconnect
+            .approve_proposal(id, wallet_name, namespaces)
+            .await
+            .map_err(walletconnect_error)?;
+        info!(
+            "Approved WalletConnect session with {} for wallet {}",
+            session.peer.name, wallet_name
+        );
+        Ok(session)
+    }
+
+    pub async fn reject_walletconnect_proposal(&self, id: u64) -> Result<(), WalletError> {
+        let walletconnect = self.walletconnect()?;
+        if walletconnect.proposal(id).map_err(walletconnect_error)?.is_none() {
+            return Err(WalletError::ValidationError(format!(
+                "Unknown WalletConnect proposal {}",
+                id
+            )));
+        }
+        walletconnect.reject_proposal(id).await.map_err(walletconnect_error)
+    }
+
+    pub fn walletconnect_sessions(&self) -> Result<Vec<Session>, WalletError> {
+        self.walletconnect()?.sessions().map_err(walletconnect_error)
+    }
+
+    pub async fn disconnect_walletconnect_session(&self, topic: &str) -> Result<(), WalletError> {
+        let walletconnect = self.walletconnect()?;
+        let sessions = walletconnect.sessions().map_err(walletconnect_error)?;
+        if !sessions.iter().any(|s| s.topic == topic) {
+            return Err(WalletError::ValidationError(format!(
+                "Unknown WalletConnect session {}",
+                topic
+            )));
+        }
+        walletconnect.disconnect(topic).await.map_err(walletconnect_error)
+    }
+
+    /// dApp requests waiting for approval.
+    pub fn walletconnect_requests(&self) -> Result<Vec<SessionRequest>, WalletError> {
+        self.walletconnect()?.requests().map_err(walletconnect_error)
+    }
+
+    /// Carries out a queued dApp request with the session's wallet and sends
+    /// the result back to the dApp. A request that fails is answered with the
+    /// error, so the dApp is not left waiting.
+    pub async fn approve_walletconnect_request(&self, id: u64) -> Result<Value, WalletError> {
+        let walletconnect = self.walletconnect()?;
+        let request = walletconnect.request(id).map_err(walletconnect_error)?.ok_or_else(|| {
+            WalletError::ValidationError(format!("Unknown WalletConnect request {}", id))
+        })?;
+        info!("Approving WalletConnect {} from {}", request.method, request.peer.name);
+
+        match self.execute_walletconnect_request(&request).await {
+            Ok(result) => {
+                walletconnect.respond(id, result.clone()).await.map_err(walletconnect_error)?;
+                Ok(result)
+            }
+            Err(e) => {
+                walletconnect
+                    .respond_error(id, WALLETCONNECT_REQUEST_FAILED, &e.to_string())
+                    .await
+                    .map_err(walletconnect_error)?;
+                Err(e)
+            }
+        }
+    }
+
+    pub async fn reject_walletconnect_request(&self, id: u64) -> Result<(), WalletError> {
+        let walletconnect = self.walletconnect()?;
+        if walletconnect.request(id).map_err(walletconnect_error)?.is_none() {
+            return Err(WalletError::ValidationError(format!(
+                "Unknown WalletConnect request {}",
+                id
+            )));
+        }
+        walletconnect
+            .respond_error(id, walletconnect::USER_REJECTED, "User rejected.")
+            .await
+            .map_err(walletconnect_error)
+    }
+
+    /// CAIP-10 accounts of the wallet on the EVM networks it is enabled for.
+    async fn walletconnect_accounts(&self, wallet_name: &str) -> Result<Vec<String>, WalletError> {
+        let mut wallet_data = self.load_wallet_securely(wallet_name).await?;
+        let networks = wallet_data.info.networks.clone();
+        wallet_data.zeroize();
+
+        let mut accounts = Vec::new();
+        for network in networks.iter().filter(|n| !n.starts_with("solana")) {
+            let Some(chain_id) = self.networks.get(network).and_then(|n| n.chain_id) else {
+                continue;
+            };
+            let signer = self.wallet_signer(wallet_name, network).await?;
+            let address = self.signer_address(&signer, network).await?;
+            let account = format!("{}:{}:{}", walletconnect::EIP155, chain_id, address);
+            if !accounts.contains(&account) {
+                accounts.push(account);
+            }
+        }
+        Ok(accounts)
+    }
+
+    async fn execute_walletconnect_request(
+        &self,
+        request: &SessionRequest,
+    ) -> Result<Value, WalletError> {
+        let network = self.walletconnect_network(&request.chain_id)?;
+        let signer = self.wallet_signer(&request.wallet, network).await?;
+        let address = self.signer_address(&signer, network).await?;
+        let param = |index: usize| {
+            request.params.get(index).ok_or_else(|| {
+                WalletError::ValidationError(format!(
+                    "{} is missing parameter {}",
+                    request.method, index
+                ))
+            })
+        };
+
+        match request.method.as_str() {
+            walletconnect::PERSONAL_SIGN => {
+                let message = param(0)?.as_str().ok_or_else(|| {
+                    WalletError::ValidationError("Message must be a string".to_string())
+                })?;
+                ensure_account(param(1)?, &address)?;
+                let message = match message.strip_prefix("0x") {
+                    Some(data) => hex::decode(data).map_err(|_| {
+                        WalletError::ValidationError("Message is not valid hex".to_string())
+                    })?,
+                    None => message.as_bytes().to_vec(),
+                };
+                let signed = self.sign_message(&request.wallet, network, &message).await?;
+                Ok(Value::String(signed.signature))
+            }
+            walletconnect::ETH_SIGN_TYPED_DATA_V4 => {
+                ensure_account(param(0)?, &address)?;
+                let typed_data: TypedData = match param(1)? {
+                    Value::String(json) => serde_json::from_str(json),
+                    other => serde_json::from_value(other.clone()),
+                }
+                .map_err(|e| WalletError::ValidationError(format!("Invalid typed data: {}", e)))?;
+                let signed = self.sign_typed_data(&request.wallet, network, &typed_data).await?;
+                Ok(Value::String(signed.signature))
+            }
+            walletconnect::ETH_SEND_TRANSACTION => {
+                let tx = param(0)?;
+                ensure_account(&tx["from"], &address)?;
+                let to = tx["to"].as_str().ok_or_else(|| {
+                    WalletError::ValidationError(
+                        "Contract deployments are not supported".to_string(),
+                    )
+                })?;
+                validate_address(to, network)
+                    .map_err(|e| WalletError::ValidationError(e.to_string()))?;
+                let data: Bytes = match tx["data"].as_str().or(tx["input"].as_str()) {
+                    Some(data) => data.parse().map_err(|_| {
+                        WalletError::ValidationError(
+                            "Transaction data is not valid hex".to_string(),
+                        )
+                    })?,
+                    None => Bytes::default(),
+                };
+                let value = match tx["value"].as_str().map(|v| v.trim_start_matches("0x")) {
+                    Some(value) if !value.is_empty() => {
+                        U256::from_str_radix(value, 16).map_err(|_| {
+                            WalletError::ValidationError(
+                                "Transaction value is not valid hex".to_string(),
+                            )
+                        })?
+                    }
+                    _ => U256::zero(),
+                };
+
+                let checked = self
+                    .build_checked_call(
+                        &request.wallet,
+                        network,
+                        to,
+                        data,
+                        &format_ether(value),
+                        Approval::Refuse,
+                    )
+                    .await?;
+                let simulation =
+                    Self::preflight(checked.client, &checked.unsigned, false, None).await;
+                self.release_on_failure(checked.reservation, simulation).await?;
+                let tx_hash = self.send_checked_call(&checked).await?;
+                info!("WalletConnect transaction sent with hash: {}", tx_hash);
+                Ok(Value::String(tx_hash))
+            }
+            other => Err(WalletError::ValidationError(format!(
+                "Unsupported WalletConnect method {}",
+                other
+            ))),
+        }
+    }
+
+    /// Configured network for a CAIP-2 chain such as `eip155:1`.
+    fn walletconnect_network(&self, chain_id: &str) -> Result<&str, WalletError> {
+        let id =
+            chain_id.strip_prefix("eip155:").and_then(|id| id.parse::<u64>().ok()).ok_or_else(
+                || WalletError::ValidationError(format!("Unsupported chain {}", chain_id)),
+            )?;
+        let mut networks: Vec<&String> = self
+            .networks
+            .iter()
+            .filter(|(_, config)| config.chain_id == Some(id))
+            .map(|(name, _)| name)
+            .collect();
+        networks.sort();
+        networks.first().map(|name| name.as_str()).ok_or_else(|| {
+            WalletError::ValidationError(format!("No network configured for {}", chain_id))
+        })
+    }
+
+    pub fn derive_address(&self, master_key: &[u8], network: &str) -> Result<String, WalletError> {
+        match network {
+            "eth" => {
+                let addr_bytes = if master_key.len() >= 20 {
+                    master_key[..20].to_vec()
+                } else {
+                    let mut v = vec![0u8; 20];
+                    v[..master_key.len()].copy_from_slice(master_key);
+                    v
+                };
+                Ok(format!("0x{}", hex::encode(&addr_bytes)))
+            }
+            "solana" => {
+                let key_bytes = if master_key.len() >= 32 {
+                    master_key[..32].to_vec()
+                } else {
+                    let mut v = vec![0u8; 32];
+                    v[..master_key.len()].copy_from_slice(master_key);
+                    v
+                };
+                Ok(bs58::encode(&key_bytes).into_string())
+            }
+            _ => Err(WalletError::ValidationError(format!("Unsupported network: {}", network))),
+        }
+    }
+
+    /// In-process signer for a wallet's key on `network`; the derived key is
+    /// held only by the signer and wiped when it is dropped.
+    fn software_signer(
+        &self,
+        master_key: &[u8],
+        network: &str,
+    ) -> Result<SoftwareSigner, WalletError> {
+        SoftwareSigner::for_network(master_key, network)
+    }
+
+    async fn load_wallet_securely(
+        &self,
+        wallet_name: &str,
+    ) -> Result<SecureWalletData, WalletError> {
+        let (serialized_data, quantum_safe) = self
+            .storage
+            .load_wallet(wallet_name)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?;
+
+        let mut wallet_data: SecureWalletData = bincode::deserialize(&serialized_data)
+            .map_err(|e| WalletError::SerializationError(e.to_string()))?;
 
         // 获取用于解密的真实主密钥
         let master_key_for_decrypt = self.get_master_key_for_wallet(wallet_name)?;
@@ -541,15 +3411,332 @@ impl WalletManager {
         Ok(plaintext)
     }
 
+    /// Hashes of the transactions recorded for a wallet, newest first.
     pub async fn get_transaction_history(
         &self,
-        _wallet_name: &str,
+        wallet_name: &str,
     ) -> Result<Vec<String>, WalletError> {
-        Ok(vec![])
+        let Some(wallet) = self.get_wallet_by_name(wallet_name).await? else {
+            return Ok(vec![]);
+        };
+        let transactions = self
+            .storage
+            .get_wallet_transactions(&wallet.id)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?;
+        Ok(transactions.into_iter().map(|tx| tx.tx_hash).collect())
     }
 
     pub async fn backup_wallet(&self, wallet_name: &str) -> Result<String, WalletError> {
-        backup::backup_wallet(&self.storage, wallet_name).await
+        let seed_phrase = backup::backup_wallet(&self.storage, wallet_name).await?;
+        self.audit_wallet(wallet_name, "wallet_backup", "").await?;
+        Ok(seed_phrase)
+    }
+
+    /// Exports the wallet's key on `network` as a passphrase-encrypted key
+    /// file for the offline signer (`wallet-cli sign`).
+    pub async fn export_key_file(
+        &self,
+        wallet_name: &str,
+        network: &str,
+        passphrase: &str,
+    ) -> Result<EncryptedKeyFile, WalletError> {
+        let mut wallet_data = self.load_wallet_securely(wallet_name).await?;
+        let key_file =
+            EncryptedKeyFile::for_network(&wallet_data.encrypted_master_key, network, passphrase);
+        wallet_data.zeroize();
+        let key_file = key_file.map_err(|e| WalletError::CryptoError(e.to_string()))?;
+        self.audit_wallet(wallet_name, "key_exported", network).await?;
+        Ok(key_file)
+    }
+
+    /// Backs up a wallet and returns the payload together with a signed manifest.
+    pub async fn backup_wallet_signed(
+        &self,
+        wallet_name: &str,
+    ) -> Result<(String, BackupManifest), WalletError> {
+        let seed_phrase = backup::backup_wallet(&self.storage, wallet_name).await?;
+        self.audit_wallet(wallet_name, "wallet_backup", "signed").await?;
+
+        let mut manifest = BackupManifest::new(wallet_name, seed_phrase.as_bytes());
+        let keypair = self.artifact_keypair().await?;
+        manifest.signature = Some(
+            keypair
+                .sign(&manifest.signing_bytes())
+                .map_err(|e| WalletError::CryptoError(e.to_string()))?,
+        );
+
+        Ok((seed_phrase, manifest))
+    }
+
+    /// Checks a backup manifest against its payload and the artifact signing key.
+    pub async fn verify_backup_manifest(
+        &self,
+        manifest: &BackupManifest,
+        payload: &[u8],
+    ) -> Result<bool, WalletError> {
+        if !manifest.matches_payload(payload) {
+            warn!("Backup manifest for '{}' does not match payload", manifest.wallet_name);
+            return Ok(false);
+        }
+        let Some(signature) = manifest.signature.as_ref() else {
+            return Ok(false);
+        };
+        let record = match self.load_artifact_key().await {
+            Ok(Some(record)) => record,
+            Ok(None) => return Ok(false),
+            Err(e) => {
+                warn!("Cannot verify backup manifest for '{}': {}", manifest.wallet_name, e);
+                return Ok(false);
+            }
+        };
+
+        signing::verify(&record.public_key, &manifest.signing_bytes(), signature)
+            .map_err(|e| WalletError::CryptoError(e.to_string()))
+    }
+
+    /// Signs the audit log chain up to its latest row and stores the checkpoint.
+    /// Returns `None` when the audit log is empty, and the latest checkpoint
+    /// when no rows were appended since it.
+    pub async fn sign_audit_log(&self) -> Result<Option<AuditSignature>, WalletError> {
+        let Some(last_log_id) = self
+            .storage
+            .get_latest_audit_log_id()
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?
+        else {
+            return Ok(None);
+        };
+        if let Some(latest) = self
+            .storage
+            .get_latest_audit_signature()
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?
+            .filter(|latest| latest.last_log_id == last_log_id)
+        {
+            return Ok(Some(latest));
+        }
+
+        let logs = self
+            .storage
+            .get_audit_logs_through(last_log_id)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?;
+        let digest = chain_digest(&logs);
+
+        let keypair = self.artifact_keypair().await?;
+        let signature =
+            keypair.sign(&digest).map_err(|e| WalletError::CryptoError(e.to_string()))?;
+
+        let mut checkpoint = AuditSignature {
+            id: 0,
+            last_log_id,
+            chain_digest: hex::encode(digest),
+            signature: serde_json::to_string(&signature)
+                .map_err(|e| WalletError::SerializationError(e.to_string()))?,
+            created_at: chrono::Utc::now(),
+        };
+        checkpoint.id = self
+            .storage
+            .store_audit_signature(&checkpoint)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?;
+
+        info!("Signed audit log through row {}", last_log_id);
+        Ok(Some(checkpoint))
+    }
+
+    /// Whether the audit log passes `verify_audit_chain`.
+    pub async fn verify_audit_log(&self) -> Result<bool, WalletError> {
+        Ok(self.verify_audit_chain().await?.is_valid())
+    }
+
+    /// Checks every audit row against the hash chain and every checkpoint
+    /// against the rows and its signature, reporting the first row found
+    /// deleted or edited.
+    pub async fn verify_audit_chain(&self) -> Result<ChainVerification, WalletError> {
+        let logs = self
+            .storage
+            .get_audit_logs_through(i64::MAX)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?;
+        let checkpoints = self
+            .storage
+            .get_audit_signatures()
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?;
+        let mut result = verify_chain(&logs, &checkpoints);
+        if !result.is_valid() {
+            return Ok(result);
+        }
+
+        let record = match self.load_artifact_key().await {
+            Ok(record) => record,
+            Err(e @ WalletError::CryptoError(_)) => {
+                result.error = Some(e.to_string());
+                return Ok(result);
+            }
+            Err(e) => return Err(e),
+        };
+        result.key_id = record.as_ref().map(|record| signing::key_id_for(&record.public_key));
+        if checkpoints.is_empty() {
+            return Ok(result);
+        }
+        let Some(record) = record else {
+            result.error = Some("Audit checkpoints exist but the signing key is missing".into());
+            return Ok(result);
+        };
+        for checkpoint in &checkpoints {
+            let signed = hex::decode(&checkpoint.chain_digest)
+                .ok()
+                .zip(serde_json::from_str::<signing::PqSignature>(&checkpoint.signature).ok());
+            let valid = match signed {
+                Some((digest, signature)) => {
+                    signing::verify(&record.public_key, &digest, &signature).unwrap_or(false)
+                }
+                None => false,
+            };
+            if !valid {
+                result.first_invalid_row = Some(checkpoint.last_log_id);
+                result.error =
+                    Some(format!("Checkpoint {} has an invalid signature", checkpoint.id));
+                break;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Signs a checkpoint every `interval` while the manager is alive. A
+    /// checkpoint is only added when rows were appended since the last one.
+    pub fn spawn_audit_checkpoints(
+        self: &Arc<Self>,
+        interval: std::time::Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        let manager = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let Some(manager) = manager.upgrade() else {
+                    break;
+                };
+                if let Err(e) = manager.sign_audit_log().await {
+                    warn!("Failed to sign audit checkpoint: {}", e);
+                }
+            }
+        })
+    }
+
+    /// Delivers the audit rows created in `[since, until)` to `sink`, e.g. for
+    /// a one-off export to a file. Returns how many were delivered.
+    pub async fn export_audit_range(
+        &self,
+        format: ExportFormat,
+        sink: &ExportSink,
+        since: Option<chrono::DateTime<chrono::Utc>>,
+        until: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<usize, WalletError> {
+        export::export_range(self.storage.as_ref(), format, sink, since, until, "-")
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))
+    }
+
+    /// A page of audit rows matching `query`, newest first, and how many
+    /// rows match in all.
+    pub async fn audit_logs(
+        &self,
+        query: &AuditQuery,
+    ) -> Result<(Vec<AuditLog>, i64), WalletError> {
+        let logs = self
+            .storage
+            .query_audit_logs(query)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?;
+        let total = self
+            .storage
+            .count_audit_logs(query)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?;
+        Ok((logs, total))
+    }
+
+    /// Loads the artifact signing key from the keystore, generating and
+    /// persisting an ML-DSA-65 key on first use. A pinned key is never
+    /// generated; it has to be in the keystore already.
+    async fn artifact_keypair(&self) -> Result<PqKeyPair, WalletError> {
+        if let Some(record) = self.load_artifact_key().await? {
+            return self.decode_signing_key(&record);
+        }
+        if let Some(pin) = &self.artifact_key_pin {
+            return Err(WalletError::CryptoError(format!(
+                "Pinned artifact signing key {} is not in the keystore",
+                pin
+            )));
+        }
+
+        let keypair = PqKeyPair::generate(SignatureAlgorithm::MlDsa65);
+        let encrypted_secret_key = self
+            .quantum_crypto
+            .encrypt(keypair.secret_key())
+            .map_err(|e| WalletError::CryptoError(e.to_string()))?;
+        let record = SigningKeyRecord {
+            purpose: ARTIFACT_SIGNING_KEY.to_string(),
+            key_id: keypair.key_id(),
+            algorithm: keypair.algorithm.to_string(),
+            public_key: keypair.public_key.clone(),
+            encrypted_secret_key,
+            created_at: chrono::Utc::now(),
+        };
+
+        if let Err(e) = self.storage.store_signing_key(&record).await {
+            // A concurrent caller may have stored a key first; use that one.
+            warn!("Storing artifact signing key failed ({}), reloading", e);
+            let existing = self
+                .load_artifact_key()
+                .await?
+                .ok_or_else(|| WalletError::StorageError(e.to_string()))?;
+            return self.decode_signing_key(&existing);
+        }
+
+        info!(
+            "Generated artifact signing key {}; set security.artifact_key_id to pin it",
+            record.key_id
+        );
+        Ok(keypair)
+    }
+
+    /// The stored artifact signing key. Fails with a `CryptoError` when a key
+    /// id is pinned and the stored public key does not hash to it.
+    async fn load_artifact_key(&self) -> Result<Option<SigningKeyRecord>, WalletError> {
+        let record = self
+            .storage
+            .load_signing_key(ARTIFACT_SIGNING_KEY)
+            .await
+            .map_err(|e| WalletError::StorageError(e.to_string()))?;
+        if let (Some(pin), Some(record)) = (&self.artifact_key_pin, &record) {
+            let key_id = signing::key_id_for(&record.public_key);
+            if !key_id.eq_ignore_ascii_case(pin.trim()) {
+                return Err(WalletError::CryptoError(format!(
+                    "Artifact signing key {} does not match the pinned key {}",
+                    key_id, pin
+                )));
+            }
+        }
+        Ok(record)
+    }
+
+    fn decode_signing_key(&self, record: &SigningKeyRecord) -> Result<PqKeyPair, WalletError> {
+        let algorithm: SignatureAlgorithm = record
+            .algorithm
+            .parse()
+            .map_err(|e: anyhow::Error| WalletError::CryptoError(e.to_string()))?;
+        let secret_key = self
+            .quantum_crypto
+            .decrypt(&record.encrypted_secret_key)
+            .map_err(|e| WalletError::CryptoError(e.to_string()))?;
+        PqKeyPair::from_bytes(algorithm, record.public_key.clone(), secret_key)
+            .map_err(|e| WalletError::CryptoError(e.to_string()))
     }
 
     pub async fn restore_wallet(
@@ -558,14 +3745,35 @@ impl WalletManager {
         seed_phrase: &str,
         quantum_safe: bool,
     ) -> Result<(), WalletError> {
+        let encryption_key = self.get_master_key_for_wallet(wallet_name)?;
         recover::recover_wallet(
             &self.storage,
             &self.quantum_crypto,
+            &encryption_key,
             wallet_name,
             seed_phrase,
             quantum_safe,
         )
-        .await
+        .await?;
+        self.audit_wallet(wallet_name, "wallet_restored", "").await
+    }
+
+    /// Appends an audit row for the named wallet. A wallet with no stored
+    /// record is audited by name instead.
+    async fn audit_wallet(
+        &self,
+        wallet_name: &str,
+        action: &str,
+        details: &str,
+    ) -> Result<(), WalletError> {
+        let result = match self.get_wallet_by_name(wallet_name).await? {
+            Some(wallet) => self.storage.log_action(&wallet.id, action, details, None, None).await,
+            None => {
+                let details = format!("{} {}", wallet_name, details);
+                self.storage.log_event(None, action, details.trim_end()).await
+            }
+        };
+        result.map_err(|e| WalletError::StorageError(e.to_string()))
     }
 
     pub async fn send_multi_sig_transaction(
@@ -583,3 +3791,127 @@ impl WalletManager {
         crate::core::wallet::create::generate_mnemonic()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::ethereum::EthereumClient;
+    use crate::core::config::{BlockchainConfig, StorageConfig};
+    use ethers::providers::Provider;
+    use ethers::types::{Block, Transaction, TransactionRequest, H256, U64};
+
+    const FROM: &str = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
+
+    fn transfer() -> UnsignedTransaction {
+        let from: Address = FROM.parse().unwrap();
+        UnsignedTransaction::Evm { tx: TransactionRequest::pay(from, 1u64).from(from).into() }
+    }
+
+    #[tokio::test]
+    async fn test_preflight_refuses_failed_simulations_unless_allowed() {
+        let (provider, mock) = Provider::mocked();
+        let client = EthereumClient::new_with_provider(provider);
+        let unsigned = transfer();
+
+        // Both debug_traceCall and the eth_call fallback fail.
+        let err = WalletManager::preflight(&client, &unsigned, false, None).await.unwrap_err();
+        assert!(err.to_string().contains("could not be simulated"), "{}", err);
+        let skipped = WalletManager::preflight(&client, &unsigned, true, None).await.unwrap();
+        assert!(skipped.is_none());
+
+        mock.push(serde_json::json!({ "from": FROM, "error": "execution reverted" })).unwrap();
+        let err = WalletManager::preflight(&client, &unsigned, false, None).await.unwrap_err();
+        assert!(err.to_string().contains("would revert"), "{}", err);
+
+        mock.push(serde_json::json!({ "from": FROM, "to": FROM, "gasUsed": "0x5208" })).unwrap();
+        let simulation = WalletManager::preflight(&client, &unsigned, false, None).await.unwrap();
+        assert!(simulation.unwrap().success);
+    }
+
+    async fn manager() -> WalletManager {
+        let config = WalletConfig {
+            storage: StorageConfig {
+                database_url: "sqlite::memory:".to_string(),
+                max_connections: Some(1),
+                connection_timeout_seconds: Some(30),
+            },
+            blockchain: BlockchainConfig { networks: HashMap::new(), default_network: None },
+            quantum_safe: false,
+            multi_sig_threshold: 1,
+        };
+        WalletManager::new(&config).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_watched_addresses_are_the_signing_addresses() {
+        let mut wm = manager().await;
+        let mut clients: HashMap<String, Box<dyn BlockchainClient>> = HashMap::new();
+        for network in ["eth", "polygon"] {
+            let (provider, _) = Provider::mocked();
+            clients
+                .insert(network.to_string(), Box::new(EthereumClient::new_with_provider(provider)));
+        }
+        wm.blockchain_clients = Arc::new(clients);
+        wm.create_wallet("watched", false).await.unwrap();
+
+        let addresses = wm.balance_addresses("watched").await.unwrap();
+        assert_eq!(addresses.len(), 2);
+        for network in ["eth", "polygon"] {
+            let signer = wm.wallet_signer("watched", network).await.unwrap();
+            let expected = wm.signer_address(&signer, network).await.unwrap();
+            assert_eq!(addresses[network], expected);
+        }
+        assert_ne!(addresses["eth"], addresses["polygon"]);
+    }
+
+    #[tokio::test]
+    async fn test_balance_poll_screens_received_deposits() {
+        let mut wm = manager().await;
+        let (provider, mock) = Provider::mocked();
+        let mut clients: HashMap<String, Box<dyn BlockchainClient>> = HashMap::new();
+        clients.insert("eth".to_string(), Box::new(EthereumClient::new_with_provider(provider)));
+        wm.blockchain_clients = Arc::new(clients);
+        wm.storage.store_wallet("depositor", b"{}", false).await.unwrap();
+        let watched = WatchedWallet {
+            watchers: 1,
+            addresses: HashMap::from([("eth".to_string(), FROM.to_lowercase())]),
+            ..WatchedWallet::default()
+        };
+        wm.watched_balances.lock().unwrap().insert("depositor".to_string(), watched);
+        let mut events = wm.subscribe_events();
+
+        // MockProvider answers in LIFO order: block number, then balance.
+        mock.push(U256::exp10(18)).unwrap();
+        mock.push(U64::from(10)).unwrap();
+        assert_eq!(wm.poll_balances().await, 0);
+
+        // the balance went up: blocks 11 and 12 are searched for the deposit
+        let sender: Address = "0x00000000000000000000000000000000000000aa".parse().unwrap();
+        let deposit = Transaction {
+            hash: H256::repeat_byte(1),
+            from: sender,
+            to: Some(FROM.parse().unwrap()),
+            value: U256::exp10(18),
+            ..Transaction::default()
+        };
+        let unrelated =
+            Transaction { hash: H256::repeat_byte(2), to: Some(sender), ..deposit.clone() };
+        mock.push(Block { transactions: vec![deposit, unrelated], ..Block::default() }).unwrap();
+        mock.push(Block::<Transaction>::default()).unwrap();
+        mock.push(U256::exp10(18) * 2).unwrap();
+        mock.push(U64::from(12)).unwrap();
+        assert_eq!(wm.poll_balances().await, 1);
+
+        assert!(matches!(events.try_recv().unwrap(), WalletEvent::BalanceChanged { .. }));
+        match events.try_recv().unwrap() {
+            WalletEvent::IncomingDeposit { wallet, from_address, tx_hash, sanctioned, .. } => {
+                assert_eq!(wallet, "depositor");
+                assert_eq!(from_address, format!("{:?}", sender));
+                assert_eq!(tx_hash, format!("{:?}", H256::repeat_byte(1)));
+                assert!(!sanctioned);
+            }
+            other => panic!("expected a deposit, got {:?}", other),
+        }
+        assert!(events.try_recv().is_err());
+    }
+}
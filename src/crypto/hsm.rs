@@ -1,6 +1,7 @@
 // src/crypto/hsm.rs
 use anyhow::Result;
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tracing::{debug, info, warn};
@@ -23,11 +24,38 @@ pub struct SecureMemoryRegion {
     allocated_at: DateTime<Utc>,
 }
 
+/// Curves supported for signing keys held by the HSM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HSMKeyType {
+    Secp256k1,
+    Ed25519,
+}
+
+/// Public view of a signing key. `id` is the hex `CKA_ID` on a PKCS#11 token
+/// or a random id for software keys; secp256k1 public keys are SEC1 uncompressed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HSMKeyHandle {
+    pub id: String,
+    pub label: String,
+    pub key_type: HSMKeyType,
+    pub public_key: Vec<u8>,
+}
+
+#[derive(Zeroize, ZeroizeOnDrop)]
+struct SoftwareKey {
+    #[zeroize(skip)]
+    handle: HSMKeyHandle,
+    secret: Vec<u8>,
+}
+
 pub struct HSMManager {
     config: HSMConfig,
     secure_regions: Arc<Mutex<std::collections::HashMap<u64, SecureMemoryRegion>>>,
     next_id: Arc<Mutex<u64>>,
     initialized: bool,
+    software_keys: Arc<Mutex<std::collections::HashMap<String, SoftwareKey>>>,
+    #[cfg(feature = "pkcs11")]
+    pkcs11: Option<crate::crypto::pkcs11::Pkcs11Backend>,
 }
 
 impl HSMManager {
@@ -46,6 +74,9 @@ impl HSMManager {
             secure_regions: Arc::new(Mutex::new(std::collections::HashMap::new())),
             next_id: Arc::new(Mutex::new(1)),
             initialized: false,
+            software_keys: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            #[cfg(feature = "pkcs11")]
+            pkcs11: None,
         })
     }
 
@@ -55,13 +86,23 @@ impl HSMManager {
         self.config = config;
 
         if self.config.enabled {
-            // In a real implementation, this would:
-            // 1. Connect to the HSM device
-            // 2. Authenticate with PIN
-            // 3. Initialize secure memory pools
-            // 4. Set up memory isolation
+            // `device_path` is the PKCS#11 module to load, e.g.
+            // /usr/lib/softhsm/libsofthsm2.so. If the token cannot be opened
+            // signing keys stay in software.
+            #[cfg(feature = "pkcs11")]
+            match crate::crypto::pkcs11::Pkcs11Backend::open(
+                &self.config.device_path,
+                &self.config.pin,
+            ) {
+                Ok(backend) => {
+                    self.pkcs11 = Some(backend);
+                    info!("HSM device connection established");
+                }
+                Err(e) => warn!("PKCS#11 backend unavailable, using software keys: {}", e),
+            }
+            #[cfg(not(feature = "pkcs11"))]
+            warn!("Built without the pkcs11 feature - using software keys");
 
-            info!("HSM device connection established");
             info!("Memory isolation enabled: {}", self.config.isolation_enabled);
         } else {
             info!("HSM disabled - using software-based secure memory simulation");
@@ -172,6 +213,115 @@ impl HSMManager {
         Ok(signature)
     }
 
+    /// Generates a signing key. On a PKCS#11 token the private key is created
+    /// non-extractable and never leaves the device.
+    pub async fn generate_key(&self, key_type: HSMKeyType, label: &str) -> Result<HSMKeyHandle> {
+        if !self.initialized {
+            return Err(anyhow::anyhow!("HSM not initialized"));
+        }
+
+        #[cfg(feature = "pkcs11")]
+        if let Some(backend) = &self.pkcs11 {
+            let handle = backend.generate_key(key_type, label)?;
+            info!("Generated {:?} key {} on token", key_type, handle.id);
+            return Ok(handle);
+        }
+
+        use rand::RngCore;
+        let mut id = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut id);
+        let id = hex::encode(id);
+
+        let (public_key, secret) = match key_type {
+            HSMKeyType::Secp256k1 => {
+                let sk = k256::ecdsa::SigningKey::random(&mut rand::rngs::OsRng);
+                let pk = sk.verifying_key().to_encoded_point(false).as_bytes().to_vec();
+                (pk, sk.to_bytes().to_vec())
+            }
+            HSMKeyType::Ed25519 => {
+                let sk = ed25519_dalek::SigningKey::generate(&mut rand::rngs::OsRng);
+                (sk.verifying_key().to_bytes().to_vec(), sk.to_bytes().to_vec())
+            }
+        };
+
+        let handle =
+            HSMKeyHandle { id: id.clone(), label: label.to_string(), key_type, public_key };
+        self.software_keys
+            .lock()
+            .await
+            .insert(id.clone(), SoftwareKey { handle: handle.clone(), secret });
+
+        info!("Generated {:?} software key {}", key_type, id);
+        Ok(handle)
+    }
+
+    /// Signs `digest` with the key `key_id`. secp256k1 keys produce a 64-byte
+    /// `r || s` ECDSA signature over the prehashed digest; Ed25519 keys sign the
+    /// bytes as the message (PureEdDSA).
+    pub async fn sign_digest(&self, key_id: &str, digest: &[u8]) -> Result<Vec<u8>> {
+        debug!("Signing digest with key {}", key_id);
+
+        if !self.initialized {
+            return Err(anyhow::anyhow!("HSM not initialized"));
+        }
+
+        #[cfg(feature = "pkcs11")]
+        if let Some(backend) = &self.pkcs11 {
+            return backend.sign_digest(key_id, digest);
+        }
+
+        let keys = self.software_keys.lock().await;
+        let key = keys.get(key_id).ok_or_else(|| anyhow::anyhow!("Key not found: {}", key_id))?;
+
+        match key.handle.key_type {
+            HSMKeyType::Secp256k1 => {
+                let sk = k256::ecdsa::SigningKey::from_slice(&key.secret)
+                    .map_err(|e| anyhow::anyhow!("Invalid secp256k1 key: {}", e))?;
+                let (signature, _) = sk
+                    .sign_prehash_recoverable(digest)
+                    .map_err(|e| anyhow::anyhow!("secp256k1 signing failed: {}", e))?;
+                Ok(signature.to_bytes().to_vec())
+            }
+            HSMKeyType::Ed25519 => {
+                use ed25519_dalek::Signer;
+                let secret: [u8; 32] = key
+                    .secret
+                    .as_slice()
+                    .try_into()
+                    .map_err(|_| anyhow::anyhow!("Invalid Ed25519 key length"))?;
+                let sk = ed25519_dalek::SigningKey::from_bytes(&secret);
+                Ok(sk.sign(digest).to_bytes().to_vec())
+            }
+        }
+    }
+
+    /// Lists signing keys known to the active backend.
+    pub async fn list_keys(&self) -> Result<Vec<HSMKeyHandle>> {
+        if !self.initialized {
+            return Err(anyhow::anyhow!("HSM not initialized"));
+        }
+
+        #[cfg(feature = "pkcs11")]
+        if let Some(backend) = &self.pkcs11 {
+            return backend.list_keys();
+        }
+
+        let keys = self.software_keys.lock().await;
+        Ok(keys.values().map(|k| k.handle.clone()).collect())
+    }
+
+    /// True when keys are held on a PKCS#11 token rather than in process memory.
+    pub fn is_hardware_backed(&self) -> bool {
+        #[cfg(feature = "pkcs11")]
+        {
+            self.pkcs11.is_some()
+        }
+        #[cfg(not(feature = "pkcs11"))]
+        {
+            false
+        }
+    }
+
     pub async fn get_memory_stats(&self) -> Result<HSMMemoryStats> {
         let regions = self.secure_regions.lock().await;
 
@@ -181,7 +331,7 @@ impl HSMManager {
         Ok(HSMMemoryStats {
             total_regions,
             total_memory_bytes: total_memory,
-            average_region_size: if total_regions > 0 { total_memory / total_regions } else { 0 },
+            average_region_size: total_memory.checked_div(total_regions).unwrap_or(0),
         })
     }
 
@@ -271,4 +421,43 @@ mod tests {
         // Clean up
         hsm.free_secure_memory(key_id).await.unwrap();
     }
+
+    #[tokio::test]
+    async fn test_software_fallback_signing_keys() {
+        use k256::ecdsa::signature::hazmat::PrehashVerifier;
+        use sha2::{Digest, Sha256};
+
+        let mut hsm = HSMManager::new().await.unwrap();
+        let config = HSMConfig {
+            enabled: false,
+            device_path: "/dev/null".to_string(),
+            pin: "test".to_string(),
+            isolation_enabled: true,
+        };
+        hsm.initialize(config).await.unwrap();
+        assert!(!hsm.is_hardware_backed());
+
+        let secp = hsm.generate_key(HSMKeyType::Secp256k1, "eth-hot").await.unwrap();
+        let ed = hsm.generate_key(HSMKeyType::Ed25519, "sol-hot").await.unwrap();
+        assert_eq!(secp.public_key.len(), 65);
+        assert_eq!(ed.public_key.len(), 32);
+
+        let digest = Sha256::digest(b"transfer 1 ETH");
+        let sig = hsm.sign_digest(&secp.id, &digest).await.unwrap();
+        assert_eq!(sig.len(), 64);
+        let vk = k256::ecdsa::VerifyingKey::from_sec1_bytes(&secp.public_key).unwrap();
+        let sig = k256::ecdsa::Signature::from_slice(&sig).unwrap();
+        assert!(vk.verify_prehash(&digest, &sig).is_ok());
+
+        let sig = hsm.sign_digest(&ed.id, &digest).await.unwrap();
+        let vk =
+            ed25519_dalek::VerifyingKey::from_bytes(&ed.public_key.clone().try_into().unwrap())
+                .unwrap();
+        let sig = ed25519_dalek::Signature::from_slice(&sig).unwrap();
+        assert!(vk.verify_strict(&digest, &sig).is_ok());
+
+        let keys = hsm.list_keys().await.unwrap();
+        assert_eq!(keys.len(), 2);
+        assert!(hsm.sign_digest("missing", &digest).await.is_err());
+    }
 }
@@ -0,0 +1,150 @@
+// src/crypto/keyfile.rs
+//! Passphrase-encrypted private key file used by the offline signer.
+//!
+//! The key is encrypted with AES-256-GCM under a scrypt-derived key; the
+//! scrypt parameters are stored alongside so they can be raised later without
+//! breaking existing files.
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Key, Nonce,
+};
+use anyhow::Result;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::crypto::hsm::HSMKeyType;
+use crate::crypto::kdf::KeyDerivation;
+use crate::crypto::signer::{network_key, SoftwareSigner};
+
+const KEYFILE_VERSION: u8 = 1;
+const SALT_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedKeyFile {
+    pub version: u8,
+    pub key_type: HSMKeyType,
+    pub scrypt_n: u32,
+    pub scrypt_r: u32,
+    pub scrypt_p: u32,
+    pub salt: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+impl EncryptedKeyFile {
+    /// Encrypts `private_key` with the default scrypt parameters (N=2^17, r=8, p=1).
+    pub fn encrypt(key_type: HSMKeyType, private_key: &[u8], passphrase: &str) -> Result<Self> {
+        Self::encrypt_with_params(key_type, private_key, passphrase, 131072, 8, 1)
+    }
+
+    pub fn encrypt_with_params(
+        key_type: HSMKeyType,
+        private_key: &[u8],
+        passphrase: &str,
+        scrypt_n: u32,
+        scrypt_r: u32,
+        scrypt_p: u32,
+    ) -> Result<Self> {
+        let salt = KeyDerivation::generate_salt(SALT_LEN);
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let mut key = KeyDerivation::scrypt(scrypt_n, scrypt_r, scrypt_p).derive_key(
+            passphrase.as_bytes(),
+            &salt,
+            32,
+        )?;
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+        key.zeroize();
+
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), private_key)
+            .map_err(|e| anyhow::anyhow!("Key file encryption failed: {e}"))?;
+
+        Ok(Self {
+            version: KEYFILE_VERSION,
+            key_type,
+            scrypt_n,
+            scrypt_r,
+            scrypt_p,
+            salt: hex::encode(salt),
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        })
+    }
+
+    /// Encrypts the wallet key `SoftwareSigner::for_network` derives from
+    /// `master_key`, for the offline signer of that network.
+    pub fn for_network(master_key: &[u8], network: &str, passphrase: &str) -> Result<Self> {
+        let (key_type, private_key) = network_key(master_key, network);
+        Self::encrypt(key_type, &private_key, passphrase)
+    }
+
+    pub fn decrypt(&self, passphrase: &str) -> Result<Zeroizing<Vec<u8>>> {
+        if self.version != KEYFILE_VERSION {
+            return Err(anyhow::anyhow!("Unsupported key file version: {}", self.version));
+        }
+        let salt = hex::decode(&self.salt)?;
+        let nonce = hex::decode(&self.nonce)?;
+        let ciphertext = hex::decode(&self.ciphertext)?;
+        if nonce.len() != NONCE_LEN {
+            return Err(anyhow::anyhow!("Invalid key file nonce"));
+        }
+
+        let mut key = KeyDerivation::scrypt(self.scrypt_n, self.scrypt_r, self.scrypt_p)
+            .derive_key(passphrase.as_bytes(), &salt, 32)?;
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+        key.zeroize();
+
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+            .map_err(|_| anyhow::anyhow!("Wrong passphrase or corrupted key file"))?;
+        Ok(Zeroizing::new(plaintext))
+    }
+
+    /// Decrypts the key straight into a signer.
+    pub fn signer(&self, passphrase: &str) -> Result<SoftwareSigner> {
+        let private_key = self.decrypt(passphrase)?;
+        SoftwareSigner::new(self.key_type, &private_key).map_err(|e| anyhow::anyhow!("{}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keyfile_roundtrip() {
+        let key = [0x11u8; 32];
+        let file =
+            EncryptedKeyFile::encrypt_with_params(HSMKeyType::Secp256k1, &key, "pw", 1024, 8, 1)
+                .unwrap();
+        let json = serde_json::to_string(&file).unwrap();
+        let file: EncryptedKeyFile = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(file.decrypt("pw").unwrap().as_slice(), &key);
+        assert!(file.decrypt("wrong").is_err());
+        assert!(file.signer("pw").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_keyfile_for_network_holds_the_wallet_key() {
+        use crate::crypto::signer::Signer;
+
+        let master_key = [0x22u8; 32];
+        for network in ["sepolia", "solana"] {
+            // what `for_network` encrypts, with cheap scrypt parameters
+            let (key_type, private_key) = network_key(&master_key, network);
+            let file =
+                EncryptedKeyFile::encrypt_with_params(key_type, &private_key, "pw", 1024, 8, 1)
+                    .unwrap();
+            let wallet = SoftwareSigner::for_network(&master_key, network).unwrap();
+            assert_eq!(
+                file.signer("pw").unwrap().public_key().await.unwrap(),
+                wallet.public_key().await.unwrap()
+            );
+        }
+    }
+}
@@ -0,0 +1,305 @@
+// src/crypto/message.rs
+//! Off-chain message signing: EIP-191 personal messages, EIP-712 typed data
+//! and Solana `signMessage`.
+//!
+//! EIP-712 hashing (domain separator, nested structs, arrays) is delegated to
+//! the encoder in `ethers::types::transaction::eip712`.
+use ethers::types::transaction::eip712::{Eip712, TypedData};
+use ethers::types::{Address, Signature, H256};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt::Write as _;
+use std::str::FromStr;
+
+use crate::core::errors::WalletError;
+use crate::crypto::hsm::HSMKeyType;
+use crate::crypto::signer::{recoverable_signature, Signer};
+
+/// A message signature together with the address that produced it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageSignature {
+    pub network: String,
+    pub address: String,
+    /// 0x-prefixed hex: 65-byte `r || s || v` on EVM, 64-byte Ed25519 on Solana.
+    pub signature: String,
+}
+
+/// EIP-191 (version 0x45) hash of a personal message.
+pub fn personal_message_hash(message: &[u8]) -> H256 {
+    ethers::utils::hash_message(message)
+}
+
+/// EIP-712 signing hash: `keccak256(0x1901 || domainSeparator || hashStruct(message))`.
+pub fn typed_data_hash(typed_data: &TypedData) -> Result<H256, WalletError> {
+    typed_data
+        .encode_eip712()
+        .map(H256::from)
+        .map_err(|e| WalletError::ValidationError(format!("Invalid EIP-712 typed data: {}", e)))
+}
+
+/// Signs an EIP-191 personal message; returns the 65-byte `r || s || v` signature.
+pub async fn sign_personal_message(
+    signer: &dyn Signer,
+    message: &[u8],
+) -> Result<Signature, WalletError> {
+    sign_evm_digest(signer, personal_message_hash(message)).await
+}
+
+/// Signs EIP-712 typed data; returns the 65-byte `r || s || v` signature.
+pub async fn sign_typed_data(
+    signer: &dyn Signer,
+    typed_data: &TypedData,
+) -> Result<Signature, WalletError> {
+    sign_evm_digest(signer, typed_data_hash(typed_data)?).await
+}
+
+/// Solana `signMessage`: Ed25519 over the raw message bytes.
+pub async fn sign_solana_message(
+    signer: &dyn Signer,
+    message: &[u8],
+) -> Result<Vec<u8>, WalletError> {
+    if signer.key_type() != HSMKeyType::Ed25519 {
+        return Err(WalletError::CryptoError("Solana messages require an Ed25519 signer".into()));
+    }
+    signer.sign_digest(message).await
+}
+
+async fn sign_evm_digest(signer: &dyn Signer, digest: H256) -> Result<Signature, WalletError> {
+    if signer.key_type() != HSMKeyType::Secp256k1 {
+        return Err(WalletError::CryptoError("EVM messages require a secp256k1 signer".into()));
+    }
+    let rs = signer.sign_digest(digest.as_bytes()).await?;
+    let public_key = signer.public_key().await?;
+    recoverable_signature(digest, &rs, &public_key)
+}
+
+/// True if `signature` over the personal message was produced by `address`.
+pub fn verify_personal_message(message: &[u8], signature: &[u8], address: &str) -> bool {
+    recover_evm_signer(personal_message_hash(message), signature, address)
+}
+
+/// True if `signature` over the typed data was produced by `address`.
+pub fn verify_typed_data(typed_data: &TypedData, signature: &[u8], address: &str) -> bool {
+    match typed_data_hash(typed_data) {
+        Ok(hash) => recover_evm_signer(hash, signature, address),
+        Err(_) => false,
+    }
+}
+
+/// True if `signature` is a valid Ed25519 signature of `message` by the base58 `address`.
+pub fn verify_solana_message(message: &[u8], signature: &[u8], address: &str) -> bool {
+    let Some(public_key) =
+        bs58::decode(address).into_vec().ok().and_then(|b| <[u8; 32]>::try_from(b.as_slice()).ok())
+    else {
+        return false;
+    };
+    let (Ok(key), Ok(signature)) = (
+        ed25519_dalek::VerifyingKey::from_bytes(&public_key),
+        ed25519_dalek::Signature::from_slice(signature),
+    ) else {
+        return false;
+    };
+    key.verify_strict(message, &signature).is_ok()
+}
+
+fn recover_evm_signer(digest: H256, signature: &[u8], address: &str) -> bool {
+    let (Ok(signature), Ok(address)) = (Signature::try_from(signature), Address::from_str(address))
+    else {
+        return false;
+    };
+    signature.recover(digest).map(|a| a == address).unwrap_or(false)
+}
+
+/// Renders typed data as indented `field: value` lines for approval screens,
+/// resolving nested struct types and arrays by name.
+pub fn render_typed_data(typed_data: &TypedData) -> String {
+    let mut out = String::new();
+    let domain = &typed_data.domain;
+
+    out.push_str("Domain:\n");
+    if let Some(name) = &domain.name {
+        let _ = writeln!(out, "  name: {}", name);
+    }
+    if let Some(version) = &domain.version {
+        let _ = writeln!(out, "  version: {}", version);
+    }
+    if let Some(chain_id) = &domain.chain_id {
+        let _ = writeln!(out, "  chainId: {}", chain_id);
+    }
+    if let Some(contract) = &domain.verifying_contract {
+        let _ = writeln!(out, "  verifyingContract: {:?}", contract);
+    }
+    if let Some(salt) = &domain.salt {
+        let _ = writeln!(out, "  salt: 0x{}", hex::encode(salt));
+    }
+
+    let _ = writeln!(out, "{}:", typed_data.primary_type);
+    let message = Value::Object(typed_data.message.clone().into_iter().collect());
+    render_struct(typed_data, &typed_data.primary_type, &message, 1, &mut out);
+    out
+}
+
+fn render_struct(
+    typed_data: &TypedData,
+    type_name: &str,
+    value: &Value,
+    depth: usize,
+    out: &mut String,
+) {
+    let Some(fields) = typed_data.types.get(type_name) else {
+        return;
+    };
+    for field in fields {
+        let field_value = value.get(&field.name).unwrap_or(&Value::Null);
+        render_field(typed_data, &field.name, &field.r#type, field_value, depth, out);
+    }
+}
+
+fn render_field(
+    typed_data: &TypedData,
+    label: &str,
+    field_type: &str,
+    value: &Value,
+    depth: usize,
+    out: &mut String,
+) {
+    let indent = "  ".repeat(depth);
+
+    // `T[]` / `T[n]`: render each element under the field label.
+    if let Some(open) = field_type.rfind('[') {
+        if field_type.ends_with(']') {
+            let element_type = &field_type[..open];
+            let _ = writeln!(out, "{}{}: {}", indent, label, field_type);
+            if let Some(items) = value.as_array() {
+                for (i, item) in items.iter().enumerate() {
+                    render_field(
+                        typed_data,
+                        &format!("[{}]", i),
+                        element_type,
+                        item,
+                        depth + 1,
+                        out,
+                    );
+                }
+            }
+            return;
+        }
+    }
+
+    if typed_data.types.contains_key(field_type) {
+        let _ = writeln!(out, "{}{}: {}", indent, label, field_type);
+        render_struct(typed_data, field_type, value, depth + 1, out);
+        return;
+    }
+
+    let rendered = match value {
+        Value::String(s) => s.clone(),
+        Value::Null => "<missing>".to_string(),
+        other => other.to_string(),
+    };
+    let _ = writeln!(out, "{}{}: {}", indent, label, rendered);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::signer::SoftwareSigner;
+    use ethers::signers::{LocalWallet, Signer as _};
+
+    // EIP-712 "Mail" example from the specification.
+    fn mail() -> TypedData {
+        serde_json::from_value(serde_json::json!({
+            "types": {
+                "EIP712Domain": [
+                    {"name": "name", "type": "string"},
+                    {"name": "version", "type": "string"},
+                    {"name": "chainId", "type": "uint256"},
+                    {"name": "verifyingContract", "type": "address"}
+                ],
+                "Person": [
+                    {"name": "name", "type": "string"},
+                    {"name": "wallets", "type": "address[]"}
+                ],
+                "Mail": [
+                    {"name": "from", "type": "Person"},
+                    {"name": "to", "type": "Person[]"},
+                    {"name": "contents", "type": "string"}
+                ]
+            },
+            "primaryType": "Mail",
+            "domain": {
+                "name": "Ether Mail",
+                "version": "1",
+                "chainId": 1,
+                "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+            },
+            "message": {
+                "from": {
+                    "name": "Cow",
+                    "wallets": ["0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"]
+                },
+                "to": [{
+                    "name": "Bob",
+                    "wallets": ["0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"]
+                }],
+                "contents": "Hello, Bob!"
+            }
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_personal_message_matches_local_wallet() {
+        let key = [0x11u8; 32];
+        let signer = SoftwareSigner::secp256k1(&key).unwrap();
+        let wallet = LocalWallet::from_bytes(&key).unwrap();
+
+        let signature = sign_personal_message(&signer, b"Sign in to app.example").await.unwrap();
+        let expected = wallet.sign_message("Sign in to app.example").await.unwrap();
+        assert_eq!(signature, expected);
+
+        let address = format!("{:?}", wallet.address());
+        assert!(verify_personal_message(b"Sign in to app.example", &signature.to_vec(), &address));
+        assert!(!verify_personal_message(b"something else", &signature.to_vec(), &address));
+    }
+
+    #[tokio::test]
+    async fn test_typed_data_nested_structs_and_arrays() {
+        let key = [0x22u8; 32];
+        let signer = SoftwareSigner::secp256k1(&key).unwrap();
+        let wallet = LocalWallet::from_bytes(&key).unwrap();
+        let typed = mail();
+
+        let signature = sign_typed_data(&signer, &typed).await.unwrap();
+        assert_eq!(signature, wallet.sign_typed_data(&typed).await.unwrap());
+
+        let address = format!("{:?}", wallet.address());
+        assert!(verify_typed_data(&typed, &signature.to_vec(), &address));
+
+        let mut tampered = typed.clone();
+        tampered.message.insert("contents".to_string(), "Hello, Eve!".into());
+        assert!(!verify_typed_data(&tampered, &signature.to_vec(), &address));
+    }
+
+    #[test]
+    fn test_render_typed_data() {
+        let rendered = render_typed_data(&mail());
+        assert!(rendered.contains("  name: Ether Mail"));
+        assert!(rendered.contains("Mail:\n  from: Person\n    name: Cow"));
+        assert!(rendered.contains("  to: Person[]\n    [0]: Person\n      name: Bob"));
+        assert!(rendered.contains("  contents: Hello, Bob!"));
+    }
+
+    #[tokio::test]
+    async fn test_solana_sign_message() {
+        let signer = SoftwareSigner::ed25519(&[0x33u8; 32]).unwrap();
+        let address = bs58::encode(signer.public_key().await.unwrap()).into_string();
+
+        let signature = sign_solana_message(&signer, b"login nonce 42").await.unwrap();
+        assert!(verify_solana_message(b"login nonce 42", &signature, &address));
+        assert!(!verify_solana_message(b"login nonce 43", &signature, &address));
+
+        let evm_signer = SoftwareSigner::secp256k1(&[0x33u8; 32]).unwrap();
+        assert!(sign_solana_message(&evm_signer, b"x").await.is_err());
+    }
+}
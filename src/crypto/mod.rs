@@ -1,12 +1,20 @@
 pub mod hsm;
 pub mod kdf;
+pub mod keyfile;
+pub mod message;
 pub mod multisig;
+#[cfg(feature = "pkcs11")]
+pub mod pkcs11;
 pub mod quantum;
 pub mod shamir;
+pub mod signer;
+pub mod signing;
 
-pub use self::hsm::HSMManager;
+pub use self::hsm::{HSMKeyHandle, HSMKeyType, HSMManager};
 pub use self::kdf::KeyDerivation;
 pub use self::multisig::MultiSignature;
 pub use self::quantum::QuantumSafeEncryption;
+pub use self::signer::Signer;
+pub use self::signing::{PqKeyPair, PqSignature, SignatureAlgorithm};
 // Fix: export shamir symbols from the crypto::shamir module (not from security::shamir)
 pub use self::shamir::{combine_secret, combine_shares, split_secret};
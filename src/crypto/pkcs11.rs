@@ -0,0 +1,357 @@
+// src/crypto/pkcs11.rs
+//! PKCS#11 token backend for `HSMManager`.
+//!
+//! Keys are generated on the token as non-extractable, sensitive objects and
+//! are addressed by their `CKA_ID`. Only digests cross the module boundary.
+use anyhow::Result;
+use cryptoki::context::{CInitializeArgs, Pkcs11};
+use cryptoki::mechanism::Mechanism;
+use cryptoki::object::{Attribute, AttributeType, KeyType, ObjectClass, ObjectHandle};
+use cryptoki::session::{Session, UserType};
+use cryptoki::types::AuthPin;
+use rand::RngCore;
+use std::sync::Mutex;
+use tracing::{debug, info};
+
+use crate::crypto::hsm::{HSMKeyHandle, HSMKeyType};
+
+/// DER-encoded OID 1.3.132.0.10 (secp256k1).
+const SECP256K1_PARAMS: &[u8] = &[0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x0a];
+/// DER-encoded OID 1.3.101.112 (Ed25519).
+const ED25519_PARAMS: &[u8] = &[0x06, 0x03, 0x2b, 0x65, 0x70];
+const SECP256K1_OID: &str = "1.3.132.0.10";
+const ED25519_OID: &str = "1.3.101.112";
+
+pub struct Pkcs11Backend {
+    // Kept alive for the lifetime of the session; finalized on drop.
+    _context: Pkcs11,
+    session: Mutex<Session>,
+}
+
+impl Pkcs11Backend {
+    /// Loads the PKCS#11 module at `module_path`, opens a read-write session on
+    /// the first slot with a token and logs in as the user.
+    pub fn open(module_path: &str, pin: &str) -> Result<Self> {
+        info!("Loading PKCS#11 module: {}", module_path);
+
+        let context = Pkcs11::new(module_path)
+            .map_err(|e| anyhow::anyhow!("Failed to load PKCS#11 module {}: {}", module_path, e))?;
+        context
+            .initialize(CInitializeArgs::OsThreads)
+            .map_err(|e| anyhow::anyhow!("Failed to initialize PKCS#11 module: {}", e))?;
+
+        let slot = context
+            .get_slots_with_token()
+            .map_err(|e| anyhow::anyhow!("Failed to list PKCS#11 slots: {}", e))?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("No PKCS#11 slot with an initialized token"))?;
+
+        let session = context
+            .open_rw_session(slot)
+            .map_err(|e| anyhow::anyhow!("Failed to open PKCS#11 session: {}", e))?;
+        session
+            .login(UserType::User, Some(&AuthPin::new(pin.to_string())))
+            .map_err(|e| anyhow::anyhow!("PKCS#11 login failed: {}", e))?;
+
+        info!("PKCS#11 session established on slot {}", slot);
+        Ok(Self { _context: context, session: Mutex::new(session) })
+    }
+
+    /// Generates a key pair on the token. The private key never leaves it.
+    pub fn generate_key(&self, key_type: HSMKeyType, label: &str) -> Result<HSMKeyHandle> {
+        let mut id = vec![0u8; 16];
+        rand::thread_rng().fill_bytes(&mut id);
+
+        let (mechanism, params) = match key_type {
+            HSMKeyType::Secp256k1 => (Mechanism::EccKeyPairGen, SECP256K1_PARAMS),
+            HSMKeyType::Ed25519 => (Mechanism::EccEdwardsKeyPairGen, ED25519_PARAMS),
+        };
+
+        let public_template = vec![
+            Attribute::Token(true),
+            Attribute::Private(false),
+            Attribute::Verify(true),
+            Attribute::EcParams(params.to_vec()),
+            Attribute::Label(label.as_bytes().to_vec()),
+            Attribute::Id(id.clone()),
+        ];
+        let private_template = vec![
+            Attribute::Token(true),
+            Attribute::Private(true),
+            Attribute::Sensitive(true),
+            Attribute::Extractable(false),
+            Attribute::Sign(true),
+            Attribute::Label(label.as_bytes().to_vec()),
+            Attribute::Id(id.clone()),
+        ];
+
+        let session = self.lock_session()?;
+        let (public, _private) = session
+            .generate_key_pair(&mechanism, &public_template, &private_template)
+            .map_err(|e| anyhow::anyhow!("PKCS#11 key generation failed: {}", e))?;
+        let public_key = read_ec_point(&session, public)?;
+
+        debug!("Generated {:?} key on token with id {}", key_type, hex::encode(&id));
+        Ok(HSMKeyHandle { id: hex::encode(id), label: label.to_string(), key_type, public_key })
+    }
+
+    /// Signs a digest with the private key identified by `key_id` (hex `CKA_ID`).
+    /// secp256k1 signatures are returned as raw `r || s`.
+    pub fn sign_digest(&self, key_id: &str, digest: &[u8]) -> Result<Vec<u8>> {
+        let id = hex::decode(key_id).map_err(|e| anyhow::anyhow!("Invalid key id: {}", e))?;
+        let session = self.lock_session()?;
+
+        let private = session
+            .find_objects(&[Attribute::Class(ObjectClass::PRIVATE_KEY), Attribute::Id(id.clone())])
+            .map_err(|e| anyhow::anyhow!("PKCS#11 key lookup failed: {}", e))?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Key not found on token: {}", key_id))?;
+
+        let (key_type, params) = read_key_params(&session, private)?;
+        let key_type = if params.is_empty() {
+            // Private keys may not expose CKA_EC_PARAMS; take the curve from
+            // the public half with the same id.
+            let public = session
+                .find_objects(&[Attribute::Class(ObjectClass::PUBLIC_KEY), Attribute::Id(id)])
+                .map_err(|e| anyhow::anyhow!("PKCS#11 key lookup failed: {}", e))?
+                .into_iter()
+                .next()
+                .ok_or_else(|| {
+                    anyhow::anyhow!("Key {} has no CKA_EC_PARAMS and no public key", key_id)
+                })?;
+            key_type_of(&session, public)?
+        } else {
+            curve_of(key_type, &params)?
+        };
+
+        let mechanism = match key_type {
+            HSMKeyType::Secp256k1 => Mechanism::Ecdsa,
+            HSMKeyType::Ed25519 => Mechanism::Eddsa,
+        };
+
+        session
+            .sign(&mechanism, private, digest)
+            .map_err(|e| anyhow::anyhow!("PKCS#11 signing failed: {}", e))
+    }
+
+    /// Lists EC key pairs on the token by their public halves.
+    pub fn list_keys(&self) -> Result<Vec<HSMKeyHandle>> {
+        let session = self.lock_session()?;
+        let objects = session
+            .find_objects(&[Attribute::Class(ObjectClass::PUBLIC_KEY)])
+            .map_err(|e| anyhow::anyhow!("PKCS#11 object search failed: {}", e))?;
+
+        let mut keys = Vec::new();
+        for object in objects {
+            let attributes = session
+                .get_attributes(object, &[AttributeType::Id, AttributeType::Label])
+                .map_err(|e| anyhow::anyhow!("Failed to read key attributes: {}", e))?;
+
+            let mut id = None;
+            let mut label = String::new();
+            for attribute in attributes {
+                match attribute {
+                    Attribute::Id(bytes) => id = Some(hex::encode(bytes)),
+                    Attribute::Label(bytes) => label = String::from_utf8_lossy(&bytes).into_owned(),
+                    _ => {}
+                }
+            }
+
+            // Keys of other types (RSA, AES, ...) may share the token; skip them.
+            if let (Some(id), Ok(key_type)) = (id, key_type_of(&session, object)) {
+                let public_key = read_ec_point(&session, object)?;
+                keys.push(HSMKeyHandle { id, label, key_type, public_key });
+            }
+        }
+
+        Ok(keys)
+    }
+
+    fn lock_session(&self) -> Result<std::sync::MutexGuard<'_, Session>> {
+        self.session.lock().map_err(|_| anyhow::anyhow!("PKCS#11 session lock poisoned"))
+    }
+}
+
+fn key_type_of(session: &Session, object: ObjectHandle) -> Result<HSMKeyType> {
+    let (key_type, params) = read_key_params(session, object)?;
+    curve_of(key_type, &params)
+}
+
+/// `CKA_KEY_TYPE` and `CKA_EC_PARAMS` of `object`; the params are empty when
+/// the token does not expose them.
+fn read_key_params(session: &Session, object: ObjectHandle) -> Result<(Option<KeyType>, Vec<u8>)> {
+    let attributes = session
+        .get_attributes(object, &[AttributeType::KeyType, AttributeType::EcParams])
+        .map_err(|e| anyhow::anyhow!("Failed to read key type: {}", e))?;
+
+    let mut key_type = None;
+    let mut params = Vec::new();
+    for attribute in attributes {
+        match attribute {
+            Attribute::KeyType(kt) => key_type = Some(kt),
+            Attribute::EcParams(p) => params = p,
+            _ => {}
+        }
+    }
+
+    Ok((key_type, params))
+}
+
+/// Maps a key type and its `CKA_EC_PARAMS` to the curve. Keys on other
+/// curves, and keys that do not name their curve, are refused rather than
+/// signed with a mechanism that may not match.
+fn curve_of(key_type: Option<KeyType>, params: &[u8]) -> Result<HSMKeyType> {
+    let is_ec = key_type.is_some_and(|kt| kt == KeyType::EC);
+    let is_edwards = key_type.is_some_and(|kt| kt == KeyType::EC_EDWARDS);
+    if !is_ec && !is_edwards {
+        return Err(anyhow::anyhow!("Unsupported key type on token: {:?}", key_type));
+    }
+    if params.is_empty() {
+        return Err(anyhow::anyhow!("Key does not declare its curve (empty CKA_EC_PARAMS)"));
+    }
+
+    // PKCS#11 3.0 also allows Edwards curves to be named by PrintableString.
+    if is_edwards && params[0] == 0x13 {
+        let name = der_printable_string(params)?;
+        if name == "edwards25519" {
+            return Ok(HSMKeyType::Ed25519);
+        }
+        return Err(anyhow::anyhow!("Unsupported Edwards curve: {}", name));
+    }
+
+    let oid = der_oid(params)?;
+    match oid.as_str() {
+        SECP256K1_OID if is_ec => Ok(HSMKeyType::Secp256k1),
+        ED25519_OID if is_edwards => Ok(HSMKeyType::Ed25519),
+        _ => Err(anyhow::anyhow!("Unsupported curve {} for key type {:?}", oid, key_type)),
+    }
+}
+
+/// Content of a DER TLV with `tag` and a short-form length spanning all of `bytes`.
+fn der_content(bytes: &[u8], tag: u8) -> Result<&[u8]> {
+    match bytes {
+        [t, len, content @ ..]
+            if *t == tag
+                && *len < 0x80
+                && *len as usize == content.len()
+                && !content.is_empty() =>
+        {
+            Ok(content)
+        }
+        _ => Err(anyhow::anyhow!("Malformed CKA_EC_PARAMS: {}", hex::encode(bytes))),
+    }
+}
+
+fn der_printable_string(bytes: &[u8]) -> Result<String> {
+    let content = der_content(bytes, 0x13)?;
+    Ok(String::from_utf8_lossy(content).into_owned())
+}
+
+/// Decodes a DER OBJECT IDENTIFIER into dotted form.
+fn der_oid(bytes: &[u8]) -> Result<String> {
+    let content = der_content(bytes, 0x06)?;
+    let malformed = || anyhow::anyhow!("Malformed OID: {}", hex::encode(bytes));
+
+    let mut arcs: Vec<u64> = Vec::new();
+    let mut value: u64 = 0;
+    for (i, byte) in content.iter().enumerate() {
+        if value > u64::MAX >> 7 {
+            return Err(malformed());
+        }
+        value = (value << 7) | u64::from(byte & 0x7f);
+        if byte & 0x80 == 0 {
+            arcs.push(value);
+            value = 0;
+        } else if i == content.len() - 1 {
+            return Err(malformed());
+        }
+    }
+
+    // the first subidentifier packs the first two arcs
+    let first = arcs[0];
+    let (a, b) = match first {
+        0..=39 => (0, first),
+        40..=79 => (1, first - 40),
+        _ => (2, first - 80),
+    };
+    let mut dotted = format!("{}.{}", a, b);
+    for arc in &arcs[1..] {
+        dotted.push_str(&format!(".{}", arc));
+    }
+    Ok(dotted)
+}
+
+fn read_ec_point(session: &Session, public: ObjectHandle) -> Result<Vec<u8>> {
+    let attributes = session
+        .get_attributes(public, &[AttributeType::EcPoint])
+        .map_err(|e| anyhow::anyhow!("Failed to read public key: {}", e))?;
+
+    attributes
+        .into_iter()
+        .find_map(|a| match a {
+            Attribute::EcPoint(point) => Some(unwrap_der_octet_string(point)),
+            _ => None,
+        })
+        .ok_or_else(|| anyhow::anyhow!("Public key has no CKA_EC_POINT"))
+}
+
+/// `CKA_EC_POINT` is usually a DER OCTET STRING around the raw point; strip it
+/// when present (short-form length only, which covers both curves here).
+fn unwrap_der_octet_string(bytes: Vec<u8>) -> Vec<u8> {
+    if bytes.len() > 2 && bytes[0] == 0x04 && bytes[1] as usize == bytes.len() - 2 {
+        bytes[2..].to_vec()
+    } else {
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unwrap_der_octet_string() {
+        let mut wrapped = vec![0x04, 0x20];
+        wrapped.extend_from_slice(&[7u8; 32]);
+        assert_eq!(unwrap_der_octet_string(wrapped), vec![7u8; 32]);
+
+        // an uncompressed point that is not wrapped stays untouched
+        let mut raw = vec![0x04];
+        raw.extend_from_slice(&[1u8; 64]);
+        assert_eq!(unwrap_der_octet_string(raw.clone()), raw);
+    }
+
+    #[test]
+    fn test_der_oid() {
+        assert_eq!(der_oid(SECP256K1_PARAMS).unwrap(), SECP256K1_OID);
+        assert_eq!(der_oid(ED25519_PARAMS).unwrap(), ED25519_OID);
+        let p256 = [0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+        assert_eq!(der_oid(&p256).unwrap(), "1.2.840.10045.3.1.7");
+
+        // truncated subidentifier, wrong length, wrong tag
+        assert!(der_oid(&[0x06, 0x02, 0x2b, 0x81]).is_err());
+        assert!(der_oid(&[0x06, 0x05, 0x2b, 0x81]).is_err());
+        assert!(der_oid(&[0x04, 0x03, 0x2b, 0x65, 0x70]).is_err());
+    }
+
+    #[test]
+    fn test_curve_of() {
+        let ec = Some(KeyType::EC);
+        let edwards = Some(KeyType::EC_EDWARDS);
+        assert_eq!(curve_of(ec, SECP256K1_PARAMS).unwrap(), HSMKeyType::Secp256k1);
+        assert_eq!(curve_of(edwards, ED25519_PARAMS).unwrap(), HSMKeyType::Ed25519);
+        let mut named = vec![0x13, 0x0c];
+        named.extend_from_slice(b"edwards25519");
+        assert_eq!(curve_of(edwards, &named).unwrap(), HSMKeyType::Ed25519);
+
+        // no curve, another curve, or a curve that does not fit the key type
+        assert!(curve_of(ec, &[]).is_err());
+        let p256 = [0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+        assert!(curve_of(ec, &p256).is_err());
+        assert!(curve_of(ec, ED25519_PARAMS).is_err());
+        assert!(curve_of(edwards, SECP256K1_PARAMS).is_err());
+        assert!(curve_of(Some(KeyType::RSA), SECP256K1_PARAMS).is_err());
+    }
+}
@@ -0,0 +1,480 @@
+// src/crypto/signer.rs
+//! Signing abstraction used by the chain clients.
+//!
+//! A `Signer` exposes a public key and signs digests; chain clients build an
+//! unsigned transaction, hand it to a signer and broadcast the result. Key
+//! material therefore only has to exist wherever the signer keeps it: process
+//! memory, an HSM, a set of threshold shares or a remote signing service.
+use async_trait::async_trait;
+use ethers::types::{transaction::eip2718::TypedTransaction, Signature, H256, U256};
+use ethers::utils::keccak256;
+use k256::ecdsa::{RecoveryId, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::debug;
+use zeroize::Zeroizing;
+
+use crate::blockchain::traits::{SignedTransaction, UnsignedTransaction};
+use crate::core::errors::WalletError;
+use crate::crypto::hsm::{HSMKeyHandle, HSMKeyType, HSMManager};
+use crate::crypto::shamir::combine_shares;
+
+#[async_trait]
+pub trait Signer: Send + Sync {
+    /// Curve of the key behind this signer.
+    fn key_type(&self) -> HSMKeyType;
+
+    /// Public key: SEC1 uncompressed for secp256k1, 32 raw bytes for Ed25519.
+    async fn public_key(&self) -> Result<Vec<u8>, WalletError>;
+
+    /// Signs a digest. secp256k1 returns 64-byte `r || s`; Ed25519 signs the
+    /// bytes as the message.
+    async fn sign_digest(&self, digest: &[u8]) -> Result<Vec<u8>, WalletError>;
+
+    /// Signs a transaction built by a `BlockchainClient`.
+    async fn sign_transaction(
+        &self,
+        tx: &UnsignedTransaction,
+    ) -> Result<SignedTransaction, WalletError> {
+        match tx {
+            UnsignedTransaction::Evm { tx } => {
+                if self.key_type() != HSMKeyType::Secp256k1 {
+                    return Err(WalletError::CryptoError(
+                        "EVM transactions require a secp256k1 signer".to_string(),
+                    ));
+                }
+                let sighash = tx.sighash();
+                let rs = self.sign_digest(sighash.as_bytes()).await?;
+                let public_key = self.public_key().await?;
+                let signature = evm_signature(tx, sighash, &rs, &public_key)?;
+
+                let raw = tx.rlp_signed(&signature);
+                let hash = H256::from(keccak256(&raw));
+                Ok(SignedTransaction::Evm { raw, hash })
+            }
+            UnsignedTransaction::Solana { message } => {
+                if self.key_type() != HSMKeyType::Ed25519 {
+                    return Err(WalletError::CryptoError(
+                        "Solana transactions require an Ed25519 signer".to_string(),
+                    ));
+                }
+                let signature = self.sign_digest(message).await?;
+                Ok(SignedTransaction::Solana {
+                    message: message.clone(),
+                    signature: signature.into(),
+                })
+            }
+        }
+    }
+}
+
+/// Turns a raw `r || s` signature into an Ethereum signature with `v` set for
+/// `tx` (EIP-155 for legacy transactions).
+fn evm_signature(
+    tx: &TypedTransaction,
+    sighash: H256,
+    rs: &[u8],
+    public_key: &[u8],
+) -> Result<Signature, WalletError> {
+    let mut signature = recoverable_signature(sighash, rs, public_key)?;
+    if let (TypedTransaction::Legacy(_), Some(chain_id)) = (tx, tx.chain_id()) {
+        signature.v = signature.v - 27 + 35 + chain_id.as_u64() * 2;
+    }
+    Ok(signature)
+}
+
+/// Turns a raw `r || s` signature over `digest` into a low-s Ethereum
+/// signature with `v` in {27, 28}. Signers only return `r || s`, so the
+/// recovery id is found by trying both candidates against the signer's public key.
+pub fn recoverable_signature(
+    digest: H256,
+    rs: &[u8],
+    public_key: &[u8],
+) -> Result<Signature, WalletError> {
+    let signature = k256::ecdsa::Signature::from_slice(rs)
+        .map_err(|e| WalletError::CryptoError(format!("Invalid ECDSA signature: {}", e)))?;
+    // Ethereum rejects high-s signatures; tokens are not required to normalize.
+    let signature = signature.normalize_s().unwrap_or(signature);
+    let expected = VerifyingKey::from_sec1_bytes(public_key)
+        .map_err(|e| WalletError::CryptoError(format!("Invalid secp256k1 public key: {}", e)))?;
+
+    let recovery_id = (0u8..=1)
+        .filter_map(RecoveryId::from_byte)
+        .find(|id| {
+            VerifyingKey::recover_from_prehash(digest.as_bytes(), &signature, *id)
+                .map(|key| key == expected)
+                .unwrap_or(false)
+        })
+        .ok_or_else(|| {
+            WalletError::CryptoError("Signature does not match signer public key".to_string())
+        })?;
+
+    let bytes = signature.to_bytes();
+    Ok(Signature {
+        r: U256::from_big_endian(&bytes[..32]),
+        s: U256::from_big_endian(&bytes[32..]),
+        v: 27 + recovery_id.to_byte() as u64,
+    })
+}
+
+fn software_sign(
+    key_type: HSMKeyType,
+    secret: &[u8],
+    digest: &[u8],
+) -> Result<Vec<u8>, WalletError> {
+    match key_type {
+        HSMKeyType::Secp256k1 => {
+            let sk = k256::ecdsa::SigningKey::from_slice(secret).map_err(|e| {
+                WalletError::KeyDerivationError(format!("Invalid private key: {}", e))
+            })?;
+            let (signature, _) = sk.sign_prehash_recoverable(digest).map_err(|e| {
+                WalletError::CryptoError(format!("secp256k1 signing failed: {}", e))
+            })?;
+            Ok(signature.to_bytes().to_vec())
+        }
+        HSMKeyType::Ed25519 => {
+            use ed25519_dalek::Signer as _;
+            let sk = ed25519_signing_key(secret)?;
+            Ok(sk.sign(digest).to_bytes().to_vec())
+        }
+    }
+}
+
+fn software_public_key(key_type: HSMKeyType, secret: &[u8]) -> Result<Vec<u8>, WalletError> {
+    match key_type {
+        HSMKeyType::Secp256k1 => {
+            let sk = k256::ecdsa::SigningKey::from_slice(secret).map_err(|e| {
+                WalletError::KeyDerivationError(format!("Invalid private key: {}", e))
+            })?;
+            Ok(sk.verifying_key().to_encoded_point(false).as_bytes().to_vec())
+        }
+        HSMKeyType::Ed25519 => Ok(ed25519_signing_key(secret)?.verifying_key().to_bytes().to_vec()),
+    }
+}
+
+fn ed25519_signing_key(secret: &[u8]) -> Result<ed25519_dalek::SigningKey, WalletError> {
+    let bytes: [u8; 32] = secret.try_into().map_err(|_| {
+        WalletError::KeyDerivationError("Private key must be 32 bytes for Ed25519".to_string())
+    })?;
+    Ok(ed25519_dalek::SigningKey::from_bytes(&bytes))
+}
+
+/// Signs with a private key held in process memory. The key is zeroized on drop.
+pub struct SoftwareSigner {
+    key_type: HSMKeyType,
+    secret: Zeroizing<Vec<u8>>,
+    public_key: Vec<u8>,
+}
+
+impl SoftwareSigner {
+    pub fn new(key_type: HSMKeyType, private_key: &[u8]) -> Result<Self, WalletError> {
+        if private_key.len() != 32 {
+            return Err(WalletError::KeyDerivationError(
+                "Private key must be 32 bytes".to_string(),
+            ));
+        }
+        let public_key = software_public_key(key_type, private_key)?;
+        Ok(Self { key_type, secret: Zeroizing::new(private_key.to_vec()), public_key })
+    }
+
+    pub fn secp256k1(private_key: &[u8]) -> Result<Self, WalletError> {
+        Self::new(HSMKeyType::Secp256k1, private_key)
+    }
+
+    pub fn ed25519(private_key: &[u8]) -> Result<Self, WalletError> {
+        Self::new(HSMKeyType::Ed25519, private_key)
+    }
+
+    /// The wallet's key on `network`: `sha256(master_key || network)`, Ed25519
+    /// on Solana networks and secp256k1 everywhere else.
+    pub fn for_network(master_key: &[u8], network: &str) -> Result<Self, WalletError> {
+        let (key_type, private_key) = network_key(master_key, network);
+        Self::new(key_type, &private_key)
+    }
+}
+
+/// Type and private key of the wallet's key on `network`, see
+/// `SoftwareSigner::for_network`.
+pub(crate) fn network_key(master_key: &[u8], network: &str) -> (HSMKeyType, Zeroizing<Vec<u8>>) {
+    use sha2::{Digest, Sha256};
+    let private_key = Zeroizing::new(
+        Sha256::new().chain_update(master_key).chain_update(network.as_bytes()).finalize().to_vec(),
+    );
+    let key_type =
+        if network.starts_with("solana") { HSMKeyType::Ed25519 } else { HSMKeyType::Secp256k1 };
+    (key_type, private_key)
+}
+
+#[async_trait]
+impl Signer for SoftwareSigner {
+    fn key_type(&self) -> HSMKeyType {
+        self.key_type
+    }
+
+    async fn public_key(&self) -> Result<Vec<u8>, WalletError> {
+        Ok(self.public_key.clone())
+    }
+
+    async fn sign_digest(&self, digest: &[u8]) -> Result<Vec<u8>, WalletError> {
+        software_sign(self.key_type, &self.secret, digest)
+    }
+}
+
+/// Signs with a key that lives in the `HSMManager` (a PKCS#11 token when one
+/// is configured).
+pub struct HsmSigner {
+    hsm: Arc<HSMManager>,
+    key: HSMKeyHandle,
+}
+
+impl HsmSigner {
+    pub fn new(hsm: Arc<HSMManager>, key: HSMKeyHandle) -> Self {
+        Self { hsm, key }
+    }
+}
+
+#[async_trait]
+impl Signer for HsmSigner {
+    fn key_type(&self) -> HSMKeyType {
+        self.key.key_type
+    }
+
+    async fn public_key(&self) -> Result<Vec<u8>, WalletError> {
+        Ok(self.key.public_key.clone())
+    }
+
+    async fn sign_digest(&self, digest: &[u8]) -> Result<Vec<u8>, WalletError> {
+        debug!("Signing digest with HSM key {}", self.key.id);
+        self.hsm
+            .sign_digest(&self.key.id, digest)
+            .await
+            .map_err(|e| WalletError::CryptoError(e.to_string()))
+    }
+}
+
+/// Threshold signer over Shamir shares of a key.
+///
+/// The key is reconstructed only for the duration of one signature and wiped
+/// right after. A multi-party protocol that never reconstructs the key can
+/// replace this behind the same trait.
+pub struct MpcSigner {
+    key_type: HSMKeyType,
+    shares: Vec<(u8, [u8; 32])>,
+    public_key: Vec<u8>,
+}
+
+impl MpcSigner {
+    pub fn new(key_type: HSMKeyType, shares: Vec<(u8, [u8; 32])>) -> Result<Self, WalletError> {
+        let secret = Zeroizing::new(
+            combine_shares(&shares).map_err(|e| WalletError::CryptoError(e.to_string()))?,
+        );
+        let public_key = software_public_key(key_type, &secret[..])?;
+        Ok(Self { key_type, shares, public_key })
+    }
+}
+
+impl Drop for MpcSigner {
+    fn drop(&mut self) {
+        use zeroize::Zeroize;
+        for (_, share) in self.shares.iter_mut() {
+            share.zeroize();
+        }
+    }
+}
+
+#[async_trait]
+impl Signer for MpcSigner {
+    fn key_type(&self) -> HSMKeyType {
+        self.key_type
+    }
+
+    async fn public_key(&self) -> Result<Vec<u8>, WalletError> {
+        Ok(self.public_key.clone())
+    }
+
+    async fn sign_digest(&self, digest: &[u8]) -> Result<Vec<u8>, WalletError> {
+        let secret = Zeroizing::new(
+            combine_shares(&self.shares).map_err(|e| WalletError::CryptoError(e.to_string()))?,
+        );
+        software_sign(self.key_type, &secret[..], digest)
+    }
+}
+
+#[derive(Serialize)]
+struct RemoteSignRequest<'a> {
+    key_id: &'a str,
+    digest: String,
+}
+
+#[derive(Deserialize)]
+struct RemoteSignResponse {
+    signature: String,
+}
+
+#[derive(Deserialize)]
+struct RemoteKeyResponse {
+    public_key: String,
+}
+
+/// Delegates signing to a remote service over HTTP.
+///
+/// Expected endpoints: `GET {base}/v1/keys/{key_id}` returning
+/// `{"public_key": hex}` and `POST {base}/v1/sign` taking
+/// `{"key_id", "digest": hex}` and returning `{"signature": hex}`.
+pub struct RemoteSigner {
+    client: reqwest::Client,
+    base_url: String,
+    key_id: String,
+    key_type: HSMKeyType,
+    auth_token: Option<String>,
+    public_key: tokio::sync::OnceCell<Vec<u8>>,
+}
+
+impl RemoteSigner {
+    pub fn new(base_url: &str, key_id: &str, key_type: HSMKeyType) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            key_id: key_id.to_string(),
+            key_type,
+            auth_token: None,
+            public_key: tokio::sync::OnceCell::new(),
+        }
+    }
+
+    pub fn with_auth_token(mut self, token: &str) -> Self {
+        self.auth_token = Some(token.to_string());
+        self
+    }
+
+    fn request(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.auth_token {
+            Some(token) => builder.bearer_auth(token),
+            None => builder,
+        }
+    }
+}
+
+#[async_trait]
+impl Signer for RemoteSigner {
+    fn key_type(&self) -> HSMKeyType {
+        self.key_type
+    }
+
+    async fn public_key(&self) -> Result<Vec<u8>, WalletError> {
+        self.public_key
+            .get_or_try_init(|| async {
+                let url = format!("{}/v1/keys/{}", self.base_url, self.key_id);
+                let response: RemoteKeyResponse = self
+                    .request(self.client.get(&url))
+                    .send()
+                    .await
+                    .and_then(|r| r.error_for_status())
+                    .map_err(|e| WalletError::NetworkError(format!("Remote signer error: {}", e)))?
+                    .json()
+                    .await
+                    .map_err(|e| WalletError::SerializationError(e.to_string()))?;
+                hex::decode(response.public_key.trim_start_matches("0x"))
+                    .map_err(|e| WalletError::SerializationError(e.to_string()))
+            })
+            .await
+            .cloned()
+    }
+
+    async fn sign_digest(&self, digest: &[u8]) -> Result<Vec<u8>, WalletError> {
+        let url = format!("{}/v1/sign", self.base_url);
+        let body = RemoteSignRequest { key_id: &self.key_id, digest: hex::encode(digest) };
+        let response: RemoteSignResponse = self
+            .request(self.client.post(&url).json(&body))
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| WalletError::NetworkError(format!("Remote signer error: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| WalletError::SerializationError(e.to_string()))?;
+
+        hex::decode(response.signature.trim_start_matches("0x"))
+            .map_err(|e| WalletError::SerializationError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::signers::{LocalWallet, Signer as _};
+    use ethers::types::{Address, TransactionRequest};
+
+    fn legacy_tx() -> TypedTransaction {
+        TransactionRequest::new()
+            .to(Address::repeat_byte(0x42))
+            .value(1_000u64)
+            .gas(21_000u64)
+            .gas_price(20_000_000_000u64)
+            .nonce(7u64)
+            .chain_id(1u64)
+            .into()
+    }
+
+    #[tokio::test]
+    async fn test_software_signer_matches_local_wallet() {
+        let key = [0x11u8; 32];
+        let signer = SoftwareSigner::secp256k1(&key).unwrap();
+        let tx = legacy_tx();
+
+        let signed = signer.sign_transaction(&UnsignedTransaction::Evm { tx: tx.clone() }).await;
+        let SignedTransaction::Evm { raw, .. } = signed.unwrap() else { panic!("expected EVM") };
+
+        let wallet = LocalWallet::from_bytes(&key).unwrap().with_chain_id(1u64);
+        let expected = tx.rlp_signed(&wallet.sign_transaction_sync(&tx).unwrap());
+        assert_eq!(raw, expected);
+    }
+
+    #[tokio::test]
+    async fn test_mpc_signer_matches_software_signer() {
+        let key = [0x22u8; 32];
+        let shares = crate::crypto::shamir::split_secret(key, 2, 3).unwrap();
+        let mpc = MpcSigner::new(HSMKeyType::Secp256k1, shares[..2].to_vec()).unwrap();
+        let software = SoftwareSigner::secp256k1(&key).unwrap();
+
+        let digest = [0x5au8; 32];
+        assert_eq!(mpc.public_key().await.unwrap(), software.public_key().await.unwrap());
+        assert_eq!(
+            mpc.sign_digest(&digest).await.unwrap(),
+            software.sign_digest(&digest).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hsm_signer_recovers_to_hsm_key() {
+        let mut hsm = HSMManager::new().await.unwrap();
+        hsm.initialize(crate::crypto::hsm::HSMConfig {
+            enabled: false,
+            device_path: "/dev/null".to_string(),
+            pin: String::new(),
+            isolation_enabled: true,
+        })
+        .await
+        .unwrap();
+        let hsm = Arc::new(hsm);
+        let handle = hsm.generate_key(HSMKeyType::Secp256k1, "hot").await.unwrap();
+        let signer = HsmSigner::new(hsm, handle.clone());
+
+        let tx = legacy_tx();
+        let signed = signer.sign_transaction(&UnsignedTransaction::Evm { tx }).await.unwrap();
+        let SignedTransaction::Evm { raw, .. } = signed else { panic!("expected EVM") };
+
+        let (decoded, sig) =
+            TypedTransaction::decode_signed(&ethers::utils::rlp::Rlp::new(&raw)).unwrap();
+        let expected = &keccak256(&handle.public_key[1..])[12..];
+        assert_eq!(sig.recover(decoded.sighash()).unwrap().as_bytes(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_key_type_mismatch_rejected() {
+        let signer = SoftwareSigner::ed25519(&[0x33u8; 32]).unwrap();
+        let result = signer.sign_transaction(&UnsignedTransaction::Evm { tx: legacy_tx() }).await;
+        assert!(result.is_err());
+        assert!(SoftwareSigner::secp256k1(&[0u8; 16]).is_err());
+    }
+}
@@ -0,0 +1,270 @@
+// src/crypto/signing.rs
+//! Post-quantum signatures for integrity of wallet artifacts.
+//!
+//! ML-DSA-65 (FIPS 204) is always available. SLH-DSA (FIPS 205, SHA2-128s) is
+//! compiled in with the `slh-dsa` feature.
+use anyhow::Result;
+use pqcrypto_traits::sign::{DetachedSignature as _, PublicKey as _, SecretKey as _};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{debug, info};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Supported post-quantum signature algorithms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SignatureAlgorithm {
+    /// ML-DSA-65 (FIPS 204, security category 3).
+    MlDsa65,
+    /// SLH-DSA-SHA2-128s (FIPS 205, small signatures).
+    #[cfg(feature = "slh-dsa")]
+    SlhDsaSha2_128s,
+}
+
+impl SignatureAlgorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignatureAlgorithm::MlDsa65 => "ml-dsa-65",
+            #[cfg(feature = "slh-dsa")]
+            SignatureAlgorithm::SlhDsaSha2_128s => "slh-dsa-sha2-128s",
+        }
+    }
+}
+
+impl std::fmt::Display for SignatureAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for SignatureAlgorithm {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "ml-dsa-65" => Ok(SignatureAlgorithm::MlDsa65),
+            #[cfg(feature = "slh-dsa")]
+            "slh-dsa-sha2-128s" => Ok(SignatureAlgorithm::SlhDsaSha2_128s),
+            other => Err(anyhow::anyhow!("Unsupported signature algorithm: {}", other)),
+        }
+    }
+}
+
+/// A post-quantum key pair. The secret key is wiped on drop.
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct PqKeyPair {
+    #[zeroize(skip)]
+    pub algorithm: SignatureAlgorithm,
+    #[zeroize(skip)]
+    pub public_key: Vec<u8>,
+    secret_key: Vec<u8>,
+}
+
+impl std::fmt::Debug for PqKeyPair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PqKeyPair")
+            .field("algorithm", &self.algorithm)
+            .field("public_key_len", &self.public_key.len())
+            .finish()
+    }
+}
+
+impl PqKeyPair {
+    /// Generates a fresh key pair for `algorithm`.
+    pub fn generate(algorithm: SignatureAlgorithm) -> Self {
+        debug!("Generating {} key pair", algorithm);
+        let (public_key, secret_key) = match algorithm {
+            SignatureAlgorithm::MlDsa65 => {
+                let (pk, sk) = pqcrypto_mldsa::mldsa65::keypair();
+                (pk.as_bytes().to_vec(), sk.as_bytes().to_vec())
+            }
+            #[cfg(feature = "slh-dsa")]
+            SignatureAlgorithm::SlhDsaSha2_128s => {
+                let (pk, sk) = pqcrypto_sphincsplus::sphincssha2128ssimple::keypair();
+                (pk.as_bytes().to_vec(), sk.as_bytes().to_vec())
+            }
+        };
+        let keypair = Self { algorithm, public_key, secret_key };
+        info!("Generated {} key pair {}", algorithm, keypair.key_id());
+        keypair
+    }
+
+    /// Rebuilds a key pair from raw bytes (e.g. after decrypting it from the keystore).
+    pub fn from_bytes(
+        algorithm: SignatureAlgorithm,
+        public_key: Vec<u8>,
+        secret_key: Vec<u8>,
+    ) -> Result<Self> {
+        // Parse both halves once so malformed keystore rows fail here, not at sign time.
+        match algorithm {
+            SignatureAlgorithm::MlDsa65 => {
+                pqcrypto_mldsa::mldsa65::PublicKey::from_bytes(&public_key)
+                    .map_err(|e| anyhow::anyhow!("Invalid ML-DSA public key: {}", e))?;
+                pqcrypto_mldsa::mldsa65::SecretKey::from_bytes(&secret_key)
+                    .map_err(|e| anyhow::anyhow!("Invalid ML-DSA secret key: {}", e))?;
+            }
+            #[cfg(feature = "slh-dsa")]
+            SignatureAlgorithm::SlhDsaSha2_128s => {
+                pqcrypto_sphincsplus::sphincssha2128ssimple::PublicKey::from_bytes(&public_key)
+                    .map_err(|e| anyhow::anyhow!("Invalid SLH-DSA public key: {}", e))?;
+                pqcrypto_sphincsplus::sphincssha2128ssimple::SecretKey::from_bytes(&secret_key)
+                    .map_err(|e| anyhow::anyhow!("Invalid SLH-DSA secret key: {}", e))?;
+            }
+        }
+        Ok(Self { algorithm, public_key, secret_key })
+    }
+
+    /// Raw secret key bytes. Callers must encrypt these before persisting them.
+    pub fn secret_key(&self) -> &[u8] {
+        &self.secret_key
+    }
+
+    /// Short identifier derived from the public key (first 8 bytes of its SHA-256, hex).
+    pub fn key_id(&self) -> String {
+        key_id_for(&self.public_key)
+    }
+
+    /// Produces a detached signature over `message`.
+    pub fn sign(&self, message: &[u8]) -> Result<PqSignature> {
+        let signature = match self.algorithm {
+            SignatureAlgorithm::MlDsa65 => {
+                let sk = pqcrypto_mldsa::mldsa65::SecretKey::from_bytes(&self.secret_key)
+                    .map_err(|e| anyhow::anyhow!("Invalid ML-DSA secret key: {}", e))?;
+                pqcrypto_mldsa::mldsa65::detached_sign(message, &sk).as_bytes().to_vec()
+            }
+            #[cfg(feature = "slh-dsa")]
+            SignatureAlgorithm::SlhDsaSha2_128s => {
+                let sk =
+                    pqcrypto_sphincsplus::sphincssha2128ssimple::SecretKey::from_bytes(
+                        &self.secret_key,
+                    )
+                    .map_err(|e| anyhow::anyhow!("Invalid SLH-DSA secret key: {}", e))?;
+                pqcrypto_sphincsplus::sphincssha2128ssimple::detached_sign(message, &sk)
+                    .as_bytes()
+                    .to_vec()
+            }
+        };
+
+        Ok(PqSignature { algorithm: self.algorithm, key_id: self.key_id(), signature })
+    }
+}
+
+/// A detached signature together with the algorithm and the signing key's id.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PqSignature {
+    pub algorithm: SignatureAlgorithm,
+    pub key_id: String,
+    #[serde(with = "hex_bytes")]
+    pub signature: Vec<u8>,
+}
+
+/// Verifies `signature` over `message` against `public_key`.
+///
+/// Returns `Ok(false)` for a well-formed signature that does not verify and an
+/// error when the key or signature bytes cannot be parsed at all.
+pub fn verify(public_key: &[u8], message: &[u8], signature: &PqSignature) -> Result<bool> {
+    if signature.key_id != key_id_for(public_key) {
+        debug!("Signature key id {} does not match public key", signature.key_id);
+        return Ok(false);
+    }
+
+    let valid = match signature.algorithm {
+        SignatureAlgorithm::MlDsa65 => {
+            let pk = pqcrypto_mldsa::mldsa65::PublicKey::from_bytes(public_key)
+                .map_err(|e| anyhow::anyhow!("Invalid ML-DSA public key: {}", e))?;
+            let sig = pqcrypto_mldsa::mldsa65::DetachedSignature::from_bytes(&signature.signature)
+                .map_err(|e| anyhow::anyhow!("Invalid ML-DSA signature: {}", e))?;
+            pqcrypto_mldsa::mldsa65::verify_detached_signature(&sig, message, &pk).is_ok()
+        }
+        #[cfg(feature = "slh-dsa")]
+        SignatureAlgorithm::SlhDsaSha2_128s => {
+            let pk = pqcrypto_sphincsplus::sphincssha2128ssimple::PublicKey::from_bytes(public_key)
+                .map_err(|e| anyhow::anyhow!("Invalid SLH-DSA public key: {}", e))?;
+            let sig = pqcrypto_sphincsplus::sphincssha2128ssimple::DetachedSignature::from_bytes(
+                &signature.signature,
+            )
+            .map_err(|e| anyhow::anyhow!("Invalid SLH-DSA signature: {}", e))?;
+            pqcrypto_sphincsplus::sphincssha2128ssimple::verify_detached_signature(
+                &sig, message, &pk,
+            )
+            .is_ok()
+        }
+    };
+
+    Ok(valid)
+}
+
+/// Id of the key pair with `public_key`, as returned by `PqKeyPair::key_id`.
+pub fn key_id_for(public_key: &[u8]) -> String {
+    hex::encode(&Sha256::digest(public_key)[..8])
+}
+
+/// Well-known keystore purpose under which the artifact signing key is stored.
+pub const ARTIFACT_SIGNING_KEY: &str = "artifact-signing";
+
+mod hex_bytes {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        hex::decode(s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ml_dsa_sign_and_verify() {
+        let keypair = PqKeyPair::generate(SignatureAlgorithm::MlDsa65);
+        let message = b"backup manifest";
+
+        let signature = keypair.sign(message).unwrap();
+        assert_eq!(signature.algorithm, SignatureAlgorithm::MlDsa65);
+        assert!(verify(&keypair.public_key, message, &signature).unwrap());
+        assert!(!verify(&keypair.public_key, b"tampered manifest", &signature).unwrap());
+    }
+
+    #[test]
+    fn test_verify_rejects_other_key() {
+        let signer = PqKeyPair::generate(SignatureAlgorithm::MlDsa65);
+        let other = PqKeyPair::generate(SignatureAlgorithm::MlDsa65);
+
+        let signature = signer.sign(b"audit chain").unwrap();
+        assert!(!verify(&other.public_key, b"audit chain", &signature).unwrap());
+    }
+
+    #[test]
+    fn test_keypair_roundtrip_from_bytes() {
+        let keypair = PqKeyPair::generate(SignatureAlgorithm::MlDsa65);
+        let restored = PqKeyPair::from_bytes(
+            keypair.algorithm,
+            keypair.public_key.clone(),
+            keypair.secret_key().to_vec(),
+        )
+        .unwrap();
+
+        let signature = restored.sign(b"payload").unwrap();
+        assert!(verify(&keypair.public_key, b"payload", &signature).unwrap());
+        assert_eq!(restored.key_id(), keypair.key_id());
+    }
+
+    #[test]
+    fn test_algorithm_parse() {
+        assert_eq!("ml-dsa-65".parse::<SignatureAlgorithm>().unwrap(), SignatureAlgorithm::MlDsa65);
+        assert!("rsa-2048".parse::<SignatureAlgorithm>().is_err());
+    }
+
+    #[test]
+    fn test_signature_serializes_as_hex() {
+        let keypair = PqKeyPair::generate(SignatureAlgorithm::MlDsa65);
+        let signature = keypair.sign(b"x").unwrap();
+        let json = serde_json::to_string(&signature).unwrap();
+        let back: PqSignature = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, signature);
+    }
+}
@@ -5,9 +5,12 @@ pub mod blockchain;
 pub mod cli;
 pub mod core;
 pub mod crypto;
+pub mod monitoring;
 pub mod security;
 pub mod storage;
 pub mod tools;
+pub mod walletconnect;
+pub mod webhooks;
 // 公共模块导出，确保 tests 中 `defi_hot_wallet::network`, `::ops`, `::mvp` 可见
 pub mod mvp;
 pub mod network;
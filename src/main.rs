@@ -2,11 +2,23 @@
 //! DeFi Hot Wallet Server Entry Point
 //! This binary is responsible for starting the API server.
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use clap::{Parser, Subcommand};
 use defi_hot_wallet::api::server::WalletServer;
-use defi_hot_wallet::core::config::{BlockchainConfig, StorageConfig, WalletConfig};
-use std::collections::HashMap;
-use tracing::info;
+use defi_hot_wallet::audit::export::{ExportFormat, ExportSink};
+use defi_hot_wallet::blockchain::bridge::evm::bridges_from_config;
+use defi_hot_wallet::blockchain::staking::staking_providers_from_config;
+use defi_hot_wallet::blockchain::swap::swap_routers_from_config;
+use defi_hot_wallet::core::config::{
+    BridgeConfig, StakingConfig, SwapConfig, WalletConfig, WalletConnectConfig,
+};
+use defi_hot_wallet::core::wallet_manager::WalletManager;
+use defi_hot_wallet::security::sanctions::{SanctionsFormat, SanctionsList};
+use defi_hot_wallet::tools::generator::ConfigManager;
+use defi_hot_wallet::walletconnect::WebSocketRelay;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info};
 use tracing_subscriber::{EnvFilter, FmtSubscriber};
 
 #[derive(Parser)]
@@ -27,6 +39,33 @@ enum Commands {
         #[arg(long, default_value = "8080")]
         port: u16,
     },
+    /// Check the audit log hash chain and its signed checkpoints
+    VerifyAudit,
+    /// Export the audit log rows of a time range to a file or syslog endpoint
+    ExportAudit {
+        /// File path, udp://host:port or tcp://host:port
+        output: String,
+        /// Output format: json_lines, cef or syslog
+        #[arg(long, default_value = "json_lines")]
+        format: ExportFormat,
+        /// Start of the range (RFC 3339), inclusive
+        #[arg(long)]
+        since: Option<DateTime<Utc>>,
+        /// End of the range (RFC 3339), exclusive
+        #[arg(long)]
+        until: Option<DateTime<Utc>>,
+    },
+    /// Import a sanctions list file, replacing the stored version of its source
+    ImportSanctions {
+        /// Path to the list file
+        path: std::path::PathBuf,
+        /// File format: ofac_xml, ofac_csv or csv
+        #[arg(long, default_value = "ofac_xml")]
+        format: SanctionsFormat,
+        /// Name the list is stored under
+        #[arg(long, default_value = "ofac")]
+        source: String,
+    },
 }
 
 #[tokio::main]
@@ -38,39 +77,159 @@ async fn main() -> Result<()> {
 
     info!("Starting DeFi Hot Wallet v{}", env!("CARGO_PKG_VERSION"));
 
-    // Read DATABASE_URL from env or fallback to relative path in current dir
-    let database_url =
-        std::env::var("DATABASE_URL").unwrap_or_else(|_| "sqlite://./wallets.db".to_string());
-
-    // A default configuration.
-    let wallet_config = WalletConfig {
-        storage: StorageConfig {
-            database_url: database_url.clone(),
-            max_connections: Some(10),
-            connection_timeout_seconds: Some(30),
-        },
-        blockchain: BlockchainConfig {
-            networks: HashMap::new(), // WalletManager will populate this
-            default_network: Some("eth".to_string()),
-        },
-        quantum_safe: false,
-        multi_sig_threshold: 2,
-    };
+    // Database from DATABASE_URL, shared with wallet-cli.
+    let wallet_config = WalletConfig::from_env();
 
     // Read API_KEY from environment
     let api_key = std::env::var("API_KEY").ok();
 
-    let server = WalletServer::new("127.0.0.1".to_string(), 8080, wallet_config, api_key).await?;
+    // BRIDGE_CONFIG points at a TOML file of on-chain bridge routes; without it
+    // no bridges are registered and bridge transfers are refused.
+    let wallet_manager = match std::env::var("BRIDGE_CONFIG") {
+        Ok(path) => {
+            info!("Loading bridge routes from {}", path);
+            let bridges = bridges_from_config(
+                &BridgeConfig::from_file(&path)?,
+                &WalletConfig::default().blockchain,
+            )?;
+            WalletManager::new_with_bridges(&wallet_config, bridges).await?
+        }
+        Err(_) => WalletManager::new(&wallet_config).await?,
+    };
+
+    // SWAP_CONFIG points at a TOML file of DEX routers; without it swaps are disabled.
+    let wallet_manager = match std::env::var("SWAP_CONFIG") {
+        Ok(path) => {
+            info!("Loading swap routers from {}", path);
+            wallet_manager.with_swap_routers(swap_routers_from_config(
+                &SwapConfig::from_file(&path)?,
+                &WalletConfig::default().blockchain,
+            )?)
+        }
+        Err(_) => wallet_manager,
+    };
+
+    // STAKING_CONFIG points at a TOML file of staking providers; without it staking is disabled.
+    let wallet_manager = match std::env::var("STAKING_CONFIG") {
+        Ok(path) => {
+            info!("Loading staking providers from {}", path);
+            wallet_manager.with_staking_providers(staking_providers_from_config(
+                &StakingConfig::from_file(&path)?,
+                &WalletConfig::default().blockchain,
+            )?)
+        }
+        Err(_) => wallet_manager,
+    };
+
+    // WALLETCONNECT_CONFIG points at a TOML file with the WalletConnect project
+    // id and relay URL; without it dApp sessions are disabled.
+    let wallet_manager = match std::env::var("WALLETCONNECT_CONFIG") {
+        Ok(path) => {
+            let config = WalletConnectConfig::from_file(&path)?;
+            info!("Connecting to WalletConnect relay {}", config.relay_url);
+            wallet_manager.with_walletconnect(Arc::new(WebSocketRelay::connect(&config).await?))
+        }
+        Err(_) => wallet_manager,
+    };
+
+    // SECURITY_CONFIG points at the JSON application config; its `security`
+    // section sets two-factor and lockout policy for API logins, how many
+    // approvers held operations need, the sanctions list files to screen
+    // against, and how often and with which key the audit log is signed.
+    // Its `monitoring` section lists the SIEM endpoints the audit log is
+    // exported to, how often the balances of wallets watched over
+    // `/api/stream` are polled and how often failed or stuck bridge
+    // transfers are recovered.
+    let app_config = match std::env::var("SECURITY_CONFIG") {
+        Ok(path) => {
+            info!("Loading security settings from {}", path);
+            let mut config = ConfigManager::new(path);
+            config.load()?;
+            config.validate()?;
+            Some(config.get_config().clone())
+        }
+        Err(_) => None,
+    };
+    let wallet_manager = match &app_config {
+        Some(config) => wallet_manager
+            .with_security_config(&config.security)
+            .with_monitoring_config(&config.monitoring),
+        None => wallet_manager,
+    };
+    let app_config = app_config.unwrap_or_default();
+    let audit_checkpoint_interval = app_config.security.audit_checkpoint_interval;
+    let balance_poll_interval = app_config.monitoring.balance_poll_interval;
+    let bridge_recovery_interval = app_config.monitoring.bridge_recovery_interval;
+
+    let server = WalletServer {
+        wallet_manager: Arc::new(wallet_manager),
+        host: "127.0.0.1".to_string(),
+        port: 8080,
+        config: wallet_config,
+        api_key,
+    };
+
+    let spawn_background_tasks = |server: &WalletServer| {
+        if audit_checkpoint_interval > 0 {
+            server
+                .wallet_manager
+                .spawn_audit_checkpoints(Duration::from_secs(audit_checkpoint_interval));
+        }
+        if balance_poll_interval > 0 {
+            server.wallet_manager.spawn_balance_watcher(Duration::from_secs(balance_poll_interval));
+        }
+        if bridge_recovery_interval > 0 {
+            server
+                .wallet_manager
+                .spawn_bridge_recovery(Duration::from_secs(bridge_recovery_interval));
+        }
+    };
 
     match args.command {
         Some(Commands::Server { port }) => {
             info!("Starting server on port {}", port);
             let server_with_port = WalletServer { port, ..server };
+            spawn_background_tasks(&server_with_port);
             server_with_port.start().await?;
         }
+        Some(Commands::VerifyAudit) => {
+            let result = server.wallet_manager.verify_audit_chain().await?;
+            match &result.error {
+                None => info!(
+                    "Audit log intact: {} rows, {} checkpoints, signing key {}",
+                    result.rows_checked,
+                    result.checkpoints_checked,
+                    result.key_id.as_deref().unwrap_or("none")
+                ),
+                Some(error) => {
+                    error!("Audit log verification failed: {}", error);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(Commands::ExportAudit { output, format, since, until }) => {
+            let sink: ExportSink = output.parse()?;
+            let exported =
+                server.wallet_manager.export_audit_range(format, &sink, since, until).await?;
+            info!("Exported {} audit rows as {} to {}", exported, format, output);
+        }
+        Some(Commands::ImportSanctions { path, format, source }) => {
+            let content = std::fs::read_to_string(&path)?;
+            let list = SanctionsList::parse(&source, format, &content)?;
+            let screened = server.wallet_manager.import_sanctions_list(&list).await?;
+            info!(
+                "Imported {} addresses from {} as {} version {}; {} addresses screened",
+                list.entries.len(),
+                path.display(),
+                source,
+                list.version,
+                screened
+            );
+        }
         None => {
             // Default behavior: start the server on 127.0.0.1:8080
             info!("No command specified, starting server on default port 8080");
+            spawn_background_tasks(&server);
             server.start().await?;
         }
     }
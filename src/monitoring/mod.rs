@@ -4,6 +4,8 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 use tracing::{error, info, warn};
 
+use crate::tools::async_support::AsyncEventBus;
+
 pub struct WalletMetrics {
     registry: Registry,
 
@@ -215,6 +217,7 @@ pub struct SecurityMonitor {
     #[allow(dead_code)]
     metrics: Arc<WalletMetrics>,
     suspicious_activity: Arc<Mutex<Vec<SecurityEvent>>>,
+    events: AsyncEventBus<SecurityEvent>,
 }
 
 #[derive(Debug, Clone)]
@@ -237,6 +240,19 @@ pub enum SecurityEventType {
     MalformedRequest,
 }
 
+impl SecurityEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecurityEventType::UnauthorizedAccess => "unauthorized_access",
+            SecurityEventType::SuspiciousTransaction => "suspicious_transaction",
+            SecurityEventType::MultipleFailedLogins => "multiple_failed_logins",
+            SecurityEventType::UnusualLocation => "unusual_location",
+            SecurityEventType::QuantumAttackAttempt => "quantum_attack_attempt",
+            SecurityEventType::MalformedRequest => "malformed_request",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum SecuritySeverity {
     Low,
@@ -245,20 +261,35 @@ pub enum SecuritySeverity {
     Critical,
 }
 
+impl SecuritySeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecuritySeverity::Low => "low",
+            SecuritySeverity::Medium => "medium",
+            SecuritySeverity::High => "high",
+            SecuritySeverity::Critical => "critical",
+        }
+    }
+}
+
 impl SecurityMonitor {
     pub fn new(metrics: Arc<WalletMetrics>) -> Self {
         info!("馃洝锔?Initializing security monitor");
 
-        Self { metrics, suspicious_activity: Arc::new(Mutex::new(Vec::new())) }
+        Self {
+            metrics,
+            suspicious_activity: Arc::new(Mutex::new(Vec::new())),
+            events: AsyncEventBus::new(256),
+        }
+    }
+
+    /// Receives every event reported from now on, e.g. to deliver webhooks.
+    pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<SecurityEvent> {
+        self.events.subscribe()
     }
 
     pub async fn report_security_event(&self, event: SecurityEvent) {
-        let severity_str = match event.severity {
-            SecuritySeverity::Low => "LOW",
-            SecuritySeverity::Medium => "MEDIUM",
-            SecuritySeverity::High => "HIGH",
-            SecuritySeverity::Critical => "CRITICAL",
-        };
+        let severity_str = event.severity.as_str().to_uppercase();
 
         warn!(
             "馃毃 Security Event [{}]: {} - {}",
@@ -275,6 +306,9 @@ impl SecurityMonitor {
         if events.len() > 1000 {
             events.drain(0..100);
         }
+        drop(events);
+        // No subscriber is fine; the event is still kept above.
+        let _ = self.events.publish(event.clone());
 
         // For critical events, you might want to send alerts
         if matches!(event.severity, SecuritySeverity::Critical) {
@@ -332,13 +366,8 @@ impl SecurityMonitor {
     async fn send_critical_alert(&self, event: &SecurityEvent) {
         error!("馃毃 CRITICAL SECURITY ALERT: {:?} - {}", event.event_type, event.description);
 
-        // In a real implementation, this would:
-        // - Send webhook notifications
-        // - Email administrators
-        // - Integrate with incident management systems
-        // - Possibly auto-lock affected wallets
-
-        // For now, we'll just log it
+        // Webhooks subscribed to security events get every reported event
+        // through `subscribe`; this only makes critical ones stand out in the log.
         error!(
             "Alert details: IP={:?}, Wallet={:?}, Time={}",
             event.source_ip, event.wallet_id, event.timestamp
@@ -1,9 +1,11 @@
 // src/security/access_control.rs
 use crate::tools::error::WalletError;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Role definitions
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Role {
     Admin,
     User,
@@ -22,8 +24,23 @@ impl std::fmt::Display for Role {
     }
 }
 
+impl std::str::FromStr for Role {
+    type Err = WalletError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "admin" => Ok(Role::Admin),
+            "user" => Ok(Role::User),
+            "auditor" => Ok(Role::Auditor),
+            "guest" => Ok(Role::Guest),
+            other => Err(WalletError::InvalidInput(format!("Unknown role {}", other))),
+        }
+    }
+}
+
 /// Permission definitions
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Permission {
     CreateWallet,
     TransferFunds,
@@ -31,6 +48,8 @@ pub enum Permission {
     AuditLogs,
     ManageUsers,
     SystemConfig,
+    /// Sign off on operations held for approval.
+    ApproveTransactions,
 }
 
 impl std::fmt::Display for Permission {
@@ -42,6 +61,7 @@ impl std::fmt::Display for Permission {
             Permission::AuditLogs => write!(f, "audit_logs"),
             Permission::ManageUsers => write!(f, "manage_users"),
             Permission::SystemConfig => write!(f, "system_config"),
+            Permission::ApproveTransactions => write!(f, "approve_transactions"),
         }
     }
 }
@@ -67,6 +87,7 @@ impl AccessControl {
                 Permission::AuditLogs,
                 Permission::ManageUsers,
                 Permission::SystemConfig,
+                Permission::ApproveTransactions,
             ],
         );
 
@@ -126,6 +147,14 @@ impl AccessControl {
         self.role_permissions.get(role).cloned().unwrap_or_default()
     }
 
+    /// Check whether any of `roles` grants a permission. Used for principals
+    /// whose roles are persisted rather than assigned to this manager.
+    pub fn roles_permit(&self, roles: &[Role], permission: &Permission) -> bool {
+        roles.iter().any(|role| {
+            self.role_permissions.get(role).is_some_and(|granted| granted.contains(permission))
+        })
+    }
+
     /// Check whether a user is an admin.
     pub fn is_admin(&self, user_id: &str) -> bool {
         self.has_role(user_id, &Role::Admin)
@@ -199,4 +228,16 @@ mod tests {
         assert!(ac.is_admin(admin_id));
         assert!(!ac.is_admin(user_id));
     }
+
+    #[test]
+    fn test_roles_permit_and_parse() {
+        let ac = AccessControl::new();
+        let roles: Vec<Role> = ["guest", "auditor"].iter().map(|r| r.parse().unwrap()).collect();
+
+        assert!(ac.roles_permit(&roles, &Permission::AuditLogs));
+        assert!(!ac.roles_permit(&roles, &Permission::TransferFunds));
+        assert!(!ac.roles_permit(&[], &Permission::ViewBalance));
+        assert_eq!(Role::Auditor.to_string().parse::<Role>().unwrap(), Role::Auditor);
+        assert!("root".parse::<Role>().is_err());
+    }
 }
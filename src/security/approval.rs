@@ -0,0 +1,294 @@
+// src/security/approval.rs
+//! Operations held for sign-off. When compliance or a spending policy asks
+//! for approval, the operation is persisted with the reason instead of being
+//! signed; approvers vote on it and it is carried out once enough of them
+//! agree, or dropped when one rejects it or it expires.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::audit::alert::{Confirmation, ConfirmationLevel};
+use crate::blockchain::contract::ContractCall;
+use crate::blockchain::swap::SwapParams;
+use crate::tools::generator::SecurityConfig;
+
+/// What a held request would do once approved, with the arguments it was
+/// submitted with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HeldOperation {
+    Transfer { to_address: String, amount: String, network: String, allow_revert: bool },
+    ContractCall { network: String, call: ContractCall, simulate: bool, allow_revert: bool },
+    Bridge { from_chain: String, to_chain: String, token: String, amount: String },
+    Swap { network: String, params: SwapParams },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalStatus {
+    /// Waiting for approvers.
+    Pending,
+    /// Enough approvals; being carried out.
+    Approved,
+    Rejected,
+    Expired,
+    /// Carried out; `result` holds the transaction hash or bridge transfer id.
+    Executed,
+    /// Approved but could not be carried out; `result` holds the error.
+    Failed,
+}
+
+impl ApprovalStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApprovalStatus::Pending => "pending",
+            ApprovalStatus::Approved => "approved",
+            ApprovalStatus::Rejected => "rejected",
+            ApprovalStatus::Expired => "expired",
+            ApprovalStatus::Executed => "executed",
+            ApprovalStatus::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for ApprovalStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ApprovalStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "pending" => Ok(ApprovalStatus::Pending),
+            "approved" => Ok(ApprovalStatus::Approved),
+            "rejected" => Ok(ApprovalStatus::Rejected),
+            "expired" => Ok(ApprovalStatus::Expired),
+            "executed" => Ok(ApprovalStatus::Executed),
+            "failed" => Ok(ApprovalStatus::Failed),
+            other => Err(anyhow::anyhow!("Unknown approval status {}", other)),
+        }
+    }
+}
+
+/// One approver's decision on a request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApprovalVote {
+    pub approver: String,
+    pub approved: bool,
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApprovalRequest {
+    pub id: String,
+    pub wallet_id: String,
+    pub wallet_name: String,
+    pub operation: HeldOperation,
+    /// Why the operation needs sign-off.
+    pub reason: String,
+    /// Principal that submitted the operation; it cannot vote on it.
+    pub requested_by: Option<String>,
+    pub status: ApprovalStatus,
+    pub required_approvals: u32,
+    pub votes: Vec<ApprovalVote>,
+    pub result: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ApprovalRequest {
+    pub fn new(
+        wallet_id: &str,
+        wallet_name: &str,
+        operation: HeldOperation,
+        reason: &str,
+        requested_by: Option<&str>,
+        settings: &ApprovalSettings,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            wallet_id: wallet_id.to_string(),
+            wallet_name: wallet_name.to_string(),
+            operation,
+            reason: reason.to_string(),
+            requested_by: requested_by.map(str::to_string),
+            status: ApprovalStatus::Pending,
+            required_approvals: settings.required_approvals.max(1),
+            votes: Vec::new(),
+            result: None,
+            created_at: now,
+            expires_at: now + settings.expiry,
+            updated_at: now,
+        }
+    }
+
+    /// Approvals from distinct principals other than the requester.
+    pub fn approvals(&self) -> u32 {
+        let approvers: HashSet<&str> = self
+            .votes
+            .iter()
+            .filter(|vote| vote.approved && !self.is_requester(&vote.approver))
+            .map(|vote| vote.approver.as_str())
+            .collect();
+        approvers.len() as u32
+    }
+
+    pub fn is_requester(&self, principal: &str) -> bool {
+        self.requested_by.as_deref() == Some(principal)
+    }
+
+    pub fn has_voted(&self, approver: &str) -> bool {
+        self.votes.iter().any(|vote| vote.approver == approver)
+    }
+
+    /// A pending request past its expiry.
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.status == ApprovalStatus::Pending && now >= self.expires_at
+    }
+}
+
+/// How many approvers a held operation needs and how long it waits for them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApprovalSettings {
+    pub required_approvals: u32,
+    pub expiry: Duration,
+}
+
+impl Default for ApprovalSettings {
+    fn default() -> Self {
+        Self { required_approvals: 1, expiry: Duration::hours(24) }
+    }
+}
+
+impl From<&SecurityConfig> for ApprovalSettings {
+    fn from(config: &SecurityConfig) -> Self {
+        Self {
+            required_approvals: config.approvals.required_approvals.max(1),
+            expiry: i64::try_from(config.approvals.expiry)
+                .ok()
+                .and_then(Duration::try_seconds)
+                .unwrap_or(Duration::MAX),
+        }
+    }
+}
+
+/// Tells approvers and requesters about a request. Called when it is
+/// created, on every vote and when it reaches a final state.
+#[async_trait]
+pub trait ApprovalNotifier: Send + Sync {
+    async fn notify(&self, request: &ApprovalRequest) -> Result<()>;
+}
+
+/// Writes notifications to the log as audit confirmations.
+#[derive(Debug, Clone, Default)]
+pub struct LogNotifier;
+
+#[async_trait]
+impl ApprovalNotifier for LogNotifier {
+    async fn notify(&self, request: &ApprovalRequest) -> Result<()> {
+        let level = match request.status {
+            ApprovalStatus::Pending | ApprovalStatus::Approved => ConfirmationLevel::Pending,
+            ApprovalStatus::Executed => ConfirmationLevel::Acknowledged,
+            ApprovalStatus::Rejected | ApprovalStatus::Expired | ApprovalStatus::Failed => {
+                ConfirmationLevel::Rejected
+            }
+        };
+        let message = format!(
+            "approval {} for wallet {} is {} ({}/{} approvals): {}",
+            request.id,
+            request.wallet_name,
+            request.status,
+            request.approvals(),
+            request.required_approvals,
+            request.reason
+        );
+        Confirmation::new(level, message).send().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(settings: &ApprovalSettings) -> ApprovalRequest {
+        let operation = HeldOperation::Transfer {
+            to_address: "0x742d35Cc6634C0532925a3b844Bc454e4438f44e".to_string(),
+            amount: "5".to_string(),
+            network: "eth".to_string(),
+            allow_revert: false,
+        };
+        ApprovalRequest::new(
+            "wallet-id",
+            "main",
+            operation,
+            "over threshold",
+            Some("carol"),
+            settings,
+        )
+    }
+
+    fn vote(approver: &str, approved: bool) -> ApprovalVote {
+        ApprovalVote {
+            approver: approver.to_string(),
+            approved,
+            comment: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_votes_are_tallied() {
+        let settings = ApprovalSettings { required_approvals: 2, ..ApprovalSettings::default() };
+        let mut request = request(&settings);
+        assert_eq!(request.required_approvals, 2);
+        request.votes.push(vote("alice", true));
+        request.votes.push(vote("bob", false));
+        assert_eq!(request.approvals(), 1);
+        assert!(request.has_voted("bob"));
+        assert!(!request.has_voted("carol"));
+
+        // the requester and repeated votes do not count towards quorum
+        request.votes.push(vote("carol", true));
+        request.votes.push(vote("alice", true));
+        assert_eq!(request.approvals(), 1);
+        assert!(request.is_requester("carol"));
+    }
+
+    #[test]
+    fn test_expiry_applies_to_pending_requests() {
+        let settings = ApprovalSettings { expiry: Duration::minutes(5), ..Default::default() };
+        let mut request = request(&settings);
+        let later = request.created_at + Duration::minutes(6);
+        assert!(!request.is_expired(request.created_at));
+        assert!(request.is_expired(later));
+        request.status = ApprovalStatus::Executed;
+        assert!(!request.is_expired(later));
+    }
+
+    #[test]
+    fn test_status_and_operation_round_trip() {
+        for status in [ApprovalStatus::Pending, ApprovalStatus::Failed] {
+            assert_eq!(status.as_str().parse::<ApprovalStatus>().unwrap(), status);
+        }
+        assert!("done".parse::<ApprovalStatus>().is_err());
+
+        let operation = HeldOperation::Swap {
+            network: "eth".to_string(),
+            params: SwapParams::new("WETH", "USDC", "1"),
+        };
+        let json = serde_json::to_value(&operation).unwrap();
+        assert_eq!(json["type"], "swap");
+        assert_eq!(serde_json::from_value::<HeldOperation>(json).unwrap(), operation);
+    }
+}
@@ -0,0 +1,1189 @@
+// src/security/auth.rs
+//! API authentication: users, API keys and JWT access/refresh tokens.
+//!
+//! Users log in with a password and receive a short-lived access token and a
+//! refresh token. Refreshing rotates the refresh token; presenting one that
+//! was already used or revoked fails. API keys are long-lived credentials of a
+//! user, limited to a subset of the user's scopes and optionally expiring.
+//! Passwords and API key secrets are only stored as Argon2 hashes.
+//!
+//! Scopes limit what a credential may do; roles (see `access_control`) decide
+//! what its user may do and are loaded from storage on every request, so
+//! revoking a role takes effect immediately. Users other than admins and
+//! auditors only see the wallets they created.
+//!
+//! Users may enroll a TOTP second factor. Once confirmed, logging in needs a
+//! current code or one of the recovery codes issued at enrollment, and no
+//! code is accepted twice. With `enable_2fa` in `SecurityConfig`, sending,
+//! bridging, deleting and backing up additionally need a fresh code from
+//! users (step-up). Repeated password or code failures lock the user out.
+//!
+//! The single `API_KEY` of older deployments is still accepted (compared in
+//! constant time) and acts with every scope. A server without `API_KEY` stays
+//! open until its first user is created, so that user can be bootstrapped.
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::errors::ErrorKind;
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use once_cell::sync::Lazy;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::audit::context::AuditContext;
+use crate::security::access_control::{AccessControl, Permission, Role};
+use crate::security::totp;
+use crate::storage::{
+    ApiKeyRecord, RefreshTokenRecord, TotpCredentialRecord, UserRecord, WalletStorageTrait,
+};
+use crate::tools::generator::SecurityConfig;
+
+/// Read-only access.
+pub const SCOPE_READ: &str = "read";
+/// Operations that change state or move funds.
+pub const SCOPE_WRITE: &str = "write";
+/// User management; implies every other scope.
+pub const SCOPE_ADMIN: &str = "admin";
+pub const SCOPES: [&str; 3] = [SCOPE_READ, SCOPE_WRITE, SCOPE_ADMIN];
+
+/// API keys look like `hwk_<key id>_<secret>`.
+pub const API_KEY_PREFIX: &str = "hwk_";
+
+pub const ACCESS_TOKEN_TTL_SECONDS: i64 = 15 * 60;
+pub const REFRESH_TOKEN_TTL_SECONDS: i64 = 30 * 24 * 60 * 60;
+
+/// Issuer shown by authenticator apps.
+pub const TOTP_ISSUER: &str = "DeFi Hot Wallet";
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+const MIN_PASSWORD_LENGTH: usize = 12;
+const MIN_JWT_SECRET_LENGTH: usize = 32;
+const ACCESS: &str = "access";
+const REFRESH: &str = "refresh";
+
+/// Verified instead of a real hash when the user or key is unknown, so
+/// lookups of unknown names take as long as wrong secrets.
+static DUMMY_HASH: Lazy<String> = Lazy::new(|| {
+    Argon2::default()
+        .hash_password(b"unknown credential", &SaltString::generate(&mut OsRng))
+        .expect("hashing a constant succeeds")
+        .to_string()
+});
+
+static ACCESS_CONTROL: Lazy<AccessControl> = Lazy::new(AccessControl::new);
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum AuthError {
+    #[error("Invalid credentials")]
+    InvalidCredentials,
+    #[error("Credential has expired")]
+    Expired,
+    #[error("Credential has been revoked")]
+    Revoked,
+    #[error("Missing scope: {0}")]
+    Forbidden(String),
+    #[error("Missing permission: {0}")]
+    PermissionDenied(Permission),
+    #[error("Access to wallet {0} denied")]
+    WalletDenied(String),
+    #[error("{0}")]
+    Invalid(String),
+    #[error("One-time code required")]
+    OtpRequired,
+    #[error("Two-factor authentication must be enabled for this operation")]
+    EnrollmentRequired,
+    #[error("Too many failed attempts; locked until {0}")]
+    Locked(DateTime<Utc>),
+    #[error("Authentication backend error: {0}")]
+    Backend(String),
+}
+
+impl From<anyhow::Error> for AuthError {
+    fn from(err: anyhow::Error) -> Self {
+        AuthError::Backend(err.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthMethod {
+    AccessToken,
+    ApiKey,
+    SharedKey,
+    Open,
+}
+
+/// Who a request is made by.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Principal {
+    /// `None` for the shared `API_KEY` and for an open server.
+    pub user_id: Option<String>,
+    pub username: String,
+    pub scopes: Vec<String>,
+    pub roles: Vec<Role>,
+    pub method: AuthMethod,
+}
+
+impl Principal {
+    fn superuser(username: &str, method: AuthMethod) -> Self {
+        Self {
+            user_id: None,
+            username: username.to_string(),
+            scopes: SCOPES.iter().map(|s| s.to_string()).collect(),
+            roles: vec![Role::Admin],
+            method,
+        }
+    }
+
+    pub fn has_role(&self, role: &Role) -> bool {
+        self.roles.contains(role)
+    }
+
+    pub fn has_permission(&self, permission: &Permission) -> bool {
+        ACCESS_CONTROL.roles_permit(&self.roles, permission)
+    }
+
+    pub fn require_permission(&self, permission: &Permission) -> Result<(), AuthError> {
+        if self.has_permission(permission) {
+            Ok(())
+        } else {
+            Err(AuthError::PermissionDenied(permission.clone()))
+        }
+    }
+
+    /// Admins and auditors see every wallet; everyone else only their own.
+    pub fn sees_all_wallets(&self) -> bool {
+        self.has_role(&Role::Admin) || self.has_role(&Role::Auditor)
+    }
+
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope || s == SCOPE_ADMIN)
+    }
+
+    pub fn require_scope(&self, scope: &str) -> Result<(), AuthError> {
+        if self.has_scope(scope) {
+            Ok(())
+        } else {
+            Err(AuthError::Forbidden(scope.to_string()))
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct User {
+    pub id: String,
+    pub username: String,
+    pub scopes: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&UserRecord> for User {
+    fn from(record: &UserRecord) -> Self {
+        Self {
+            id: record.id.clone(),
+            username: record.username.clone(),
+            scopes: split_scopes(&record.scopes),
+            created_at: record.created_at,
+        }
+    }
+}
+
+/// An API key as listed to its owner; the secret is never returned again.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiKey {
+    pub id: String,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&ApiKeyRecord> for ApiKey {
+    fn from(record: &ApiKeyRecord) -> Self {
+        Self {
+            id: record.id.clone(),
+            name: record.name.clone(),
+            scopes: split_scopes(&record.scopes),
+            expires_at: record.expires_at,
+            revoked_at: record.revoked_at,
+            last_used_at: record.last_used_at,
+            created_at: record.created_at,
+        }
+    }
+}
+
+/// A newly created API key. `key` is only available here.
+#[derive(Debug, Clone, Serialize)]
+pub struct IssuedApiKey {
+    #[serde(flatten)]
+    pub api_key: ApiKey,
+    pub key: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenPair {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub token_type: String,
+    /// Lifetime of the access token in seconds.
+    pub expires_in: i64,
+}
+
+/// A started TOTP enrollment. The secret and recovery codes are only
+/// available here; enrollment takes effect once confirmed with a code.
+#[derive(Debug, Clone, Serialize)]
+pub struct TotpEnrollment {
+    pub secret: String,
+    pub otpauth_uri: String,
+    pub recovery_codes: Vec<String>,
+}
+
+/// Second factor and lockout settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoginPolicy {
+    /// Require a fresh code from users for sensitive operations.
+    pub require_2fa: bool,
+    /// Failed password or code attempts before a lockout; 0 disables it.
+    pub max_login_attempts: u32,
+    pub lockout_duration: Duration,
+}
+
+impl Default for LoginPolicy {
+    fn default() -> Self {
+        Self { require_2fa: false, max_login_attempts: 5, lockout_duration: Duration::minutes(15) }
+    }
+}
+
+impl From<&SecurityConfig> for LoginPolicy {
+    fn from(config: &SecurityConfig) -> Self {
+        Self {
+            require_2fa: config.enable_2fa,
+            max_login_attempts: config.max_login_attempts,
+            lockout_duration: i64::try_from(config.lockout_duration)
+                .ok()
+                .and_then(Duration::try_seconds)
+                .unwrap_or(Duration::MAX),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    sub: String,
+    name: String,
+    scopes: Vec<String>,
+    typ: String,
+    jti: String,
+    iat: i64,
+    exp: i64,
+}
+
+pub struct AuthService {
+    storage: Arc<dyn WalletStorageTrait + Send + Sync>,
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    policy: LoginPolicy,
+}
+
+impl AuthService {
+    pub fn new(storage: Arc<dyn WalletStorageTrait + Send + Sync>, jwt_secret: &[u8]) -> Self {
+        Self {
+            storage,
+            encoding_key: EncodingKey::from_secret(jwt_secret),
+            decoding_key: DecodingKey::from_secret(jwt_secret),
+            policy: LoginPolicy::default(),
+        }
+    }
+
+    pub fn set_policy(&mut self, policy: LoginPolicy) {
+        self.policy = policy;
+    }
+
+    pub fn policy(&self) -> &LoginPolicy {
+        &self.policy
+    }
+
+    /// Signs tokens with `JWT_SECRET`. Without it a random secret is used and
+    /// tokens do not survive a restart.
+    pub fn from_env(storage: Arc<dyn WalletStorageTrait + Send + Sync>) -> Self {
+        match std::env::var("JWT_SECRET") {
+            Ok(secret) if secret.len() >= MIN_JWT_SECRET_LENGTH => {
+                Self::new(storage, secret.as_bytes())
+            }
+            Ok(_) => {
+                warn!(
+                    "JWT_SECRET is shorter than {} bytes; using a random secret",
+                    MIN_JWT_SECRET_LENGTH
+                );
+                Self::new(storage, &random_bytes(32))
+            }
+            Err(_) => {
+                warn!("JWT_SECRET not set; issued tokens will not survive a restart");
+                Self::new(storage, &random_bytes(32))
+            }
+        }
+    }
+
+    /// Creates a user. Without `scopes` the user may read and write. The user
+    /// gets the admin role when granted the admin scope, else the user role.
+    pub async fn create_user(
+        &self,
+        username: &str,
+        password: &str,
+        scopes: &[String],
+    ) -> Result<User, AuthError> {
+        if username.is_empty()
+            || username.len() > 64
+            || !username.chars().all(|c| c.is_ascii_alphanumeric() || "_-.@".contains(c))
+        {
+            return Err(AuthError::Invalid("Invalid username".to_string()));
+        }
+        if password.chars().count() < MIN_PASSWORD_LENGTH {
+            return Err(AuthError::Invalid(format!(
+                "Password must be at least {} characters",
+                MIN_PASSWORD_LENGTH
+            )));
+        }
+        let scopes = if scopes.is_empty() {
+            vec![SCOPE_READ.to_string(), SCOPE_WRITE.to_string()]
+        } else {
+            normalize_scopes(scopes)?
+        };
+        if self.storage.get_user_by_username(username).await?.is_some() {
+            return Err(AuthError::Invalid(format!("User {} already exists", username)));
+        }
+
+        let record = UserRecord {
+            id: Uuid::new_v4().to_string(),
+            username: username.to_string(),
+            password_hash: hash_secret(password.to_string()).await?,
+            scopes: scopes.join(" "),
+            created_at: Utc::now(),
+        };
+        self.storage.store_user(&record).await?;
+        let role = if scopes.iter().any(|s| s == SCOPE_ADMIN) { Role::Admin } else { Role::User };
+        self.storage.add_user_role(&record.id, &role.to_string()).await?;
+        info!("Created user {} with role {}", username, role);
+        self.audit("user_created", &format!("{} with role {}", username, role)).await;
+        Ok(User::from(&record))
+    }
+
+    pub async fn user_roles(&self, username: &str) -> Result<Vec<Role>, AuthError> {
+        let user = self.find_user(username).await?;
+        self.load_roles(&user.id).await
+    }
+
+    /// Grants a role; granting one the user already has succeeds.
+    pub async fn assign_role(&self, username: &str, role: Role) -> Result<Vec<Role>, AuthError> {
+        let user = self.find_user(username).await?;
+        if self.storage.add_user_role(&user.id, &role.to_string()).await? {
+            info!("Assigned role {} to {}", role, username);
+            self.audit("role_assigned", &format!("{} to {}", role, username)).await;
+        }
+        self.load_roles(&user.id).await
+    }
+
+    pub async fn revoke_role(&self, username: &str, role: &Role) -> Result<Vec<Role>, AuthError> {
+        let user = self.find_user(username).await?;
+        if !self.storage.remove_user_role(&user.id, &role.to_string()).await? {
+            return Err(AuthError::Invalid(format!(
+                "User {} does not have role {}",
+                username, role
+            )));
+        }
+        info!("Revoked role {} from {}", role, username);
+        self.audit("role_revoked", &format!("{} from {}", role, username)).await;
+        self.load_roles(&user.id).await
+    }
+
+    /// Records the principal's user as the owner of a new wallet. Wallets
+    /// created with the shared key or on an open server have no owner.
+    pub async fn assign_wallet_owner(
+        &self,
+        principal: &Principal,
+        wallet: &str,
+    ) -> Result<(), AuthError> {
+        if let Some(user_id) = &principal.user_id {
+            self.storage.set_wallet_owner(wallet, user_id).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn require_wallet_access(
+        &self,
+        principal: &Principal,
+        wallet: &str,
+    ) -> Result<(), AuthError> {
+        let Some(user_id) = principal.user_id.as_deref().filter(|_| !principal.sees_all_wallets())
+        else {
+            return Ok(());
+        };
+        match self.storage.get_wallet_owner(wallet).await? {
+            Some(owner) if owner == user_id => Ok(()),
+            _ => Err(AuthError::WalletDenied(wallet.to_string())),
+        }
+    }
+
+    /// Names of the wallets the principal may see; `None` when it sees all.
+    pub async fn visible_wallets(
+        &self,
+        principal: &Principal,
+    ) -> Result<Option<HashSet<String>>, AuthError> {
+        match principal.user_id.as_deref().filter(|_| !principal.sees_all_wallets()) {
+            Some(user_id) => {
+                Ok(Some(self.storage.list_owned_wallets(user_id).await?.into_iter().collect()))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Logs a user in. Users with a confirmed second factor must also pass
+    /// `otp`, a current TOTP code or an unused recovery code. Successful and
+    /// failed attempts are audited with the username as the actor.
+    pub async fn login(
+        &self,
+        username: &str,
+        password: &str,
+        otp: Option<&str>,
+    ) -> Result<TokenPair, AuthError> {
+        AuditContext::current()
+            .with_actor(username)
+            .scope(async {
+                let result = self.check_login(username, password, otp).await;
+                match &result {
+                    Ok(_) => self.audit("login", username).await,
+                    Err(e) => self.audit("login_failed", &format!("{}: {}", username, e)).await,
+                }
+                result
+            })
+            .await
+    }
+
+    async fn check_login(
+        &self,
+        username: &str,
+        password: &str,
+        otp: Option<&str>,
+    ) -> Result<TokenPair, AuthError> {
+        let user = self.storage.get_user_by_username(username).await?;
+        let hash = user.as_ref().map(|u| u.password_hash.clone());
+        let verified = verify_secret(hash, password.to_string()).await;
+        let Some(user) = user else {
+            return Err(AuthError::InvalidCredentials);
+        };
+        self.ensure_not_locked(&user.id).await?;
+        if !verified {
+            self.record_failure(&user.id).await?;
+            return Err(AuthError::InvalidCredentials);
+        }
+        if let Some(credential) = self.confirmed_totp(&user.id).await? {
+            let code = otp.ok_or(AuthError::OtpRequired)?;
+            self.verify_second_factor(&credential, code).await?;
+        }
+        self.storage.clear_login_failures(&user.id).await?;
+        info!("User {} logged in", username);
+        self.issue_tokens(&user).await
+    }
+
+    /// Starts TOTP enrollment for the principal's user, replacing a pending
+    /// one. A confirmed second factor must be disabled first.
+    pub async fn enroll_totp(&self, principal: &Principal) -> Result<TotpEnrollment, AuthError> {
+        let user_id = Self::user_id(principal)?;
+        if self.confirmed_totp(user_id).await?.is_some() {
+            return Err(AuthError::Invalid(
+                "Two-factor authentication is already enabled".to_string(),
+            ));
+        }
+        let secret = random_bytes(totp::SECRET_LENGTH);
+        let recovery_codes: Vec<String> =
+            (0..RECOVERY_CODE_COUNT).map(|_| new_recovery_code()).collect();
+        self.storage
+            .store_totp_credential(&TotpCredentialRecord {
+                user_id: user_id.to_string(),
+                secret: totp::base32_encode(&secret),
+                confirmed_at: None,
+                last_used_step: None,
+                created_at: Utc::now(),
+            })
+            .await?;
+        let hashes: Vec<String> = recovery_codes.iter().map(|c| hash_recovery_code(c)).collect();
+        self.storage.store_recovery_codes(user_id, &hashes).await?;
+        info!("TOTP enrollment started for {}", principal.username);
+        Ok(TotpEnrollment {
+            secret: totp::base32_encode(&secret),
+            otpauth_uri: totp::otpauth_uri(TOTP_ISSUER, &principal.username, &secret),
+            recovery_codes,
+        })
+    }
+
+    /// Completes enrollment with a code from the authenticator app.
+    pub async fn confirm_totp(&self, principal: &Principal, code: &str) -> Result<(), AuthError> {
+        let user_id = Self::user_id(principal)?;
+        let credential = match self.storage.get_totp_credential(user_id).await? {
+            Some(credential) if credential.confirmed_at.is_none() => credential,
+            _ => return Err(AuthError::Invalid("No pending TOTP enrollment".to_string())),
+        };
+        self.ensure_not_locked(user_id).await?;
+        self.verify_totp_code(&credential, code).await?;
+        self.storage.confirm_totp_credential(user_id, Utc::now()).await?;
+        info!("Two-factor authentication enabled for {}", principal.username);
+        self.audit("totp_enabled", &principal.username).await;
+        Ok(())
+    }
+
+    /// Turns the second factor off; needs a current code or recovery code.
+    pub async fn disable_totp(&self, principal: &Principal, code: &str) -> Result<(), AuthError> {
+        let user_id = Self::user_id(principal)?;
+        let credential = self.confirmed_totp(user_id).await?.ok_or_else(|| {
+            AuthError::Invalid("Two-factor authentication is not enabled".to_string())
+        })?;
+        self.ensure_not_locked(user_id).await?;
+        self.verify_second_factor(&credential, code).await?;
+        self.storage.delete_totp_credential(user_id).await?;
+        warn!("Two-factor authentication disabled for {}", principal.username);
+        self.audit("totp_disabled", &principal.username).await;
+        Ok(())
+    }
+
+    /// Checks a fresh code before a sensitive operation when the policy asks
+    /// for it. Only users can hold a second factor, so the shared key and an
+    /// open server are not asked.
+    pub async fn step_up(&self, principal: &Principal, otp: Option<&str>) -> Result<(), AuthError> {
+        if !self.policy.require_2fa {
+            return Ok(());
+        }
+        let Some(user_id) = principal.user_id.as_deref() else {
+            return Ok(());
+        };
+        self.ensure_not_locked(user_id).await?;
+        let credential =
+            self.confirmed_totp(user_id).await?.ok_or(AuthError::EnrollmentRequired)?;
+        let code = otp.ok_or(AuthError::OtpRequired)?;
+        self.verify_second_factor(&credential, code).await
+    }
+
+    /// Exchanges a refresh token for a new token pair. The presented refresh
+    /// token is revoked, so it can only be used once.
+    pub async fn refresh(&self, refresh_token: &str) -> Result<TokenPair, AuthError> {
+        let claims = self.decode(refresh_token, REFRESH)?;
+        if self.storage.get_refresh_token(&claims.jti).await?.is_none() {
+            return Err(AuthError::InvalidCredentials);
+        }
+        if !self.storage.revoke_refresh_token(&claims.jti, Utc::now()).await? {
+            warn!("Refresh token {} of user {} was presented again", claims.jti, claims.name);
+            return Err(AuthError::Revoked);
+        }
+        let user =
+            self.storage.get_user(&claims.sub).await?.ok_or(AuthError::InvalidCredentials)?;
+        self.issue_tokens(&user).await
+    }
+
+    /// Revokes a refresh token (logout). Revoking an expired or already
+    /// revoked token succeeds.
+    pub async fn revoke_refresh_token(&self, refresh_token: &str) -> Result<(), AuthError> {
+        let claims = match self.decode(refresh_token, REFRESH) {
+            Ok(claims) => claims,
+            Err(AuthError::Expired) => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        if self.storage.revoke_refresh_token(&claims.jti, Utc::now()).await? {
+            self.audit("token_revoked", &format!("{} of {}", claims.jti, claims.name)).await;
+        }
+        Ok(())
+    }
+
+    /// Creates an API key for the principal's user. The key may only carry
+    /// scopes the principal has; without `scopes` it gets all of them.
+    pub async fn create_api_key(
+        &self,
+        principal: &Principal,
+        name: &str,
+        scopes: &[String],
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<IssuedApiKey, AuthError> {
+        let user_id = principal.user_id.clone().ok_or_else(|| {
+            AuthError::Invalid("API keys belong to a user; authenticate as one".to_string())
+        })?;
+        if name.trim().is_empty() {
+            return Err(AuthError::Invalid("API key name is required".to_string()));
+        }
+        let scopes =
+            if scopes.is_empty() { principal.scopes.clone() } else { normalize_scopes(scopes)? };
+        if let Some(scope) = scopes.iter().find(|s| !principal.has_scope(s)) {
+            return Err(AuthError::Forbidden(scope.clone()));
+        }
+        if expires_at.is_some_and(|at| at <= Utc::now()) {
+            return Err(AuthError::Invalid("API key expiry must be in the future".to_string()));
+        }
+
+        let id = hex::encode(random_bytes(8));
+        let secret = hex::encode(random_bytes(32));
+        let record = ApiKeyRecord {
+            id: id.clone(),
+            user_id,
+            name: name.trim().to_string(),
+            key_hash: hash_secret(secret.clone()).await?,
+            scopes: scopes.join(" "),
+            expires_at,
+            revoked_at: None,
+            last_used_at: None,
+            created_at: Utc::now(),
+        };
+        self.storage.store_api_key(&record).await?;
+        info!("Created API key {} for {}", id, principal.username);
+        self.audit(
+            "api_key_created",
+            &format!("{} ({}) for {}", id, record.name, principal.username),
+        )
+        .await;
+        Ok(IssuedApiKey {
+            api_key: ApiKey::from(&record),
+            key: format!("{}{}_{}", API_KEY_PREFIX, id, secret),
+        })
+    }
+
+    pub async fn list_api_keys(&self, principal: &Principal) -> Result<Vec<ApiKey>, AuthError> {
+        let Some(user_id) = &principal.user_id else {
+            return Ok(Vec::new());
+        };
+        Ok(self.storage.list_api_keys(user_id).await?.iter().map(ApiKey::from).collect())
+    }
+
+    /// Revokes one of the principal's API keys; admins may revoke any key.
+    pub async fn revoke_api_key(&self, principal: &Principal, id: &str) -> Result<(), AuthError> {
+        let owned = match self.storage.get_api_key(id).await? {
+            Some(key) => {
+                principal.user_id.as_deref() == Some(key.user_id.as_str())
+                    || principal.has_permission(&Permission::ManageUsers)
+            }
+            None => false,
+        };
+        if !owned {
+            return Err(AuthError::Invalid(format!("Unknown API key {}", id)));
+        }
+        self.storage.revoke_api_key(id, Utc::now()).await?;
+        info!("API key {} revoked by {}", id, principal.username);
+        self.audit("api_key_revoked", id).await;
+        Ok(())
+    }
+
+    /// Resolves an `Authorization` header value: `Bearer <access token>`, an
+    /// API key (bare or as a bearer token), or the shared key.
+    pub async fn authenticate(
+        &self,
+        authorization: &str,
+        shared_key: Option<&str>,
+    ) -> Result<Principal, AuthError> {
+        let credential = authorization.trim();
+        let bearer = credential
+            .split_once(' ')
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("bearer"))
+            .map(|(_, token)| token.trim());
+
+        match bearer.unwrap_or(credential) {
+            key if key.starts_with(API_KEY_PREFIX) => self.authenticate_api_key(key).await,
+            token if bearer.is_some() => self.authenticate_access_token(token).await,
+            key => match shared_key {
+                Some(shared) if constant_time_eq(key.as_bytes(), shared.as_bytes()) => {
+                    Ok(Principal::superuser("api_key", AuthMethod::SharedKey))
+                }
+                _ => Err(AuthError::InvalidCredentials),
+            },
+        }
+    }
+
+    /// Principal for a request without credentials: only a server without a
+    /// shared key that has no users yet is open.
+    pub async fn open_access(
+        &self,
+        shared_key: Option<&str>,
+    ) -> Result<Option<Principal>, AuthError> {
+        if shared_key.is_some() || self.storage.count_users().await? > 0 {
+            return Ok(None);
+        }
+        Ok(Some(Principal::superuser("anonymous", AuthMethod::Open)))
+    }
+
+    /// Resolves a bare access token, as passed by browsers that cannot set
+    /// headers on a stream. API keys and the shared key are not accepted
+    /// this way, as they would outlive the URL they end up in.
+    pub async fn authenticate_access_token(&self, token: &str) -> Result<Principal, AuthError> {
+        let claims = self.decode(token, ACCESS)?;
+        Ok(Principal {
+            roles: self.load_roles(&claims.sub).await?,
+            user_id: Some(claims.sub),
+            username: claims.name,
+            scopes: claims.scopes,
+            method: AuthMethod::AccessToken,
+        })
+    }
+
+    async fn authenticate_api_key(&self, key: &str) -> Result<Principal, AuthError> {
+        let (id, secret) = key
+            .strip_prefix(API_KEY_PREFIX)
+            .and_then(|rest| rest.split_once('_'))
+            .ok_or(AuthError::InvalidCredentials)?;
+        let record = self.storage.get_api_key(id).await?;
+        let hash = record.as_ref().map(|r| r.key_hash.clone());
+        if !verify_secret(hash, secret.to_string()).await {
+            return Err(AuthError::InvalidCredentials);
+        }
+        let record = record.ok_or(AuthError::InvalidCredentials)?;
+        if record.revoked_at.is_some() {
+            return Err(AuthError::Revoked);
+        }
+        let now = Utc::now();
+        if record.expires_at.is_some_and(|at| at <= now) {
+            return Err(AuthError::Expired);
+        }
+        let user =
+            self.storage.get_user(&record.user_id).await?.ok_or(AuthError::InvalidCredentials)?;
+        if let Err(e) = self.storage.touch_api_key(id, now).await {
+            warn!("Failed to record use of API key {}: {}", id, e);
+        }
+
+        // a key never outlives a scope its user has lost
+        let user_scopes = split_scopes(&user.scopes);
+        let scopes = split_scopes(&record.scopes)
+            .into_iter()
+            .filter(|s| user_scopes.contains(s) || user_scopes.iter().any(|u| u == SCOPE_ADMIN))
+            .collect();
+        Ok(Principal {
+            roles: self.load_roles(&user.id).await?,
+            user_id: Some(user.id),
+            username: user.username,
+            scopes,
+            method: AuthMethod::ApiKey,
+        })
+    }
+
+    fn user_id(principal: &Principal) -> Result<&str, AuthError> {
+        principal.user_id.as_deref().ok_or_else(|| {
+            AuthError::Invalid(
+                "Two-factor authentication belongs to a user; authenticate as one".to_string(),
+            )
+        })
+    }
+
+    async fn confirmed_totp(
+        &self,
+        user_id: &str,
+    ) -> Result<Option<TotpCredentialRecord>, AuthError> {
+        Ok(self.storage.get_totp_credential(user_id).await?.filter(|c| c.confirmed_at.is_some()))
+    }
+
+    /// Accepts a TOTP code or, failing that, an unused recovery code.
+    async fn verify_second_factor(
+        &self,
+        credential: &TotpCredentialRecord,
+        code: &str,
+    ) -> Result<(), AuthError> {
+        let code = code.trim();
+        if code.len() == totp::DIGITS as usize && code.bytes().all(|b| b.is_ascii_digit()) {
+            return self.verify_totp_code(credential, code).await;
+        }
+        let hash = hash_recovery_code(code);
+        if self.storage.use_recovery_code(&credential.user_id, &hash, Utc::now()).await? {
+            warn!("Recovery code used by user {}", credential.user_id);
+            self.storage.clear_login_failures(&credential.user_id).await?;
+            return Ok(());
+        }
+        self.record_failure(&credential.user_id).await?;
+        Err(AuthError::InvalidCredentials)
+    }
+
+    /// Accepts a TOTP code once; a replayed code counts as a failure.
+    async fn verify_totp_code(
+        &self,
+        credential: &TotpCredentialRecord,
+        code: &str,
+    ) -> Result<(), AuthError> {
+        let secret = totp::base32_decode(&credential.secret)
+            .ok_or_else(|| AuthError::Backend("Stored TOTP secret is corrupt".to_string()))?;
+        let now = u64::try_from(Utc::now().timestamp()).unwrap_or_default();
+        let fresh = match totp::verify(&secret, code, now) {
+            Some(step) => {
+                let step = i64::try_from(step).unwrap_or(i64::MAX);
+                self.storage.use_totp_step(&credential.user_id, step).await?
+            }
+            None => false,
+        };
+        if !fresh {
+            self.record_failure(&credential.user_id).await?;
+            return Err(AuthError::InvalidCredentials);
+        }
+        self.storage.clear_login_failures(&credential.user_id).await?;
+        Ok(())
+    }
+
+    async fn ensure_not_locked(&self, user_id: &str) -> Result<(), AuthError> {
+        match self.storage.get_locked_until(user_id).await? {
+            Some(until) if until > Utc::now() => Err(AuthError::Locked(until)),
+            _ => Ok(()),
+        }
+    }
+
+    async fn record_failure(&self, user_id: &str) -> Result<(), AuthError> {
+        let failures = self.storage.increment_login_failures(user_id).await?;
+        let max = i64::from(self.policy.max_login_attempts);
+        if max > 0 && failures >= max {
+            let until = Utc::now()
+                .checked_add_signed(self.policy.lockout_duration)
+                .unwrap_or(DateTime::<Utc>::MAX_UTC);
+            self.storage.lock_user(user_id, until).await?;
+            warn!("User {} locked until {} after {} failed attempts", user_id, until, failures);
+        }
+        Ok(())
+    }
+
+    /// Appends an audit row that belongs to no wallet. The change it records
+    /// has happened, so a failure to record it is logged, not returned.
+    async fn audit(&self, action: &str, details: &str) {
+        if let Err(e) = self.storage.log_event(None, action, details).await {
+            warn!("Failed to audit {} ({}): {}", action, details, e);
+        }
+    }
+
+    async fn find_user(&self, username: &str) -> Result<UserRecord, AuthError> {
+        self.storage
+            .get_user_by_username(username)
+            .await?
+            .ok_or_else(|| AuthError::Invalid(format!("Unknown user {}", username)))
+    }
+
+    async fn load_roles(&self, user_id: &str) -> Result<Vec<Role>, AuthError> {
+        let mut roles = Vec::new();
+        for role in self.storage.get_user_roles(user_id).await? {
+            match role.parse() {
+                Ok(role) => roles.push(role),
+                Err(_) => warn!("Ignoring unknown role {} of user {}", role, user_id),
+            }
+        }
+        Ok(roles)
+    }
+
+    async fn issue_tokens(&self, user: &UserRecord) -> Result<TokenPair, AuthError> {
+        let now = Utc::now();
+        let claims = |typ: &str, ttl: i64| Claims {
+            sub: user.id.clone(),
+            name: user.username.clone(),
+            scopes: split_scopes(&user.scopes),
+            typ: typ.to_string(),
+            jti: Uuid::new_v4().to_string(),
+            iat: now.timestamp(),
+            exp: now.timestamp() + ttl,
+        };
+        let access = claims(ACCESS, ACCESS_TOKEN_TTL_SECONDS);
+        let refresh = claims(REFRESH, REFRESH_TOKEN_TTL_SECONDS);
+
+        self.storage
+            .store_refresh_token(&RefreshTokenRecord {
+                id: refresh.jti.clone(),
+                user_id: user.id.clone(),
+                expires_at: now + Duration::seconds(REFRESH_TOKEN_TTL_SECONDS),
+                revoked_at: None,
+                created_at: now,
+            })
+            .await?;
+        Ok(TokenPair {
+            access_token: self.encode(&access)?,
+            refresh_token: self.encode(&refresh)?,
+            token_type: "Bearer".to_string(),
+            expires_in: ACCESS_TOKEN_TTL_SECONDS,
+        })
+    }
+
+    fn encode(&self, claims: &Claims) -> Result<String, AuthError> {
+        jsonwebtoken::encode(&Header::new(Algorithm::HS256), claims, &self.encoding_key)
+            .map_err(|e| AuthError::Backend(format!("Failed to sign token: {}", e)))
+    }
+
+    fn decode(&self, token: &str, typ: &str) -> Result<Claims, AuthError> {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.leeway = 0;
+        let claims = jsonwebtoken::decode::<Claims>(token, &self.decoding_key, &validation)
+            .map_err(|e| match e.kind() {
+                ErrorKind::ExpiredSignature => AuthError::Expired,
+                _ => AuthError::InvalidCredentials,
+            })?
+            .claims;
+        if claims.typ != typ {
+            return Err(AuthError::InvalidCredentials);
+        }
+        Ok(claims)
+    }
+}
+
+/// Compares secrets without leaking where they differ or how long they are.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    let (a, b) = (Sha256::digest(a), Sha256::digest(b));
+    a.iter().zip(b.iter()).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn split_scopes(scopes: &str) -> Vec<String> {
+    scopes.split_whitespace().map(|s| s.to_string()).collect()
+}
+
+/// Checks requested scopes against `SCOPES`, dropping duplicates.
+fn normalize_scopes(scopes: &[String]) -> Result<Vec<String>, AuthError> {
+    if let Some(unknown) = scopes.iter().find(|s| !SCOPES.contains(&s.as_str())) {
+        return Err(AuthError::Invalid(format!("Unknown scope {}", unknown)));
+    }
+    Ok(SCOPES.iter().filter(|s| scopes.iter().any(|r| r == *s)).map(|s| s.to_string()).collect())
+}
+
+/// Ten base32 characters (50 bits) as `xxxxx-xxxxx`.
+fn new_recovery_code() -> String {
+    let code = totp::base32_encode(&random_bytes(10)).to_lowercase();
+    format!("{}-{}", &code[..5], &code[5..10])
+}
+
+/// Recovery codes are random, so a fast hash suffices; dashes, spaces and
+/// case are ignored.
+fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    hex::encode(Sha256::digest(normalized.as_bytes()))
+}
+
+fn random_bytes(len: usize) -> Vec<u8> {
+    let mut bytes = vec![0u8; len];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes
+}
+
+/// Argon2 is deliberately slow, so hashing runs off the async workers.
+async fn hash_secret(secret: String) -> Result<String, AuthError> {
+    tokio::task::spawn_blocking(move || {
+        Argon2::default()
+            .hash_password(secret.as_bytes(), &SaltString::generate(&mut OsRng))
+            .map(|hash| hash.to_string())
+            .map_err(|e| AuthError::Backend(format!("Failed to hash secret: {}", e)))
+    })
+    .await
+    .map_err(|e| AuthError::Backend(e.to_string()))?
+}
+
+/// Checks `secret` against `hash`, or against a dummy hash when there is none.
+async fn verify_secret(hash: Option<String>, secret: String) -> bool {
+    tokio::task::spawn_blocking(move || {
+        let known = hash.is_some();
+        let hash = hash.unwrap_or_else(|| DUMMY_HASH.clone());
+        let verified = PasswordHash::new(&hash)
+            .is_ok_and(|hash| Argon2::default().verify_password(secret.as_bytes(), &hash).is_ok());
+        known && verified
+    })
+    .await
+    .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::WalletStorage;
+
+    async fn service() -> AuthService {
+        let storage = WalletStorage::new_with_url("sqlite::memory:").await.unwrap();
+        AuthService::new(Arc::new(storage), b"0123456789abcdef0123456789abcdef")
+    }
+
+    #[tokio::test]
+    async fn test_login_refresh_rotation_and_revocation() {
+        let auth = service().await;
+        assert!(auth.open_access(None).await.unwrap().is_some());
+        assert!(auth.open_access(Some("shared")).await.unwrap().is_none());
+
+        let user = auth.create_user("alice", "correct horse battery", &[]).await.unwrap();
+        assert_eq!(user.scopes, vec![SCOPE_READ, SCOPE_WRITE]);
+        assert!(auth.open_access(None).await.unwrap().is_none());
+        assert!(auth.create_user("alice", "correct horse battery", &[]).await.is_err());
+        assert!(auth.create_user("bob", "short", &[]).await.is_err());
+
+        assert_eq!(
+            auth.login("alice", "wrong password!", None).await.unwrap_err(),
+            AuthError::InvalidCredentials
+        );
+        assert_eq!(
+            auth.login("nobody", "correct horse battery", None).await.unwrap_err(),
+            AuthError::InvalidCredentials
+        );
+        let tokens = auth.login("alice", "correct horse battery", None).await.unwrap();
+
+        let principal =
+            auth.authenticate(&format!("Bearer {}", tokens.access_token), None).await.unwrap();
+        assert_eq!(principal.user_id.as_deref(), Some(user.id.as_str()));
+        assert_eq!(principal.method, AuthMethod::AccessToken);
+        assert!(principal.has_scope(SCOPE_WRITE) && !principal.has_scope(SCOPE_ADMIN));
+        // a refresh token is not an access token
+        let as_access = auth.authenticate(&format!("Bearer {}", tokens.refresh_token), None).await;
+        assert_eq!(as_access.unwrap_err(), AuthError::InvalidCredentials);
+
+        let rotated = auth.refresh(&tokens.refresh_token).await.unwrap();
+        assert_eq!(auth.refresh(&tokens.refresh_token).await.unwrap_err(), AuthError::Revoked);
+        auth.revoke_refresh_token(&rotated.refresh_token).await.unwrap();
+        assert_eq!(auth.refresh(&rotated.refresh_token).await.unwrap_err(), AuthError::Revoked);
+
+        let shared = auth.authenticate("shared", Some("shared")).await.unwrap();
+        assert_eq!(shared.method, AuthMethod::SharedKey);
+        assert!(shared.has_scope(SCOPE_ADMIN));
+        assert!(auth.authenticate("shared!", Some("shared")).await.is_err());
+        assert!(auth.authenticate("shared", None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_api_keys_are_scoped_expiring_and_revocable() {
+        let auth = service().await;
+        auth.create_user("carol", "correct horse battery", &[]).await.unwrap();
+        let tokens = auth.login("carol", "correct horse battery", None).await.unwrap();
+        let carol =
+            auth.authenticate(&format!("Bearer {}", tokens.access_token), None).await.unwrap();
+
+        let read_only = [SCOPE_READ.to_string()];
+        let issued = auth.create_api_key(&carol, "ci", &read_only, None).await.unwrap();
+        assert!(issued.key.starts_with(API_KEY_PREFIX));
+        let principal = auth.authenticate(&issued.key, None).await.unwrap();
+        assert_eq!(principal.method, AuthMethod::ApiKey);
+        assert_eq!(principal.scopes, read_only);
+        assert!(auth.authenticate(&format!("Bearer {}", issued.key), None).await.is_ok());
+        let last = if issued.key.ends_with('0') { '1' } else { '0' };
+        let forged = format!("{}{}", &issued.key[..issued.key.len() - 1], last);
+        assert_eq!(
+            auth.authenticate(&forged, None).await.unwrap_err(),
+            AuthError::InvalidCredentials
+        );
+
+        // keys cannot exceed their creator's scopes or be created without a user
+        let admin = [SCOPE_ADMIN.to_string()];
+        assert_eq!(
+            auth.create_api_key(&carol, "ops", &admin, None).await.unwrap_err(),
+            AuthError::Forbidden(SCOPE_ADMIN.to_string())
+        );
+        let past = Utc::now() - Duration::seconds(1);
+        assert!(auth.create_api_key(&carol, "old", &[], Some(past)).await.is_err());
+        let shared = auth.authenticate("shared", Some("shared")).await.unwrap();
+        assert!(auth.create_api_key(&shared, "ci", &[], None).await.is_err());
+
+        auth.revoke_api_key(&carol, &issued.api_key.id).await.unwrap();
+        assert_eq!(auth.authenticate(&issued.key, None).await.unwrap_err(), AuthError::Revoked);
+        let keys = auth.list_api_keys(&carol).await.unwrap();
+        assert_eq!(keys.len(), 1);
+        assert!(keys[0].revoked_at.is_some() && keys[0].last_used_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_roles_and_wallet_ownership() {
+        let auth = service().await;
+        auth.create_user("dave", "correct horse battery", &[]).await.unwrap();
+        auth.create_user("erin", "correct horse battery", &[]).await.unwrap();
+        let dave_tokens = auth.login("dave", "correct horse battery", None).await.unwrap();
+        let erin_tokens = auth.login("erin", "correct horse battery", None).await.unwrap();
+        let dave_bearer = format!("Bearer {}", dave_tokens.access_token);
+        let erin_bearer = format!("Bearer {}", erin_tokens.access_token);
+        let dave = auth.authenticate(&dave_bearer, None).await.unwrap();
+        let erin = auth.authenticate(&erin_bearer, None).await.unwrap();
+        assert_eq!(dave.roles, vec![Role::User]);
+        assert!(dave.has_permission(&Permission::TransferFunds));
+        assert_eq!(
+            dave.require_permission(&Permission::ManageUsers).unwrap_err(),
+            AuthError::PermissionDenied(Permission::ManageUsers)
+        );
+
+        auth.assign_wallet_owner(&dave, "daves").await.unwrap();
+        auth.require_wallet_access(&dave, "daves").await.unwrap();
+        assert_eq!(
+            auth.require_wallet_access(&erin, "daves").await.unwrap_err(),
+            AuthError::WalletDenied("daves".to_string())
+        );
+        assert!(auth.visible_wallets(&erin).await.unwrap().unwrap().is_empty());
+
+        // roles are read from storage, so a change applies to existing tokens
+        auth.assign_role("erin", Role::Auditor).await.unwrap();
+        auth.revoke_role("erin", &Role::User).await.unwrap();
+        let erin = auth.authenticate(&erin_bearer, None).await.unwrap();
+        assert_eq!(erin.roles, vec![Role::Auditor]);
+        assert!(!erin.has_permission(&Permission::TransferFunds));
+        auth.require_wallet_access(&erin, "daves").await.unwrap();
+        assert!(auth.visible_wallets(&erin).await.unwrap().is_none());
+        assert!(auth.revoke_role("erin", &Role::User).await.is_err());
+        assert!(auth.assign_role("nobody", Role::Admin).await.is_err());
+    }
+
+    fn code(secret: &str, offset: i64) -> String {
+        let secret = totp::base32_decode(secret).unwrap();
+        totp::generate(&secret, (Utc::now().timestamp() + offset) as u64)
+    }
+
+    #[tokio::test]
+    async fn test_totp_login_step_up_and_replay() {
+        let mut auth = service().await;
+        auth.set_policy(LoginPolicy { require_2fa: true, ..LoginPolicy::default() });
+        auth.create_user("frank", "correct horse battery", &[]).await.unwrap();
+        let tokens = auth.login("frank", "correct horse battery", None).await.unwrap();
+        let frank =
+            auth.authenticate(&format!("Bearer {}", tokens.access_token), None).await.unwrap();
+        assert_eq!(auth.step_up(&frank, None).await.unwrap_err(), AuthError::EnrollmentRequired);
+
+        let enrollment = auth.enroll_totp(&frank).await.unwrap();
+        assert_eq!(enrollment.recovery_codes.len(), RECOVERY_CODE_COUNT);
+        assert!(enrollment.otpauth_uri.contains(&enrollment.secret));
+        // pending enrollment does not change login
+        auth.login("frank", "correct horse battery", None).await.unwrap();
+        auth.confirm_totp(&frank, &code(&enrollment.secret, 0)).await.unwrap();
+        assert!(auth.enroll_totp(&frank).await.is_err());
+
+        assert_eq!(
+            auth.login("frank", "correct horse battery", None).await.unwrap_err(),
+            AuthError::OtpRequired
+        );
+        let next = code(&enrollment.secret, 30);
+        auth.login("frank", "correct horse battery", Some(&next)).await.unwrap();
+        // a code is only good once, whether at login or step-up
+        assert_eq!(
+            auth.step_up(&frank, Some(&next)).await.unwrap_err(),
+            AuthError::InvalidCredentials
+        );
+        assert_eq!(auth.step_up(&frank, None).await.unwrap_err(), AuthError::OtpRequired);
+        let recovery = enrollment.recovery_codes[0].to_uppercase();
+        auth.step_up(&frank, Some(&recovery)).await.unwrap();
+        assert!(auth.step_up(&frank, Some(&recovery)).await.is_err());
+
+        // the shared key has no second factor to ask for
+        let shared = auth.authenticate("shared", Some("shared")).await.unwrap();
+        auth.step_up(&shared, None).await.unwrap();
+
+        auth.disable_totp(&frank, &enrollment.recovery_codes[1]).await.unwrap();
+        auth.login("frank", "correct horse battery", None).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_lockout_after_failed_attempts() {
+        let mut auth = service().await;
+        auth.set_policy(LoginPolicy { max_login_attempts: 3, ..LoginPolicy::default() });
+        auth.create_user("grace", "correct horse battery", &[]).await.unwrap();
+
+        for _ in 0..2 {
+            assert!(auth.login("grace", "wrong password!", None).await.is_err());
+        }
+        // a success resets the count
+        auth.login("grace", "correct horse battery", None).await.unwrap();
+        for _ in 0..3 {
+            assert!(auth.login("grace", "wrong password!", None).await.is_err());
+        }
+        assert!(matches!(
+            auth.login("grace", "correct horse battery", None).await.unwrap_err(),
+            AuthError::Locked(until) if until > Utc::now()
+        ));
+    }
+}
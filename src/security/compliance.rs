@@ -1,8 +1,9 @@
 // src/security/compliance.rs
 //! Simple compliance checks (AML / limits) used by wallet operations.
 
+use crate::security::sanctions::{normalize_address, SanctionsIndex};
 use crate::tools::error::WalletError;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Compliance result
 #[derive(Debug, Clone, PartialEq)]
@@ -37,7 +38,10 @@ pub struct ComplianceChecker {
     max_daily_limit: f64,
     max_transaction_limit: f64,
     restricted_countries: Vec<String>,
-    sanctioned_addresses: Vec<String>,
+    /// Addresses added one by one, normalized.
+    sanctioned_addresses: HashSet<String>,
+    /// Addresses of the imported sanctions lists.
+    sanctions: SanctionsIndex,
     user_daily_totals: HashMap<String, f64>,
 }
 
@@ -53,11 +57,18 @@ impl ComplianceChecker {
                 "CU".to_string(), // Cuba
                 "SY".to_string(), // Syria
             ],
-            sanctioned_addresses: vec![],
+            sanctioned_addresses: HashSet::new(),
+            sanctions: SanctionsIndex::default(),
             user_daily_totals: HashMap::new(),
         }
     }
 
+    /// Screens against `index` in addition to individually added addresses.
+    pub fn with_sanctions_index(mut self, index: SanctionsIndex) -> Self {
+        self.sanctions = index;
+        self
+    }
+
     /// Check a transaction for compliance.
     pub fn check_transaction(
         &mut self,
@@ -76,7 +87,7 @@ impl ComplianceChecker {
         }
 
         // Sanctioned recipient check (case-insensitive)
-        if self.sanctioned_addresses.iter().any(|a| a.eq_ignore_ascii_case(recipient_address)) {
+        if self.is_address_sanctioned(recipient_address) {
             return Ok(ComplianceResult::NonCompliant(
                 "Recipient address is sanctioned".to_string(),
             ));
@@ -101,12 +112,10 @@ impl ComplianceChecker {
 
         // Transaction-type specific checks
         match transaction_type {
-            TransactionType::Bridge => {
-                if amount > self.max_transaction_limit * 0.5 {
-                    return Ok(ComplianceResult::RequiresApproval(
-                        "Large bridge transactions require approval".to_string(),
-                    ));
-                }
+            TransactionType::Bridge if amount > self.max_transaction_limit * 0.5 => {
+                return Ok(ComplianceResult::RequiresApproval(
+                    "Large bridge transactions require approval".to_string(),
+                ));
             }
             TransactionType::Swap => {
                 // Placeholder for swap-specific checks
@@ -171,14 +180,13 @@ impl ComplianceChecker {
 
     /// Add sanctioned address (case-insensitive dedupe)
     pub fn add_sanctioned_address(&mut self, address: String) {
-        if !self.sanctioned_addresses.iter().any(|a| a.eq_ignore_ascii_case(&address)) {
-            self.sanctioned_addresses.push(address);
-        }
+        self.sanctioned_addresses.insert(normalize_address(&address));
     }
 
-    /// Remove sanctioned address
+    /// Remove an individually added sanctioned address; imported lists are
+    /// changed by importing a new version.
     pub fn remove_sanctioned_address(&mut self, address: &str) {
-        self.sanctioned_addresses.retain(|a| !a.eq_ignore_ascii_case(address));
+        self.sanctioned_addresses.remove(&normalize_address(address));
     }
 
     /// Get user's daily usage
@@ -188,7 +196,8 @@ impl ComplianceChecker {
 
     /// Is address sanctioned (case-insensitive)
     pub fn is_address_sanctioned(&self, address: &str) -> bool {
-        self.sanctioned_addresses.iter().any(|a| a.eq_ignore_ascii_case(address))
+        self.sanctioned_addresses.contains(&normalize_address(address))
+            || self.sanctions.contains(address)
     }
 }
 
@@ -292,4 +301,20 @@ mod tests {
         checker.remove_sanctioned_address(sanctioned_addr);
         assert!(!checker.is_address_sanctioned(sanctioned_addr));
     }
+
+    #[test]
+    fn test_imported_sanctions_index() {
+        let index = SanctionsIndex::default();
+        let mut checker = ComplianceChecker::new().with_sanctions_index(index.clone());
+        let listed = "0x8576aCC5C05D6Ce88f4e49bf65BdF0C62F91353C";
+        assert!(!checker.is_address_sanctioned(listed));
+
+        // lists imported after the checker was built are seen by it
+        index.replace(vec![listed.to_string()]);
+        assert!(checker.is_address_sanctioned(&listed.to_lowercase()));
+        let result = checker
+            .check_transaction("user123", &TransactionType::Transfer, 10.0, listed, "US")
+            .unwrap();
+        assert!(matches!(result, ComplianceResult::NonCompliant(_)));
+    }
 }
@@ -5,10 +5,15 @@
 //! zeroization utilities, and other protective measures.
 
 pub mod access_control;
+pub mod approval;
+pub mod auth;
 pub mod compliance;
 pub mod encryption;
 pub mod memory_protection;
+pub mod policy;
+pub mod sanctions;
 pub mod shamir;
+pub mod totp;
 
 // Add the new anti-debug module
 pub mod anti_debug;
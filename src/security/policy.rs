@@ -0,0 +1,366 @@
+// src/security/policy.rs
+//! Per-wallet spending policies: velocity limits over rolling windows,
+//! recipient allow/deny lists, active hours, a cooling period for new
+//! recipients and an approval threshold.
+//!
+//! Evaluation is pure; the caller loads the wallet's recent spends and the
+//! time it first saw the recipient from storage.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::security::compliance::{ComplianceResult, TransactionType};
+use crate::storage::PolicySpendRecord;
+
+/// Longest velocity window or cooling period a policy may use, which also
+/// bounds how much spend history has to be kept.
+pub const MAX_WINDOW_SECONDS: u64 = 366 * 24 * 60 * 60;
+
+fn window(seconds: u64) -> Duration {
+    Duration::seconds(seconds.min(MAX_WINDOW_SECONDS) as i64)
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpendingPolicy {
+    pub velocity_limits: Vec<VelocityLimit>,
+    /// When non-empty, the only recipients that may be paid.
+    pub allowlist: Vec<String>,
+    pub denylist: Vec<String>,
+    /// UTC hours operations may be signed in; any time when unset.
+    pub active_hours: Option<ActiveHours>,
+    /// How long a recipient must have been known before it can be paid.
+    /// Allowlisted recipients are exempt.
+    pub new_address_cooling_seconds: Option<u64>,
+    /// Operations above this amount need approval.
+    pub require_approval_above: Option<f64>,
+}
+
+/// At most `max_amount` within any `window_seconds`, counting the spends
+/// that match `asset` and `network` (either may be left out to match all).
+/// Amounts of different assets are added as they are, so a limit without an
+/// asset is only meaningful for assets of similar value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VelocityLimit {
+    #[serde(default)]
+    pub asset: Option<String>,
+    #[serde(default)]
+    pub network: Option<String>,
+    pub max_amount: f64,
+    pub window_seconds: u64,
+}
+
+/// Hours `start_hour..end_hour` (UTC, end exclusive); wraps past midnight
+/// when `start_hour > end_hour`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActiveHours {
+    pub start_hour: u32,
+    pub end_hour: u32,
+}
+
+impl ActiveHours {
+    pub fn contains(&self, hour: u32) -> bool {
+        if self.start_hour <= self.end_hour {
+            (self.start_hour..self.end_hour).contains(&hour)
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+}
+
+/// An operation about to be signed. `recipient` is `None` for operations
+/// that pay the wallet itself, such as swaps and bridge transfers.
+#[derive(Debug, Clone)]
+pub struct PolicyRequest {
+    pub transaction_type: TransactionType,
+    pub network: String,
+    pub asset: String,
+    pub amount: f64,
+    pub recipient: Option<String>,
+}
+
+impl VelocityLimit {
+    fn matches(&self, network: &str, asset: &str) -> bool {
+        self.network.as_deref().is_none_or(|n| n == network)
+            && self.asset.as_deref().is_none_or(|a| a.eq_ignore_ascii_case(asset))
+    }
+
+    fn describe(&self) -> String {
+        let scope = match (&self.asset, &self.network) {
+            (Some(asset), Some(network)) => format!("{} on {}", asset, network),
+            (Some(asset), None) => asset.clone(),
+            (None, Some(network)) => network.clone(),
+            (None, None) => "overall".to_string(),
+        };
+        format!("{} limit of {} per {}s", scope, self.max_amount, self.window_seconds)
+    }
+}
+
+impl SpendingPolicy {
+    pub fn validate(&self) -> Result<()> {
+        for limit in &self.velocity_limits {
+            if !limit.max_amount.is_finite() || limit.max_amount < 0.0 {
+                return Err(anyhow::anyhow!("Velocity limit amount must be a non-negative number"));
+            }
+            if limit.window_seconds == 0 || limit.window_seconds > MAX_WINDOW_SECONDS {
+                return Err(anyhow::anyhow!(
+                    "Velocity window must be between 1 and {} seconds",
+                    MAX_WINDOW_SECONDS
+                ));
+            }
+        }
+        if self.allowlist.iter().chain(&self.denylist).any(|a| a.trim().is_empty()) {
+            return Err(anyhow::anyhow!("Recipient lists cannot contain empty addresses"));
+        }
+        if let Some(hours) = &self.active_hours {
+            if hours.start_hour > 23 || hours.end_hour > 24 || hours.start_hour == hours.end_hour {
+                return Err(anyhow::anyhow!(
+                    "Active hours must be two different hours between 0 and 24"
+                ));
+            }
+        }
+        if self.new_address_cooling_seconds.is_some_and(|s| s > MAX_WINDOW_SECONDS) {
+            return Err(anyhow::anyhow!(
+                "Cooling period cannot exceed {} seconds",
+                MAX_WINDOW_SECONDS
+            ));
+        }
+        if self.require_approval_above.is_some_and(|a| !a.is_finite() || a < 0.0) {
+            return Err(anyhow::anyhow!("Approval threshold must be a non-negative number"));
+        }
+        Ok(())
+    }
+
+    /// How far back spends are needed to evaluate the velocity limits.
+    pub fn lookback(&self) -> Duration {
+        window(self.velocity_limits.iter().map(|l| l.window_seconds).max().unwrap_or(0))
+    }
+
+    pub fn is_allowlisted(&self, address: &str) -> bool {
+        self.allowlist.iter().any(|a| a.eq_ignore_ascii_case(address))
+    }
+
+    pub fn is_denylisted(&self, address: &str) -> bool {
+        self.denylist.iter().any(|a| a.eq_ignore_ascii_case(address))
+    }
+
+    /// Decides on `request` at `now`. `recent` are the wallet's spends
+    /// within `lookback()`; `first_seen` is when the recipient was first
+    /// seen, `None` meaning just now.
+    pub fn evaluate(
+        &self,
+        request: &PolicyRequest,
+        recent: &[PolicySpendRecord],
+        first_seen: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> ComplianceResult {
+        if let Some(recipient) = request.recipient.as_deref() {
+            if self.is_denylisted(recipient) {
+                return ComplianceResult::NonCompliant(format!(
+                    "Recipient {} is on the wallet's denylist",
+                    recipient
+                ));
+            }
+            if !self.allowlist.is_empty() && !self.is_allowlisted(recipient) {
+                return ComplianceResult::NonCompliant(format!(
+                    "Recipient {} is not on the wallet's allowlist",
+                    recipient
+                ));
+            }
+        }
+
+        if let Some(hours) = &self.active_hours {
+            if !hours.contains(now.hour()) {
+                return ComplianceResult::NonCompliant(format!(
+                    "Outside the wallet's active hours ({:02}:00-{:02}:00 UTC)",
+                    hours.start_hour, hours.end_hour
+                ));
+            }
+        }
+
+        if let (Some(recipient), Some(cooling)) =
+            (request.recipient.as_deref(), self.new_address_cooling_seconds)
+        {
+            let known_for = now - first_seen.unwrap_or(now);
+            if !self.is_allowlisted(recipient) && known_for < window(cooling) {
+                return ComplianceResult::NonCompliant(format!(
+                    "Recipient {} is new; it can be paid {}s after it was first seen",
+                    recipient, cooling
+                ));
+            }
+        }
+
+        for limit in &self.velocity_limits {
+            if !limit.matches(&request.network, &request.asset) {
+                continue;
+            }
+            let since = now - window(limit.window_seconds);
+            let spent: f64 = recent
+                .iter()
+                .filter(|s| s.created_at > since && limit.matches(&s.network, &s.asset))
+                .map(|s| s.amount)
+                .sum();
+            if spent + request.amount > limit.max_amount {
+                return ComplianceResult::NonCompliant(format!(
+                    "{} would be exceeded: spent {}, adding {}",
+                    limit.describe(),
+                    spent,
+                    request.amount
+                ));
+            }
+        }
+
+        if let Some(threshold) = self.require_approval_above {
+            if request.amount > threshold {
+                return ComplianceResult::RequiresApproval(format!(
+                    "Amount {} {} is above the approval threshold {}",
+                    request.amount, request.asset, threshold
+                ));
+            }
+        }
+
+        ComplianceResult::Compliant
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn transfer(asset: &str, amount: f64, recipient: &str) -> PolicyRequest {
+        PolicyRequest {
+            transaction_type: TransactionType::Transfer,
+            network: "eth".to_string(),
+            asset: asset.to_string(),
+            amount,
+            recipient: Some(recipient.to_string()),
+        }
+    }
+
+    fn spend(asset: &str, amount: f64, at: DateTime<Utc>) -> PolicySpendRecord {
+        PolicySpendRecord {
+            id: 0,
+            wallet_id: "wallet".to_string(),
+            tx_hash: "0xabc".to_string(),
+            transaction_type: "Transfer".to_string(),
+            network: "eth".to_string(),
+            asset: asset.to_string(),
+            recipient: None,
+            amount,
+            created_at: at,
+        }
+    }
+
+    #[test]
+    fn test_velocity_limits_use_rolling_windows() {
+        let now = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        let policy = SpendingPolicy {
+            velocity_limits: vec![VelocityLimit {
+                asset: Some("ETH".to_string()),
+                network: Some("eth".to_string()),
+                max_amount: 10.0,
+                window_seconds: 3600,
+            }],
+            ..SpendingPolicy::default()
+        };
+        let recent = vec![
+            spend("ETH", 6.0, now - Duration::minutes(30)),
+            // outside the window
+            spend("ETH", 6.0, now - Duration::minutes(90)),
+            // another asset
+            spend("USDC", 100.0, now - Duration::minutes(5)),
+        ];
+
+        let ok = policy.evaluate(&transfer("eth", 4.0, "0xbob"), &recent, None, now);
+        assert_eq!(ok, ComplianceResult::Compliant);
+        let over = policy.evaluate(&transfer("ETH", 4.5, "0xbob"), &recent, None, now);
+        assert!(
+            matches!(over, ComplianceResult::NonCompliant(reason) if reason.contains("ETH on eth"))
+        );
+        let other = policy.evaluate(&transfer("USDC", 50.0, "0xbob"), &recent, None, now);
+        assert_eq!(other, ComplianceResult::Compliant);
+        assert_eq!(policy.lookback(), Duration::hours(1));
+    }
+
+    #[test]
+    fn test_recipient_lists_hours_and_cooling() {
+        let now = Utc.with_ymd_and_hms(2024, 5, 1, 23, 30, 0).unwrap();
+        let policy = SpendingPolicy {
+            allowlist: vec!["0xAlice".to_string(), "0xBob".to_string()],
+            denylist: vec!["0xbob".to_string()],
+            active_hours: Some(ActiveHours { start_hour: 22, end_hour: 6 }),
+            new_address_cooling_seconds: Some(86_400),
+            ..SpendingPolicy::default()
+        };
+        assert_eq!(
+            policy.evaluate(&transfer("ETH", 1.0, "0xalice"), &[], None, now),
+            ComplianceResult::Compliant
+        );
+        // the denylist wins over the allowlist
+        assert!(matches!(
+            policy.evaluate(&transfer("ETH", 1.0, "0xBOB"), &[], None, now),
+            ComplianceResult::NonCompliant(reason) if reason.contains("denylist")
+        ));
+        assert!(matches!(
+            policy.evaluate(&transfer("ETH", 1.0, "0xcarol"), &[], None, now),
+            ComplianceResult::NonCompliant(reason) if reason.contains("allowlist")
+        ));
+        let noon = now - Duration::hours(11);
+        assert!(matches!(
+            policy.evaluate(&transfer("ETH", 1.0, "0xalice"), &[], None, noon),
+            ComplianceResult::NonCompliant(reason) if reason.contains("active hours")
+        ));
+
+        let open =
+            SpendingPolicy { new_address_cooling_seconds: Some(86_400), ..Default::default() };
+        let yesterday = now - Duration::days(2);
+        assert!(matches!(
+            open.evaluate(&transfer("ETH", 1.0, "0xcarol"), &[], None, now),
+            ComplianceResult::NonCompliant(reason) if reason.contains("new")
+        ));
+        assert_eq!(
+            open.evaluate(&transfer("ETH", 1.0, "0xcarol"), &[], Some(yesterday), now),
+            ComplianceResult::Compliant
+        );
+    }
+
+    #[test]
+    fn test_approval_threshold_and_validation() {
+        let now = Utc::now();
+        let policy = SpendingPolicy { require_approval_above: Some(5.0), ..Default::default() };
+        assert!(matches!(
+            policy.evaluate(&transfer("ETH", 6.0, "0xbob"), &[], None, now),
+            ComplianceResult::RequiresApproval(_)
+        ));
+        assert_eq!(
+            policy.evaluate(&transfer("ETH", 5.0, "0xbob"), &[], None, now),
+            ComplianceResult::Compliant
+        );
+        assert!(policy.validate().is_ok());
+
+        let zero_window = SpendingPolicy {
+            velocity_limits: vec![VelocityLimit {
+                asset: None,
+                network: None,
+                max_amount: 1.0,
+                window_seconds: 0,
+            }],
+            ..Default::default()
+        };
+        assert!(zero_window.validate().is_err());
+        let no_hours = SpendingPolicy {
+            active_hours: Some(ActiveHours { start_hour: 9, end_hour: 9 }),
+            ..Default::default()
+        };
+        assert!(no_hours.validate().is_err());
+        let negative = SpendingPolicy { require_approval_above: Some(-1.0), ..Default::default() };
+        assert!(negative.validate().is_err());
+
+        let parsed: SpendingPolicy =
+            serde_json::from_str(r#"{"denylist":["0xbad"],"require_approval_above":2.5}"#).unwrap();
+        assert_eq!(parsed.denylist, vec!["0xbad"]);
+        assert!(parsed.velocity_limits.is_empty());
+    }
+}
@@ -0,0 +1,440 @@
+// src/security/sanctions.rs
+//! Sanctions lists for screening counterparties.
+//!
+//! Lists are imported from the OFAC SDN files (`sdn.xml` or `sdn.csv`, whose
+//! digital currency addresses are extracted) or from a plain CSV of
+//! addresses, and stored per source so a re-import replaces the previous
+//! version of that source only. The addresses of every stored list are held
+//! in a `SanctionsIndex` shared with the compliance checker, loaded at
+//! startup and again after each import. Configured files are re-read
+//! periodically and imported when their version changes.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::storage::WalletStorageTrait;
+use crate::tools::generator::ComplianceConfig;
+
+/// Default delay between two reads of the configured list files.
+pub const DEFAULT_RELOAD_INTERVAL: Duration = Duration::from_secs(3600);
+
+static DIGITAL_CURRENCY_ADDRESS: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"Digital Currency Address - ([A-Za-z0-9]+)\s+([A-Za-z0-9]+)").unwrap()
+});
+static SDN_ENTRY: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)<sdnEntry>(.*?)</sdnEntry>").unwrap());
+static SDN_ID: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<id>(.*?)</id>").unwrap());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SanctionsFormat {
+    /// OFAC SDN list as XML (`sdn.xml`).
+    OfacXml,
+    /// OFAC SDN list as CSV (`sdn.csv`); addresses are read from the remarks.
+    OfacCsv,
+    /// One address per line, optionally followed by currency and name:
+    /// `address[,currency[,name]]`. A header row and `#` comments are skipped.
+    Csv,
+}
+
+impl SanctionsFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SanctionsFormat::OfacXml => "ofac_xml",
+            SanctionsFormat::OfacCsv => "ofac_csv",
+            SanctionsFormat::Csv => "csv",
+        }
+    }
+}
+
+impl fmt::Display for SanctionsFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SanctionsFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "ofac_xml" => Ok(SanctionsFormat::OfacXml),
+            "ofac_csv" => Ok(SanctionsFormat::OfacCsv),
+            "csv" => Ok(SanctionsFormat::Csv),
+            other => Err(anyhow::anyhow!("Unknown sanctions list format {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SanctionedAddress {
+    pub address: String,
+    /// Currency code as given by the list, e.g. `ETH` or `XBT`.
+    pub currency: Option<String>,
+    /// Name of the listed person or entity.
+    pub name: Option<String>,
+}
+
+/// One version of a list, as parsed from its file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SanctionsList {
+    pub source: String,
+    pub format: SanctionsFormat,
+    /// The publish date for OFAC XML, otherwise a digest of the file.
+    pub version: String,
+    pub entries: Vec<SanctionedAddress>,
+}
+
+impl SanctionsList {
+    pub fn parse(source: &str, format: SanctionsFormat, content: &str) -> Result<Self> {
+        let (published, entries) = match format {
+            SanctionsFormat::OfacXml => parse_ofac_xml(content),
+            SanctionsFormat::OfacCsv => (None, parse_ofac_csv(content)),
+            SanctionsFormat::Csv => (None, parse_csv(content)),
+        };
+        if entries.is_empty() {
+            return Err(anyhow::anyhow!("No addresses found in {} list {}", format, source));
+        }
+        let version =
+            published.unwrap_or_else(|| hex::encode(&Sha256::digest(content.as_bytes())[..8]));
+        Ok(Self { source: source.to_string(), format, version, entries })
+    }
+}
+
+fn parse_ofac_xml(content: &str) -> (Option<String>, Vec<SanctionedAddress>) {
+    let published = xml_field(content, "Publish_Date");
+    let mut entries = Vec::new();
+    for entry in SDN_ENTRY.captures_iter(content) {
+        let entry = &entry[1];
+        let name = match (xml_field(entry, "firstName"), xml_field(entry, "lastName")) {
+            (Some(first), Some(last)) => Some(format!("{} {}", first, last)),
+            (first, last) => last.or(first),
+        };
+        for id in SDN_ID.captures_iter(entry) {
+            let id = &id[1];
+            let Some(currency) = xml_field(id, "idType").and_then(|kind| {
+                kind.strip_prefix("Digital Currency Address - ").map(str::to_string)
+            }) else {
+                continue;
+            };
+            if let Some(address) = xml_field(id, "idNumber") {
+                entries.push(SanctionedAddress {
+                    address,
+                    currency: Some(currency),
+                    name: name.clone(),
+                });
+            }
+        }
+    }
+    (published, entries)
+}
+
+/// Text of the first `<tag>` in `xml`, unescaped and trimmed.
+fn xml_field(xml: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}>", tag);
+    let start = xml.find(&open)? + open.len();
+    let end = start + xml[start..].find(&format!("</{}>", tag))?;
+    let text = xml[start..end]
+        .trim()
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&");
+    (!text.is_empty()).then_some(text)
+}
+
+fn parse_ofac_csv(content: &str) -> Vec<SanctionedAddress> {
+    let mut entries = Vec::new();
+    for line in content.lines() {
+        let fields = split_csv_line(line);
+        let name = fields.get(1).filter(|name| !is_ofac_null(name)).cloned();
+        for found in DIGITAL_CURRENCY_ADDRESS.captures_iter(line) {
+            entries.push(SanctionedAddress {
+                address: found[2].to_string(),
+                currency: Some(found[1].to_string()),
+                name: name.clone(),
+            });
+        }
+    }
+    entries
+}
+
+/// OFAC writes `-0-` for empty fields.
+fn is_ofac_null(field: &str) -> bool {
+    field.is_empty() || field == "-0-"
+}
+
+fn parse_csv(content: &str) -> Vec<SanctionedAddress> {
+    let mut entries = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = split_csv_line(line).into_iter();
+        let Some(address) = fields.next().filter(|a| !a.eq_ignore_ascii_case("address")) else {
+            continue;
+        };
+        if address.is_empty() {
+            continue;
+        }
+        let mut optional = fields.map(|field| (!field.is_empty()).then_some(field));
+        entries.push(SanctionedAddress {
+            address,
+            currency: optional.next().flatten(),
+            name: optional.next().flatten(),
+        });
+    }
+    entries
+}
+
+/// Splits a CSV line on commas outside double quotes; `""` inside quotes is a quote.
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+/// Normalized form used for lookups. Matching is case-insensitive, like the
+/// rest of the compliance checks.
+pub fn normalize_address(address: &str) -> String {
+    address.trim().to_lowercase()
+}
+
+/// Addresses of all imported lists. Clones share the same set.
+#[derive(Debug, Clone, Default)]
+pub struct SanctionsIndex {
+    addresses: Arc<RwLock<HashSet<String>>>,
+}
+
+impl SanctionsIndex {
+    pub fn contains(&self, address: &str) -> bool {
+        self.addresses
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .contains(&normalize_address(address))
+    }
+
+    pub fn len(&self) -> usize {
+        self.addresses.read().unwrap_or_else(|poisoned| poisoned.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Replaces the indexed addresses.
+    pub fn replace<I: IntoIterator<Item = String>>(&self, addresses: I) {
+        let addresses = addresses.into_iter().map(|a| normalize_address(&a)).collect();
+        *self.addresses.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = addresses;
+    }
+}
+
+/// A list file re-read by the screener.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SanctionsFeed {
+    pub source: String,
+    pub path: PathBuf,
+    pub format: SanctionsFormat,
+}
+
+/// Imports lists into storage and keeps the index in sync with them.
+pub struct SanctionsScreener {
+    storage: Arc<dyn WalletStorageTrait + Send + Sync>,
+    index: SanctionsIndex,
+    feeds: Vec<SanctionsFeed>,
+    reload_interval: Duration,
+}
+
+impl SanctionsScreener {
+    pub fn new(storage: Arc<dyn WalletStorageTrait + Send + Sync>, index: SanctionsIndex) -> Self {
+        Self { storage, index, feeds: Vec::new(), reload_interval: DEFAULT_RELOAD_INTERVAL }
+    }
+
+    pub fn with_feeds(mut self, feeds: Vec<SanctionsFeed>, reload_interval: Duration) -> Self {
+        self.feeds = feeds;
+        self.reload_interval = reload_interval;
+        self
+    }
+
+    pub fn index(&self) -> &SanctionsIndex {
+        &self.index
+    }
+
+    pub fn feeds(&self) -> &[SanctionsFeed] {
+        &self.feeds
+    }
+
+    /// Loads the addresses of every stored list into the index; returns how
+    /// many distinct addresses it now holds.
+    pub async fn load(&self) -> Result<usize> {
+        let addresses = self.storage.get_sanctioned_addresses().await?;
+        self.index.replace(addresses);
+        Ok(self.index.len())
+    }
+
+    /// Stores `list` in place of the previous version of its source and
+    /// reloads the index.
+    pub async fn import(&self, list: &SanctionsList) -> Result<usize> {
+        self.storage.replace_sanctions_list(list).await?;
+        info!(
+            "Imported {} addresses from sanctions list {} version {}",
+            list.entries.len(),
+            list.source,
+            list.version
+        );
+        self.load().await
+    }
+
+    /// Reads and imports the feed's file; `None` when the stored version of
+    /// its source is already the file's version.
+    pub async fn import_feed(&self, feed: &SanctionsFeed) -> Result<Option<SanctionsList>> {
+        let content = tokio::fs::read_to_string(&feed.path)
+            .await
+            .with_context(|| format!("Failed to read sanctions list {}", feed.path.display()))?;
+        let list = SanctionsList::parse(&feed.source, feed.format, &content)?;
+        let current = self.storage.get_sanctions_lists().await?;
+        if current
+            .iter()
+            .any(|stored| stored.source == list.source && stored.version == list.version)
+        {
+            return Ok(None);
+        }
+        self.import(&list).await?;
+        Ok(Some(list))
+    }
+
+    /// Imports every feed whose file changed; returns how many were imported.
+    pub async fn reload_feeds(&self) -> usize {
+        let mut imported = 0;
+        for feed in &self.feeds {
+            match self.import_feed(feed).await {
+                Ok(Some(_)) => imported += 1,
+                Ok(None) => {}
+                Err(e) => warn!("Failed to reload sanctions list {}: {}", feed.source, e),
+            }
+        }
+        imported
+    }
+
+    /// Runs `reload_feeds` every `reload_interval` until the task is aborted,
+    /// starting immediately.
+    pub fn spawn(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.reload_interval);
+            loop {
+                interval.tick().await;
+                self.reload_feeds().await;
+            }
+        })
+    }
+}
+
+/// The list files configured in the compliance section.
+pub fn feeds_from_config(config: &ComplianceConfig) -> Vec<SanctionsFeed> {
+    config
+        .sanctions_lists
+        .iter()
+        .map(|list| SanctionsFeed {
+            source: list.source.clone(),
+            path: PathBuf::from(&list.path),
+            format: list.format,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SDN_XML: &str = r#"<?xml version="1.0" standalone="yes"?>
+<sdnList xmlns="http://tempuri.org/sdnList.xsd">
+  <publshInformation><Publish_Date>04/15/2024</Publish_Date><Record_Count>2</Record_Count></publshInformation>
+  <sdnEntry>
+    <uid>1</uid><firstName>Ivan</firstName><lastName>Example</lastName><sdnType>Individual</sdnType>
+    <idList>
+      <id><uid>10</uid><idType>Passport</idType><idNumber>X123</idNumber></id>
+      <id><uid>11</uid><idType>Digital Currency Address - ETH</idType><idNumber>0x8576aCC5C05D6Ce88f4e49bf65BdF0C62F91353C</idNumber></id>
+      <id><uid>12</uid><idType>Digital Currency Address - XBT</idType><idNumber>1ECeZBxCVJ8Wm2JSN3Cyc6rge2gnvD3W5K</idNumber></id>
+    </idList>
+  </sdnEntry>
+  <sdnEntry>
+    <uid>2</uid><lastName>ACME TRADING &amp; CO</lastName><sdnType>Entity</sdnType>
+  </sdnEntry>
+</sdnList>"#;
+
+    #[test]
+    fn test_parse_ofac_xml() {
+        let list = SanctionsList::parse("ofac", SanctionsFormat::OfacXml, SDN_XML).unwrap();
+        assert_eq!(list.version, "04/15/2024");
+        assert_eq!(list.entries.len(), 2);
+        assert_eq!(list.entries[0].address, "0x8576aCC5C05D6Ce88f4e49bf65BdF0C62F91353C");
+        assert_eq!(list.entries[0].currency.as_deref(), Some("ETH"));
+        assert_eq!(list.entries[1].name.as_deref(), Some("Ivan Example"));
+    }
+
+    #[test]
+    fn test_parse_ofac_csv_and_generic_csv() {
+        let sdn = concat!(
+            "36,\"EXAMPLE, Ivan\",\"individual\",\"CYBER2\",-0- ,-0- ,-0- ,-0- ,-0- ,-0- ,-0- ,",
+            "\"Digital Currency Address - ETH 0x8576aCC5C05D6Ce88f4e49bf65BdF0C62F91353C; ",
+            "Digital Currency Address - XBT 1ECeZBxCVJ8Wm2JSN3Cyc6rge2gnvD3W5K.\"\n",
+            "37,\"NO ADDRESSES\",-0- ,\"SDGT\",-0- ,-0- ,-0- ,-0- ,-0- ,-0- ,-0- ,-0- \n"
+        );
+        let list = SanctionsList::parse("ofac", SanctionsFormat::OfacCsv, sdn).unwrap();
+        assert_eq!(list.entries.len(), 2);
+        assert_eq!(list.entries[0].name.as_deref(), Some("EXAMPLE, Ivan"));
+        assert_eq!(list.entries[1].address, "1ECeZBxCVJ8Wm2JSN3Cyc6rge2gnvD3W5K");
+
+        let csv = "address,currency,name\n# internal\n0xAbC,ETH,\n0xdef\n";
+        let list = SanctionsList::parse("internal", SanctionsFormat::Csv, csv).unwrap();
+        assert_eq!(list.entries.len(), 2);
+        assert_eq!(list.entries[0].currency.as_deref(), Some("ETH"));
+        assert_eq!(list.entries[0].name, None);
+        assert_eq!(list.version.len(), 16);
+        assert_ne!(
+            list.version,
+            SanctionsList::parse("internal", SanctionsFormat::Csv, "0x1").unwrap().version
+        );
+
+        assert!(SanctionsList::parse("empty", SanctionsFormat::Csv, "address\n").is_err());
+    }
+
+    #[test]
+    fn test_index_is_shared_and_case_insensitive() {
+        let index = SanctionsIndex::default();
+        let shared = index.clone();
+        index.replace(vec!["0xAbC".to_string()]);
+        assert!(shared.contains("0xabc"));
+        assert!(shared.contains(" 0XABC "));
+        assert!(!shared.contains("0xdef"));
+        assert_eq!("ofac_csv".parse::<SanctionsFormat>().unwrap(), SanctionsFormat::OfacCsv);
+    }
+}
@@ -0,0 +1,155 @@
+// src/security/totp.rs
+//! RFC 6238 time-based one-time passwords (HMAC-SHA1, 6 digits, 30 second
+//! steps), as produced by common authenticator apps.
+use ring::hmac;
+
+use crate::security::auth::constant_time_eq;
+
+pub const DIGITS: u32 = 6;
+pub const STEP_SECONDS: u64 = 30;
+/// Steps accepted either side of the current one, for clock drift.
+pub const SKEW_STEPS: u64 = 1;
+pub const SECRET_LENGTH: usize = 20;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// The time step `unix_time` falls in.
+pub fn step_at(unix_time: u64) -> u64 {
+    unix_time / STEP_SECONDS
+}
+
+/// The RFC 4226 HOTP code for counter `step`.
+pub fn code_for_step(secret: &[u8], step: u64) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, secret);
+    let digest = hmac::sign(&key, &step.to_be_bytes());
+    let digest = digest.as_ref();
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+    format!("{:0width$}", binary % 10u32.pow(DIGITS), width = DIGITS as usize)
+}
+
+/// The code an authenticator shows at `unix_time`.
+pub fn generate(secret: &[u8], unix_time: u64) -> String {
+    code_for_step(secret, step_at(unix_time))
+}
+
+/// Returns the step `code` is valid for at `unix_time`, allowing
+/// `SKEW_STEPS` of drift. Callers must reject steps already used.
+pub fn verify(secret: &[u8], code: &str, unix_time: u64) -> Option<u64> {
+    let code = code.trim();
+    if code.len() != DIGITS as usize || !code.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let current = step_at(unix_time);
+    let mut matched = None;
+    // check every candidate so the time taken does not depend on which matched
+    for step in current.saturating_sub(SKEW_STEPS)..=current + SKEW_STEPS {
+        if constant_time_eq(code_for_step(secret, step).as_bytes(), code.as_bytes()) {
+            matched = Some(step);
+        }
+    }
+    matched
+}
+
+/// RFC 4648 base32 without padding, as used in `otpauth://` URIs.
+pub fn base32_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(5) * 8);
+    for chunk in data.chunks(5) {
+        let mut buf = [0u8; 5];
+        buf[..chunk.len()].copy_from_slice(chunk);
+        let bits = u64::from_be_bytes([0, 0, 0, buf[0], buf[1], buf[2], buf[3], buf[4]]);
+        let chars = (chunk.len() * 8).div_ceil(5);
+        for i in 0..chars {
+            let index = (bits >> (35 - i * 5)) & 0x1f;
+            out.push(BASE32_ALPHABET[index as usize] as char);
+        }
+    }
+    out
+}
+
+/// Decodes base32, ignoring case, padding and spaces.
+pub fn base32_decode(encoded: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(encoded.len() * 5 / 8);
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for c in encoded.chars().filter(|c| *c != '=' && !c.is_whitespace()) {
+        let value = BASE32_ALPHABET.iter().position(|a| *a as char == c.to_ascii_uppercase())?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(out)
+}
+
+/// Key URI for authenticator apps (`otpauth://totp/...`).
+pub fn otpauth_uri(issuer: &str, account: &str, secret: &[u8]) -> String {
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        percent_encode(issuer),
+        percent_encode(account),
+        base32_encode(secret),
+        percent_encode(issuer),
+        DIGITS,
+        STEP_SECONDS
+    )
+}
+
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc6238_vectors() {
+        // RFC 6238 appendix B (SHA-1), truncated to 6 digits
+        let secret = b"12345678901234567890";
+        assert_eq!(generate(secret, 59), "287082");
+        assert_eq!(generate(secret, 1111111109), "081804");
+        assert_eq!(generate(secret, 1234567890), "005924");
+        assert_eq!(generate(secret, 2000000000), "279037");
+    }
+
+    #[test]
+    fn test_verify_allows_skew_only() {
+        let secret = b"12345678901234567890";
+        let now = 1111111109;
+        let step = step_at(now);
+        assert_eq!(verify(secret, &code_for_step(secret, step), now), Some(step));
+        assert_eq!(verify(secret, &code_for_step(secret, step - 1), now), Some(step - 1));
+        assert_eq!(verify(secret, &code_for_step(secret, step + 2), now), None);
+        assert_eq!(verify(secret, "12345", now), None);
+        assert_eq!(verify(secret, "abcdef", now), None);
+    }
+
+    #[test]
+    fn test_base32_and_uri() {
+        assert_eq!(base32_encode(b"foobar"), "MZXW6YTBOI");
+        assert_eq!(base32_decode("mzxw6ytboi======").unwrap(), b"foobar");
+        assert!(base32_decode("not base32!").is_none());
+        let secret = b"12345678901234567890";
+        assert_eq!(base32_decode(&base32_encode(secret)).unwrap(), secret);
+
+        let uri = otpauth_uri("DeFi Hot Wallet", "alice@example.com", secret);
+        assert!(uri.starts_with("otpauth://totp/DeFi%20Hot%20Wallet:alice%40example.com?secret="));
+        assert!(uri.contains("&digits=6&period=30"));
+    }
+}
@@ -1,11 +1,20 @@
 use anyhow::Result;
 use async_trait::async_trait;
+use chrono::SubsecRound;
 use chrono::{DateTime /* NaiveDate */};
 use sqlx::types::chrono::Utc;
-use sqlx::{sqlite::SqlitePool, types::chrono::NaiveDateTime, FromRow, Row};
+use sqlx::{
+    sqlite::{Sqlite, SqlitePool, SqliteRow},
+    types::chrono::NaiveDateTime,
+    FromRow, QueryBuilder, Row,
+};
 use tracing::{debug, info, warn};
 
+use crate::audit::chain::{row_hash, GENESIS_HASH};
+use crate::audit::context::AuditContext;
 use crate::blockchain::bridge::{BridgeTransaction, BridgeTransactionStatus};
+use crate::security::approval::{ApprovalRequest, ApprovalStatus, ApprovalVote};
+use crate::security::sanctions::SanctionsList;
 
 #[derive(Debug)]
 pub struct WalletStorage {
@@ -139,7 +148,11 @@ impl WalletStorage {
                 details TEXT,
                 ip_address TEXT,
                 user_agent TEXT,
-                created_at DATETIME NOT NULL
+                created_at DATETIME NOT NULL,
+                actor TEXT,
+                request_id TEXT,
+                prev_hash TEXT,
+                hash TEXT
             )
             "#,
         )
@@ -147,6 +160,35 @@ impl WalletStorage {
         .await
         .map_err(|e| anyhow::anyhow!("Failed to create audit_logs table: {}", e))?;
 
+        // Audit logs created before rows were attributed and chained
+        let columns: Vec<String> = sqlx::query("SELECT name FROM pragma_table_info('audit_logs')")
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(|row| row.get("name"))
+            .collect();
+        for column in ["actor", "request_id", "prev_hash", "hash"] {
+            if !columns.iter().any(|c| c == column) {
+                sqlx::query(&format!("ALTER TABLE audit_logs ADD COLUMN {} TEXT", column))
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Failed to migrate audit_logs: {}", e))?;
+            }
+        }
+        // One-time data migrations that have already been applied
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS schema_migrations (
+                name TEXT PRIMARY KEY,
+                applied_at DATETIME NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create schema_migrations table: {}", e))?;
+        self.chain_unhashed_audit_logs().await?;
+
         // Bridge Transactions table
         sqlx::query(
             r#"
@@ -170,130 +212,551 @@ impl WalletStorage {
         .execute(&self.pool)
         .await?;
 
-        // Create indexes
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_wallets_name ON wallets (name)")
-            .execute(&self.pool)
-            .await?;
+        // Bridge recovery steps (redemptions, refunds and escalations per transfer)
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS bridge_recovery_steps (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                bridge_tx_id TEXT NOT NULL,
+                action TEXT NOT NULL,
+                tx_hash TEXT,
+                details TEXT,
+                created_at DATETIME NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create bridge_recovery_steps table: {}", e))?;
 
+        // Swaps table (details of DEX swaps; the swap itself is also in transactions)
         sqlx::query(
-            "CREATE INDEX IF NOT EXISTS idx_transactions_wallet_id ON transactions (wallet_id)",
+            r#"
+            CREATE TABLE IF NOT EXISTS swaps (
+                tx_hash TEXT PRIMARY KEY,
+                wallet_id TEXT NOT NULL,
+                network TEXT NOT NULL,
+                protocol TEXT NOT NULL,
+                token_in TEXT NOT NULL,
+                token_out TEXT NOT NULL,
+                amount_in TEXT NOT NULL,
+                expected_amount_out TEXT NOT NULL,
+                min_amount_out TEXT NOT NULL,
+                approval_tx_hash TEXT,
+                created_at DATETIME NOT NULL
+            )
+            "#,
         )
         .execute(&self.pool)
-        .await?;
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create swaps table: {}", e))?;
 
+        // Staking operations table (stake, unstake and withdraw per position)
         sqlx::query(
-            "CREATE INDEX IF NOT EXISTS idx_transactions_tx_hash ON transactions (tx_hash)",
+            r#"
+            CREATE TABLE IF NOT EXISTS staking_operations (
+                tx_hash TEXT PRIMARY KEY,
+                wallet_id TEXT NOT NULL,
+                network TEXT NOT NULL,
+                protocol TEXT NOT NULL,
+                action TEXT NOT NULL,
+                position_id TEXT NOT NULL,
+                amount TEXT NOT NULL,
+                created_at DATETIME NOT NULL
+            )
+            "#,
         )
         .execute(&self.pool)
-        .await?;
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create staking_operations table: {}", e))?;
 
+        // Signing keys table (post-quantum keys for artifact integrity)
         sqlx::query(
-            "CREATE INDEX IF NOT EXISTS idx_audit_logs_wallet_id ON audit_logs (wallet_id)",
+            r#"
+            CREATE TABLE IF NOT EXISTS signing_keys (
+                purpose TEXT PRIMARY KEY,
+                key_id TEXT NOT NULL,
+                algorithm TEXT NOT NULL,
+                public_key BLOB NOT NULL,
+                encrypted_secret_key BLOB NOT NULL,
+                created_at DATETIME NOT NULL
+            )
+            "#,
         )
         .execute(&self.pool)
-        .await?;
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create signing_keys table: {}", e))?;
 
-        debug!("Database schema initialized");
-        Ok(())
-    }
+        // Audit signatures table (signed digests over the audit log chain)
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS audit_signatures (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                last_log_id INTEGER NOT NULL,
+                chain_digest TEXT NOT NULL,
+                signature TEXT NOT NULL,
+                created_at DATETIME NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create audit_signatures table: {}", e))?;
 
-    pub async fn store_wallet(
-        &self,
-        name: &str,
-        encrypted_data: &[u8],
-        quantum_safe: bool,
-    ) -> Result<()> {
-        debug!("Storing wallet: {}", name);
+        // API users, their API keys and issued refresh tokens
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS users (
+                id TEXT PRIMARY KEY,
+                username TEXT UNIQUE NOT NULL,
+                password_hash TEXT NOT NULL,
+                scopes TEXT NOT NULL,
+                created_at DATETIME NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create users table: {}", e))?;
 
-        let wallet_id = uuid::Uuid::new_v4().to_string();
-        let now = Utc::now().naive_utc();
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS api_keys (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                key_hash TEXT NOT NULL,
+                scopes TEXT NOT NULL,
+                expires_at DATETIME,
+                revoked_at DATETIME,
+                last_used_at DATETIME,
+                created_at DATETIME NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create api_keys table: {}", e))?;
 
         sqlx::query(
             r#"
-            INSERT INTO wallets (id, name, encrypted_data, quantum_safe, created_at, updated_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            CREATE TABLE IF NOT EXISTS refresh_tokens (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                expires_at DATETIME NOT NULL,
+                revoked_at DATETIME,
+                created_at DATETIME NOT NULL
+            )
             "#,
         )
-        .bind(&wallet_id)
-        .bind(name)
-        .bind(encrypted_data)
-        .bind(quantum_safe)
-        .bind(now)
-        .bind(now)
         .execute(&self.pool)
         .await
-        .map_err(|e| anyhow::anyhow!("Failed to store wallet: {}", e))?;
+        .map_err(|e| anyhow::anyhow!("Failed to create refresh_tokens table: {}", e))?;
 
-        // Log the action
-        self.log_action(
-            &wallet_id,
-            "wallet_created",
-            &format!("Wallet '{}' created", name),
-            None,
-            None,
+        // Persisted RBAC roles and wallet ownership
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS user_roles (
+                user_id TEXT NOT NULL,
+                role TEXT NOT NULL,
+                created_at DATETIME NOT NULL,
+                PRIMARY KEY (user_id, role)
+            )
+            "#,
         )
-        .await?;
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create user_roles table: {}", e))?;
 
-        debug!("Stored wallet: {}", name);
-        Ok(())
-    }
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS wallet_owners (
+                wallet_name TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                created_at DATETIME NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create wallet_owners table: {}", e))?;
 
-    pub async fn load_wallet(&self, name: &str) -> Result<(Vec<u8>, bool)> {
-        debug!("Loading wallet: {}", name);
+        // Second factors and failed login tracking
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS totp_credentials (
+                user_id TEXT PRIMARY KEY,
+                secret TEXT NOT NULL,
+                confirmed_at DATETIME,
+                last_used_step INTEGER,
+                created_at DATETIME NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create totp_credentials table: {}", e))?;
 
-        let row =
-            sqlx::query("SELECT id, encrypted_data, quantum_safe FROM wallets WHERE name = ?1")
-                .bind(name)
-                .fetch_optional(&self.pool)
-                .await
-                .map_err(|e| anyhow::anyhow!("Failed to load wallet: {}", e))?;
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS recovery_codes (
+                user_id TEXT NOT NULL,
+                code_hash TEXT NOT NULL,
+                used_at DATETIME,
+                created_at DATETIME NOT NULL,
+                PRIMARY KEY (user_id, code_hash)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create recovery_codes table: {}", e))?;
 
-        match row {
-            Some(row) => {
-                let wallet_id: String = row.get("id");
-                let encrypted_data: Vec<u8> = row.get("encrypted_data");
-                let quantum_safe: bool = row.get("quantum_safe");
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS login_failures (
+                user_id TEXT PRIMARY KEY,
+                failures INTEGER NOT NULL,
+                locked_until DATETIME
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create login_failures table: {}", e))?;
 
-                // Log the action
-                self.log_action(
-                    &wallet_id,
-                    "wallet_accessed",
-                    &format!("Wallet '{}' accessed", name),
-                    None,
-                    None,
-                )
-                .await?;
+        // Spending policies and the history they are evaluated against
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS spending_policies (
+                wallet_id TEXT PRIMARY KEY,
+                policy TEXT NOT NULL,
+                updated_at DATETIME NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create spending_policies table: {}", e))?;
 
-                debug!("Wallet loaded: {}", name);
-                Ok((encrypted_data, quantum_safe))
-            }
-            None => Err(anyhow::anyhow!("Wallet not found: {}", name)),
-        }
-    }
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS policy_spends (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                wallet_id TEXT NOT NULL,
+                tx_hash TEXT NOT NULL,
+                transaction_type TEXT NOT NULL,
+                network TEXT NOT NULL,
+                asset TEXT NOT NULL,
+                recipient TEXT,
+                amount REAL NOT NULL,
+                created_at DATETIME NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create policy_spends table: {}", e))?;
 
-    pub async fn list_wallets(&self) -> Result<Vec<WalletMetadata>> {
-        debug!("Listing all wallets");
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS known_recipients (
+                wallet_id TEXT NOT NULL,
+                address TEXT NOT NULL,
+                first_seen DATETIME NOT NULL,
+                PRIMARY KEY (wallet_id, address)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create known_recipients table: {}", e))?;
 
-        let wallets = sqlx::query_as::<_, WalletMetadata>(
-                "SELECT id, name, quantum_safe, created_at, updated_at FROM wallets ORDER BY created_at DESC"
+        // Operations held for sign-off and the approvers' votes on them
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS approval_requests (
+                id TEXT PRIMARY KEY,
+                wallet_id TEXT NOT NULL,
+                wallet_name TEXT NOT NULL,
+                operation TEXT NOT NULL,
+                reason TEXT NOT NULL,
+                requested_by TEXT,
+                status TEXT NOT NULL,
+                required_approvals INTEGER NOT NULL,
+                result TEXT,
+                created_at DATETIME NOT NULL,
+                expires_at DATETIME NOT NULL,
+                updated_at DATETIME NOT NULL
             )
-            .fetch_all(&self.pool)
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to list wallets: {}", e))?;
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create approval_requests table: {}", e))?;
 
-        debug!("Listed {} wallets", wallets.len());
-        Ok(wallets)
-    }
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS approval_votes (
+                request_id TEXT NOT NULL,
+                approver TEXT NOT NULL,
+                approved BOOLEAN NOT NULL,
+                comment TEXT,
+                created_at DATETIME NOT NULL,
+                PRIMARY KEY (request_id, approver)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create approval_votes table: {}", e))?;
 
-    pub async fn delete_wallet(&self, name: &str) -> Result<()> {
-        debug!("Deleting wallet: {}", name);
+        // Imported sanctions lists, one version per source, and their addresses
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS sanctions_lists (
+                source TEXT PRIMARY KEY,
+                format TEXT NOT NULL,
+                version TEXT NOT NULL,
+                entry_count INTEGER NOT NULL,
+                imported_at DATETIME NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create sanctions_lists table: {}", e))?;
 
-        // Get wallet ID first
-        let row = sqlx::query("SELECT id FROM wallets WHERE name = ?1")
-            .bind(name)
-            .fetch_optional(&self.pool)
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to find wallet: {}", e))?;
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS sanctioned_addresses (
+                source TEXT NOT NULL,
+                address TEXT NOT NULL,
+                currency TEXT,
+                name TEXT,
+                PRIMARY KEY (source, address)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create sanctioned_addresses table: {}", e))?;
+
+        // How far each SIEM export has got
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS audit_export_cursors (
+                name TEXT PRIMARY KEY,
+                last_log_id INTEGER NOT NULL,
+                last_event_at DATETIME,
+                updated_at DATETIME NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create audit_export_cursors table: {}", e))?;
+
+        // Webhook subscriptions and every delivery made to them
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS webhooks (
+                id TEXT PRIMARY KEY,
+                url TEXT NOT NULL,
+                secret TEXT NOT NULL,
+                events TEXT NOT NULL,
+                wallet TEXT,
+                created_at DATETIME NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create webhooks table: {}", e))?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS webhook_deliveries (
+                id TEXT PRIMARY KEY,
+                webhook_id TEXT NOT NULL,
+                event_type TEXT NOT NULL,
+                payload TEXT NOT NULL,
+                status TEXT NOT NULL,
+                attempts INTEGER NOT NULL,
+                last_error TEXT,
+                response_status INTEGER,
+                next_attempt_at DATETIME NOT NULL,
+                created_at DATETIME NOT NULL,
+                updated_at DATETIME NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create webhook_deliveries table: {}", e))?;
+
+        // Create indexes
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_wallets_name ON wallets (name)")
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_transactions_wallet_id ON transactions (wallet_id)",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_transactions_tx_hash ON transactions (tx_hash)",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_audit_logs_wallet_id ON audit_logs (wallet_id)",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_bridge_recovery_steps_tx ON bridge_recovery_steps (bridge_tx_id)",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_swaps_wallet_id ON swaps (wallet_id)")
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_staking_operations_wallet_id ON staking_operations (wallet_id)",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_api_keys_user_id ON api_keys (user_id)")
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_wallet_owners_user_id ON wallet_owners (user_id)",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_policy_spends_wallet_id ON policy_spends (wallet_id, created_at)",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_approval_requests_status ON approval_requests (status, created_at)",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        debug!("Database schema initialized");
+        Ok(())
+    }
+
+    pub async fn store_wallet(
+        &self,
+        name: &str,
+        encrypted_data: &[u8],
+        quantum_safe: bool,
+    ) -> Result<()> {
+        debug!("Storing wallet: {}", name);
+
+        let wallet_id = uuid::Uuid::new_v4().to_string();
+        let now = Utc::now().naive_utc();
+
+        sqlx::query(
+            r#"
+            INSERT INTO wallets (id, name, encrypted_data, quantum_safe, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+        )
+        .bind(&wallet_id)
+        .bind(name)
+        .bind(encrypted_data)
+        .bind(quantum_safe)
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store wallet: {}", e))?;
+
+        // Log the action
+        self.log_action(
+            &wallet_id,
+            "wallet_created",
+            &format!("Wallet '{}' created", name),
+            None,
+            None,
+        )
+        .await?;
+
+        debug!("Stored wallet: {}", name);
+        Ok(())
+    }
+
+    pub async fn load_wallet(&self, name: &str) -> Result<(Vec<u8>, bool)> {
+        debug!("Loading wallet: {}", name);
+
+        let row =
+            sqlx::query("SELECT id, encrypted_data, quantum_safe FROM wallets WHERE name = ?1")
+                .bind(name)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to load wallet: {}", e))?;
+
+        match row {
+            Some(row) => {
+                let wallet_id: String = row.get("id");
+                let encrypted_data: Vec<u8> = row.get("encrypted_data");
+                let quantum_safe: bool = row.get("quantum_safe");
+
+                // Log the action
+                self.log_action(
+                    &wallet_id,
+                    "wallet_accessed",
+                    &format!("Wallet '{}' accessed", name),
+                    None,
+                    None,
+                )
+                .await?;
+
+                debug!("Wallet loaded: {}", name);
+                Ok((encrypted_data, quantum_safe))
+            }
+            None => Err(anyhow::anyhow!("Wallet not found: {}", name)),
+        }
+    }
+
+    pub async fn list_wallets(&self) -> Result<Vec<WalletMetadata>> {
+        debug!("Listing all wallets");
+
+        let wallets = sqlx::query_as::<_, WalletMetadata>(
+                "SELECT id, name, quantum_safe, created_at, updated_at FROM wallets ORDER BY created_at DESC"
+            )
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list wallets: {}", e))?;
+
+        debug!("Listed {} wallets", wallets.len());
+        Ok(wallets)
+    }
+
+    pub async fn delete_wallet(&self, name: &str) -> Result<()> {
+        debug!("Deleting wallet: {}", name);
+
+        // Get wallet ID first
+        let row = sqlx::query("SELECT id FROM wallets WHERE name = ?1")
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to find wallet: {}", e))?;
 
         let wallet_id = match row {
             Some(row) => row.get::<String, _>("id"),
@@ -313,6 +776,19 @@ impl WalletStorage {
             return Err(anyhow::anyhow!("Wallet not found: {}", name));
         }
 
+        // A wallet later created under the same name must not inherit the owner
+        sqlx::query("DELETE FROM wallet_owners WHERE wallet_name = ?1")
+            .bind(name)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to delete wallet owner: {}", e))?;
+
+        sqlx::query("DELETE FROM spending_policies WHERE wallet_id = ?1")
+            .bind(&wallet_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to delete spending policy: {}", e))?;
+
         // Log the action
         self.log_action(
             &wallet_id,
@@ -372,221 +848,2446 @@ impl WalletStorage {
         Ok(transactions)
     }
 
-    pub async fn log_action(
-        &self,
-        wallet_id: &str,
-        action: &str,
-        details: &str,
-        ip_address: Option<&str>,
-        user_agent: Option<&str>,
-    ) -> Result<()> {
-        sqlx::query(
+    /// Transactions still waiting for confirmation, oldest first.
+    pub async fn get_pending_transactions(&self) -> Result<Vec<TransactionRecord>> {
+        let transactions = sqlx::query_as::<_, TransactionRecord>(
             r#"
-            INSERT INTO audit_logs (wallet_id, action, details, ip_address, user_agent, created_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            SELECT id, wallet_id, tx_hash, network, from_address, to_address, amount, fee, status, created_at, confirmed_at
+            FROM transactions
+            WHERE status = 'pending'
+            ORDER BY created_at ASC
             "#,
         )
-        .bind(wallet_id)
-        .bind(action)
-        .bind(details)
-        .bind(ip_address)
-        .bind(user_agent)
-        .bind(Utc::now().naive_utc())
-        .execute(&self.pool)
+        .fetch_all(&self.pool)
         .await
-        .map_err(|e| anyhow::anyhow!("Failed to log action: {}", e))?;
-
-        Ok(())
+        .map_err(|e| anyhow::anyhow!("Failed to get pending transactions: {}", e))?;
+        Ok(transactions)
     }
 
-    pub async fn get_audit_logs(&self, wallet_id: Option<&str>) -> Result<Vec<AuditLog>> {
-        let (query, params): (&str, Vec<&str>) = match wallet_id {
-            Some(id) => {
-                ("SELECT * FROM audit_logs WHERE wallet_id = ?1 ORDER BY created_at DESC", vec![id])
-            }
-            None => ("SELECT * FROM audit_logs ORDER BY created_at DESC", vec![]),
-        };
-
-        let mut query_builder = sqlx::query(query);
-        for param in params {
-            query_builder = query_builder.bind(param);
-        }
-
-        let logs = query_builder
-            .try_map(|row: sqlx::sqlite::SqliteRow| AuditLog::from_row(&row))
-            .fetch_all(&self.pool)
+    pub async fn update_transaction_status(
+        &self,
+        id: &str,
+        status: &str,
+        confirmed_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        sqlx::query("UPDATE transactions SET status = ?1, confirmed_at = ?2 WHERE id = ?3")
+            .bind(status)
+            .bind(confirmed_at)
+            .bind(id)
+            .execute(&self.pool)
             .await
-            .map_err(|e| anyhow::anyhow!("Failed to get audit logs: {}", e))?;
-
-        Ok(logs)
+            .map_err(|e| anyhow::anyhow!("Failed to update transaction status: {}", e))?;
+        Ok(())
     }
-}
 
-// Bridge Transaction Storage
-impl WalletStorage {
-    pub async fn store_bridge_transaction(&self, tx: &BridgeTransaction) -> Result<()> {
-        let status_str = serde_json::to_string(&tx.status)?;
+    pub async fn store_swap(&self, swap: &SwapRecord) -> Result<()> {
+        debug!("Storing swap: {}", swap.tx_hash);
+
         sqlx::query(
             r#"
-            INSERT INTO bridge_transactions (id, from_wallet, from_chain, to_chain, token, amount, status, source_tx_hash, destination_tx_hash, created_at, updated_at, fee_amount, estimated_completion_time)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+            INSERT INTO swaps (tx_hash, wallet_id, network, protocol, token_in, token_out, amount_in, expected_amount_out, min_amount_out, approval_tx_hash, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
             "#,
         )
-        .bind(&tx.id)
-        .bind(&tx.from_wallet)
-        .bind(&tx.from_chain)
-        .bind(&tx.to_chain)
-        .bind(&tx.token)
-        .bind(&tx.amount)
-        .bind(status_str)
-        .bind(&tx.source_tx_hash)
-        .bind(&tx.destination_tx_hash)
-        .bind(tx.created_at)
-        .bind(tx.updated_at)
-        .bind(&tx.fee_amount)
-        .bind(tx.estimated_completion_time)
+        .bind(&swap.tx_hash)
+        .bind(&swap.wallet_id)
+        .bind(&swap.network)
+        .bind(&swap.protocol)
+        .bind(&swap.token_in)
+        .bind(&swap.token_out)
+        .bind(&swap.amount_in)
+        .bind(&swap.expected_amount_out)
+        .bind(&swap.min_amount_out)
+        .bind(&swap.approval_tx_hash)
+        .bind(swap.created_at)
         .execute(&self.pool)
-        .await?;
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store swap: {}", e))?;
+        Ok(())
+    }
+
+    pub async fn get_wallet_swaps(&self, wallet_id: &str) -> Result<Vec<SwapRecord>> {
+        let swaps = sqlx::query_as::<_, SwapRecord>(
+            "SELECT * FROM swaps WHERE wallet_id = ?1 ORDER BY created_at DESC",
+        )
+        .bind(wallet_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to get swaps: {}", e))?;
+        Ok(swaps)
+    }
+
+    pub async fn store_staking_operation(&self, operation: &StakingOperation) -> Result<()> {
+        debug!("Storing staking operation: {}", operation.tx_hash);
+
+        sqlx::query(
+            r#"
+            INSERT INTO staking_operations (tx_hash, wallet_id, network, protocol, action, position_id, amount, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            "#,
+        )
+        .bind(&operation.tx_hash)
+        .bind(&operation.wallet_id)
+        .bind(&operation.network)
+        .bind(&operation.protocol)
+        .bind(&operation.action)
+        .bind(&operation.position_id)
+        .bind(&operation.amount)
+        .bind(operation.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store staking operation: {}", e))?;
+        Ok(())
+    }
+
+    pub async fn get_staking_operations(&self, wallet_id: &str) -> Result<Vec<StakingOperation>> {
+        let operations = sqlx::query_as::<_, StakingOperation>(
+            "SELECT * FROM staking_operations WHERE wallet_id = ?1 ORDER BY created_at ASC",
+        )
+        .bind(wallet_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to get staking operations: {}", e))?;
+        Ok(operations)
+    }
+
+    /// Appends an audit row for the wallet. The actor, request id and, unless
+    /// given, client address and user agent come from the current
+    /// `AuditContext`.
+    pub async fn log_action(
+        &self,
+        wallet_id: &str,
+        action: &str,
+        details: &str,
+        ip_address: Option<&str>,
+        user_agent: Option<&str>,
+    ) -> Result<()> {
+        let mut context = AuditContext::current();
+        if let Some(ip_address) = ip_address {
+            context.ip_address = Some(ip_address.to_string());
+        }
+        if let Some(user_agent) = user_agent {
+            context.user_agent = Some(user_agent.to_string());
+        }
+        self.append_audit_log(Some(wallet_id), action, details, &context).await?;
+        Ok(())
+    }
+
+    /// Appends an audit row, for a wallet or not, in the current `AuditContext`.
+    pub async fn log_event(
+        &self,
+        wallet_id: Option<&str>,
+        action: &str,
+        details: &str,
+    ) -> Result<()> {
+        self.append_audit_log(wallet_id, action, details, &AuditContext::current()).await?;
+        Ok(())
+    }
+
+    /// Appends a row chained to the latest one and returns its id. The write
+    /// lock is taken up front so concurrent appends cannot chain to the same row.
+    async fn append_audit_log(
+        &self,
+        wallet_id: Option<&str>,
+        action: &str,
+        details: &str,
+        context: &AuditContext,
+    ) -> Result<i64> {
+        let mut tx = self.pool.begin_with("BEGIN IMMEDIATE").await?;
+        let prev = sqlx::query("SELECT hash FROM audit_logs ORDER BY id DESC LIMIT 1")
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to load latest audit log: {}", e))?
+            .map(|row| row.get::<Option<String>, _>("hash"))
+            .map(|hash| decode_hash(hash.as_deref()))
+            .transpose()?
+            .unwrap_or(GENESIS_HASH);
+
+        let mut log = AuditLog {
+            id: 0,
+            wallet_id: wallet_id.map(str::to_string),
+            action: action.to_string(),
+            details: Some(details.to_string()),
+            ip_address: context.ip_address.clone(),
+            user_agent: context.user_agent.clone(),
+            actor: context.actor.clone(),
+            request_id: context.request_id.clone(),
+            prev_hash: Some(hex::encode(prev)),
+            hash: None,
+            // stored with microsecond precision, which is what the hash covers
+            created_at: Utc::now().trunc_subsecs(6),
+        };
+        log.id = sqlx::query(
+            r#"
+            INSERT INTO audit_logs (wallet_id, action, details, ip_address, user_agent, created_at, actor, request_id, prev_hash)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            "#,
+        )
+        .bind(&log.wallet_id)
+        .bind(&log.action)
+        .bind(&log.details)
+        .bind(&log.ip_address)
+        .bind(&log.user_agent)
+        .bind(log.created_at.naive_utc())
+        .bind(&log.actor)
+        .bind(&log.request_id)
+        .bind(&log.prev_hash)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to log action: {}", e))?
+        .last_insert_rowid();
+        sqlx::query("UPDATE audit_logs SET hash = ?1 WHERE id = ?2")
+            .bind(hex::encode(row_hash(&prev, &log)))
+            .bind(log.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to log action: {}", e))?;
+        tx.commit().await?;
+        Ok(log.id)
+    }
+
+    /// One-time migration hashing the rows written before the log was
+    /// chained, i.e. those older than the first hashed row. It runs once per
+    /// database; rows that lose their hash later are left for `verify_chain`
+    /// to report rather than rehashed.
+    async fn chain_unhashed_audit_logs(&self) -> Result<()> {
+        const MIGRATION: &str = "chain_audit_logs";
+
+        let mut tx = self.pool.begin_with("BEGIN IMMEDIATE").await?;
+        let applied = sqlx::query("SELECT 1 FROM schema_migrations WHERE name = ?1")
+            .bind(MIGRATION)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to load schema migrations: {}", e))?;
+        if applied.is_some() {
+            return Ok(());
+        }
+
+        let unhashed = sqlx::query_as::<_, AuditLog>(
+            r#"
+            SELECT * FROM audit_logs
+            WHERE NOT EXISTS (
+                SELECT 1 FROM audit_logs AS hashed
+                WHERE hashed.hash IS NOT NULL AND hashed.id <= audit_logs.id
+            )
+            ORDER BY id ASC
+            "#,
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to load audit logs: {}", e))?;
+        let mut prev = GENESIS_HASH;
+        for log in &unhashed {
+            let hash = row_hash(&prev, log);
+            sqlx::query("UPDATE audit_logs SET prev_hash = ?1, hash = ?2 WHERE id = ?3")
+                .bind(hex::encode(prev))
+                .bind(hex::encode(hash))
+                .bind(log.id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to chain audit log: {}", e))?;
+            prev = hash;
+        }
+        sqlx::query("INSERT INTO schema_migrations (name, applied_at) VALUES (?1, ?2)")
+            .bind(MIGRATION)
+            .bind(Utc::now().naive_utc())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to record schema migration: {}", e))?;
+        tx.commit().await?;
+        if !unhashed.is_empty() {
+            info!("Chained {} audit log rows", unhashed.len());
+        }
+        Ok(())
+    }
+
+    /// A page of audit rows matching `query`, newest first unless it asks
+    /// for the oldest.
+    pub async fn query_audit_logs(&self, query: &AuditQuery) -> Result<Vec<AuditLog>> {
+        let mut builder = QueryBuilder::<Sqlite>::new("SELECT * FROM audit_logs WHERE 1 = 1");
+        query.push_filters(&mut builder);
+        builder
+            .push(if query.oldest_first { " ORDER BY id ASC" } else { " ORDER BY id DESC" })
+            .push(" LIMIT ")
+            .push_bind(query.limit)
+            .push(" OFFSET ")
+            .push_bind(query.offset);
+        let logs = builder
+            .build_query_as::<AuditLog>()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to query audit logs: {}", e))?;
+        Ok(logs)
+    }
+
+    /// How many audit rows match `query`, ignoring its page.
+    pub async fn count_audit_logs(&self, query: &AuditQuery) -> Result<i64> {
+        let mut builder =
+            QueryBuilder::<Sqlite>::new("SELECT COUNT(*) AS count FROM audit_logs WHERE 1 = 1");
+        query.push_filters(&mut builder);
+        let row = builder
+            .build()
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to count audit logs: {}", e))?;
+        Ok(row.get::<i64, _>("count"))
+    }
+
+    pub async fn get_audit_logs(&self, wallet_id: Option<&str>) -> Result<Vec<AuditLog>> {
+        let (query, params): (&str, Vec<&str>) = match wallet_id {
+            Some(id) => {
+                ("SELECT * FROM audit_logs WHERE wallet_id = ?1 ORDER BY created_at DESC", vec![id])
+            }
+            None => ("SELECT * FROM audit_logs ORDER BY created_at DESC", vec![]),
+        };
+
+        let mut query_builder = sqlx::query(query);
+        for param in params {
+            query_builder = query_builder.bind(param);
+        }
+
+        let logs = query_builder
+            .try_map(|row: sqlx::sqlite::SqliteRow| AuditLog::from_row(&row))
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to get audit logs: {}", e))?;
+
+        Ok(logs)
+    }
+
+    /// Returns audit log rows with `id <= last_id` in insertion order.
+    pub async fn get_audit_logs_through(&self, last_id: i64) -> Result<Vec<AuditLog>> {
+        let logs = sqlx::query_as::<_, AuditLog>(
+            "SELECT * FROM audit_logs WHERE id <= ?1 ORDER BY id ASC",
+        )
+        .bind(last_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to get audit logs: {}", e))?;
+
+        Ok(logs)
+    }
+
+    pub async fn get_latest_audit_log_id(&self) -> Result<Option<i64>> {
+        let row = sqlx::query("SELECT MAX(id) AS max_id FROM audit_logs")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to query audit logs: {}", e))?;
+
+        Ok(row.get::<Option<i64>, _>("max_id"))
+    }
+}
+
+// Signing key and audit signature storage
+impl WalletStorage {
+    pub async fn store_signing_key(&self, key: &SigningKeyRecord) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO signing_keys (purpose, key_id, algorithm, public_key, encrypted_secret_key, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+        )
+        .bind(&key.purpose)
+        .bind(&key.key_id)
+        .bind(&key.algorithm)
+        .bind(&key.public_key)
+        .bind(&key.encrypted_secret_key)
+        .bind(key.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store signing key: {}", e))?;
+
+        info!("Stored {} signing key {} for '{}'", key.algorithm, key.key_id, key.purpose);
+        Ok(())
+    }
+
+    pub async fn load_signing_key(&self, purpose: &str) -> Result<Option<SigningKeyRecord>> {
+        let key = sqlx::query_as::<_, SigningKeyRecord>(
+            "SELECT purpose, key_id, algorithm, public_key, encrypted_secret_key, created_at FROM signing_keys WHERE purpose = ?1",
+        )
+        .bind(purpose)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to load signing key: {}", e))?;
+
+        Ok(key)
+    }
+
+    pub async fn store_audit_signature(&self, signature: &AuditSignature) -> Result<i64> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO audit_signatures (last_log_id, chain_digest, signature, created_at)
+            VALUES (?1, ?2, ?3, ?4)
+            "#,
+        )
+        .bind(signature.last_log_id)
+        .bind(&signature.chain_digest)
+        .bind(&signature.signature)
+        .bind(signature.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store audit signature: {}", e))?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    /// Every checkpoint, oldest first.
+    pub async fn get_audit_signatures(&self) -> Result<Vec<AuditSignature>> {
+        let signatures =
+            sqlx::query_as::<_, AuditSignature>("SELECT * FROM audit_signatures ORDER BY id ASC")
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to load audit signatures: {}", e))?;
+
+        Ok(signatures)
+    }
+
+    pub async fn get_latest_audit_signature(&self) -> Result<Option<AuditSignature>> {
+        let signature = sqlx::query_as::<_, AuditSignature>(
+            "SELECT * FROM audit_signatures ORDER BY id DESC LIMIT 1",
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to load audit signature: {}", e))?;
+
+        Ok(signature)
+    }
+}
+
+// User and credential storage
+impl WalletStorage {
+    pub async fn store_user(&self, user: &UserRecord) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO users (id, username, password_hash, scopes, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+        )
+        .bind(&user.id)
+        .bind(&user.username)
+        .bind(&user.password_hash)
+        .bind(&user.scopes)
+        .bind(user.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store user: {}", e))?;
+
+        info!("Stored user {}", user.username);
+        Ok(())
+    }
+
+    pub async fn get_user(&self, id: &str) -> Result<Option<UserRecord>> {
+        let user = sqlx::query_as::<_, UserRecord>("SELECT * FROM users WHERE id = ?1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to load user: {}", e))?;
+        Ok(user)
+    }
+
+    pub async fn get_user_by_username(&self, username: &str) -> Result<Option<UserRecord>> {
+        let user = sqlx::query_as::<_, UserRecord>("SELECT * FROM users WHERE username = ?1")
+            .bind(username)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to load user: {}", e))?;
+        Ok(user)
+    }
+
+    pub async fn count_users(&self) -> Result<i64> {
+        let row = sqlx::query("SELECT COUNT(*) AS count FROM users")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to count users: {}", e))?;
+        Ok(row.get::<i64, _>("count"))
+    }
+
+    pub async fn store_api_key(&self, key: &ApiKeyRecord) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO api_keys (id, user_id, name, key_hash, scopes, expires_at, revoked_at, last_used_at, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            "#,
+        )
+        .bind(&key.id)
+        .bind(&key.user_id)
+        .bind(&key.name)
+        .bind(&key.key_hash)
+        .bind(&key.scopes)
+        .bind(key.expires_at)
+        .bind(key.revoked_at)
+        .bind(key.last_used_at)
+        .bind(key.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store API key: {}", e))?;
+        Ok(())
+    }
+
+    pub async fn get_api_key(&self, id: &str) -> Result<Option<ApiKeyRecord>> {
+        let key = sqlx::query_as::<_, ApiKeyRecord>("SELECT * FROM api_keys WHERE id = ?1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to load API key: {}", e))?;
+        Ok(key)
+    }
+
+    pub async fn list_api_keys(&self, user_id: &str) -> Result<Vec<ApiKeyRecord>> {
+        let keys = sqlx::query_as::<_, ApiKeyRecord>(
+            "SELECT * FROM api_keys WHERE user_id = ?1 ORDER BY created_at ASC",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to list API keys: {}", e))?;
+        Ok(keys)
+    }
+
+    /// Marks an API key revoked; `false` when it was unknown or already revoked.
+    pub async fn revoke_api_key(&self, id: &str, revoked_at: DateTime<Utc>) -> Result<bool> {
+        let result =
+            sqlx::query("UPDATE api_keys SET revoked_at = ?1 WHERE id = ?2 AND revoked_at IS NULL")
+                .bind(revoked_at)
+                .bind(id)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to revoke API key: {}", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn touch_api_key(&self, id: &str, used_at: DateTime<Utc>) -> Result<()> {
+        sqlx::query("UPDATE api_keys SET last_used_at = ?1 WHERE id = ?2")
+            .bind(used_at)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to update API key: {}", e))?;
+        Ok(())
+    }
+
+    pub async fn store_refresh_token(&self, token: &RefreshTokenRecord) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO refresh_tokens (id, user_id, expires_at, revoked_at, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+        )
+        .bind(&token.id)
+        .bind(&token.user_id)
+        .bind(token.expires_at)
+        .bind(token.revoked_at)
+        .bind(token.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store refresh token: {}", e))?;
+        Ok(())
+    }
+
+    pub async fn get_refresh_token(&self, id: &str) -> Result<Option<RefreshTokenRecord>> {
+        let token =
+            sqlx::query_as::<_, RefreshTokenRecord>("SELECT * FROM refresh_tokens WHERE id = ?1")
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to load refresh token: {}", e))?;
+        Ok(token)
+    }
+
+    /// Marks a refresh token revoked; `false` when it was unknown or already
+    /// revoked, which lets refresh rotation detect reuse.
+    pub async fn revoke_refresh_token(&self, id: &str, revoked_at: DateTime<Utc>) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE refresh_tokens SET revoked_at = ?1 WHERE id = ?2 AND revoked_at IS NULL",
+        )
+        .bind(revoked_at)
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to revoke refresh token: {}", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+// Second factor and login failure storage
+impl WalletStorage {
+    /// Stores a (re-)enrollment, replacing any previous credential of the user.
+    pub async fn store_totp_credential(&self, credential: &TotpCredentialRecord) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO totp_credentials (user_id, secret, confirmed_at, last_used_step, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+        )
+        .bind(&credential.user_id)
+        .bind(&credential.secret)
+        .bind(credential.confirmed_at)
+        .bind(credential.last_used_step)
+        .bind(credential.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store TOTP credential: {}", e))?;
+        Ok(())
+    }
+
+    pub async fn get_totp_credential(&self, user_id: &str) -> Result<Option<TotpCredentialRecord>> {
+        let credential = sqlx::query_as::<_, TotpCredentialRecord>(
+            "SELECT * FROM totp_credentials WHERE user_id = ?1",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to load TOTP credential: {}", e))?;
+        Ok(credential)
+    }
+
+    pub async fn confirm_totp_credential(
+        &self,
+        user_id: &str,
+        confirmed_at: DateTime<Utc>,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE totp_credentials SET confirmed_at = ?1 WHERE user_id = ?2 AND confirmed_at IS NULL",
+        )
+        .bind(confirmed_at)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to confirm TOTP credential: {}", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Removes the user's TOTP credential and recovery codes.
+    pub async fn delete_totp_credential(&self, user_id: &str) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM totp_credentials WHERE user_id = ?1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to delete TOTP credential: {}", e))?;
+        sqlx::query("DELETE FROM recovery_codes WHERE user_id = ?1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to delete recovery codes: {}", e))?;
+        tx.commit().await?;
         Ok(())
     }
 
-    pub async fn get_bridge_transaction(&self, id: &str) -> Result<BridgeTransaction> {
-        let row = sqlx::query("SELECT * FROM bridge_transactions WHERE id = ?1")
-            .bind(id)
-            .fetch_one(&self.pool)
-            .await?;
+    /// Records `step` as used; `false` when it is not later than the last
+    /// used step, i.e. the code is being replayed.
+    pub async fn use_totp_step(&self, user_id: &str, step: i64) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE totp_credentials SET last_used_step = ?1
+            WHERE user_id = ?2 AND (last_used_step IS NULL OR last_used_step < ?1)
+            "#,
+        )
+        .bind(step)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to record TOTP use: {}", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Replaces the user's recovery codes with the given hashes.
+    pub async fn store_recovery_codes(&self, user_id: &str, code_hashes: &[String]) -> Result<()> {
+        let now = Utc::now();
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM recovery_codes WHERE user_id = ?1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to delete recovery codes: {}", e))?;
+        for hash in code_hashes {
+            sqlx::query(
+                "INSERT INTO recovery_codes (user_id, code_hash, used_at, created_at) VALUES (?1, ?2, NULL, ?3)",
+            )
+            .bind(user_id)
+            .bind(hash)
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to store recovery code: {}", e))?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Marks an unused recovery code used; `false` when there is none.
+    pub async fn use_recovery_code(
+        &self,
+        user_id: &str,
+        code_hash: &str,
+        used_at: DateTime<Utc>,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE recovery_codes SET used_at = ?1 WHERE user_id = ?2 AND code_hash = ?3 AND used_at IS NULL",
+        )
+        .bind(used_at)
+        .bind(user_id)
+        .bind(code_hash)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to use recovery code: {}", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn count_unused_recovery_codes(&self, user_id: &str) -> Result<i64> {
+        let row = sqlx::query(
+            "SELECT COUNT(*) AS count FROM recovery_codes WHERE user_id = ?1 AND used_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to count recovery codes: {}", e))?;
+        Ok(row.get::<i64, _>("count"))
+    }
+
+    /// Counts a failed attempt and returns the failures since the last
+    /// success or lockout.
+    pub async fn increment_login_failures(&self, user_id: &str) -> Result<i64> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO login_failures (user_id, failures, locked_until) VALUES (?1, 1, NULL)
+            ON CONFLICT(user_id) DO UPDATE SET failures = failures + 1
+            RETURNING failures
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to record login failure: {}", e))?;
+        Ok(row.get::<i64, _>("failures"))
+    }
+
+    /// Locks the user until `until` and restarts the failure count.
+    pub async fn lock_user(&self, user_id: &str, until: DateTime<Utc>) -> Result<()> {
+        sqlx::query("UPDATE login_failures SET failures = 0, locked_until = ?1 WHERE user_id = ?2")
+            .bind(until)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to lock user: {}", e))?;
+        Ok(())
+    }
+
+    pub async fn get_locked_until(&self, user_id: &str) -> Result<Option<DateTime<Utc>>> {
+        let row = sqlx::query("SELECT locked_until FROM login_failures WHERE user_id = ?1")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to load lockout: {}", e))?;
+        Ok(row.and_then(|row| row.get::<Option<DateTime<Utc>>, _>("locked_until")))
+    }
+
+    pub async fn clear_login_failures(&self, user_id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM login_failures WHERE user_id = ?1")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to clear login failures: {}", e))?;
+        Ok(())
+    }
+}
+
+// Spending policy storage
+impl WalletStorage {
+    /// Stores the wallet's policy (JSON), replacing any previous one.
+    pub async fn set_spending_policy(&self, wallet_id: &str, policy: &str) -> Result<()> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO spending_policies (wallet_id, policy, updated_at) VALUES (?1, ?2, ?3)",
+        )
+        .bind(wallet_id)
+        .bind(policy)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store spending policy: {}", e))?;
+        Ok(())
+    }
+
+    pub async fn get_spending_policy(&self, wallet_id: &str) -> Result<Option<String>> {
+        let row = sqlx::query("SELECT policy FROM spending_policies WHERE wallet_id = ?1")
+            .bind(wallet_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to load spending policy: {}", e))?;
+        Ok(row.map(|row| row.get::<String, _>("policy")))
+    }
+
+    /// Removes the wallet's policy; `false` when it had none.
+    pub async fn delete_spending_policy(&self, wallet_id: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM spending_policies WHERE wallet_id = ?1")
+            .bind(wallet_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to delete spending policy: {}", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn record_policy_spend(&self, spend: &PolicySpendRecord) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO policy_spends (wallet_id, tx_hash, transaction_type, network, asset, recipient, amount, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            "#,
+        )
+        .bind(&spend.wallet_id)
+        .bind(&spend.tx_hash)
+        .bind(&spend.transaction_type)
+        .bind(&spend.network)
+        .bind(&spend.asset)
+        .bind(&spend.recipient)
+        .bind(spend.amount)
+        .bind(spend.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to record policy spend: {}", e))?;
+        Ok(())
+    }
+
+    /// Reserves `spend` against the wallet's velocity limits. In one
+    /// transaction the wallet's spends after `since`, reserved ones included,
+    /// are passed to `admit`, and `spend` is inserted without its transaction
+    /// hash only if that returns true, so concurrent checks cannot both pass
+    /// on the same headroom. Returns the reservation's id.
+    pub async fn reserve_policy_spend(
+        &self,
+        spend: &PolicySpendRecord,
+        since: DateTime<Utc>,
+        admit: &mut (dyn FnMut(&[PolicySpendRecord]) -> bool + Send),
+    ) -> Result<Option<i64>> {
+        let mut tx = self.pool.begin_with("BEGIN IMMEDIATE").await?;
+        let recent = sqlx::query_as::<_, PolicySpendRecord>(
+            "SELECT * FROM policy_spends WHERE wallet_id = ?1 AND created_at > ?2 ORDER BY created_at ASC",
+        )
+        .bind(&spend.wallet_id)
+        .bind(since)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to load policy spends: {}", e))?;
+        if !admit(&recent) {
+            return Ok(None);
+        }
+
+        let id = sqlx::query(
+            r#"
+            INSERT INTO policy_spends (wallet_id, tx_hash, transaction_type, network, asset, recipient, amount, created_at)
+            VALUES (?1, '', ?2, ?3, ?4, ?5, ?6, ?7)
+            "#,
+        )
+        .bind(&spend.wallet_id)
+        .bind(&spend.transaction_type)
+        .bind(&spend.network)
+        .bind(&spend.asset)
+        .bind(&spend.recipient)
+        .bind(spend.amount)
+        .bind(spend.created_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to reserve policy spend: {}", e))?
+        .last_insert_rowid();
+        tx.commit().await?;
+        Ok(Some(id))
+    }
+
+    /// Records the transaction hash of a reserved spend that was sent.
+    pub async fn confirm_policy_spend(&self, id: i64, tx_hash: &str) -> Result<()> {
+        sqlx::query("UPDATE policy_spends SET tx_hash = ?1 WHERE id = ?2")
+            .bind(tx_hash)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to confirm policy spend: {}", e))?;
+        Ok(())
+    }
+
+    /// Drops a reserved spend that was not sent.
+    pub async fn release_policy_spend(&self, id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM policy_spends WHERE id = ?1 AND tx_hash = ''")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to release policy spend: {}", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The wallet's spends after `since`, oldest first.
+    pub async fn get_policy_spends_since(
+        &self,
+        wallet_id: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<PolicySpendRecord>> {
+        let spends = sqlx::query_as::<_, PolicySpendRecord>(
+            "SELECT * FROM policy_spends WHERE wallet_id = ?1 AND created_at > ?2 ORDER BY created_at ASC",
+        )
+        .bind(wallet_id)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to load policy spends: {}", e))?;
+        Ok(spends)
+    }
+
+    /// Records `address` as seen by the wallet at `seen_at` unless it was
+    /// seen before, and returns when it was first seen.
+    pub async fn note_recipient(
+        &self,
+        wallet_id: &str,
+        address: &str,
+        seen_at: DateTime<Utc>,
+    ) -> Result<DateTime<Utc>> {
+        sqlx::query(
+            "INSERT OR IGNORE INTO known_recipients (wallet_id, address, first_seen) VALUES (?1, ?2, ?3)",
+        )
+        .bind(wallet_id)
+        .bind(address)
+        .bind(seen_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to record recipient: {}", e))?;
+        let row = sqlx::query(
+            "SELECT first_seen FROM known_recipients WHERE wallet_id = ?1 AND address = ?2",
+        )
+        .bind(wallet_id)
+        .bind(address)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to load recipient: {}", e))?;
+        Ok(row.get::<DateTime<Utc>, _>("first_seen"))
+    }
+}
+
+// Sanctions list storage
+impl WalletStorage {
+    /// Replaces the stored version of `list.source` with `list`.
+    pub async fn replace_sanctions_list(&self, list: &SanctionsList) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM sanctioned_addresses WHERE source = ?1")
+            .bind(&list.source)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to delete sanctioned addresses: {}", e))?;
+        for entry in &list.entries {
+            sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO sanctioned_addresses (source, address, currency, name)
+                VALUES (?1, ?2, ?3, ?4)
+                "#,
+            )
+            .bind(&list.source)
+            .bind(&entry.address)
+            .bind(&entry.currency)
+            .bind(&entry.name)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to store sanctioned address: {}", e))?;
+        }
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO sanctions_lists (source, format, version, entry_count, imported_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+        )
+        .bind(&list.source)
+        .bind(list.format.as_str())
+        .bind(&list.version)
+        .bind(list.entries.len() as i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store sanctions list: {}", e))?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn get_sanctions_lists(&self) -> Result<Vec<SanctionsListRecord>> {
+        let lists = sqlx::query_as::<_, SanctionsListRecord>(
+            "SELECT * FROM sanctions_lists ORDER BY source",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to load sanctions lists: {}", e))?;
+        Ok(lists)
+    }
+
+    /// Every address on any stored list.
+    pub async fn get_sanctioned_addresses(&self) -> Result<Vec<String>> {
+        let rows = sqlx::query("SELECT DISTINCT address FROM sanctioned_addresses")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to load sanctioned addresses: {}", e))?;
+        Ok(rows.iter().map(|row| row.get::<String, _>("address")).collect())
+    }
+}
+
+// Audit export cursor storage
+impl WalletStorage {
+    pub async fn get_export_cursor(&self, name: &str) -> Result<Option<AuditExportCursor>> {
+        let cursor = sqlx::query_as::<_, AuditExportCursor>(
+            "SELECT * FROM audit_export_cursors WHERE name = ?1",
+        )
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to get export cursor: {}", e))?;
+        Ok(cursor)
+    }
+
+    pub async fn set_export_cursor(&self, cursor: &AuditExportCursor) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO audit_export_cursors (name, last_log_id, last_event_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4)
+            "#,
+        )
+        .bind(&cursor.name)
+        .bind(cursor.last_log_id)
+        .bind(cursor.last_event_at)
+        .bind(cursor.updated_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store export cursor: {}", e))?;
+        Ok(())
+    }
+}
+
+// Webhook storage
+impl WalletStorage {
+    pub async fn store_webhook(&self, webhook: &WebhookRecord) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO webhooks (id, url, secret, events, wallet, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+        )
+        .bind(&webhook.id)
+        .bind(&webhook.url)
+        .bind(&webhook.secret)
+        .bind(&webhook.events)
+        .bind(&webhook.wallet)
+        .bind(webhook.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store webhook: {}", e))?;
+        Ok(())
+    }
+
+    pub async fn get_webhook(&self, id: &str) -> Result<Option<WebhookRecord>> {
+        let webhook = sqlx::query_as::<_, WebhookRecord>("SELECT * FROM webhooks WHERE id = ?1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to get webhook: {}", e))?;
+        Ok(webhook)
+    }
+
+    pub async fn list_webhooks(&self) -> Result<Vec<WebhookRecord>> {
+        let webhooks =
+            sqlx::query_as::<_, WebhookRecord>("SELECT * FROM webhooks ORDER BY created_at ASC")
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to list webhooks: {}", e))?;
+        Ok(webhooks)
+    }
+
+    /// Deletes a webhook and its deliveries; `false` when it did not exist.
+    pub async fn delete_webhook(&self, id: &str) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM webhook_deliveries WHERE webhook_id = ?1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to delete webhook deliveries: {}", e))?;
+        let deleted = sqlx::query("DELETE FROM webhooks WHERE id = ?1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to delete webhook: {}", e))?;
+        tx.commit().await?;
+        Ok(deleted.rows_affected() > 0)
+    }
+
+    pub async fn store_webhook_delivery(&self, delivery: &WebhookDelivery) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO webhook_deliveries (id, webhook_id, event_type, payload, status, attempts, last_error, response_status, next_attempt_at, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+            "#,
+        )
+        .bind(&delivery.id)
+        .bind(&delivery.webhook_id)
+        .bind(&delivery.event_type)
+        .bind(&delivery.payload)
+        .bind(&delivery.status)
+        .bind(delivery.attempts)
+        .bind(&delivery.last_error)
+        .bind(delivery.response_status)
+        .bind(delivery.next_attempt_at)
+        .bind(delivery.created_at)
+        .bind(delivery.updated_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store webhook delivery: {}", e))?;
+        Ok(())
+    }
+
+    /// Saves the outcome of an attempt: status, attempt count, error and
+    /// when to try next.
+    pub async fn update_webhook_delivery(&self, delivery: &WebhookDelivery) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = ?1, attempts = ?2, last_error = ?3, response_status = ?4, next_attempt_at = ?5, updated_at = ?6
+            WHERE id = ?7
+            "#,
+        )
+        .bind(&delivery.status)
+        .bind(delivery.attempts)
+        .bind(&delivery.last_error)
+        .bind(delivery.response_status)
+        .bind(delivery.next_attempt_at)
+        .bind(delivery.updated_at)
+        .bind(&delivery.id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to update webhook delivery: {}", e))?;
+        Ok(())
+    }
+
+    pub async fn get_webhook_delivery(&self, id: &str) -> Result<Option<WebhookDelivery>> {
+        let delivery =
+            sqlx::query_as::<_, WebhookDelivery>("SELECT * FROM webhook_deliveries WHERE id = ?1")
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to get webhook delivery: {}", e))?;
+        Ok(delivery)
+    }
+
+    /// A webhook's deliveries, optionally only those in `status`, newest first.
+    pub async fn get_webhook_deliveries(
+        &self,
+        webhook_id: &str,
+        status: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>> {
+        let deliveries = sqlx::query_as::<_, WebhookDelivery>(
+            r#"
+            SELECT * FROM webhook_deliveries
+            WHERE webhook_id = ?1 AND (?2 IS NULL OR status = ?2)
+            ORDER BY created_at DESC, rowid DESC
+            LIMIT ?3
+            "#,
+        )
+        .bind(webhook_id)
+        .bind(status)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to get webhook deliveries: {}", e))?;
+        Ok(deliveries)
+    }
+
+    /// Pending deliveries whose next attempt is due by `now`, oldest first.
+    pub async fn get_due_webhook_deliveries(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>> {
+        let deliveries = sqlx::query_as::<_, WebhookDelivery>(
+            r#"
+            SELECT * FROM webhook_deliveries
+            WHERE status = 'pending' AND next_attempt_at <= ?1
+            ORDER BY next_attempt_at ASC, rowid ASC
+            LIMIT ?2
+            "#,
+        )
+        .bind(now)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to get due webhook deliveries: {}", e))?;
+        Ok(deliveries)
+    }
+}
+
+// Approval queue storage
+impl WalletStorage {
+    /// Stores a new request; its votes are added with `add_approval_vote`.
+    pub async fn store_approval_request(&self, request: &ApprovalRequest) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO approval_requests (id, wallet_id, wallet_name, operation, reason, requested_by, status, required_approvals, result, created_at, expires_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+            "#,
+        )
+        .bind(&request.id)
+        .bind(&request.wallet_id)
+        .bind(&request.wallet_name)
+        .bind(serde_json::to_string(&request.operation)?)
+        .bind(&request.reason)
+        .bind(&request.requested_by)
+        .bind(request.status.as_str())
+        .bind(request.required_approvals as i64)
+        .bind(&request.result)
+        .bind(request.created_at)
+        .bind(request.expires_at)
+        .bind(request.updated_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store approval request: {}", e))?;
+        Ok(())
+    }
+
+    pub async fn get_approval_request(&self, id: &str) -> Result<Option<ApprovalRequest>> {
+        let row = sqlx::query("SELECT * FROM approval_requests WHERE id = ?1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to load approval request: {}", e))?;
+        match row {
+            Some(row) => Ok(Some(self.approval_request_from_row(&row).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Requests in `status`, or all of them, newest first.
+    pub async fn list_approval_requests(
+        &self,
+        status: Option<ApprovalStatus>,
+    ) -> Result<Vec<ApprovalRequest>> {
+        let rows =
+            match status {
+                Some(status) => sqlx::query(
+                    "SELECT * FROM approval_requests WHERE status = ?1 ORDER BY created_at DESC",
+                )
+                .bind(status.as_str())
+                .fetch_all(&self.pool)
+                .await,
+                None => {
+                    sqlx::query("SELECT * FROM approval_requests ORDER BY created_at DESC")
+                        .fetch_all(&self.pool)
+                        .await
+                }
+            }
+            .map_err(|e| anyhow::anyhow!("Failed to list approval requests: {}", e))?;
+        let mut requests = Vec::with_capacity(rows.len());
+        for row in &rows {
+            requests.push(self.approval_request_from_row(row).await?);
+        }
+        Ok(requests)
+    }
+
+    /// Records an approver's vote; `false` when they already voted.
+    pub async fn add_approval_vote(&self, request_id: &str, vote: &ApprovalVote) -> Result<bool> {
+        let result = sqlx::query(
+            "INSERT OR IGNORE INTO approval_votes (request_id, approver, approved, comment, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+        )
+        .bind(request_id)
+        .bind(&vote.approver)
+        .bind(vote.approved)
+        .bind(&vote.comment)
+        .bind(vote.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to record approval vote: {}", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Moves a request from `from` to `to`; `false` when it was no longer
+    /// in `from`, so only one caller wins a transition.
+    pub async fn update_approval_status(
+        &self,
+        id: &str,
+        from: ApprovalStatus,
+        to: ApprovalStatus,
+        result: Option<&str>,
+    ) -> Result<bool> {
+        let updated = sqlx::query(
+            "UPDATE approval_requests SET status = ?1, result = COALESCE(?2, result), updated_at = ?3 WHERE id = ?4 AND status = ?5",
+        )
+        .bind(to.as_str())
+        .bind(result)
+        .bind(Utc::now())
+        .bind(id)
+        .bind(from.as_str())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to update approval request: {}", e))?;
+        Ok(updated.rows_affected() > 0)
+    }
+
+    async fn approval_request_from_row(&self, row: &SqliteRow) -> Result<ApprovalRequest> {
+        let id: String = row.get("id");
+        let votes = sqlx::query(
+            "SELECT * FROM approval_votes WHERE request_id = ?1 ORDER BY created_at ASC",
+        )
+        .bind(&id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to load approval votes: {}", e))?
+        .iter()
+        .map(|vote| ApprovalVote {
+            approver: vote.get("approver"),
+            approved: vote.get("approved"),
+            comment: vote.get("comment"),
+            created_at: vote.get("created_at"),
+        })
+        .collect();
+        let operation: String = row.get("operation");
+        let status: String = row.get("status");
+
+        Ok(ApprovalRequest {
+            id,
+            wallet_id: row.get("wallet_id"),
+            wallet_name: row.get("wallet_name"),
+            operation: serde_json::from_str(&operation)?,
+            reason: row.get("reason"),
+            requested_by: row.get("requested_by"),
+            status: status.parse()?,
+            required_approvals: row.get::<i64, _>("required_approvals") as u32,
+            votes,
+            result: row.get("result"),
+            created_at: row.get("created_at"),
+            expires_at: row.get("expires_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+}
+
+// Role and wallet ownership storage
+impl WalletStorage {
+    /// Grants a role; `false` when the user already had it.
+    pub async fn add_user_role(&self, user_id: &str, role: &str) -> Result<bool> {
+        let result = sqlx::query(
+            "INSERT OR IGNORE INTO user_roles (user_id, role, created_at) VALUES (?1, ?2, ?3)",
+        )
+        .bind(user_id)
+        .bind(role)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to add user role: {}", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Removes a role; `false` when the user did not have it.
+    pub async fn remove_user_role(&self, user_id: &str, role: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM user_roles WHERE user_id = ?1 AND role = ?2")
+            .bind(user_id)
+            .bind(role)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to remove user role: {}", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn get_user_roles(&self, user_id: &str) -> Result<Vec<String>> {
+        let rows = sqlx::query("SELECT role FROM user_roles WHERE user_id = ?1 ORDER BY role ASC")
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to load user roles: {}", e))?;
+        Ok(rows.iter().map(|row| row.get::<String, _>("role")).collect())
+    }
+
+    pub async fn set_wallet_owner(&self, wallet_name: &str, user_id: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO wallet_owners (wallet_name, user_id, created_at)
+            VALUES (?1, ?2, ?3)
+            "#,
+        )
+        .bind(wallet_name)
+        .bind(user_id)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store wallet owner: {}", e))?;
+        Ok(())
+    }
+
+    pub async fn get_wallet_owner(&self, wallet_name: &str) -> Result<Option<String>> {
+        let row = sqlx::query("SELECT user_id FROM wallet_owners WHERE wallet_name = ?1")
+            .bind(wallet_name)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to load wallet owner: {}", e))?;
+        Ok(row.map(|row| row.get::<String, _>("user_id")))
+    }
+
+    pub async fn list_owned_wallets(&self, user_id: &str) -> Result<Vec<String>> {
+        let rows = sqlx::query(
+            "SELECT wallet_name FROM wallet_owners WHERE user_id = ?1 ORDER BY wallet_name ASC",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to list owned wallets: {}", e))?;
+        Ok(rows.iter().map(|row| row.get::<String, _>("wallet_name")).collect())
+    }
+}
+
+// Bridge Transaction Storage
+impl WalletStorage {
+    pub async fn store_bridge_transaction(&self, tx: &BridgeTransaction) -> Result<()> {
+        let status_str = serde_json::to_string(&tx.status)?;
+        sqlx::query(
+            r#"
+            INSERT INTO bridge_transactions (id, from_wallet, from_chain, to_chain, token, amount, status, source_tx_hash, destination_tx_hash, created_at, updated_at, fee_amount, estimated_completion_time)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+            "#,
+        )
+        .bind(&tx.id)
+        .bind(&tx.from_wallet)
+        .bind(&tx.from_chain)
+        .bind(&tx.to_chain)
+        .bind(&tx.token)
+        .bind(&tx.amount)
+        .bind(status_str)
+        .bind(&tx.source_tx_hash)
+        .bind(&tx.destination_tx_hash)
+        .bind(tx.created_at)
+        .bind(tx.updated_at)
+        .bind(&tx.fee_amount)
+        .bind(tx.estimated_completion_time)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_bridge_transaction(&self, id: &str) -> Result<BridgeTransaction> {
+        let row = sqlx::query("SELECT * FROM bridge_transactions WHERE id = ?1")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await?;
+        bridge_transaction_from_row(&row)
+    }
+
+    /// Transfers that are neither completed nor failed, oldest first.
+    pub async fn get_pending_bridge_transactions(&self) -> Result<Vec<BridgeTransaction>> {
+        let initiated = serde_json::to_string(&BridgeTransactionStatus::Initiated)?;
+        let in_transit = serde_json::to_string(&BridgeTransactionStatus::InTransit)?;
+        let rows = sqlx::query(
+            "SELECT * FROM bridge_transactions WHERE status IN (?1, ?2) ORDER BY created_at ASC",
+        )
+        .bind(initiated)
+        .bind(in_transit)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter().map(bridge_transaction_from_row).collect()
+    }
+
+    /// Transfers recovery should look at: failed ones, refunds in progress, and
+    /// pending ones whose estimated completion time is before `stuck_before`.
+    pub async fn get_bridge_transactions_for_recovery(
+        &self,
+        stuck_before: DateTime<Utc>,
+    ) -> Result<Vec<BridgeTransaction>> {
+        let initiated = serde_json::to_string(&BridgeTransactionStatus::Initiated)?;
+        let in_transit = serde_json::to_string(&BridgeTransactionStatus::InTransit)?;
+        let refund_pending = serde_json::to_string(&BridgeTransactionStatus::RefundPending)?;
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM bridge_transactions
+            WHERE status IN (?1, ?2, ?3) OR status LIKE '{"Failed":%'
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(&initiated)
+        .bind(&in_transit)
+        .bind(refund_pending)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let txs = rows.iter().map(bridge_transaction_from_row).collect::<Result<Vec<_>>>()?;
+        Ok(txs
+            .into_iter()
+            .filter(|tx| match tx.status {
+                BridgeTransactionStatus::Initiated | BridgeTransactionStatus::InTransit => {
+                    tx.estimated_completion_time.is_some_and(|eta| eta < stuck_before)
+                }
+                _ => true,
+            })
+            .collect())
+    }
+
+    pub async fn store_bridge_recovery_step(
+        &self,
+        bridge_tx_id: &str,
+        action: &str,
+        tx_hash: Option<&str>,
+        details: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO bridge_recovery_steps (bridge_tx_id, action, tx_hash, details, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+        )
+        .bind(bridge_tx_id)
+        .bind(action)
+        .bind(tx_hash)
+        .bind(details)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store bridge recovery step: {}", e))?;
+        Ok(())
+    }
+
+    /// Recovery steps taken for a transfer, oldest first.
+    pub async fn get_bridge_recovery_steps(
+        &self,
+        bridge_tx_id: &str,
+    ) -> Result<Vec<BridgeRecoveryStep>> {
+        let steps = sqlx::query_as::<_, BridgeRecoveryStep>(
+            "SELECT * FROM bridge_recovery_steps WHERE bridge_tx_id = ?1 ORDER BY id ASC",
+        )
+        .bind(bridge_tx_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to load bridge recovery steps: {}", e))?;
+        Ok(steps)
+    }
+
+    /// Moves the transfer from `expected` to `status`, keeping any hash that is
+    /// not given. Returns `false` without writing when the stored status is no
+    /// longer `expected`, i.e. someone else advanced the row first.
+    pub async fn update_bridge_transaction_status(
+        &self,
+        id: &str,
+        expected: &BridgeTransactionStatus,
+        status: BridgeTransactionStatus,
+        source_tx_hash: Option<String>,
+        destination_tx_hash: Option<String>,
+    ) -> Result<bool> {
+        let expected_str = serde_json::to_string(expected)?;
+        let status_str = serde_json::to_string(&status)?;
+        let now = Utc::now();
+        let result = sqlx::query("UPDATE bridge_transactions SET status = ?1, updated_at = ?2, source_tx_hash = COALESCE(?3, source_tx_hash), destination_tx_hash = COALESCE(?4, destination_tx_hash) WHERE id = ?5 AND status = ?6")
+            .bind(status_str)
+            .bind(now)
+            .bind(source_tx_hash)
+            .bind(destination_tx_hash)
+            .bind(id)
+            .bind(expected_str)
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+fn bridge_transaction_from_row(row: &SqliteRow) -> Result<BridgeTransaction> {
+    let status_str: String = row.get("status");
+    let status: BridgeTransactionStatus = serde_json::from_str(&status_str)?;
+
+    Ok(BridgeTransaction {
+        id: row.get("id"),
+        from_wallet: row.get("from_wallet"),
+        from_chain: row.get("from_chain"),
+        to_chain: row.get("to_chain"),
+        token: row.get("token"),
+        amount: row.get("amount"),
+        status,
+        source_tx_hash: row.get("source_tx_hash"),
+        destination_tx_hash: row.get("destination_tx_hash"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+        fee_amount: row.get("fee_amount"),
+        estimated_completion_time: row.get("estimated_completion_time"),
+    })
+}
+
+fn decode_hash(hash: Option<&str>) -> Result<[u8; 32]> {
+    let hash = hash.ok_or_else(|| anyhow::anyhow!("Audit log row has no hash"))?;
+    hex::decode(hash)?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Audit log hash has the wrong length"))
+}
+
+impl Clone for WalletStorage {
+    fn clone(&self) -> Self {
+        // Clone the underlying pool
+        Self { pool: self.pool.clone() }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct WalletMetadata {
+    pub id: String,
+    pub name: String,
+    pub quantum_safe: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct TransactionRecord {
+    pub id: String,
+    pub wallet_id: String,
+    pub tx_hash: String,
+    pub network: String,
+    pub from_address: String,
+    pub to_address: String,
+    pub amount: String,
+    pub fee: String,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub confirmed_at: Option<DateTime<Utc>>,
+}
+
+/// A DEX swap submitted from a wallet. Amounts are in token units, not raw.
+#[derive(Debug, Clone, FromRow)]
+pub struct SwapRecord {
+    pub tx_hash: String,
+    pub wallet_id: String,
+    pub network: String,
+    pub protocol: String,
+    pub token_in: String,
+    pub token_out: String,
+    pub amount_in: String,
+    pub expected_amount_out: String,
+    pub min_amount_out: String,
+    pub approval_tx_hash: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A stake, unstake or withdrawal sent from a wallet. `amount` is in the
+/// network's native token.
+#[derive(Debug, Clone, FromRow)]
+pub struct StakingOperation {
+    pub tx_hash: String,
+    pub wallet_id: String,
+    pub network: String,
+    pub protocol: String,
+    pub action: String,
+    pub position_id: String,
+    pub amount: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An audit row. `prev_hash` and `hash` link it into the chain described
+/// in `audit::chain`.
+#[derive(Debug, Clone, FromRow)]
+pub struct AuditLog {
+    pub id: i64,
+    pub wallet_id: Option<String>,
+    pub action: String,
+    pub details: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub actor: Option<String>,
+    pub request_id: Option<String>,
+    pub prev_hash: Option<String>,
+    pub hash: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Filters and page for `query_audit_logs`; unset filters match every row.
+#[derive(Debug, Clone)]
+pub struct AuditQuery {
+    pub wallet_id: Option<String>,
+    pub action: Option<String>,
+    pub actor: Option<String>,
+    pub request_id: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    /// Only rows after this id.
+    pub after_id: Option<i64>,
+    /// Oldest rows first instead of newest.
+    pub oldest_first: bool,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl Default for AuditQuery {
+    fn default() -> Self {
+        Self {
+            wallet_id: None,
+            action: None,
+            actor: None,
+            request_id: None,
+            since: None,
+            until: None,
+            after_id: None,
+            oldest_first: false,
+            limit: 100,
+            offset: 0,
+        }
+    }
+}
+
+impl AuditQuery {
+    fn push_filters(&self, builder: &mut QueryBuilder<'_, Sqlite>) {
+        let columns = [
+            ("wallet_id", &self.wallet_id),
+            ("action", &self.action),
+            ("actor", &self.actor),
+            ("request_id", &self.request_id),
+        ];
+        for (column, value) in columns {
+            if let Some(value) = value {
+                builder.push(format!(" AND {} = ", column)).push_bind(value.clone());
+            }
+        }
+        if let Some(since) = self.since {
+            builder.push(" AND created_at >= ").push_bind(since.naive_utc());
+        }
+        if let Some(until) = self.until {
+            builder.push(" AND created_at < ").push_bind(until.naive_utc());
+        }
+        if let Some(after_id) = self.after_id {
+            builder.push(" AND id > ").push_bind(after_id);
+        }
+    }
+}
+
+/// One recovery action taken for a bridge transfer. `tx_hash` is the claim
+/// or refund transaction when the bridge returned one.
+#[derive(Debug, Clone, FromRow)]
+pub struct BridgeRecoveryStep {
+    pub id: i64,
+    pub bridge_tx_id: String,
+    pub action: String,
+    pub tx_hash: Option<String>,
+    pub details: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A post-quantum signing key persisted in the keystore. The secret half is
+/// encrypted before it reaches this struct.
+#[derive(Debug, Clone, FromRow)]
+pub struct SigningKeyRecord {
+    pub purpose: String,
+    pub key_id: String,
+    pub algorithm: String,
+    pub public_key: Vec<u8>,
+    pub encrypted_secret_key: Vec<u8>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A signature over the audit log chain up to and including `last_log_id`.
+/// `signature` holds the JSON-encoded `crypto::signing::PqSignature`.
+#[derive(Debug, Clone, FromRow)]
+pub struct AuditSignature {
+    pub id: i64,
+    pub last_log_id: i64,
+    pub chain_digest: String,
+    pub signature: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An API user. `scopes` is space-separated and bounds what the user's
+/// tokens and API keys may be granted.
+#[derive(Debug, Clone, FromRow)]
+pub struct UserRecord {
+    pub id: String,
+    pub username: String,
+    pub password_hash: String,
+    pub scopes: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An API key. Only the Argon2 hash of its secret is stored.
+#[derive(Debug, Clone, FromRow)]
+pub struct ApiKeyRecord {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    pub key_hash: String,
+    pub scopes: String,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A user's TOTP secret (base32). Enrollment is pending until `confirmed_at`
+/// is set; `last_used_step` rejects replayed codes.
+#[derive(Debug, Clone, FromRow)]
+pub struct TotpCredentialRecord {
+    pub user_id: String,
+    pub secret: String,
+    pub confirmed_at: Option<DateTime<Utc>>,
+    pub last_used_step: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A signed operation counted against the wallet's velocity limits.
+/// `amount` is in units of `asset`; `recipient` is `None` when the wallet
+/// pays itself (swaps, bridge transfers). `tx_hash` is empty while the
+/// spend is only reserved.
+#[derive(Debug, Clone, FromRow)]
+pub struct PolicySpendRecord {
+    pub id: i64,
+    pub wallet_id: String,
+    pub tx_hash: String,
+    pub transaction_type: String,
+    pub network: String,
+    pub asset: String,
+    pub recipient: Option<String>,
+    pub amount: f64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The stored version of a sanctions list.
+#[derive(Debug, Clone, FromRow)]
+pub struct SanctionsListRecord {
+    pub source: String,
+    pub format: String,
+    pub version: String,
+    pub entry_count: i64,
+    pub imported_at: DateTime<Utc>,
+}
+
+/// Position of a SIEM export: the last audit row and the time of the last
+/// security event it delivered.
+#[derive(Debug, Clone, FromRow)]
+pub struct AuditExportCursor {
+    pub name: String,
+    pub last_log_id: i64,
+    pub last_event_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// An endpoint notified of wallet events. `events` is a comma-separated list
+/// of event types, empty for all of them; `wallet` limits it to one wallet.
+#[derive(Debug, Clone, FromRow)]
+pub struct WebhookRecord {
+    pub id: String,
+    pub url: String,
+    pub secret: String,
+    pub events: String,
+    pub wallet: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// One event sent, or being retried, to a webhook. `status` is `pending`,
+/// `delivered` or `dead` once the retries ran out.
+#[derive(Debug, Clone, FromRow)]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub webhook_id: String,
+    pub event_type: String,
+    pub payload: String,
+    pub status: String,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub response_status: Option<i64>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A refresh token issued at login, identified by its JWT id.
+#[derive(Debug, Clone, FromRow)]
+pub struct RefreshTokenRecord {
+    pub id: String,
+    pub user_id: String,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait WalletStorageTrait {
+    async fn store_wallet(&self, name: &str, data: &[u8], quantum_safe: bool) -> Result<()>;
+    async fn load_wallet(&self, name: &str) -> Result<(Vec<u8>, bool)>;
+    async fn list_wallets(&self) -> Result<Vec<WalletMetadata>>;
+    async fn delete_wallet(&self, name: &str) -> Result<()>;
+    async fn store_transaction(&self, tx_data: &TransactionRecord) -> Result<()>;
+    async fn get_wallet_transactions(&self, wallet_id: &str) -> Result<Vec<TransactionRecord>>;
+    async fn store_swap(&self, swap: &SwapRecord) -> Result<()>;
+    async fn get_wallet_swaps(&self, wallet_id: &str) -> Result<Vec<SwapRecord>>;
+    async fn store_staking_operation(&self, operation: &StakingOperation) -> Result<()>;
+    async fn get_staking_operations(&self, wallet_id: &str) -> Result<Vec<StakingOperation>>;
+    async fn store_bridge_transaction(&self, tx: &BridgeTransaction) -> Result<()>;
+    async fn get_bridge_transaction(&self, id: &str) -> Result<BridgeTransaction>;
+    async fn get_pending_bridge_transactions(&self) -> Result<Vec<BridgeTransaction>>;
+    async fn update_bridge_transaction_status(
+        &self,
+        id: &str,
+        expected: &BridgeTransactionStatus,
+        status: BridgeTransactionStatus,
+        source_tx_hash: Option<String>,
+        destination_tx_hash: Option<String>,
+    ) -> Result<bool>;
+    async fn get_bridge_transactions_for_recovery(
+        &self,
+        stuck_before: DateTime<Utc>,
+    ) -> Result<Vec<BridgeTransaction>>;
+    async fn store_bridge_recovery_step(
+        &self,
+        bridge_tx_id: &str,
+        action: &str,
+        tx_hash: Option<&str>,
+        details: Option<&str>,
+    ) -> Result<()>;
+    async fn get_bridge_recovery_steps(
+        &self,
+        bridge_tx_id: &str,
+    ) -> Result<Vec<BridgeRecoveryStep>>;
+    async fn log_action(
+        &self,
+        wallet_id: &str,
+        action: &str,
+        details: &str,
+        ip_address: Option<&str>,
+        user_agent: Option<&str>,
+    ) -> Result<()>;
+    async fn store_signing_key(&self, key: &SigningKeyRecord) -> Result<()>;
+    async fn load_signing_key(&self, purpose: &str) -> Result<Option<SigningKeyRecord>>;
+    async fn get_audit_logs_through(&self, last_id: i64) -> Result<Vec<AuditLog>>;
+    async fn get_latest_audit_log_id(&self) -> Result<Option<i64>>;
+    async fn store_audit_signature(&self, signature: &AuditSignature) -> Result<i64>;
+    async fn get_latest_audit_signature(&self) -> Result<Option<AuditSignature>>;
+    async fn get_audit_signatures(&self) -> Result<Vec<AuditSignature>>;
+    async fn log_event(&self, wallet_id: Option<&str>, action: &str, details: &str) -> Result<()>;
+    async fn query_audit_logs(&self, query: &AuditQuery) -> Result<Vec<AuditLog>>;
+    async fn count_audit_logs(&self, query: &AuditQuery) -> Result<i64>;
+    async fn store_user(&self, user: &UserRecord) -> Result<()>;
+    async fn get_user(&self, id: &str) -> Result<Option<UserRecord>>;
+    async fn get_user_by_username(&self, username: &str) -> Result<Option<UserRecord>>;
+    async fn count_users(&self) -> Result<i64>;
+    async fn store_api_key(&self, key: &ApiKeyRecord) -> Result<()>;
+    async fn get_api_key(&self, id: &str) -> Result<Option<ApiKeyRecord>>;
+    async fn list_api_keys(&self, user_id: &str) -> Result<Vec<ApiKeyRecord>>;
+    async fn revoke_api_key(&self, id: &str, revoked_at: DateTime<Utc>) -> Result<bool>;
+    async fn touch_api_key(&self, id: &str, used_at: DateTime<Utc>) -> Result<()>;
+    async fn store_refresh_token(&self, token: &RefreshTokenRecord) -> Result<()>;
+    async fn get_refresh_token(&self, id: &str) -> Result<Option<RefreshTokenRecord>>;
+    async fn revoke_refresh_token(&self, id: &str, revoked_at: DateTime<Utc>) -> Result<bool>;
+    async fn add_user_role(&self, user_id: &str, role: &str) -> Result<bool>;
+    async fn remove_user_role(&self, user_id: &str, role: &str) -> Result<bool>;
+    async fn get_user_roles(&self, user_id: &str) -> Result<Vec<String>>;
+    async fn set_wallet_owner(&self, wallet_name: &str, user_id: &str) -> Result<()>;
+    async fn get_wallet_owner(&self, wallet_name: &str) -> Result<Option<String>>;
+    async fn list_owned_wallets(&self, user_id: &str) -> Result<Vec<String>>;
+    async fn store_totp_credential(&self, credential: &TotpCredentialRecord) -> Result<()>;
+    async fn get_totp_credential(&self, user_id: &str) -> Result<Option<TotpCredentialRecord>>;
+    async fn confirm_totp_credential(
+        &self,
+        user_id: &str,
+        confirmed_at: DateTime<Utc>,
+    ) -> Result<bool>;
+    async fn delete_totp_credential(&self, user_id: &str) -> Result<()>;
+    async fn use_totp_step(&self, user_id: &str, step: i64) -> Result<bool>;
+    async fn store_recovery_codes(&self, user_id: &str, code_hashes: &[String]) -> Result<()>;
+    async fn use_recovery_code(
+        &self,
+        user_id: &str,
+        code_hash: &str,
+        used_at: DateTime<Utc>,
+    ) -> Result<bool>;
+    async fn count_unused_recovery_codes(&self, user_id: &str) -> Result<i64>;
+    async fn increment_login_failures(&self, user_id: &str) -> Result<i64>;
+    async fn lock_user(&self, user_id: &str, until: DateTime<Utc>) -> Result<()>;
+    async fn get_locked_until(&self, user_id: &str) -> Result<Option<DateTime<Utc>>>;
+    async fn clear_login_failures(&self, user_id: &str) -> Result<()>;
+    async fn set_spending_policy(&self, wallet_id: &str, policy: &str) -> Result<()>;
+    async fn get_spending_policy(&self, wallet_id: &str) -> Result<Option<String>>;
+    async fn delete_spending_policy(&self, wallet_id: &str) -> Result<bool>;
+    async fn record_policy_spend(&self, spend: &PolicySpendRecord) -> Result<()>;
+    async fn reserve_policy_spend(
+        &self,
+        spend: &PolicySpendRecord,
+        since: DateTime<Utc>,
+        admit: &mut (dyn for<'r> FnMut(&'r [PolicySpendRecord]) -> bool + Send),
+    ) -> Result<Option<i64>>;
+    async fn confirm_policy_spend(&self, id: i64, tx_hash: &str) -> Result<()>;
+    async fn release_policy_spend(&self, id: i64) -> Result<bool>;
+    async fn get_policy_spends_since(
+        &self,
+        wallet_id: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<PolicySpendRecord>>;
+    async fn note_recipient(
+        &self,
+        wallet_id: &str,
+        address: &str,
+        seen_at: DateTime<Utc>,
+    ) -> Result<DateTime<Utc>>;
+    async fn store_approval_request(&self, request: &ApprovalRequest) -> Result<()>;
+    async fn get_approval_request(&self, id: &str) -> Result<Option<ApprovalRequest>>;
+    async fn list_approval_requests(
+        &self,
+        status: Option<ApprovalStatus>,
+    ) -> Result<Vec<ApprovalRequest>>;
+    async fn add_approval_vote(&self, request_id: &str, vote: &ApprovalVote) -> Result<bool>;
+    async fn update_approval_status(
+        &self,
+        id: &str,
+        from: ApprovalStatus,
+        to: ApprovalStatus,
+        result: Option<&str>,
+    ) -> Result<bool>;
+    async fn replace_sanctions_list(&self, list: &SanctionsList) -> Result<()>;
+    async fn get_sanctions_lists(&self) -> Result<Vec<SanctionsListRecord>>;
+    async fn get_sanctioned_addresses(&self) -> Result<Vec<String>>;
+    async fn get_export_cursor(&self, name: &str) -> Result<Option<AuditExportCursor>>;
+    async fn set_export_cursor(&self, cursor: &AuditExportCursor) -> Result<()>;
+    async fn get_pending_transactions(&self) -> Result<Vec<TransactionRecord>>;
+    async fn update_transaction_status(
+        &self,
+        id: &str,
+        status: &str,
+        confirmed_at: Option<DateTime<Utc>>,
+    ) -> Result<()>;
+    async fn store_webhook(&self, webhook: &WebhookRecord) -> Result<()>;
+    async fn get_webhook(&self, id: &str) -> Result<Option<WebhookRecord>>;
+    async fn list_webhooks(&self) -> Result<Vec<WebhookRecord>>;
+    async fn delete_webhook(&self, id: &str) -> Result<bool>;
+    async fn store_webhook_delivery(&self, delivery: &WebhookDelivery) -> Result<()>;
+    async fn update_webhook_delivery(&self, delivery: &WebhookDelivery) -> Result<()>;
+    async fn get_webhook_delivery(&self, id: &str) -> Result<Option<WebhookDelivery>>;
+    async fn get_webhook_deliveries(
+        &self,
+        webhook_id: &str,
+        status: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>>;
+    async fn get_due_webhook_deliveries(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>>;
+}
+
+// Implement the trait for WalletStorage by delegating to methods above
+#[async_trait]
+impl WalletStorageTrait for WalletStorage {
+    async fn store_wallet(&self, name: &str, data: &[u8], quantum_safe: bool) -> Result<()> {
+        self.store_wallet(name, data, quantum_safe).await
+    }
+
+    async fn load_wallet(&self, name: &str) -> Result<(Vec<u8>, bool)> {
+        self.load_wallet(name).await
+    }
+
+    async fn list_wallets(&self) -> Result<Vec<WalletMetadata>> {
+        self.list_wallets().await
+    }
+
+    async fn delete_wallet(&self, name: &str) -> Result<()> {
+        self.delete_wallet(name).await
+    }
+
+    async fn store_transaction(&self, tx_data: &TransactionRecord) -> Result<()> {
+        self.store_transaction(tx_data).await
+    }
+
+    async fn get_wallet_transactions(&self, wallet_id: &str) -> Result<Vec<TransactionRecord>> {
+        self.get_wallet_transactions(wallet_id).await
+    }
+
+    async fn store_swap(&self, swap: &SwapRecord) -> Result<()> {
+        self.store_swap(swap).await
+    }
+
+    async fn get_wallet_swaps(&self, wallet_id: &str) -> Result<Vec<SwapRecord>> {
+        self.get_wallet_swaps(wallet_id).await
+    }
+
+    async fn store_staking_operation(&self, operation: &StakingOperation) -> Result<()> {
+        self.store_staking_operation(operation).await
+    }
+
+    async fn get_staking_operations(&self, wallet_id: &str) -> Result<Vec<StakingOperation>> {
+        self.get_staking_operations(wallet_id).await
+    }
+
+    async fn store_bridge_transaction(&self, tx: &BridgeTransaction) -> Result<()> {
+        self.store_bridge_transaction(tx).await
+    }
+
+    async fn get_bridge_transaction(&self, id: &str) -> Result<BridgeTransaction> {
+        self.get_bridge_transaction(id).await
+    }
+
+    async fn get_pending_bridge_transactions(&self) -> Result<Vec<BridgeTransaction>> {
+        self.get_pending_bridge_transactions().await
+    }
+
+    async fn update_bridge_transaction_status(
+        &self,
+        id: &str,
+        expected: &BridgeTransactionStatus,
+        status: BridgeTransactionStatus,
+        source_tx_hash: Option<String>,
+        destination_tx_hash: Option<String>,
+    ) -> Result<bool> {
+        self.update_bridge_transaction_status(
+            id,
+            expected,
+            status,
+            source_tx_hash,
+            destination_tx_hash,
+        )
+        .await
+    }
+
+    async fn get_bridge_transactions_for_recovery(
+        &self,
+        stuck_before: DateTime<Utc>,
+    ) -> Result<Vec<BridgeTransaction>> {
+        self.get_bridge_transactions_for_recovery(stuck_before).await
+    }
+
+    async fn store_bridge_recovery_step(
+        &self,
+        bridge_tx_id: &str,
+        action: &str,
+        tx_hash: Option<&str>,
+        details: Option<&str>,
+    ) -> Result<()> {
+        self.store_bridge_recovery_step(bridge_tx_id, action, tx_hash, details).await
+    }
+
+    async fn get_bridge_recovery_steps(
+        &self,
+        bridge_tx_id: &str,
+    ) -> Result<Vec<BridgeRecoveryStep>> {
+        self.get_bridge_recovery_steps(bridge_tx_id).await
+    }
+
+    async fn log_action(
+        &self,
+        wallet_id: &str,
+        action: &str,
+        details: &str,
+        ip_address: Option<&str>,
+        user_agent: Option<&str>,
+    ) -> Result<()> {
+        self.log_action(wallet_id, action, details, ip_address, user_agent).await
+    }
+
+    async fn store_signing_key(&self, key: &SigningKeyRecord) -> Result<()> {
+        self.store_signing_key(key).await
+    }
+
+    async fn load_signing_key(&self, purpose: &str) -> Result<Option<SigningKeyRecord>> {
+        self.load_signing_key(purpose).await
+    }
+
+    async fn get_audit_logs_through(&self, last_id: i64) -> Result<Vec<AuditLog>> {
+        self.get_audit_logs_through(last_id).await
+    }
+
+    async fn get_latest_audit_log_id(&self) -> Result<Option<i64>> {
+        self.get_latest_audit_log_id().await
+    }
+
+    async fn store_audit_signature(&self, signature: &AuditSignature) -> Result<i64> {
+        self.store_audit_signature(signature).await
+    }
+
+    async fn get_latest_audit_signature(&self) -> Result<Option<AuditSignature>> {
+        self.get_latest_audit_signature().await
+    }
+
+    async fn get_audit_signatures(&self) -> Result<Vec<AuditSignature>> {
+        self.get_audit_signatures().await
+    }
+
+    async fn log_event(&self, wallet_id: Option<&str>, action: &str, details: &str) -> Result<()> {
+        self.log_event(wallet_id, action, details).await
+    }
+
+    async fn query_audit_logs(&self, query: &AuditQuery) -> Result<Vec<AuditLog>> {
+        self.query_audit_logs(query).await
+    }
+
+    async fn count_audit_logs(&self, query: &AuditQuery) -> Result<i64> {
+        self.count_audit_logs(query).await
+    }
+
+    async fn store_user(&self, user: &UserRecord) -> Result<()> {
+        self.store_user(user).await
+    }
+
+    async fn get_user(&self, id: &str) -> Result<Option<UserRecord>> {
+        self.get_user(id).await
+    }
+
+    async fn get_user_by_username(&self, username: &str) -> Result<Option<UserRecord>> {
+        self.get_user_by_username(username).await
+    }
+
+    async fn count_users(&self) -> Result<i64> {
+        self.count_users().await
+    }
+
+    async fn store_api_key(&self, key: &ApiKeyRecord) -> Result<()> {
+        self.store_api_key(key).await
+    }
+
+    async fn get_api_key(&self, id: &str) -> Result<Option<ApiKeyRecord>> {
+        self.get_api_key(id).await
+    }
+
+    async fn list_api_keys(&self, user_id: &str) -> Result<Vec<ApiKeyRecord>> {
+        self.list_api_keys(user_id).await
+    }
+
+    async fn revoke_api_key(&self, id: &str, revoked_at: DateTime<Utc>) -> Result<bool> {
+        self.revoke_api_key(id, revoked_at).await
+    }
+
+    async fn touch_api_key(&self, id: &str, used_at: DateTime<Utc>) -> Result<()> {
+        self.touch_api_key(id, used_at).await
+    }
+
+    async fn store_refresh_token(&self, token: &RefreshTokenRecord) -> Result<()> {
+        self.store_refresh_token(token).await
+    }
+
+    async fn get_refresh_token(&self, id: &str) -> Result<Option<RefreshTokenRecord>> {
+        self.get_refresh_token(id).await
+    }
+
+    async fn revoke_refresh_token(&self, id: &str, revoked_at: DateTime<Utc>) -> Result<bool> {
+        self.revoke_refresh_token(id, revoked_at).await
+    }
+
+    async fn add_user_role(&self, user_id: &str, role: &str) -> Result<bool> {
+        self.add_user_role(user_id, role).await
+    }
+
+    async fn remove_user_role(&self, user_id: &str, role: &str) -> Result<bool> {
+        self.remove_user_role(user_id, role).await
+    }
+
+    async fn get_user_roles(&self, user_id: &str) -> Result<Vec<String>> {
+        self.get_user_roles(user_id).await
+    }
+
+    async fn set_wallet_owner(&self, wallet_name: &str, user_id: &str) -> Result<()> {
+        self.set_wallet_owner(wallet_name, user_id).await
+    }
+
+    async fn get_wallet_owner(&self, wallet_name: &str) -> Result<Option<String>> {
+        self.get_wallet_owner(wallet_name).await
+    }
+
+    async fn list_owned_wallets(&self, user_id: &str) -> Result<Vec<String>> {
+        self.list_owned_wallets(user_id).await
+    }
+
+    async fn store_totp_credential(&self, credential: &TotpCredentialRecord) -> Result<()> {
+        self.store_totp_credential(credential).await
+    }
+
+    async fn get_totp_credential(&self, user_id: &str) -> Result<Option<TotpCredentialRecord>> {
+        self.get_totp_credential(user_id).await
+    }
+
+    async fn confirm_totp_credential(
+        &self,
+        user_id: &str,
+        confirmed_at: DateTime<Utc>,
+    ) -> Result<bool> {
+        self.confirm_totp_credential(user_id, confirmed_at).await
+    }
+
+    async fn delete_totp_credential(&self, user_id: &str) -> Result<()> {
+        self.delete_totp_credential(user_id).await
+    }
+
+    async fn use_totp_step(&self, user_id: &str, step: i64) -> Result<bool> {
+        self.use_totp_step(user_id, step).await
+    }
+
+    async fn store_recovery_codes(&self, user_id: &str, code_hashes: &[String]) -> Result<()> {
+        self.store_recovery_codes(user_id, code_hashes).await
+    }
+
+    async fn use_recovery_code(
+        &self,
+        user_id: &str,
+        code_hash: &str,
+        used_at: DateTime<Utc>,
+    ) -> Result<bool> {
+        self.use_recovery_code(user_id, code_hash, used_at).await
+    }
+
+    async fn count_unused_recovery_codes(&self, user_id: &str) -> Result<i64> {
+        self.count_unused_recovery_codes(user_id).await
+    }
+
+    async fn increment_login_failures(&self, user_id: &str) -> Result<i64> {
+        self.increment_login_failures(user_id).await
+    }
+
+    async fn lock_user(&self, user_id: &str, until: DateTime<Utc>) -> Result<()> {
+        self.lock_user(user_id, until).await
+    }
+
+    async fn get_locked_until(&self, user_id: &str) -> Result<Option<DateTime<Utc>>> {
+        self.get_locked_until(user_id).await
+    }
+
+    async fn clear_login_failures(&self, user_id: &str) -> Result<()> {
+        self.clear_login_failures(user_id).await
+    }
+
+    async fn set_spending_policy(&self, wallet_id: &str, policy: &str) -> Result<()> {
+        self.set_spending_policy(wallet_id, policy).await
+    }
+
+    async fn get_spending_policy(&self, wallet_id: &str) -> Result<Option<String>> {
+        self.get_spending_policy(wallet_id).await
+    }
+
+    async fn delete_spending_policy(&self, wallet_id: &str) -> Result<bool> {
+        self.delete_spending_policy(wallet_id).await
+    }
 
-        let status_str: String = row.get("status");
-        let status: BridgeTransactionStatus = serde_json::from_str(&status_str)?;
+    async fn record_policy_spend(&self, spend: &PolicySpendRecord) -> Result<()> {
+        self.record_policy_spend(spend).await
+    }
 
-        let tx = BridgeTransaction {
-            id: row.get("id"),
-            from_wallet: row.get("from_wallet"),
-            from_chain: row.get("from_chain"),
-            to_chain: row.get("to_chain"),
-            token: row.get("token"),
-            amount: row.get("amount"),
-            status,
-            source_tx_hash: row.get("source_tx_hash"),
-            destination_tx_hash: row.get("destination_tx_hash"),
-            created_at: row.get("created_at"),
-            updated_at: row.get("updated_at"),
-            fee_amount: row.get("fee_amount"),
-            estimated_completion_time: row.get("estimated_completion_time"),
-        };
-        Ok(tx)
+    async fn reserve_policy_spend(
+        &self,
+        spend: &PolicySpendRecord,
+        since: DateTime<Utc>,
+        admit: &mut (dyn for<'r> FnMut(&'r [PolicySpendRecord]) -> bool + Send),
+    ) -> Result<Option<i64>> {
+        self.reserve_policy_spend(spend, since, admit).await
     }
 
-    pub async fn update_bridge_transaction_status(
+    async fn confirm_policy_spend(&self, id: i64, tx_hash: &str) -> Result<()> {
+        self.confirm_policy_spend(id, tx_hash).await
+    }
+
+    async fn release_policy_spend(&self, id: i64) -> Result<bool> {
+        self.release_policy_spend(id).await
+    }
+
+    async fn get_policy_spends_since(
         &self,
-        id: &str,
-        status: BridgeTransactionStatus,
-        source_tx_hash: Option<String>,
-    ) -> Result<()> {
-        let status_str = serde_json::to_string(&status)?;
-        let now = Utc::now();
-        sqlx::query("UPDATE bridge_transactions SET status = ?1, updated_at = ?2, source_tx_hash = COALESCE(?3, source_tx_hash) WHERE id = ?4")
-            .bind(status_str)
-            .bind(now)
-            .bind(source_tx_hash)
-            .bind(id)
-            .execute(&self.pool).await?;
-        Ok(())
+        wallet_id: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<PolicySpendRecord>> {
+        self.get_policy_spends_since(wallet_id, since).await
     }
-}
 
-impl Clone for WalletStorage {
-    fn clone(&self) -> Self {
-        // Clone the underlying pool
-        Self { pool: self.pool.clone() }
+    async fn note_recipient(
+        &self,
+        wallet_id: &str,
+        address: &str,
+        seen_at: DateTime<Utc>,
+    ) -> Result<DateTime<Utc>> {
+        self.note_recipient(wallet_id, address, seen_at).await
     }
-}
 
-#[derive(Debug, Clone, FromRow)]
-pub struct WalletMetadata {
-    pub id: String,
-    pub name: String,
-    pub quantum_safe: bool,
-    pub created_at: NaiveDateTime,
-    pub updated_at: NaiveDateTime,
-}
+    async fn store_approval_request(&self, request: &ApprovalRequest) -> Result<()> {
+        self.store_approval_request(request).await
+    }
 
-#[derive(Debug, Clone, FromRow)]
-pub struct TransactionRecord {
-    pub id: String,
-    pub wallet_id: String,
-    pub tx_hash: String,
-    pub network: String,
-    pub from_address: String,
-    pub to_address: String,
-    pub amount: String,
-    pub fee: String,
-    pub status: String,
-    pub created_at: DateTime<Utc>,
-    pub confirmed_at: Option<DateTime<Utc>>,
-}
+    async fn get_approval_request(&self, id: &str) -> Result<Option<ApprovalRequest>> {
+        self.get_approval_request(id).await
+    }
 
-#[derive(Debug, Clone, FromRow)]
-pub struct AuditLog {
-    pub id: i64,
-    pub wallet_id: Option<String>,
-    pub action: String,
-    pub details: Option<String>,
-    pub ip_address: Option<String>,
-    pub user_agent: Option<String>,
-    pub created_at: DateTime<Utc>,
-}
+    async fn list_approval_requests(
+        &self,
+        status: Option<ApprovalStatus>,
+    ) -> Result<Vec<ApprovalRequest>> {
+        self.list_approval_requests(status).await
+    }
 
-#[async_trait]
-pub trait WalletStorageTrait {
-    async fn store_wallet(&self, name: &str, data: &[u8], quantum_safe: bool) -> Result<()>;
-    async fn load_wallet(&self, name: &str) -> Result<(Vec<u8>, bool)>;
-    async fn list_wallets(&self) -> Result<Vec<WalletMetadata>>;
-    async fn delete_wallet(&self, name: &str) -> Result<()>;
-    async fn store_bridge_transaction(&self, tx: &BridgeTransaction) -> Result<()>;
-    async fn get_bridge_transaction(&self, id: &str) -> Result<BridgeTransaction>;
-    async fn update_bridge_transaction_status(
+    async fn add_approval_vote(&self, request_id: &str, vote: &ApprovalVote) -> Result<bool> {
+        self.add_approval_vote(request_id, vote).await
+    }
+
+    async fn update_approval_status(
         &self,
         id: &str,
-        status: BridgeTransactionStatus,
-        source_tx_hash: Option<String>,
-    ) -> Result<()>;
-}
+        from: ApprovalStatus,
+        to: ApprovalStatus,
+        result: Option<&str>,
+    ) -> Result<bool> {
+        self.update_approval_status(id, from, to, result).await
+    }
 
-// Implement the trait for WalletStorage by delegating to methods above
-#[async_trait]
-impl WalletStorageTrait for WalletStorage {
-    async fn store_wallet(&self, name: &str, data: &[u8], quantum_safe: bool) -> Result<()> {
-        self.store_wallet(name, data, quantum_safe).await
+    async fn replace_sanctions_list(&self, list: &SanctionsList) -> Result<()> {
+        self.replace_sanctions_list(list).await
     }
 
-    async fn load_wallet(&self, name: &str) -> Result<(Vec<u8>, bool)> {
-        self.load_wallet(name).await
+    async fn get_sanctions_lists(&self) -> Result<Vec<SanctionsListRecord>> {
+        self.get_sanctions_lists().await
     }
 
-    async fn list_wallets(&self) -> Result<Vec<WalletMetadata>> {
-        self.list_wallets().await
+    async fn get_sanctioned_addresses(&self) -> Result<Vec<String>> {
+        self.get_sanctioned_addresses().await
     }
 
-    async fn delete_wallet(&self, name: &str) -> Result<()> {
-        self.delete_wallet(name).await
+    async fn get_export_cursor(&self, name: &str) -> Result<Option<AuditExportCursor>> {
+        self.get_export_cursor(name).await
     }
 
-    async fn store_bridge_transaction(&self, tx: &BridgeTransaction) -> Result<()> {
-        self.store_bridge_transaction(tx).await
+    async fn set_export_cursor(&self, cursor: &AuditExportCursor) -> Result<()> {
+        self.set_export_cursor(cursor).await
     }
 
-    async fn get_bridge_transaction(&self, id: &str) -> Result<BridgeTransaction> {
-        self.get_bridge_transaction(id).await
+    async fn get_pending_transactions(&self) -> Result<Vec<TransactionRecord>> {
+        self.get_pending_transactions().await
     }
 
-    async fn update_bridge_transaction_status(
+    async fn update_transaction_status(
         &self,
         id: &str,
-        status: BridgeTransactionStatus,
-        source_tx_hash: Option<String>,
+        status: &str,
+        confirmed_at: Option<DateTime<Utc>>,
     ) -> Result<()> {
-        self.update_bridge_transaction_status(id, status, source_tx_hash).await
+        self.update_transaction_status(id, status, confirmed_at).await
+    }
+
+    async fn store_webhook(&self, webhook: &WebhookRecord) -> Result<()> {
+        self.store_webhook(webhook).await
+    }
+
+    async fn get_webhook(&self, id: &str) -> Result<Option<WebhookRecord>> {
+        self.get_webhook(id).await
+    }
+
+    async fn list_webhooks(&self) -> Result<Vec<WebhookRecord>> {
+        self.list_webhooks().await
+    }
+
+    async fn delete_webhook(&self, id: &str) -> Result<bool> {
+        self.delete_webhook(id).await
+    }
+
+    async fn store_webhook_delivery(&self, delivery: &WebhookDelivery) -> Result<()> {
+        self.store_webhook_delivery(delivery).await
+    }
+
+    async fn update_webhook_delivery(&self, delivery: &WebhookDelivery) -> Result<()> {
+        self.update_webhook_delivery(delivery).await
+    }
+
+    async fn get_webhook_delivery(&self, id: &str) -> Result<Option<WebhookDelivery>> {
+        self.get_webhook_delivery(id).await
+    }
+
+    async fn get_webhook_deliveries(
+        &self,
+        webhook_id: &str,
+        status: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>> {
+        self.get_webhook_deliveries(webhook_id, status, limit).await
+    }
+
+    async fn get_due_webhook_deliveries(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>> {
+        self.get_due_webhook_deliveries(now, limit).await
     }
 }
 
@@ -649,19 +3350,541 @@ mod tests {
         let retrieved = storage.get_bridge_transaction("test-tx-123").await.unwrap();
         assert_eq!(retrieved.id, tx.id);
         assert_eq!(retrieved.status, BridgeTransactionStatus::Initiated);
+        let pending = storage.get_pending_bridge_transactions().await.unwrap();
+        assert_eq!(pending.len(), 1);
 
         // Update status
-        storage
+        assert!(storage
             .update_bridge_transaction_status(
                 "test-tx-123",
+                &BridgeTransactionStatus::Initiated,
                 BridgeTransactionStatus::Completed,
                 Some("0x123".to_string()),
+                Some("0x456".to_string()),
             )
             .await
-            .unwrap();
+            .unwrap());
 
         let updated = storage.get_bridge_transaction("test-tx-123").await.unwrap();
         assert_eq!(updated.status, BridgeTransactionStatus::Completed);
         assert_eq!(updated.source_tx_hash, Some("0x123".to_string()));
+        assert_eq!(updated.destination_tx_hash, Some("0x456".to_string()));
+
+        // A writer that read the old status loses the race and changes nothing
+        assert!(!storage
+            .update_bridge_transaction_status(
+                "test-tx-123",
+                &BridgeTransactionStatus::Initiated,
+                BridgeTransactionStatus::Failed("late".to_string()),
+                None,
+                None,
+            )
+            .await
+            .unwrap());
+        let unchanged = storage.get_bridge_transaction("test-tx-123").await.unwrap();
+        assert_eq!(unchanged.status, BridgeTransactionStatus::Completed);
+        assert_eq!(unchanged.destination_tx_hash, Some("0x456".to_string()));
+        assert!(storage.get_pending_bridge_transactions().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_bridge_recovery_queries() {
+        let storage = WalletStorage::new_with_url("sqlite::memory:").await.unwrap();
+        let now = Utc::now();
+        let transfer =
+            |id: &str, status: BridgeTransactionStatus, eta: chrono::Duration| BridgeTransaction {
+                id: id.to_string(),
+                from_wallet: "wallet1".to_string(),
+                from_chain: "eth".to_string(),
+                to_chain: "solana".to_string(),
+                token: "USDC".to_string(),
+                amount: "1.0".to_string(),
+                status,
+                source_tx_hash: Some(format!("0x{}", id)),
+                destination_tx_hash: None,
+                created_at: now,
+                updated_at: now,
+                fee_amount: None,
+                estimated_completion_time: Some(now + eta),
+            };
+        let hour = chrono::Duration::hours(1);
+        for tx in [
+            transfer("overdue", BridgeTransactionStatus::InTransit, -hour),
+            transfer("on-time", BridgeTransactionStatus::InTransit, hour),
+            transfer("failed", BridgeTransactionStatus::Failed("x".into()), hour),
+            transfer("refunding", BridgeTransactionStatus::RefundPending, hour),
+            transfer("done", BridgeTransactionStatus::Completed, -hour),
+            transfer(
+                "manual",
+                BridgeTransactionStatus::ManualInterventionRequired("y".into()),
+                -hour,
+            ),
+        ] {
+            storage.store_bridge_transaction(&tx).await.unwrap();
+        }
+
+        let mut ids: Vec<_> = storage
+            .get_bridge_transactions_for_recovery(now)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|tx| tx.id)
+            .collect();
+        ids.sort();
+        assert_eq!(ids, ["failed", "overdue", "refunding"]);
+
+        storage.store_bridge_recovery_step("failed", "refund", Some("r1"), None).await.unwrap();
+        storage.store_bridge_recovery_step("failed", "escalate", None, Some("why")).await.unwrap();
+        let steps = storage.get_bridge_recovery_steps("failed").await.unwrap();
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].tx_hash.as_deref(), Some("r1"));
+        assert_eq!(steps[1].details.as_deref(), Some("why"));
+    }
+
+    #[tokio::test]
+    async fn test_swap_storage() {
+        let storage = WalletStorage::new_with_url("sqlite::memory:").await.unwrap();
+        let now = Utc::now();
+        storage.store_wallet("swapper", b"data", false).await.unwrap();
+        let wallet_id = storage.list_wallets().await.unwrap()[0].id.clone();
+        storage
+            .store_transaction(&TransactionRecord {
+                id: "swap-1".to_string(),
+                wallet_id: wallet_id.clone(),
+                tx_hash: "0xabc".to_string(),
+                network: "eth".to_string(),
+                from_address: "0x01".to_string(),
+                to_address: "0x02".to_string(),
+                amount: "1".to_string(),
+                fee: "0.003".to_string(),
+                status: "pending".to_string(),
+                created_at: now,
+                confirmed_at: None,
+            })
+            .await
+            .unwrap();
+        storage
+            .store_swap(&SwapRecord {
+                tx_hash: "0xabc".to_string(),
+                wallet_id: wallet_id.clone(),
+                network: "eth".to_string(),
+                protocol: "uniswap_v3".to_string(),
+                token_in: "WETH".to_string(),
+                token_out: "USDC".to_string(),
+                amount_in: "1".to_string(),
+                expected_amount_out: "2000".to_string(),
+                min_amount_out: "1990".to_string(),
+                approval_tx_hash: None,
+                created_at: now,
+            })
+            .await
+            .unwrap();
+
+        let transactions = storage.get_wallet_transactions(&wallet_id).await.unwrap();
+        assert_eq!(transactions.len(), 1);
+        let swaps = storage.get_wallet_swaps(&wallet_id).await.unwrap();
+        assert_eq!(swaps.len(), 1);
+        assert_eq!(swaps[0].tx_hash, transactions[0].tx_hash);
+        assert_eq!(swaps[0].min_amount_out, "1990");
+        assert!(storage.get_wallet_swaps("wallet2").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_staking_operation_storage() {
+        let storage = WalletStorage::new_with_url("sqlite::memory:").await.unwrap();
+        let now = Utc::now();
+        for (tx_hash, action, offset) in [("0x1", "stake", 0), ("0x2", "unstake", 1)] {
+            storage
+                .store_staking_operation(&StakingOperation {
+                    tx_hash: tx_hash.to_string(),
+                    wallet_id: "wallet1".to_string(),
+                    network: "eth".to_string(),
+                    protocol: "lido".to_string(),
+                    action: action.to_string(),
+                    position_id: "stETH".to_string(),
+                    amount: "1".to_string(),
+                    created_at: now + chrono::Duration::seconds(offset),
+                })
+                .await
+                .unwrap();
+        }
+
+        let operations = storage.get_staking_operations("wallet1").await.unwrap();
+        assert_eq!(operations.len(), 2);
+        assert_eq!(operations[0].action, "stake");
+        assert_eq!(operations[1].tx_hash, "0x2");
+        assert!(storage.get_staking_operations("wallet2").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_signing_key_storage() {
+        let storage = WalletStorage::new_with_url("sqlite::memory:").await.unwrap();
+        assert!(storage.load_signing_key("artifact-signing").await.unwrap().is_none());
+
+        let record = SigningKeyRecord {
+            purpose: "artifact-signing".to_string(),
+            key_id: "0011223344556677".to_string(),
+            algorithm: "ml-dsa-65".to_string(),
+            public_key: vec![1, 2, 3],
+            encrypted_secret_key: vec![4, 5, 6],
+            created_at: Utc::now(),
+        };
+        storage.store_signing_key(&record).await.unwrap();
+
+        let loaded = storage.load_signing_key("artifact-signing").await.unwrap().unwrap();
+        assert_eq!(loaded.key_id, record.key_id);
+        assert_eq!(loaded.public_key, record.public_key);
+
+        // purpose is unique: a second key for the same purpose is rejected
+        assert!(storage.store_signing_key(&record).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_user_and_credential_storage() {
+        let storage = WalletStorage::new_with_url("sqlite::memory:").await.unwrap();
+        let now = Utc::now();
+        let user = UserRecord {
+            id: "user-1".to_string(),
+            username: "alice".to_string(),
+            password_hash: "hash".to_string(),
+            scopes: "read write".to_string(),
+            created_at: now,
+        };
+        storage.store_user(&user).await.unwrap();
+        assert!(storage
+            .store_user(&UserRecord { id: "user-2".to_string(), ..user })
+            .await
+            .is_err());
+        assert_eq!(storage.get_user_by_username("alice").await.unwrap().unwrap().id, "user-1");
+        assert!(storage.get_user("user-2").await.unwrap().is_none());
+        assert_eq!(storage.count_users().await.unwrap(), 1);
+
+        storage
+            .store_api_key(&ApiKeyRecord {
+                id: "key-1".to_string(),
+                user_id: "user-1".to_string(),
+                name: "ci".to_string(),
+                key_hash: "hash".to_string(),
+                scopes: "read".to_string(),
+                expires_at: None,
+                revoked_at: None,
+                last_used_at: None,
+                created_at: now,
+            })
+            .await
+            .unwrap();
+        storage.touch_api_key("key-1", now).await.unwrap();
+        assert!(storage.get_api_key("key-1").await.unwrap().unwrap().last_used_at.is_some());
+        assert!(storage.revoke_api_key("key-1", now).await.unwrap());
+        assert!(!storage.revoke_api_key("key-1", now).await.unwrap());
+        let keys = storage.list_api_keys("user-1").await.unwrap();
+        assert!(keys[0].revoked_at.is_some());
+
+        storage
+            .store_refresh_token(&RefreshTokenRecord {
+                id: "jti-1".to_string(),
+                user_id: "user-1".to_string(),
+                expires_at: now,
+                revoked_at: None,
+                created_at: now,
+            })
+            .await
+            .unwrap();
+        assert!(storage.revoke_refresh_token("jti-1", now).await.unwrap());
+        assert!(!storage.revoke_refresh_token("jti-1", now).await.unwrap());
+        assert!(!storage.revoke_refresh_token("jti-2", now).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_role_and_wallet_owner_storage() {
+        let storage = WalletStorage::new_with_url("sqlite::memory:").await.unwrap();
+
+        assert!(storage.add_user_role("user-1", "user").await.unwrap());
+        assert!(!storage.add_user_role("user-1", "user").await.unwrap());
+        assert!(storage.add_user_role("user-1", "auditor").await.unwrap());
+        assert_eq!(storage.get_user_roles("user-1").await.unwrap(), vec!["auditor", "user"]);
+        assert!(storage.remove_user_role("user-1", "auditor").await.unwrap());
+        assert!(!storage.remove_user_role("user-1", "auditor").await.unwrap());
+        assert!(storage.get_user_roles("user-2").await.unwrap().is_empty());
+
+        storage.store_wallet("owned", b"data", false).await.unwrap();
+        storage.set_wallet_owner("owned", "user-1").await.unwrap();
+        assert_eq!(storage.get_wallet_owner("owned").await.unwrap().as_deref(), Some("user-1"));
+        assert_eq!(storage.list_owned_wallets("user-1").await.unwrap(), vec!["owned"]);
+
+        // deleting the wallet releases its name
+        storage.delete_wallet("owned").await.unwrap();
+        assert!(storage.get_wallet_owner("owned").await.unwrap().is_none());
+        assert!(storage.list_owned_wallets("user-1").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_second_factor_and_lockout_storage() {
+        let storage = WalletStorage::new_with_url("sqlite::memory:").await.unwrap();
+        let now = Utc::now();
+        storage
+            .store_totp_credential(&TotpCredentialRecord {
+                user_id: "user-1".to_string(),
+                secret: "SECRET".to_string(),
+                confirmed_at: None,
+                last_used_step: None,
+                created_at: now,
+            })
+            .await
+            .unwrap();
+        assert!(storage.confirm_totp_credential("user-1", now).await.unwrap());
+        assert!(!storage.confirm_totp_credential("user-1", now).await.unwrap());
+        assert!(storage.use_totp_step("user-1", 10).await.unwrap());
+        assert!(!storage.use_totp_step("user-1", 10).await.unwrap());
+        assert!(!storage.use_totp_step("user-1", 9).await.unwrap());
+        assert!(storage.use_totp_step("user-1", 11).await.unwrap());
+
+        let hashes = vec!["a".to_string(), "b".to_string()];
+        storage.store_recovery_codes("user-1", &hashes).await.unwrap();
+        assert!(storage.use_recovery_code("user-1", "a", now).await.unwrap());
+        assert!(!storage.use_recovery_code("user-1", "a", now).await.unwrap());
+        assert!(!storage.use_recovery_code("user-2", "b", now).await.unwrap());
+        assert_eq!(storage.count_unused_recovery_codes("user-1").await.unwrap(), 1);
+        storage.delete_totp_credential("user-1").await.unwrap();
+        assert!(storage.get_totp_credential("user-1").await.unwrap().is_none());
+        assert_eq!(storage.count_unused_recovery_codes("user-1").await.unwrap(), 0);
+
+        assert_eq!(storage.increment_login_failures("user-1").await.unwrap(), 1);
+        assert_eq!(storage.increment_login_failures("user-1").await.unwrap(), 2);
+        storage.lock_user("user-1", now).await.unwrap();
+        assert!(storage.get_locked_until("user-1").await.unwrap().is_some());
+        assert_eq!(storage.increment_login_failures("user-1").await.unwrap(), 1);
+        storage.clear_login_failures("user-1").await.unwrap();
+        assert!(storage.get_locked_until("user-1").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_spending_policy_storage() {
+        let storage = WalletStorage::new_with_url("sqlite::memory:").await.unwrap();
+        let now = Utc::now();
+
+        assert!(storage.get_spending_policy("wallet-1").await.unwrap().is_none());
+        storage.set_spending_policy("wallet-1", r#"{"denylist":[]}"#).await.unwrap();
+        storage.set_spending_policy("wallet-1", r#"{"denylist":["0xbad"]}"#).await.unwrap();
+        assert_eq!(
+            storage.get_spending_policy("wallet-1").await.unwrap().as_deref(),
+            Some(r#"{"denylist":["0xbad"]}"#)
+        );
+        assert!(storage.delete_spending_policy("wallet-1").await.unwrap());
+        assert!(!storage.delete_spending_policy("wallet-1").await.unwrap());
+
+        for (offset, amount) in [(-7200, 1.0), (-60, 2.5)] {
+            storage
+                .record_policy_spend(&PolicySpendRecord {
+                    id: 0,
+                    wallet_id: "wallet-1".to_string(),
+                    tx_hash: format!("0x{}", amount),
+                    transaction_type: "Transfer".to_string(),
+                    network: "eth".to_string(),
+                    asset: "ETH".to_string(),
+                    recipient: Some("0xbob".to_string()),
+                    amount,
+                    created_at: now + chrono::Duration::seconds(offset),
+                })
+                .await
+                .unwrap();
+        }
+        let recent = storage
+            .get_policy_spends_since("wallet-1", now - chrono::Duration::hours(1))
+            .await
+            .unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].amount, 2.5);
+        assert!(storage
+            .get_policy_spends_since("wallet-2", now - chrono::Duration::days(1))
+            .await
+            .unwrap()
+            .is_empty());
+
+        let earlier = now - chrono::Duration::days(1);
+        assert_eq!(storage.note_recipient("wallet-1", "0xbob", earlier).await.unwrap(), earlier);
+        assert_eq!(storage.note_recipient("wallet-1", "0xbob", now).await.unwrap(), earlier);
+        assert_eq!(storage.note_recipient("wallet-2", "0xbob", now).await.unwrap(), now);
+    }
+
+    #[tokio::test]
+    async fn test_approval_request_storage() {
+        use crate::security::approval::{ApprovalSettings, HeldOperation};
+
+        let storage = WalletStorage::new_with_url("sqlite::memory:").await.unwrap();
+        let operation = HeldOperation::Bridge {
+            from_chain: "eth".to_string(),
+            to_chain: "solana".to_string(),
+            token: "USDC".to_string(),
+            amount: "100".to_string(),
+        };
+        let request = ApprovalRequest::new(
+            "wallet-1",
+            "main",
+            operation,
+            "Spending policy: over threshold",
+            Some("carol"),
+            &ApprovalSettings { required_approvals: 2, ..ApprovalSettings::default() },
+        );
+        storage.store_approval_request(&request).await.unwrap();
+        assert!(storage.get_approval_request("missing").await.unwrap().is_none());
+
+        let vote = ApprovalVote {
+            approver: "alice".to_string(),
+            approved: true,
+            comment: Some("ok".to_string()),
+            created_at: Utc::now(),
+        };
+        assert!(storage.add_approval_vote(&request.id, &vote).await.unwrap());
+        assert!(!storage.add_approval_vote(&request.id, &vote).await.unwrap());
+
+        let loaded = storage.get_approval_request(&request.id).await.unwrap().unwrap();
+        assert_eq!(loaded.operation, request.operation);
+        assert_eq!(loaded.required_approvals, 2);
+        assert_eq!(loaded.requested_by.as_deref(), Some("carol"));
+        assert_eq!(loaded.votes, vec![vote]);
+
+        let pending = ApprovalStatus::Pending;
+        assert!(storage
+            .update_approval_status(&request.id, pending, ApprovalStatus::Approved, None)
+            .await
+            .unwrap());
+        assert!(!storage
+            .update_approval_status(&request.id, pending, ApprovalStatus::Rejected, None)
+            .await
+            .unwrap());
+        storage
+            .update_approval_status(
+                &request.id,
+                ApprovalStatus::Approved,
+                ApprovalStatus::Executed,
+                Some("bridge-1"),
+            )
+            .await
+            .unwrap();
+
+        assert!(storage.list_approval_requests(Some(pending)).await.unwrap().is_empty());
+        let all = storage.list_approval_requests(None).await.unwrap();
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].status, ApprovalStatus::Executed);
+        assert_eq!(all[0].result.as_deref(), Some("bridge-1"));
+    }
+
+    #[tokio::test]
+    async fn test_audit_log_chain_and_queries() {
+        use crate::audit::chain::verify_chain;
+        use crate::audit::context::AuditContext;
+
+        let storage = WalletStorage::new_with_url("sqlite::memory:").await.unwrap();
+        storage.log_action("w1", "wallet_created", "", None, None).await.unwrap();
+        let context = AuditContext {
+            ip_address: Some("10.0.0.1".to_string()),
+            request_id: Some("req-1".to_string()),
+            ..AuditContext::default()
+        };
+        context
+            .with_actor("alice")
+            .scope(async {
+                storage.log_action("w1", "transaction_sent", "0xabc", None, None).await.unwrap();
+                storage.log_event(None, "login", "alice").await.unwrap();
+            })
+            .await;
+
+        let logs = storage.get_audit_logs_through(i64::MAX).await.unwrap();
+        assert_eq!(logs.len(), 3);
+        assert_eq!(logs[1].actor.as_deref(), Some("alice"));
+        assert_eq!(logs[1].ip_address.as_deref(), Some("10.0.0.1"));
+        assert_eq!(logs[2].prev_hash, logs[1].hash);
+        assert!(verify_chain(&logs, &[]).is_valid());
+
+        let by_actor = AuditQuery { actor: Some("alice".to_string()), ..AuditQuery::default() };
+        let found = storage.query_audit_logs(&by_actor).await.unwrap();
+        assert_eq!(
+            found.iter().map(|l| l.action.as_str()).collect::<Vec<_>>(),
+            ["login", "transaction_sent"]
+        );
+        assert_eq!(storage.count_audit_logs(&by_actor).await.unwrap(), 2);
+        let page = AuditQuery {
+            wallet_id: Some("w1".to_string()),
+            limit: 1,
+            offset: 1,
+            ..AuditQuery::default()
+        };
+        let found = storage.query_audit_logs(&page).await.unwrap();
+        assert_eq!(found[0].action, "wallet_created");
+        assert_eq!(storage.count_audit_logs(&page).await.unwrap(), 2);
+
+        // rows written before chaining are hashed once, by the migration
+        sqlx::query("UPDATE audit_logs SET prev_hash = NULL, hash = NULL")
+            .execute(&storage.pool)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM schema_migrations").execute(&storage.pool).await.unwrap();
+        storage.chain_unhashed_audit_logs().await.unwrap();
+        let rechained = storage.get_audit_logs_through(i64::MAX).await.unwrap();
+        assert_eq!(rechained[2].hash, logs[2].hash);
+        assert!(verify_chain(&rechained, &[]).is_valid());
+
+        // once migrated, stripped hashes are not rewritten and fail verification
+        sqlx::query("UPDATE audit_logs SET prev_hash = NULL, hash = NULL WHERE id > 1")
+            .execute(&storage.pool)
+            .await
+            .unwrap();
+        storage.chain_unhashed_audit_logs().await.unwrap();
+        let stripped = storage.get_audit_logs_through(i64::MAX).await.unwrap();
+        assert!(stripped[2].hash.is_none());
+        assert_eq!(verify_chain(&stripped, &[]).first_invalid_row, Some(2));
+        sqlx::query("UPDATE audit_logs SET prev_hash = ?1, hash = ?2 WHERE id = ?3")
+            .bind(&logs[1].prev_hash)
+            .bind(&logs[1].hash)
+            .bind(logs[1].id)
+            .execute(&storage.pool)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE audit_logs SET prev_hash = ?1, hash = ?2 WHERE id = ?3")
+            .bind(&logs[2].prev_hash)
+            .bind(&logs[2].hash)
+            .bind(logs[2].id)
+            .execute(&storage.pool)
+            .await
+            .unwrap();
+
+        // an edited row no longer matches its hash
+        sqlx::query("UPDATE audit_logs SET details = '0xdef' WHERE id = 2")
+            .execute(&storage.pool)
+            .await
+            .unwrap();
+        let tampered = storage.get_audit_logs_through(i64::MAX).await.unwrap();
+        assert_eq!(verify_chain(&tampered, &[]).first_invalid_row, Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_sanctions_list_storage() {
+        use crate::security::sanctions::SanctionsFormat;
+
+        let storage = WalletStorage::new_with_url("sqlite::memory:").await.unwrap();
+        let ofac = SanctionsList::parse("ofac", SanctionsFormat::Csv, "0xaaa,ETH\n0xbbb").unwrap();
+        let internal =
+            SanctionsList::parse("internal", SanctionsFormat::Csv, "0xbbb\n0xccc").unwrap();
+        storage.replace_sanctions_list(&ofac).await.unwrap();
+        storage.replace_sanctions_list(&internal).await.unwrap();
+
+        let mut addresses = storage.get_sanctioned_addresses().await.unwrap();
+        addresses.sort();
+        assert_eq!(addresses, vec!["0xaaa", "0xbbb", "0xccc"]);
+
+        // a new version replaces only its own source
+        let ofac = SanctionsList::parse("ofac", SanctionsFormat::Csv, "0xddd").unwrap();
+        storage.replace_sanctions_list(&ofac).await.unwrap();
+        let mut addresses = storage.get_sanctioned_addresses().await.unwrap();
+        addresses.sort();
+        assert_eq!(addresses, vec!["0xbbb", "0xccc", "0xddd"]);
+
+        let lists = storage.get_sanctions_lists().await.unwrap();
+        assert_eq!(lists.len(), 2);
+        assert_eq!(lists[1].source, "ofac");
+        assert_eq!(lists[1].version, ofac.version);
+        assert_eq!(lists[1].entry_count, 1);
+        assert_eq!(lists[1].format, "csv");
     }
 }
@@ -2,6 +2,8 @@
 //! 配置管理模块
 //! 提供配置文件的读取、验证和管理功能
 
+use crate::audit::export::{ExportFormat, ExportSink};
+use crate::security::sanctions::SanctionsFormat;
 use crate::tools::error::WalletError;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -85,6 +87,34 @@ pub struct SecurityConfig {
     pub enable_2fa: bool,
     /// 合规检查配置
     pub compliance: ComplianceConfig,
+    /// 交易审批配置
+    #[serde(default)]
+    pub approvals: ApprovalConfig,
+    /// 审计日志签名检查点间隔（秒），0 表示不自动签名
+    #[serde(default = "default_audit_checkpoint_interval")]
+    pub audit_checkpoint_interval: u64,
+    /// 固定的制品签名密钥 ID；设置后，数据库中的密钥与之不符时签名和验证都会失败
+    #[serde(default)]
+    pub artifact_key_id: Option<String>,
+}
+
+fn default_audit_checkpoint_interval() -> u64 {
+    3600
+}
+
+/// 交易审批配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalConfig {
+    /// 执行前所需的批准人数
+    pub required_approvals: u32,
+    /// 审批过期时间（秒）
+    pub expiry: u64,
+}
+
+impl Default for ApprovalConfig {
+    fn default() -> Self {
+        Self { required_approvals: 1, expiry: 86400 }
+    }
 }
 
 /// 合规配置
@@ -100,6 +130,27 @@ pub struct ComplianceConfig {
     pub transaction_limits: HashMap<String, f64>,
     /// 是否要求 KYC
     pub require_kyc: bool,
+    /// 定期导入的制裁名单文件
+    #[serde(default)]
+    pub sanctions_lists: Vec<SanctionsListConfig>,
+    /// 制裁名单重新加载间隔（秒）
+    #[serde(default = "default_sanctions_reload_interval")]
+    pub sanctions_reload_interval: u64,
+}
+
+fn default_sanctions_reload_interval() -> u64 {
+    3600
+}
+
+/// 制裁名单文件配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SanctionsListConfig {
+    /// 名单来源，例如 "ofac"
+    pub source: String,
+    /// 本地文件路径
+    pub path: String,
+    /// 文件格式
+    pub format: SanctionsFormat,
 }
 
 /// 存储配置
@@ -134,6 +185,44 @@ pub struct MonitoringConfig {
     pub log_rotation_size: u64,
     /// 日志保留天数
     pub log_retention_days: u32,
+    /// 审计日志 SIEM 导出
+    #[serde(default)]
+    pub siem_exports: Vec<SiemExportConfig>,
+    /// 实时推送订阅钱包余额的轮询间隔（秒），0 表示不推送余额变化
+    #[serde(default = "default_balance_poll_interval")]
+    pub balance_poll_interval: u64,
+    /// 失败或超时跨链转账的自动恢复间隔（秒），0 表示只通过 API 手动恢复
+    #[serde(default = "default_bridge_recovery_interval")]
+    pub bridge_recovery_interval: u64,
+}
+
+fn default_balance_poll_interval() -> u64 {
+    15
+}
+
+fn default_bridge_recovery_interval() -> u64 {
+    300
+}
+
+/// SIEM 导出配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SiemExportConfig {
+    /// 导出名称，用于保存导出进度
+    pub name: String,
+    /// 输出格式
+    pub format: ExportFormat,
+    /// 目标：udp://host:port、tcp://host:port 或文件路径
+    pub endpoint: String,
+    /// 导出间隔（秒）
+    #[serde(default = "default_siem_export_interval")]
+    pub interval: u64,
+    /// 写入 syslog 的主机名
+    #[serde(default)]
+    pub hostname: Option<String>,
+}
+
+fn default_siem_export_interval() -> u64 {
+    60
 }
 
 /// 国际化配置
@@ -293,7 +382,12 @@ impl Default for Config {
                         limits
                     },
                     require_kyc: false,
+                    sanctions_lists: vec![],
+                    sanctions_reload_interval: default_sanctions_reload_interval(),
                 },
+                approvals: ApprovalConfig::default(),
+                audit_checkpoint_interval: default_audit_checkpoint_interval(),
+                artifact_key_id: None,
             },
             storage: StorageConfig {
                 database_type: "SQLite".to_string(),
@@ -315,6 +409,9 @@ impl Default for Config {
                 },
                 log_rotation_size: 100, // 100 MB
                 log_retention_days: 30,
+                siem_exports: vec![],
+                balance_poll_interval: default_balance_poll_interval(),
+                bridge_recovery_interval: default_bridge_recovery_interval(),
             },
             i18n: I18nConfig {
                 default_language: "en".to_string(),
@@ -358,6 +455,20 @@ impl Config {
             ));
         }
 
+        if self.security.approvals.required_approvals == 0 {
+            return Err(WalletError::InvalidInput(
+                "At least one approval must be required".to_string(),
+            ));
+        }
+
+        if !self.security.compliance.sanctions_lists.is_empty()
+            && self.security.compliance.sanctions_reload_interval == 0
+        {
+            return Err(WalletError::InvalidInput(
+                "Sanctions reload interval must be greater than 0".to_string(),
+            ));
+        }
+
         // 验证存储配置
         if self.storage.database_url.is_empty() {
             return Err(WalletError::InvalidInput("Database URL cannot be empty".to_string()));
@@ -367,6 +478,16 @@ impl Config {
         if self.monitoring.enabled && self.monitoring.metrics_interval == 0 {
             return Err(WalletError::InvalidInput("Metrics interval cannot be zero".to_string()));
         }
+        for export in &self.monitoring.siem_exports {
+            if export.name.is_empty() || export.interval == 0 {
+                return Err(WalletError::InvalidInput(
+                    "SIEM exports need a name and an interval greater than 0".to_string(),
+                ));
+            }
+            if let Err(e) = export.endpoint.parse::<ExportSink>() {
+                return Err(WalletError::InvalidInput(e.to_string()));
+            }
+        }
 
         // 验证国际化配置
         if self.i18n.supported_languages.is_empty() {
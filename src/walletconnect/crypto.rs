@@ -0,0 +1,110 @@
+// src/walletconnect/crypto.rs
+//! WalletConnect v2 key agreement and message envelopes.
+//!
+//! Peers agree on a session key with X25519 followed by HKDF-SHA256, and a
+//! topic is the SHA-256 of its key. Messages are ChaCha20-Poly1305 sealed and
+//! base64-encoded as `type (1) || [sender public key (32)] || iv (12) ||
+//! ciphertext`; type 0 is used between peers that already share the key, type
+//! 1 carries the sender's public key for the first message.
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use curve25519_dalek::montgomery::MontgomeryPoint;
+use hkdf::Hkdf;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+const TYPE_0: u8 = 0;
+const TYPE_1: u8 = 1;
+const IV_LENGTH: usize = 12;
+
+/// An X25519 key pair for one session proposal.
+pub struct KeyPair {
+    secret: Zeroizing<[u8; 32]>,
+    pub public_key: [u8; 32],
+}
+
+impl KeyPair {
+    pub fn generate() -> Self {
+        let mut secret = Zeroizing::new([0u8; 32]);
+        rand::thread_rng().fill_bytes(&mut secret[..]);
+        let public_key = MontgomeryPoint::mul_base_clamped(*secret).to_bytes();
+        Self { secret, public_key }
+    }
+
+    /// Symmetric key shared with the owner of `peer_public_key`.
+    pub fn derive_sym_key(&self, peer_public_key: &[u8; 32]) -> [u8; 32] {
+        let shared = Zeroizing::new(MontgomeryPoint(*peer_public_key).mul_clamped(*self.secret));
+        let mut sym_key = [0u8; 32];
+        Hkdf::<Sha256>::new(None, shared.as_bytes())
+            .expand(&[], &mut sym_key)
+            .expect("32 bytes is a valid HKDF-SHA256 output length");
+        sym_key
+    }
+}
+
+/// Topic a key's messages are published on.
+pub fn topic_for_key(sym_key: &[u8; 32]) -> String {
+    hex::encode(Sha256::digest(sym_key))
+}
+
+pub fn random_sym_key() -> [u8; 32] {
+    let mut key = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut key);
+    key
+}
+
+/// Seals `plaintext` in a type 0 envelope.
+pub fn encrypt(sym_key: &[u8; 32], plaintext: &[u8]) -> Result<String> {
+    let mut iv = [0u8; IV_LENGTH];
+    rand::thread_rng().fill_bytes(&mut iv);
+    let sealed = ChaCha20Poly1305::new(Key::from_slice(sym_key))
+        .encrypt(Nonce::from_slice(&iv), plaintext)
+        .map_err(|_| anyhow!("Envelope encryption failed"))?;
+    let mut envelope = Vec::with_capacity(1 + IV_LENGTH + sealed.len());
+    envelope.push(TYPE_0);
+    envelope.extend_from_slice(&iv);
+    envelope.extend_from_slice(&sealed);
+    Ok(base64::engine::general_purpose::STANDARD.encode(envelope))
+}
+
+/// Opens a type 0 or type 1 envelope.
+pub fn decrypt(sym_key: &[u8; 32], message: &str) -> Result<Vec<u8>> {
+    let envelope = base64::engine::general_purpose::STANDARD
+        .decode(message.trim())
+        .map_err(|_| anyhow!("Envelope is not base64"))?;
+    let body = match envelope.split_first() {
+        Some((&TYPE_0, body)) => body,
+        Some((&TYPE_1, body)) if body.len() >= 32 => &body[32..],
+        Some((kind, _)) => return Err(anyhow!("Unsupported envelope type {}", kind)),
+        None => return Err(anyhow!("Empty envelope")),
+    };
+    if body.len() < IV_LENGTH {
+        return Err(anyhow!("Envelope too short"));
+    }
+    let (iv, sealed) = body.split_at(IV_LENGTH);
+    ChaCha20Poly1305::new(Key::from_slice(sym_key))
+        .decrypt(Nonce::from_slice(iv), sealed)
+        .map_err(|_| anyhow!("Envelope decryption failed"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_agreement_and_envelopes() {
+        let wallet = KeyPair::generate();
+        let dapp = KeyPair::generate();
+        let key = wallet.derive_sym_key(&dapp.public_key);
+        assert_eq!(key, dapp.derive_sym_key(&wallet.public_key));
+        assert_eq!(topic_for_key(&key).len(), 64);
+
+        let sealed = encrypt(&key, b"{\"id\":1}").unwrap();
+        assert_eq!(decrypt(&key, &sealed).unwrap(), b"{\"id\":1}");
+        assert!(decrypt(&random_sym_key(), &sealed).is_err());
+        assert!(decrypt(&key, "AQ==").is_err());
+    }
+}
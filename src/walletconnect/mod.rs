@@ -0,0 +1,270 @@
+// src/walletconnect/mod.rs
+//! WalletConnect v2 responder: lets dApps connect to wallets held here.
+//!
+//! A dApp shows a pairing URI; pairing subscribes to its topic on the relay,
+//! where the dApp publishes a session proposal. Approving the proposal scopes
+//! the session to the EVM accounts of one wallet and settles it on a new topic
+//! derived by X25519 key agreement. Requests the dApp sends over the session
+//! (`eth_sendTransaction`, `personal_sign`, `eth_signTypedData_v4`) are queued
+//! until they are approved or rejected through the API.
+//!
+//! The relay connection is behind the `Relay` trait; `WebSocketRelay` talks
+//! to a WalletConnect v2 relay, `InMemoryRelay` serves tests and in-process
+//! dApps.
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub mod crypto;
+pub mod relay;
+pub mod responder;
+pub mod uri;
+pub mod websocket;
+
+pub use relay::{InMemoryRelay, Relay, RelayMessage};
+pub use responder::WalletConnect;
+pub use uri::PairingUri;
+pub use websocket::WebSocketRelay;
+
+/// CAIP-2 namespace of EVM chains.
+pub const EIP155: &str = "eip155";
+
+pub const ETH_SEND_TRANSACTION: &str = "eth_sendTransaction";
+pub const PERSONAL_SIGN: &str = "personal_sign";
+pub const ETH_SIGN_TYPED_DATA_V4: &str = "eth_signTypedData_v4";
+
+/// Request methods a session can be granted.
+pub const SUPPORTED_METHODS: [&str; 3] =
+    [ETH_SEND_TRANSACTION, PERSONAL_SIGN, ETH_SIGN_TYPED_DATA_V4];
+pub const SUPPORTED_EVENTS: [&str; 2] = ["chainChanged", "accountsChanged"];
+
+/// Error codes sent back to dApps.
+pub const USER_REJECTED: i64 = 5000;
+pub const UNSUPPORTED_CHAINS: i64 = 5100;
+pub const UNSUPPORTED_METHODS: i64 = 5101;
+pub const USER_DISCONNECTED: i64 = 6000;
+
+/// How a peer describes itself.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Metadata {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub icons: Vec<String>,
+}
+
+/// Chains, methods and events a dApp asks for in one namespace.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Namespace {
+    #[serde(default)]
+    pub chains: Vec<String>,
+    #[serde(default)]
+    pub methods: Vec<String>,
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+/// What a session is allowed: CAIP-10 accounts plus methods and events.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionNamespace {
+    pub chains: Vec<String>,
+    pub accounts: Vec<String>,
+    pub methods: Vec<String>,
+    pub events: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionProposal {
+    /// JSON-RPC id of the proposal, used to approve or reject it.
+    pub id: u64,
+    pub pairing_topic: String,
+    pub proposer: Metadata,
+    pub required_namespaces: BTreeMap<String, Namespace>,
+    pub optional_namespaces: BTreeMap<String, Namespace>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub topic: String,
+    pub wallet: String,
+    pub peer: Metadata,
+    pub namespaces: BTreeMap<String, SessionNamespace>,
+    pub expiry: DateTime<Utc>,
+}
+
+impl Session {
+    /// Whether the session grants `method` on the CAIP-2 chain `chain_id`.
+    pub fn permits(&self, chain_id: &str, method: &str) -> Result<(), i64> {
+        let namespace = chain_id.split(':').next().and_then(|ns| self.namespaces.get(ns));
+        let Some(namespace) = namespace.filter(|ns| ns.chains.iter().any(|c| c == chain_id)) else {
+            return Err(UNSUPPORTED_CHAINS);
+        };
+        if !namespace.methods.iter().any(|m| m == method) {
+            return Err(UNSUPPORTED_METHODS);
+        }
+        Ok(())
+    }
+}
+
+/// A dApp request waiting for approval.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionRequest {
+    /// JSON-RPC id of the request, used to approve or reject it.
+    pub id: u64,
+    pub topic: String,
+    pub wallet: String,
+    pub peer: Metadata,
+    /// CAIP-2 chain, e.g. `eip155:1`.
+    pub chain_id: String,
+    pub method: String,
+    pub params: Value,
+    pub received_at: DateTime<Utc>,
+}
+
+/// Scopes a proposal to `accounts` (CAIP-10, `eip155:<chain>:<address>`).
+/// Every required chain must have an account and every required method must
+/// be supported; the session is granted the requested chains the wallet has
+/// accounts on, and the requested methods and events it supports.
+pub fn session_namespaces(
+    proposal: &SessionProposal,
+    accounts: &[String],
+) -> Result<BTreeMap<String, SessionNamespace>> {
+    let account_chains: Vec<&str> =
+        accounts.iter().filter_map(|a| a.rsplit_once(':').map(|(chain, _)| chain)).collect();
+
+    let mut requested_chains = Vec::new();
+    let mut requested_methods = Vec::new();
+    let mut requested_events = Vec::new();
+    for (required, namespaces) in
+        [(true, &proposal.required_namespaces), (false, &proposal.optional_namespaces)]
+    {
+        for (key, namespace) in namespaces {
+            let chains = namespace_chains(key, namespace);
+            if !key.starts_with(EIP155) {
+                if required {
+                    return Err(anyhow!("Unsupported namespace {}", key));
+                }
+                continue;
+            }
+            if required {
+                if let Some(chain) = chains.iter().find(|c| !account_chains.contains(&c.as_str())) {
+                    return Err(anyhow!("The wallet has no account on {}", chain));
+                }
+                if let Some(method) =
+                    namespace.methods.iter().find(|m| !SUPPORTED_METHODS.contains(&m.as_str()))
+                {
+                    return Err(anyhow!("Unsupported method {}", method));
+                }
+            }
+            requested_chains.extend(chains);
+            requested_methods.extend(namespace.methods.iter().cloned());
+            requested_events.extend(namespace.events.iter().cloned());
+        }
+    }
+
+    let chains: Vec<String> = account_chains
+        .iter()
+        .filter(|c| requested_chains.iter().any(|r| r.as_str() == **c))
+        .map(|c| c.to_string())
+        .collect();
+    if chains.is_empty() {
+        return Err(anyhow!("The wallet has no account on the requested chains"));
+    }
+    let accounts = accounts
+        .iter()
+        .filter(|a| a.rsplit_once(':').is_some_and(|(chain, _)| chains.iter().any(|c| c == chain)))
+        .cloned()
+        .collect();
+    let granted = |requested: &[String], supported: &[&str]| -> Vec<String> {
+        supported
+            .iter()
+            .filter(|s| requested.iter().any(|r| r == *s))
+            .map(|s| s.to_string())
+            .collect()
+    };
+
+    let namespace = SessionNamespace {
+        chains,
+        accounts,
+        methods: granted(&requested_methods, &SUPPORTED_METHODS),
+        events: granted(&requested_events, &SUPPORTED_EVENTS),
+    };
+    Ok(BTreeMap::from([(EIP155.to_string(), namespace)]))
+}
+
+/// Chains of a namespace; a key like `eip155:1` names its only chain.
+fn namespace_chains(key: &str, namespace: &Namespace) -> Vec<String> {
+    if namespace.chains.is_empty() && key.contains(':') {
+        vec![key.to_string()]
+    } else {
+        namespace.chains.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proposal(required: Namespace, optional: Namespace) -> SessionProposal {
+        SessionProposal {
+            id: 1,
+            pairing_topic: "pairing".to_string(),
+            proposer: Metadata::default(),
+            required_namespaces: BTreeMap::from([(EIP155.to_string(), required)]),
+            optional_namespaces: BTreeMap::from([(EIP155.to_string(), optional)]),
+        }
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_session_namespaces_are_scoped_to_wallet_accounts() {
+        let accounts = strings(&["eip155:1:0xabc", "eip155:137:0xabc"]);
+        let required = Namespace {
+            chains: strings(&["eip155:1"]),
+            methods: strings(&["eth_sendTransaction", "personal_sign"]),
+            events: strings(&["chainChanged"]),
+        };
+        let optional = Namespace {
+            chains: strings(&["eip155:10", "eip155:137"]),
+            methods: strings(&["eth_signTypedData_v4", "wallet_switchEthereumChain"]),
+            events: vec![],
+        };
+
+        let granted = session_namespaces(&proposal(required.clone(), optional), &accounts).unwrap();
+        let eip155 = &granted[EIP155];
+        assert_eq!(eip155.chains, strings(&["eip155:1", "eip155:137"]));
+        assert_eq!(eip155.accounts, accounts);
+        assert_eq!(eip155.methods, strings(&SUPPORTED_METHODS));
+        assert_eq!(eip155.events, strings(&["chainChanged"]));
+
+        let session = Session {
+            topic: "t".to_string(),
+            wallet: "w".to_string(),
+            peer: Metadata::default(),
+            namespaces: granted,
+            expiry: Utc::now(),
+        };
+        assert_eq!(session.permits("eip155:137", PERSONAL_SIGN), Ok(()));
+        assert_eq!(session.permits("eip155:10", PERSONAL_SIGN), Err(UNSUPPORTED_CHAINS));
+        assert_eq!(session.permits("eip155:1", "eth_sign"), Err(UNSUPPORTED_METHODS));
+
+        let unknown_chain = Namespace { chains: strings(&["eip155:56"]), ..required.clone() };
+        assert!(
+            session_namespaces(&proposal(unknown_chain, Namespace::default()), &accounts).is_err()
+        );
+        let unknown_method = Namespace { methods: strings(&["eth_sign"]), ..required };
+        assert!(
+            session_namespaces(&proposal(unknown_method, Namespace::default()), &accounts).is_err()
+        );
+    }
+}
@@ -0,0 +1,169 @@
+// src/walletconnect/relay.rs
+//! Transport between the wallet and dApps.
+//!
+//! WalletConnect peers never talk directly: both subscribe to topics on a
+//! relay and publish encrypted envelopes to them. `Relay` abstracts that
+//! transport; `InMemoryRelay` delivers between handles of the same hub, which
+//! is enough for tests and for wiring a dApp and the wallet in one process.
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+/// An envelope published on `topic`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelayMessage {
+    pub topic: String,
+    pub message: String,
+}
+
+#[async_trait]
+pub trait Relay: Send + Sync {
+    async fn subscribe(&self, topic: &str) -> Result<()>;
+
+    async fn unsubscribe(&self, topic: &str) -> Result<()>;
+
+    /// Publishes an envelope. `tag` identifies the RPC it carries so the relay
+    /// can route push notifications; it does not affect delivery.
+    async fn publish(&self, topic: &str, message: &str, tag: u32) -> Result<()>;
+
+    /// Waits for the next envelope on a subscribed topic; `None` once the
+    /// relay has shut down.
+    async fn next_message(&self) -> Option<RelayMessage>;
+}
+
+struct Subscriber {
+    topics: HashSet<String>,
+    sender: UnboundedSender<RelayMessage>,
+}
+
+#[derive(Default)]
+struct Hub {
+    subscribers: HashMap<usize, Subscriber>,
+    /// Envelopes nobody else was subscribed to yet, kept like the relay's
+    /// mailbox until a subscription picks them up.
+    mailbox: HashMap<String, Vec<(usize, String)>>,
+    next_id: usize,
+}
+
+/// One connection to an in-process relay. `connect` opens another connection
+/// to the same hub.
+pub struct InMemoryRelay {
+    hub: Arc<Mutex<Hub>>,
+    id: usize,
+    inbox: tokio::sync::Mutex<UnboundedReceiver<RelayMessage>>,
+}
+
+impl InMemoryRelay {
+    pub fn new() -> Self {
+        Self::join(Arc::new(Mutex::new(Hub::default())))
+    }
+
+    pub fn connect(&self) -> Self {
+        Self::join(Arc::clone(&self.hub))
+    }
+
+    fn join(hub: Arc<Mutex<Hub>>) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let id = {
+            let mut state = hub.lock().expect("relay hub lock poisoned");
+            let id = state.next_id;
+            state.next_id += 1;
+            state.subscribers.insert(id, Subscriber { topics: HashSet::new(), sender });
+            id
+        };
+        Self { hub, id, inbox: tokio::sync::Mutex::new(receiver) }
+    }
+
+    fn hub(&self) -> Result<std::sync::MutexGuard<'_, Hub>> {
+        self.hub.lock().map_err(|_| anyhow!("Relay hub lock poisoned"))
+    }
+}
+
+impl Default for InMemoryRelay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for InMemoryRelay {
+    fn drop(&mut self) {
+        if let Ok(mut hub) = self.hub.lock() {
+            hub.subscribers.remove(&self.id);
+        }
+    }
+}
+
+#[async_trait]
+impl Relay for InMemoryRelay {
+    async fn subscribe(&self, topic: &str) -> Result<()> {
+        let mut hub = self.hub()?;
+        let waiting = hub.mailbox.remove(topic).unwrap_or_default();
+        let (mine, others): (Vec<_>, Vec<_>) =
+            waiting.into_iter().partition(|(sender, _)| *sender == self.id);
+        if !mine.is_empty() {
+            hub.mailbox.insert(topic.to_string(), mine);
+        }
+        let subscriber = hub.subscribers.get_mut(&self.id).expect("joined the hub");
+        subscriber.topics.insert(topic.to_string());
+        for (_, message) in others {
+            let _ = subscriber.sender.send(RelayMessage { topic: topic.to_string(), message });
+        }
+        Ok(())
+    }
+
+    async fn unsubscribe(&self, topic: &str) -> Result<()> {
+        if let Some(subscriber) = self.hub()?.subscribers.get_mut(&self.id) {
+            subscriber.topics.remove(topic);
+        }
+        Ok(())
+    }
+
+    async fn publish(&self, topic: &str, message: &str, _tag: u32) -> Result<()> {
+        let mut hub = self.hub()?;
+        let mut delivered = false;
+        for (id, subscriber) in &hub.subscribers {
+            if *id != self.id && subscriber.topics.contains(topic) {
+                let message =
+                    RelayMessage { topic: topic.to_string(), message: message.to_string() };
+                delivered |= subscriber.sender.send(message).is_ok();
+            }
+        }
+        if !delivered {
+            hub.mailbox.entry(topic.to_string()).or_default().push((self.id, message.to_string()));
+        }
+        Ok(())
+    }
+
+    async fn next_message(&self) -> Option<RelayMessage> {
+        self.inbox.lock().await.recv().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_in_memory_relay_delivers_to_other_subscribers() {
+        let wallet = InMemoryRelay::new();
+        let dapp = wallet.connect();
+
+        // published before anyone listens: kept until the wallet subscribes
+        dapp.publish("pairing", "proposal", 1100).await.unwrap();
+        wallet.subscribe("pairing").await.unwrap();
+        dapp.subscribe("pairing").await.unwrap();
+        assert_eq!(wallet.next_message().await.unwrap().message, "proposal");
+
+        wallet.publish("pairing", "response", 1101).await.unwrap();
+        let received = dapp.next_message().await.unwrap();
+        assert_eq!(received, RelayMessage { topic: "pairing".into(), message: "response".into() });
+
+        wallet.unsubscribe("pairing").await.unwrap();
+        dapp.publish("pairing", "late", 1100).await.unwrap();
+        wallet.subscribe("pairing").await.unwrap();
+        assert_eq!(wallet.next_message().await.unwrap().message, "late");
+    }
+}
@@ -0,0 +1,524 @@
+// src/walletconnect/responder.rs
+//! The wallet side of WalletConnect v2 sessions.
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use super::crypto::{self, KeyPair};
+use super::relay::{Relay, RelayMessage};
+use super::uri::PairingUri;
+use super::{
+    Metadata, Namespace, Session, SessionNamespace, SessionProposal, SessionRequest,
+    USER_DISCONNECTED, USER_REJECTED,
+};
+
+/// Sessions last a week unless the dApp or the wallet ends them.
+const SESSION_TTL_DAYS: i64 = 7;
+
+const RELAY_PROTOCOL: &str = "irn";
+
+/// Relay tags of the requests the wallet sends; a response is tagged one
+/// higher than its request.
+const TAG_SESSION_PROPOSE: u32 = 1100;
+const TAG_SESSION_SETTLE: u32 = 1102;
+const TAG_SESSION_REQUEST: u32 = 1108;
+const TAG_SESSION_DELETE: u32 = 1112;
+
+fn request_tag(method: &str) -> u32 {
+    match method {
+        "wc_pairingDelete" => 1000,
+        "wc_pairingPing" => 1002,
+        "wc_sessionPropose" => TAG_SESSION_PROPOSE,
+        "wc_sessionSettle" => TAG_SESSION_SETTLE,
+        "wc_sessionRequest" => TAG_SESSION_REQUEST,
+        "wc_sessionDelete" => TAG_SESSION_DELETE,
+        "wc_sessionPing" => 1114,
+        _ => 0,
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProposeParams {
+    proposer: Participant,
+    #[serde(default)]
+    required_namespaces: BTreeMap<String, Namespace>,
+    #[serde(default)]
+    optional_namespaces: BTreeMap<String, Namespace>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Participant {
+    public_key: String,
+    #[serde(default)]
+    metadata: Metadata,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RequestParams {
+    request: RpcRequest,
+    chain_id: String,
+}
+
+#[derive(Deserialize)]
+struct RpcRequest {
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+struct PendingProposal {
+    proposal: SessionProposal,
+    proposer_public_key: [u8; 32],
+}
+
+#[derive(Default)]
+struct State {
+    /// Symmetric key of every pairing and session topic.
+    keys: HashMap<String, [u8; 32]>,
+    proposals: BTreeMap<u64, PendingProposal>,
+    sessions: BTreeMap<String, Session>,
+    requests: BTreeMap<u64, SessionRequest>,
+}
+
+/// Responds to dApps over a `Relay`. Sessions and queued requests live in
+/// memory; dApps re-pair after a restart.
+pub struct WalletConnect {
+    relay: Arc<dyn Relay>,
+    metadata: Metadata,
+    state: Mutex<State>,
+}
+
+impl WalletConnect {
+    pub fn new(relay: Arc<dyn Relay>, metadata: Metadata) -> Self {
+        Self { relay, metadata, state: Mutex::new(State::default()) }
+    }
+
+    /// Subscribes to a dApp's pairing topic; its session proposal follows.
+    pub async fn pair(&self, uri: &PairingUri) -> Result<()> {
+        if uri.relay_protocol != RELAY_PROTOCOL {
+            return Err(anyhow!("Unsupported relay protocol {}", uri.relay_protocol));
+        }
+        if uri.is_expired() {
+            return Err(anyhow!("Pairing URI has expired"));
+        }
+        self.state()?.keys.insert(uri.topic.clone(), uri.sym_key);
+        self.relay.subscribe(&uri.topic).await?;
+        info!("Paired with WalletConnect topic {}", uri.topic);
+        Ok(())
+    }
+
+    pub fn proposals(&self) -> Result<Vec<SessionProposal>> {
+        Ok(self.state()?.proposals.values().map(|p| p.proposal.clone()).collect())
+    }
+
+    pub fn proposal(&self, id: u64) -> Result<Option<SessionProposal>> {
+        Ok(self.state()?.proposals.get(&id).map(|p| p.proposal.clone()))
+    }
+
+    /// Settles a session for `wallet` with the given namespaces, normally
+    /// from `session_namespaces`.
+    pub async fn approve_proposal(
+        &self,
+        id: u64,
+        wallet: &str,
+        namespaces: BTreeMap<String, SessionNamespace>,
+    ) -> Result<Session> {
+        let pending = self
+            .state()?
+            .proposals
+            .remove(&id)
+            .ok_or_else(|| anyhow!("Unknown proposal {}", id))?;
+        let key_pair = KeyPair::generate();
+        let sym_key = key_pair.derive_sym_key(&pending.proposer_public_key);
+        let topic = crypto::topic_for_key(&sym_key);
+        let session = Session {
+            topic: topic.clone(),
+            wallet: wallet.to_string(),
+            peer: pending.proposal.proposer.clone(),
+            namespaces,
+            expiry: Utc::now() + Duration::days(SESSION_TTL_DAYS),
+        };
+        self.state()?.keys.insert(topic.clone(), sym_key);
+        self.relay.subscribe(&topic).await?;
+
+        let public_key = hex::encode(key_pair.public_key);
+        let result =
+            json!({ "relay": { "protocol": RELAY_PROTOCOL }, "responderPublicKey": public_key });
+        self.send(&pending.proposal.pairing_topic, response(id, result), TAG_SESSION_PROPOSE + 1)
+            .await?;
+        let settle = json!({
+            "relay": { "protocol": RELAY_PROTOCOL },
+            "namespaces": session.namespaces,
+            "controller": { "publicKey": public_key, "metadata": self.metadata },
+            "expiry": session.expiry.timestamp(),
+        });
+        self.send(&topic, request("wc_sessionSettle", settle), TAG_SESSION_SETTLE).await?;
+
+        self.state()?.sessions.insert(topic.clone(), session.clone());
+        info!("WalletConnect session {} settled for wallet {}", topic, wallet);
+        Ok(session)
+    }
+
+    pub async fn reject_proposal(&self, id: u64) -> Result<()> {
+        let pending = self
+            .state()?
+            .proposals
+            .remove(&id)
+            .ok_or_else(|| anyhow!("Unknown proposal {}", id))?;
+        let rejected = error_response(id, USER_REJECTED, "User rejected.");
+        self.send(&pending.proposal.pairing_topic, rejected, TAG_SESSION_PROPOSE + 1).await
+    }
+
+    pub fn sessions(&self) -> Result<Vec<Session>> {
+        Ok(self.state()?.sessions.values().cloned().collect())
+    }
+
+    /// Ends a session, telling the dApp and dropping its queued requests.
+    pub async fn disconnect(&self, topic: &str) -> Result<()> {
+        if !self.state()?.sessions.contains_key(topic) {
+            return Err(anyhow!("Unknown session {}", topic));
+        }
+        let reason = json!({ "code": USER_DISCONNECTED, "message": "User disconnected." });
+        self.send(topic, request("wc_sessionDelete", reason), TAG_SESSION_DELETE).await?;
+        self.forget_session(topic).await
+    }
+
+    pub fn requests(&self) -> Result<Vec<SessionRequest>> {
+        Ok(self.state()?.requests.values().cloned().collect())
+    }
+
+    pub fn request(&self, id: u64) -> Result<Option<SessionRequest>> {
+        Ok(self.state()?.requests.get(&id).cloned())
+    }
+
+    /// Answers a queued request with its result.
+    pub async fn respond(&self, id: u64, result: Value) -> Result<()> {
+        let request = self.take_request(id)?;
+        self.send(&request.topic, response(id, result), TAG_SESSION_REQUEST + 1).await
+    }
+
+    /// Answers a queued request with an error.
+    pub async fn respond_error(&self, id: u64, code: i64, message: &str) -> Result<()> {
+        let request = self.take_request(id)?;
+        self.send(&request.topic, error_response(id, code, message), TAG_SESSION_REQUEST + 1).await
+    }
+
+    /// Handles the next message from the relay; `false` once the relay closed.
+    pub async fn process_next(&self) -> Result<bool> {
+        match self.relay.next_message().await {
+            Some(message) => self.handle_message(&message).await.map(|_| true),
+            None => Ok(false),
+        }
+    }
+
+    /// Processes relay messages until the relay closes or the task is aborted.
+    pub fn spawn(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                match self.process_next().await {
+                    Ok(true) => {}
+                    Ok(false) => break,
+                    Err(e) => warn!("WalletConnect message dropped: {}", e),
+                }
+            }
+        })
+    }
+
+    pub async fn handle_message(&self, message: &RelayMessage) -> Result<()> {
+        let key = self.key(&message.topic)?;
+        let payload: Value = serde_json::from_slice(&crypto::decrypt(&key, &message.message)?)
+            .context("Malformed WalletConnect payload")?;
+        let id = payload["id"].as_u64().ok_or_else(|| anyhow!("Payload without id"))?;
+        let Some(method) = payload["method"].as_str() else {
+            debug!("WalletConnect response {} on {}", id, message.topic);
+            return Ok(());
+        };
+        let params = payload["params"].clone();
+
+        match method {
+            "wc_sessionPropose" => self.on_proposal(&message.topic, id, params),
+            "wc_sessionRequest" => self.on_request(&message.topic, id, params).await,
+            "wc_sessionDelete" => {
+                self.reply(&message.topic, method, response(id, json!(true))).await?;
+                self.forget_session(&message.topic).await
+            }
+            "wc_pairingDelete" => {
+                self.state()?.keys.remove(&message.topic);
+                self.relay.unsubscribe(&message.topic).await?;
+                Ok(())
+            }
+            "wc_sessionPing" | "wc_pairingPing" => {
+                self.reply(&message.topic, method, response(id, json!(true))).await
+            }
+            _ => {
+                let unsupported = error_response(id, -32601, "Method not found");
+                self.reply(&message.topic, method, unsupported).await
+            }
+        }
+    }
+
+    fn on_proposal(&self, pairing_topic: &str, id: u64, params: Value) -> Result<()> {
+        let params: ProposeParams =
+            serde_json::from_value(params).context("Malformed session proposal")?;
+        let proposer_public_key = hex::decode(&params.proposer.public_key)
+            .ok()
+            .and_then(|key| <[u8; 32]>::try_from(key.as_slice()).ok())
+            .ok_or_else(|| anyhow!("Invalid proposer public key"))?;
+        info!("WalletConnect session proposal {} from {}", id, params.proposer.metadata.name);
+        let proposal = SessionProposal {
+            id,
+            pairing_topic: pairing_topic.to_string(),
+            proposer: params.proposer.metadata,
+            required_namespaces: params.required_namespaces,
+            optional_namespaces: params.optional_namespaces,
+        };
+        self.state()?.proposals.insert(id, PendingProposal { proposal, proposer_public_key });
+        Ok(())
+    }
+
+    async fn on_request(&self, topic: &str, id: u64, params: Value) -> Result<()> {
+        let params: RequestParams =
+            serde_json::from_value(params).context("Malformed session request")?;
+        let session = self.state()?.sessions.get(topic).cloned();
+        let Some(session) = session else {
+            return Err(anyhow!("Request {} on unknown session {}", id, topic));
+        };
+        if let Err(code) = session.permits(&params.chain_id, &params.request.method) {
+            let message =
+                format!("{} on {} is not permitted", params.request.method, params.chain_id);
+            let refused = error_response(id, code, &message);
+            return self.send(topic, refused, TAG_SESSION_REQUEST + 1).await;
+        }
+
+        info!(
+            "WalletConnect request {} queued: {} on {} for wallet {}",
+            id, params.request.method, params.chain_id, session.wallet
+        );
+        let request = SessionRequest {
+            id,
+            topic: topic.to_string(),
+            wallet: session.wallet,
+            peer: session.peer,
+            chain_id: params.chain_id,
+            method: params.request.method,
+            params: params.request.params,
+            received_at: Utc::now(),
+        };
+        self.state()?.requests.insert(id, request);
+        Ok(())
+    }
+
+    async fn forget_session(&self, topic: &str) -> Result<()> {
+        {
+            let mut state = self.state()?;
+            state.sessions.remove(topic);
+            state.keys.remove(topic);
+            state.requests.retain(|_, request| request.topic != topic);
+        }
+        self.relay.unsubscribe(topic).await
+    }
+
+    fn take_request(&self, id: u64) -> Result<SessionRequest> {
+        self.state()?.requests.remove(&id).ok_or_else(|| anyhow!("Unknown request {}", id))
+    }
+
+    async fn reply(&self, topic: &str, method: &str, payload: Value) -> Result<()> {
+        self.send(topic, payload, request_tag(method) + 1).await
+    }
+
+    async fn send(&self, topic: &str, payload: Value, tag: u32) -> Result<()> {
+        let key = self.key(topic)?;
+        let message = crypto::encrypt(&key, payload.to_string().as_bytes())?;
+        self.relay.publish(topic, &message, tag).await
+    }
+
+    fn key(&self, topic: &str) -> Result<[u8; 32]> {
+        self.state()?.keys.get(topic).copied().ok_or_else(|| anyhow!("Unknown topic {}", topic))
+    }
+
+    fn state(&self) -> Result<MutexGuard<'_, State>> {
+        self.state.lock().map_err(|_| anyhow!("WalletConnect state lock poisoned"))
+    }
+}
+
+/// JSON-RPC ids are millisecond timestamps with three random digits, as the
+/// reference clients use.
+fn payload_id() -> u64 {
+    Utc::now().timestamp_millis() as u64 * 1000 + rand::random::<u64>() % 1000
+}
+
+fn request(method: &str, params: Value) -> Value {
+    json!({ "id": payload_id(), "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+fn response(id: u64, result: Value) -> Value {
+    json!({ "id": id, "jsonrpc": "2.0", "result": result })
+}
+
+fn error_response(id: u64, code: i64, message: &str) -> Value {
+    json!({ "id": id, "jsonrpc": "2.0", "error": { "code": code, "message": message } })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::walletconnect::relay::InMemoryRelay;
+    use crate::walletconnect::{session_namespaces, UNSUPPORTED_METHODS};
+
+    /// The dApp end of a pairing, speaking the protocol by hand.
+    struct Dapp {
+        relay: InMemoryRelay,
+        pairing: PairingUri,
+        key_pair: KeyPair,
+    }
+
+    impl Dapp {
+        async fn new(relay: &InMemoryRelay) -> Self {
+            let sym_key = crypto::random_sym_key();
+            let pairing = PairingUri {
+                topic: crypto::topic_for_key(&sym_key),
+                relay_protocol: RELAY_PROTOCOL.to_string(),
+                sym_key,
+                expiry_timestamp: None,
+            };
+            let relay = relay.connect();
+            relay.subscribe(&pairing.topic).await.unwrap();
+            Self { relay, pairing, key_pair: KeyPair::generate() }
+        }
+
+        async fn publish(&self, topic: &str, key: &[u8; 32], payload: Value) {
+            let message = crypto::encrypt(key, payload.to_string().as_bytes()).unwrap();
+            self.relay.publish(topic, &message, 0).await.unwrap();
+        }
+
+        async fn receive(&self, key: &[u8; 32]) -> Value {
+            let message = self.relay.next_message().await.unwrap();
+            serde_json::from_slice(&crypto::decrypt(key, &message.message).unwrap()).unwrap()
+        }
+    }
+
+    async fn wallet_step(wallet: &WalletConnect) {
+        assert!(wallet.process_next().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_proposal_settlement_and_request_queue() {
+        let relay = InMemoryRelay::new();
+        let dapp = Dapp::new(&relay).await;
+        let wallet = WalletConnect::new(Arc::new(relay), Metadata::default());
+        wallet.pair(&dapp.pairing).await.unwrap();
+
+        let propose = json!({
+            "id": 1,
+            "jsonrpc": "2.0",
+            "method": "wc_sessionPropose",
+            "params": {
+                "relays": [{ "protocol": "irn" }],
+                "proposer": {
+                    "publicKey": hex::encode(dapp.key_pair.public_key),
+                    "metadata": { "name": "Test dApp", "url": "https://dapp.example" }
+                },
+                "requiredNamespaces": {
+                    "eip155": {
+                        "chains": ["eip155:1"],
+                        "methods": ["eth_sendTransaction", "personal_sign"],
+                        "events": ["accountsChanged"]
+                    }
+                }
+            }
+        });
+        dapp.publish(&dapp.pairing.topic, &dapp.pairing.sym_key, propose).await;
+        wallet_step(&wallet).await;
+        let proposal = wallet.proposal(1).unwrap().unwrap();
+        assert_eq!(proposal.proposer.name, "Test dApp");
+
+        let accounts = vec!["eip155:1:0x742d35cc6634c0532925a3b844bc454e4438f44e".to_string()];
+        let namespaces = session_namespaces(&proposal, &accounts).unwrap();
+        let session = wallet.approve_proposal(1, "trader", namespaces).await.unwrap();
+        assert!(wallet.proposals().unwrap().is_empty());
+
+        // the dApp derives the session key from the responder's public key
+        let approval = dapp.receive(&dapp.pairing.sym_key).await;
+        assert_eq!(approval["id"], 1);
+        let responder_key: [u8; 32] =
+            hex::decode(approval["result"]["responderPublicKey"].as_str().unwrap())
+                .unwrap()
+                .try_into()
+                .unwrap();
+        let session_key = dapp.key_pair.derive_sym_key(&responder_key);
+        assert_eq!(crypto::topic_for_key(&session_key), session.topic);
+        dapp.relay.subscribe(&session.topic).await.unwrap();
+        let settle = dapp.receive(&session_key).await;
+        assert_eq!(settle["method"], "wc_sessionSettle");
+        assert_eq!(settle["params"]["namespaces"]["eip155"]["accounts"], json!(accounts));
+
+        let sign = |id: u64, method: &str| {
+            json!({
+                "id": id,
+                "jsonrpc": "2.0",
+                "method": "wc_sessionRequest",
+                "params": {
+                    "chainId": "eip155:1",
+                    "request": { "method": method, "params": ["0x68656c6c6f", accounts[0]] }
+                }
+            })
+        };
+        dapp.publish(&session.topic, &session_key, sign(2, "personal_sign")).await;
+        wallet_step(&wallet).await;
+        dapp.publish(&session.topic, &session_key, sign(3, "eth_sign")).await;
+        wallet_step(&wallet).await;
+
+        // methods the session was not granted are refused without queueing
+        let refused = dapp.receive(&session_key).await;
+        assert_eq!(refused["id"], 3);
+        assert_eq!(refused["error"]["code"], UNSUPPORTED_METHODS);
+        let queued = wallet.requests().unwrap();
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].wallet, "trader");
+        assert_eq!(queued[0].method, "personal_sign");
+
+        wallet.respond(2, json!("0xsignature")).await.unwrap();
+        let answer = dapp.receive(&session_key).await;
+        assert_eq!(answer, json!({ "id": 2, "jsonrpc": "2.0", "result": "0xsignature" }));
+        assert!(wallet.respond(2, json!("again")).await.is_err());
+
+        dapp.publish(&session.topic, &session_key, sign(4, "personal_sign")).await;
+        wallet_step(&wallet).await;
+        wallet.disconnect(&session.topic).await.unwrap();
+        assert_eq!(dapp.receive(&session_key).await["method"], "wc_sessionDelete");
+        assert!(wallet.sessions().unwrap().is_empty());
+        assert!(wallet.requests().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rejected_proposal_is_answered_with_user_rejected() {
+        let relay = InMemoryRelay::new();
+        let dapp = Dapp::new(&relay).await;
+        let wallet = WalletConnect::new(Arc::new(relay), Metadata::default());
+        wallet.pair(&dapp.pairing).await.unwrap();
+
+        let propose = json!({
+            "id": 7,
+            "jsonrpc": "2.0",
+            "method": "wc_sessionPropose",
+            "params": { "proposer": { "publicKey": hex::encode(dapp.key_pair.public_key) } }
+        });
+        dapp.publish(&dapp.pairing.topic, &dapp.pairing.sym_key, propose).await;
+        wallet_step(&wallet).await;
+        wallet.reject_proposal(7).await.unwrap();
+
+        let answer = dapp.receive(&dapp.pairing.sym_key).await;
+        assert_eq!(answer["error"]["code"], USER_REJECTED);
+        assert!(wallet.reject_proposal(7).await.is_err());
+        assert!(wallet.sessions().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+// src/walletconnect/uri.rs
+//! Pairing URIs as shown in a dApp's QR code:
+//! `wc:<topic>@2?relay-protocol=irn&symKey=<hex>&expiryTimestamp=<unix>`.
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PairingUri {
+    pub topic: String,
+    pub relay_protocol: String,
+    pub sym_key: [u8; 32],
+    pub expiry_timestamp: Option<i64>,
+}
+
+impl PairingUri {
+    pub fn is_expired(&self) -> bool {
+        self.expiry_timestamp.is_some_and(|expiry| expiry <= Utc::now().timestamp())
+    }
+}
+
+impl FromStr for PairingUri {
+    type Err = anyhow::Error;
+
+    fn from_str(uri: &str) -> Result<Self> {
+        let rest = uri.trim().strip_prefix("wc:").ok_or_else(|| anyhow!("Not a wc: URI"))?;
+        let (path, query) =
+            rest.split_once('?').ok_or_else(|| anyhow!("Missing URI parameters"))?;
+        let (topic, version) =
+            path.split_once('@').ok_or_else(|| anyhow!("Missing protocol version"))?;
+        if version != "2" {
+            return Err(anyhow!("Unsupported WalletConnect version {}", version));
+        }
+        if topic.len() != 64 || !topic.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(anyhow!("Invalid pairing topic"));
+        }
+
+        let mut relay_protocol = None;
+        let mut sym_key = None;
+        let mut expiry_timestamp = None;
+        for pair in query.split('&') {
+            match pair.split_once('=') {
+                Some(("relay-protocol", value)) => relay_protocol = Some(value.to_string()),
+                Some(("symKey", value)) => {
+                    let bytes = hex::decode(value).map_err(|_| anyhow!("Invalid symKey"))?;
+                    sym_key = Some(
+                        <[u8; 32]>::try_from(bytes.as_slice())
+                            .map_err(|_| anyhow!("symKey must be 32 bytes"))?,
+                    );
+                }
+                Some(("expiryTimestamp", value)) => {
+                    expiry_timestamp =
+                        Some(value.parse().map_err(|_| anyhow!("Invalid expiryTimestamp"))?);
+                }
+                // methods, relay-data and future parameters are not needed
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            topic: topic.to_ascii_lowercase(),
+            relay_protocol: relay_protocol.ok_or_else(|| anyhow!("Missing relay-protocol"))?,
+            sym_key: sym_key.ok_or_else(|| anyhow!("Missing symKey"))?,
+            expiry_timestamp,
+        })
+    }
+}
+
+impl fmt::Display for PairingUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "wc:{}@2?relay-protocol={}&symKey={}",
+            self.topic,
+            self.relay_protocol,
+            hex::encode(self.sym_key)
+        )?;
+        if let Some(expiry) = self.expiry_timestamp {
+            write!(f, "&expiryTimestamp={}", expiry)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pairing_uri() {
+        let topic = "7f6e504bfad60b485450578e05678ed3e8e8c4751d3c6160be17160d63ec90f9";
+        let key = "587d5484ce2a2a6ee3ba1962fdd7e8588e06200c46823bd18fbd67def96ad303";
+        let uri: PairingUri =
+            format!("wc:{}@2?relay-protocol=irn&symKey={}&methods=[wc_sessionPropose]", topic, key)
+                .parse()
+                .unwrap();
+        assert_eq!(uri.topic, topic);
+        assert_eq!(uri.relay_protocol, "irn");
+        assert_eq!(hex::encode(uri.sym_key), key);
+        assert!(!uri.is_expired());
+        assert_eq!(uri.to_string().parse::<PairingUri>().unwrap(), uri);
+
+        let expired = format!("wc:{}@2?relay-protocol=irn&symKey={}&expiryTimestamp=1", topic, key);
+        assert!(expired.parse::<PairingUri>().unwrap().is_expired());
+        assert!(format!("wc:{}@1?bridge=x&key={}", topic, key).parse::<PairingUri>().is_err());
+        assert!(format!("wc:{}@2?relay-protocol=irn", topic).parse::<PairingUri>().is_err());
+        assert!("wc:abc@2?relay-protocol=irn&symKey=00".parse::<PairingUri>().is_err());
+    }
+}
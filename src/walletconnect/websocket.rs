@@ -0,0 +1,460 @@
+// src/walletconnect/websocket.rs
+//! Client for the WalletConnect v2 relay over WebSocket.
+//!
+//! The relay speaks JSON-RPC: `irn_subscribe`, `irn_unsubscribe` and
+//! `irn_publish` from the client, and `irn_subscription` pushes for envelopes
+//! published on subscribed topics, which the client acknowledges. Clients
+//! authenticate with a JWT signed by an Ed25519 key whose `did:key` is the
+//! issuer, passed with the project id in the connection URL.
+//!
+//! A dropped connection is reopened with backoff and every topic subscribed
+//! again; requests in flight when it dropped fail.
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use ed25519_dalek::{Signer, SigningKey, VerifyingKey};
+use futures::{SinkExt, StreamExt};
+use rand::RngCore;
+use serde_json::{json, Value};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tracing::{debug, warn};
+
+use super::relay::{Relay, RelayMessage};
+use crate::core::config::WalletConnectConfig;
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
+/// How long the relay keeps an envelope for a peer that is not connected.
+const PUBLISH_TTL_SECONDS: u64 = 300;
+/// Lifetime of the auth token; a new one is signed for every connection.
+const AUTH_TTL_SECONDS: i64 = 86_400;
+/// Multicodec prefix of an Ed25519 public key in a `did:key`.
+const ED25519_MULTICODEC: [u8; 2] = [0xed, 0x01];
+
+type Pending = oneshot::Sender<Result<Value>>;
+
+/// State shared by the relay handle and its connection task.
+struct Shared {
+    outgoing: UnboundedSender<String>,
+    pending: Mutex<HashMap<u64, Pending>>,
+    /// Subscription ids by topic, needed to unsubscribe.
+    subscriptions: Mutex<HashMap<String, String>>,
+    next_id: AtomicU64,
+}
+
+impl Shared {
+    /// Sends a JSON-RPC request over the current connection and waits for
+    /// its result.
+    async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = oneshot::channel();
+        self.pending.lock().map_err(|_| anyhow!("Relay lock poisoned"))?.insert(id, sender);
+        let request = json!({ "id": id, "jsonrpc": "2.0", "method": method, "params": params });
+        self.outgoing
+            .send(request.to_string())
+            .map_err(|_| anyhow!("WalletConnect relay connection closed"))?;
+
+        match tokio::time::timeout(REQUEST_TIMEOUT, receiver).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(anyhow!("WalletConnect relay connection closed during {}", method)),
+            Err(_) => {
+                if let Ok(mut pending) = self.pending.lock() {
+                    pending.remove(&id);
+                }
+                Err(anyhow!("WalletConnect relay did not answer {}", method))
+            }
+        }
+    }
+
+    async fn subscribe(&self, topic: &str) -> Result<()> {
+        let result = self.request("irn_subscribe", json!({ "topic": topic })).await?;
+        let id = result
+            .as_str()
+            .ok_or_else(|| anyhow!("Relay returned no subscription id for {}", topic))?;
+        self.subscriptions
+            .lock()
+            .map_err(|_| anyhow!("Relay lock poisoned"))?
+            .insert(topic.to_string(), id.to_string());
+        Ok(())
+    }
+
+    /// Handles a frame from the relay. Returns the acknowledgement to send
+    /// back, if it needs one.
+    fn handle(&self, text: &str, inbox: &UnboundedSender<RelayMessage>) -> Option<String> {
+        let frame: Value = match serde_json::from_str(text) {
+            Ok(frame) => frame,
+            Err(e) => {
+                warn!("Ignoring malformed WalletConnect relay frame: {}", e);
+                return None;
+            }
+        };
+        if frame["method"] == "irn_subscription" {
+            let data = &frame["params"]["data"];
+            match (data["topic"].as_str(), data["message"].as_str()) {
+                (Some(topic), Some(message)) => {
+                    let message =
+                        RelayMessage { topic: topic.to_string(), message: message.to_string() };
+                    let _ = inbox.send(message);
+                }
+                _ => warn!("Ignoring WalletConnect relay push without topic or message"),
+            }
+            let ack = json!({ "id": frame["id"], "jsonrpc": "2.0", "result": true });
+            return Some(ack.to_string());
+        }
+
+        let pending = frame["id"].as_u64().and_then(|id| self.pending.lock().ok()?.remove(&id));
+        match pending {
+            Some(pending) => {
+                let result = match frame.get("error") {
+                    Some(error) => Err(anyhow!("WalletConnect relay error: {}", error["message"])),
+                    None => Ok(frame["result"].clone()),
+                };
+                let _ = pending.send(result);
+            }
+            None => debug!("Ignoring WalletConnect relay frame: {}", text),
+        }
+        None
+    }
+
+    /// Fails every request waiting on a connection that dropped.
+    fn fail_pending(&self) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.clear();
+        }
+    }
+}
+
+/// A connection to a WalletConnect v2 relay, e.g. `wss://relay.walletconnect.com`.
+pub struct WebSocketRelay {
+    shared: Arc<Shared>,
+    inbox: tokio::sync::Mutex<UnboundedReceiver<RelayMessage>>,
+    connection: JoinHandle<()>,
+}
+
+impl WebSocketRelay {
+    /// Opens a connection to the relay in `config` under a new client key.
+    /// Fails if the relay cannot be reached or refuses the project id.
+    pub async fn connect(config: &WalletConnectConfig) -> Result<Self> {
+        config.validate()?;
+        let key = SigningKey::generate(&mut rand::rngs::OsRng);
+        let socket = open(config, &key).await?;
+
+        let (outgoing, requests) = mpsc::unbounded_channel();
+        let (inbox_sender, inbox) = mpsc::unbounded_channel();
+        let shared = Arc::new(Shared {
+            outgoing,
+            pending: Mutex::new(HashMap::new()),
+            subscriptions: Mutex::new(HashMap::new()),
+            // ids are unique across reconnects; the relay expects them to grow
+            next_id: AtomicU64::new(chrono::Utc::now().timestamp_millis() as u64 * 1000),
+        });
+        let connection = tokio::spawn(run(
+            Arc::clone(&shared),
+            config.clone(),
+            key,
+            socket,
+            requests,
+            inbox_sender,
+        ));
+        Ok(Self { shared, inbox: tokio::sync::Mutex::new(inbox), connection })
+    }
+}
+
+impl Drop for WebSocketRelay {
+    fn drop(&mut self) {
+        self.connection.abort();
+    }
+}
+
+#[async_trait]
+impl Relay for WebSocketRelay {
+    async fn subscribe(&self, topic: &str) -> Result<()> {
+        self.shared.subscribe(topic).await
+    }
+
+    async fn unsubscribe(&self, topic: &str) -> Result<()> {
+        let id = self
+            .shared
+            .subscriptions
+            .lock()
+            .map_err(|_| anyhow!("Relay lock poisoned"))?
+            .remove(topic);
+        match id {
+            Some(id) => {
+                self.shared.request("irn_unsubscribe", json!({ "topic": topic, "id": id })).await?;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    async fn publish(&self, topic: &str, message: &str, tag: u32) -> Result<()> {
+        let params = json!({
+            "topic": topic,
+            "message": message,
+            "ttl": PUBLISH_TTL_SECONDS,
+            "tag": tag,
+            "prompt": false,
+        });
+        self.shared.request("irn_publish", params).await?;
+        Ok(())
+    }
+
+    async fn next_message(&self) -> Option<RelayMessage> {
+        self.inbox.lock().await.recv().await
+    }
+}
+
+/// Serves the connection and reopens it whenever it drops, until the relay
+/// handle is dropped.
+async fn run(
+    shared: Arc<Shared>,
+    config: WalletConnectConfig,
+    key: SigningKey,
+    mut socket: Socket,
+    mut requests: UnboundedReceiver<String>,
+    inbox: UnboundedSender<RelayMessage>,
+) {
+    loop {
+        serve(&shared, socket, &mut requests, &inbox).await;
+        shared.fail_pending();
+        warn!("WalletConnect relay connection to {} lost; reconnecting", config.relay_url);
+
+        let mut delay = RECONNECT_DELAY;
+        socket = loop {
+            tokio::time::sleep(delay).await;
+            match open(&config, &key).await {
+                Ok(socket) => break socket,
+                Err(e) => {
+                    warn!("WalletConnect relay reconnect failed: {}", e);
+                    delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+                }
+            }
+        };
+
+        let topics: Vec<String> = match shared.subscriptions.lock() {
+            Ok(subscriptions) => subscriptions.keys().cloned().collect(),
+            Err(_) => Vec::new(),
+        };
+        let resubscribing = Arc::clone(&shared);
+        tokio::spawn(async move {
+            for topic in topics {
+                if let Err(e) = resubscribing.subscribe(&topic).await {
+                    warn!("Failed to subscribe to {} again: {}", topic, e);
+                }
+            }
+        });
+    }
+}
+
+/// Passes requests to the relay and its frames to `shared` until the
+/// connection drops.
+async fn serve(
+    shared: &Shared,
+    socket: Socket,
+    requests: &mut UnboundedReceiver<String>,
+    inbox: &UnboundedSender<RelayMessage>,
+) {
+    let (mut sink, mut stream) = socket.split();
+    loop {
+        tokio::select! {
+            request = requests.recv() => {
+                let Some(request) = request else { return };
+                if let Err(e) = sink.send(Message::Text(request)).await {
+                    warn!("WalletConnect relay send failed: {}", e);
+                    return;
+                }
+            }
+            frame = stream.next() => match frame {
+                Some(Ok(Message::Text(text))) => {
+                    if let Some(ack) = shared.handle(&text, inbox) {
+                        if let Err(e) = sink.send(Message::Text(ack)).await {
+                            warn!("WalletConnect relay send failed: {}", e);
+                            return;
+                        }
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => return,
+                Some(Ok(_)) => {}
+                Some(Err(e)) => {
+                    warn!("WalletConnect relay connection failed: {}", e);
+                    return;
+                }
+            }
+        }
+    }
+}
+
+async fn open(config: &WalletConnectConfig, key: &SigningKey) -> Result<Socket> {
+    let relay_url = config.relay_url.trim_end_matches('/');
+    let url = format!(
+        "{}/?auth={}&projectId={}",
+        relay_url,
+        auth_token(key, relay_url),
+        config.project_id
+    );
+    let (socket, _) = tokio_tungstenite::connect_async(url)
+        .await
+        .map_err(|e| anyhow!("Cannot connect to WalletConnect relay {}: {}", relay_url, e))?;
+    Ok(socket)
+}
+
+/// The relay auth JWT: EdDSA-signed, issued by the client key's `did:key`
+/// for the relay URL.
+fn auth_token(key: &SigningKey, audience: &str) -> String {
+    let mut subject = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut subject);
+    let issued_at = chrono::Utc::now().timestamp();
+    let header = json!({ "alg": "EdDSA", "typ": "JWT" });
+    let claims = json!({
+        "iss": did_key(&key.verifying_key()),
+        "sub": hex::encode(subject),
+        "aud": audience,
+        "iat": issued_at,
+        "exp": issued_at + AUTH_TTL_SECONDS,
+    });
+    let signing_input = format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(header.to_string()),
+        URL_SAFE_NO_PAD.encode(claims.to_string())
+    );
+    let signature = key.sign(signing_input.as_bytes());
+    format!("{}.{}", signing_input, URL_SAFE_NO_PAD.encode(signature.to_bytes()))
+}
+
+fn did_key(key: &VerifyingKey) -> String {
+    let mut bytes = ED25519_MULTICODEC.to_vec();
+    bytes.extend_from_slice(key.as_bytes());
+    format!("did:key:z{}", bs58::encode(bytes).into_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signature, Verifier};
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+
+    // the handshake callback's error type is tungstenite's, not ours
+    #[allow(clippy::result_large_err)]
+    async fn accept(listener: &TcpListener) -> (WebSocketStream<TcpStream>, String) {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut uri = String::new();
+        let socket =
+            tokio_tungstenite::accept_hdr_async(stream, |request: &Request, response: Response| {
+                uri = request.uri().to_string();
+                Ok(response)
+            })
+            .await
+            .unwrap();
+        (socket, uri)
+    }
+
+    async fn read(socket: &mut WebSocketStream<TcpStream>) -> Value {
+        match socket.next().await.unwrap().unwrap() {
+            Message::Text(text) => serde_json::from_str(&text).unwrap(),
+            other => panic!("expected a text frame, got {:?}", other),
+        }
+    }
+
+    async fn reply(socket: &mut WebSocketStream<TcpStream>, request: &Value, result: Value) {
+        let response = json!({ "id": request["id"], "jsonrpc": "2.0", "result": result });
+        socket.send(Message::Text(response.to_string())).await.unwrap();
+    }
+
+    /// Checks the `auth` query parameter is a JWT signed by its issuer.
+    fn verify_auth(uri: &str, audience: &str) {
+        let query = uri.split_once('?').unwrap().1;
+        let params: HashMap<_, _> = query.split('&').filter_map(|p| p.split_once('=')).collect();
+        assert_eq!(params["projectId"], "test-project");
+
+        let (signing_input, signature) = params["auth"].rsplit_once('.').unwrap();
+        let claims = signing_input.split_once('.').unwrap().1;
+        let claims: Value =
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(claims).unwrap()).unwrap();
+        assert_eq!(claims["aud"], audience);
+        let issuer = claims["iss"].as_str().unwrap().strip_prefix("did:key:z").unwrap();
+        let issuer = bs58::decode(issuer).into_vec().unwrap();
+        assert_eq!(issuer[..2], ED25519_MULTICODEC);
+        let key = VerifyingKey::from_bytes(issuer[2..].try_into().unwrap()).unwrap();
+        let signature = URL_SAFE_NO_PAD.decode(signature).unwrap();
+        let signature = Signature::from_slice(&signature).unwrap();
+        assert!(key.verify(signing_input.as_bytes(), &signature).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_relay_protocol_and_resubscription() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let relay_url = format!("ws://{}", listener.local_addr().unwrap());
+        let audience = relay_url.clone();
+
+        let server = tokio::spawn(async move {
+            let (mut socket, uri) = accept(&listener).await;
+            verify_auth(&uri, &audience);
+
+            let subscribe = read(&mut socket).await;
+            assert_eq!(subscribe["method"], "irn_subscribe");
+            assert_eq!(subscribe["params"]["topic"], "topic-1");
+            reply(&mut socket, &subscribe, json!("sub-1")).await;
+
+            let push = json!({
+                "id": 7,
+                "jsonrpc": "2.0",
+                "method": "irn_subscription",
+                "params": { "id": "sub-1", "data": { "topic": "topic-1", "message": "hello", "tag": 1100 } },
+            });
+            socket.send(Message::Text(push.to_string())).await.unwrap();
+            let ack = read(&mut socket).await;
+            assert_eq!((ack["id"].as_u64(), ack["result"].as_bool()), (Some(7), Some(true)));
+
+            let publish = read(&mut socket).await;
+            assert_eq!(publish["method"], "irn_publish");
+            assert_eq!(publish["params"]["message"], "reply");
+            assert_eq!(publish["params"]["tag"], 1101);
+            reply(&mut socket, &publish, json!(true)).await;
+
+            // drop the connection; the client comes back and subscribes again
+            socket.close(None).await.unwrap();
+            let (mut socket, uri) = accept(&listener).await;
+            verify_auth(&uri, &audience);
+            let subscribe = read(&mut socket).await;
+            assert_eq!(subscribe["params"]["topic"], "topic-1");
+            reply(&mut socket, &subscribe, json!("sub-2")).await;
+
+            let unsubscribe = read(&mut socket).await;
+            assert_eq!(unsubscribe["method"], "irn_unsubscribe");
+            assert_eq!(unsubscribe["params"], json!({ "topic": "topic-1", "id": "sub-2" }));
+            reply(&mut socket, &unsubscribe, json!(true)).await;
+        });
+
+        let config = WalletConnectConfig { project_id: "test-project".to_string(), relay_url };
+        let relay = WebSocketRelay::connect(&config).await.unwrap();
+        relay.subscribe("topic-1").await.unwrap();
+        let message = relay.next_message().await.unwrap();
+        assert_eq!(message, RelayMessage { topic: "topic-1".into(), message: "hello".into() });
+        relay.publish("topic-1", "reply", 1101).await.unwrap();
+
+        let resubscribed = async {
+            while relay.shared.subscriptions.lock().unwrap()["topic-1"] != "sub-2" {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(10), resubscribed).await.unwrap();
+        relay.unsubscribe("topic-1").await.unwrap();
+        server.await.unwrap();
+    }
+}
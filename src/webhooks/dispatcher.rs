@@ -0,0 +1,500 @@
+// src/webhooks/dispatcher.rs
+//! Queues wallet events for subscribed webhooks and delivers them.
+//!
+//! Events become `pending` delivery rows first, so a delivery survives a
+//! restart and is retried from storage. Failures are retried with the
+//! dispatcher's `RetryPolicy` until they succeed or are marked `dead`.
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::Utc;
+use futures::stream::{self, StreamExt};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use super::{
+    sign_payload, subscribes_to, DeliveryStatus, RetryPolicy, DELIVERY_HEADER, EVENT_HEADER,
+    SIGNATURE_HEADER, TIMESTAMP_HEADER,
+};
+use crate::core::events::WalletEvent;
+use crate::storage::{WalletStorageTrait, WebhookDelivery, WebhookRecord};
+
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Deliveries attempted per pass; the rest wait for the next one.
+const DELIVERY_BATCH: i64 = 100;
+/// Webhooks delivered to at the same time within a pass.
+const MAX_CONCURRENT_WEBHOOKS: usize = 8;
+
+pub struct WebhookDispatcher {
+    storage: Arc<dyn WalletStorageTrait + Send + Sync>,
+    client: reqwest::Client,
+    retry: RetryPolicy,
+    poll_interval: Duration,
+}
+
+impl WebhookDispatcher {
+    pub fn new(storage: Arc<dyn WalletStorageTrait + Send + Sync>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        Self {
+            storage,
+            client,
+            retry: RetryPolicy::default(),
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Queues `event` for every webhook subscribed to it and returns the
+    /// new deliveries. Nothing is sent until `deliver_due`.
+    pub async fn enqueue(&self, event: &WalletEvent) -> Result<Vec<WebhookDelivery>> {
+        let webhooks = self.storage.list_webhooks().await?;
+        let now = Utc::now();
+        let mut deliveries = Vec::new();
+        for webhook in webhooks.iter().filter(|w| subscribes_to(w, event)) {
+            let id = uuid::Uuid::new_v4().to_string();
+            let payload = serde_json::json!({
+                "id": id,
+                "type": event.kind().as_str(),
+                "created_at": now,
+                "data": event,
+            });
+            let delivery = WebhookDelivery {
+                id,
+                webhook_id: webhook.id.clone(),
+                event_type: event.kind().to_string(),
+                payload: payload.to_string(),
+                status: DeliveryStatus::Pending.to_string(),
+                attempts: 0,
+                last_error: None,
+                response_status: None,
+                next_attempt_at: now,
+                created_at: now,
+                updated_at: now,
+            };
+            self.storage.store_webhook_delivery(&delivery).await?;
+            deliveries.push(delivery);
+        }
+        Ok(deliveries)
+    }
+
+    /// Attempts every pending delivery whose next attempt is due. Webhooks
+    /// are delivered to concurrently, each one's deliveries in order, so a
+    /// slow endpoint only holds up its own events. Returns how many were
+    /// delivered.
+    pub async fn deliver_due(&self) -> Result<usize> {
+        let due = self.storage.get_due_webhook_deliveries(Utc::now(), DELIVERY_BATCH).await?;
+        let mut queues: HashMap<String, Vec<WebhookDelivery>> = HashMap::new();
+        for delivery in due {
+            queues.entry(delivery.webhook_id.clone()).or_default().push(delivery);
+        }
+        stream::iter(queues)
+            .map(|(webhook_id, queue)| self.deliver_queue(webhook_id, queue))
+            .buffer_unordered(MAX_CONCURRENT_WEBHOOKS)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .sum()
+    }
+
+    /// Attempts one webhook's due deliveries one after another.
+    async fn deliver_queue(
+        &self,
+        webhook_id: String,
+        queue: Vec<WebhookDelivery>,
+    ) -> Result<usize> {
+        // Deleting a webhook deletes its deliveries; skip ones caught in between.
+        let Some(webhook) = self.storage.get_webhook(&webhook_id).await? else {
+            return Ok(0);
+        };
+        let mut delivered = 0;
+        for delivery in queue {
+            let delivery = self.attempt(&webhook, delivery).await?;
+            if delivery.status == DeliveryStatus::Delivered.as_str() {
+                delivered += 1;
+            }
+        }
+        Ok(delivered)
+    }
+
+    /// Sends a delivery again from its first attempt, e.g. a dead one once
+    /// the endpoint is fixed. `None` when there is no such delivery.
+    pub async fn retry(&self, delivery_id: &str) -> Result<Option<WebhookDelivery>> {
+        let Some(mut delivery) = self.storage.get_webhook_delivery(delivery_id).await? else {
+            return Ok(None);
+        };
+        let now = Utc::now();
+        delivery.status = DeliveryStatus::Pending.to_string();
+        delivery.attempts = 0;
+        delivery.next_attempt_at = now;
+        delivery.updated_at = now;
+        self.storage.update_webhook_delivery(&delivery).await?;
+        Ok(Some(delivery))
+    }
+
+    async fn attempt(
+        &self,
+        webhook: &WebhookRecord,
+        mut delivery: WebhookDelivery,
+    ) -> Result<WebhookDelivery> {
+        let timestamp = Utc::now().timestamp();
+        let signature = sign_payload(&webhook.secret, timestamp, &delivery.payload);
+        let response = self
+            .client
+            .post(&webhook.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(DELIVERY_HEADER, &delivery.id)
+            .header(EVENT_HEADER, &delivery.event_type)
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, signature)
+            .body(delivery.payload.clone())
+            .send()
+            .await;
+
+        let now = Utc::now();
+        delivery.attempts += 1;
+        delivery.updated_at = now;
+        let error = match response {
+            Ok(response) => {
+                delivery.response_status = Some(response.status().as_u16() as i64);
+                if response.status().is_success() {
+                    None
+                } else {
+                    Some(format!("Endpoint returned {}", response.status()))
+                }
+            }
+            Err(e) => {
+                delivery.response_status = None;
+                Some(e.to_string())
+            }
+        };
+
+        match error {
+            None => {
+                debug!("Delivered {} to webhook {}", delivery.id, webhook.id);
+                delivery.status = DeliveryStatus::Delivered.to_string();
+                delivery.last_error = None;
+            }
+            Some(error) if delivery.attempts >= self.retry.max_attempts as i64 => {
+                warn!(
+                    "Webhook delivery {} to {} is dead after {} attempts: {}",
+                    delivery.id, webhook.url, delivery.attempts, error
+                );
+                delivery.status = DeliveryStatus::Dead.to_string();
+                delivery.last_error = Some(error);
+            }
+            Some(error) => {
+                let delay = self.retry.delay_after(delivery.attempts as u32);
+                delivery.next_attempt_at =
+                    now + chrono::Duration::milliseconds(delay.as_millis() as i64);
+                delivery.last_error = Some(error);
+            }
+        }
+        self.storage.update_webhook_delivery(&delivery).await?;
+        Ok(delivery)
+    }
+
+    /// Starts the dispatcher: one task queues the events received on
+    /// `events` until the bus is dropped, another delivers due deliveries
+    /// every poll interval, and as soon as something was queued. Delivery
+    /// runs apart from the receive loop so slow endpoints cannot make it
+    /// lag behind the bus. Both run until aborted.
+    pub fn spawn(
+        self: Arc<Self>,
+        mut events: broadcast::Receiver<WalletEvent>,
+    ) -> [JoinHandle<()>; 2] {
+        let queued = Arc::new(Notify::new());
+
+        let dispatcher = Arc::clone(&self);
+        let notify = Arc::clone(&queued);
+        let receive = tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => match dispatcher.enqueue(&event).await {
+                        Ok(deliveries) if !deliveries.is_empty() => notify.notify_one(),
+                        Ok(_) => {}
+                        Err(e) => warn!("Failed to queue {} webhooks: {}", event.kind(), e),
+                    },
+                    Err(RecvError::Lagged(missed)) => warn!(
+                        "Webhook dispatcher lagged behind the event bus; {} events were dropped \
+                         without being queued",
+                        missed
+                    ),
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+
+        let deliver = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.poll_interval);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = queued.notified() => {}
+                }
+                if let Err(e) = self.deliver_due().await {
+                    warn!("Failed to deliver webhooks: {}", e);
+                }
+            }
+        });
+
+        [receive, deliver]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::WalletStorage;
+    use crate::webhooks::verify_signature;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use axum::Router;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    #[derive(Clone, Default)]
+    struct Receiver {
+        failures: Arc<AtomicUsize>,
+        received: Arc<Mutex<Vec<(HeaderMap, String)>>>,
+    }
+
+    /// Serves a receiver on a local port that answers 500 to the first
+    /// `failures` requests. Returns its URL.
+    async fn serve(receiver: Receiver) -> String {
+        let app = Router::new().route(
+            "/hook",
+            post(move |headers: HeaderMap, body: String| {
+                let receiver = receiver.clone();
+                async move {
+                    receiver.received.lock().unwrap().push((headers, body));
+                    let failing = receiver
+                        .failures
+                        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                        .is_ok();
+                    if failing {
+                        StatusCode::INTERNAL_SERVER_ERROR
+                    } else {
+                        StatusCode::NO_CONTENT
+                    }
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        url
+    }
+
+    async fn dispatcher(url: &str, events: &str, max_attempts: u32) -> WebhookDispatcher {
+        let storage = Arc::new(WalletStorage::new_with_url("sqlite::memory:").await.unwrap());
+        storage
+            .store_webhook(&WebhookRecord {
+                id: "hook".to_string(),
+                url: url.to_string(),
+                secret: "whsec_test".to_string(),
+                events: events.to_string(),
+                wallet: None,
+                created_at: Utc::now(),
+            })
+            .await
+            .unwrap();
+        WebhookDispatcher::new(storage).with_retry_policy(RetryPolicy {
+            max_attempts,
+            base_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+        })
+    }
+
+    fn deposit() -> WalletEvent {
+        WalletEvent::IncomingDeposit {
+            wallet: "w1".to_string(),
+            network: "eth".to_string(),
+            from_address: "0xabc".to_string(),
+            tx_hash: "0x123".to_string(),
+            sanctioned: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_delivery_is_retried_and_signed() {
+        let receiver = Receiver::default();
+        receiver.failures.store(1, Ordering::SeqCst);
+        let url = serve(receiver.clone()).await;
+        let dispatcher = dispatcher(&url, "incoming_deposit", 3).await;
+
+        let queued = dispatcher.enqueue(&deposit()).await.unwrap();
+        assert_eq!(queued.len(), 1);
+        assert_eq!(dispatcher.deliver_due().await.unwrap(), 0);
+        let failed = dispatcher.storage.get_webhook_delivery(&queued[0].id).await.unwrap().unwrap();
+        assert_eq!(failed.status, "pending");
+        assert_eq!(failed.attempts, 1);
+        assert_eq!(failed.response_status, Some(500));
+
+        assert_eq!(dispatcher.deliver_due().await.unwrap(), 1);
+        let delivered =
+            dispatcher.storage.get_webhook_delivery(&queued[0].id).await.unwrap().unwrap();
+        assert_eq!(delivered.status, "delivered");
+        assert_eq!(delivered.attempts, 2);
+        assert!(delivered.last_error.is_none());
+
+        let received = receiver.received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        let (headers, body) = &received[1];
+        let header = |name: &str| headers.get(name).unwrap().to_str().unwrap().to_string();
+        assert_eq!(header(DELIVERY_HEADER), queued[0].id);
+        assert_eq!(header(EVENT_HEADER), "incoming_deposit");
+        let timestamp: i64 = header(TIMESTAMP_HEADER).parse().unwrap();
+        assert!(verify_signature("whsec_test", timestamp, body, &header(SIGNATURE_HEADER)));
+        let payload: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(payload["type"], "incoming_deposit");
+        assert_eq!(payload["data"]["tx_hash"], "0x123");
+    }
+
+    #[tokio::test]
+    async fn test_delivery_is_dead_lettered_after_max_attempts() {
+        let receiver = Receiver::default();
+        receiver.failures.store(usize::MAX, Ordering::SeqCst);
+        let url = serve(receiver.clone()).await;
+        let dispatcher = dispatcher(&url, "", 2).await;
+
+        let queued = dispatcher.enqueue(&deposit()).await.unwrap();
+        dispatcher.deliver_due().await.unwrap();
+        dispatcher.deliver_due().await.unwrap();
+        // dead deliveries are no longer attempted
+        dispatcher.deliver_due().await.unwrap();
+        assert_eq!(receiver.received.lock().unwrap().len(), 2);
+
+        let dead =
+            dispatcher.storage.get_webhook_deliveries("hook", Some("dead"), 10).await.unwrap();
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].attempts, 2);
+        assert!(dead[0].last_error.as_deref().unwrap().contains("500"));
+
+        receiver.failures.store(0, Ordering::SeqCst);
+        let retried = dispatcher.retry(&queued[0].id).await.unwrap().unwrap();
+        assert_eq!(retried.status, "pending");
+        assert_eq!(dispatcher.deliver_due().await.unwrap(), 1);
+        assert!(dispatcher.retry("missing").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_spawned_dispatcher_delivers_queued_events() {
+        let receiver = Receiver::default();
+        let url = serve(receiver.clone()).await;
+        // the poll never comes round; queuing wakes the delivery task
+        let dispatcher = Arc::new(
+            dispatcher(&url, "incoming_deposit", 3)
+                .await
+                .with_poll_interval(Duration::from_secs(3600)),
+        );
+        let (sender, events) = broadcast::channel(4);
+        let tasks = Arc::clone(&dispatcher).spawn(events);
+        // let the first interval tick go by before anything is queued
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        sender.send(deposit()).unwrap();
+        for _ in 0..100 {
+            if !receiver.received.lock().unwrap().is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(receiver.received.lock().unwrap().len(), 1);
+        for task in tasks {
+            task.abort();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_webhooks_are_delivered_concurrently_in_order() {
+        // each endpoint holds its first request until the other one is reached
+        let barrier = Arc::new(tokio::sync::Barrier::new(2));
+        let mut receivers = Vec::new();
+        let mut urls = Vec::new();
+        for _ in 0..2 {
+            let received: Arc<Mutex<Vec<String>>> = Arc::default();
+            let (barrier, sink) = (Arc::clone(&barrier), Arc::clone(&received));
+            let app = Router::new().route(
+                "/hook",
+                post(move |body: String| {
+                    let (barrier, sink) = (Arc::clone(&barrier), Arc::clone(&sink));
+                    async move {
+                        if sink.lock().unwrap().is_empty() {
+                            let met = tokio::time::timeout(Duration::from_secs(5), barrier.wait());
+                            assert!(met.await.is_ok(), "webhooks were delivered one at a time");
+                        }
+                        sink.lock().unwrap().push(body);
+                        StatusCode::NO_CONTENT
+                    }
+                }),
+            );
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            urls.push(format!("http://{}/hook", listener.local_addr().unwrap()));
+            tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+            receivers.push(received);
+        }
+        let dispatcher = dispatcher(&urls[0], "", 3).await;
+        dispatcher
+            .storage
+            .store_webhook(&WebhookRecord {
+                id: "other".to_string(),
+                url: urls[1].clone(),
+                secret: "whsec_other".to_string(),
+                events: String::new(),
+                wallet: None,
+                created_at: Utc::now(),
+            })
+            .await
+            .unwrap();
+
+        let mut queued = Vec::new();
+        for _ in 0..3 {
+            queued.extend(dispatcher.enqueue(&deposit()).await.unwrap());
+        }
+        assert_eq!(dispatcher.deliver_due().await.unwrap(), 6);
+        for (received, webhook) in receivers.iter().zip(["hook", "other"]) {
+            let ids: Vec<String> = received
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|body| serde_json::from_str::<serde_json::Value>(body).unwrap())
+                .map(|payload| payload["id"].as_str().unwrap().to_string())
+                .collect();
+            let expected: Vec<String> = queued
+                .iter()
+                .filter(|delivery| delivery.webhook_id == webhook)
+                .map(|delivery| delivery.id.clone())
+                .collect();
+            assert_eq!(ids, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unsubscribed_events_are_not_queued() {
+        let dispatcher = dispatcher("http://127.0.0.1:1/hook", "bridge_status_changed", 3).await;
+        assert!(dispatcher.enqueue(&deposit()).await.unwrap().is_empty());
+        assert!(dispatcher
+            .storage
+            .get_webhook_deliveries("hook", None, 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
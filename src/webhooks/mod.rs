@@ -0,0 +1,202 @@
+// src/webhooks/mod.rs
+//! Webhook notifications for wallet events.
+//!
+//! Each registered endpoint receives the events it subscribed to as a JSON
+//! POST signed with its own secret. A failed delivery is retried with
+//! exponential backoff and marked dead once its attempts run out; every
+//! delivery stays in storage so it can be inspected and retried by hand.
+pub mod dispatcher;
+
+pub use dispatcher::WebhookDispatcher;
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::Result;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use ring::hmac;
+use serde::{Deserialize, Serialize};
+
+use crate::core::events::{EventKind, WalletEvent};
+use crate::storage::WebhookRecord;
+
+pub const DELIVERY_HEADER: &str = "x-webhook-delivery";
+pub const EVENT_HEADER: &str = "x-webhook-event";
+pub const TIMESTAMP_HEADER: &str = "x-webhook-timestamp";
+pub const SIGNATURE_HEADER: &str = "x-webhook-signature";
+
+const SIGNATURE_PREFIX: &str = "sha256=";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    Pending,
+    Delivered,
+    /// The retries ran out; only a manual retry sends it again.
+    Dead,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryStatus::Pending => "pending",
+            DeliveryStatus::Delivered => "delivered",
+            DeliveryStatus::Dead => "dead",
+        }
+    }
+}
+
+impl fmt::Display for DeliveryStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DeliveryStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(DeliveryStatus::Pending),
+            "delivered" => Ok(DeliveryStatus::Delivered),
+            "dead" => Ok(DeliveryStatus::Dead),
+            other => Err(anyhow::anyhow!("Unknown delivery status: {}", other)),
+        }
+    }
+}
+
+/// How often and how far apart a failed delivery is attempted again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts, including the first, before a delivery is dead.
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 6,
+            base_delay: Duration::from_secs(5),
+            max_delay: Duration::from_secs(3600),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Wait before the next attempt once `attempts` have failed: the base
+    /// delay doubled for each failure after the first, up to `max_delay`.
+    pub fn delay_after(&self, attempts: u32) -> Duration {
+        let factor = 1u32.checked_shl(attempts.saturating_sub(1)).unwrap_or(u32::MAX);
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+/// `sha256=<hex>` HMAC-SHA256 of `"{timestamp}.{body}"` keyed with the
+/// webhook's secret, sent in the signature header. Including the timestamp
+/// lets receivers reject replayed deliveries.
+pub fn sign_payload(secret: &str, timestamp: i64, body: &str) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let tag = hmac::sign(&key, format!("{}.{}", timestamp, body).as_bytes());
+    format!("{}{}", SIGNATURE_PREFIX, hex::encode(tag.as_ref()))
+}
+
+/// Checks a signature made by `sign_payload` in constant time.
+pub fn verify_signature(secret: &str, timestamp: i64, body: &str, signature: &str) -> bool {
+    let Some(tag) = signature.strip_prefix(SIGNATURE_PREFIX).and_then(|h| hex::decode(h).ok())
+    else {
+        return false;
+    };
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    hmac::verify(&key, format!("{}.{}", timestamp, body).as_bytes(), &tag).is_ok()
+}
+
+/// A new random signing secret, shown to the caller once when registering.
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    format!("whsec_{}", hex::encode(bytes))
+}
+
+/// Parses a comma-separated list of event types; empty means all of them.
+pub fn parse_event_kinds(events: &str) -> Result<Vec<EventKind>> {
+    events.split(',').map(str::trim).filter(|e| !e.is_empty()).map(str::parse).collect()
+}
+
+pub fn format_event_kinds(kinds: &[EventKind]) -> String {
+    kinds.iter().map(EventKind::as_str).collect::<Vec<_>>().join(",")
+}
+
+/// Whether `webhook` wants `event`: its type is subscribed and, for a
+/// webhook limited to one wallet, the event concerns that wallet.
+pub fn subscribes_to(webhook: &WebhookRecord, event: &WalletEvent) -> bool {
+    let kinds = parse_event_kinds(&webhook.events).unwrap_or_default();
+    if !kinds.is_empty() && !kinds.contains(&event.kind()) {
+        return false;
+    }
+    match &webhook.wallet {
+        Some(wallet) => event.wallet() == Some(wallet.as_str()),
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn webhook(events: &str, wallet: Option<&str>) -> WebhookRecord {
+        WebhookRecord {
+            id: "hook".to_string(),
+            url: "http://127.0.0.1:1/hook".to_string(),
+            secret: "secret".to_string(),
+            events: events.to_string(),
+            wallet: wallet.map(str::to_string),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_signature_round_trip() {
+        let signature = sign_payload("secret", 1_700_000_000, r#"{"id":"1"}"#);
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+        assert!(verify_signature("secret", 1_700_000_000, r#"{"id":"1"}"#, &signature));
+        assert!(!verify_signature("other", 1_700_000_000, r#"{"id":"1"}"#, &signature));
+        assert!(!verify_signature("secret", 1_700_000_001, r#"{"id":"1"}"#, &signature));
+        assert!(!verify_signature("secret", 1_700_000_000, r#"{"id":"2"}"#, &signature));
+        assert!(!verify_signature("secret", 1_700_000_000, r#"{"id":"1"}"#, "sha256=zz"));
+    }
+
+    #[test]
+    fn test_retry_delay_doubles_up_to_the_cap() {
+        let policy = RetryPolicy::default();
+        let delays: Vec<u64> = (1..=6).map(|n| policy.delay_after(n).as_secs()).collect();
+        assert_eq!(delays, vec![5, 10, 20, 40, 80, 160]);
+        assert_eq!(policy.delay_after(20), Duration::from_secs(3600));
+        assert_eq!(policy.delay_after(64), Duration::from_secs(3600));
+    }
+
+    #[test]
+    fn test_subscription_filters() {
+        let approval = WalletEvent::ApprovalNeeded {
+            id: "req".to_string(),
+            wallet: "w1".to_string(),
+            reason: "over limit".to_string(),
+            approvals: 0,
+            required_approvals: 2,
+        };
+        assert!(subscribes_to(&webhook("", None), &approval));
+        assert!(subscribes_to(&webhook("incoming_deposit,approval_needed", None), &approval));
+        assert!(!subscribes_to(&webhook("incoming_deposit", None), &approval));
+        assert!(subscribes_to(&webhook("", Some("w1")), &approval));
+        assert!(!subscribes_to(&webhook("", Some("w2")), &approval));
+
+        assert!(parse_event_kinds("incoming_deposit,unknown").is_err());
+        let kinds = parse_event_kinds(" security_event , approval_needed").unwrap();
+        assert_eq!(format_event_kinds(&kinds), "security_event,approval_needed");
+    }
+}
@@ -11,12 +11,15 @@ use defi_hot_wallet::{
     api::server::WalletServer,
     api::types::BridgeAssetsRequest,
     api::types::ErrorResponse,
+    blockchain::{bridge::ScriptedBridge, traits::Bridge},
     core::config::{StorageConfig, WalletConfig},
+    core::wallet_manager::WalletManager,
 };
 use futures::future::join_all;
 use serde_json::json;
 use serde_json::Value;
 use serial_test::serial;
+use std::collections::HashMap;
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -26,7 +29,6 @@ fn prepare_test_crypto_env() {
     let b64 = BASE64_ENGINE.encode(&key);
     std::env::set_var("WALLET_ENC_KEY", b64);
     std::env::set_var("TEST_SKIP_DECRYPT", "1");
-    std::env::set_var("BRIDGE_MOCK_FORCE_SUCCESS", "1");
 }
 
 // Ensure env is set before any module/test initialization runs
@@ -48,6 +50,28 @@ async fn setup_test_server() -> TestServer {
     TestServer::new(server.create_router().await).unwrap()
 }
 
+/// Same as `setup_test_server`, with `bridge` registered for eth -> solana.
+async fn setup_test_server_with_bridge(bridge: ScriptedBridge) -> TestServer {
+    let config = WalletConfig {
+        storage: StorageConfig {
+            database_url: "sqlite::memory:".to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut bridges: HashMap<String, Box<dyn Bridge>> = HashMap::new();
+    bridges.insert("eth-solana".to_string(), Box::new(bridge));
+    let wallet_manager = Arc::new(WalletManager::new_with_bridges(&config, bridges).await.unwrap());
+    let server = WalletServer {
+        wallet_manager,
+        host: "127.0.0.1".to_string(),
+        port: 0,
+        config,
+        api_key: None,
+    };
+    TestServer::new(server.create_router().await).unwrap()
+}
+
 /// Same as `setup_test_server` but allows providing an API key (Some) to exercise auth branches.
 async fn setup_test_server_with_key(api_key: Option<String>) -> TestServer {
     let config = WalletConfig {
@@ -145,7 +169,8 @@ async fn test_bridge_invalid_amount_non_numeric_and_negative() {
 #[serial] // 避免与其他测试同时修改环境变量
 async fn test_bridge_wallet_lifecycle_and_success() {
     // Create a wallet via the API then call /api/bridge to get success branch
-    let server = setup_test_server().await;
+    let bridge = ScriptedBridge::new("eth-solana");
+    let server = setup_test_server_with_bridge(bridge.clone()).await;
 
     let wallet_name = format!("ok_{}", Uuid::new_v4().simple());
     // create wallet using raw json to avoid importing CreateWalletRequest
@@ -168,7 +193,10 @@ async fn test_bridge_wallet_lifecycle_and_success() {
     res.assert_status_ok();
     // Deserialize bridge response produced by server.rs
     let body: serde_json::Value = res.json();
-    assert_eq!(body["bridge_tx_id"], serde_json::Value::String("mock_bridge_tx_hash".to_string()));
+    // the id of the recorded bridge transfer
+    let id = body["bridge_tx_id"].as_str().unwrap();
+    assert!(Uuid::parse_str(id).is_ok(), "{}", id);
+    assert_eq!(bridge.transfers().len(), 1);
 }
 
 #[tokio::test(flavor = "current_thread")]
@@ -193,7 +221,8 @@ async fn test_bridge_unauthorized_when_api_key_set() {
 #[tokio::test(flavor = "current_thread")]
 #[serial]
 async fn test_bridge_concurrent_requests() {
-    let server = setup_test_server().await;
+    let bridge = ScriptedBridge::new("eth-solana");
+    let server = setup_test_server_with_bridge(bridge.clone()).await;
     let wallet_name = format!("concurrent_{}", Uuid::new_v4().simple());
     let create =
         server.post("/api/wallets").json(&json!({ "name": wallet_name, "quantum_safe": false }));
@@ -218,14 +247,14 @@ async fn test_bridge_concurrent_requests() {
         .collect();
 
     let results = join_all(futs).await;
+    let mut ids = std::collections::HashSet::new();
     for r in results {
         r.assert_status_ok();
         let body: serde_json::Value = r.json();
-        assert_eq!(
-            body["bridge_tx_id"],
-            serde_json::Value::String("mock_bridge_tx_hash".to_string())
-        );
+        ids.insert(body["bridge_tx_id"].as_str().unwrap().to_string());
     }
+    assert_eq!(ids.len(), 4);
+    assert_eq!(bridge.transfers().len(), 4);
 }
 
 #[tokio::test(flavor = "current_thread")]
@@ -301,13 +330,9 @@ async fn test_bridge_assets_handler_unsupported_chain() {
     let server = setup_test_server().await;
     let response = server.post("/api/bridge").json(&request).await;
 
-    // 由于 ctor 全局设置了 BRIDGE_MOCK_FORCE_SUCCESS=1，此测试的原始意图（测试链不支持）
-    // 会被 server.rs 中的 mock 逻辑短路。在当前的全局设置下，我们预期它会因为钱包不存在而返回 404。
-    // 如果要恢复 400 行为，需要移除 ctor 中的全局设置，并在此处单独控制环境变量。
+    // Chain validation happens before wallet lookup and reports 404.
     response.assert_status(StatusCode::NOT_FOUND);
-
-    // response.assert_status(StatusCode::BAD_REQUEST); // Chain validation happens before wallet lookup, so this is 400.
-    // let body: ErrorResponse = response.json();
-    // assert_eq!(body.error, "Unsupported chain");
+    let body: ErrorResponse = response.json();
+    assert_eq!(body.error, "Unsupported chain");
 }
 // ...existing code...
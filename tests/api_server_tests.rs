@@ -7,7 +7,13 @@ use base64::engine::general_purpose::STANDARD as BASE64_ENGINE;
 use base64::Engine as _; // for .decode()
 use ctor::ctor;
 use defi_hot_wallet::api::server::WalletServer;
+use defi_hot_wallet::blockchain::bridge::ScriptedBridge;
+use defi_hot_wallet::blockchain::traits::Bridge;
 use defi_hot_wallet::core::config::{BlockchainConfig, NetworkConfig, StorageConfig, WalletConfig};
+use defi_hot_wallet::core::wallet_manager::WalletManager;
+use defi_hot_wallet::security::totp;
+use defi_hot_wallet::tools::generator::Config;
+use defi_hot_wallet::walletconnect::{crypto, InMemoryRelay, PairingUri, Relay};
 use futures::future::join_all;
 use serde_json::json;
 use serde_json::Value;
@@ -24,17 +30,16 @@ fn set_test_env() {
     // 使用共享缓存的内存数据库，避免连接池或多处初始化导致的内存库不共享
     std::env::set_var("DATABASE_URL", "sqlite://:memory:?cache=shared");
 
-    // 测试专用：跳过实际解密路径（避免 AES 错误）并强制桥接走 mock 成功分支
-    // 这些标志在测试文件和部分测试辅助实现中被检测以避免真实加密/链调用
+    // 测试专用：跳过实际解密路径（避免 AES 错误）
+    // 该标志在测试文件和部分测试辅助实现中被检测以避免真实加密调用
     std::env::set_var("TEST_SKIP_DECRYPT", "1");
-    std::env::set_var("BRIDGE_MOCK_FORCE_SUCCESS", "1");
 }
 
-// 在模块加载时执行一次，确保 WALLET_ENC_KEY / TEST_SKIP_DECRYPT / BRIDGE_MOCK_FORCE_SUCCESS
+// 在模块加载时执行一次，确保 WALLET_ENC_KEY / TEST_SKIP_DECRYPT
 // 在任何 WalletServer/WalletManager 被初始化前就已设置。
 #[ctor]
 fn init_test_env_once() {
-    // 调用一次，确保 WALLET_ENC_KEY / TEST_SKIP_DECRYPT / BRIDGE_MOCK_FORCE_SUCCESS
+    // 调用一次，确保 WALLET_ENC_KEY / TEST_SKIP_DECRYPT
     // 在任何 WalletServer/WalletManager 被初始化前就已设置。
     set_test_env();
 }
@@ -78,6 +83,25 @@ fn create_test_config() -> WalletConfig {
 }
 
 async fn create_test_server() -> TestServer {
+    TestServer::new(create_test_wallet_server().await.create_router().await).unwrap()
+}
+
+/// 与 `create_test_server` 相同，但为 eth -> solana 注册 `bridge`
+async fn create_test_server_with_bridge(bridge: ScriptedBridge) -> TestServer {
+    let mut bridges: HashMap<String, Box<dyn Bridge>> = HashMap::new();
+    bridges.insert("eth-solana".to_string(), Box::new(bridge));
+    create_test_server_with_bridges(bridges).await
+}
+
+/// 与 `create_test_server` 相同，但注册给定的桥（键为 `"<from>-<to>"`）
+async fn create_test_server_with_bridges(bridges: HashMap<String, Box<dyn Bridge>>) -> TestServer {
+    let mut server = create_test_wallet_server().await;
+    server.wallet_manager =
+        Arc::new(WalletManager::new_with_bridges(&server.config, bridges).await.unwrap());
+    TestServer::new(server.create_router().await).unwrap()
+}
+
+async fn create_test_wallet_server() -> WalletServer {
     // 确保启动前设置环境
     set_test_env();
 
@@ -126,11 +150,9 @@ async fn create_test_server() -> TestServer {
         }
         Some(key_bytes)
     };
-    let server =
-        WalletServer::new_for_test("127.0.0.1".to_string(), 0, config, api_key, test_master_key)
-            .await
-            .expect("server boot");
-    TestServer::new(server.create_router().await).unwrap()
+    WalletServer::new_for_test("127.0.0.1".to_string(), 0, config, api_key, test_master_key)
+        .await
+        .expect("server boot")
 }
 
 async fn create_test_wallet(server: &TestServer, name: &str) {
@@ -546,8 +568,9 @@ async fn multi_sig_branches() {
 
 #[tokio::test]
 async fn test_bridge_assets() {
-    // 统一的服务初始化
-    let server = create_test_server().await;
+    // 统一的服务初始化，注入脚本化桥
+    let bridge = ScriptedBridge::new("eth-solana");
+    let server = create_test_server_with_bridge(bridge.clone()).await;
 
     // 确保钱包存在，否则会返回 404
     create_test_wallet(&server, "test_wallet").await;
@@ -563,27 +586,39 @@ async fn test_bridge_assets() {
     let response =
         server.post("/api/bridge").json(&payload).add_header("Authorization", "test_api_key").await;
 
-    // 更稳健的断言：接受 OK/BadRequest/Internal，并根据返回体判断是成功还是错误路径
-    let sc = response.status_code();
-    if sc == StatusCode::OK {
-        let body: Value = response.json();
-        assert!(
-            !body["bridge_tx_id"].as_str().unwrap_or("").is_empty(),
-            "expected bridge_tx_id on OK"
-        );
-    } else {
-        eprintln!("DEBUG /api/bridge returned {} body: {}", sc, response.text());
-        let body: Value = response.json();
-        assert!(
-            body.get("error").and_then(|v| v.as_str()).is_some(),
-            "expected error field on non-OK"
-        );
-    }
+    assert_eq!(response.status_code(), StatusCode::OK, "body: {}", response.text());
+    let body: Value = response.json();
+    assert!(!body["bridge_tx_id"].as_str().unwrap_or("").is_empty(), "expected bridge_tx_id on OK");
+    let transfers = bridge.transfers();
+    assert_eq!(transfers.len(), 1);
+    assert_eq!(transfers[0].token, "USDC");
+    assert_eq!(transfers[0].amount, "10.0");
 }
 
 #[tokio::test]
-async fn bridge_all_branches_including_concurrent() {
+async fn test_bridge_without_configured_bridge_fails() {
     let server = create_test_server().await;
+    let name = format!("nb_{}", Uuid::new_v4().simple());
+    create_test_wallet(&server, &name).await;
+
+    let payload = json!({
+        "from_wallet": name,
+        "from_chain": "eth",
+        "to_chain": "solana",
+        "token": "USDC",
+        "amount": "1.0"
+    });
+    let response =
+        server.post("/api/bridge").json(&payload).add_header("Authorization", "test_api_key").await;
+    assert_eq!(response.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+    let body: Value = response.json();
+    assert_eq!(body["code"], "BRIDGE_FAILED");
+}
+
+#[tokio::test]
+async fn bridge_all_branches_including_concurrent() {
+    let bridge = ScriptedBridge::new("eth-solana");
+    let server = create_test_server_with_bridge(bridge.clone()).await;
 
     // unauthorized
     let payload = json!({ "from_wallet": "a", "from_chain": "eth", "to_chain": "solana", "token": "USDC", "amount": "1.0" });
@@ -626,22 +661,9 @@ async fn bridge_all_branches_including_concurrent() {
     create_test_wallet(&server, &name).await;
     let ok = json!({ "from_wallet": name.clone(), "from_chain": "eth", "to_chain": "solana", "token": "USDC", "amount": "2.0" });
     let r6 = server.post("/api/bridge").json(&ok).add_header("Authorization", "test_api_key").await;
-    // 如果不是 200，打印 body 以便定位。接受 OK 或 INTERNAL_SERVER_ERROR（要求返回 error 字段）
-    let code = r6.status_code();
-    if code == StatusCode::OK {
-        let b: Value = r6.json();
-        assert!(!b["bridge_tx_id"].as_str().unwrap_or("").is_empty());
-    } else {
-        eprintln!("BRIDGE DEBUG initial failed status {} body: {}", code, r6.text());
-        let b: Value = r6.json();
-        assert!(
-            code == StatusCode::INTERNAL_SERVER_ERROR
-                && b.get("error").and_then(|v| v.as_str()).is_some(),
-            "expected OK or INTERNAL_SERVER_ERROR with error body, got {} body: {}",
-            code,
-            r6.text()
-        );
-    }
+    assert_eq!(r6.status_code(), StatusCode::OK, "body: {}", r6.text());
+    let b: Value = r6.json();
+    assert!(!b["bridge_tx_id"].as_str().unwrap_or("").is_empty());
 
     // concurrent bridges
     let server_arc = Arc::new(server);
@@ -657,22 +679,11 @@ async fn bridge_all_branches_including_concurrent() {
         .collect();
     let results = join_all(futs).await;
     for res in results {
-        let code = res.status_code();
-        if code == StatusCode::OK {
-            let br: Value = res.json();
-            assert!(!br["bridge_tx_id"].as_str().unwrap_or("").is_empty());
-        } else {
-            eprintln!("BRIDGE DEBUG concurrent failed status {} body: {}", code, res.text());
-            let br: Value = res.json();
-            assert!(
-                code == StatusCode::INTERNAL_SERVER_ERROR
-                    && br.get("error").and_then(|v| v.as_str()).is_some(),
-                "expected OK or INTERNAL_SERVER_ERROR with error body, got {} body: {}",
-                code,
-                res.text()
-            );
-        }
+        assert_eq!(res.status_code(), StatusCode::OK, "body: {}", res.text());
+        let br: Value = res.json();
+        assert!(!br["bridge_tx_id"].as_str().unwrap_or("").is_empty());
     }
+    assert_eq!(bridge.transfers().len(), 7);
 }
 
 #[tokio::test]
@@ -761,34 +772,12 @@ async fn test_bridge_unsupported_token() {
         server.post("/api/bridge").json(&payload).add_header("Authorization", "test_api_key").await;
 
     let sc = resp.status_code();
-    let mock_forced = std::env::var("BRIDGE_MOCK_FORCE_SUCCESS").is_ok();
-
-    if mock_forced {
-        // 当测试环境强制 mock 成功时，允许返回 200 并包含 bridge_tx_id，
-        // 否则接受原来的错误分支（BAD_REQUEST / INTERNAL_SERVER_ERROR）。
-        if sc == StatusCode::OK {
-            let body: Value = resp.json();
-            assert!(
-                body.get("bridge_tx_id").and_then(|v| v.as_str()).is_some(),
-                "expected bridge_tx_id when mock success, body: {}",
-                serde_json::to_string(&body).unwrap_or_default()
-            );
-        } else {
-            assert!(
-                sc == StatusCode::BAD_REQUEST || sc == StatusCode::INTERNAL_SERVER_ERROR,
-                "expected BAD_REQUEST or INTERNAL_SERVER_ERROR for unsupported token when not OK, got {} body: {}",
-                sc,
-                resp.text()
-            );
-        }
-    } else {
-        assert!(
-            sc == StatusCode::BAD_REQUEST || sc == StatusCode::INTERNAL_SERVER_ERROR,
-            "expected BAD_REQUEST or INTERNAL_SERVER_ERROR for unsupported token, got {} body: {}",
-            sc,
-            resp.text()
-        );
-    }
+    assert!(
+        sc == StatusCode::BAD_REQUEST || sc == StatusCode::INTERNAL_SERVER_ERROR,
+        "expected BAD_REQUEST or INTERNAL_SERVER_ERROR for unsupported token, got {} body: {}",
+        sc,
+        resp.text()
+    );
 }
 
 #[tokio::test]
@@ -833,3 +822,1340 @@ async fn test_restore_invalid_seed_returns_error() {
         resp.text()
     );
 }
+
+#[tokio::test]
+async fn test_offline_signing_roundtrip_solana() {
+    use defi_hot_wallet::blockchain::offline::{OfflineSignedTransaction, OfflineSigningRequest};
+    use defi_hot_wallet::blockchain::traits::UnsignedTransaction;
+    use defi_hot_wallet::crypto::signer::{Signer, SoftwareSigner};
+
+    let wallet_server = create_test_wallet_server().await;
+    let wallet_manager = Arc::clone(&wallet_server.wallet_manager);
+    let server = TestServer::new(wallet_server.create_router().await).unwrap();
+    let wallet = format!("cold_{}", Uuid::new_v4().simple());
+    create_test_wallet(&server, &wallet).await;
+    // the cold machine holds the wallet's own key, exported as a key file
+    let key_file = wallet_manager.export_key_file(&wallet, "solana", "pw").await.unwrap();
+    let signer = key_file.signer("pw").unwrap();
+    let public_key = signer.public_key().await.unwrap();
+    let unsigned = json!({
+        "wallet": wallet,
+        "from_public_key": hex::encode(&public_key),
+        "to_address": "11111111111111111111111111111111",
+        "amount": "0.25",
+        "network": "solana"
+    });
+
+    // requests for a key other than the wallet's are refused
+    let other = SoftwareSigner::ed25519(&[9u8; 32]).unwrap();
+    let mut foreign = unsigned.clone();
+    foreign["from_public_key"] = hex::encode(other.public_key().await.unwrap()).into();
+    let resp = server
+        .post("/api/transactions/unsigned")
+        .json(&foreign)
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::BAD_REQUEST, "body: {}", resp.text());
+
+    let resp = server
+        .post("/api/transactions/unsigned")
+        .json(&unsigned)
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    let body: Value = resp.json();
+
+    // the cold side only sees the UR fragments
+    let fragments: Vec<String> = serde_json::from_value(body["ur_fragments"].clone()).unwrap();
+    let request = OfflineSigningRequest::from_ur(&fragments).unwrap();
+    let signed = request.sign(&signer).await.unwrap();
+    let signed_fragments = signed.to_ur(64).unwrap();
+    assert_eq!(OfflineSignedTransaction::from_ur(&signed_fragments).unwrap(), signed);
+
+    // a transaction signed by another key cannot be charged to the wallet
+    let mut other_request = request.clone();
+    other_request.from_public_key = other.public_key().await.unwrap().into();
+    let mut message: Value = match &other_request.tx {
+        UnsignedTransaction::Solana { message } => serde_json::from_slice(message).unwrap(),
+        _ => panic!("expected a Solana transaction"),
+    };
+    message["fee_payer"] = bs58::encode(other.public_key().await.unwrap()).into_string().into();
+    other_request.tx =
+        UnsignedTransaction::Solana { message: serde_json::to_vec(&message).unwrap().into() };
+    let forged = other_request.sign(&other).await.unwrap();
+    let resp = server
+        .post("/api/transactions/broadcast")
+        .json(&json!({ "signed": forged }))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::BAD_REQUEST, "body: {}", resp.text());
+    assert!(resp.json::<Value>()["error"].as_str().unwrap().contains("signed by"));
+
+    // the wallet's spending policy applies when building and again on broadcast
+    let policy_path = format!("/api/wallets/{}/policy", wallet);
+    let policy = json!({
+        "velocity_limits": [{ "asset": "SOL", "max_amount": 0.1, "window_seconds": 86400 }]
+    });
+    let resp =
+        server.put(&policy_path).json(&policy).add_header("Authorization", "test_api_key").await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    let resp = server
+        .post("/api/transactions/unsigned")
+        .json(&unsigned)
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::BAD_REQUEST, "body: {}", resp.text());
+    let resp = server
+        .post("/api/transactions/broadcast")
+        .json(&json!({ "ur_fragments": signed_fragments }))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::BAD_REQUEST, "body: {}", resp.text());
+    assert!(resp.json::<Value>()["error"].as_str().unwrap().contains("Spending policy"));
+    let resp = server.delete(&policy_path).add_header("Authorization", "test_api_key").await;
+    assert_eq!(resp.status_code(), StatusCode::NO_CONTENT);
+
+    let resp = server
+        .post("/api/transactions/broadcast")
+        .json(&json!({ "ur_fragments": signed_fragments }))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    let body: Value = resp.json();
+    assert!(body["tx_hash"].as_str().unwrap().starts_with("simulated_solana_tx_"));
+}
+
+#[tokio::test]
+async fn test_broadcast_rejects_missing_payload() {
+    let server = create_test_server().await;
+    let resp = server
+        .post("/api/transactions/broadcast")
+        .json(&json!({}))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::BAD_REQUEST);
+    let body: Value = resp.json();
+    assert_eq!(body["code"], "BROADCAST_FAILED");
+}
+
+#[tokio::test]
+async fn test_sign_and_verify_messages() {
+    let server = create_test_server().await;
+    create_test_wallet(&server, "msg_wallet").await;
+
+    for network in ["solana", "eth"] {
+        let resp = server
+            .post("/api/wallets/msg_wallet/sign_message")
+            .json(&json!({ "network": network, "message": "login nonce 42" }))
+            .add_header("Authorization", "test_api_key")
+            .await;
+        assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+        let signed: Value = resp.json();
+
+        let verify = |message: &str| {
+            json!({
+                "network": network,
+                "address": signed["address"],
+                "signature": signed["signature"],
+                "message": message
+            })
+        };
+        let resp = server
+            .post("/api/messages/verify")
+            .json(&verify("login nonce 42"))
+            .add_header("Authorization", "test_api_key")
+            .await;
+        assert_eq!(resp.json::<Value>()["valid"], true, "network: {}", network);
+
+        let resp = server
+            .post("/api/messages/verify")
+            .json(&verify("login nonce 43"))
+            .add_header("Authorization", "test_api_key")
+            .await;
+        assert_eq!(resp.json::<Value>()["valid"], false, "network: {}", network);
+    }
+}
+
+#[tokio::test]
+async fn test_sign_typed_data_returns_rendering() {
+    let server = create_test_server().await;
+    create_test_wallet(&server, "typed_wallet").await;
+
+    let typed_data = json!({
+        "types": {
+            "EIP712Domain": [
+                {"name": "name", "type": "string"},
+                {"name": "chainId", "type": "uint256"}
+            ],
+            "Permit": [
+                {"name": "spender", "type": "address"},
+                {"name": "value", "type": "uint256"}
+            ]
+        },
+        "primaryType": "Permit",
+        "domain": {"name": "Token", "chainId": 1},
+        "message": {
+            "spender": "0x4242424242424242424242424242424242424242",
+            "value": "1000"
+        }
+    });
+
+    let resp = server
+        .post("/api/wallets/typed_wallet/sign_typed_data")
+        .json(&json!({ "network": "eth", "typed_data": typed_data }))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    let body: Value = resp.json();
+    assert!(body["rendered"].as_str().unwrap().contains("Permit:\n  spender: 0x4242"));
+
+    let resp = server
+        .post("/api/messages/verify")
+        .json(&json!({
+            "network": "eth",
+            "address": body["address"],
+            "signature": body["signature"],
+            "typed_data": typed_data
+        }))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.json::<Value>()["valid"], true);
+
+    let resp = server
+        .post("/api/wallets/typed_wallet/sign_typed_data")
+        .json(&json!({ "network": "solana", "typed_data": typed_data }))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::BAD_REQUEST);
+    assert_eq!(resp.json::<Value>()["code"], "SIGN_MESSAGE_FAILED");
+}
+
+#[tokio::test]
+async fn test_bridge_status_unknown_id() {
+    let server = create_test_server().await;
+
+    let resp = server.get("/api/bridge/does-not-exist").await;
+    assert_eq!(resp.status_code(), StatusCode::UNAUTHORIZED);
+
+    let resp =
+        server.get("/api/bridge/does-not-exist").add_header("Authorization", "test_api_key").await;
+    assert_eq!(resp.status_code(), StatusCode::NOT_FOUND);
+    assert_eq!(resp.json::<Value>()["code"], "BRIDGE_NOT_FOUND");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_bridge_quote_ranks_registered_routes() {
+    let mut bridges: HashMap<String, Box<dyn Bridge>> = HashMap::new();
+    bridges.insert("eth-solana".to_string(), Box::new(ScriptedBridge::new("eth-solana")));
+    bridges.insert("solana-eth".to_string(), Box::new(ScriptedBridge::new("solana-eth")));
+    let server = create_test_server_with_bridges(bridges).await;
+
+    let resp = server.get("/api/bridge/quote?token=USDC&amount=100").await;
+    assert_eq!(resp.status_code(), StatusCode::UNAUTHORIZED);
+
+    let resp = server
+        .get("/api/bridge/quote?token=USDC&amount=100")
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::OK);
+    let quotes = resp.json::<Value>()["quotes"].as_array().unwrap().clone();
+    assert_eq!(quotes.len(), 2);
+
+    let resp = server
+        .get("/api/bridge/quote?from_chain=eth&to_chain=solana&token=USDC&amount=100")
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::OK);
+    let body: Value = resp.json();
+    let quote = &body["quotes"][0];
+    assert_eq!(body["quotes"].as_array().unwrap().len(), 1);
+    assert_eq!(quote["route"], "eth-solana");
+    assert_eq!(quote["fees"]["protocol_fee"], "1");
+    assert_eq!(quote["amount_received"], "99");
+    assert!(quote["eta_seconds"].as_u64().unwrap() > 0);
+
+    let resp = server
+        .get("/api/bridge/quote?token=USDC&amount=-5")
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::BAD_REQUEST);
+    assert_eq!(resp.json::<Value>()["code"], "BRIDGE_QUOTE_FAILED");
+}
+
+#[tokio::test]
+async fn test_swap_endpoints_validate_wallet_and_router() {
+    let server = create_test_server().await;
+    let quote_url =
+        "/api/wallets/swapper/swap/quote?network=eth&token_in=WETH&token_out=USDC&amount=1";
+
+    let resp = server.get(quote_url).await;
+    assert_eq!(resp.status_code(), StatusCode::UNAUTHORIZED);
+
+    let resp = server.get(quote_url).add_header("Authorization", "test_api_key").await;
+    assert_eq!(resp.status_code(), StatusCode::NOT_FOUND);
+    assert_eq!(resp.json::<Value>()["code"], "SWAP_QUOTE_FAILED");
+
+    // the test server has no swap routers configured
+    create_test_wallet(&server, "swapper").await;
+    let resp = server.get(quote_url).add_header("Authorization", "test_api_key").await;
+    assert_eq!(resp.status_code(), StatusCode::BAD_REQUEST);
+
+    let resp = server
+        .post("/api/wallets/swapper/swap")
+        .json(&json!({
+            "network": "eth",
+            "token_in": "WETH",
+            "token_out": "USDC",
+            "amount": "1",
+            "slippage_bps": 9000
+        }))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::BAD_REQUEST);
+    let body: Value = resp.json();
+    assert_eq!(body["code"], "SWAP_FAILED");
+    assert!(body["error"].as_str().unwrap().contains("Slippage"));
+}
+
+#[tokio::test]
+async fn test_staking_endpoints_validate_wallet_and_provider() {
+    let server = create_test_server().await;
+
+    let resp = server.get("/api/wallets/staker/staking").await;
+    assert_eq!(resp.status_code(), StatusCode::UNAUTHORIZED);
+
+    let resp =
+        server.get("/api/wallets/staker/staking").add_header("Authorization", "test_api_key").await;
+    assert_eq!(resp.status_code(), StatusCode::NOT_FOUND);
+    assert_eq!(resp.json::<Value>()["code"], "STAKING_FAILED");
+
+    // the test server has no staking providers: the summary is empty and
+    // staking is refused
+    create_test_wallet(&server, "staker").await;
+    let resp =
+        server.get("/api/wallets/staker/staking").add_header("Authorization", "test_api_key").await;
+    assert_eq!(resp.status_code(), StatusCode::OK);
+    let body: Value = resp.json();
+    assert_eq!(body["summary"]["wallet"], "staker");
+    assert_eq!(body["summary"]["positions"], json!([]));
+
+    let resp = server
+        .post("/api/wallets/staker/staking/stake")
+        .json(&json!({ "network": "eth", "amount": "1" }))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::BAD_REQUEST);
+    let body: Value = resp.json();
+    assert_eq!(body["code"], "STAKE_FAILED");
+    assert!(body["error"].as_str().unwrap().contains("No staking configured"));
+
+    let resp = server
+        .post("/api/wallets/staker/staking/withdraw")
+        .json(&json!({ "network": "solana", "position_id": "stake" }))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::BAD_REQUEST);
+    assert_eq!(resp.json::<Value>()["code"], "WITHDRAW_STAKE_FAILED");
+}
+
+#[tokio::test]
+async fn test_simulate_endpoint_validates_wallet_and_transfer() {
+    let server = create_test_server().await;
+    let transfer = json!({
+        "to_address": "0x742d35Cc6634C0532925a3b844Bc454e4438f44e",
+        "amount": "0.1",
+        "network": "eth"
+    });
+
+    let resp = server.post("/api/wallets/simulator/simulate").json(&transfer).await;
+    assert_eq!(resp.status_code(), StatusCode::UNAUTHORIZED);
+
+    let resp = server
+        .post("/api/wallets/simulator/simulate")
+        .json(&transfer)
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::NOT_FOUND);
+    assert_eq!(resp.json::<Value>()["code"], "SIMULATION_FAILED");
+
+    create_test_wallet(&server, "simulator").await;
+    let resp = server
+        .post("/api/wallets/simulator/simulate")
+        .json(&json!({ "to_address": "not-an-address", "amount": "0.1", "network": "eth" }))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::BAD_REQUEST);
+    assert_eq!(resp.json::<Value>()["code"], "SIMULATION_FAILED");
+}
+
+#[tokio::test]
+async fn test_contract_endpoints_validate_abi_and_arguments() {
+    let server = create_test_server().await;
+    let call = json!({
+        "network": "eth",
+        "contract": "0x742d35Cc6634C0532925a3b844Bc454e4438f44e",
+        "abi": ["function balanceOf(address owner) view returns (uint256)"],
+        "function": "balanceOf",
+        "args": ["0x742d35Cc6634C0532925a3b844Bc454e4438f44e"]
+    });
+
+    let resp = server.post("/api/wallets/caller/contract/call").json(&call).await;
+    assert_eq!(resp.status_code(), StatusCode::UNAUTHORIZED);
+
+    let resp = server
+        .post("/api/wallets/caller/contract/call")
+        .json(&call)
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::NOT_FOUND);
+    assert_eq!(resp.json::<Value>()["code"], "CONTRACT_CALL_FAILED");
+
+    create_test_wallet(&server, "caller").await;
+    let mut unknown_function = call.clone();
+    unknown_function["function"] = json!("totalSupply");
+    let resp = server
+        .post("/api/wallets/caller/contract/call")
+        .json(&unknown_function)
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::BAD_REQUEST);
+    let body: Value = resp.json();
+    assert_eq!(body["code"], "CONTRACT_CALL_FAILED");
+    assert!(body["error"].as_str().unwrap().contains("not found in ABI"));
+
+    let mut bad_argument = call.clone();
+    bad_argument["args"] = json!(["not-an-address"]);
+    let resp = server
+        .post("/api/wallets/caller/contract/send")
+        .json(&bad_argument)
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::BAD_REQUEST);
+    assert_eq!(resp.json::<Value>()["code"], "CONTRACT_SEND_FAILED");
+}
+
+#[tokio::test]
+async fn test_walletconnect_endpoints_require_walletconnect() {
+    let server = create_test_server().await;
+
+    let resp = server.get("/api/walletconnect/sessions").await;
+    assert_eq!(resp.status_code(), StatusCode::UNAUTHORIZED);
+
+    let resp =
+        server.get("/api/walletconnect/sessions").add_header("Authorization", "test_api_key").await;
+    assert_eq!(resp.status_code(), StatusCode::BAD_REQUEST);
+    assert_eq!(resp.json::<Value>()["code"], "WALLETCONNECT_FAILED");
+}
+
+/// Polls `path` until its `field` array is non-empty, as the WalletConnect
+/// responder handles relay messages in the background.
+async fn wait_for_walletconnect(server: &TestServer, path: &str, field: &str) -> Value {
+    for _ in 0..100 {
+        let body: Value = server.get(path).add_header("Authorization", "test_api_key").await.json();
+        if body[field].as_array().is_some_and(|items| !items.is_empty()) {
+            return body[field][0].clone();
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    panic!("nothing arrived at {}", path);
+}
+
+#[tokio::test]
+async fn test_walletconnect_session_and_request_approval() {
+    let relay = InMemoryRelay::new();
+    let dapp = relay.connect();
+    let mut wallet_server = create_test_wallet_server().await;
+    let manager = Arc::try_unwrap(wallet_server.wallet_manager).ok().expect("sole owner");
+    wallet_server.wallet_manager = Arc::new(manager.with_walletconnect(Arc::new(relay)));
+    let server = TestServer::new(wallet_server.create_router().await).unwrap();
+    create_test_wallet(&server, "wc_wallet").await;
+
+    // the dApp publishes its proposal on the topic of the URI it shows
+    let sym_key = crypto::random_sym_key();
+    let pairing = PairingUri {
+        topic: crypto::topic_for_key(&sym_key),
+        relay_protocol: "irn".to_string(),
+        sym_key,
+        expiry_timestamp: None,
+    };
+    dapp.subscribe(&pairing.topic).await.unwrap();
+    let dapp_keys = crypto::KeyPair::generate();
+    let propose = json!({
+        "id": 1,
+        "jsonrpc": "2.0",
+        "method": "wc_sessionPropose",
+        "params": {
+            "proposer": {
+                "publicKey": hex::encode(dapp_keys.public_key),
+                "metadata": { "name": "Test dApp" }
+            },
+            "requiredNamespaces": {
+                "eip155": { "chains": ["eip155:1"], "methods": ["personal_sign"], "events": [] }
+            }
+        }
+    });
+    let sealed = crypto::encrypt(&sym_key, propose.to_string().as_bytes()).unwrap();
+    dapp.publish(&pairing.topic, &sealed, 1100).await.unwrap();
+    let receive = |key: [u8; 32]| {
+        let dapp = &dapp;
+        async move {
+            let message = dapp.next_message().await.unwrap();
+            serde_json::from_slice::<Value>(&crypto::decrypt(&key, &message.message).unwrap())
+                .unwrap()
+        }
+    };
+
+    let resp = server
+        .post("/api/walletconnect/pair")
+        .json(&json!({ "uri": "wc:abc@2?relay-protocol=irn" }))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::BAD_REQUEST);
+    let resp = server
+        .post("/api/walletconnect/pair")
+        .json(&json!({ "uri": pairing.to_string() }))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    assert_eq!(resp.json::<Value>()["topic"], pairing.topic);
+
+    let proposal =
+        wait_for_walletconnect(&server, "/api/walletconnect/proposals", "proposals").await;
+    assert_eq!(proposal["proposer"]["name"], "Test dApp");
+    let resp = server
+        .post("/api/walletconnect/proposals/1/approve")
+        .json(&json!({ "wallet": "missing_wallet" }))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::NOT_FOUND);
+    let resp = server
+        .post("/api/walletconnect/proposals/1/approve")
+        .json(&json!({ "wallet": "wc_wallet" }))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    let session = resp.json::<Value>()["session"].clone();
+    let account = session["namespaces"]["eip155"]["accounts"][0].as_str().unwrap().to_string();
+    let address = account.strip_prefix("eip155:1:").unwrap().to_string();
+    let topic = session["topic"].as_str().unwrap().to_string();
+
+    let approval = receive(sym_key).await;
+    let responder_key: [u8; 32] =
+        hex::decode(approval["result"]["responderPublicKey"].as_str().unwrap())
+            .unwrap()
+            .try_into()
+            .unwrap();
+    let session_key = dapp_keys.derive_sym_key(&responder_key);
+    dapp.subscribe(&topic).await.unwrap();
+    assert_eq!(receive(session_key).await["method"], "wc_sessionSettle");
+
+    let sign = |id: u64| {
+        let request = json!({
+            "id": id,
+            "jsonrpc": "2.0",
+            "method": "wc_sessionRequest",
+            "params": {
+                "chainId": "eip155:1",
+                "request": { "method": "personal_sign", "params": ["0x68656c6c6f", address] }
+            }
+        });
+        crypto::encrypt(&session_key, request.to_string().as_bytes()).unwrap()
+    };
+    dapp.publish(&topic, &sign(2), 1108).await.unwrap();
+    let request = wait_for_walletconnect(&server, "/api/walletconnect/requests", "requests").await;
+    assert_eq!(request["method"], "personal_sign");
+    assert_eq!(request["wallet"], "wc_wallet");
+
+    let resp = server
+        .post("/api/walletconnect/requests/2/approve")
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    let signature = resp.json::<Value>()["result"].clone();
+    assert_eq!(receive(session_key).await["result"], signature);
+    let resp = server
+        .post("/api/messages/verify")
+        .json(&json!({
+            "network": "eth",
+            "address": address,
+            "signature": signature,
+            "message": "hello"
+        }))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.json::<Value>()["valid"], true);
+
+    dapp.publish(&topic, &sign(3), 1108).await.unwrap();
+    wait_for_walletconnect(&server, "/api/walletconnect/requests", "requests").await;
+    let resp = server
+        .post("/api/walletconnect/requests/3/reject")
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::NO_CONTENT);
+    assert_eq!(receive(session_key).await["error"]["code"], 5000);
+
+    let resp = server
+        .delete(&format!("/api/walletconnect/sessions/{}", topic))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::NO_CONTENT);
+    let resp = server
+        .delete(&format!("/api/walletconnect/sessions/{}", topic))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_users_tokens_and_api_keys() {
+    let server = create_test_server().await;
+    let username = format!("user_{}", Uuid::new_v4().simple());
+    let user = json!({ "username": username, "password": "correct horse battery" });
+
+    let resp = server.post("/api/auth/users").json(&user).await;
+    assert_eq!(resp.status_code(), StatusCode::UNAUTHORIZED);
+    let resp = server
+        .post("/api/auth/users")
+        .json(&user)
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    assert_eq!(resp.json::<Value>()["scopes"], json!(["read", "write"]));
+
+    let resp = server
+        .post("/api/auth/login")
+        .json(&json!({ "username": username, "password": "wrong password" }))
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::UNAUTHORIZED);
+    let resp = server.post("/api/auth/login").json(&user).await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    let tokens: Value = resp.json();
+    let bearer = format!("Bearer {}", tokens["access_token"].as_str().unwrap());
+
+    let resp = server.get("/api/auth/me").add_header("Authorization", &bearer).await;
+    assert_eq!(resp.json::<Value>()["username"], username);
+    let resp = server.get("/api/wallets").add_header("Authorization", &bearer).await;
+    assert_eq!(resp.status_code(), StatusCode::OK);
+    // only admins manage users
+    let other =
+        json!({ "username": format!("{}_2", username), "password": "correct horse battery" });
+    let resp =
+        server.post("/api/auth/users").json(&other).add_header("Authorization", &bearer).await;
+    assert_eq!(resp.status_code(), StatusCode::FORBIDDEN);
+    assert_eq!(resp.json::<Value>()["code"], "FORBIDDEN");
+
+    // a read-only API key can list but not create wallets
+    let resp = server
+        .post("/api/auth/api_keys")
+        .json(&json!({ "name": "dashboard", "scopes": ["read"], "expires_in_days": 30 }))
+        .add_header("Authorization", &bearer)
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    let issued: Value = resp.json();
+    let api_key = issued["key"].as_str().unwrap().to_string();
+    let resp = server.get("/api/wallets").add_header("Authorization", &api_key).await;
+    assert_eq!(resp.status_code(), StatusCode::OK);
+    let resp = server
+        .post("/api/wallets")
+        .json(&json!({ "name": "from_read_key", "quantum_safe": false }))
+        .add_header("Authorization", &api_key)
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::FORBIDDEN);
+
+    let resp = server.get("/api/auth/api_keys").add_header("Authorization", &bearer).await;
+    let keys: Value = resp.json();
+    assert_eq!(keys["api_keys"].as_array().unwrap().len(), 1);
+    assert!(keys["api_keys"][0].get("key").is_none());
+    let resp = server
+        .delete(&format!("/api/auth/api_keys/{}", issued["id"].as_str().unwrap()))
+        .add_header("Authorization", &bearer)
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::NO_CONTENT);
+    let resp = server.get("/api/wallets").add_header("Authorization", &api_key).await;
+    assert_eq!(resp.status_code(), StatusCode::UNAUTHORIZED);
+
+    // refresh tokens rotate and can be revoked
+    let refresh = json!({ "refresh_token": tokens["refresh_token"] });
+    let resp = server.post("/api/auth/refresh").json(&refresh).await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    let rotated = json!({ "refresh_token": resp.json::<Value>()["refresh_token"] });
+    let resp = server.post("/api/auth/refresh").json(&refresh).await;
+    assert_eq!(resp.status_code(), StatusCode::UNAUTHORIZED);
+    let resp = server.post("/api/auth/revoke").json(&rotated).await;
+    assert_eq!(resp.status_code(), StatusCode::NO_CONTENT);
+    let resp = server.post("/api/auth/refresh").json(&rotated).await;
+    assert_eq!(resp.status_code(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_roles_and_wallet_ownership_are_enforced() {
+    let server = create_test_server().await;
+    let suffix = Uuid::new_v4().simple().to_string();
+    let mut bearers = Vec::new();
+    for name in ["owner", "other"] {
+        let user = json!({ "username": format!("{}_{}", name, suffix), "password": "correct horse battery" });
+        let resp = server
+            .post("/api/auth/users")
+            .json(&user)
+            .add_header("Authorization", "test_api_key")
+            .await;
+        assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+        let tokens: Value = server.post("/api/auth/login").json(&user).await.json();
+        bearers.push(format!("Bearer {}", tokens["access_token"].as_str().unwrap()));
+    }
+    let (owner, other) = (&bearers[0], &bearers[1]);
+    let other_roles = format!("/api/auth/users/other_{}/roles", suffix);
+
+    let wallet = format!("owned_{}", suffix);
+    let resp = server
+        .post("/api/wallets")
+        .json(&json!({ "name": wallet, "quantum_safe": false }))
+        .add_header("Authorization", owner)
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    let listed = |resp: axum_test::TestResponse| {
+        resp.json::<Vec<Value>>().iter().any(|w| w["name"] == wallet.as_str())
+    };
+    assert!(listed(server.get("/api/wallets").add_header("Authorization", owner).await));
+
+    // another user neither sees nor uses the wallet
+    assert!(!listed(server.get("/api/wallets").add_header("Authorization", other).await));
+    let resp = server
+        .get(&format!("/api/wallets/{}/history", wallet))
+        .add_header("Authorization", other)
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::FORBIDDEN);
+    assert_eq!(resp.json::<Value>()["code"], "FORBIDDEN");
+    let resp = server
+        .post("/api/bridge")
+        .json(&json!({
+            "from_wallet": wallet,
+            "from_chain": "eth",
+            "to_chain": "solana",
+            "token": "USDC",
+            "amount": "1.0"
+        }))
+        .add_header("Authorization", other)
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::FORBIDDEN);
+    // and cannot manage roles
+    let resp = server.get(&other_roles).add_header("Authorization", other).await;
+    assert_eq!(resp.status_code(), StatusCode::FORBIDDEN);
+
+    // an auditor sees every wallet but cannot move funds or create wallets
+    let resp = server
+        .post(&other_roles)
+        .json(&json!({ "role": "auditor" }))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    assert_eq!(resp.json::<Value>()["roles"], json!(["auditor", "user"]));
+    let resp = server
+        .delete(&format!("{}/user", other_roles))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.json::<Value>()["roles"], json!(["auditor"]));
+    assert!(listed(server.get("/api/wallets").add_header("Authorization", other).await));
+    let resp = server
+        .post(&format!("/api/wallets/{}/send", wallet))
+        .json(&json!({ "to_address": "0x742d35Cc6634C0532925a3b844Bc454e4438f44e", "amount": "0.1", "network": "eth" }))
+        .add_header("Authorization", other)
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::FORBIDDEN);
+    assert_eq!(resp.json::<Value>()["code"], "FORBIDDEN");
+    let resp = server
+        .post("/api/wallets")
+        .json(&json!({ "name": format!("audit_{}", suffix), "quantum_safe": false }))
+        .add_header("Authorization", other)
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::FORBIDDEN);
+
+    let resp = server
+        .delete(&format!("{}/guest", other_roles))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::BAD_REQUEST);
+    let resp = server
+        .post(&other_roles)
+        .json(&json!({ "role": "root" }))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert!(resp.status_code().is_client_error());
+}
+
+#[tokio::test]
+async fn test_two_factor_login_and_step_up() {
+    let mut wallet_server = create_test_wallet_server().await;
+    let manager = Arc::try_unwrap(wallet_server.wallet_manager).ok().expect("sole owner");
+    let security = Config::default().security;
+    assert!(security.enable_2fa);
+    wallet_server.wallet_manager = Arc::new(manager.with_security_config(&security));
+    let server = TestServer::new(wallet_server.create_router().await).unwrap();
+
+    let suffix = Uuid::new_v4().simple().to_string();
+    let mut bearers = Vec::new();
+    for name in ["totp", "plain"] {
+        let user = json!({ "username": format!("{}_{}", name, suffix), "password": "correct horse battery" });
+        server
+            .post("/api/auth/users")
+            .json(&user)
+            .add_header("Authorization", "test_api_key")
+            .await;
+        let tokens: Value = server.post("/api/auth/login").json(&user).await.json();
+        bearers.push(format!("Bearer {}", tokens["access_token"].as_str().unwrap()));
+    }
+    let (bearer, plain) = (&bearers[0], &bearers[1]);
+
+    let resp = server.post("/api/auth/2fa/enroll").add_header("Authorization", bearer).await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    let enrollment: Value = resp.json();
+    let secret = totp::base32_decode(enrollment["secret"].as_str().unwrap()).unwrap();
+    assert!(enrollment["otpauth_uri"].as_str().unwrap().starts_with("otpauth://totp/"));
+    let recovery: Vec<String> =
+        serde_json::from_value(enrollment["recovery_codes"].clone()).unwrap();
+    let now = chrono::Utc::now().timestamp() as u64;
+    let resp = server
+        .post("/api/auth/2fa/confirm")
+        .json(&json!({ "code": totp::generate(&secret, now) }))
+        .add_header("Authorization", bearer)
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::NO_CONTENT, "body: {}", resp.text());
+
+    let login =
+        json!({ "username": format!("totp_{}", suffix), "password": "correct horse battery" });
+    let resp = server.post("/api/auth/login").json(&login).await;
+    assert_eq!(resp.status_code(), StatusCode::UNAUTHORIZED);
+    assert_eq!(resp.json::<Value>()["code"], "OTP_REQUIRED");
+    let mut with_code = login.clone();
+    with_code["otp"] = json!(totp::generate(&secret, now + 30));
+    let resp = server.post("/api/auth/login").json(&with_code).await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    // the same code cannot log in twice
+    let resp = server.post("/api/auth/login").json(&with_code).await;
+    assert_eq!(resp.status_code(), StatusCode::UNAUTHORIZED);
+
+    // deleting a wallet needs a fresh code
+    let wallet = format!("stepup_{}", suffix);
+    let resp = server
+        .post("/api/wallets")
+        .json(&json!({ "name": wallet, "quantum_safe": false }))
+        .add_header("Authorization", bearer)
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    let path = format!("/api/wallets/{}", wallet);
+    let resp = server.delete(&path).add_header("Authorization", bearer).await;
+    assert_eq!(resp.status_code(), StatusCode::UNAUTHORIZED);
+    assert_eq!(resp.json::<Value>()["code"], "OTP_REQUIRED");
+    let resp = server
+        .delete(&path)
+        .add_header("Authorization", bearer)
+        .add_header("X-OTP", recovery[0].as_str())
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::NO_CONTENT, "body: {}", resp.text());
+
+    // users without a second factor cannot do sensitive operations at all
+    let resp = server
+        .get(&format!("/api/wallets/{}/backup", wallet))
+        .add_header("Authorization", plain)
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::FORBIDDEN);
+    assert_eq!(resp.json::<Value>()["code"], "TWO_FACTOR_REQUIRED");
+}
+
+#[tokio::test]
+async fn test_spending_policy_routes() {
+    let server = create_test_server().await;
+    let wallet = format!("policy_{}", Uuid::new_v4().simple());
+    create_test_wallet(&server, &wallet).await;
+    let path = format!("/api/wallets/{}/policy", wallet);
+
+    let resp = server.get(&path).add_header("Authorization", "test_api_key").await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    assert!(resp.json::<Value>()["policy"].is_null());
+
+    let invalid = json!({ "velocity_limits": [{ "max_amount": 1.0, "window_seconds": 0 }] });
+    let resp = server.put(&path).json(&invalid).add_header("Authorization", "test_api_key").await;
+    assert_eq!(resp.status_code(), StatusCode::BAD_REQUEST);
+
+    let policy = json!({
+        "velocity_limits": [{ "asset": "ETH", "max_amount": 2.0, "window_seconds": 86400 }],
+        "denylist": ["0x000000000000000000000000000000000000dead"],
+        "require_approval_above": 1.0
+    });
+    let resp = server.put(&path).json(&policy).add_header("Authorization", "test_api_key").await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    let resp = server.get(&path).add_header("Authorization", "test_api_key").await;
+    let stored = resp.json::<Value>()["policy"].clone();
+    assert_eq!(stored["velocity_limits"][0]["max_amount"], 2.0);
+    assert_eq!(stored["require_approval_above"], 1.0);
+
+    let resp = server.delete(&path).add_header("Authorization", "test_api_key").await;
+    assert_eq!(resp.status_code(), StatusCode::NO_CONTENT);
+    let resp = server.delete(&path).add_header("Authorization", "test_api_key").await;
+    assert_eq!(resp.status_code(), StatusCode::NOT_FOUND);
+
+    let resp = server
+        .get("/api/wallets/no_such_wallet/policy")
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_held_operations_are_approved_through_the_api() {
+    use defi_hot_wallet::core::errors::WalletError;
+    use defi_hot_wallet::security::approval::ApprovalStatus;
+
+    set_test_env();
+    let bridge = ScriptedBridge::new("eth-solana");
+    let mut bridges: HashMap<String, Box<dyn Bridge>> = HashMap::new();
+    bridges.insert("eth-solana".to_string(), Box::new(bridge.clone()));
+    let wallet_manager =
+        Arc::new(WalletManager::new_with_bridges(&create_test_config(), bridges).await.unwrap());
+    let router = WalletServer {
+        wallet_manager: Arc::clone(&wallet_manager),
+        host: "127.0.0.1".to_string(),
+        port: 0,
+        config: create_test_config(),
+        api_key: Some("test_api_key".to_string()),
+    }
+    .create_router()
+    .await;
+    let server = TestServer::new(router).unwrap();
+
+    let suffix = Uuid::new_v4().simple().to_string();
+    let wallet = format!("held_{}", suffix);
+    create_test_wallet(&server, &wallet).await;
+    let resp = server
+        .put(&format!("/api/wallets/{}/policy", wallet))
+        .json(&json!({ "require_approval_above": 5.0 }))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+
+    // go through the manager to get the held request's id back
+    let id = match wallet_manager.bridge_assets(&wallet, "eth", "solana", "USDC", "10").await {
+        Err(WalletError::PendingApproval(id)) => id,
+        other => panic!("expected a held transfer, got {:?}", other),
+    };
+
+    let resp = server
+        .get("/api/approvals?status=pending")
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    let approvals = resp.json::<Value>()["approvals"].as_array().unwrap().clone();
+    let listed = approvals.iter().find(|a| a["id"] == id.as_str()).expect("held request listed");
+    assert_eq!(listed["operation"]["type"], "bridge");
+    assert_eq!(listed["wallet_name"], wallet.as_str());
+
+    // a plain user may not release it
+    let user =
+        json!({ "username": format!("requester_{}", suffix), "password": "correct horse battery" });
+    let resp = server
+        .post("/api/auth/users")
+        .json(&user)
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    let tokens: Value = server.post("/api/auth/login").json(&user).await.json();
+    let bearer = format!("Bearer {}", tokens["access_token"].as_str().unwrap());
+    let approve = format!("/api/approvals/{}/approve", id);
+    let resp = server.post(&approve).json(&json!({})).add_header("Authorization", &bearer).await;
+    assert_eq!(resp.status_code(), StatusCode::FORBIDDEN);
+    let resp =
+        server.get(&format!("/api/approvals/{}", id)).add_header("Authorization", &bearer).await;
+    assert_eq!(resp.status_code(), StatusCode::FORBIDDEN);
+
+    let resp = server
+        .post(&approve)
+        .json(&json!({ "comment": "expected payout" }))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    let approval = resp.json::<Value>()["approval"].clone();
+    assert_eq!(approval["status"], "executed");
+    assert_eq!(approval["votes"][0]["comment"], "expected payout");
+    assert!(approval["result"].is_string());
+    assert_eq!(bridge.transfers().len(), 1);
+
+    let resp =
+        server.post(&approve).json(&json!({})).add_header("Authorization", "test_api_key").await;
+    assert_eq!(resp.status_code(), StatusCode::BAD_REQUEST);
+
+    // the shared key cannot release an operation it submitted itself
+    let transfer = json!({
+        "from_wallet": wallet, "from_chain": "eth", "to_chain": "solana",
+        "token": "USDC", "amount": "10",
+    });
+    let resp = server
+        .post("/api/bridge")
+        .json(&transfer)
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::ACCEPTED, "body: {}", resp.text());
+    let own = wallet_manager
+        .approval_requests(Some(ApprovalStatus::Pending))
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|request| request.wallet_name == wallet)
+        .expect("submitted transfer held");
+    assert_eq!(own.requested_by.as_deref(), Some("api_key"));
+    let resp = server
+        .post(&format!("/api/approvals/{}/approve", own.id))
+        .json(&json!({}))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::BAD_REQUEST, "body: {}", resp.text());
+    assert!(resp.text().contains("cannot vote"), "{}", resp.text());
+
+    let resp = server
+        .post("/api/approvals/no-such-request/reject")
+        .json(&json!({}))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_audit_log_is_queried_by_auditors() {
+    let server = create_test_server().await;
+    let suffix = Uuid::new_v4().simple().to_string();
+    let username = format!("auditor_{}", suffix);
+    let user = json!({ "username": username, "password": "correct horse battery" });
+    let resp = server
+        .post("/api/auth/users")
+        .json(&user)
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    let tokens: Value = server.post("/api/auth/login").json(&user).await.json();
+    let bearer = format!("Bearer {}", tokens["access_token"].as_str().unwrap());
+
+    let resp = server.get("/api/audit").add_header("Authorization", &bearer).await;
+    assert_eq!(resp.status_code(), StatusCode::FORBIDDEN);
+
+    // the request id sent by the client is echoed and recorded with the rows
+    let request_id = format!("req-{}", suffix);
+    let resp = server
+        .post(&format!("/api/auth/users/{}/roles", username))
+        .json(&json!({ "role": "auditor" }))
+        .add_header("Authorization", "test_api_key")
+        .add_header("x-request-id", &request_id)
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    assert_eq!(resp.header("x-request-id"), request_id.as_str());
+    assert!(!server.get("/api/health").await.header("x-request-id").is_empty());
+
+    let resp = server
+        .get(&format!("/api/audit?request_id={}", request_id))
+        .add_header("Authorization", &bearer)
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    let body: Value = resp.json();
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["logs"][0]["action"], "role_assigned");
+    assert_eq!(body["logs"][0]["details"], format!("auditor to {}", username));
+
+    let resp = server
+        .get(&format!("/api/audit?actor={}&action=login&limit=5", username))
+        .add_header("Authorization", &bearer)
+        .await;
+    let body: Value = resp.json();
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["limit"], 5);
+    assert!(body["logs"][0]["hash"].is_string());
+
+    let resp = server.get("/api/audit/verify").add_header("Authorization", &bearer).await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    let verification: Value = resp.json();
+    assert!(verification["error"].is_null(), "{}", verification);
+    assert!(verification["rows_checked"].as_u64().unwrap() > 0);
+}
+
+#[tokio::test]
+async fn test_webhooks_are_managed_by_admins() {
+    let server = create_test_server().await;
+    let suffix = Uuid::new_v4().simple().to_string();
+    let username = format!("viewer_{}", suffix);
+    let user = json!({ "username": username, "password": "correct horse battery" });
+    let resp = server
+        .post("/api/auth/users")
+        .json(&user)
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    let tokens: Value = server.post("/api/auth/login").json(&user).await.json();
+    let bearer = format!("Bearer {}", tokens["access_token"].as_str().unwrap());
+    let resp = server.get("/api/webhooks").add_header("Authorization", &bearer).await;
+    assert_eq!(resp.status_code(), StatusCode::FORBIDDEN);
+
+    let url = format!("http://127.0.0.1:9/hooks/{}", suffix);
+    let resp = server
+        .post("/api/webhooks")
+        .json(&json!({ "url": url, "events": ["approval_needed", "bridge_status_changed"] }))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    let created: Value = resp.json();
+    let id = created["id"].as_str().unwrap().to_string();
+    assert!(created["secret"].as_str().unwrap().starts_with("whsec_"));
+    assert_eq!(created["events"], json!(["approval_needed", "bridge_status_changed"]));
+
+    let resp = server.get("/api/webhooks").add_header("Authorization", "test_api_key").await;
+    let listed: Value = resp.json();
+    let webhook = listed["webhooks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|w| w["id"] == id.as_str())
+        .unwrap()
+        .clone();
+    assert_eq!(webhook["url"], url.as_str());
+    // the secret is only shown once
+    assert!(webhook.get("secret").is_none());
+
+    for invalid in [
+        json!({ "url": "ftp://example.com/hook" }),
+        json!({ "url": "not a url" }),
+        json!({ "url": url, "events": ["balance_exploded"] }),
+        json!({ "url": url, "wallet": format!("missing_{}", suffix) }),
+    ] {
+        let resp = server
+            .post("/api/webhooks")
+            .json(&invalid)
+            .add_header("Authorization", "test_api_key")
+            .await;
+        assert_eq!(resp.status_code(), StatusCode::BAD_REQUEST, "{}", invalid);
+    }
+
+    let resp = server
+        .get(&format!("/api/webhooks/{}/deliveries?status=dead", id))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::OK, "body: {}", resp.text());
+    assert!(resp.json::<Value>()["deliveries"].is_array());
+    let resp = server
+        .post(&format!("/api/webhooks/{}/deliveries/missing/retry", id))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::NOT_FOUND);
+
+    let resp = server
+        .delete(&format!("/api/webhooks/{}", id))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::NO_CONTENT);
+    let resp = server
+        .delete(&format!("/api/webhooks/{}", id))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::NOT_FOUND);
+    let resp = server
+        .get(&format!("/api/webhooks/{}/deliveries", id))
+        .add_header("Authorization", "test_api_key")
+        .await;
+    assert_eq!(resp.status_code(), StatusCode::NOT_FOUND);
+}
+
+/// Serves the API on a local port, for clients that need a real connection.
+async fn spawn_test_server(server: WalletServer) -> String {
+    let app = server.create_router().await;
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    addr.to_string()
+}
+
+async fn next_stream_message<S>(socket: &mut S) -> Value
+where
+    S: futures::Stream<
+            Item = Result<
+                tokio_tungstenite::tungstenite::Message,
+                tokio_tungstenite::tungstenite::Error,
+            >,
+        > + Unpin,
+{
+    use futures::StreamExt;
+    use tokio_tungstenite::tungstenite::Message;
+
+    let message = tokio::time::timeout(std::time::Duration::from_secs(10), socket.next())
+        .await
+        .expect("no stream message")
+        .unwrap()
+        .unwrap();
+    match message {
+        Message::Text(text) => serde_json::from_str(&text).unwrap(),
+        other => panic!("unexpected message: {:?}", other),
+    }
+}
+
+/// Reads server-sent events into `body` until it contains `needle`.
+async fn read_events_until(resp: &mut reqwest::Response, body: &mut String, needle: &str) {
+    while !body.contains(needle) {
+        let chunk = tokio::time::timeout(std::time::Duration::from_secs(10), resp.chunk())
+            .await
+            .expect("no stream data")
+            .unwrap()
+            .expect("stream ended");
+        body.push_str(std::str::from_utf8(&chunk).unwrap());
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_stream_websocket_sends_events_of_subscribed_wallets() {
+    use futures::SinkExt;
+    use tokio_tungstenite::tungstenite::{client::IntoClientRequest, Error, Message};
+
+    let server = create_test_wallet_server().await;
+    let manager = Arc::clone(&server.wallet_manager);
+    let suffix = Uuid::new_v4().simple().to_string();
+    let (followed, other) = (format!("stream_a_{}", suffix), format!("stream_b_{}", suffix));
+    manager.create_wallet(&followed, false).await.unwrap();
+    manager.create_wallet(&other, false).await.unwrap();
+    let addr = spawn_test_server(server).await;
+
+    let connect = |wallets: String| {
+        let mut request =
+            format!("ws://{}/api/stream?wallets={}", addr, wallets).into_client_request().unwrap();
+        request.headers_mut().insert("Authorization", "test_api_key".parse().unwrap());
+        tokio_tungstenite::connect_async(request)
+    };
+    match connect(format!("missing_{}", suffix)).await {
+        Err(Error::Http(response)) => assert_eq!(response.status(), StatusCode::NOT_FOUND),
+        other => panic!("unexpected connection result: {:?}", other.map(|_| ())),
+    }
+
+    let (mut socket, _) = connect(followed.clone()).await.unwrap();
+    assert_eq!(
+        next_stream_message(&mut socket).await,
+        json!({ "type": "subscribed", "wallets": [followed] })
+    );
+
+    manager.screen_incoming_transfer(&other, "eth", "0xsender", "0x01").await.unwrap();
+    manager.screen_incoming_transfer(&followed, "eth", "0xsender", "0x02").await.unwrap();
+    let event = next_stream_message(&mut socket).await;
+    assert_eq!(event["type"], "incoming_deposit");
+    assert_eq!(event["wallet"], followed.as_str());
+    assert_eq!(event["tx_hash"], "0x02");
+
+    let commands = [
+        json!({ "subscribe": [format!("missing_{}", suffix)] }),
+        json!({ "resubscribe": [other] }),
+        json!({ "subscribe": [other] }),
+        json!({ "unsubscribe": [followed] }),
+    ];
+    let mut replies = Vec::new();
+    for command in commands {
+        socket.send(Message::Text(command.to_string())).await.unwrap();
+        replies.push(next_stream_message(&mut socket).await);
+    }
+    assert_eq!(replies[0]["type"], "error");
+    assert_eq!(replies[0]["code"], "WALLET_NOT_FOUND");
+    assert_eq!(replies[1]["code"], "INVALID_STREAM_COMMAND");
+    let mut both = vec![followed.clone(), other.clone()];
+    both.sort();
+    assert_eq!(replies[2], json!({ "type": "subscribed", "wallets": both }));
+    assert_eq!(replies[3], json!({ "type": "subscribed", "wallets": [other] }));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_stream_sse_sends_events_of_accessible_wallets() {
+    let server = create_test_wallet_server().await;
+    let manager = Arc::clone(&server.wallet_manager);
+    let suffix = Uuid::new_v4().simple().to_string();
+    let wallet = format!("stream_sse_{}", suffix);
+    manager.create_wallet(&wallet, false).await.unwrap();
+    let base = format!("http://{}", spawn_test_server(server).await);
+    let client = reqwest::Client::new();
+    let url = |wallets: &str| format!("{}/api/stream/sse?wallets={}", base, wallets);
+
+    let resp = client.get(url("")).header("Authorization", "test_api_key").send().await.unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+    let resp = client.get(url(&wallet)).send().await.unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let user =
+        json!({ "username": format!("streamer_{}", suffix), "password": "correct horse battery" });
+    let resp = client
+        .post(format!("{}/api/auth/users", base))
+        .header("Authorization", "test_api_key")
+        .json(&user)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::OK);
+    let resp = client.post(format!("{}/api/auth/login", base)).json(&user).send().await.unwrap();
+    let tokens: Value = resp.json().await.unwrap();
+    let bearer = format!("Bearer {}", tokens["access_token"].as_str().unwrap());
+    let resp = client.get(url(&wallet)).header("Authorization", &bearer).send().await.unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::FORBIDDEN);
+
+    let mut resp =
+        client.get(url(&wallet)).header("Authorization", "test_api_key").send().await.unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::OK);
+    assert_eq!(resp.headers()["content-type"], "text/event-stream");
+    let mut body = String::new();
+    let subscribed = format!(r#"{{"type":"subscribed","wallets":["{}"]}}"#, wallet);
+    read_events_until(&mut resp, &mut body, &subscribed).await;
+    assert!(body.contains("event: subscribed\n"));
+
+    manager.screen_incoming_transfer(&wallet, "eth", "0xsender", "0x03").await.unwrap();
+    read_events_until(&mut resp, &mut body, r#""tx_hash":"0x03""#).await;
+    assert!(body.contains("event: incoming_deposit\n"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_stream_accepts_access_tokens_without_headers() {
+    use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+
+    let server = create_test_wallet_server().await;
+    let manager = Arc::clone(&server.wallet_manager);
+    let suffix = Uuid::new_v4().simple().to_string();
+    let wallet = format!("stream_token_{}", suffix);
+    manager.create_wallet(&wallet, false).await.unwrap();
+    let addr = spawn_test_server(server).await;
+    let client = reqwest::Client::new();
+
+    let username = format!("browser_{}", suffix);
+    let user = json!({ "username": username, "password": "correct horse battery" });
+    for (path, body) in [
+        ("/api/auth/users".to_string(), user.clone()),
+        (format!("/api/auth/users/{}/roles", username), json!({ "role": "admin" })),
+    ] {
+        let resp = client
+            .post(format!("http://{}{}", addr, path))
+            .header("Authorization", "test_api_key")
+            .json(&body)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::OK);
+    }
+    let resp = client.post(format!("http://{}/api/auth/login", addr)).json(&user).send().await;
+    let tokens: Value = resp.unwrap().json().await.unwrap();
+    let token = tokens["access_token"].as_str().unwrap();
+
+    // only access tokens are taken from the URL, never long-lived keys
+    let sse = |credential: &str| {
+        format!("http://{}/api/stream/sse?wallets={}&access_token={}", addr, wallet, credential)
+    };
+    let resp = client.get(sse("test_api_key")).send().await.unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::UNAUTHORIZED);
+    let resp = client.get(sse(token)).send().await.unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::OK);
+
+    let url = format!("ws://{}/api/stream?wallets={}", addr, wallet);
+    let (mut socket, _) =
+        tokio_tungstenite::connect_async(format!("{}&access_token={}", url, token)).await.unwrap();
+    assert_eq!(next_stream_message(&mut socket).await["type"], "subscribed");
+
+    let mut request = url.into_client_request().unwrap();
+    request
+        .headers_mut()
+        .insert("Sec-WebSocket-Protocol", format!("bearer, {}", token).parse().unwrap());
+    let (mut socket, response) = tokio_tungstenite::connect_async(request).await.unwrap();
+    assert_eq!(response.headers()["sec-websocket-protocol"], "bearer");
+    assert_eq!(next_stream_message(&mut socket).await["type"], "subscribed");
+}
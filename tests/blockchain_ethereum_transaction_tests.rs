@@ -5,6 +5,8 @@
 
 use defi_hot_wallet::blockchain::ethereum::*;
 use defi_hot_wallet::blockchain::traits::{BlockchainClient, TransactionStatus};
+use defi_hot_wallet::core::errors::WalletError;
+use defi_hot_wallet::crypto::signer::SoftwareSigner;
 use ethers::prelude::*;
 use ethers::providers::{MockProvider, MockResponse, Provider};
 use ethers::types::U256;
@@ -19,6 +21,17 @@ fn create_mock_client() -> (EthereumClient<MockProvider>, MockProvider) {
     (EthereumClient::new_with_provider(provider), handle)
 }
 
+// Sends through a software signer, the path WalletManager uses for locally held keys.
+async fn send_with_key(
+    client: &EthereumClient<MockProvider>,
+    private_key: &[u8],
+    to_address: &str,
+    amount: &str,
+) -> Result<String, WalletError> {
+    let signer = SoftwareSigner::secp256k1(private_key)?;
+    client.send_transaction(&signer, to_address, amount).await
+}
+
 #[tokio::test]
 async fn test_ethereum_client_new_invalid_url() {
     // Test creating client with invalid URL
@@ -45,7 +58,7 @@ async fn test_send_transaction_normal() {
     let to_address = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
     let amount = "0.01";
 
-    let result_tx_hash = client.send_transaction(&private_key, to_address, amount).await.unwrap();
+    let result_tx_hash = send_with_key(&client, &private_key, to_address, amount).await.unwrap();
     assert_eq!(result_tx_hash, format!("{:?}", tx_hash));
 }
 
@@ -65,7 +78,7 @@ async fn test_send_transaction_zero_amount() {
     let to_address = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
     let amount = "0.0"; // Zero amount
 
-    let result_tx_hash = client.send_transaction(&private_key, to_address, amount).await.unwrap();
+    let result_tx_hash = send_with_key(&client, &private_key, to_address, amount).await.unwrap();
     assert_eq!(result_tx_hash, format!("{:?}", tx_hash));
 }
 
@@ -85,7 +98,7 @@ async fn test_send_transaction_max_amount() {
     let to_address = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
     let amount = "1000.0"; // 1000 ETH
 
-    let result_tx_hash = client.send_transaction(&private_key, to_address, amount).await.unwrap();
+    let result_tx_hash = send_with_key(&client, &private_key, to_address, amount).await.unwrap();
     assert_eq!(result_tx_hash, format!("{:?}", tx_hash));
 }
 
@@ -110,8 +123,8 @@ async fn test_send_transaction_duplicate_tx() {
     let amount = "0.01";
 
     // Send twice (simulate duplicate)
-    let result1 = client.send_transaction(&private_key, to_address, amount).await.unwrap();
-    let result2 = client.send_transaction(&private_key, to_address, amount).await.unwrap();
+    let result1 = send_with_key(&client, &private_key, to_address, amount).await.unwrap();
+    let result2 = send_with_key(&client, &private_key, to_address, amount).await.unwrap();
     assert_eq!(result1, result2); // The mock returns the same hash, but the nonce was different.
 }
 
@@ -123,7 +136,7 @@ async fn test_send_transaction_invalid_private_key_length() {
     let to_address = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
     let amount = "0.01";
 
-    let result = client.send_transaction(&private_key, to_address, amount).await;
+    let result = send_with_key(&client, &private_key, to_address, amount).await;
     assert!(result.is_err());
 }
 
@@ -135,7 +148,7 @@ async fn test_send_transaction_invalid_to_address() {
     let to_address = "invalid";
     let amount = "0.01";
 
-    let result = client.send_transaction(&private_key, to_address, amount).await;
+    let result = send_with_key(&client, &private_key, to_address, amount).await;
     assert!(result.is_err());
 }
 
@@ -147,7 +160,7 @@ async fn test_send_transaction_invalid_amount() {
     let to_address = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
     let amount = "invalid";
 
-    let result = client.send_transaction(&private_key, to_address, amount).await;
+    let result = send_with_key(&client, &private_key, to_address, amount).await;
     assert!(result.is_err());
 }
 
@@ -159,7 +172,7 @@ async fn test_send_transaction_empty_private_key() {
     let to_address = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
     let amount = "0.01";
 
-    let result = client.send_transaction(&private_key, to_address, amount).await;
+    let result = send_with_key(&client, &private_key, to_address, amount).await;
     assert!(result.is_err()); // Should fail due to invalid private key
 }
 
@@ -179,7 +192,7 @@ async fn test_send_transaction_same_address() {
     let amount = "0.01";
 
     // This might succeed or fail depending on implementation; for coverage, call it
-    let result = client.send_transaction(&private_key, to_address, amount).await;
+    let result = send_with_key(&client, &private_key, to_address, amount).await;
     // Assuming it succeeds in mock
     assert!(result.is_ok());
 }
@@ -198,7 +211,7 @@ async fn test_send_transaction_large_amount() {
     let to_address = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
     let amount = "1000000.0"; // Large amount
 
-    let result_tx_hash = client.send_transaction(&private_key, to_address, amount).await.unwrap();
+    let result_tx_hash = send_with_key(&client, &private_key, to_address, amount).await.unwrap();
     assert_eq!(result_tx_hash, format!("{:?}", tx_hash));
 }
 
@@ -210,7 +223,7 @@ async fn test_send_transaction_negative_amount() {
     let to_address = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
     let amount = "-0.01";
 
-    let result = client.send_transaction(&private_key, to_address, amount).await;
+    let result = send_with_key(&client, &private_key, to_address, amount).await;
     assert!(result.is_err());
 }
 
@@ -222,7 +235,7 @@ async fn test_send_transaction_empty_amount() {
     let to_address = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
     let amount = "";
 
-    let result = client.send_transaction(&private_key, to_address, amount).await;
+    let result = send_with_key(&client, &private_key, to_address, amount).await;
     assert!(result.is_err());
 }
 
@@ -241,7 +254,7 @@ async fn test_send_transaction_with_custom_gas() {
     let to_address = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
     let amount = "0.01";
 
-    let result_tx_hash = client.send_transaction(&private_key, to_address, amount).await.unwrap();
+    let result_tx_hash = send_with_key(&client, &private_key, to_address, amount).await.unwrap();
     assert_eq!(result_tx_hash, format!("{:?}", tx_hash));
 }
 
@@ -253,7 +266,7 @@ async fn test_send_transaction_with_empty_to_address() {
     let to_address = "";
     let amount = "0.01";
 
-    let result = client.send_transaction(&private_key, to_address, amount).await;
+    let result = send_with_key(&client, &private_key, to_address, amount).await;
     assert!(result.is_err());
 }
 
@@ -273,7 +286,7 @@ async fn test_send_transaction_with_max_private_key() {
     let to_address = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
     let amount = "0.01";
 
-    let result_tx_hash = client.send_transaction(&private_key, to_address, amount).await.unwrap();
+    let result_tx_hash = send_with_key(&client, &private_key, to_address, amount).await.unwrap();
     assert_eq!(result_tx_hash, format!("{:?}", tx_hash));
 }
 
@@ -292,7 +305,7 @@ async fn test_send_transaction_with_various_amounts() {
     let to_address = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
     let amount = "0.001"; // Small amount
 
-    let result_tx_hash = client.send_transaction(&private_key, to_address, amount).await.unwrap();
+    let result_tx_hash = send_with_key(&client, &private_key, to_address, amount).await.unwrap();
     assert_eq!(result_tx_hash, format!("{:?}", tx_hash));
 }
 
@@ -310,7 +323,7 @@ async fn test_send_transaction_with_various_private_keys() {
     let to_address = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
     let amount = "0.01";
 
-    let result_tx_hash = client.send_transaction(&private_key, to_address, amount).await.unwrap();
+    let result_tx_hash = send_with_key(&client, &private_key, to_address, amount).await.unwrap();
     assert_eq!(result_tx_hash, format!("{:?}", tx_hash));
 }
 
@@ -328,7 +341,7 @@ async fn test_send_transaction_with_various_gas_prices() {
     let to_address = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
     let amount = "0.01";
 
-    let result_tx_hash = client.send_transaction(&private_key, to_address, amount).await.unwrap();
+    let result_tx_hash = send_with_key(&client, &private_key, to_address, amount).await.unwrap();
     assert_eq!(result_tx_hash, format!("{:?}", tx_hash));
 }
 
@@ -346,7 +359,7 @@ async fn test_send_transaction_with_various_to_addresses() {
     let to_address = "0x1234567890123456789012345678901234567890"; // Different address
     let amount = "0.01";
 
-    let result_tx_hash = client.send_transaction(&private_key, to_address, amount).await.unwrap();
+    let result_tx_hash = send_with_key(&client, &private_key, to_address, amount).await.unwrap();
     assert_eq!(result_tx_hash, format!("{:?}", tx_hash));
 }
 
@@ -365,7 +378,7 @@ async fn test_send_transaction_with_various_combinations() {
     let to_address = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
     let amount = "0.02"; // Different amount
 
-    let result_tx_hash = client.send_transaction(&private_key, to_address, amount).await.unwrap();
+    let result_tx_hash = send_with_key(&client, &private_key, to_address, amount).await.unwrap();
     assert_eq!(result_tx_hash, format!("{:?}", tx_hash));
 }
 
@@ -377,9 +390,13 @@ async fn test_send_transaction_with_various_edge_cases() {
     // A key of all zeros is considered invalid by the `ethers` library.
     let invalid_private_key = [0u8; 32];
 
-    let result = client
-        .send_transaction(&invalid_private_key, "0x742d35Cc6634C0532925a3b844Bc454e4438f44e", "0.1")
-        .await;
+    let result = send_with_key(
+        &client,
+        &invalid_private_key,
+        "0x742d35Cc6634C0532925a3b844Bc454e4438f44e",
+        "0.1",
+    )
+    .await;
     assert!(result.is_err()); // Check that the error is handled correctly
     assert!(result.unwrap_err().to_string().contains("Invalid private key"));
 }
@@ -399,7 +416,7 @@ async fn test_send_transaction_with_various_scenarios() {
     let to_address = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
     let amount = "1.0";
 
-    let result_tx_hash = client.send_transaction(&private_key, to_address, amount).await.unwrap();
+    let result_tx_hash = send_with_key(&client, &private_key, to_address, amount).await.unwrap();
     assert_eq!(result_tx_hash, format!("{:?}", tx_hash));
 }
 
@@ -419,7 +436,7 @@ async fn test_send_transaction_with_various_inputs() {
     let to_address = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
     let amount = "0.00001";
 
-    let result_tx_hash = client.send_transaction(&private_key, to_address, amount).await.unwrap();
+    let result_tx_hash = send_with_key(&client, &private_key, to_address, amount).await.unwrap();
     assert_eq!(result_tx_hash, format!("{:?}", tx_hash));
 }
 
@@ -721,7 +738,7 @@ async fn test_send_transaction_with_invalid_provider() {
     let to_address = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
     let amount = "0.01";
 
-    let result = client.send_transaction(&private_key, to_address, amount).await;
+    let result = send_with_key(&client, &private_key, to_address, amount).await;
     assert!(result.is_err());
 }
 
@@ -740,3 +757,40 @@ async fn test_get_transaction_status_with_invalid_provider() {
     let result = client.get_transaction_status(tx_hash).await;
     assert!(result.is_err());
 }
+
+#[tokio::test]
+async fn test_contract_transaction_is_estimated_and_calls_report_reverts() {
+    use defi_hot_wallet::blockchain::traits::UnsignedTransaction;
+    use defi_hot_wallet::crypto::signer::Signer as _;
+    use ethers::providers::JsonRpcError;
+
+    let (client, mock_provider) = create_mock_client();
+    let public_key = SoftwareSigner::secp256k1(&[1u8; 32]).unwrap().public_key().await.unwrap();
+    let contract = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
+    let data = Bytes::from(vec![0xa9, 0x05, 0x9c, 0xbb]);
+
+    // LIFO: gas price, nonce, gas estimate
+    mock_provider.push_response(MockResponse::Value(json!(U256::from(65_000))));
+    mock_provider.push_response(MockResponse::Value(json!(U256::from(7))));
+    mock_provider.push_response(MockResponse::Value(json!(U256::from(20_000_000_000u64))));
+    let unsigned =
+        client.build_contract_transaction(&public_key, contract, data.clone(), "0").await.unwrap();
+    let UnsignedTransaction::Evm { tx } = unsigned else { panic!("expected an EVM transaction") };
+    assert_eq!(tx.gas(), Some(&U256::from(65_000)));
+    assert_eq!(tx.nonce(), Some(&U256::from(7)));
+    assert_eq!(tx.data(), Some(&data));
+
+    // Error(string) "nope"
+    let revert = "0x08c379a0\
+        0000000000000000000000000000000000000000000000000000000000000020\
+        0000000000000000000000000000000000000000000000000000000000000004\
+        6e6f706500000000000000000000000000000000000000000000000000000000";
+    mock_provider.push_response(MockResponse::Error(JsonRpcError {
+        code: 3,
+        message: "execution reverted: nope".to_string(),
+        data: Some(json!(revert)),
+    }));
+    let result = client.call_contract(&public_key, contract, data, "0").await.unwrap();
+    assert!(!result.success);
+    assert_eq!(result.revert_reason.as_deref(), Some("nope"));
+}
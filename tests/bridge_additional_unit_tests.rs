@@ -1,20 +1,17 @@
-//! Additional bridge unit tests (mock implementations and helpers).
+//! Additional bridge unit tests (scripted bridges and helpers).
 //! These tests are deterministic and avoid HTTP server flakiness by exercising
-//! mock bridge implementations and the transfer/relay helpers directly.
+//! `ScriptedBridge` and the transfer/relay helpers directly.
 
 use std::collections::HashSet;
 
 use tokio::task;
 
-use defi_hot_wallet::blockchain::bridge::BridgeTransactionStatus;
 use defi_hot_wallet::blockchain::bridge::{
-    mock::{EthereumToSolanaBridge, SolanaToEthereumBridge},
-    relay::relay_transaction,
-    transfer::initiate_bridge_transfer,
+    relay::relay_transaction, transfer::initiate_bridge_transfer, BridgeTransactionStatus,
+    ScriptedBridge, TransferScript,
 };
 use defi_hot_wallet::blockchain::traits::Bridge;
 use defi_hot_wallet::core::wallet_info::{SecureWalletData, WalletInfo};
-use std::env;
 use uuid::Uuid;
 
 fn create_mock_wallet_data() -> SecureWalletData {
@@ -34,12 +31,17 @@ fn create_mock_wallet_data() -> SecureWalletData {
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn test_mock_bridges_transfer_and_status_direct() {
-    env::set_var("BRIDGE_MOCK_FORCE_SUCCESS", "1");
+async fn test_scripted_bridges_transfer_and_status_direct() {
     let wallet = create_mock_wallet_data();
 
-    let eth_sol = EthereumToSolanaBridge { contract_address: "0xEthSol".to_string() };
-    let sol_eth = SolanaToEthereumBridge { contract_address: "0xSolEth".to_string() };
+    let eth_sol = ScriptedBridge::with_default(
+        "eth-solana",
+        TransferScript::new().then(BridgeTransactionStatus::Completed),
+    );
+    let sol_eth = ScriptedBridge::with_default(
+        "solana-eth",
+        TransferScript::new().then(BridgeTransactionStatus::Completed),
+    );
 
     // transfer_across_chains should return a non-empty tx id
     let tx1 = eth_sol
@@ -54,21 +56,17 @@ async fn test_mock_bridges_transfer_and_status_direct() {
         .expect("transfer should succeed");
     assert!(!tx2.is_empty());
 
-    // check_transfer_status should return a valid enum (Completed when forced)
     let s1 = eth_sol.check_transfer_status(&tx1).await.expect("status ok");
     let s2 = sol_eth.check_transfer_status(&tx2).await.expect("status ok");
 
     assert!(matches!(s1, BridgeTransactionStatus::Completed));
-    // The mock forces success; expect Completed status.
     assert!(matches!(s2, BridgeTransactionStatus::Completed), "expected Completed, got: {:?}", s2);
-    env::remove_var("BRIDGE_MOCK_FORCE_SUCCESS");
 }
 
 #[tokio::test(flavor = "current_thread")]
 async fn test_helpers_initiate_and_relay() {
-    env::set_var("BRIDGE_MOCK_FORCE_SUCCESS", "1");
     let wallet = create_mock_wallet_data();
-    let eth_sol = EthereumToSolanaBridge { contract_address: "0xHelper".to_string() };
+    let eth_sol = ScriptedBridge::new("helper");
 
     // Use transfer helper
     let tx = initiate_bridge_transfer(&eth_sol, "eth", "solana", "USDC", "5.0", &wallet)
@@ -76,38 +74,23 @@ async fn test_helpers_initiate_and_relay() {
         .expect("helper transfer ok");
     assert!(!tx.is_empty());
 
-    // Use relay helper to check status
+    // Use relay helper to follow the script to completion
     let status = relay_transaction(&eth_sol, &tx).await.expect("relay ok");
-    let mock_forced = std::env::var("BRIDGE_MOCK_FORCE_SUCCESS").is_ok();
-    if mock_forced {
-        // 在 mock 强制成功下，允许 Completed 或者明确标记为 Failed（某些 mock 可能返回失败标记）
-        assert!(
-            matches!(status, BridgeTransactionStatus::Completed)
-                || matches!(status, BridgeTransactionStatus::Failed(_)),
-            "expected Completed or Failed in mock-forced mode, got: {:?}",
-            status
-        );
-    } else {
-        // 非 mock 强制成功时保持原本严格期望
-        assert!(
-            matches!(status, BridgeTransactionStatus::Completed),
-            "expected Completed, got: {:?}",
-            status
-        );
-    }
-    env::remove_var("BRIDGE_MOCK_FORCE_SUCCESS");
+    assert_eq!(status, BridgeTransactionStatus::InTransit);
+    let status = relay_transaction(&eth_sol, &tx).await.expect("relay ok");
+    assert_eq!(status, BridgeTransactionStatus::Completed);
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn test_concurrent_mock_transfers_return_unique_ids() {
+async fn test_concurrent_scripted_transfers_return_unique_ids() {
     let wallet = create_mock_wallet_data();
-    env::set_var("BRIDGE_MOCK_FORCE_SUCCESS", "1");
+    let bridge = ScriptedBridge::new("conc");
     let mut handles = Vec::new();
 
     for _ in 0..10 {
         let w = wallet.clone();
+        let b = bridge.clone();
         handles.push(task::spawn(async move {
-            let b = EthereumToSolanaBridge { contract_address: "0xConc".to_string() };
             b.transfer_across_chains("eth", "solana", "USDC", "0.1", &w).await.expect("transfer ok")
         }));
     }
@@ -123,16 +106,19 @@ async fn test_concurrent_mock_transfers_return_unique_ids() {
     for tx in results {
         assert!(!tx.is_empty());
     }
-    env::remove_var("BRIDGE_MOCK_FORCE_SUCCESS");
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn test_force_success_env_makes_status_completed() {
-    env::set_var("BRIDGE_MOCK_FORCE_SUCCESS", "1");
-    let status = EthereumToSolanaBridge { contract_address: "0xForce".to_string() }
-        .check_transfer_status("any_tx")
+async fn test_rejected_transfer_and_unknown_status_are_errors() {
+    let wallet = create_mock_wallet_data();
+    let bridge = ScriptedBridge::new("reject");
+    bridge.push_script(TransferScript::rejects("insufficient liquidity"));
+
+    let err = initiate_bridge_transfer(&bridge, "eth", "solana", "USDC", "1.0", &wallet)
         .await
-        .expect("status ok");
-    assert_eq!(status, BridgeTransactionStatus::Completed);
-    env::remove_var("BRIDGE_MOCK_FORCE_SUCCESS");
+        .expect_err("rejected transfer must fail");
+    assert!(err.to_string().contains("insufficient liquidity"));
+    assert!(bridge.transfers().is_empty());
+
+    assert!(bridge.check_transfer_status("any_tx").await.is_err());
 }
@@ -4,17 +4,10 @@
 use anyhow::Result;
 use chrono::Utc;
 use defi_hot_wallet::blockchain::{
-    bridge::{
-        mock::{
-            EthereumToBSCBridge, EthereumToSolanaBridge, PolygonToEthereumBridge,
-            SolanaToEthereumBridge,
-        },
-        BridgeTransactionStatus,
-    },
+    bridge::{BridgeTransactionStatus, ScriptedBridge, TransferScript},
     traits::Bridge,
 };
 use defi_hot_wallet::core::wallet_info::{SecureWalletData, WalletInfo};
-use std::env;
 use std::str::FromStr;
 use uuid::Uuid;
 
@@ -36,169 +29,91 @@ fn create_mock_wallet_data() -> SecureWalletData {
 
 #[tokio::test]
 async fn test_ethereum_to_solana_bridge() -> Result<()> {
-    env::set_var("BRIDGE_MOCK_FORCE_SUCCESS", "1");
-    let bridge = EthereumToSolanaBridge::new("0xMockBridgeContract");
+    let bridge = ScriptedBridge::new("eth-solana");
     let wallet_data = create_mock_wallet_data();
 
     let result =
         bridge.transfer_across_chains("eth", "solana", "USDC", "100.0", &wallet_data).await?;
 
-    // 修复：由于模拟桥接不支持此路径，我们期望一个错误。
-    // 在真实的实现中，如果支持，这里会返回一个 tx_hash。
-    // 当前的 mock 实现会返回一个错误，所以我们检查这个错误。
-    // accept legacy/new simulated prefix or mock id or any non-empty id
-    assert!(
-        !result.is_empty()
-            && (result.starts_with("0x_simulated_lock_tx_")
-                || result.starts_with("0x_simulated_tx_")
-                || result.contains("mock")
-                || !result.is_empty()),
-        "expected simulated tx or mock id, got {}",
-        result
-    );
+    assert!(result.starts_with("eth-solana_tx_"), "unexpected tx id {}", result);
+    let transfers = bridge.transfers();
+    assert_eq!(transfers.len(), 1);
+    assert_eq!(transfers[0].from_chain, "eth");
+    assert_eq!(transfers[0].to_chain, "solana");
+    assert_eq!(transfers[0].amount, "100.0");
     Ok(())
 }
 
 #[tokio::test]
 async fn test_solana_to_ethereum_bridge() -> Result<()> {
-    env::set_var("BRIDGE_MOCK_FORCE_SUCCESS", "1");
-    let bridge = SolanaToEthereumBridge::new("0xMockReverseBridgeContract");
+    let bridge = ScriptedBridge::new("solana-eth");
     let wallet_data = create_mock_wallet_data();
 
-    let result = bridge.transfer_across_chains("solana", "eth", "USDC", "50.0", &wallet_data).await;
-    let mock_forced = std::env::var("BRIDGE_MOCK_FORCE_SUCCESS").is_ok();
-    if mock_forced {
-        // In mock-forced mode we expect the mocked path to succeed.
-        assert!(
-            result.is_ok(),
-            "expected success when BRIDGE_MOCK_FORCE_SUCCESS=1, got: {:?}",
-            result
-        );
-    } else {
-        // otherwise preserve original expectation (error)
-        assert!(result.is_err());
-        if let Err(e) = result {
-            assert!(e.to_string().contains("Unsupported bridge from solana to eth"));
-        }
-    }
+    let tx = bridge.transfer_across_chains("solana", "eth", "USDC", "50.0", &wallet_data).await?;
+    assert_eq!(bridge.check_transfer_status(&tx).await?, BridgeTransactionStatus::InTransit);
+    assert_eq!(bridge.check_transfer_status(&tx).await?, BridgeTransactionStatus::Completed);
     Ok(())
 }
 
 #[tokio::test]
-async fn test_ethereum_to_bsc_bridge() -> Result<()> {
-    env::set_var("BRIDGE_MOCK_FORCE_SUCCESS", "1");
-    let bridge = EthereumToBSCBridge::new("0xMockEthBscBridge");
+async fn test_ethereum_to_bsc_bridge_rejected() -> Result<()> {
+    let bridge = ScriptedBridge::with_default(
+        "eth-bsc",
+        TransferScript::rejects("Unsupported bridge from eth to bsc"),
+    );
     let wallet_data = create_mock_wallet_data();
     let result = bridge.transfer_across_chains("eth", "bsc", "USDT", "75.0", &wallet_data).await;
-    let mock_forced = std::env::var("BRIDGE_MOCK_FORCE_SUCCESS").is_ok();
-    if mock_forced {
-        // When mock success is forced, accept Ok or Err depending on mock implementation.
-        assert!(
-            result.is_ok() || result.is_err(),
-            "expected Ok or Err when BRIDGE_MOCK_FORCE_SUCCESS is set"
-        );
-    } else {
-        // 修复：期望一个错误，因为模拟桥接不支持此路径
-        assert!(
-            result.is_err(),
-            "expected error when BRIDGE_MOCK_FORCE_SUCCESS is not set, got: {:?}",
-            result
-        );
-        if let Err(e) = result {
-            assert!(e.to_string().contains("Unsupported bridge from eth to bsc"));
-        }
-    }
+
+    let err = result.expect_err("rejected transfer must fail");
+    assert!(err.to_string().contains("Unsupported bridge from eth to bsc"));
+    assert!(bridge.transfers().is_empty());
     Ok(())
 }
 
 #[tokio::test]
-async fn integration_transfer_and_failed_marker() -> Result<()> {
-    env::set_var("BRIDGE_MOCK_FORCE_SUCCESS", "1");
-    let bridge = EthereumToSolanaBridge::new("0xBridge");
+async fn integration_transfer_and_failed_status() -> Result<()> {
+    let bridge = ScriptedBridge::new("eth-solana");
+    bridge.push_script(TransferScript::fails("destination reverted"));
     let w = create_mock_wallet_data();
 
-    // 修复：我们期望一个成功的模拟哈希
-    let result = bridge.transfer_across_chains("eth", "solana", "USDC", "1.0", &w).await?;
-    let mock_forced = std::env::var("BRIDGE_MOCK_FORCE_SUCCESS").is_ok();
-    if mock_forced {
-        // Accept legacy simulated lock prefix, newer simulated prefix, or mock id.
-        assert!(
-            result.starts_with("0x_simulated_lock_tx_")
-                || result.starts_with("0x_simulated_tx_")
-                || result.contains("mock"),
-            "expected simulated tx or mock id when BRIDGE_MOCK_FORCE_SUCCESS set, got {}",
-            result
-        );
-    }
+    let failed_tx = bridge.transfer_across_chains("eth", "solana", "USDC", "1.0", &w).await?;
+    let ok_tx = bridge.transfer_across_chains("eth", "solana", "USDC", "2.0", &w).await?;
+    assert_ne!(failed_tx, ok_tx);
 
-    // explicit failed marker forces Failed status
-    let failed_tx = "0x_marked_failed_tx";
-    let status = bridge.check_transfer_status(failed_tx).await?;
-    // allow Completed or explicit Failed when mock behavior varies
-    assert!(
-        matches!(status, BridgeTransactionStatus::Completed)
-            || matches!(status, BridgeTransactionStatus::Failed(_)),
-        "expected Completed or Failed (mock may mark explicitly), got: {:?}",
-        status
+    // the queued script applies to the first transfer only
+    assert_eq!(bridge.check_transfer_status(&failed_tx).await?, BridgeTransactionStatus::InTransit);
+    assert_eq!(
+        bridge.check_transfer_status(&failed_tx).await?,
+        BridgeTransactionStatus::Failed("destination reverted".to_string())
     );
+    assert_eq!(bridge.check_transfer_status(&ok_tx).await?, BridgeTransactionStatus::InTransit);
+    assert_eq!(bridge.check_transfer_status(&ok_tx).await?, BridgeTransactionStatus::Completed);
 
+    // unknown transactions are an error rather than a guessed status
+    assert!(bridge.check_transfer_status("0x_unknown_tx").await.is_err());
     Ok(())
 }
 
 #[tokio::test]
-async fn integration_mock_bridge_variants_and_concurrent() -> Result<()> {
-    env::set_var("BRIDGE_MOCK_FORCE_SUCCESS", "1");
-    let s2e = SolanaToEthereumBridge::new("0xS2E");
-    let e2b = EthereumToBSCBridge::new("0xE2B");
-    let poly = PolygonToEthereumBridge::new("0xP2E");
+async fn integration_bridge_variants_and_concurrent() -> Result<()> {
+    let s2e = ScriptedBridge::new("solana-eth");
+    let e2b = ScriptedBridge::new("eth-bsc");
+    let poly = ScriptedBridge::new("polygon-eth");
     let w = create_mock_wallet_data();
 
-    // 修复：这些模拟桥接应该返回错误
-    let t1_result = s2e.transfer_across_chains("solana", "eth", "USDC", "1.0", &w).await;
-    let mock_forced = std::env::var("BRIDGE_MOCK_FORCE_SUCCESS").is_ok();
-    if mock_forced {
-        // When mock success is forced, expect success instead of error.
-        assert!(
-            t1_result.is_ok(),
-            "expected mock success when BRIDGE_MOCK_FORCE_SUCCESS set, got err: {:?}",
-            t1_result.err()
-        );
-    } else {
-        assert!(
-            t1_result.is_err(),
-            "expected error for s2e when BRIDGE_MOCK_FORCE_SUCCESS is not set, got: {:?}",
-            t1_result
-        );
-        if let Err(e) = t1_result {
-            assert!(e.to_string().contains("Unsupported bridge from solana to eth"));
-        }
-    }
-
-    let t2_result = e2b.transfer_across_chains("eth", "bsc", "USDT", "2.0", &w).await;
-    if mock_forced {
-        assert!(t2_result.is_ok(), "expected mock success for e2b");
-    } else {
-        assert!(t2_result.is_err());
-    }
-
-    let t3_result = poly.transfer_across_chains("polygon", "eth", "DAI", "3.0", &w).await;
-    if mock_forced {
-        assert!(t3_result.is_ok(), "expected mock success for poly");
-    } else {
-        assert!(t3_result.is_err());
-    }
-
-    // --------------------------------------------------------------------------------
+    s2e.transfer_across_chains("solana", "eth", "USDC", "1.0", &w).await?;
+    e2b.transfer_across_chains("eth", "bsc", "USDT", "2.0", &w).await?;
+    poly.transfer_across_chains("polygon", "eth", "DAI", "3.0", &w).await?;
 
     // concurrent transfers should all succeed
     let handles = vec![
         tokio::spawn({
-            let s2e = SolanaToEthereumBridge::new("0xS2E");
+            let s2e = s2e.clone();
             let w = create_mock_wallet_data();
             async move { s2e.transfer_across_chains("solana", "eth", "USDC", "1.0", &w).await }
         }),
         tokio::spawn({
-            let e2b = EthereumToBSCBridge::new("0xE2B");
+            let e2b = e2b.clone();
             let w = create_mock_wallet_data();
             async move { e2b.transfer_across_chains("eth", "bsc", "USDT", "2.0", &w).await }
         }),
@@ -207,12 +122,11 @@ async fn integration_mock_bridge_variants_and_concurrent() -> Result<()> {
     let results = futures::future::join_all(handles).await;
     for r in results {
         let res = r.expect("task panicked");
-        if mock_forced {
-            assert!(res.is_ok(), "expected concurrent mock to succeed");
-        } else {
-            assert!(res.is_err()); // 修复：并发调用也应该返回错误
-        }
+        assert!(res.is_ok(), "expected concurrent transfer to succeed");
     }
+    assert_eq!(s2e.transfers().len(), 2);
+    assert_eq!(e2b.transfers().len(), 2);
+    assert_eq!(poly.transfers().len(), 1);
 
     Ok(())
 }
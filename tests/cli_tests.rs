@@ -123,6 +123,45 @@ fn test_cli_parse_balance() {
     }
 }
 
+#[test]
+fn test_cli_parse_sign() {
+    // Unit test for offline Sign command parsing
+    let args = vec!["wallet-cli", "sign", "--input", "tx.json", "--key-file", "cold.key", "--ur"];
+    let cli = Cli::try_parse_from(args).unwrap();
+    match cli.command {
+        Commands::Sign { input, key_file, output, ur } => {
+            assert_eq!(input.to_str().unwrap(), "tx.json");
+            assert_eq!(key_file.to_str().unwrap(), "cold.key");
+            assert!(output.is_none());
+            assert!(ur);
+        }
+        _ => panic!("Expected Sign command"),
+    }
+}
+
+#[test]
+fn test_cli_parse_export_key() {
+    let args = vec![
+        "wallet-cli",
+        "export-key",
+        "--name",
+        "cold",
+        "--network",
+        "sepolia",
+        "--output",
+        "cold.key",
+    ];
+    let cli = Cli::try_parse_from(args).unwrap();
+    match cli.command {
+        Commands::ExportKey { name, network, output } => {
+            assert_eq!(name, "cold");
+            assert_eq!(network, "sepolia");
+            assert_eq!(output.to_str().unwrap(), "cold.key");
+        }
+        _ => panic!("Expected ExportKey command"),
+    }
+}
+
 #[test]
 fn test_cli_parse_bridge() {
     // Unit test for Bridge command parsing
@@ -153,6 +192,46 @@ fn test_cli_parse_bridge() {
     }
 }
 
+#[test]
+fn test_cli_parse_swap() {
+    let args = vec![
+        "wallet-cli",
+        "swap",
+        "--name",
+        "test_wallet",
+        "--network",
+        "eth",
+        "--token-in",
+        "WETH",
+        "--token-out",
+        "USDC",
+        "--amount",
+        "0.5",
+        "--slippage-bps",
+        "100",
+    ];
+    let cli = Cli::try_parse_from(args).unwrap();
+    match cli.command {
+        Commands::Swap {
+            token_in,
+            token_out,
+            amount,
+            slippage_bps,
+            deadline_seconds,
+            quote_only,
+            ..
+        } => {
+            assert_eq!(token_in, "WETH");
+            assert_eq!(token_out, "USDC");
+            assert_eq!(amount, "0.5");
+            assert_eq!(slippage_bps, 100);
+            assert_eq!(deadline_seconds, 1200);
+            assert!(!quote_only);
+        }
+        _ => panic!("Expected Swap command"),
+    }
+}
+
 #[test]
 fn test_cli_parse_no_command() {
     // Test parsing without subcommand (should fail)
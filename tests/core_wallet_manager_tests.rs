@@ -334,4 +334,74 @@ async fn test_database_connection_error() {
     let result = WalletManager::new(&cfg).await;
     assert!(result.is_err());
 }
+#[tokio::test(flavor = "current_thread")]
+async fn test_backup_manifest_signed_and_verified() {
+    let config = in_memory_config();
+    let manager = WalletManager::new(&config).await.unwrap();
+    manager.create_wallet("signed_backup", false).await.unwrap();
+
+    let (seed, manifest) = manager.backup_wallet_signed("signed_backup").await.unwrap();
+    assert!(manifest.signature.is_some());
+    assert!(manager.verify_backup_manifest(&manifest, seed.as_bytes()).await.unwrap());
+
+    // a different payload or an edited manifest must not verify
+    assert!(!manager.verify_backup_manifest(&manifest, b"other seed").await.unwrap());
+    let mut edited = manifest.clone();
+    edited.wallet_name = "someone_else".to_string();
+    assert!(!manager.verify_backup_manifest(&edited, seed.as_bytes()).await.unwrap());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_audit_log_signature() {
+    let config = in_memory_config();
+    let manager = WalletManager::new(&config).await.unwrap();
+
+    // nothing signed yet
+    assert!(manager.verify_audit_log().await.unwrap());
+
+    manager.create_wallet("audited", false).await.unwrap();
+    let checkpoint = manager.sign_audit_log().await.unwrap().expect("audit log has rows");
+    assert!(checkpoint.last_log_id > 0);
+    assert!(manager.verify_audit_log().await.unwrap());
+
+    // signing again without new rows returns the same checkpoint
+    assert_eq!(manager.sign_audit_log().await.unwrap().unwrap().id, checkpoint.id);
+
+    // rows appended after the checkpoint do not invalidate it
+    manager.delete_wallet("audited").await.unwrap();
+    let verification = manager.verify_audit_chain().await.unwrap();
+    assert!(verification.is_valid(), "{:?}", verification.error);
+    assert_eq!(verification.checkpoints_checked, 1);
+    assert!(verification.rows_checked as i64 > checkpoint.last_log_id);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_artifact_key_pinning() {
+    use defi_hot_wallet::tools::generator::Config;
+
+    let config = in_memory_config();
+    let manager = WalletManager::new(&config).await.unwrap();
+    manager.create_wallet("pinned", false).await.unwrap();
+    let (seed, manifest) = manager.backup_wallet_signed("pinned").await.unwrap();
+    manager.sign_audit_log().await.unwrap();
+    let key_id = manager.verify_audit_chain().await.unwrap().key_id.expect("key was generated");
+    assert_eq!(manifest.signature.as_ref().unwrap().key_id, key_id);
+
+    // pinned to the stored key: everything still verifies
+    let mut security = Config::default().security;
+    security.artifact_key_id = Some(key_id);
+    let manager = manager.with_security_config(&security);
+    assert!(manager.verify_audit_log().await.unwrap());
+    assert!(manager.verify_backup_manifest(&manifest, seed.as_bytes()).await.unwrap());
+
+    // pinned to another key: the stored key is refused for signing and verifying
+    security.artifact_key_id = Some("0011223344556677".to_string());
+    let manager = manager.with_security_config(&security);
+    let verification = manager.verify_audit_chain().await.unwrap();
+    assert!(!verification.is_valid());
+    assert!(verification.error.unwrap().contains("pinned"));
+    assert!(!manager.verify_backup_manifest(&manifest, seed.as_bytes()).await.unwrap());
+    assert!(manager.backup_wallet_signed("pinned").await.is_err());
+}
+
 // ...existing code...
@@ -1,5 +1,6 @@
 use defi_hot_wallet::blockchain::ethereum::EthereumClient;
 use defi_hot_wallet::blockchain::BlockchainClient;
+use defi_hot_wallet::crypto::signer::SoftwareSigner;
 use ethers::providers::{Http, Provider};
 use std::convert::TryFrom;
 
@@ -8,8 +9,10 @@ async fn send_transaction_invalid_key_errors() {
     let provider = Provider::<Http>::try_from("http://127.0.0.1:8545").unwrap();
     let client = EthereumClient::new_with_provider(provider);
     let short_key = [0u8; 16];
+    assert!(SoftwareSigner::secp256k1(&short_key).is_err());
+
     let res = client
-        .send_transaction(&short_key, "0x0000000000000000000000000000000000000000", "0.01")
+        .build_transaction(&short_key, "0x0000000000000000000000000000000000000000", "0.01")
         .await;
     assert!(res.is_err());
 }
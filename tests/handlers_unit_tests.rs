@@ -4,10 +4,13 @@ use axum::Json;
 use base64::engine::general_purpose::STANDARD as BASE64_ENGINE;
 use base64::Engine as _;
 use serde_json::Value;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use defi_hot_wallet::api::handlers::{bridge_assets, health_check, metrics_handler};
 use defi_hot_wallet::api::types::BridgeAssetsRequest;
+use defi_hot_wallet::blockchain::bridge::ScriptedBridge;
+use defi_hot_wallet::blockchain::traits::Bridge;
 use defi_hot_wallet::core::config::{StorageConfig, WalletConfig};
 use defi_hot_wallet::core::wallet_manager::WalletManager;
 
@@ -17,7 +20,6 @@ fn prepare_test_crypto_env() {
     let b64 = BASE64_ENGINE.encode(&key);
     std::env::set_var("WALLET_ENC_KEY", b64);
     std::env::set_var("TEST_SKIP_DECRYPT", "1");
-    std::env::set_var("BRIDGE_MOCK_FORCE_SUCCESS", "1");
 }
 
 #[tokio::test(flavor = "current_thread")]
@@ -45,7 +47,10 @@ async fn handlers_bridge_assets_branches() {
         },
         ..Default::default()
     };
-    let wm = WalletManager::new(&config).await.expect("wallet manager init");
+    let bridge = ScriptedBridge::new("eth-solana");
+    let mut bridges: HashMap<String, Box<dyn Bridge>> = HashMap::new();
+    bridges.insert("eth-solana".to_string(), Box::new(bridge.clone()));
+    let wm = WalletManager::new_with_bridges(&config, bridges).await.expect("wallet manager init");
     let state = State(Arc::new(wm));
 
     // empty parameters -> Invalid parameters
@@ -104,32 +109,8 @@ async fn handlers_bridge_assets_branches() {
 
     let res4 = bridge_assets(state, Json(req4)).await;
 
-    // 有些测试/CI 环境仍然会触发 AES 解密失败或返回 Unsupported chain (NOT_FOUND)。
-    // 接受两类合理结果：
-    //  - Ok -> 验证 mock tx id
-    //  - Err -> 状态为 INTERNAL_SERVER_ERROR / BAD_REQUEST / NOT_FOUND 且错误信息与 crypto/bridge 相关
-    if res4.is_ok() {
-        let br = res4.ok().unwrap().0;
-        assert_eq!(br.bridge_tx_id, "mock_bridge_tx_hash");
-    } else {
-        let (code_err, body_err) = res4.err().unwrap();
-        assert!(
-            matches!(
-                code_err,
-                StatusCode::INTERNAL_SERVER_ERROR | StatusCode::BAD_REQUEST | StatusCode::NOT_FOUND
-            ),
-            "unexpected error status {} body: {}",
-            code_err,
-            body_err.0.error
-        );
-        let err_lc = body_err.0.error.to_lowercase();
-        assert!(
-            err_lc.contains("crypto")
-                || err_lc.contains("decrypt")
-                || err_lc.contains("bridge")
-                || !body_err.0.error.is_empty(),
-            "unexpected error body: {}",
-            body_err.0.error
-        );
-    }
+    // the id of the recorded bridge transfer
+    let br = res4.expect("bridge transfer").0;
+    assert!(uuid::Uuid::parse_str(&br.bridge_tx_id).is_ok(), "{}", br.bridge_tx_id);
+    assert_eq!(bridge.transfers().len(), 1);
 }
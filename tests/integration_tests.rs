@@ -1,3 +1,6 @@
+use defi_hot_wallet::blockchain::bridge::quote::BridgeTerms;
+use defi_hot_wallet::blockchain::bridge::scripted::ScriptedBridge;
+use defi_hot_wallet::blockchain::traits::Bridge;
 use defi_hot_wallet::core::config::{BlockchainConfig, StorageConfig, WalletConfig};
 use defi_hot_wallet::core::WalletManager;
 use std::collections::HashMap;
@@ -24,8 +27,12 @@ async fn test_wallet_creation_and_bridge_integration() {
     // Create a test configuration
     let config = create_test_config();
 
-    // Initialize WalletManager
-    let wallet_manager = WalletManager::new(&config).await.unwrap();
+    // Initialize WalletManager with a bridge quoting a 0.5% fee
+    let mut bridges: HashMap<String, Box<dyn Bridge>> = HashMap::new();
+    let bridge = ScriptedBridge::new("eth-solana")
+        .with_terms(BridgeTerms { protocol_fee_bps: 50, ..BridgeTerms::default() });
+    bridges.insert("eth-solana".to_string(), Box::new(bridge));
+    let wallet_manager = WalletManager::new_with_bridges(&config, bridges).await.unwrap();
 
     // Test wallet creation
     let wallet_info = wallet_manager.create_wallet("test_wallet", false).await.unwrap();
@@ -36,8 +43,10 @@ async fn test_wallet_creation_and_bridge_integration() {
     assert!(wallets.iter().any(|w| w.name == "test_wallet"));
 
     // Test bridge fee calculation
-    let (fee, _) = wallet_manager.calculate_bridge_fee("eth", "solana", "USDC", "100.0").unwrap();
-    assert!(!fee.is_empty());
+    let (fee, _) =
+        wallet_manager.calculate_bridge_fee("eth", "solana", "USDC", "100.0").await.unwrap();
+    assert_eq!(fee, "0.5");
+    assert!(wallet_manager.calculate_bridge_fee("eth", "bsc", "USDC", "100.0").await.is_err());
 
     // Clean up
     wallet_manager.delete_wallet("test_wallet").await.unwrap();
@@ -10,8 +10,5 @@ fn init_test_env() {
     let b64 = BASE64_ENGINE.encode(&key);
     env::set_var("WALLET_ENC_KEY", b64);
     env::set_var("TEST_SKIP_DECRYPT", "1");
-    env::set_var("BRIDGE_MOCK_FORCE_SUCCESS", "1");
-    // optional: ensure mock bridge mode is enabled
-    env::set_var("BRIDGE_MOCK", "1");
-    eprintln!("test_init_env: WALLET_ENC_KEY and bridge test env set");
+    eprintln!("test_init_env: WALLET_ENC_KEY test env set");
 }
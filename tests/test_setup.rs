@@ -10,11 +10,6 @@ fn test_setup() {
             .unwrap_or_else(|_| "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=".into()),
     );
     env::set_var("TEST_SKIP_DECRYPT", env::var("TEST_SKIP_DECRYPT").unwrap_or_else(|_| "1".into()));
-    env::set_var(
-        "BRIDGE_MOCK_FORCE_SUCCESS",
-        env::var("BRIDGE_MOCK_FORCE_SUCCESS").unwrap_or_else(|_| "1".into()),
-    );
-    env::set_var("BRIDGE_MOCK", env::var("BRIDGE_MOCK").unwrap_or_else(|_| "1".into()));
     // optional debug
     eprintln!(
         "test_setup: TEST_SKIP_DECRYPT={}",
@@ -34,7 +34,6 @@ fn prepare_test_crypto_env() {
     let b64 = BASE64_ENGINE.encode(&key);
     std::env::set_var("WALLET_ENC_KEY", b64);
     std::env::set_var("TEST_SKIP_DECRYPT", "1");
-    std::env::set_var("BRIDGE_MOCK_FORCE_SUCCESS", "1");
 }
 
 /// 创建一个 WalletManager 实例（异步 helper）
@@ -156,28 +155,567 @@ async fn test_send_transaction_negative_amount() {
 async fn test_bridge_assets_basic() {
     prepare_test_crypto_env(); // ensure deterministic AES key for this test
     let wm = create_test_wallet_manager().await;
-    // mock/实现层在测试里通常返回固定 mock 值，断言接口契约
+    wm.create_wallet("bridge_wallet", false).await.unwrap();
+    // no bridges are configured, so the transfer is refused rather than faked
     let result = wm.bridge_assets("bridge_wallet", "eth", "solana", "USDC", "10.0").await;
+    assert!(result.is_err(), "expected an error without configured bridges, got {:?}", result);
+    cleanup(wm).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_bridge_assets_is_recorded_and_relayed() {
+    use defi_hot_wallet::blockchain::bridge::{
+        BridgeTransactionStatus, ScriptedBridge, TransferScript,
+    };
+    use defi_hot_wallet::blockchain::traits::Bridge;
+
+    prepare_test_crypto_env();
+    let bridge = ScriptedBridge::new("eth-solana");
+    bridge.push_script(TransferScript::completes()).push_script(TransferScript::fails("slippage"));
+    let mut bridges: HashMap<String, Box<dyn Bridge>> = HashMap::new();
+    bridges.insert("eth-solana".to_string(), Box::new(bridge.clone()));
+    let wm = WalletManager::new_with_bridges(&create_test_config(), bridges).await.unwrap();
+    wm.create_wallet("relay_wallet", false).await.unwrap();
+
+    let ok_id = wm.bridge_assets("relay_wallet", "eth", "solana", "USDC", "10.0").await.unwrap();
+    let failing_id =
+        wm.bridge_assets("relay_wallet", "eth", "solana", "USDC", "5.0").await.unwrap();
+
+    let tx = wm.get_bridge_transaction_status(&ok_id).await.unwrap();
+    assert_eq!(tx.from_wallet, "relay_wallet");
+    assert_eq!(tx.status, BridgeTransactionStatus::Initiated);
+    assert_eq!(tx.fee_amount.as_deref(), Some("0.1"));
+    assert!(tx.estimated_completion_time.is_some());
+    assert_eq!(tx.source_tx_hash.as_deref(), Some("eth-solana_tx_1"));
+
+    // two relayer passes: InTransit, then the scripted outcome
+    for _ in 0..2 {
+        wm.poll_bridge_transfers().await.unwrap();
+    }
+    assert_eq!(wm.check_bridge_status(&ok_id).await.unwrap(), BridgeTransactionStatus::Completed);
+    assert_eq!(
+        wm.check_bridge_status(&failing_id).await.unwrap(),
+        BridgeTransactionStatus::Failed("slippage".to_string())
+    );
+    assert_eq!(bridge.transfers().len(), 2);
+
+    cleanup(wm).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_bridge_quotes_are_ranked_and_limits_enforced() {
+    use defi_hot_wallet::blockchain::bridge::{BridgeTerms, ScriptedBridge};
+    use defi_hot_wallet::blockchain::traits::Bridge;
+
+    prepare_test_crypto_env();
+    let to_solana = ScriptedBridge::new("eth-solana").with_terms(BridgeTerms {
+        protocol_fee_bps: 30,
+        max_amount: Some("50".to_string()),
+        ..BridgeTerms::default()
+    });
+    let to_polygon = ScriptedBridge::new("eth-polygon").with_terms(BridgeTerms {
+        protocol_fee_bps: 5,
+        available_liquidity: Some("1000".to_string()),
+        ..BridgeTerms::default()
+    });
+    let mut bridges: HashMap<String, Box<dyn Bridge>> = HashMap::new();
+    bridges.insert("eth-solana".to_string(), Box::new(to_solana.clone()));
+    bridges.insert("eth-polygon".to_string(), Box::new(to_polygon.clone()));
+    bridges.insert("solana-eth".to_string(), Box::new(ScriptedBridge::new("solana-eth")));
+    let wm = WalletManager::new_with_bridges(&create_test_config(), bridges).await.unwrap();
+
+    let quotes = wm.quote_bridge(Some("eth"), None, "USDC", "40").await.unwrap();
+    let routes: Vec<_> = quotes.iter().map(|q| q.route.as_str()).collect();
+    assert_eq!(routes, ["eth-polygon", "eth-solana"]);
+    assert_eq!(quotes[0].fees.protocol_fee, "0.02");
+    assert_eq!(quotes[1].fees.protocol_fee, "0.12");
+
+    // over the eth-solana maximum: still quoted, but flagged as not executable
+    let quotes = wm.quote_bridge(None, Some("solana"), "USDC", "60").await.unwrap();
+    assert_eq!(quotes.len(), 1);
+    assert!(!quotes[0].within_limits);
+    assert!(wm.quote_bridge(None, None, "USDC", "0").await.is_err());
+
+    wm.create_wallet("quote_wallet", false).await.unwrap();
+    let err = wm.bridge_assets("quote_wallet", "eth", "solana", "USDC", "60").await.unwrap_err();
+    assert!(err.to_string().contains("limits"), "{}", err);
+    let err = wm.bridge_assets("quote_wallet", "eth", "polygon", "USDC", "2000").await.unwrap_err();
+    assert!(err.to_string().contains("liquidity"), "{}", err);
+    assert!(to_solana.transfers().is_empty());
+    assert!(to_polygon.transfers().is_empty());
+
+    cleanup(wm).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_spending_policy_limits_bridge_transfers() {
+    use defi_hot_wallet::blockchain::bridge::ScriptedBridge;
+    use defi_hot_wallet::blockchain::traits::Bridge;
+    use defi_hot_wallet::security::policy::{SpendingPolicy, VelocityLimit};
+
+    prepare_test_crypto_env();
+    let bridge = ScriptedBridge::new("eth-solana");
+    let mut bridges: HashMap<String, Box<dyn Bridge>> = HashMap::new();
+    bridges.insert("eth-solana".to_string(), Box::new(bridge.clone()));
+    let wm = WalletManager::new_with_bridges(&create_test_config(), bridges).await.unwrap();
+    wm.create_wallet("policy_wallet", false).await.unwrap();
+
+    let policy = SpendingPolicy {
+        velocity_limits: vec![VelocityLimit {
+            asset: Some("USDC".to_string()),
+            network: None,
+            max_amount: 15.0,
+            window_seconds: 86_400,
+        }],
+        ..SpendingPolicy::default()
+    };
+    wm.set_spending_policy("policy_wallet", &policy).await.unwrap();
+
+    wm.bridge_assets("policy_wallet", "eth", "solana", "USDC", "10.0").await.unwrap();
+    // the first transfer counts towards the daily USDC limit
+    let err = wm.bridge_assets("policy_wallet", "eth", "solana", "USDC", "10.0").await.unwrap_err();
+    assert!(err.to_string().contains("Spending policy"), "{}", err);
+    assert_eq!(bridge.transfers().len(), 1);
+
+    wm.remove_spending_policy("policy_wallet").await.unwrap();
+    assert!(wm.spending_policy("policy_wallet").await.unwrap().is_none());
+    wm.bridge_assets("policy_wallet", "eth", "solana", "USDC", "10.0").await.unwrap();
+    assert_eq!(bridge.transfers().len(), 2);
+
+    cleanup(wm).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_spending_policy_reserves_spends_at_check_time() {
+    use defi_hot_wallet::blockchain::bridge::{ScriptedBridge, TransferScript};
+    use defi_hot_wallet::blockchain::traits::Bridge;
+    use defi_hot_wallet::security::policy::{SpendingPolicy, VelocityLimit};
+
+    prepare_test_crypto_env();
+    let bridge = ScriptedBridge::new("eth-solana");
+    let mut bridges: HashMap<String, Box<dyn Bridge>> = HashMap::new();
+    bridges.insert("eth-solana".to_string(), Box::new(bridge.clone()));
+    let wm = WalletManager::new_with_bridges(&create_test_config(), bridges).await.unwrap();
+    wm.create_wallet("racing_wallet", false).await.unwrap();
+    let policy = SpendingPolicy {
+        velocity_limits: vec![VelocityLimit {
+            asset: Some("USDC".to_string()),
+            network: None,
+            max_amount: 15.0,
+            window_seconds: 86_400,
+        }],
+        ..SpendingPolicy::default()
+    };
+    wm.set_spending_policy("racing_wallet", &policy).await.unwrap();
 
-    if result.is_ok() {
-        // success path: accept any successful return value
-        let _ok = result.ok().unwrap();
-        // optionally inspect _ok if you know its shape, e.g. assert_eq!(_ok.tx_id, "mock_bridge_tx_hash");
-    } else {
-        // Acceptable failures in CI/test envs: crypto/decrypt errors, unsupported chain (NOT_FOUND), bridge failures.
-        let err_str = format!("{}", result.err().unwrap());
-        let el = err_str.to_lowercase();
-        assert!(
-            el.contains("crypto")
-                || el.contains("decrypt")
-                || el.contains("unsupported")
-                || el.contains("bridge")
-                || !el.is_empty(),
-            "unexpected bridge error: {}",
-            err_str
-        );
+    // a transfer that is not sent gives its reservation back
+    bridge.push_script(TransferScript::rejects("insufficient liquidity"));
+    assert!(wm.bridge_assets("racing_wallet", "eth", "solana", "USDC", "10.0").await.is_err());
+
+    // of two concurrent transfers only one fits the limit
+    let (first, second) = tokio::join!(
+        wm.bridge_assets("racing_wallet", "eth", "solana", "USDC", "10.0"),
+        wm.bridge_assets("racing_wallet", "eth", "solana", "USDC", "10.0"),
+    );
+    let denied = match (first, second) {
+        (Ok(_), Err(e)) | (Err(e), Ok(_)) => e,
+        other => panic!("expected exactly one transfer to pass, got {:?}", other),
+    };
+    assert!(denied.to_string().contains("Spending policy"), "{}", denied);
+    assert_eq!(bridge.transfers().len(), 1);
+
+    cleanup(wm).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_held_bridge_transfer_runs_after_approvals() {
+    use defi_hot_wallet::audit::context::AuditContext;
+    use defi_hot_wallet::blockchain::bridge::ScriptedBridge;
+    use defi_hot_wallet::blockchain::traits::Bridge;
+    use defi_hot_wallet::core::errors::WalletError;
+    use defi_hot_wallet::security::approval::{
+        ApprovalNotifier, ApprovalRequest, ApprovalStatus, HeldOperation,
+    };
+    use defi_hot_wallet::security::policy::SpendingPolicy;
+    use defi_hot_wallet::tools::generator::Config;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<(String, ApprovalStatus)>>);
+
+    #[async_trait::async_trait]
+    impl ApprovalNotifier for Recorder {
+        async fn notify(&self, request: &ApprovalRequest) -> anyhow::Result<()> {
+            self.0.lock().unwrap().push((request.id.clone(), request.status));
+            Ok(())
+        }
     }
 
+    prepare_test_crypto_env();
+    let bridge = ScriptedBridge::new("eth-solana");
+    let mut bridges: HashMap<String, Box<dyn Bridge>> = HashMap::new();
+    bridges.insert("eth-solana".to_string(), Box::new(bridge.clone()));
+    let mut security = Config::default().security;
+    security.approvals.required_approvals = 2;
+    let recorder = Arc::new(Recorder::default());
+    let wm = WalletManager::new_with_bridges(&create_test_config(), bridges)
+        .await
+        .unwrap()
+        .with_security_config(&security)
+        .with_approval_notifier(recorder.clone());
+    wm.create_wallet("held_wallet", false).await.unwrap();
+    let policy = SpendingPolicy { require_approval_above: Some(50.0), ..SpendingPolicy::default() };
+    wm.set_spending_policy("held_wallet", &policy).await.unwrap();
+
+    let held = |result: Result<String, WalletError>| match result {
+        Err(WalletError::PendingApproval(id)) => id,
+        other => panic!("expected a held transfer, got {:?}", other),
+    };
+    let id = held(
+        AuditContext::default()
+            .with_actor("dave")
+            .scope(wm.bridge_assets("held_wallet", "eth", "solana", "USDC", "100"))
+            .await,
+    );
+    assert!(bridge.transfers().is_empty());
+    let pending = wm.approval_requests(Some(ApprovalStatus::Pending)).await.unwrap();
+    assert_eq!(pending.len(), 1);
+    assert!(pending[0].reason.contains("Spending policy"), "{}", pending[0].reason);
+    assert!(
+        matches!(&pending[0].operation, HeldOperation::Bridge { amount, .. } if amount == "100")
+    );
+
+    assert_eq!(pending[0].requested_by.as_deref(), Some("dave"));
+    let err = wm.approve_request(&id, "dave", None).await.unwrap_err();
+    assert!(err.to_string().contains("cannot vote"), "{}", err);
+
+    let request = wm.approve_request(&id, "alice", Some("checked")).await.unwrap();
+    assert_eq!((request.status, request.approvals()), (ApprovalStatus::Pending, 1));
+    let err = wm.approve_request(&id, "alice", None).await.unwrap_err();
+    assert!(err.to_string().contains("already voted"), "{}", err);
+    assert!(bridge.transfers().is_empty());
+
+    // the second approval carries the transfer out under the same checks
+    let request = wm.approve_request(&id, "bob", None).await.unwrap();
+    assert_eq!(request.status, ApprovalStatus::Executed);
+    let transfer_id = request.result.unwrap();
+    let tx = wm.get_bridge_transaction_status(&transfer_id).await.unwrap();
+    assert_eq!(tx.amount, "100");
+    assert_eq!(bridge.transfers().len(), 1);
+    assert!(wm.approve_request(&id, "carol", None).await.is_err());
+
+    let rejected = held(wm.bridge_assets("held_wallet", "eth", "solana", "USDC", "80").await);
+    let request = wm.reject_request(&rejected, "carol", Some("unexpected")).await.unwrap();
+    assert_eq!(request.status, ApprovalStatus::Rejected);
+    let err = wm.approve_request(&rejected, "alice", None).await.unwrap_err();
+    assert!(err.to_string().contains("rejected"), "{}", err);
+    assert_eq!(bridge.transfers().len(), 1);
+
+    let notified = recorder.0.lock().unwrap().clone();
+    assert_eq!(notified.first(), Some(&(id.clone(), ApprovalStatus::Pending)));
+    assert!(notified.contains(&(id, ApprovalStatus::Executed)));
+    assert_eq!(notified.last(), Some(&(rejected, ApprovalStatus::Rejected)));
+
+    cleanup(wm).await;
+}
+
+#[tokio::test]
+async fn test_sanctions_list_files_are_imported_and_screened() {
+    use defi_hot_wallet::security::sanctions::SanctionsFormat;
+    use defi_hot_wallet::tools::generator::{Config, SanctionsListConfig};
+
+    prepare_test_crypto_env();
+    let listed = "0x8576aCC5C05D6Ce88f4e49bf65BdF0C62F91353C";
+    let relisted = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("internal.csv");
+    std::fs::write(&path, format!("address,currency,name\n{},ETH,Example\n", listed)).unwrap();
+
+    let mut security = Config::default().security;
+    security.compliance.sanctions_lists.push(SanctionsListConfig {
+        source: "internal".to_string(),
+        path: path.display().to_string(),
+        format: SanctionsFormat::Csv,
+    });
+    let wm =
+        WalletManager::new(&create_test_config()).await.unwrap().with_security_config(&security);
+    wm.create_wallet("screened_wallet", false).await.unwrap();
+
+    // the first import runs in the background right away
+    for _ in 0..100 {
+        if wm.is_address_sanctioned(listed).unwrap() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    assert!(wm.is_address_sanctioned(&listed.to_lowercase()).unwrap());
+    let err = wm.send_transaction("screened_wallet", listed, "0.1", "eth").await.unwrap_err();
+    assert!(err.to_string().contains("sanctioned"), "{}", err);
+    assert!(wm.screen_incoming_transfer("screened_wallet", "eth", listed, "0xfeed").await.unwrap());
+    assert!(!wm
+        .screen_incoming_transfer("screened_wallet", "eth", relisted, "0xbeef")
+        .await
+        .unwrap());
+
+    // unchanged files are not imported again; a new version replaces the old one
+    assert_eq!(wm.reload_sanctions_lists().await, 0);
+    std::fs::write(&path, format!("{}\n", relisted)).unwrap();
+    assert_eq!(wm.reload_sanctions_lists().await, 1);
+    assert!(!wm.is_address_sanctioned(listed).unwrap());
+    assert!(wm.is_address_sanctioned(relisted).unwrap());
+    let lists = wm.sanctions_lists().await.unwrap();
+    assert_eq!(lists.len(), 1);
+    assert_eq!(lists[0].source, "internal");
+    assert_eq!(lists[0].entry_count, 1);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_failed_and_stuck_bridge_transfers_are_recovered() {
+    use defi_hot_wallet::blockchain::bridge::{
+        BridgeTransactionStatus, ScriptedBridge, TransferScript,
+    };
+    use defi_hot_wallet::blockchain::traits::Bridge;
+
+    prepare_test_crypto_env();
+    let bridge = ScriptedBridge::new("eth-solana");
+    bridge
+        .push_script(
+            TransferScript::fails("slippage").refundable(BridgeTransactionStatus::Completed),
+        )
+        .push_script(TransferScript::new().then(BridgeTransactionStatus::InTransit).redeemable())
+        .push_script(TransferScript::fails("paused"));
+    let mut bridges: HashMap<String, Box<dyn Bridge>> = HashMap::new();
+    bridges.insert("eth-solana".to_string(), Box::new(bridge.clone()));
+    let wm = WalletManager::new_with_bridges(&create_test_config(), bridges).await.unwrap();
+    wm.create_wallet("recovery_wallet", false).await.unwrap();
+
+    let refunded = wm.bridge_assets("recovery_wallet", "eth", "solana", "USDC", "1").await.unwrap();
+    let stuck = wm.bridge_assets("recovery_wallet", "eth", "solana", "USDC", "2").await.unwrap();
+    let manual = wm.bridge_assets("recovery_wallet", "eth", "solana", "USDC", "3").await.unwrap();
+    for _ in 0..2 {
+        wm.poll_bridge_transfers().await.unwrap();
+    }
+    assert_eq!(wm.check_bridge_status(&stuck).await.unwrap(), BridgeTransactionStatus::InTransit);
+
+    // nothing is overdue yet: only the failed transfers are picked up
+    let not_stuck = chrono::Utc::now() - chrono::Duration::hours(1);
+    assert_eq!(wm.recover_bridge_transfers(not_stuck).await.unwrap(), 2);
+    assert_eq!(
+        wm.check_bridge_status(&refunded).await.unwrap(),
+        BridgeTransactionStatus::RefundPending
+    );
+    assert!(matches!(
+        wm.check_bridge_status(&manual).await.unwrap(),
+        BridgeTransactionStatus::ManualInterventionRequired(_)
+    ));
+
+    // past every ETA: the in-transit transfer is claimed, the refund lands
+    let overdue = chrono::Utc::now() + chrono::Duration::days(1);
+    assert_eq!(wm.recover_bridge_transfers(overdue).await.unwrap(), 2);
+    assert_eq!(wm.check_bridge_status(&refunded).await.unwrap(), BridgeTransactionStatus::Refunded);
+    assert!(bridge.transfers()[1].redeemed);
+    wm.poll_bridge_transfers().await.unwrap();
+    assert_eq!(wm.check_bridge_status(&stuck).await.unwrap(), BridgeTransactionStatus::Completed);
+
+    cleanup(wm).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_bridge_recovery_runs_in_the_background() {
+    use defi_hot_wallet::blockchain::bridge::{
+        BridgeTransactionStatus, ScriptedBridge, TransferScript,
+    };
+    use defi_hot_wallet::blockchain::traits::Bridge;
+    use std::sync::Arc;
+
+    prepare_test_crypto_env();
+    let bridge = ScriptedBridge::with_default(
+        "eth-solana",
+        TransferScript::fails("slippage").refundable(BridgeTransactionStatus::Completed),
+    );
+    let mut bridges: HashMap<String, Box<dyn Bridge>> = HashMap::new();
+    bridges.insert("eth-solana".to_string(), Box::new(bridge.clone()));
+    let wm =
+        Arc::new(WalletManager::new_with_bridges(&create_test_config(), bridges).await.unwrap());
+    wm.create_wallet("recovery_wallet", false).await.unwrap();
+    let id = wm.bridge_assets("recovery_wallet", "eth", "solana", "USDC", "1").await.unwrap();
+    for _ in 0..2 {
+        wm.poll_bridge_transfers().await.unwrap();
+    }
+    assert!(matches!(
+        wm.check_bridge_status(&id).await.unwrap(),
+        BridgeTransactionStatus::Failed(_)
+    ));
+
+    let task = wm.spawn_bridge_recovery(std::time::Duration::from_millis(10));
+    let mut status = BridgeTransactionStatus::Initiated;
+    for _ in 0..100 {
+        status = wm.check_bridge_status(&id).await.unwrap();
+        if status == BridgeTransactionStatus::Refunded {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    task.abort();
+    assert_eq!(status, BridgeTransactionStatus::Refunded);
+    assert!(bridge.transfers()[0].refunded);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_swap_is_quoted_sent_and_recorded_in_history() {
+    use defi_hot_wallet::blockchain::swap::{SwapParams, UniswapRouter};
+    use defi_hot_wallet::blockchain::traits::SwapRouter;
+    use defi_hot_wallet::core::config::{BridgeTokenConfig, SwapProtocol, SwapRouterConfig};
+    use ethers::abi::{encode, Token};
+    use ethers::providers::Provider;
+    use ethers::types::{Bytes, H256, U256};
+
+    prepare_test_crypto_env();
+    let mut tokens = HashMap::new();
+    tokens.insert(
+        "WETH".to_string(),
+        BridgeTokenConfig {
+            address: "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2".to_string(),
+            decimals: 18,
+        },
+    );
+    tokens.insert(
+        "USDC".to_string(),
+        BridgeTokenConfig {
+            address: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string(),
+            decimals: 6,
+        },
+    );
+    let config = SwapRouterConfig {
+        protocol: SwapProtocol::UniswapV3,
+        router: "0xE592427A0AEce92De3Edee1F18E0157C05861564".to_string(),
+        quoter: Some("0x61fFE014bA17989E743c5F6cB21bF9697530B21e".to_string()),
+        fee_tier: 3000,
+        tokens,
+    };
+    let (provider, mock) = Provider::mocked();
+    let word = |value: U256| Bytes::from(encode(&[Token::Uint(value)]));
+    let quoted = U256::from(2_000_000_000u64);
+    let swap_hash = H256::repeat_byte(0x33);
+    // MockProvider answers in LIFO order. Swap: quote, allowance, gas price,
+    // nonce, simulation, send (the allowance already covers the amount).
+    mock.push(swap_hash).unwrap();
+    mock.push(serde_json::json!({
+        "from": "0x0000000000000000000000000000000000000001",
+        "to": "0x0000000000000000000000000000000000000002",
+        "output": word(quoted),
+    }))
+    .unwrap();
+    mock.push(U256::from(0)).unwrap();
+    mock.push(U256::from(10_000_000_000u64)).unwrap();
+    mock.push::<Bytes, _>(word(U256::MAX)).unwrap();
+    mock.push::<Bytes, _>(word(quoted)).unwrap();
+    // Quote: quote, allowance, gas price.
+    mock.push(U256::from(10_000_000_000u64)).unwrap();
+    mock.push::<Bytes, _>(word(U256::zero())).unwrap();
+    mock.push::<Bytes, _>(word(quoted)).unwrap();
+
+    let mut routers: HashMap<String, Box<dyn SwapRouter>> = HashMap::new();
+    routers.insert(
+        "eth".to_string(),
+        Box::new(UniswapRouter::with_provider("eth", "ETH", config, provider, 1)),
+    );
+    let wm = WalletManager::new(&create_test_config()).await.unwrap().with_swap_routers(routers);
+    wm.create_wallet("swap_wallet", false).await.unwrap();
+
+    let params = SwapParams::new("WETH", "USDC", "1");
+    let quote = wm.quote_swap("swap_wallet", "eth", &params).await.unwrap();
+    assert_eq!(quote.amount_out, "2000");
+    assert_eq!(quote.amount_out_min, "1990");
+    assert!(quote.approval_required);
+
+    let receipt = wm.swap_tokens("swap_wallet", "eth", &params).await.unwrap();
+    assert_eq!(receipt.tx_hash, format!("{:?}", swap_hash));
+    assert!(receipt.simulation.as_ref().unwrap().success);
+    assert_eq!(receipt.approval_tx_hash, None);
+    let history = wm.get_transaction_history("swap_wallet").await.unwrap();
+    assert_eq!(history, vec![receipt.tx_hash.clone()]);
+
+    // no router on polygon, and bad parameters are rejected before any RPC call
+    assert!(wm.swap_tokens("swap_wallet", "polygon", &params).await.is_err());
+    let greedy = SwapParams { slippage_bps: 9_000, ..params };
+    assert!(wm.quote_swap("swap_wallet", "eth", &greedy).await.is_err());
+
+    cleanup(wm).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_lido_staking_is_checked_recorded_and_summarized() {
+    use defi_hot_wallet::blockchain::staking::{LidoStaking, StakeAction, StakeState};
+    use defi_hot_wallet::blockchain::traits::StakingProvider;
+    use defi_hot_wallet::core::config::{StakingProtocol, StakingProviderConfig};
+    use ethers::abi::{encode, Token};
+    use ethers::providers::Provider;
+    use ethers::types::{Bytes, H256, U256};
+
+    prepare_test_crypto_env();
+    let config = StakingProviderConfig {
+        protocol: StakingProtocol::Lido,
+        steth: None,
+        withdrawal_queue: None,
+        default_validator: None,
+    };
+    let (provider, mock) = Provider::mocked();
+    let steth = |wei: u64| Bytes::from(encode(&[Token::Uint(U256::from(wei) * U256::exp10(8))]));
+    let no_requests = Bytes::from(encode(&[Token::Array(vec![])]));
+    let gas_price = U256::from(10_000_000_000u64);
+    let stake_hash = H256::repeat_byte(0x44);
+    let approve_hash = H256::repeat_byte(0x55);
+    let request_hash = H256::repeat_byte(0x66);
+    // MockProvider answers in LIFO order. Unstake: balance, requests, gas
+    // price, nonce, approve, request.
+    mock.push(request_hash).unwrap();
+    mock.push(approve_hash).unwrap();
+    mock.push(U256::from(1)).unwrap();
+    mock.push(gas_price).unwrap();
+    mock.push::<Bytes, _>(no_requests.clone()).unwrap();
+    mock.push::<Bytes, _>(steth(21_000_000_000)).unwrap();
+    // Summary: balance (2.1 stETH), requests.
+    mock.push::<Bytes, _>(no_requests).unwrap();
+    mock.push::<Bytes, _>(steth(21_000_000_000)).unwrap();
+    // Stake: gas price, nonce, submit.
+    mock.push(stake_hash).unwrap();
+    mock.push(U256::from(0)).unwrap();
+    mock.push(gas_price).unwrap();
+
+    let mut providers: HashMap<String, Box<dyn StakingProvider>> = HashMap::new();
+    providers.insert(
+        "eth".to_string(),
+        Box::new(LidoStaking::with_provider("eth", &config, provider, 1).unwrap()),
+    );
+    let wm =
+        WalletManager::new(&create_test_config()).await.unwrap().with_staking_providers(providers);
+    wm.create_wallet("staking_wallet", false).await.unwrap();
+
+    let staked = wm.stake("staking_wallet", "eth", "2", None).await.unwrap();
+    assert_eq!(staked.tx_hash, format!("{:?}", stake_hash));
+    assert_eq!(staked.action, StakeAction::Stake);
+
+    let summary = wm.staking_summary("staking_wallet").await.unwrap();
+    assert_eq!(summary.positions.len(), 1);
+    assert_eq!(summary.positions[0].balance, "2.1");
+    assert_eq!(summary.positions[0].state, StakeState::Active);
+    assert_eq!(summary.positions[0].rewards, "0.1");
+
+    // over the single-transaction compliance limit: refused before any RPC call
+    let err = wm.stake("staking_wallet", "eth", "5000", None).await.unwrap_err();
+    assert!(err.to_string().contains("Compliance"));
+    assert!(wm.stake("staking_wallet", "solana", "1", None).await.is_err());
+
+    let unstaked = wm.unstake("staking_wallet", "eth", "stETH", Some("1")).await.unwrap();
+    assert_eq!(unstaked.tx_hash, format!("{:?}", request_hash));
+    assert_eq!(unstaked.approval_tx_hash, Some(format!("{:?}", approve_hash)));
+
+    let history = wm.get_transaction_history("staking_wallet").await.unwrap();
+    assert_eq!(history.len(), 2);
+    assert!(history.contains(&staked.tx_hash));
+    assert!(history.contains(&unstaked.tx_hash));
+
     cleanup(wm).await;
 }
 
@@ -232,3 +770,146 @@ async fn test_generate_and_derive_helpers() {
     assert!(addr_eth.is_ok() || addr_eth.is_err());
     cleanup(wm).await;
 }
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_wallet_events_are_delivered_to_webhooks() {
+    use axum::http::HeaderMap;
+    use axum::routing::post;
+    use defi_hot_wallet::core::events::{EventKind, WalletEvent};
+    use defi_hot_wallet::webhooks::{self, DeliveryStatus};
+    use std::sync::{Arc, Mutex};
+
+    prepare_test_crypto_env();
+    let received: Arc<Mutex<Vec<(HeaderMap, String)>>> = Arc::default();
+    let sink = Arc::clone(&received);
+    let app = axum::Router::new().route(
+        "/hook",
+        post(move |headers: HeaderMap, body: String| {
+            let sink = Arc::clone(&sink);
+            async move { sink.lock().unwrap().push((headers, body)) }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let wm = create_test_wallet_manager().await;
+    wm.create_wallet("hooked_wallet", false).await.unwrap();
+    wm.create_wallet("other_wallet", false).await.unwrap();
+    let webhook = wm
+        .create_webhook(&url, &[EventKind::IncomingDeposit], Some("hooked_wallet"))
+        .await
+        .unwrap();
+    let mut events = wm.subscribe_events();
+
+    let from = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
+    wm.screen_incoming_transfer("other_wallet", "eth", from, "0xaaa").await.unwrap();
+    wm.screen_incoming_transfer("hooked_wallet", "eth", from, "0xbbb").await.unwrap();
+    match events.recv().await.unwrap() {
+        WalletEvent::IncomingDeposit { wallet, sanctioned, .. } => {
+            assert_eq!(wallet, "other_wallet");
+            assert!(!sanctioned);
+        }
+        other => panic!("unexpected event {:?}", other),
+    }
+
+    for _ in 0..200 {
+        if !received.lock().unwrap().is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    let (headers, body) = received.lock().unwrap().first().cloned().expect("no delivery");
+    let header = |name: &str| headers.get(name).unwrap().to_str().unwrap().to_string();
+    let timestamp: i64 = header(webhooks::TIMESTAMP_HEADER).parse().unwrap();
+    assert!(webhooks::verify_signature(
+        &webhook.secret,
+        timestamp,
+        &body,
+        &header(webhooks::SIGNATURE_HEADER)
+    ));
+    let payload: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(payload["data"]["wallet"], "hooked_wallet");
+    assert_eq!(payload["data"]["tx_hash"], "0xbbb");
+
+    for _ in 0..100 {
+        let delivered =
+            wm.webhook_deliveries(&webhook.id, Some(DeliveryStatus::Delivered), 10).await.unwrap();
+        if !delivered.is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    let deliveries = wm.webhook_deliveries(&webhook.id, None, 10).await.unwrap();
+    assert_eq!(deliveries.len(), 1);
+    assert_eq!(deliveries[0].status, "delivered");
+    assert_eq!(deliveries[0].attempts, 1);
+
+    assert!(wm.delete_webhook(&webhook.id).await.unwrap());
+    assert!(wm.webhook_deliveries(&webhook.id, None, 10).await.is_err());
+    cleanup(wm).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_balance_polling_does_not_load_the_wallet() {
+    use defi_hot_wallet::core::config::NetworkConfig;
+    use defi_hot_wallet::storage::AuditQuery;
+
+    prepare_test_crypto_env();
+    let mut config = create_test_config();
+    // nothing listens here, so polls fail fast after the address lookup
+    config.blockchain.networks.insert(
+        "eth".to_string(),
+        NetworkConfig {
+            rpc_url: "http://127.0.0.1:1".to_string(),
+            chain_id: Some(1),
+            native_token: "ETH".to_string(),
+            block_time_seconds: 12,
+        },
+    );
+    let wm = WalletManager::new(&config).await.unwrap();
+    wm.create_wallet("watched_wallet", false).await.unwrap();
+    assert!(wm.watch_balances("missing_wallet").await.is_err());
+
+    let accessed = AuditQuery { action: Some("wallet_accessed".to_string()), ..Default::default() };
+    let watch = wm.watch_balances("watched_wallet").await.unwrap();
+    let second = wm.watch_balances("watched_wallet").await.unwrap();
+    let (_, loads) = wm.audit_logs(&accessed).await.unwrap();
+    assert_eq!(loads, 1, "only the first watch loads the wallet");
+
+    assert_eq!(wm.poll_balances().await, 0);
+    assert_eq!(wm.poll_balances().await, 0);
+    let (_, after_polls) = wm.audit_logs(&accessed).await.unwrap();
+    assert_eq!(after_polls, loads);
+
+    drop(second);
+    drop(watch);
+    cleanup(wm).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_token_calls_cannot_carry_native_value() {
+    use defi_hot_wallet::blockchain::contract::ContractCall;
+    use defi_hot_wallet::core::errors::WalletError;
+
+    let wm = create_test_wallet_manager().await;
+    wm.create_wallet("token_wallet", false).await.unwrap();
+    let call = ContractCall {
+        contract: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string(),
+        abi: serde_json::json!(["function transfer(address to, uint256 amount) returns (bool)"]),
+        function: "transfer".to_string(),
+        args: vec![
+            serde_json::json!("0x742d35Cc6634C0532925a3b844Bc454e4438f44e"),
+            serde_json::json!("1000000"),
+        ],
+        value: Some("1".to_string()),
+    };
+    let err = wm.send_contract_transaction("token_wallet", "eth", &call, true, false).await;
+    match err {
+        Err(WalletError::ValidationError(reason)) => {
+            assert!(reason.contains("native token"), "{}", reason)
+        }
+        other => panic!("expected a validation error, got {:?}", other),
+    }
+    cleanup(wm).await;
+}